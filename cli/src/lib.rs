@@ -9,6 +9,7 @@ extern crate serde_json;
 
 extern crate ansi_term;
 extern crate clap;
+extern crate rayon;
 extern crate genco;
 #[cfg(feature = "notify")]
 extern crate notify;
@@ -19,6 +20,7 @@ extern crate reproto_compile as compile;
 extern crate reproto_core as core;
 extern crate reproto_derive as derive;
 extern crate reproto_env as env;
+extern crate reproto_lint as lint;
 extern crate reproto_manifest as manifest;
 extern crate reproto_parser as parser;
 extern crate reproto_repository as repository;
@@ -31,4 +33,9 @@ pub mod ops;
 pub mod output;
 mod utils;
 
+pub use compile::{Compiler, Input, Output};
+pub use core::{Reported, Source};
+pub use env::convert_lang;
+pub use manifest::Language;
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
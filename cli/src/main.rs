@@ -21,10 +21,14 @@ fn setup_opts<'a, 'b>() -> App<'a, 'b> {
                 .long("no-color")
                 .help("Disable colored output."),
         ).arg(
-            Arg::with_name("output-format")
-                .long("output-format")
+            Arg::with_name("message-format")
+                .long("message-format")
+                .alias("output-format")
                 .takes_value(true)
-                .help("Select a different output format (json, human) (default: human)."),
+                .help(
+                    "Select a different format for messages and diagnostics (json, human) \
+                     (default: human).",
+                ),
         )
 }
 
@@ -54,7 +58,7 @@ fn main() {
     let colored = matches.is_present("color")
         || !matches.is_present("no-color") && atty::is(atty::Stream::Stdout);
 
-    let output_format = match matches.value_of("output-format") {
+    let output_format = match matches.value_of("message-format") {
         Some("json") => output::OutputFormat::Json,
         _ => output::OutputFormat::Human,
     };
@@ -1,11 +1,18 @@
 //! build command
 
 use clap::{App, Arg, ArgMatches, SubCommand};
-use core::errors::Result;
-use core::{Filesystem, Reporter};
+use core::errors::{Error, Result};
+use core::{Filesystem, MemoryFilesystem, Reported, Reporter};
 use env;
-use utils::{session, load_manifest};
-use core::model::Language;
+use manifest::{Language, Manifest, ModuleDoc};
+use rayon::{self, prelude::*};
+use serde_json;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+use utils::{load_manifest, load_workspace_members, session, session_with_hook};
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("build").about("Build specifications");
@@ -17,53 +24,302 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Language to build for"),
     );
 
+    let out = out.arg(
+        Arg::with_name("jobs")
+            .long("jobs")
+            .short("j")
+            .takes_value(true)
+            .help("Number of targets to build in parallel (default: number of CPUs)"),
+    );
+
     let out = out.arg(
         Arg::with_name("list-modules")
             .long("list-modules")
             .help("List available modules and their corresponding configurations"),
     );
 
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["human", "json"])
+            .help("Output format of `--list-modules` (default: human)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("workspace")
+            .long("workspace")
+            .help("Build every member of the workspace declared in the manifest"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("emit-plan")
+            .long("emit-plan")
+            .help("Print the inputs and outputs of every target as JSON, without building"),
+    );
+
     out
 }
 
-pub fn entry(fs: &Filesystem, reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
+/// Description of what building would do, for use by external build systems.
+#[derive(Serialize)]
+struct Plan {
+    /// The manifest the plan was built from.
+    manifest: Option<PathBuf>,
+    targets: Vec<TargetPlan>,
+}
 
-    let mut done = false;
+#[derive(Serialize)]
+struct TargetPlan {
+    /// Directory that files are generated in.
+    output: Option<PathBuf>,
+    /// Packages required by the target.
+    packages: Vec<String>,
+    /// Every specification read when building the target, including imported ones.
+    inputs: BTreeSet<PathBuf>,
+    /// Every generated file.
+    outputs: Vec<PathBuf>,
+}
 
+pub fn entry(
+    fs: &(Filesystem + Sync),
+    reporter: &mut Reporter,
+    matches: &ArgMatches,
+) -> Result<()> {
     if matches.is_present("list-modules") {
-        //list_modules()?;
-
-        match Language {
-            Csharp => println!("csharp"),
-            Go => println!("go"),
-            Java => println!("java"),
-            JavaScript => println!("js"),
-            Json => println!("json"),
-            OpenApi => println!("openapi"),
-            Python => println!("python"),
-            Python3 => println!("python3"),
-            Reproto => println!("reproto"),
-            Rust => println!("rust"),
-            Swift => println!("swift")
+        return list_modules(matches);
+    }
+
+    let jobs = match matches.value_of("jobs") {
+        Some(jobs) => jobs
+            .parse::<usize>()
+            .map_err(|e| format!("bad `--jobs` argument: {}", e))?,
+        // use the default number of threads.
+        None => 0,
+    };
+
+    let manifest = load_manifest(reporter, matches)?;
+    let path = manifest.path.clone();
+
+    let language = match matches.value_of("lang") {
+        Some(lang) => {
+            Some(Language::parse(lang).ok_or_else(|| format!("not a valid language: {}", lang))?)
         }
+        None => None,
+    };
 
-        done = true;
+    let targets = if matches.is_present("workspace") {
+        let mut targets = Vec::new();
+
+        for member in load_workspace_members(reporter, &manifest)? {
+            targets.extend(member.into_targets(language.as_ref())?);
+        }
+
+        targets
+    } else {
+        manifest.into_targets(language.as_ref())?
+    };
+
+    if matches.is_present("emit-plan") {
+        return emit_plan(reporter, path, targets);
     }
 
-    if done {
-        return Ok(())
+    // no need to spin up a thread pool.
+    if targets.len() == 1 || jobs == 1 {
+        for manifest in targets {
+            build_target(fs, reporter, manifest)?;
+        }
+
+        return Ok(());
     }
 
-    let manifest = load_manifest(matches)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e| format!("failed to set up thread pool: {}", e))?;
+
+    // only targets are built in parallel. The packages of a target share a session, which holds
+    // reference-counted state and can't be sent across threads (see TODO.md).
+    //
+    // each target reports into its own reporter, which is forwarded once all targets are built.
+    let results: Vec<(Vec<Reported>, Result<()>)> = pool.install(|| {
+        targets
+            .into_par_iter()
+            .map(|manifest| {
+                let mut reported = Vec::new();
+                let result = build_target(fs, &mut reported, manifest);
+                (reported, result)
+            }).collect()
+    });
+
+    let mut errors = Vec::new();
+
+    for (reported, result) in results {
+        for r in reported {
+            match r {
+                Reported::Diagnostics(d) => reporter.diagnostics(d),
+                Reported::SourceDiagnostics(d) => reporter.source_diagnostics(d),
+            }
+        }
+
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::new("error when building targets").with_suppressed(errors));
+    }
+
+    Ok(())
+}
+
+/// Modules of a single language, as printed by `--list-modules`.
+#[derive(Serialize)]
+struct LanguageModules {
+    language: String,
+    modules: &'static [ModuleDoc],
+}
+
+/// List the modules of the language given with `--lang`, or of all languages.
+fn list_modules(matches: &ArgMatches) -> Result<()> {
+    let languages = match matches.value_of("lang") {
+        Some(lang) => {
+            vec![Language::parse(lang).ok_or_else(|| format!("not a valid language: {}", lang))?]
+        }
+        None => Language::all(),
+    };
+
+    let languages = languages
+        .into_iter()
+        .map(|language| LanguageModules {
+            language: language.id(),
+            modules: env::convert_lang(language).modules(),
+        })
+        .collect::<Vec<_>>();
+
+    if let Some("json") = matches.value_of("format") {
+        let stdout = io::stdout();
+        serde_json::to_writer_pretty(stdout.lock(), &languages)?;
+        println!("");
+        return Ok(());
+    }
+
+    for l in &languages {
+        if l.modules.is_empty() {
+            println!("{}: no modules", l.language);
+            continue;
+        }
+
+        println!("{}:", l.language);
+
+        for m in l.modules {
+            println!("  {} - {}", m.name, m.description);
+
+            for o in m.options {
+                match o.default {
+                    Some(default) => println!(
+                        "    {}: {} (default: {}) - {}",
+                        o.name, o.ty, default, o.description
+                    ),
+                    None => println!("    {}: {} - {}", o.name, o.ty, o.description),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a single target.
+pub fn build_target(fs: &Filesystem, reporter: &mut Reporter, manifest: Manifest) -> Result<()> {
     let lang = manifest.lang().ok_or_else(|| {
-        "no language to build for, either specify in manifest under `language` or `--lang`"
+        "no language to build for, either specify in manifest under `language`, `[[targets]]`, \
+         or `--lang`"
     })?;
 
-    let mut resolver = env::resolver(&manifest)?;
+    let mut resolver = env::locking_resolver(&manifest)?;
     let handle = fs.open_root(manifest.output.as_ref().map(AsRef::as_ref))?;
     let session = session(lang.copy(), &manifest, reporter, resolver.as_mut())?;
     lang.compile(handle.as_ref(), session, manifest)?;
     Ok(())
 }
 
+/// Print the build plan for all targets.
+fn emit_plan(
+    reporter: &mut Reporter,
+    manifest: Option<PathBuf>,
+    targets: Vec<Manifest>,
+) -> Result<()> {
+    let mut plan = Plan {
+        manifest,
+        targets: Vec::new(),
+    };
+
+    for manifest in targets {
+        plan.targets.push(plan_target(reporter, manifest)?);
+    }
+
+    let stdout = io::stdout();
+    serde_json::to_writer_pretty(stdout.lock(), &plan)?;
+    println!("");
+    Ok(())
+}
+
+/// Plan a single target by building it in memory.
+fn plan_target(reporter: &mut Reporter, manifest: Manifest) -> Result<TargetPlan> {
+    let lang = manifest.lang().ok_or_else(|| {
+        "no language to build for, either specify in manifest under `language`, `[[targets]]`, \
+         or `--lang`"
+    })?;
+
+    let inputs = Rc::new(RefCell::new(BTreeSet::new()));
+
+    let output = manifest.output.clone();
+
+    let packages = manifest
+        .packages
+        .iter()
+        .flat_map(|p| p.iter())
+        .map(|p| p.to_string())
+        .collect();
 
+    let mut resolver = env::resolver(&manifest)?;
+
+    let session = {
+        let inputs = inputs.clone();
+
+        session_with_hook(
+            lang.copy(),
+            &manifest,
+            reporter,
+            resolver.as_mut(),
+            move |path| {
+                inputs.borrow_mut().insert(path.to_owned());
+                Ok(())
+            },
+        )?
+    };
+
+    let fs = MemoryFilesystem::new();
+    let handle = fs.open_root(None)?;
+    lang.compile(handle.as_ref(), session, manifest)?;
+
+    let outputs = fs
+        .take_files()?
+        .into_iter()
+        .map(|(path, _)| match output {
+            Some(ref output) => path.to_path(output),
+            None => path.to_path("."),
+        })
+        .collect();
+
+    let inputs = inputs.borrow().clone();
+
+    Ok(TargetPlan {
+        output,
+        packages,
+        inputs,
+        outputs,
+    })
+}
@@ -20,7 +20,7 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
 }
 
 pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
-    let manifest = load_manifest(m)?;
+    let manifest = load_manifest(reporter, m)?;
     let mut resolver = env::resolver(&manifest)?;
     let mut session = simple_config(&manifest, reporter, resolver.as_mut())?;
 
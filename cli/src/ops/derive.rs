@@ -22,7 +22,12 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .long("file")
             .short("i")
             .takes_value(true)
-            .help("File to read from, otherwise will read from stdin"),
+            .multiple(true)
+            .number_of_values(1)
+            .help(
+                "File to read from, otherwise will read from stdin. Can be specified multiple \
+                 times to derive a schema which fits all files",
+            ),
     );
 
     let out = out.arg(
@@ -51,7 +56,7 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
         Arg::with_name("lang")
             .long("lang")
             .takes_value(true)
-            .help("Language to compile to"),
+            .help("Language to compile to (default: reproto)"),
     );
 
     let out = out.arg(
@@ -82,14 +87,14 @@ pub fn entry(reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
         Some(value) => return Err(format!("Unsupported format: {}", value).into()),
     };
 
-    let source = match matches.value_of("file") {
-        Some(file) => Source::from_path(file),
-        None => Source::stdin(),
+    let sources = match matches.values_of("file") {
+        Some(files) => files.map(Source::from_path).collect(),
+        None => vec![Source::stdin()],
     };
 
     let derive = derive::Derive::new(root_name, format, Some(package_prefix.clone()));
 
-    let decl = derive::derive(derive, &source)?;
+    let decl = derive::derive(derive, &sources)?;
 
     let file = ast::File {
         comment: vec!["Generated from reproto derive CLI".to_string().into()],
@@ -114,10 +119,12 @@ pub fn entry(reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
         .flat_map(|s| s.map(|s| s.to_string()))
         .collect();
 
-    let language = matches
-        .value_of("lang")
-        .and_then(Language::parse)
-        .ok_or_else(|| "no language specified, use `--lang`")?;
+    let language = match matches.value_of("lang") {
+        Some(lang) => {
+            Language::parse(lang).ok_or_else(|| format!("not a valid language: {}", lang))?
+        }
+        None => Language::Reproto,
+    };
 
     let lang = env::convert_lang(language);
 
@@ -144,7 +151,7 @@ pub fn entry(reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
 
     return Ok(());
 
-    fn load_modules(lang: &Lang, names: Vec<String>) -> Result<Vec<Box<Any>>> {
+    fn load_modules(lang: &Lang, names: Vec<String>) -> Result<Vec<Box<Any + Send>>> {
         let mut modules = Vec::new();
 
         for name in names {
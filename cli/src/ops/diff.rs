@@ -0,0 +1,96 @@
+//! Print the structural changes between two versions of a package.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::errors::*;
+use core::flavored::RpFile;
+use core::{CoreFlavor, Reporter, RpPackage, RpVersionedPackage, Version};
+use env;
+use repository::Repository;
+use semck;
+use trans::Session;
+use utils::{load_manifest, simple_config};
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("diff")
+        .about("Print the changes between two published versions of a package");
+
+    let out = out.arg(
+        Arg::with_name("from")
+            .required(true)
+            .help("Package and version to compare from, like `foo.bar@1.0.0`"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("to")
+            .required(true)
+            .help("Package and version to compare to, like `foo.bar@1.1.0`"),
+    );
+
+    out
+}
+
+pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, m)?;
+    let mut resolver = env::resolver(&manifest)?;
+    let mut session = simple_config(&manifest, reporter, resolver.as_mut())?;
+    let mut repository = env::repository(&manifest)?;
+
+    let from = parse_versioned(m.value_of("from").ok_or_else(|| "missing <from>")?)?;
+    let to = parse_versioned(m.value_of("to").ok_or_else(|| "missing <to>")?)?;
+
+    let file_from = load(&mut repository, &mut session, &from)?;
+    let file_to = load(&mut repository, &mut session, &to)?;
+
+    let changes = semck::diff(&file_from, &file_to)?;
+
+    println!("{} -> {}", from, to);
+
+    if changes.is_empty() {
+        println!("  no changes");
+    }
+
+    for change in changes {
+        println!("  {}", change);
+    }
+
+    Ok(())
+}
+
+/// Parse a package with an exact version, like `foo.bar@1.0.0`.
+fn parse_versioned(input: &str) -> Result<RpVersionedPackage> {
+    let mut parts = input.splitn(2, '@');
+
+    let package = match parts.next() {
+        Some(package) if !package.is_empty() => RpPackage::parse(package),
+        _ => return Err(format!("missing package: {}", input).into()),
+    };
+
+    let version = parts
+        .next()
+        .ok_or_else(|| format!("missing version, expected `<package>@<version>`: {}", input))?;
+
+    let version =
+        Version::parse(version).map_err(|e| format!("bad version: {}: {}", version, e))?;
+
+    Ok(RpVersionedPackage::new(package, Some(version)))
+}
+
+/// Load the given version of a package from the repository.
+fn load(
+    repository: &mut Repository,
+    session: &mut Session<CoreFlavor>,
+    package: &RpVersionedPackage,
+) -> Result<RpFile> {
+    let deployment = repository
+        .all(&package.package)?
+        .into_iter()
+        .find(|d| Some(&d.version) == package.version.as_ref())
+        .ok_or_else(|| format!("no such package in repository: {}", package))?;
+
+    let source = repository
+        .get_object(&deployment)?
+        .ok_or_else(|| format!("No object found for deployment: {:?}", deployment))?;
+
+    let source = source.with_name(package.to_string());
+    session.load_source(source, package)
+}
@@ -1,18 +1,422 @@
 //! Action to build documentation.
 
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use core::errors::*;
 use core::Reporter;
 use env;
+use output::Output;
 use utils::{load_manifest, simple_config};
 
+/// Default port to serve documentation on.
+const DEFAULT_PORT: &str = "8000";
+
 pub fn options<'a, 'b>() -> App<'a, 'b> {
-    ::doc::shared_options(SubCommand::with_name("doc").about("Generate documentation"))
+    let out = ::doc::shared_options(SubCommand::with_name("doc").about("Generate documentation"));
+
+    let out = out.arg(
+        Arg::with_name("serve")
+            .long("serve")
+            .help("Serve the documentation over HTTP, and rebuild it when sources change"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("port")
+            .long("port")
+            .takes_value(true)
+            .requires("serve")
+            .help("Port to serve documentation on (default: 8000)"),
+    );
+
+    out
 }
 
-pub fn entry(reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
-    let manifest = load_manifest(matches)?;
+pub fn entry(reporter: &mut Reporter, matches: &ArgMatches, output: &Output) -> Result<()> {
+    if matches.is_present("serve") {
+        return serve::entry(reporter, matches, output);
+    }
+
+    let mut manifest = load_manifest(reporter, matches)?;
+    manifest.all_versions = manifest.doc.all_versions || matches.is_present("all-versions");
     let mut resolver = env::resolver(&manifest)?;
     let session = simple_config(&manifest, reporter, resolver.as_mut())?;
     ::doc::compile(session, matches, manifest).map_err(Into::into)
 }
+
+#[cfg(not(feature = "notify"))]
+mod serve {
+    use clap::ArgMatches;
+    use core::errors::Result;
+    use core::Reporter;
+    use output::Output;
+
+    pub fn entry(_: &mut Reporter, _: &ArgMatches, _: &Output) -> Result<()> {
+        Err("`doc --serve` is not supported: `notify` feature is disabled".into())
+    }
+}
+
+#[cfg(feature = "notify")]
+mod serve {
+    use super::DEFAULT_PORT;
+    use clap::ArgMatches;
+    use core::errors::Result;
+    use core::{Reported, Reporter};
+    use env;
+    use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+    use output::Output;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::time::Duration;
+    use url::percent_encoding::percent_decode;
+    use utils::{load_manifest, session_with_hook};
+
+    /// Path polled by served pages to detect that the documentation has been rebuilt.
+    const GENERATION_PATH: &str = "/__reproto/generation";
+
+    /// Script injected into served pages, reloading them when the documentation is rebuilt.
+    const LIVE_RELOAD: &str = "<script>
+(function() {
+  var current = null;
+
+  function poll() {
+    var xhr = new XMLHttpRequest();
+
+    xhr.onload = function() {
+      if (current !== null && xhr.responseText !== current) {
+        window.location.reload();
+        return;
+      }
+
+      current = xhr.responseText;
+      setTimeout(poll, 1000);
+    };
+
+    xhr.onerror = function() {
+      setTimeout(poll, 1000);
+    };
+
+    xhr.open(\"GET\", \"/__reproto/generation\");
+    xhr.send();
+  }
+
+  poll();
+})();
+</script>
+";
+
+    pub fn entry(reporter: &mut Reporter, matches: &ArgMatches, output: &Output) -> Result<()> {
+        let port = matches.value_of("port").unwrap_or(DEFAULT_PORT);
+        let port = port
+            .parse::<u16>()
+            .map_err(|e| format!("bad port `{}`: {}", port, e))?;
+
+        let root = load_manifest(reporter, matches)?
+            .output
+            .ok_or("Missing `--out` or `output=`")?;
+
+        // incremented every time the documentation has been rebuilt.
+        let generation = Arc::new(AtomicUsize::new(0));
+
+        // files discovered while building.
+        let paths: Rc<RefCell<HashSet<PathBuf>>> = Rc::new(RefCell::new(HashSet::new()));
+        // files being watched.
+        let mut watching: HashSet<PathBuf> = HashSet::new();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(0))?;
+
+        let mut reporter = Vec::new();
+        let mut serving = false;
+
+        loop {
+            info!("building documentation");
+
+            reporter.clear();
+
+            let built = match try_build(&mut reporter, matches, &paths) {
+                Err(e) => {
+                    output.handle_context(&reporter)?;
+
+                    // NB: errors which are not diagnostics are not part of the context.
+                    if !reporter.has_errors() {
+                        output.handle_error(&e, None)?;
+                    }
+
+                    false
+                }
+                Ok(()) => {
+                    output.handle_context(&reporter)?;
+                    generation.fetch_add(1, Ordering::SeqCst);
+                    true
+                }
+            };
+
+            {
+                let mut paths = paths.try_borrow_mut()?;
+
+                // a failed build might stop before discovering all files, so keep watching the
+                // files of earlier builds until a build succeeds.
+                if !built {
+                    paths.extend(watching.iter().cloned());
+                }
+
+                update_watches(&mut watcher, &watching, &paths)?;
+
+                watching.clear();
+                watching.extend(paths.drain());
+            }
+
+            if watching.is_empty() {
+                return Err("nothing to watch: no manifest or specifications were found".into());
+            }
+
+            if !serving {
+                spawn_server(port, root.clone(), generation.clone())?;
+                serving = true;
+            }
+
+            // wait for a change to one of the watched files.
+            loop {
+                use notify::DebouncedEvent::*;
+
+                match rx.recv()? {
+                    NoticeWrite(p) | NoticeRemove(p) | Create(p) | Write(p) | Chmod(p)
+                    | Remove(p) => {
+                        if watching.contains(&p) {
+                            break;
+                        }
+                    }
+                    Rename(from, to) => {
+                        if watching.contains(&from) || watching.contains(&to) {
+                            break;
+                        }
+                    }
+                    Rescan => break,
+                    Error(e, _) => return Err(e.into()),
+                }
+            }
+
+            // Sleep to permit file changes to settle...
+            thread::sleep(Duration::from_millis(200));
+
+            while let Ok(_) = rx.try_recv() {}
+        }
+    }
+
+    /// Build documentation, and collect all paths that were used in the build.
+    fn try_build(
+        reporter: &mut Vec<Reported>,
+        matches: &ArgMatches,
+        paths: &Rc<RefCell<HashSet<PathBuf>>>,
+    ) -> Result<()> {
+        // NB: watch the manifest before loading it, so that fixing a broken manifest rebuilds.
+        let manifest_path = matches
+            .value_of("manifest-path")
+            .unwrap_or(env::MANIFEST_NAME);
+        let manifest_path = Path::new(manifest_path);
+
+        if manifest_path.is_file() {
+            let manifest_path = manifest_path.canonicalize()?;
+            paths.try_borrow_mut()?.insert(manifest_path);
+        }
+
+        let mut manifest = load_manifest(reporter, matches)?;
+        manifest.all_versions = manifest.doc.all_versions || matches.is_present("all-versions");
+
+        let local_paths = paths.clone();
+
+        let mut resolver = env::resolver(&manifest)?;
+        let lang = manifest.lang_or_nolang();
+
+        let session = session_with_hook(lang, &manifest, reporter, resolver.as_mut(), move |p| {
+            let p = p
+                .to_owned()
+                .canonicalize()
+                .map_err(|e| format!("{}: {}", p.display(), e))?;
+            local_paths.try_borrow_mut()?.insert(p);
+            Ok(())
+        })?;
+
+        ::doc::compile(session, matches, manifest).map_err(Into::into)
+    }
+
+    /// Update which files are watched, from the files in `watching` to the files in `paths`.
+    ///
+    /// The directories of the files are also watched, so that files which are replaced when they
+    /// are saved, like many editors do, are still noticed.
+    fn update_watches(
+        watcher: &mut RecommendedWatcher,
+        watching: &HashSet<PathBuf>,
+        paths: &HashSet<PathBuf>,
+    ) -> Result<()> {
+        let watching_dirs = parents(watching);
+        let dirs = parents(paths);
+
+        for p in paths
+            .difference(watching)
+            .chain(dirs.difference(&watching_dirs))
+        {
+            debug!("watch: {}", p.display());
+            watcher.watch(p, RecursiveMode::NonRecursive)?;
+        }
+
+        for p in watching
+            .difference(paths)
+            .chain(watching_dirs.difference(&dirs))
+        {
+            debug!("unwatch: {}", p.display());
+            watcher.unwatch(p)?;
+        }
+
+        return Ok(());
+
+        fn parents(paths: &HashSet<PathBuf>) -> HashSet<PathBuf> {
+            paths
+                .iter()
+                .filter_map(|p| p.parent())
+                .map(Path::to_owned)
+                .collect()
+        }
+    }
+
+    /// Spawn a thread serving the given directory.
+    fn spawn_server(port: u16, root: PathBuf, generation: Arc<AtomicUsize>) -> Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        info!(
+            "serving documentation on http://{}/",
+            listener.local_addr()?
+        );
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream.and_then(|stream| handle(&root, &generation, stream));
+
+                if let Err(e) = result {
+                    warn!("failed to serve request: {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Handle a single request.
+    fn handle(root: &Path, generation: &AtomicUsize, mut stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+
+        // skip headers.
+        loop {
+            let mut header = String::new();
+
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+
+        let mut parts = request.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let target = parts.next().unwrap_or("/");
+
+        if method != "GET" {
+            return respond(&mut stream, "405 Method Not Allowed", "text/plain", b"");
+        }
+
+        let target = target.split(|c| c == '?' || c == '#').next().unwrap_or("/");
+
+        if target == GENERATION_PATH {
+            let generation = generation.load(Ordering::SeqCst).to_string();
+            return respond(&mut stream, "200 OK", "text/plain", generation.as_bytes());
+        }
+
+        let path = match resolve(root, target) {
+            Some(path) => path,
+            None => return respond(&mut stream, "404 Not Found", "text/plain", b"not found"),
+        };
+
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(_) => return respond(&mut stream, "404 Not Found", "text/plain", b"not found"),
+        };
+
+        let content_type = content_type(&path);
+
+        if content_type.starts_with("text/html") {
+            let content = inject_live_reload(&content);
+            return respond(&mut stream, "200 OK", content_type, &content);
+        }
+
+        respond(&mut stream, "200 OK", content_type, &content)
+    }
+
+    /// Resolve the requested path to a file in the root directory.
+    ///
+    /// Every segment of the path is percent-decoded, and segments which would escape the root
+    /// directory once decoded are rejected.
+    fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+        let mut path = root.to_owned();
+
+        for part in target.split('/').filter(|p| !p.is_empty()) {
+            let part = percent_decode(part.as_bytes()).decode_utf8().ok()?;
+
+            if part == "." || part == ".." || part.contains(|c| c == '/' || c == '\\') {
+                return None;
+            }
+
+            path = path.join(part.as_ref());
+        }
+
+        if path.is_dir() {
+            path = path.join("index.html");
+        }
+
+        Some(path)
+    }
+
+    /// Insert the live-reload script at the end of the document body.
+    fn inject_live_reload(content: &[u8]) -> Vec<u8> {
+        let content = String::from_utf8_lossy(content);
+
+        let content = match content.rfind("</body>") {
+            Some(index) => format!("{}{}{}", &content[..index], LIVE_RELOAD, &content[index..]),
+            None => format!("{}{}", content, LIVE_RELOAD),
+        };
+
+        content.into_bytes()
+    }
+
+    fn content_type(path: &Path) -> &'static str {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("html") => "text/html; charset=utf-8",
+            Some("css") => "text/css; charset=utf-8",
+            Some("js") => "application/javascript; charset=utf-8",
+            Some("json") => "application/json",
+            Some("md") => "text/markdown; charset=utf-8",
+            _ => "application/octet-stream",
+        }
+    }
+
+    fn respond(
+        stream: &mut TcpStream,
+        status: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> io::Result<()> {
+        write!(stream, "HTTP/1.1 {}\r\n", status)?;
+        write!(stream, "Content-Type: {}\r\n", content_type)?;
+        write!(stream, "Content-Length: {}\r\n", body.len())?;
+        write!(stream, "Cache-Control: no-cache\r\n")?;
+        write!(stream, "Connection: close\r\n\r\n")?;
+        stream.write_all(body)?;
+        stream.flush()
+    }
+}
@@ -0,0 +1,43 @@
+//! Explain error codes.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::codes;
+use core::errors::*;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("explain")
+        .about("Explain an error code, or list all error codes if none is given");
+
+    let out = out.arg(
+        Arg::with_name("code")
+            .takes_value(true)
+            .help("Error code to explain, like `E0001`."),
+    );
+
+    out
+}
+
+pub fn entry(matches: &ArgMatches) -> Result<()> {
+    let id = match matches.value_of("code") {
+        Some(id) => id,
+        None => {
+            for code in codes::ALL {
+                println!("{}: {}", code.id, code.title);
+            }
+
+            return Ok(());
+        }
+    };
+
+    let code = codes::lookup(id).ok_or_else(|| {
+        format!(
+            "no such error code: {}, use `reproto explain` to list all error codes",
+            id
+        )
+    })?;
+
+    println!("{}: {}", code.id, code.title);
+    println!("");
+    print!("{}", code.explanation);
+    Ok(())
+}
@@ -0,0 +1,339 @@
+//! Print the dependency graph of specifications.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::errors::*;
+use core::flavored::{RpDecl, RpField, RpName, RpType, RpVersionedPackage};
+use core::{CoreFlavor, Loc, Reporter, RpRequiredPackage};
+use env;
+use serde_json;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io;
+use trans::Translated;
+use utils::{load_manifest, simple_config};
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("graph").about("Print the dependency graph of specifications");
+
+    let out = out.arg(
+        Arg::with_name("level")
+            .long("level")
+            .takes_value(true)
+            .possible_values(&["package", "type"])
+            .help("Print dependencies between packages or between types (default: package)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["dot", "json"])
+            .help("Output format of the graph (default: dot)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("depth")
+            .long("depth")
+            .takes_value(true)
+            .help("Only include dependencies this many steps away from the given packages"),
+    );
+
+    out
+}
+
+/// A directed graph, where every node is mapped to the nodes that it depends on.
+#[derive(Debug, Default)]
+pub struct Graph {
+    nodes: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[derive(Serialize)]
+struct JsonGraph<'a> {
+    nodes: Vec<&'a str>,
+    edges: Vec<JsonEdge<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonEdge<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl Graph {
+    /// Add a node without any dependencies.
+    fn node(&mut self, node: String) {
+        self.nodes.entry(node).or_insert_with(BTreeSet::new);
+    }
+
+    /// Add a dependency between two nodes.
+    fn edge(&mut self, from: String, to: String) {
+        if from == to {
+            return;
+        }
+
+        self.node(to.clone());
+        self.nodes
+            .entry(from)
+            .or_insert_with(BTreeSet::new)
+            .insert(to);
+    }
+
+    /// Nodes that the given node depends on.
+    pub fn dependencies<'a>(&'a self, node: &str) -> impl Iterator<Item = &'a str> {
+        self.nodes
+            .get(node)
+            .into_iter()
+            .flat_map(|dependencies| dependencies.iter().map(String::as_str))
+    }
+
+    /// Only keep nodes which are at most `depth` steps away from the given roots.
+    fn retain_reachable(&mut self, roots: BTreeSet<String>, depth: Option<usize>) {
+        let mut seen = BTreeSet::new();
+        let mut queue = roots.into_iter().map(|n| (n, 0)).collect::<VecDeque<_>>();
+
+        while let Some((node, d)) = queue.pop_front() {
+            if !seen.insert(node.clone()) {
+                continue;
+            }
+
+            if depth.map(|depth| d >= depth).unwrap_or(false) {
+                continue;
+            }
+
+            if let Some(dependencies) = self.nodes.get(&node) {
+                queue.extend(dependencies.iter().cloned().map(|n| (n, d + 1)));
+            }
+        }
+
+        self.nodes.retain(|node, _| seen.contains(node));
+
+        for dependencies in self.nodes.values_mut() {
+            dependencies.retain(|node| seen.contains(node));
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("digraph dependencies {\n");
+
+        for node in self.nodes.keys() {
+            out.push_str(&format!("  {};\n", dot_id(node)));
+        }
+
+        for (from, dependencies) in &self.nodes {
+            for to in dependencies {
+                out.push_str(&format!("  {} -> {};\n", dot_id(from), dot_id(to)));
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_json(&self) -> JsonGraph {
+        let nodes = self.nodes.keys().map(String::as_str).collect();
+
+        let edges = self
+            .nodes
+            .iter()
+            .flat_map(|(from, dependencies)| {
+                dependencies.iter().map(move |to| JsonEdge {
+                    from: from.as_str(),
+                    to: to.as_str(),
+                })
+            })
+            .collect();
+
+        JsonGraph { nodes, edges }
+    }
+}
+
+/// Builds a graph from the types referenced by declarations.
+struct GraphBuilder {
+    types: bool,
+    graph: Graph,
+}
+
+impl GraphBuilder {
+    fn decl(&mut self, decl: &RpDecl) {
+        use core::RpDecl::*;
+
+        let from = self.node(decl.name());
+        self.graph.node(from.clone());
+
+        match *decl {
+            Type(ref body) => self.fields(&from, &body.fields),
+            Tuple(ref body) => self.fields(&from, &body.fields),
+            Interface(ref body) => {
+                self.fields(&from, &body.fields);
+
+                for sub_type in &body.sub_types {
+                    self.fields(&from, &sub_type.fields);
+                }
+            }
+            Union(ref body) => {
+                for variant in &body.variants {
+                    self.ty(&from, &variant.ty);
+                }
+            }
+            Newtype(ref body) => {
+                self.ty(&from, &body.ty);
+            }
+            Service(ref body) => {
+                for endpoint in &body.endpoints {
+                    for argument in &endpoint.arguments {
+                        self.ty(&from, argument.channel.ty());
+                    }
+
+                    if let Some(ref response) = endpoint.response {
+                        self.ty(&from, response.ty());
+                    }
+                }
+            }
+            Enum(..) => {}
+        }
+    }
+
+    fn fields(&mut self, from: &str, fields: &[Loc<RpField>]) {
+        for field in fields {
+            self.ty(from, &field.ty);
+        }
+    }
+
+    fn ty(&mut self, from: &str, ty: &RpType) {
+        use core::RpType::*;
+
+        match *ty {
+            Name { ref name } => {
+                let to = self.node(name);
+                self.graph.edge(from.to_string(), to);
+            }
+            Array { ref inner } | Set { ref inner } => self.ty(from, inner),
+            Map { ref key, ref value } => {
+                self.ty(from, key);
+                self.ty(from, value);
+            }
+            _ => {}
+        }
+    }
+
+    /// The node that the given name belongs to.
+    fn node(&self, name: &RpName) -> String {
+        if self.types {
+            type_node(name)
+        } else {
+            name.package.to_string()
+        }
+    }
+}
+
+pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, m)?;
+    let mut resolver = env::resolver(&manifest)?;
+    let session = simple_config(&manifest, reporter, resolver.as_mut())?;
+    let session = session.translate_default()?;
+
+    let types = match m.value_of("level") {
+        Some("type") => true,
+        _ => false,
+    };
+
+    let depth = match m.value_of("depth") {
+        Some(depth) => Some(
+            depth
+                .parse::<usize>()
+                .map_err(|e| format!("bad `--depth` argument: {}", e))?,
+        ),
+        None => None,
+    };
+
+    let packages = manifest
+        .packages
+        .iter()
+        .flat_map(|p| p.iter())
+        .collect::<Vec<_>>();
+
+    let mut graph = build_graph(&session, types);
+
+    // required packages (`--package` or `packages` in the manifest) are the roots of the graph.
+    if !packages.is_empty() {
+        let roots = roots(&session, &packages, types);
+        graph.retain_reachable(roots, depth);
+    }
+
+    match m.value_of("format") {
+        Some("json") => {
+            let stdout = io::stdout();
+            serde_json::to_writer_pretty(stdout.lock(), &graph.to_json())?;
+            println!("");
+        }
+        _ => print!("{}", graph.to_dot()),
+    }
+
+    Ok(())
+}
+
+/// Build the graph for all loaded declarations.
+pub fn build_graph(session: &Translated<CoreFlavor>, types: bool) -> Graph {
+    let mut builder = GraphBuilder {
+        types: types,
+        graph: Graph::default(),
+    };
+
+    for (package, file) in session.for_each_file() {
+        if !types {
+            builder.graph.node(package.to_string());
+        }
+
+        for decl in file.for_each_decl() {
+            builder.decl(decl);
+        }
+    }
+
+    builder.graph
+}
+
+/// Find the nodes belonging to the required packages.
+fn roots(
+    session: &Translated<CoreFlavor>,
+    packages: &[&RpRequiredPackage],
+    types: bool,
+) -> BTreeSet<String> {
+    let mut roots = BTreeSet::new();
+
+    for (package, file) in session.for_each_file() {
+        if !packages.iter().any(|p| matches_package(p, package)) {
+            continue;
+        }
+
+        if !types {
+            roots.insert(package.to_string());
+            continue;
+        }
+
+        roots.extend(file.for_each_decl().map(|d| type_node(d.name())));
+    }
+
+    roots
+}
+
+fn matches_package(required: &RpRequiredPackage, package: &RpVersionedPackage) -> bool {
+    if required.package != package.package {
+        return false;
+    }
+
+    match package.version {
+        Some(ref version) => required.range.matches(version),
+        None => true,
+    }
+}
+
+/// Fully qualified name of a type, used as its node in the graph.
+fn type_node(name: &RpName) -> String {
+    format!("{}::{}", name.package, name.path.join("::"))
+}
+
+/// Quote a node for use as an identifier in the DOT language.
+fn dot_id(node: &str) -> String {
+    format!("\"{}\"", node.replace('"', "\\\""))
+}
@@ -0,0 +1,129 @@
+//! Print information about a package.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::errors::*;
+use core::{Reporter, Resolved, Resolver, RpRequiredPackage, RpVersionedPackage};
+use env::{self, Vendored};
+use ops::graph::build_graph;
+use utils::{load_manifest, simple_config};
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("info")
+        .about("Print the versions of a package, and what the resolved version contains");
+
+    let out = out.arg(
+        Arg::with_name("package")
+            .required(true)
+            .help("Package to print, optionally limited to a range of versions like `foo@^1`"),
+    );
+
+    out
+}
+
+pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, m)?;
+
+    let package = m.value_of("package").ok_or_else(|| "missing <package>")?;
+    let package = RpRequiredPackage::parse(package)?;
+
+    println!("{}", package.package);
+    println!("");
+    println!("versions:");
+
+    if let Some(mut resolver) = env::path_resolver(&manifest)? {
+        print_resolved("path", resolver.resolve_all(&package)?);
+    }
+
+    if let Some(path) = env::vendor_path(&manifest) {
+        if path.is_dir() {
+            print_resolved("vendor", Vendored::new(path).resolve_all(&package)?);
+        }
+    }
+
+    let repository = env::repository(&manifest)?;
+
+    let deployments = repository
+        .all(&package.package)?
+        .into_iter()
+        .filter(|d| package.range.matches(&d.version))
+        .collect::<Vec<_>>();
+
+    if !deployments.is_empty() {
+        println!("  repository:");
+    }
+
+    for d in deployments {
+        match d.signature {
+            Some(ref signature) => println!(
+                "    {} {} (signed by {})",
+                d.version, d.object, signature.key
+            ),
+            None => println!("    {} {}", d.version, d.object),
+        }
+
+        for change in &d.changelog {
+            println!("      {}", change);
+        }
+    }
+
+    let mut resolver = env::resolver(&manifest)?;
+
+    let resolved = resolver
+        .resolve(&package)?
+        .ok_or_else(|| format!("no matching package: {}", package))?;
+
+    let Resolved { version, source } = resolved;
+
+    println!("");
+
+    match version {
+        Some(ref version) => println!("resolves to: {} (from {})", version, source),
+        None => println!("resolves to: {}", source),
+    }
+
+    let versioned = RpVersionedPackage::new(package.package.clone(), version);
+
+    let mut session = simple_config(&manifest, reporter, resolver.as_mut())?;
+    session.import_source(source, Some(versioned.clone()))?;
+    let session = session.translate_default()?;
+
+    let file = session
+        .for_each_file()
+        .find(|&(p, _)| *p == versioned)
+        .map(|(_, file)| file)
+        .ok_or_else(|| format!("package not loaded: {}", versioned))?;
+
+    println!("");
+    println!("declarations:");
+
+    for decl in file.for_each_decl() {
+        println!("  {} {}", decl.kind(), decl.name());
+    }
+
+    let graph = build_graph(&session, false);
+
+    println!("");
+    println!("dependencies:");
+
+    for dependency in graph.dependencies(&versioned.to_string()) {
+        println!("  {}", dependency);
+    }
+
+    Ok(())
+}
+
+/// Print the versions found by a single resolver.
+fn print_resolved(name: &str, resolved: Vec<Resolved>) {
+    if resolved.is_empty() {
+        return;
+    }
+
+    println!("  {}:", name);
+
+    for r in resolved {
+        match r.version {
+            Some(version) => println!("    {} ({})", version, r.source),
+            None => println!("    (no version) ({})", r.source),
+        }
+    }
+}
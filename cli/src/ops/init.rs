@@ -2,8 +2,9 @@
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 use core::errors::*;
-use core::Filesystem;
+use core::{Filesystem, RpPackage};
 use env;
+use manifest::Language;
 use std::path::Path;
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
@@ -16,6 +17,30 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Path to initialize the new project in. Defaults to current."),
     );
 
+    let out = out.arg(
+        Arg::with_name("lang")
+            .long("lang")
+            .takes_value(true)
+            .help("Language to build for, like `java` or `rust`."),
+    );
+
+    let out = out.arg(
+        Arg::with_name("package")
+            .long("package")
+            .takes_value(true)
+            .help("Package of the example specification. Defaults to `io.reproto.example`."),
+    );
+
+    let out = out.arg(
+        Arg::with_name("editor")
+            .long("editor")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .possible_values(&["vscode", "editorconfig"])
+            .help("Write settings for the given editor."),
+    );
+
     out
 }
 
@@ -26,7 +51,25 @@ pub fn entry(fs: &Filesystem, matches: &ArgMatches) -> Result<()> {
         ::std::env::current_dir()?
     };
 
+    let mut options = env::InitializeOptions::default();
+
+    if let Some(lang) = matches.value_of("lang") {
+        let language =
+            Language::parse(lang).ok_or_else(|| format!("unsupported language: {}", lang))?;
+        options.language = Some(language);
+    }
+
+    if let Some(package) = matches.value_of("package") {
+        options.package = Some(RpPackage::parse(package));
+    }
+
+    for editor in matches.values_of("editor").into_iter().flat_map(|it| it) {
+        let editor = env::Editor::parse(editor)
+            .ok_or_else(|| format!("unsupported editor: {}", editor))?;
+        options.editors.push(editor);
+    }
+
     let handle = fs.open_root(Some(&path))?;
-    env::initialize(handle.as_ref())?;
+    env::initialize(handle.as_ref(), options)?;
     Ok(())
 }
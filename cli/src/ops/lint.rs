@@ -0,0 +1,100 @@
+//! Action to check specifications against style rules.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::errors::*;
+use core::{Diagnostics, Reporter, Source};
+use env;
+use lint;
+use manifest;
+use parser;
+use std::fs::File;
+use std::io::Write;
+use utils::load_manifest;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("lint").about("Check specifications against style rules");
+
+    let out = out.arg(
+        Arg::with_name("fix")
+            .long("fix")
+            .help("Apply suggested fixes to the specifications"),
+    );
+
+    out
+}
+
+pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, m)?;
+    let mut resolver = env::resolver(&manifest)?;
+    let fix = m.is_present("fix");
+
+    let mut errors = Vec::new();
+
+    for manifest::Source { source, .. } in manifest.resolve(resolver.as_mut())? {
+        let content = parser::read_to_string(source.read()?)?;
+        let mut diag = Diagnostics::new(source.clone());
+
+        let lints = match parser::parse(&mut diag, content.as_str()) {
+            Ok(file) => lint::lint(&manifest.lint, &file),
+            Err(()) => {
+                reporter.diagnostics(diag);
+                errors.push(format!("{}: failed to parse", source).into());
+                continue;
+            }
+        };
+
+        let mut denied = 0usize;
+        let mut edits = Vec::new();
+
+        for l in lints {
+            let message = format!("{} (`{}`)", l.message, l.rule);
+
+            match l.level {
+                lint::Level::Deny => {
+                    diag.err(l.span, message);
+
+                    if !fix || l.suggestion.is_none() {
+                        denied += 1;
+                    }
+                }
+                lint::Level::Warn => diag.warn(l.span, message),
+                lint::Level::Allow => continue,
+            }
+
+            if let Some(suggestion) = l.suggestion {
+                diag.info(l.span, format!("help: {}", suggestion.message));
+                edits.extend(suggestion.edits);
+            }
+        }
+
+        reporter.diagnostics(diag);
+
+        if fix && !edits.is_empty() {
+            apply_edits(&source, &content, edits)?;
+        }
+
+        if denied > 0 {
+            errors.push(format!("{}: {} lint(s) denied", source, denied).into());
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::new("error when linting").with_suppressed(errors));
+    }
+
+    Ok(())
+}
+
+/// Apply the edits from suggestions to the file backing the given source.
+fn apply_edits(source: &Source, content: &str, edits: Vec<lint::Edit>) -> Result<()> {
+    let path = match source.path() {
+        Some(path) if !source.read_only => path,
+        _ => return Err(format!("{}: source can't be modified", source).into()),
+    };
+
+    let content = lint::apply_edits(content, edits);
+
+    info!("fixed: {}", path.display());
+    File::create(path)?.write_all(content.as_bytes())?;
+    Ok(())
+}
@@ -1,13 +1,20 @@
 mod build;
 mod check;
 mod derive;
+mod diff;
 mod doc;
+mod explain;
+mod graph;
+mod info;
 mod init;
 mod language_server;
+mod lint;
 mod publish;
 mod repo;
 mod self_update;
+mod test;
 mod update;
+mod vendor;
 mod watch;
 
 use clap::{App, Arg, ArgMatches};
@@ -39,6 +46,13 @@ pub fn base_args<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .help("Completely disable repository operations"),
     );
 
+    let out = out.arg(
+        Arg::with_name("offline")
+            .long("offline")
+            .takes_value(false)
+            .help("Only use packages which are available in local caches"),
+    );
+
     let out = out.arg(
         Arg::with_name("objects")
             .long("objects")
@@ -54,7 +68,7 @@ pub fn base_args<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .takes_value(true)
             .multiple(true)
             .number_of_values(1)
-            .help("Paths to look for definitions."),
+            .help("Additional paths to look for definitions."),
     );
 
     let out = out.arg(
@@ -64,6 +78,15 @@ pub fn base_args<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .help("Path to manifest to build"),
     );
 
+    let out = out.arg(
+        Arg::with_name("set")
+            .long("set")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Override a value in the manifest, like `output=gen/` or `java.modules=builder`"),
+    );
+
     out
 }
 
@@ -130,11 +153,18 @@ pub fn options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
     let out = out.subcommand(build_args(doc::options()));
     let out = out.subcommand(build_args(watch::options()));
     let out = out.subcommand(base_args(check::options()));
+    let out = out.subcommand(base_args(diff::options()));
+    let out = out.subcommand(build_args(lint::options()));
+    let out = out.subcommand(build_args(graph::options()));
+    let out = out.subcommand(build_args(test::options()));
     let out = out.subcommand(base_args(publish::options()));
+    let out = out.subcommand(base_args(info::options()));
     let out = out.subcommand(base_args(update::options()));
     let out = out.subcommand(base_args(self_update::options()));
     let out = out.subcommand(base_args(repo::options()));
+    let out = out.subcommand(base_args(vendor::options()));
     let out = out.subcommand(derive::options());
+    let out = out.subcommand(explain::options());
     let out = out.subcommand(init::options());
     out
 }
@@ -156,7 +186,7 @@ fn default_logging(matches: &ArgMatches, output: &Output) -> Result<()> {
 }
 
 pub fn entry(
-    fs: &Filesystem,
+    fs: &(Filesystem + Sync),
     reporter: &mut Reporter,
     matches: &ArgMatches,
     output: &Output,
@@ -176,12 +206,19 @@ pub fn entry(
         "build" => return build::entry(fs, reporter, matches),
         "check" => return check::entry(reporter, matches),
         "derive" => return derive::entry(reporter, matches),
-        "doc" => return doc::entry(reporter, matches),
+        "diff" => return diff::entry(reporter, matches),
+        "doc" => return doc::entry(reporter, matches, output),
+        "explain" => return explain::entry(matches),
+        "graph" => return graph::entry(reporter, matches),
+        "info" => return info::entry(reporter, matches),
         "init" => return init::entry(fs, matches),
+        "lint" => return lint::entry(reporter, matches),
         "publish" => return publish::entry(reporter, matches),
         "repo" => return repo::entry(matches),
         "self-update" => return self_update::entry(matches),
-        "update" => return update::entry(matches),
+        "test" => return test::entry(reporter, matches),
+        "update" => return update::entry(reporter, matches),
+        "vendor" => return vendor::entry(reporter, matches),
         "watch" => return watch::entry(fs, matches, output),
         _ => {}
     }
@@ -2,7 +2,9 @@ use clap::{App, Arg, ArgMatches, SubCommand};
 use core::errors::*;
 use core::{Diagnostics, Reporter, RpRequiredPackage, RpVersionedPackage, Version};
 use env;
-use utils::{load_manifest, matches, publish_matches, semck_check, simple_config, Match};
+use utils::{
+    changelog, load_manifest, matches, publish_matches, semck_check, simple_config, Match,
+};
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("publish").about("Publish specifications");
@@ -25,6 +27,19 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Disable Semantic Checks"),
     );
 
+    let out = out.arg(
+        Arg::with_name("changelog")
+            .long("changelog")
+            .help("Store a changelog of the changes since the previously published version"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("signing-key")
+            .long("signing-key")
+            .takes_value(true)
+            .help("Path to a PKCS#8 encoded ed25519 key to sign published packages with"),
+    );
+
     let out = out.arg(
         Arg::with_name("version")
             .long("version")
@@ -38,7 +53,7 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
 }
 
 pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
-    let manifest = load_manifest(m)?;
+    let manifest = load_manifest(reporter, m)?;
     let mut resolver = env::resolver(&manifest)?;
     let mut session = simple_config(&manifest, reporter, resolver.as_mut())?;
 
@@ -79,12 +94,15 @@ pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
     let force = m.is_present("force");
     let pretend = m.is_present("pretend");
     let no_semck = m.is_present("no-semck");
+    let with_changelog = m.is_present("changelog");
 
     let mut repository = env::repository(&manifest)?;
 
     // errors that would prevent publishing
     let mut semck_errors = Vec::new();
     let mut feature_ok = true;
+    // changelogs of each published package, in the same order as results
+    let mut changelogs = Vec::new();
 
     for m in &results {
         let Match {
@@ -106,6 +124,12 @@ pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
             &file,
         )?;
 
+        changelogs.push(if with_changelog {
+            changelog(&mut repository, &mut session, version, &package, &file)?
+        } else {
+            vec![]
+        });
+
         if !file.features.is_empty() {
             let mut diag = Diagnostics::new(source.clone());
 
@@ -128,7 +152,7 @@ pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
         return Err(Error::new("Validation errors").with_suppressed(semck_errors));
     }
 
-    for m in results {
+    for (m, changelog) in results.into_iter().zip(changelogs) {
         let Match {
             version,
             source,
@@ -140,9 +164,13 @@ pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
                 "(pretend) publishing: {}@{} (from {})",
                 package, version, source
             );
+
+            for change in &changelog {
+                info!("(pretend)   {}", change);
+            }
         } else {
             info!("publishing: {}@{} (from {})", package, version, source);
-            repository.publish(&source, &package, &version, force)?;
+            repository.publish(&source, &package, &version, &changelog, force)?;
         }
     }
 
@@ -2,7 +2,8 @@
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 use core::errors::*;
-use repository::init_file_index;
+use env;
+use repository::{init_file_index, Cache, SigningKey};
 
 fn init(matches: &ArgMatches) -> Result<()> {
     for path in matches.values_of("path").into_iter().flat_map(|it| it) {
@@ -25,9 +26,95 @@ fn init_options<'a, 'b>() -> App<'a, 'b> {
     out
 }
 
+fn keygen(matches: &ArgMatches) -> Result<()> {
+    let path = matches.value_of("path").ok_or_else(|| "missing <path>")?;
+    let public_key = SigningKey::generate(path)?;
+
+    info!("Wrote signing key to: {}", path);
+    info!("Public key: {}", public_key);
+    Ok(())
+}
+
+fn keygen_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("keygen").about("Generate a key used to sign packages");
+
+    let out = out.arg(
+        Arg::with_name("path")
+            .required(true)
+            .help("Path to write the key to"),
+    );
+
+    out
+}
+
+/// Open the global cache.
+fn cache() -> Result<Cache> {
+    let config_env = env::ConfigEnvironment::new()?
+        .ok_or_else(|| "could not determine the location of the cache")?;
+
+    Ok(Cache::new(config_env.cache_home))
+}
+
+fn prune(matches: &ArgMatches) -> Result<()> {
+    let cache = cache()?;
+
+    info!("Pruning cache: {}", cache.path().display());
+
+    let removed = cache.prune(matches.is_present("all"))?;
+
+    for path in &removed {
+        debug!("removed: {}", path.display());
+    }
+
+    info!("Removed {} entries", removed.len());
+    Ok(())
+}
+
+fn prune_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("prune")
+        .about("Remove stale and corrupt entries from the global cache");
+
+    let out = out.arg(
+        Arg::with_name("all")
+            .long("all")
+            .help("Remove everything in the cache"),
+    );
+
+    out
+}
+
+fn verify(_matches: &ArgMatches) -> Result<()> {
+    let cache = cache()?;
+
+    info!("Verifying cache: {}", cache.path().display());
+
+    let corrupt = cache.verify()?;
+
+    if corrupt.is_empty() {
+        return Ok(());
+    }
+
+    for path in &corrupt {
+        error!("corrupt object: {}", path.display());
+    }
+
+    Err(format!(
+        "{} corrupt object(s) in cache, run `reproto repo prune` to remove them",
+        corrupt.len()
+    ).into())
+}
+
+fn verify_options<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("verify")
+        .about("Verify the checksums of all objects in the global cache")
+}
+
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("repo").about("Manage repositories");
     let out = out.subcommand(init_options());
+    let out = out.subcommand(keygen_options());
+    let out = out.subcommand(prune_options());
+    let out = out.subcommand(verify_options());
     out
 }
 
@@ -37,6 +124,9 @@ pub fn entry(matches: &ArgMatches) -> Result<()> {
 
     match name {
         "init" => init(matches),
+        "keygen" => keygen(matches),
+        "prune" => prune(matches),
+        "verify" => verify(matches),
         _ => unreachable!("bad subcommand"),
     }
 }
@@ -0,0 +1,331 @@
+//! Run round-trip tests against the projects declared under `[test]` in the manifest.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use core::errors::{Error, Result};
+use core::{RealFilesystem, Reporter};
+use manifest::{Manifest, TestProject};
+use ops::build::build_target;
+use serde_json as json;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use utils::load_manifest;
+
+/// Make target used to build projects which don't specify one.
+const DEFAULT_INSTANCE: &str = "default";
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("test")
+        .about("Run round-trip tests for the projects declared under [test] in the manifest");
+
+    let out = out.arg(
+        Arg::with_name("project")
+            .long("project")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Only test the project with the given name"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("workdir")
+            .long("workdir")
+            .takes_value(true)
+            .help("Directory to build projects in, overriding `workdir` in the manifest"),
+    );
+
+    out
+}
+
+pub fn entry(reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, matches)?;
+
+    let workdir = match matches.value_of("workdir") {
+        Some(workdir) => PathBuf::from(workdir),
+        None => default_workdir(&manifest)?,
+    };
+
+    let fixtures = read_fixtures(&manifest.test.fixtures)?;
+
+    if fixtures.is_empty() {
+        return Err("no fixtures to test with, declare them under `[test]` as `fixtures`".into());
+    }
+
+    let filter = matches
+        .values_of("project")
+        .map(|p| p.map(ToOwned::to_owned).collect::<Vec<_>>());
+
+    let projects = manifest
+        .into_test_projects()
+        .into_iter()
+        .filter(|&(ref project, _)| match filter {
+            Some(ref filter) => filter.contains(&project.name),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    if projects.is_empty() {
+        return Err("no projects to test, declare them under `[[test.projects]]`".into());
+    }
+
+    let mut errors = Vec::new();
+
+    for (project, manifest) in projects {
+        let target = workdir.join(&project.name);
+
+        match test_project(reporter, &project, manifest, &target, &fixtures) {
+            Ok(()) => info!(
+                "{}: {} document(s) round-tripped",
+                project.name,
+                fixtures.len()
+            ),
+            Err(e) => {
+                error!("{}: failed", project.name);
+                errors.push(Error::from(format!("{}: {}", project.name, e.display())));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::new("round-trip tests failed").with_suppressed(errors));
+    }
+
+    Ok(())
+}
+
+/// Directory to build projects in, unless one is specified.
+fn default_workdir(manifest: &Manifest) -> Result<PathBuf> {
+    if let Some(workdir) = manifest.test.workdir.as_ref() {
+        return Ok(workdir.clone());
+    }
+
+    let parent = manifest
+        .path
+        .as_ref()
+        .and_then(|p| p.parent())
+        .ok_or_else(|| "no manifest to test, or `--workdir` to build projects in")?;
+
+    Ok(parent.join("target").join("reproto-test"))
+}
+
+/// A single document to round-trip.
+struct Fixture {
+    /// Where the document was loaded from.
+    location: String,
+    /// The line to send to the project.
+    line: String,
+    /// The parsed document.
+    document: json::Value,
+}
+
+/// Read all documents from the given fixtures.
+///
+/// Every non-empty line which is not a comment (starting with `#`) is a document.
+fn read_fixtures(paths: &[PathBuf]) -> Result<Vec<Fixture>> {
+    let mut out = Vec::new();
+
+    for path in paths {
+        let f = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        for (index, line) in BufReader::new(f).lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let location = format!("{}:{}", path.display(), index + 1);
+
+            let document = json::from_str(&line)
+                .map_err(|e| format!("{}: not a JSON document: {}", location, e))?;
+
+            out.push(Fixture {
+                location,
+                line,
+                document,
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+/// Build a single project, and feed every fixture through it.
+fn test_project(
+    reporter: &mut Reporter,
+    project: &TestProject,
+    mut manifest: Manifest,
+    target: &Path,
+    fixtures: &[Fixture],
+) -> Result<()> {
+    if target.is_dir() {
+        fs::remove_dir_all(target).map_err(|e| format!("{}: {}", target.display(), e))?;
+    }
+
+    copy_dir(&project.path, target)?;
+
+    manifest.output = Some(project.output.to_path(target));
+    build_target(&RealFilesystem::new(), reporter, manifest)?;
+
+    let instance = project
+        .instance
+        .as_ref()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_INSTANCE);
+
+    let output = Command::new("make")
+        .arg(instance)
+        .current_dir(target)
+        .output()
+        .map_err(|e| format!("failed to run `make {}`: {}", instance, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "`make {}` failed: {}\nstdout:\n{}\nstderr:\n{}",
+            instance,
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    let script = target.join("script.sh");
+
+    if !script.is_file() {
+        return Err(format!("`make {}` did not build: {}", instance, script.display()).into());
+    }
+
+    let mut child = Command::new(&script)
+        .current_dir(target)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format!("{}: {}", script.display(), e))?;
+
+    let mut stdin = child.stdin.take().ok_or_else(|| "no stdin")?;
+    let lines = fixtures.iter().map(|f| f.line.clone()).collect::<Vec<_>>();
+
+    // write from a separate thread, so that the project is not blocked on a full stdout.
+    let writer = thread::spawn(move || -> ::std::io::Result<()> {
+        for line in lines {
+            writeln!(stdin, "{}", line)?;
+        }
+
+        Ok(())
+    });
+
+    let stdout = child.stdout.take().ok_or_else(|| "no stdout")?;
+
+    let mut actual = Vec::new();
+
+    for line in BufReader::new(stdout).lines() {
+        actual.push(line?);
+    }
+
+    let status = child.wait()?;
+
+    writer
+        .join()
+        .map_err(|_| "failed to write fixtures")?
+        .map_err(|e| format!("failed to write fixtures: {}", e))?;
+
+    if !status.success() {
+        return Err(format!("{} exited with {}", script.display(), status).into());
+    }
+
+    if actual.len() != fixtures.len() {
+        return Err(format!(
+            "expected {} document(s) on stdout, but got {}",
+            fixtures.len(),
+            actual.len()
+        )
+        .into());
+    }
+
+    let mut errors = Vec::new();
+
+    for (fixture, line) in fixtures.iter().zip(actual) {
+        let document = match json::from_str::<json::Value>(&line) {
+            Ok(document) => document,
+            Err(e) => {
+                errors.push(format!("{}: bad JSON in output: {}", fixture.location, e));
+                continue;
+            }
+        };
+
+        if !similar(&fixture.document, &document) {
+            errors.push(format!(
+                "{}: document changed by round-trip\n  expected: {}\n  actual:   {}",
+                fixture.location, fixture.document, document
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n").into());
+    }
+
+    Ok(())
+}
+
+/// Recursively copy the source directory into the target directory.
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    fs::create_dir_all(target).map_err(|e| format!("{}: {}", target.display(), e))?;
+
+    for e in fs::read_dir(source).map_err(|e| format!("{}: {}", source.display(), e))? {
+        let e = e?;
+        let path = e.path();
+        let dest = target.join(e.file_name());
+
+        if path.is_dir() {
+            copy_dir(&path, &dest)?;
+            continue;
+        }
+
+        fs::copy(&path, &dest).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Check if two documents are similar enough to be considered equal.
+///
+/// Floating point numbers are permitted to differ slightly, since not every language preserves
+/// their exact representation.
+fn similar(left: &json::Value, right: &json::Value) -> bool {
+    use self::json::Value::*;
+
+    match (left, right) {
+        (&Null, &Null) => true,
+        (&Bool(ref left), &Bool(ref right)) => left == right,
+        (&Number(ref left), &Number(ref right)) => {
+            if let (Some(l), Some(r)) = (left.as_u64(), right.as_u64()) {
+                return l == r;
+            }
+
+            if let (Some(l), Some(r)) = (left.as_i64(), right.as_i64()) {
+                return l == r;
+            }
+
+            match (left.as_f64(), right.as_f64()) {
+                (Some(l), Some(r)) => (l - r).abs() < 0.0001f64,
+                _ => false,
+            }
+        }
+        (&String(ref left), &String(ref right)) => left == right,
+        (&Array(ref left), &Array(ref right)) => {
+            left.len() == right.len() && left.iter().zip(right.iter()).all(|(l, r)| similar(l, r))
+        }
+        (&Object(ref left), &Object(ref right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .all(|(key, l)| right.get(key).map(|r| similar(l, r)).unwrap_or(false))
+        }
+        _ => false,
+    }
+}
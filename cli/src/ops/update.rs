@@ -1,19 +1,25 @@
-//! Update action that synchronizes all repositories.
+//! Update action that synchronizes all repositories, and refreshes the lockfile.
 
 use clap::{App, ArgMatches, SubCommand};
 use core::errors::*;
+use core::Reporter;
 use env;
 use repository::Update;
 use std::collections::HashSet;
 use utils::load_manifest;
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
-    let out = SubCommand::with_name("update").about("Update local repository");
+    let out = SubCommand::with_name("update")
+        .about("Update local repository, and lock packages to their most recent versions");
     out
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
-    let manifest = load_manifest(matches)?;
+pub fn entry(reporter: &mut Reporter, matches: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, matches)?;
+
+    if manifest.repository.offline {
+        return Err("cannot update repositories in offline mode".into());
+    }
 
     let repository = env::repository(&manifest)?;
     let updates: HashSet<Update> = repository.update()?.into_iter().collect();
@@ -22,5 +28,6 @@ pub fn entry(matches: &ArgMatches) -> Result<()> {
         update.update()?;
     }
 
+    env::update_lockfile(&manifest)?;
     Ok(())
 }
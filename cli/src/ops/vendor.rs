@@ -0,0 +1,58 @@
+//! Vendor all packages resolved from the repository into a local directory.
+
+use clap::{App, ArgMatches, SubCommand};
+use core::errors::*;
+use core::{Reporter, Resolver};
+use env;
+use repository::Resolvers;
+use std::fs;
+use utils::{load_manifest, simple_config};
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("vendor").about(
+        "Download all packages resolved from the repository into the vendor directory, which will \
+         be used in favor of the repository in subsequent builds",
+    );
+
+    out
+}
+
+pub fn entry(reporter: &mut Reporter, m: &ArgMatches) -> Result<()> {
+    let manifest = load_manifest(reporter, m)?;
+
+    let path = env::vendor_path(&manifest).ok_or_else(|| "manifest does not have a path")?;
+
+    // NB: vendor into a temporary directory, to keep the existing vendor directory intact if
+    // vendoring fails.
+    let mut tmp_path = path.clone();
+    tmp_path.set_extension("tmp");
+
+    if tmp_path.is_dir() {
+        fs::remove_dir_all(&tmp_path)?;
+    }
+
+    fs::create_dir_all(&tmp_path)?;
+
+    {
+        let mut resolvers = Vec::<Box<Resolver>>::new();
+        resolvers.extend(env::path_resolver(&manifest)?);
+        resolvers.push(Box::new(env::Vendoring::new(
+            tmp_path.clone(),
+            env::repository_resolver(&manifest)?,
+        )));
+
+        let mut resolver = Resolvers::new(resolvers);
+
+        // NB: setting up the session resolves all packages, and everything they import.
+        simple_config(&manifest, reporter, &mut resolver)?;
+    }
+
+    if path.is_dir() {
+        fs::remove_dir_all(&path)?;
+    }
+
+    fs::rename(&tmp_path, &path)?;
+
+    info!("vendored packages into: {}", path.display());
+    Ok(())
+}
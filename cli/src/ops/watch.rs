@@ -76,15 +76,19 @@ pub fn entry(fs: &Filesystem, matches: &ArgMatches, output: &Output) -> Result<(
             &written_dirs,
         ) {
             Err(e) => {
-                if reporter.is_empty() {
+                output.handle_context(&reporter)?;
+
+                // NB: errors which are not diagnostics are not part of the context.
+                if !reporter.has_errors() {
                     output.handle_error(&e, None)?;
-                } else {
-                    output.handle_context(&reporter)?;
                 }
 
                 false
             }
-            Ok(_) => true,
+            Ok(_) => {
+                output.handle_context(&reporter)?;
+                true
+            }
         };
 
         // if last build _was_ successful, delete files.
@@ -261,7 +265,7 @@ pub fn entry(fs: &Filesystem, matches: &ArgMatches, output: &Output) -> Result<(
     ) -> Result<()> {
         let fs = stalker::StalkerFilesystem::new(fs, added_files.clone(), added_dirs.clone());
 
-        let manifest = load_manifest(matches)?;
+        let manifest = load_manifest(reporter, matches)?;
         let lang = manifest.lang().ok_or_else(|| "no language to build for")?;
 
         if let Some(path) = manifest.path.as_ref() {
@@ -274,7 +278,7 @@ pub fn entry(fs: &Filesystem, matches: &ArgMatches, output: &Output) -> Result<(
 
         let local_paths = paths.clone();
 
-        let manifest = load_manifest(matches)?;
+        let manifest = load_manifest(reporter, matches)?;
         let mut resolver = env::resolver(&manifest)?;
 
         let session = session_with_hook(
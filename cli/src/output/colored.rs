@@ -1,7 +1,8 @@
+use super::snippet::{write_snippet, Level, Palette};
 use super::{LockableWrite, Output};
-use ansi_term::Colour::{self, Blue, Red};
+use ansi_term::Colour::{Blue, Red};
 use core::errors::*;
-use core::{self, Source, Span};
+use core::{Code, Source, Span};
 use log;
 use std::io;
 
@@ -17,33 +18,16 @@ where
         Colored { out: out }
     }
 
-    fn print_positional(&self, source: &Source, span: &Span, m: &str, color: Colour) -> Result<()> {
-        use std::cmp::max;
-        use std::iter::repeat;
-
+    fn print_positional(
+        &self,
+        level: Level,
+        source: &Source,
+        span: &Span,
+        m: &str,
+        code: Option<&Code>,
+    ) -> Result<()> {
         let mut o = self.out.lock();
-
-        let (line_str, line, (s, e)) =
-            core::utils::find_line(source.read()?, (span.start, span.end))?;
-
-        let line_no = format!("{:>3}:", line + 1);
-
-        let mut indicator = String::new();
-
-        indicator.extend(repeat(' ').take(line_no.len() + s + 1));
-        indicator.extend(repeat('^').take(max(1, e - s)));
-
-        writeln!(o, "{}:{}:{}-{}:", source, line + 1, s + 1, e + 1)?;
-        writeln!(o, "{} {}", Blue.paint(line_no), line_str)?;
-        writeln!(
-            o,
-            "{}{}{}",
-            color.paint(indicator),
-            color.paint(" - "),
-            color.paint(m)
-        )?;
-
-        Ok(())
+        write_snippet(&mut o, &Palette::colored(), level, source, span, m, code)
     }
 }
 
@@ -55,6 +39,10 @@ where
         self.out.lock()
     }
 
+    fn error_header(&self, m: &str) -> Result<String> {
+        Ok(format!("{}: {}", Red.bold().paint("error"), m))
+    }
+
     fn error_message(&self, m: &str) -> Result<String> {
         use ansi_term::Colour::Red;
         Ok(format!("{}", Red.paint(m)))
@@ -73,11 +61,21 @@ where
     }
 
     fn print_info(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
-        self.print_positional(source, span, m, Colour::Yellow)
+        self.print_positional(Level::Note, source, span, m, None)
+    }
+
+    fn print_error(
+        &self,
+        source: &Source,
+        span: &Span,
+        m: &str,
+        code: Option<&Code>,
+    ) -> Result<()> {
+        self.print_positional(Level::Error, source, span, m, code)
     }
 
-    fn print_error(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
-        self.print_positional(source, span, m, Colour::Red)
+    fn print_warning(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
+        self.print_positional(Level::Warning, source, span, m, None)
     }
 }
 
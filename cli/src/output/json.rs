@@ -1,7 +1,7 @@
 use super::{LockableWrite, Output};
 use core::errors::*;
 use core::flavored::RpName;
-use core::{Encoding, Source, Span, SymbolKind};
+use core::{Code, Diagnostic, Encoding, Reported, Source, Span, SymbolKind};
 use log;
 use serde_json;
 use std::io;
@@ -16,9 +16,18 @@ pub enum Message {
     Log { level: String, message: String },
     #[serde(rename = "diagnostics")]
     Diagnostics {
+        severity: Severity,
+        /// Stable code identifying the error, explained by `reproto explain`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
         message: String,
-        path: PathBuf,
+        path: Option<PathBuf>,
         range: Range,
+        span: Span,
+        /// Informational diagnostics related to this one, like where something was previously
+        /// defined.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        related: Vec<Related>,
     },
     #[serde(rename = "symbol")]
     Symbol {
@@ -32,6 +41,22 @@ pub enum Message {
     Error { message: String },
 }
 
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Serialize)]
+pub struct Related {
+    message: String,
+    path: Option<PathBuf>,
+    range: Range,
+    span: Span,
+}
+
 #[derive(Serialize)]
 pub struct Range {
     line_start: usize,
@@ -56,28 +81,56 @@ where
         Json { out: out }
     }
 
-    fn print_diagnostics(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
-        if let Some(path) = source.path() {
-            let (start, end) = source.span_to_range(*span, Encoding::Utf8)?;
-
-            let m = Message::Diagnostics {
-                message: m.to_string(),
-                path: path.to_owned(),
-                range: Range {
-                    line_start: start.line,
-                    col_start: start.col,
-                    line_end: end.line,
-                    col_end: end.col,
-                },
-            };
+    /// Build a diagnostics message.
+    fn diagnostics(
+        &self,
+        severity: Severity,
+        source: &Source,
+        span: &Span,
+        m: &str,
+        code: Option<&Code>,
+    ) -> Result<Message> {
+        Ok(Message::Diagnostics {
+            severity,
+            code: code.map(|code| code.id.to_string()),
+            message: m.to_string(),
+            path: source.path().map(|p| p.to_owned()),
+            range: self.range(source, span)?,
+            span: *span,
+            related: Vec::new(),
+        })
+    }
 
-            let mut out = self.out.lock();
-            serde_json::to_writer(&mut out, &m)?;
-            out.write(&[NL])?;
-        }
+    /// Convert a span into a range.
+    fn range(&self, source: &Source, span: &Span) -> Result<Range> {
+        let (start, end) = source.span_to_range(*span, Encoding::Utf8)?;
 
+        Ok(Range {
+            line_start: start.line,
+            col_start: start.col,
+            line_end: end.line,
+            col_end: end.col,
+        })
+    }
+
+    fn write(&self, m: &Message) -> Result<()> {
+        let mut out = self.out.lock();
+        serde_json::to_writer(&mut out, m)?;
+        out.write(&[NL])?;
         Ok(())
     }
+
+    fn print_diagnostics(
+        &self,
+        severity: Severity,
+        source: &Source,
+        span: &Span,
+        m: &str,
+        code: Option<&Code>,
+    ) -> Result<()> {
+        let m = self.diagnostics(severity, source, span, m, code)?;
+        self.write(&m)
+    }
 }
 
 impl<T> log::Log for JsonLogger<T>
@@ -118,23 +171,104 @@ where
         })
     }
 
+    /// Informational diagnostics are attached as related information to the error or warning
+    /// preceding them.
+    fn handle_context(&self, diagnostics: &[Reported]) -> Result<()> {
+        let mut items = Vec::new();
+
+        for d in diagnostics {
+            match *d {
+                Reported::Diagnostics(ref diagnostics) => {
+                    items.extend(diagnostics.items().map(|d| (&diagnostics.source, d)));
+                }
+                Reported::SourceDiagnostics(ref diagnostics) => {
+                    items.extend(diagnostics.items().map(|d| (&d.0, &d.1)));
+                }
+            }
+        }
+
+        let mut current = None;
+
+        for (source, item) in items {
+            match *item {
+                Diagnostic::Info {
+                    ref span,
+                    ref message,
+                } => {
+                    if let Some(Message::Diagnostics {
+                        ref mut related, ..
+                    }) = current
+                    {
+                        related.push(Related {
+                            message: message.to_string(),
+                            path: source.path().map(|p| p.to_owned()),
+                            range: self.range(source, span)?,
+                            span: *span,
+                        });
+
+                        continue;
+                    }
+
+                    self.print_info(source, span, message.as_str())?;
+                }
+                Diagnostic::Error {
+                    ref span,
+                    ref message,
+                    code,
+                } => {
+                    if let Some(m) = current.take() {
+                        self.write(&m)?;
+                    }
+
+                    current =
+                        Some(self.diagnostics(Severity::Error, source, span, message, code)?);
+                }
+                Diagnostic::Warning {
+                    ref span,
+                    ref message,
+                } => {
+                    if let Some(m) = current.take() {
+                        self.write(&m)?;
+                    }
+
+                    current =
+                        Some(self.diagnostics(Severity::Warning, source, span, message, None)?);
+                }
+                Diagnostic::Symbol {
+                    ref kind,
+                    ref span,
+                    ref name,
+                } => {
+                    self.print_symbol(source, *kind, span, name)?;
+                }
+            }
+        }
+
+        if let Some(m) = current.take() {
+            self.write(&m)?;
+        }
+
+        Ok(())
+    }
+
     fn print(&self, m: &str) -> Result<()> {
         let m = Message::Error {
             message: m.to_string(),
         };
 
-        let mut out = self.out.lock();
-        serde_json::to_writer(&mut out, &m)?;
-        out.write(&[NL])?;
-        Ok(())
+        self.write(&m)
     }
 
     fn print_info(&self, source: &Source, p: &Span, m: &str) -> Result<()> {
-        self.print_diagnostics(source, p, m)
+        self.print_diagnostics(Severity::Info, source, p, m, None)
+    }
+
+    fn print_error(&self, source: &Source, p: &Span, m: &str, code: Option<&Code>) -> Result<()> {
+        self.print_diagnostics(Severity::Error, source, p, m, code)
     }
 
-    fn print_error(&self, source: &Source, p: &Span, m: &str) -> Result<()> {
-        self.print_diagnostics(source, p, m)
+    fn print_warning(&self, source: &Source, p: &Span, m: &str) -> Result<()> {
+        self.print_diagnostics(Severity::Warning, source, p, m, None)
     }
 
     fn print_symbol(
@@ -155,25 +289,14 @@ where
             path.to_owned()
         };
 
-        let (start, end) = source.span_to_range(*span, Encoding::Utf8)?;
-
         let m = Message::Symbol {
             kind,
             name: name.path.join("::"),
             package: name.package.to_string(),
             path: path.to_owned(),
-            range: Range {
-                line_start: start.line,
-                col_start: start.col,
-                line_end: end.line,
-                col_end: end.col,
-            },
+            range: self.range(source, span)?,
         };
 
-        let mut out = self.out.lock();
-        serde_json::to_writer(&mut out, &m)?;
-        out.write(&[NL])?;
-
-        Ok(())
+        self.write(&m)
     }
 }
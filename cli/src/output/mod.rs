@@ -1,6 +1,7 @@
 mod colored;
 mod json;
 mod non_colored;
+mod snippet;
 
 pub use self::colored::Colored;
 pub use self::json::Json;
@@ -71,8 +72,15 @@ pub trait Output {
             Diagnostic::Error {
                 ref span,
                 ref message,
+                code,
             } => {
-                self.print_error(source, span, message.as_str())?;
+                self.print_error(source, span, message.as_str(), code)?;
+            }
+            Diagnostic::Warning {
+                ref span,
+                ref message,
+            } => {
+                self.print_warning(source, span, message.as_str())?;
             }
             Diagnostic::Symbol {
                 ref kind,
@@ -109,7 +117,8 @@ pub trait Output {
             None => e.message().to_string(),
         };
 
-        self.print(&message)?;
+        let message = self.error_header(message.as_str())?;
+        self.print(message.as_str())?;
 
         for e in e.causes().skip(1) {
             let msg = self.error_message(format!("  caused by: {}", e.message()).as_str())?;
@@ -119,6 +128,11 @@ pub trait Output {
         Ok(())
     }
 
+    /// Format the first line of a reported error.
+    fn error_header(&self, m: &str) -> Result<String> {
+        Ok(m.to_string())
+    }
+
     fn error_message(&self, m: &str) -> Result<String> {
         Ok(m.to_string())
     }
@@ -129,7 +143,15 @@ pub trait Output {
 
     fn print_info(&self, source: &core::Source, p: &core::Span, m: &str) -> Result<()>;
 
-    fn print_error(&self, source: &core::Source, p: &core::Span, m: &str) -> Result<()>;
+    fn print_error(
+        &self,
+        source: &core::Source,
+        p: &core::Span,
+        m: &str,
+        code: Option<&core::Code>,
+    ) -> Result<()>;
+
+    fn print_warning(&self, source: &core::Source, p: &core::Span, m: &str) -> Result<()>;
 
     fn print_symbol(
         &self,
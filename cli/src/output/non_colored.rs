@@ -1,6 +1,7 @@
+use super::snippet::{write_snippet, Level, Palette};
 use super::{LockableWrite, Output};
 use core::errors::*;
-use core::{self, Source, Span};
+use core::{Code, Source, Span};
 use log;
 use std::io;
 
@@ -20,27 +21,16 @@ where
         NonColored { out: out }
     }
 
-    fn print_positional(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
-        use std::cmp::max;
-        use std::iter::repeat;
-
+    fn print_positional(
+        &self,
+        level: Level,
+        source: &Source,
+        span: &Span,
+        m: &str,
+        code: Option<&Code>,
+    ) -> Result<()> {
         let mut o = self.out.lock();
-
-        let (line_str, line, (s, e)) =
-            core::utils::find_line(source.read()?, (span.start, span.end))?;
-
-        let line_no = format!("{:>3}:", line + 1);
-
-        let mut indicator = String::new();
-
-        indicator.extend(repeat(' ').take(line_no.len() + s + 1));
-        indicator.extend(repeat('^').take(max(1, e - s)));
-
-        writeln!(o, "{}:{}:{}-{}:", source, line + 1, s + 1, e + 1)?;
-        writeln!(o, "{} {}", line_no, line_str)?;
-        writeln!(o, "{}{}{}", indicator, " - ", m)?;
-
-        Ok(())
+        write_snippet(&mut o, &Palette::plain(), level, source, span, m, code)
     }
 }
 
@@ -76,6 +66,10 @@ where
         })
     }
 
+    fn error_header(&self, m: &str) -> Result<String> {
+        Ok(format!("error: {}", m))
+    }
+
     fn print(&self, m: &str) -> Result<()> {
         let mut o = self.out.lock();
         writeln!(o, "{}", m)?;
@@ -83,10 +77,20 @@ where
     }
 
     fn print_info(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
-        self.print_positional(source, span, m)
+        self.print_positional(Level::Note, source, span, m, None)
+    }
+
+    fn print_error(
+        &self,
+        source: &Source,
+        span: &Span,
+        m: &str,
+        code: Option<&Code>,
+    ) -> Result<()> {
+        self.print_positional(Level::Error, source, span, m, code)
     }
 
-    fn print_error(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
-        self.print_positional(source, span, m)
+    fn print_warning(&self, source: &Source, span: &Span, m: &str) -> Result<()> {
+        self.print_positional(Level::Warning, source, span, m, None)
     }
 }
@@ -0,0 +1,128 @@
+//! Rendering of diagnostics as annotated source snippets.
+
+use ansi_term::Colour::{Blue, Purple, Red, Yellow};
+use ansi_term::Style;
+use core::errors::*;
+use core::{self, Code, Source, Span};
+use std::cmp::max;
+use std::io::Write;
+use std::iter::repeat;
+
+/// The level of a rendered diagnostic.
+#[derive(Debug, Clone, Copy)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Level {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+        }
+    }
+}
+
+/// Styles used when rendering snippets.
+pub struct Palette {
+    error: Style,
+    warning: Style,
+    note: Style,
+    gutter: Style,
+    message: Style,
+}
+
+impl Palette {
+    /// Palette which doesn't emit any escape codes.
+    pub fn plain() -> Palette {
+        Palette {
+            error: Style::new(),
+            warning: Style::new(),
+            note: Style::new(),
+            gutter: Style::new(),
+            message: Style::new(),
+        }
+    }
+
+    /// Palette for terminals supporting colors.
+    pub fn colored() -> Palette {
+        Palette {
+            error: Red.bold(),
+            warning: Purple.bold(),
+            note: Yellow.bold(),
+            gutter: Blue.bold(),
+            message: Style::new().bold(),
+        }
+    }
+
+    fn level(&self, level: Level) -> Style {
+        match level {
+            Level::Error => self.error,
+            Level::Warning => self.warning,
+            Level::Note => self.note,
+        }
+    }
+}
+
+/// Write a diagnostic with the line that the span points to, like:
+///
+/// ```text
+/// error[E0001]: field with name `foo` is already defined
+///  --> proto/example.reproto:3:3
+///   |
+/// 3 |   foo: string;
+///   |   ^^^^^^^^^^^^
+/// ```
+pub fn write_snippet(
+    o: &mut Write,
+    palette: &Palette,
+    level: Level,
+    source: &Source,
+    span: &Span,
+    m: &str,
+    code: Option<&Code>,
+) -> Result<()> {
+    let (line_str, line, (s, e)) = core::utils::find_line(source.read()?, (span.start, span.end))?;
+
+    let style = palette.level(level);
+    let line_no = (line + 1).to_string();
+    let pad = repeat(' ').take(line_no.len()).collect::<String>();
+    let gutter = palette.gutter.paint("|");
+
+    let indent = repeat(' ').take(s).collect::<String>();
+    let carets = repeat('^').take(max(1, e - s)).collect::<String>();
+
+    let header = match code {
+        Some(code) => format!("{}[{}]", level.as_str(), code),
+        None => level.as_str().to_string(),
+    };
+
+    writeln!(
+        o,
+        "{}{}",
+        style.paint(header),
+        palette.message.paint(format!(": {}", m))
+    )?;
+    writeln!(
+        o,
+        "{}{} {}:{}:{}",
+        pad,
+        palette.gutter.paint("-->"),
+        source,
+        line + 1,
+        s + 1
+    )?;
+    writeln!(o, "{} {}", pad, gutter)?;
+    writeln!(
+        o,
+        "{} {} {}",
+        palette.gutter.paint(line_no),
+        gutter,
+        line_str
+    )?;
+    writeln!(o, "{} {} {}{}", pad, gutter, indent, style.paint(carets))?;
+    Ok(())
+}
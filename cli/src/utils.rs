@@ -1,21 +1,75 @@
 use clap::ArgMatches;
 use core::errors::{Error, Result, ResultExt};
 use core::{
-    CoreFlavor, Flavor, Reporter, Resolved, ResolvedByPrefix, Resolver, RpChannel, RpFile,
-    RpPackage, RpPackageFormat, RpRequiredPackage, RpVersionedPackage, Source, SourceDiagnostics,
-    Version,
+    CoreFlavor, Diagnostics, Flavor, Reporter, Resolved, ResolvedByPrefix, Resolver, RpChannel,
+    RpFile, RpPackage, RpPackageFormat, RpRequiredPackage, RpVersionedPackage, Source,
+    SourceDiagnostics, Version,
 };
 use env;
-use manifest::{self, Lang, Language, Manifest, Publish};
+use manifest::{self, Lang, Language, Manifest, Override, Publish};
 use repository::Repository;
 use semck;
 use std::fmt;
-use std::fs::File;
+use std::io;
 use std::path::Path;
 use trans::Session;
 
+/// Read the manifest at the given path into `manifest`.
+///
+/// Problems with the manifest are reported as diagnostics.
+fn read_manifest(reporter: &mut Reporter, manifest: &mut Manifest, path: &Path) -> Result<()> {
+    debug!("reading manifest: {}", path.display());
+    let mut diag = Diagnostics::new(Source::from_path(path));
+    let result = manifest.from_source(&mut diag, env::convert_lang);
+
+    if !diag.items.is_empty() {
+        reporter.diagnostics(diag);
+    }
+
+    result
+}
+
+/// Load the manifests of every member of the workspace declared in the given manifest.
+///
+/// Members share the lockfile and vendored packages of the workspace.
+pub fn load_workspace_members(
+    reporter: &mut Reporter,
+    manifest: &Manifest,
+) -> Result<Vec<Manifest>> {
+    let workspace = manifest
+        .workspace
+        .as_ref()
+        .ok_or_else(|| "manifest does not declare a `[workspace]`")?;
+
+    let root = manifest
+        .path
+        .as_ref()
+        .and_then(|p| p.parent())
+        .ok_or_else(|| "workspace manifest does not have a path")?;
+
+    let mut members = Vec::new();
+
+    for member in &workspace.members {
+        let path = member.join(env::MANIFEST_NAME);
+
+        if !path.is_file() {
+            return Err(format!("workspace member has no manifest: {}", path.display()).into());
+        }
+
+        let mut manifest = Manifest::default();
+        manifest.path = Some(path.clone());
+        manifest.workspace_root = Some(root.to_owned());
+        read_manifest(reporter, &mut manifest, &path)?;
+        members.push(manifest);
+    }
+
+    Ok(members)
+}
+
 /// Load the manifest based on commandline arguments.
-pub fn load_manifest<'a>(m: &ArgMatches<'a>) -> Result<Manifest> {
+///
+/// Problems with the manifest are reported as diagnostics.
+pub fn load_manifest<'a>(reporter: &mut Reporter, m: &ArgMatches<'a>) -> Result<Manifest> {
     let mut manifest = manifest::Manifest::default();
 
     let path = m
@@ -24,6 +78,7 @@ pub fn load_manifest<'a>(m: &ArgMatches<'a>) -> Result<Manifest> {
         .unwrap_or_else(|| Ok(Path::new(env::MANIFEST_NAME)))?;
 
     manifest.path = Some(path.to_owned());
+    manifest.overrides = overrides(m)?;
 
     if let Some(lang) = m.value_of("lang") {
         let lang =
@@ -32,13 +87,35 @@ pub fn load_manifest<'a>(m: &ArgMatches<'a>) -> Result<Manifest> {
     }
 
     if path.is_file() {
-        debug!("reading manifest: {}", path.display());
-        manifest.from_yaml(File::open(&path)?, env::convert_lang)?;
+        read_manifest(reporter, &mut manifest, path)?;
+    } else if !manifest.overrides.is_empty() {
+        // NB: overrides still apply without a manifest.
+        manifest.from_yaml(io::empty(), env::convert_lang)?;
     }
 
     matches_to_manifest(&mut manifest, m)?;
     return Ok(manifest);
 
+    /// Overrides from the environment, followed by overrides from the command line so that they
+    /// take precedence.
+    fn overrides(m: &ArgMatches) -> Result<Vec<Override>> {
+        let mut overrides = Vec::new();
+
+        if let Ok(value) = ::std::env::var(env::SET_VARIABLE) {
+            for o in value.split(';').map(str::trim).filter(|o| !o.is_empty()) {
+                let o = Override::parse(o)
+                    .chain_err(|| format!("bad override in {}: {}", env::SET_VARIABLE, o))?;
+                overrides.push(o);
+            }
+        }
+
+        for o in m.values_of("set").into_iter().flat_map(|it| it) {
+            overrides.push(Override::parse(o).chain_err(|| format!("bad --set argument: {}", o))?);
+        }
+
+        Ok(overrides)
+    }
+
     /// Populate manifest with overrides and extensions from the command line.
     fn matches_to_manifest(manifest: &mut Manifest, m: &ArgMatches) -> Result<()> {
         manifest.paths.extend(
@@ -104,6 +181,7 @@ pub fn load_manifest<'a>(m: &ArgMatches<'a>) -> Result<Manifest> {
     /// CLI arguments take precedence.
     fn matches_to_repository(repository: &mut manifest::Repository, m: &ArgMatches) -> Result<()> {
         repository.no_repository = repository.no_repository || m.is_present("no-repository");
+        repository.offline = repository.offline || m.is_present("offline");
 
         if let Some(objects) = m.value_of("objects").map(ToOwned::to_owned) {
             repository.objects = Some(objects);
@@ -113,6 +191,10 @@ pub fn load_manifest<'a>(m: &ArgMatches<'a>) -> Result<Manifest> {
             repository.index = Some(index);
         }
 
+        if let Some(signing_key) = m.value_of("signing-key").map(Path::new) {
+            repository.signing_key = Some(signing_key.to_owned());
+        }
+
         Ok(())
     }
 }
@@ -139,8 +221,13 @@ where
 {
     let package_prefix = manifest.package_prefix.clone();
 
-    let mut session = lang
-        .into_session(package_prefix, reporter, resolver)?
+    let session = lang.into_session(package_prefix, reporter, resolver)?;
+
+    let mut session = manifest
+        .naming
+        .apply(session)
+        .with_scalars(manifest.scalars.clone())
+        .with_numbers_as_strings(manifest.numbers_as_strings)
         .with_path_hook(path_hook);
 
     let mut errors: Vec<Error> = Vec::new();
@@ -174,6 +261,14 @@ where
         errors.push(e.into());
     }
 
+    if let Err(e) = env::assign_field_ids(manifest, &mut session) {
+        errors.push(e.into());
+    }
+
+    if let Err(e) = env::verify_consumers(manifest, &session) {
+        errors.push(e.into());
+    }
+
     if !errors.is_empty() {
         return Err(Error::new("error when building").with_suppressed(errors));
     }
@@ -273,36 +368,73 @@ where
     Ok(results)
 }
 
-pub fn semck_check(
-    errors: &mut Vec<Error>,
+/// Load the latest published version which precedes the given version of a package.
+///
+/// Pre-releases are never considered.
+fn load_previous(
     repository: &mut Repository,
     session: &mut Session<CoreFlavor>,
     version_to: &Version,
-    source_to: &Source,
     package_to: &RpVersionedPackage,
-    file_to: &RpFile<CoreFlavor>,
-) -> Result<()> {
-    // perform semck verification
-    if let Some(d) = repository
+) -> Result<Option<(Version, Source, RpFile<CoreFlavor>)>> {
+    let d = match repository
         .all(&package_to.package)?
         .into_iter()
         .filter(|d| d.version <= *version_to && !d.version.is_prerelease())
         .last()
     {
-        debug!("Checking semantics of {} -> {}", d.version, version_to);
+        Some(d) => d,
+        None => return Ok(None),
+    };
+
+    let current = repository
+        .get_object(&d)?
+        .ok_or_else(|| format!("No object found for deployment: {:?}", d))?;
 
-        let current = repository
-            .get_object(&d)?
-            .ok_or_else(|| format!("No object found for deployment: {:?}", d))?;
+    let name = RpPackageFormat(&package_to.package, Some(&d.version)).to_string();
+    let current = current.with_name(name);
 
-        let name = RpPackageFormat(&package_to.package, Some(&d.version)).to_string();
-        let current = current.with_name(name);
+    let package_from = RpVersionedPackage::new(package_to.package.clone(), Some(d.version.clone()));
+    let file_from = session.load_source(current.clone(), &package_from)?;
+
+    Ok(Some((d.version, current, file_from)))
+}
+
+/// Build a changelog of the given version, compared to the previously published version.
+///
+/// The first published version of a package has an empty changelog.
+pub fn changelog(
+    repository: &mut Repository,
+    session: &mut Session<CoreFlavor>,
+    version_to: &Version,
+    package_to: &RpVersionedPackage,
+    file_to: &RpFile<CoreFlavor>,
+) -> Result<Vec<String>> {
+    let (_, _, file_from) = match load_previous(repository, session, version_to, package_to)? {
+        Some(previous) => previous,
+        None => return Ok(vec![]),
+    };
+
+    let changes = semck::diff(&file_from, file_to)?;
+    Ok(changes.into_iter().map(|c| c.to_string()).collect())
+}
 
-        let package_from =
-            RpVersionedPackage::new(package_to.package.clone(), Some(d.version.clone()));
-        let file_from = session.load_source(current.clone(), &package_from)?;
+pub fn semck_check(
+    errors: &mut Vec<Error>,
+    repository: &mut Repository,
+    session: &mut Session<CoreFlavor>,
+    version_to: &Version,
+    source_to: &Source,
+    package_to: &RpVersionedPackage,
+    file_to: &RpFile<CoreFlavor>,
+) -> Result<()> {
+    // perform semck verification
+    if let Some((version_from, current, file_from)) =
+        load_previous(repository, session, version_to, package_to)?
+    {
+        debug!("Checking semantics of {} -> {}", version_from, version_to);
 
-        let violations = semck::check((&d.version, &file_from), (&version_to, file_to))?;
+        let violations = semck::check((&version_from, &file_from), (&version_to, file_to))?;
 
         if !violations.is_empty() {
             errors.push(Error::new(format!(
@@ -354,6 +486,13 @@ pub fn semck_check(
             AddField(c, field) => {
                 diag.err(source_to, field, format!("{}: field added", c.describe()));
             }
+            RemoveReserved(c, span) => {
+                diag.err(
+                    current,
+                    span,
+                    format!("{}: reserved field removed", c.describe()),
+                );
+            }
             AddVariant(c, field) => {
                 diag.err(source_to, field, format!("{}: variant added", c.describe()));
             }
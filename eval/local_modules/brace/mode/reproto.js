@@ -22,13 +22,13 @@ var reprotoHighlightRules = function() {
       ]
     },
     { token: 'keyword.reproto',
-      regex: '\\b(?:type|interface|enum|tuple|service|use|as)\\b' },
+      regex: '\\b(?:type|interface|enum|tuple|service|use|as|reserved)\\b' },
     { token: 'comment.block.documentation.reproto',
       regex: '(//!.*|///.*)$' },
     { token: 'comment.line.double-slash.reproto',
       regex: '//.*$' },
     { token: 'storage.type.reproto',
-      regex: '\\b(any|float|double|boolean|string|bytes|datetime|u32|u64|i32|i64)\\b' },
+      regex: '\\b(any|float|double|boolean|string|bytes|datetime|decimal|u32|u64|u128|i32|i64|i128)\\b' },
     { token: 'storage.modifier.reproto',
       regex: '\\b(?:stream)\\b' },
     { token : "paren.lparen", regex : /[\[({]/ },
@@ -63,7 +63,7 @@ pub enum Output {
 
 impl Output {
     /// Convert into a manifest language and accumulate modules.
-    fn into_lang(self, settings: Settings, modules: &mut Vec<Box<Any>>) -> Box<manifest::Lang> {
+    fn into_lang(self, settings: Settings, modules: &mut Vec<Box<Any + Send>>) -> Box<manifest::Lang> {
         match self {
             Output::Reproto => Box::new(reproto::ReprotoLang),
             Output::Java => {
@@ -3,6 +3,8 @@ extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
 extern crate chrono;
+extern crate percent_encoding;
 extern crate reqwest;
+extern crate url;
 
 pub mod gen;
@@ -2,6 +2,7 @@ package test
 
 import "encoding/json"
 import "errors"
+import "strconv"
 
 type Entry struct {
 }
@@ -88,6 +89,24 @@ const (
   RootEnum_Foo RootEnum = iota
 )
 
+func (this RootEnum) String() string {
+  switch this {
+  case RootEnum_Foo:
+    return "Foo"
+  default:
+    return "RootEnum(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseRootEnum(s string) (RootEnum, error) {
+  switch s {
+  case "Foo":
+    return RootEnum_Foo, nil
+  default:
+    return 0, errors.New("bad RootEnum: " + s)
+  }
+}
+
 func (this *RootEnum) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -221,6 +240,24 @@ const (
   RootType_NestedEnum_Foo RootType_NestedEnum = iota
 )
 
+func (this RootType_NestedEnum) String() string {
+  switch this {
+  case RootType_NestedEnum_Foo:
+    return "Foo"
+  default:
+    return "RootType_NestedEnum(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseRootType_NestedEnum(s string) (RootType_NestedEnum, error) {
+  switch s {
+  case "Foo":
+    return RootType_NestedEnum_Foo, nil
+  default:
+    return 0, errors.New("bad RootType_NestedEnum: " + s)
+  }
+}
+
 func (this *RootType_NestedEnum) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -354,6 +391,24 @@ const (
   RootInterface_Foo_NestedEnum_Foo RootInterface_Foo_NestedEnum = iota
 )
 
+func (this RootInterface_Foo_NestedEnum) String() string {
+  switch this {
+  case RootInterface_Foo_NestedEnum_Foo:
+    return "Foo"
+  default:
+    return "RootInterface_Foo_NestedEnum(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseRootInterface_Foo_NestedEnum(s string) (RootInterface_Foo_NestedEnum, error) {
+  switch s {
+  case "Foo":
+    return RootInterface_Foo_NestedEnum_Foo, nil
+  default:
+    return 0, errors.New("bad RootInterface_Foo_NestedEnum: " + s)
+  }
+}
+
 func (this *RootInterface_Foo_NestedEnum) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -487,6 +542,24 @@ const (
   RootTuple_NestedEnum_Foo RootTuple_NestedEnum = iota
 )
 
+func (this RootTuple_NestedEnum) String() string {
+  switch this {
+  case RootTuple_NestedEnum_Foo:
+    return "Foo"
+  default:
+    return "RootTuple_NestedEnum(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseRootTuple_NestedEnum(s string) (RootTuple_NestedEnum, error) {
+  switch s {
+  case "Foo":
+    return RootTuple_NestedEnum_Foo, nil
+  default:
+    return 0, errors.New("bad RootTuple_NestedEnum: " + s)
+  }
+}
+
 func (this *RootTuple_NestedEnum) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -620,6 +693,24 @@ const (
   RootService_NestedEnum_Foo RootService_NestedEnum = iota
 )
 
+func (this RootService_NestedEnum) String() string {
+  switch this {
+  case RootService_NestedEnum_Foo:
+    return "Foo"
+  default:
+    return "RootService_NestedEnum(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseRootService_NestedEnum(s string) (RootService_NestedEnum, error) {
+  switch s {
+  case "Foo":
+    return RootService_NestedEnum_Foo, nil
+  default:
+    return 0, errors.New("bad RootService_NestedEnum: " + s)
+  }
+}
+
 func (this *RootService_NestedEnum) UnmarshalJSON(b []byte) error {
   var s string
 
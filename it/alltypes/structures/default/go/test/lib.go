@@ -19,7 +19,7 @@ type Entry struct {
 
   DoubleType *float64 `json:"double_type,omitempty"`
 
-  BytesType *string `json:"bytes_type,omitempty"`
+  BytesType *[]byte `json:"bytes_type,omitempty"`
 
   AnyType *interface{} `json:"any_type,omitempty"`
 
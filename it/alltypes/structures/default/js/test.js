@@ -92,7 +92,7 @@ export class Entry {
     let v_bytes_type = data["bytes_type"];
 
     if (v_bytes_type !== null && v_bytes_type !== undefined) {
-      v_bytes_type = v_bytes_type;
+      v_bytes_type = Uint8Array.from(atob(v_bytes_type), function(c) { return c.charCodeAt(0); });
     } else {
       v_bytes_type = null;
     }
@@ -172,7 +172,7 @@ export class Entry {
     }
 
     if (this.bytes_type !== null && this.bytes_type !== undefined) {
-      data["bytes_type"] = this.bytes_type;
+      data["bytes_type"] = btoa(Array.from(this.bytes_type, function(b) { return String.fromCharCode(b); }).join(""));
     }
 
     if (this.any_type !== null && this.any_type !== undefined) {
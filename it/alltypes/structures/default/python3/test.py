@@ -1,3 +1,5 @@
+import base64
+
 class Entry:
   def __init__(self, boolean_type, string_type, datetime_type, unsigned_32, unsigned_64, signed_32, signed_64, float_type, double_type, bytes_type, any_type, array_type, array_of_array_type, map_type):
     self.boolean_type = boolean_type
@@ -148,6 +150,7 @@ class Entry:
       if f_bytes_type is not None:
         if not isinstance(f_bytes_type, str):
           raise Exception("not a string")
+        f_bytes_type = base64.b64decode(f_bytes_type)
 
     f_any_type = None
 
@@ -249,7 +252,7 @@ class Entry:
       data["double_type"] = self.double_type
 
     if self.bytes_type is not None:
-      data["bytes_type"] = self.bytes_type
+      data["bytes_type"] = base64.b64encode(self.bytes_type).decode("ascii")
 
     if self.any_type is not None:
       data["any_type"] = self.any_type
@@ -2,6 +2,7 @@ package test
 
 import "encoding/json"
 import "errors"
+import "strconv"
 
 type Entry struct {
 }
@@ -88,6 +89,24 @@ const (
   Enum_Variant Enum = iota
 )
 
+func (this Enum) String() string {
+  switch this {
+  case Enum_Variant:
+    return "Variant"
+  default:
+    return "Enum(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnum(s string) (Enum, error) {
+  switch s {
+  case "Variant":
+    return Enum_Variant, nil
+  default:
+    return 0, errors.New("bad Enum: " + s)
+  }
+}
+
 func (this *Enum) UnmarshalJSON(b []byte) error {
   var s string
 
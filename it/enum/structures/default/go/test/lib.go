@@ -2,6 +2,7 @@ package test
 
 import "encoding/json"
 import "errors"
+import "strconv"
 
 type Entry struct {
   Explicit *EnumExplicit `json:"explicit,omitempty"`
@@ -25,6 +26,28 @@ const (
   EnumExplicit_B
 )
 
+func (this EnumExplicit) String() string {
+  switch this {
+  case EnumExplicit_A:
+    return "foo"
+  case EnumExplicit_B:
+    return "bar"
+  default:
+    return "EnumExplicit(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumExplicit(s string) (EnumExplicit, error) {
+  switch s {
+  case "foo":
+    return EnumExplicit_A, nil
+  case "bar":
+    return EnumExplicit_B, nil
+  default:
+    return 0, errors.New("bad EnumExplicit: " + s)
+  }
+}
+
 func (this *EnumExplicit) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -67,6 +90,28 @@ const (
   EnumImplicit_B
 )
 
+func (this EnumImplicit) String() string {
+  switch this {
+  case EnumImplicit_A:
+    return "A"
+  case EnumImplicit_B:
+    return "B"
+  default:
+    return "EnumImplicit(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumImplicit(s string) (EnumImplicit, error) {
+  switch s {
+  case "A":
+    return EnumImplicit_A, nil
+  case "B":
+    return EnumImplicit_B, nil
+  default:
+    return 0, errors.New("bad EnumImplicit: " + s)
+  }
+}
+
 func (this *EnumImplicit) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -109,6 +154,28 @@ const (
   EnumLongNames_Baz
 )
 
+func (this EnumLongNames) String() string {
+  switch this {
+  case EnumLongNames_FooBar:
+    return "FooBar"
+  case EnumLongNames_Baz:
+    return "Baz"
+  default:
+    return "EnumLongNames(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumLongNames(s string) (EnumLongNames, error) {
+  switch s {
+  case "FooBar":
+    return EnumLongNames_FooBar, nil
+  case "Baz":
+    return EnumLongNames_Baz, nil
+  default:
+    return 0, errors.New("bad EnumLongNames: " + s)
+  }
+}
+
 func (this *EnumLongNames) UnmarshalJSON(b []byte) error {
   var s string
 
@@ -150,6 +217,28 @@ const (
   EnumU32_Max
 )
 
+func (this EnumU32) String() string {
+  switch this {
+  case EnumU32_Min:
+    return "Min"
+  case EnumU32_Max:
+    return "Max"
+  default:
+    return "EnumU32(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumU32(s string) (EnumU32, error) {
+  switch s {
+  case "Min":
+    return EnumU32_Min, nil
+  case "Max":
+    return EnumU32_Max, nil
+  default:
+    return 0, errors.New("bad EnumU32: " + s)
+  }
+}
+
 func (this *EnumU32) UnmarshalJSON(b []byte) error {
   var s uint32
 
@@ -191,6 +280,28 @@ const (
   EnumU64_Max
 )
 
+func (this EnumU64) String() string {
+  switch this {
+  case EnumU64_Min:
+    return "Min"
+  case EnumU64_Max:
+    return "Max"
+  default:
+    return "EnumU64(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumU64(s string) (EnumU64, error) {
+  switch s {
+  case "Min":
+    return EnumU64_Min, nil
+  case "Max":
+    return EnumU64_Max, nil
+  default:
+    return 0, errors.New("bad EnumU64: " + s)
+  }
+}
+
 func (this *EnumU64) UnmarshalJSON(b []byte) error {
   var s uint64
 
@@ -234,6 +345,36 @@ const (
   EnumI32_Max
 )
 
+func (this EnumI32) String() string {
+  switch this {
+  case EnumI32_Min:
+    return "Min"
+  case EnumI32_NegativeOne:
+    return "NegativeOne"
+  case EnumI32_Zero:
+    return "Zero"
+  case EnumI32_Max:
+    return "Max"
+  default:
+    return "EnumI32(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumI32(s string) (EnumI32, error) {
+  switch s {
+  case "Min":
+    return EnumI32_Min, nil
+  case "NegativeOne":
+    return EnumI32_NegativeOne, nil
+  case "Zero":
+    return EnumI32_Zero, nil
+  case "Max":
+    return EnumI32_Max, nil
+  default:
+    return 0, errors.New("bad EnumI32: " + s)
+  }
+}
+
 func (this *EnumI32) UnmarshalJSON(b []byte) error {
   var s int32
 
@@ -285,6 +426,36 @@ const (
   EnumI64_Max
 )
 
+func (this EnumI64) String() string {
+  switch this {
+  case EnumI64_Min:
+    return "Min"
+  case EnumI64_NegativeOne:
+    return "NegativeOne"
+  case EnumI64_Zero:
+    return "Zero"
+  case EnumI64_Max:
+    return "Max"
+  default:
+    return "EnumI64(" + strconv.Itoa(int(this)) + ")"
+  }
+}
+
+func ParseEnumI64(s string) (EnumI64, error) {
+  switch s {
+  case "Min":
+    return EnumI64_Min, nil
+  case "NegativeOne":
+    return EnumI64_NegativeOne, nil
+  case "Zero":
+    return EnumI64_Zero, nil
+  case "Max":
+    return EnumI64_Max, nil
+  default:
+    return 0, errors.New("bad EnumI64: " + s)
+  }
+}
+
 func (this *EnumI64) UnmarshalJSON(b []byte) error {
   var s int64
 
@@ -0,0 +1,97 @@
+/**
+ * An entry.
+ */
+export class Entry {
+  /**
+   * @param {string} name Name of the entry.
+   * @param {?Entry} parent
+   */
+  constructor(name, parent) {
+    this.name = name;
+    this.parent = parent;
+  }
+
+  static decode(data) {
+    const v_name = data["name"];
+
+    if (v_name === null || v_name === undefined) {
+      throw new Error("name" + ": required field");
+    }
+
+    let v_parent = data["parent"];
+
+    if (v_parent !== null && v_parent !== undefined) {
+      v_parent = Entry.decode(v_parent);
+    } else {
+      v_parent = null;
+    }
+
+    return new Entry(v_name, v_parent);
+  }
+
+  encode() {
+    const data = {};
+
+    if (this.name === null || this.name === undefined) {
+      throw new Error("name: is a required field");
+    }
+
+    data["name"] = this.name;
+
+    if (this.parent !== null && this.parent !== undefined) {
+      data["parent"] = this.parent.encode();
+    }
+
+    return data;
+  }
+}
+
+/**
+ * Entries.
+ */
+export class MyServiceFetchClient {
+  /**
+   * @param {string} url
+   * @param {typeof fetch} fetchFn
+   */
+  constructor(url = "http://example.com", fetchFn = globalThis.fetch) {
+    this.url = url;
+    this.fetchFn = fetchFn;
+  }
+
+  /**
+   * Get an entry.
+   */
+  async getEntry(id) {
+    const url = this.url + "/entry/" + encodeURIComponent(id);
+    const fetchFn = this.fetchFn;
+
+    const res = await fetchFn(url, {
+      method: "GET",
+    });
+
+    if (!res.ok) {
+      throw new Error("getEntry: bad response status: " + res.status);
+    }
+
+    return Entry.decode(await res.json());
+  }
+
+  /**
+   * Store an entry.
+   */
+  async putEntry(request, id) {
+    const url = this.url + "/entry/" + encodeURIComponent(id);
+    const fetchFn = this.fetchFn;
+
+    const res = await fetchFn(url, {
+      method: "PUT",
+      headers: {"Content-Type": "application/json"},
+      body: JSON.stringify(request.encode()),
+    });
+
+    if (!res.ok) {
+      throw new Error("putEntry: bad response status: " + res.status);
+    }
+  }
+}
@@ -0,0 +1,112 @@
+import httpx
+
+class Entry:
+  def __init__(self):
+    pass
+
+  @staticmethod
+  def decode(data):
+    return Entry()
+
+  def encode(self):
+    data = dict()
+
+    return data
+
+  def __repr__(self):
+    return "<Entry>".format()
+
+class MyService_Httpx:
+  def __init__(self, **kw):
+    url = kw.pop("url", None)
+
+    if url is None:
+      url = "http://example.com"
+
+    session = kw.pop("session", None)
+
+    if session is None:
+      session = httpx.AsyncClient()
+
+    self.url = url
+    self.session = session
+
+  async def unknown(self, id):
+    """
+    UNKNOWN
+    """
+    path = list()
+    path.append(self.url)
+    path.append("/")
+    path.append("unknown")
+    path.append("/")
+    path.append(str(id))
+
+    url = "".join(path)
+
+    r = await self.session.request("GET", url)
+
+    r.raise_for_status()
+
+  async def unknown_return(self, id):
+    """
+    UNKNOWN
+    """
+    path = list()
+    path.append(self.url)
+    path.append("/")
+    path.append("unknown-return")
+    path.append("/")
+    path.append(str(id))
+
+    url = "".join(path)
+
+    r = await self.session.request("GET", url)
+
+    r.raise_for_status()
+
+    data = r.json()
+
+    data = Entry.decode(data)
+
+    return data
+
+  async def unknown_argument(self, request, id):
+    """
+    UNKNOWN
+    """
+    path = list()
+    path.append(self.url)
+    path.append("/")
+    path.append("unknown-argument")
+    path.append("/")
+    path.append(str(id))
+
+    url = "".join(path)
+
+    r = await self.session.request("GET", url, json=request.encode())
+
+    r.raise_for_status()
+
+  async def unary(self, request, id):
+    """
+    UNARY
+    """
+    path = list()
+    path.append(self.url)
+    path.append("/")
+    path.append("unary")
+    path.append("/")
+    path.append(str(id))
+
+    url = "".join(path)
+
+    r = await self.session.request("GET", url, json=request.encode())
+
+    r.raise_for_status()
+
+    data = r.json()
+
+    data = Entry.decode(data)
+
+    return data
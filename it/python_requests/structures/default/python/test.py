@@ -84,7 +84,7 @@ class MyService_Requests:
 
     url = "".join(path)
 
-    r = self.session.request("GET", url)
+    r = self.session.request("GET", url, json=request.encode())
 
     r.raise_for_status()
 
@@ -101,7 +101,7 @@ class MyService_Requests:
 
     url = "".join(path)
 
-    r = self.session.request("GET", url)
+    r = self.session.request("GET", url, json=request.encode())
 
     r.raise_for_status()
 
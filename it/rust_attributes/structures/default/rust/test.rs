@@ -0,0 +1,17 @@
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash)]
+pub struct Entry {
+  #[serde(skip_serializing_if="Option::is_none")]
+  pub point: Option<Point>,
+
+  #[serde(skip_serializing_if="Option::is_none")]
+  pub tags: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash)]
+pub struct Point(pub u32, pub u32);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub struct Other {
+  #[serde(skip_serializing_if="Option::is_none")]
+  pub a: Option<String>,
+}
@@ -1,13 +1,17 @@
+use percent_encoding;
 use reqwest;
-use reqwest::header::parsing;
+use std::error;
 use std::fmt;
 use std::result;
+use url;
 
 #[derive(Debug)]
 pub enum Error {
   ReqwestError(reqwest::Error),
-  UrlError(reqwest::UrlError),
+  UrlError(url::ParseError),
   FormatError(fmt::Error),
+  /// The server responded with a non-successful status.
+  Status(reqwest::StatusCode),
 }
 
 pub type Result<T> = result::Result<T, Error>;
@@ -18,8 +22,8 @@ impl From<reqwest::Error> for Error {
   }
 }
 
-impl From<reqwest::UrlError> for Error {
-  fn from(value: reqwest::UrlError) -> Self {
+impl From<url::ParseError> for Error {
+  fn from(value: url::ParseError) -> Self {
     Error::UrlError(value)
   }
 }
@@ -36,10 +40,13 @@ impl fmt::Display for Error {
       Error::ReqwestError(ref e) => e.fmt(fmt),
       Error::UrlError(ref e) => e.fmt(fmt),
       Error::FormatError(ref e) => e.fmt(fmt),
+      Error::Status(ref s) => write!(fmt, "unexpected status: {}", s),
     }
   }
 }
 
+impl error::Error for Error {}
+
 pub struct PathEncode<T>(pub T);
 
 impl<T> fmt::Display for PathEncode<T>
@@ -47,6 +54,7 @@ where
   T: fmt::Display
 {
   fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-    parsing::http_percent_encode(fmt, self.0.to_string().as_bytes())
+    let value = self.0.to_string();
+    write!(fmt, "{}", percent_encoding::utf8_percent_encode(&value, percent_encoding::NON_ALPHANUMERIC))
   }
 }
@@ -25,7 +25,7 @@ impl MyService_Reqwest {
   }
 
   /// UNKNOWN
-  pub fn unknown(&self, id: u32) -> reproto::Result<()> {
+  pub async fn unknown(&self, id: u32) -> reproto::Result<()> {
     let mut path_ = String::new();
     path_.push_str("/");
     path_.push_str("unknown");
@@ -34,15 +34,20 @@ impl MyService_Reqwest {
 
     let url_ = self.url.join(&path_)?;
 
-    let mut req_ = self.client.request(reqwest::Method::Get, url_);
+    let req_ = self.client.request(reqwest::Method::GET, url_);
 
-    req_.send()?;
+    let res_ = req_.send().await?;
+    let status_ = res_.status();
+
+    if !status_.is_success() {
+      return Err(reproto::Error::Status(status_));
+    }
 
     Ok(())
   }
 
   /// UNKNOWN
-  pub fn unknown_return(&self, id: u32) -> reproto::Result<Entry> {
+  pub async fn unknown_return(&self, id: u32) -> reproto::Result<Entry> {
     let mut path_ = String::new();
     path_.push_str("/");
     path_.push_str("unknown-return");
@@ -51,17 +56,22 @@ impl MyService_Reqwest {
 
     let url_ = self.url.join(&path_)?;
 
-    let mut req_ = self.client.request(reqwest::Method::Get, url_);
+    let req_ = self.client.request(reqwest::Method::GET, url_);
+
+    let res_ = req_.send().await?;
+    let status_ = res_.status();
 
-    let mut res_ = req_.send()?;
+    if !status_.is_success() {
+      return Err(reproto::Error::Status(status_));
+    }
 
-    let body_ = res_.json()?;
+    let body_ = res_.json().await?;
 
     Ok(body_)
   }
 
   /// UNKNOWN
-  pub fn unknown_argument(&self, request: Entry, id: u32) -> reproto::Result<()> {
+  pub async fn unknown_argument(&self, request: Entry, id: u32) -> reproto::Result<()> {
     let mut path_ = String::new();
     path_.push_str("/");
     path_.push_str("unknown-argument");
@@ -70,17 +80,21 @@ impl MyService_Reqwest {
 
     let url_ = self.url.join(&path_)?;
 
-    let mut req_ = self.client.request(reqwest::Method::Get, url_);
+    let req_ = self.client.request(reqwest::Method::GET, url_);
+    let req_ = req_.json(&request);
 
-    req_.json(&request);
+    let res_ = req_.send().await?;
+    let status_ = res_.status();
 
-    req_.send()?;
+    if !status_.is_success() {
+      return Err(reproto::Error::Status(status_));
+    }
 
     Ok(())
   }
 
   /// UNARY
-  pub fn unary(&self, request: Entry, id: u32) -> reproto::Result<Entry> {
+  pub async fn unary(&self, request: Entry, id: u32) -> reproto::Result<Entry> {
     let mut path_ = String::new();
     path_.push_str("/");
     path_.push_str("unary");
@@ -89,13 +103,17 @@ impl MyService_Reqwest {
 
     let url_ = self.url.join(&path_)?;
 
-    let mut req_ = self.client.request(reqwest::Method::Get, url_);
+    let req_ = self.client.request(reqwest::Method::GET, url_);
+    let req_ = req_.json(&request);
 
-    req_.json(&request);
+    let res_ = req_.send().await?;
+    let status_ = res_.status();
 
-    let mut res_ = req_.send()?;
+    if !status_.is_success() {
+      return Err(reproto::Error::Status(status_));
+    }
 
-    let body_ = res_.json()?;
+    let body_ = res_.json().await?;
 
     Ok(body_)
   }
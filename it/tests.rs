@@ -1,4 +1,16 @@
 use Language::*;
+use Suite;
+
+/// Round-trip the structures of another suite through the binary encoding.
+fn binary(suite: &mut Suite, dir: &str) {
+    suite.dir(dir);
+    suite.include(Rust);
+    suite.include(Java);
+    suite.arg(Rust, &["-m", "binary"]);
+    suite.arg(Java, &["-m", "binary"]);
+    suite.workdir(Rust, "rust-binary");
+    suite.workdir(Java, "java-binary");
+}
 
 define!{
     allstructures => {
@@ -9,6 +21,21 @@ define!{
     },
     basic => {
     },
+    binary_basic => {
+        binary(binary_basic, "basic");
+    },
+    binary_enum => {
+        binary(binary_enum, "enum");
+    },
+    binary_inner => {
+        binary(binary_inner, "inner");
+    },
+    binary_interfaces => {
+        binary(binary_interfaces, "interfaces");
+    },
+    binary_tuple => {
+        binary(binary_tuple, "tuple");
+    },
     code => {
     },
     enum_ => {
@@ -38,12 +65,18 @@ define!{
     js_keywords => {
         js_keywords.include(JavaScript);
     },
+    js_fetch => {
+        js_fetch.include(JavaScript);
+    },
     python_keywords => {
         python_keywords.include(Python);
     },
     python_requests => {
         python_requests.include(Python);
     },
+    python_httpx => {
+        python_httpx.include(Python);
+    },
     service => {
         service.package("service");
         service.arg(Java, &["-m", "grpc"]);
@@ -51,6 +84,10 @@ define!{
         service.include(Java);
         service.include(Rust);
         service.include(OpenApi);
+        service.include(AsyncApi);
+    },
+    rust_attributes => {
+        rust_attributes.include(Rust);
     },
     rust_keywords => {
         rust_keywords.include(Rust);
@@ -58,6 +95,12 @@ define!{
     rust_reqwest => {
         rust_reqwest.include(Rust);
     },
+    websocket => {
+        websocket.include(Rust);
+        websocket.include(JavaScript);
+        websocket.workdir(Rust, "rust-websocket");
+        websocket.workdir(JavaScript, "js-websocket");
+    },
     tuple => {},
     versions => {},
     default_naming => {},
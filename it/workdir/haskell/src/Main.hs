@@ -0,0 +1,18 @@
+module Main where
+
+import qualified Data.Aeson as Aeson
+import qualified Data.ByteString.Lazy.Char8 as BL
+import System.Exit (exitFailure)
+import System.IO (hPutStrLn, stderr)
+import qualified Test
+
+main :: IO ()
+main = do
+    input <- BL.getContents
+    mapM_ roundTrip (BL.lines input)
+
+roundTrip :: BL.ByteString -> IO ()
+roundTrip line =
+    case Aeson.eitherDecode line :: Either String Test.Entry of
+        Left e -> hPutStrLn stderr e >> exitFailure
+        Right entry -> BL.putStrLn (Aeson.encode entry)
@@ -0,0 +1,82 @@
+import {Entry, MyServiceWebSocketClient, serveMyService} from './test.js';
+import * as readline from 'readline';
+
+/**
+ * One end of an in-memory socket of text messages.
+ */
+class Socket {
+  constructor() {
+    this.peer = null;
+    this.listeners = [];
+  }
+
+  send(data) {
+    const peer = this.peer;
+
+    setImmediate(() => {
+      for (const listener of peer.listeners) {
+        listener({data: data});
+      }
+    });
+  }
+
+  addEventListener(type, listener) {
+    if (type === 'message') {
+      this.listeners.push(listener);
+    }
+  }
+}
+
+/**
+ * Service which echoes everything sent to it.
+ */
+const service = {
+  echo(entry) {
+    return entry;
+  },
+
+  async *echoStream(entries) {
+    for await (const entry of entries) {
+      yield entry;
+    }
+  }
+};
+
+async function run(entries) {
+  const client = new Socket();
+  const server = new Socket();
+  client.peer = server;
+  server.peer = client;
+
+  serveMyService(service, server);
+  const myService = new MyServiceWebSocketClient(client);
+
+  const echoed = [];
+
+  for (const entry of entries) {
+    echoed.push(await myService.echo(entry));
+  }
+
+  for await (const entry of myService.echoStream(echoed)) {
+    console.log(JSON.stringify(entry.encode()));
+  }
+}
+
+var rl = readline.createInterface({
+  input: process.stdin,
+  output: process.stdout,
+  terminal: false
+});
+
+const entries = [];
+
+rl.on('line', function(line) {
+  entries.push(Entry.decode(JSON.parse(line)));
+});
+
+rl.on('close', function() {
+  run(entries).catch(function(e) {
+    console.error(e);
+    process.exit(1);
+  });
+});
@@ -0,0 +1,24 @@
+plugins {
+    kotlin("jvm") version "1.9.24"
+    kotlin("plugin.serialization") version "1.9.24"
+    application
+}
+
+repositories {
+    mavenCentral()
+}
+
+dependencies {
+    implementation("org.jetbrains.kotlinx:kotlinx-serialization-json:1.6.3")
+    implementation("org.jetbrains.kotlinx:kotlinx-datetime:0.6.0")
+}
+
+sourceSets {
+    main {
+        kotlin.srcDir("generated")
+    }
+}
+
+application {
+    mainClass.set("MainKt")
+}
@@ -0,0 +1,30 @@
+import kotlinx.serialization.json.Json
+import kotlinx.serialization.modules.EmptySerializersModule
+import kotlinx.serialization.modules.SerializersModule
+import test.Entry
+
+/**
+ * The `serializersModule` of the `test` package, which is only declared if the package has
+ * interfaces.
+ */
+fun serializersModule(): SerializersModule =
+    try {
+        val file = Class.forName("test.TestKt")
+        file.getMethod("getSerializersModule").invoke(null) as SerializersModule
+    } catch (e: ClassNotFoundException) {
+        EmptySerializersModule()
+    } catch (e: NoSuchMethodException) {
+        EmptySerializersModule()
+    }
+
+fun main() {
+    val json = Json {
+        serializersModule = serializersModule()
+    }
+
+    while (true) {
+        val line = readLine() ?: break
+        val entry = json.decodeFromString(Entry.serializer(), line)
+        println(json.encodeToString(Entry.serializer(), entry))
+    }
+}
@@ -0,0 +1,8 @@
+#[allow(unused_extern_crates)]
+extern crate serde;
+#[allow(unused_extern_crates)]
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
+
+pub mod generated;
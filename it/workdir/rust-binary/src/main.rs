@@ -0,0 +1,26 @@
+extern crate rust;
+extern crate serde_json as json;
+
+use rust::generated::reproto_binary::Binary;
+use rust::generated::test::Entry;
+use std::io;
+use std::io::BufRead;
+
+fn main() {
+    let stdin = io::stdin();
+    let input = stdin.lock();
+
+    for line in input.lines() {
+        let line = line.unwrap();
+        let entry: Entry = json::from_str(&line).unwrap();
+
+        let mut out = Vec::new();
+        entry.encode(&mut out);
+
+        let mut input = &out[..];
+        let decoded = Entry::decode(&mut input).unwrap();
+        assert!(input.is_empty(), "trailing bytes: {:?}", input);
+
+        println!("{}", json::to_string(&decoded).unwrap());
+    }
+}
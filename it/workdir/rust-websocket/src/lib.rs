@@ -0,0 +1,4 @@
+#[macro_use]
+extern crate serde;
+
+pub mod generated;
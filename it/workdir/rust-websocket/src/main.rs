@@ -0,0 +1,120 @@
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::future::{self, BoxFuture, Either};
+use futures::stream::{self, BoxStream};
+use futures::{FutureExt, Sink, Stream, StreamExt};
+use rust::generated::test::{Entry, MyService_WebSocket, MyService_WebSocketClient};
+use serde_json as json;
+use std::io::{self, BufRead};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// One end of an in-memory socket of text messages.
+struct Socket {
+    tx: mpsc::UnboundedSender<String>,
+    rx: mpsc::UnboundedReceiver<String>,
+}
+
+impl Socket {
+    /// Build two connected ends of a socket.
+    fn pair() -> (Socket, Socket) {
+        let (a_tx, a_rx) = mpsc::unbounded();
+        let (b_tx, b_rx) = mpsc::unbounded();
+
+        let a = Socket { tx: a_tx, rx: b_rx };
+        let b = Socket { tx: b_tx, rx: a_rx };
+        (a, b)
+    }
+}
+
+impl Stream for Socket {
+    type Item = Result<String, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_next_unpin(cx).map(|m| m.map(Ok))
+    }
+}
+
+impl Sink<String> for Socket {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.tx).poll_ready(cx).map_err(Into::into)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: String) -> Result<(), Error> {
+        Pin::new(&mut self.tx).start_send(item).map_err(Into::into)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.tx).poll_flush(cx).map_err(Into::into)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.tx).poll_close(cx).map_err(Into::into)
+    }
+}
+
+/// Service which echoes everything sent to it.
+struct Echo;
+
+impl MyService_WebSocket for Echo {
+    type Error = String;
+
+    fn echo(&self, entry: Entry) -> BoxFuture<'static, Result<Entry, String>> {
+        future::ready(Ok(entry)).boxed()
+    }
+
+    fn echo_stream(
+        &self,
+        entries: BoxStream<'static, Entry>,
+    ) -> BoxStream<'static, Result<Entry, String>> {
+        entries.map(Ok).boxed()
+    }
+}
+
+fn main() {
+    let stdin = io::stdin();
+    let input = stdin.lock();
+
+    let mut entries = Vec::new();
+
+    for line in input.lines() {
+        let line = line.unwrap();
+        let entry: Entry = json::from_str(&line).unwrap();
+        entries.push(entry);
+    }
+
+    let (client_socket, server_socket) = Socket::pair();
+
+    let server = Arc::new(Echo).serve(server_socket);
+    let (client, connection) = MyService_WebSocketClient::new(client_socket);
+
+    let calls = async move {
+        let mut echoed = Vec::new();
+
+        for entry in entries {
+            echoed.push(client.echo(entry).await.unwrap());
+        }
+
+        client
+            .echo_stream(stream::iter(echoed).boxed())
+            .map(Result::unwrap)
+            .collect::<Vec<Entry>>()
+            .await
+    };
+
+    let driver = future::try_join(server, connection);
+
+    let entries = match block_on(future::select(calls.boxed(), driver)) {
+        Either::Left((entries, _)) => entries,
+        Either::Right((result, _)) => panic!("connection closed: {:?}", result.err()),
+    };
+
+    for entry in entries {
+        println!("{}", json::to_string(&entry).unwrap());
+    }
+}
@@ -79,12 +79,20 @@ pub enum Type<'input> {
     Unsigned {
         size: usize,
     },
+    /// Arbitrary precision decimal number.
+    Decimal,
     Boolean,
     String,
     Bytes,
     Any,
     /// ISO-8601 for date and time.
     DateTime,
+    /// ISO-8601 calendar date, without a time.
+    Date,
+    /// ISO-8601 time of day, without a date or timezone.
+    Time,
+    /// ISO-8601 duration.
+    Duration,
     Name {
         name: Loc<Name<'input>>,
     },
@@ -95,6 +103,15 @@ pub enum Type<'input> {
         key: Box<Loc<Type<'input>>>,
         value: Box<Loc<Type<'input>>>,
     },
+    /// A custom scalar declared in the manifest, like `uuid`.
+    Scalar {
+        name: Loc<Cow<'input, str>>,
+    },
+    /// A type with arguments, like `set<string>`.
+    Generic {
+        name: Loc<Cow<'input, str>>,
+        arguments: Vec<Loc<Type<'input>>>,
+    },
     /// A complete error.
     Error,
 }
@@ -107,6 +124,8 @@ pub enum Decl<'input> {
     Interface(Item<'input, InterfaceBody<'input>>),
     Enum(Item<'input, EnumBody<'input>>),
     Service(Item<'input, ServiceBody<'input>>),
+    Union(Item<'input, UnionBody<'input>>),
+    Newtype(Item<'input, NewtypeBody<'input>>),
 }
 
 impl<'input> Decl<'input> {
@@ -120,11 +139,28 @@ impl<'input> Decl<'input> {
             Interface(ref body) => &body.name,
             Enum(ref body) => &body.name,
             Service(ref body) => &body.name,
+            Union(ref body) => &body.name,
+            Newtype(ref body) => &body.name,
         };
 
         Loc::map(Loc::as_ref(name), |n| n.as_ref())
     }
 
+    /// Get the span of the whole declaration.
+    pub fn span(&self) -> Span {
+        use self::Decl::*;
+
+        match *self {
+            Type(ref body) => Loc::span(&body.item),
+            Tuple(ref body) => Loc::span(&body.item),
+            Interface(ref body) => Loc::span(&body.item),
+            Enum(ref body) => Loc::span(&body.item),
+            Service(ref body) => Loc::span(&body.item),
+            Union(ref body) => Loc::span(&body.item),
+            Newtype(ref body) => Loc::span(&body.item),
+        }
+    }
+
     /// Get all the sub-declarations of this declaraiton.
     pub fn decls(&self) -> impl Iterator<Item = &Decl<'input>> {
         use self::Decl::*;
@@ -135,6 +171,8 @@ impl<'input> Decl<'input> {
             Interface(ref body) => body.decls(),
             Enum(ref body) => body.decls(),
             Service(ref body) => body.decls(),
+            Union(_) => Vec::new(),
+            Newtype(_) => Vec::new(),
         };
 
         decls.into_iter()
@@ -150,6 +188,23 @@ impl<'input> Decl<'input> {
             Interface(ref body) => &body.comment,
             Enum(ref body) => &body.comment,
             Service(ref body) => &body.comment,
+            Union(ref body) => &body.comment,
+            Newtype(ref body) => &body.comment,
+        }
+    }
+
+    /// Attributes.
+    pub fn attributes(&self) -> &Vec<Loc<Attribute<'input>>> {
+        use self::Decl::*;
+
+        match *self {
+            Type(ref body) => &body.attributes,
+            Tuple(ref body) => &body.attributes,
+            Interface(ref body) => &body.attributes,
+            Enum(ref body) => &body.attributes,
+            Service(ref body) => &body.attributes,
+            Union(ref body) => &body.attributes,
+            Newtype(ref body) => &body.attributes,
         }
     }
 }
@@ -184,6 +239,8 @@ impl<'input> EnumBody<'input> {
 pub struct EnumVariant<'input> {
     pub name: Loc<Cow<'input, str>>,
     pub argument: Option<Loc<Value<'input>>>,
+    /// Additional values which are accepted for the variant when decoding.
+    pub aliases: Vec<Loc<Value<'input>>>,
 }
 
 /// A member in a tuple, type, or interface.
@@ -223,6 +280,8 @@ pub struct File<'input> {
     pub attributes: Vec<Loc<Attribute<'input>>>,
     pub uses: Vec<Loc<UseDecl<'input>>>,
     pub decls: Vec<Decl<'input>>,
+    /// Syntax errors which were recovered from by skipping the declaration they occurred in.
+    pub errors: Vec<Loc<String>>,
 }
 
 impl<'input> Field<'input> {
@@ -314,6 +373,30 @@ pub enum TypeMember<'input> {
     Field(Item<'input, Field<'input>>),
     Code(Loc<Code<'input>>),
     InnerDecl(Decl<'input>),
+    /// Field names which are reserved, like `reserved foo, "bar";`.
+    Reserved(Loc<Vec<Loc<Value<'input>>>>),
+}
+
+/// The body of an untagged union.
+///
+/// ```ignore
+/// type <name> = <variant> | <variant>;
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnionBody<'input> {
+    pub name: Loc<Cow<'input, str>>,
+    pub variants: Vec<Loc<Type<'input>>>,
+}
+
+/// The body of a newtype, which wraps a single type.
+///
+/// ```ignore
+/// type <name>(<type>);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewtypeBody<'input> {
+    pub name: Loc<Cow<'input, str>>,
+    pub ty: Loc<Type<'input>>,
 }
 
 /// The body of a service declaration.
@@ -367,6 +450,7 @@ pub enum ServiceMember<'input> {
 /// The argument in and endpoint.
 #[derive(Debug, PartialEq, Eq)]
 pub struct EndpointArgument<'input> {
+    pub comment: Vec<Cow<'input, str>>,
     pub ident: Loc<Cow<'input, str>>,
     pub channel: Loc<Channel<'input>>,
 }
@@ -374,7 +458,7 @@ pub struct EndpointArgument<'input> {
 /// An endpoint
 ///
 /// ```ignore
-/// <id>(<arguments>) -> <response> as <alias> {
+/// <id>(<arguments>) -> <response> throws <errors> as <alias> {
 ///   <options>
 /// }
 /// ```
@@ -384,6 +468,19 @@ pub struct Endpoint<'input> {
     pub alias: Option<String>,
     pub arguments: Vec<EndpointArgument<'input>>,
     pub response: Option<Loc<Channel<'input>>>,
+    pub errors: Vec<Loc<EndpointError<'input>>>,
+}
+
+/// An error which an endpoint responds with.
+///
+/// ```ignore
+/// <ident>: <ty> @ <status>
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct EndpointError<'input> {
+    pub ident: Loc<Cow<'input, str>>,
+    pub ty: Loc<Type<'input>>,
+    pub status: Loc<RpNumber>,
 }
 
 /// Describes how data is transferred over a channel.
@@ -0,0 +1,379 @@
+#[macro_use]
+extern crate log;
+#[allow(unused)]
+#[macro_use]
+extern crate reproto_backend as backend;
+extern crate reproto_core as core;
+extern crate reproto_json_schema as json_schema;
+#[macro_use]
+extern crate reproto_manifest as manifest;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate linked_hash_map;
+extern crate serde_json as json;
+extern crate serde_yaml as yaml;
+extern crate toml;
+
+mod spec;
+
+const ASYNCAPI_VERSION: &str = "2.6.0";
+
+use self::spec::*;
+use core::errors::*;
+use core::flavored::{RpChannel, RpServiceBody, RpVersionedPackage};
+use core::{CoreFlavor, Handle, Loc, RelativePath, RelativePathBuf, RpAccept};
+use json_schema::{Dialect, Queued, SchemaBuilder};
+use linked_hash_map::LinkedHashMap;
+use manifest::{checked_modules, Lang, Manifest, ModuleDoc, ModuleOption, NoModule, TryFromToml};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::path::Path;
+use trans::{Session, Translated};
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AsyncApiLang;
+
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "json",
+        description: "Write documents as JSON instead of YAML.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "info",
+        description: "Metadata to include in every document.",
+        options: &[
+            ModuleOption {
+                name: "title",
+                ty: "string",
+                default: None,
+                description: "Title of the document, defaults to the name of the service.",
+            },
+            ModuleOption {
+                name: "description",
+                ty: "string",
+                default: None,
+                description: "Description of the document.",
+            },
+        ],
+    },
+];
+
+impl Lang for AsyncApiLang {
+    lang_base!(AsyncApiModule, compile);
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+}
+
+#[derive(Debug)]
+pub enum AsyncApiModule {
+    Json,
+    Info(InfoConfig),
+}
+
+/// Metadata to include in every document.
+#[derive(Debug, Default, Deserialize)]
+pub struct InfoConfig {
+    /// Title of the document.
+    title: Option<String>,
+    /// Description of the document.
+    description: Option<String>,
+}
+
+impl TryFromToml for AsyncApiModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
+        use self::AsyncApiModule::*;
+
+        let result = match id {
+            "json" => Json,
+            "info" => Info(InfoConfig::default()),
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        use self::AsyncApiModule::*;
+
+        let result = match id {
+            "json" => Json,
+            "info" => Info(value.try_into()?),
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+}
+
+fn compile(handle: &Handle, env: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let env = env.translate_default()?;
+
+    let modules = checked_modules(manifest.modules)?;
+
+    let mut compiler = Compiler::new(handle, env);
+    compiler.load_options(modules)?;
+    compiler.compile()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum OutputFormat {
+    Yaml,
+    Json,
+}
+
+impl OutputFormat {
+    /// Get the file extension to use.
+    fn ext(&self) -> &'static str {
+        use self::OutputFormat::*;
+
+        match *self {
+            Yaml => "yaml",
+            Json => "json",
+        }
+    }
+}
+
+struct Compiler<'handle> {
+    handle: &'handle Handle,
+    env: Translated<CoreFlavor>,
+    output_format: OutputFormat,
+    info: InfoConfig,
+}
+
+impl<'handle> Compiler<'handle> {
+    pub fn new(handle: &'handle Handle, env: Translated<CoreFlavor>) -> Self {
+        Compiler {
+            handle,
+            env,
+            output_format: OutputFormat::Yaml,
+            info: InfoConfig::default(),
+        }
+    }
+
+    /// Load options from the given modules.
+    fn load_options(&mut self, modules: Vec<AsyncApiModule>) -> Result<()> {
+        use self::AsyncApiModule::*;
+
+        for module in modules {
+            match module {
+                Json => {
+                    self.output_format = OutputFormat::Json;
+                }
+                Info(info) => {
+                    self.info = info;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compile(self) -> Result<()> {
+        let root = RelativePathBuf::from(".");
+
+        for (package, file) in self.env.for_each_file() {
+            let mut dir = package
+                .package
+                .parts()
+                .fold(root.clone(), |path, part| path.join(part));
+
+            for d in file.for_each_decl() {
+                // Use services as entrypoints.
+                let service = match *d {
+                    core::RpDecl::Service(ref service) => service,
+                    _ => continue,
+                };
+
+                let mut builder = SpecBuilder {
+                    schemas: SchemaBuilder::new(Dialect::AsyncApi, &self.env),
+                    handle: self.handle,
+                    output_format: self.output_format,
+                    info: &self.info,
+                };
+
+                let (spec, path) = builder.build(&dir, package, service)?;
+
+                debug!("+file: {}", path.display());
+
+                let out = self.handle.create(&path)?;
+
+                match self.output_format {
+                    OutputFormat::Yaml => yaml::to_writer(out, &spec)?,
+                    OutputFormat::Json => json::to_writer_pretty(out, &spec)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps track of important and temporary state for a single Spec.
+struct SpecBuilder<'builder> {
+    /// Converts declarations into schemas.
+    schemas: SchemaBuilder<'builder>,
+    handle: &'builder Handle,
+    /// Format to write output as.
+    output_format: OutputFormat,
+    /// Metadata to include in the document.
+    info: &'builder InfoConfig,
+}
+
+impl<'builder> SpecBuilder<'builder> {
+    /// Process a service into a Spec.
+    fn build(
+        self,
+        dir: &RelativePath,
+        package: &'builder RpVersionedPackage,
+        service: &'builder RpServiceBody,
+    ) -> Result<(Spec<'builder>, RelativePathBuf)> {
+        let mut queue = VecDeque::new();
+
+        let ext = self.output_format.ext();
+
+        let ident = if let Some(version) = package.version.as_ref() {
+            format!("{}-{}.{}", service.ident, version, ext)
+        } else {
+            format!("{}.{}", service.ident, ext)
+        };
+
+        let path = dir.join(ident);
+
+        let title = match self.info.title {
+            Some(ref title) => title.as_str(),
+            None => service.ident.as_str(),
+        };
+
+        let mut spec = Spec {
+            asyncapi: ASYNCAPI_VERSION,
+            info: Info {
+                title,
+                description: self.info.description.clone(),
+                version: package.version.as_ref(),
+            },
+            servers: LinkedHashMap::new(),
+            tags: Vec::new(),
+            channels: LinkedHashMap::new(),
+            components: None,
+        };
+
+        if let Some(ref url) = service.http.url {
+            let url = url.as_str();
+
+            // NB: the protocol is the scheme of the url, like `ws` or `kafka`.
+            match url.find("://") {
+                Some(index) => {
+                    let protocol = &url[..index];
+                    spec.servers.insert("default", Server { url, protocol });
+                }
+                None => warn!(
+                    "{}: ignoring url without a protocol: {}",
+                    service.ident, url
+                ),
+            }
+        }
+
+        // NB: every operation is tagged with the service that it belongs to.
+        spec.tags.push(Tag {
+            name: service.ident.as_str(),
+            description: if !service.comment.is_empty() {
+                Some(service.comment.join("\n"))
+            } else {
+                None
+            },
+        });
+
+        for e in &service.endpoints {
+            let mut channel = Channel::default();
+
+            if !e.comment.is_empty() {
+                channel.description = Some(e.comment.join("\n"));
+            }
+
+            // NB: clients publish requests, and subscribe to responses.
+            if let Some(req) = e.request.as_ref() {
+                channel.publish = Some(self.channel_to_operation(
+                    &mut queue,
+                    service,
+                    format!("{}_request", e.ident()),
+                    e.http.accepts,
+                    &req.channel,
+                )?);
+            }
+
+            if let Some(res) = e.response.as_ref() {
+                channel.subscribe = Some(self.channel_to_operation(
+                    &mut queue,
+                    service,
+                    format!("{}_response", e.ident()),
+                    e.http.produces,
+                    res,
+                )?);
+            }
+
+            // endpoints without messages have nothing to document.
+            if channel.publish.is_none() && channel.subscribe.is_none() {
+                continue;
+            }
+
+            spec.channels.insert(e.name(), channel);
+        }
+
+        let schemas = self.schemas.process_components(queue)?;
+
+        if !schemas.is_empty() {
+            spec.components = Some(Components { schemas });
+        }
+
+        if let Some(parent) = path.parent() {
+            if !self.handle.is_dir(parent) {
+                debug!("+dir: {}", parent.display());
+                self.handle.create_dir_all(parent)?;
+            }
+        }
+
+        Ok((spec, path))
+    }
+
+    /// Convert a channel into an operation sending or receiving messages.
+    fn channel_to_operation(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        service: &'builder RpServiceBody,
+        operation_id: String,
+        accept: RpAccept,
+        channel: &'builder RpChannel,
+    ) -> Result<Operation<'builder>> {
+        let ty = channel.ty();
+        let payload = self.schemas.type_to_schema(queue, ty)?;
+
+        if let core::RpType::Name { ref name } = *ty {
+            queue.push_back(Queued::Named(Loc::borrow(name)));
+        }
+
+        let summary = if channel.is_streaming() {
+            Some("Stream of messages.")
+        } else {
+            None
+        };
+
+        Ok(Operation {
+            operation_id,
+            summary,
+            tags: vec![Tag {
+                name: service.ident.as_str(),
+                description: None,
+            }],
+            message: Message {
+                content_type: accept.as_str(),
+                payload,
+            },
+        })
+    }
+}
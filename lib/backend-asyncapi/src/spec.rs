@@ -0,0 +1,81 @@
+use core::Version;
+use json_schema::spec::Schema;
+use linked_hash_map::LinkedHashMap;
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub struct Info<'a> {
+    pub title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<&'a Version>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub struct Tag<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub struct Message<'a> {
+    pub content_type: &'static str,
+    pub payload: Schema<'a>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub struct Operation<'a> {
+    pub operation_id: String,
+    /// Set for channels which stream any number of messages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag<'a>>,
+    pub message: Message<'a>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Default, Debug, Serialize)]
+pub struct Channel<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Messages sent by clients, and received by the service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish: Option<Operation<'a>>,
+    /// Messages sent by the service, and received by clients.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribe: Option<Operation<'a>>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize)]
+pub struct Components<'a> {
+    #[serde(skip_serializing_if = "LinkedHashMap::is_empty")]
+    pub schemas: LinkedHashMap<String, Schema<'a>>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub struct Server<'a> {
+    pub url: &'a str,
+    pub protocol: &'a str,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub struct Spec<'a> {
+    pub asyncapi: &'static str,
+    pub info: Info<'a>,
+    #[serde(skip_serializing_if = "LinkedHashMap::is_empty")]
+    pub servers: LinkedHashMap<&'static str, Server<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag<'a>>,
+    pub channels: LinkedHashMap<&'a str, Channel<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Components<'a>>,
+}
@@ -0,0 +1,363 @@
+#[macro_use]
+extern crate log;
+#[allow(unused)]
+#[macro_use]
+extern crate reproto_backend as backend;
+extern crate reproto_core as core;
+#[macro_use]
+extern crate reproto_manifest as manifest;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json as json;
+extern crate toml;
+
+mod spec;
+
+use core::errors::*;
+use core::flavored::{RpDecl, RpEnumBody, RpField, RpInterfaceBody, RpName, RpType, RpUnionBody};
+use core::{CoreFlavor, Handle, Loc, RelativePathBuf, RpNumberKind};
+use manifest::{checked_modules, Lang, Manifest, NoModule, TryFromToml};
+use std::any::Any;
+use std::collections::HashSet;
+use std::path::Path;
+use trans::{Session, Translated};
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AvroLang;
+
+impl Lang for AvroLang {
+    lang_base!(AvroModule, compile);
+}
+
+#[derive(Debug)]
+pub enum AvroModule {}
+
+impl TryFromToml for AvroModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
+        NoModule::illegal(path, id, value)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        NoModule::illegal(path, id, value)
+    }
+}
+
+fn compile(handle: &Handle, env: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let env = env.translate_default()?;
+    let _modules: Vec<AvroModule> = checked_modules(manifest.modules)?;
+
+    let root = RelativePathBuf::from(".");
+
+    for (package, file) in env.for_each_file() {
+        let dir = package
+            .package
+            .parts()
+            .fold(root.clone(), |path, part| path.join(part));
+
+        for decl in file.for_each_decl() {
+            // services describe endpoints, which have no representation in Avro.
+            if let RpDecl::Service(_) = *decl {
+                continue;
+            }
+
+            let mut builder = SchemaBuilder {
+                env: &env,
+                defined: HashSet::new(),
+            };
+
+            let schema = builder.named(decl.name())?;
+
+            if !handle.is_dir(&dir) {
+                debug!("+dir: {}", dir.display());
+                handle.create_dir_all(&dir)?;
+            }
+
+            let ident = decl.name().path.join(".");
+
+            let path = match package.version.as_ref() {
+                Some(version) => dir.join(format!("{}-{}.avsc", ident, version)),
+                None => dir.join(format!("{}.avsc", ident)),
+            };
+
+            debug!("+file: {}", path.display());
+            json::to_writer_pretty(handle.create(&path)?, &schema)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a single self-contained schema.
+///
+/// Named types are defined the first time they are referenced, and referenced by their full name
+/// after that.
+struct SchemaBuilder<'a> {
+    env: &'a Translated<CoreFlavor>,
+    defined: HashSet<RpName>,
+}
+
+impl<'a> SchemaBuilder<'a> {
+    /// Build the schema for a named declaration.
+    fn named(&mut self, name: &RpName) -> Result<spec::Schema> {
+        let env = self.env;
+        let decl = env.lookup_decl(name)?;
+
+        match *decl {
+            // interfaces and unions are represented as unions in Avro, which are not named and
+            // are built in full wherever they are used.
+            RpDecl::Interface(ref body) => return self.interface(body),
+            RpDecl::Union(ref body) => return self.union(body),
+            // newtypes are serialized as the type they wrap.
+            RpDecl::Newtype(ref body) => return self.ty(&body.ty),
+            _ => {}
+        }
+
+        if !self.defined.insert(name.clone()) {
+            return Ok(spec::Schema::Name(full_name(name)));
+        }
+
+        match *decl {
+            RpDecl::Type(ref body) => {
+                self.record(&body.name, &body.comment, body.fields().map(Loc::borrow))
+            }
+            RpDecl::Tuple(ref body) => {
+                self.record(&body.name, &body.comment, body.fields().map(Loc::borrow))
+            }
+            RpDecl::Enum(ref body) => self.enum_(body),
+            _ => Err(format!("{}: not supported by Avro", name).into()),
+        }
+    }
+
+    fn record<'f, I>(
+        &mut self,
+        name: &RpName,
+        comment: &[String],
+        fields: I,
+    ) -> Result<spec::Schema>
+    where
+        I: IntoIterator<Item = &'f RpField>,
+    {
+        let (namespace, ident) = split_name(name);
+        let mut record = spec::Record::new(ident, namespace);
+        record.doc = doc(comment);
+
+        for field in fields {
+            record.fields.push(self.field(field)?);
+        }
+
+        Ok(record.into())
+    }
+
+    fn field(&mut self, field: &RpField) -> Result<spec::Field> {
+        let name = field.name();
+
+        if !is_name(name) {
+            return Err(format!("field `{}`: not a valid Avro name", name).into());
+        }
+
+        // NB: Avro has no patterns, so numbers encoded as strings accept any string.
+        let mut ty = if field.is_number_as_string() {
+            spec::Schema::primitive("string")
+        } else {
+            self.ty(field.ty())?
+        };
+
+        let mut default = None;
+
+        // optional fields must default to `null` so that they can be left out.
+        if field.is_optional() {
+            ty = ty.optional();
+            default = Some(json::Value::Null);
+        }
+
+        Ok(spec::Field {
+            name: name.to_string(),
+            ty,
+            default,
+            doc: doc(&field.comment),
+        })
+    }
+
+    fn enum_(&mut self, body: &RpEnumBody) -> Result<spec::Schema> {
+        let variants = match body.variants {
+            core::RpVariants::String { ref variants } => variants,
+            // numeric enums are encoded using their value.
+            core::RpVariants::Number { .. } => match body.enum_type {
+                core::RpEnumType::Number(ref number) => return number_type(number.kind),
+                _ => return Err("unexpected enum type".into()),
+            },
+        };
+
+        let (namespace, ident) = split_name(&body.name);
+        let mut out = spec::Enum::new(ident, namespace);
+        out.doc = doc(&body.comment);
+
+        for v in variants {
+            if !is_name(&v.value) {
+                return Err(format!(
+                    "{}: variant value `{}` is not a valid Avro enum symbol",
+                    body.name, v.value
+                )
+                .into());
+            }
+
+            out.symbols.push(v.value.to_string());
+        }
+
+        Ok(out.into())
+    }
+
+    fn interface(&mut self, body: &RpInterfaceBody) -> Result<spec::Schema> {
+        let mut variants = Vec::new();
+
+        for sub_type in &body.sub_types {
+            if !self.defined.insert(Loc::borrow(&sub_type.name).clone()) {
+                variants.push(spec::Schema::Name(full_name(&sub_type.name)));
+                continue;
+            }
+
+            let mut comment = body.comment.clone();
+            comment.extend(sub_type.comment.iter().cloned());
+
+            let fields = body.fields().chain(sub_type.fields()).map(Loc::borrow);
+
+            variants.push(self.record(&sub_type.name, &comment, fields)?);
+        }
+
+        Ok(spec::Schema::Union(variants))
+    }
+
+    fn union(&mut self, body: &RpUnionBody) -> Result<spec::Schema> {
+        let mut variants = Vec::new();
+
+        for variant in &body.variants {
+            match self.ty(&variant.ty)? {
+                spec::Schema::Union(inner) => variants.extend(inner),
+                other => variants.push(other),
+            }
+        }
+
+        Ok(spec::Schema::Union(variants))
+    }
+
+    fn ty(&mut self, ty: &RpType) -> Result<spec::Schema> {
+        use core::RpType::*;
+
+        let out = match *ty {
+            Double => spec::Schema::primitive("double"),
+            Float => spec::Schema::primitive("float"),
+            Number(ref number) => number_type(number.kind)?,
+            Boolean => spec::Schema::primitive("boolean"),
+            String(..) => spec::Schema::primitive("string"),
+            DateTime => spec::Schema::from(spec::Logical {
+                ty: "long",
+                logical_type: "timestamp-millis",
+            }),
+            Date => spec::Schema::from(spec::Logical {
+                ty: "int",
+                logical_type: "date",
+            }),
+            Time => spec::Schema::from(spec::Logical {
+                ty: "int",
+                logical_type: "time-millis",
+            }),
+            // NB: the `duration` logical type counts months, days, and milliseconds separately,
+            // which can't represent every ISO-8601 duration.
+            Duration => spec::Schema::primitive("string"),
+            Bytes => spec::Schema::primitive("bytes"),
+            Any => return Err("`any` is not supported by Avro".into()),
+            Scalar(ref scalar) => self.ty(&scalar.base_type())?,
+            Name { ref name } => self.named(name)?,
+            Array { ref inner } | Set { ref inner } => {
+                spec::Schema::from(spec::Array::new(self.ty(inner)?))
+            }
+            Map { ref key, ref value } => {
+                match **key {
+                    String(..) => {}
+                    ref key => {
+                        return Err(format!(
+                            "map key `{}` is not supported by Avro, only `string` is",
+                            key
+                        )
+                        .into())
+                    }
+                }
+
+                spec::Schema::from(spec::Map::new(self.ty(value)?))
+            }
+        };
+
+        Ok(out)
+    }
+}
+
+/// Avro type used to store the given kind of number.
+///
+/// Unsigned numbers are stored in a `long`, since Avro doesn't have unsigned types.
+fn number_type(kind: RpNumberKind) -> Result<spec::Schema> {
+    let out = match kind {
+        RpNumberKind::I32 => spec::Schema::primitive("int"),
+        RpNumberKind::U32 | RpNumberKind::I64 | RpNumberKind::U64 => {
+            spec::Schema::primitive("long")
+        }
+        RpNumberKind::U128 | RpNumberKind::I128 | RpNumberKind::Decimal => {
+            spec::Schema::primitive("string")
+        }
+    };
+
+    Ok(out)
+}
+
+/// Split a name into its namespace and local name.
+///
+/// Outer declarations are part of the namespace.
+fn split_name(name: &RpName) -> (Option<String>, String) {
+    let mut parts = name
+        .package
+        .package
+        .parts()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+
+    parts.extend(name.path.iter().cloned());
+
+    let ident = parts.pop().unwrap_or_default();
+
+    let namespace = if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("."))
+    };
+
+    (namespace, ident)
+}
+
+/// Full name used to refer to already defined types.
+fn full_name(name: &RpName) -> String {
+    match split_name(name) {
+        (Some(namespace), ident) => format!("{}.{}", namespace, ident),
+        (None, ident) => ident,
+    }
+}
+
+fn doc(comment: &[String]) -> Option<String> {
+    if comment.is_empty() {
+        return None;
+    }
+
+    Some(comment.join("\n"))
+}
+
+/// Check that the given string is a valid Avro name.
+fn is_name(name: &str) -> bool {
+    let mut it = name.chars();
+
+    match it.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    it.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,167 @@
+//! Data structures for Avro schemas.
+
+use json;
+
+/// A single Avro schema.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Schema {
+    /// A primitive type, like `"long"`, or a reference to an already defined named type.
+    Name(String),
+    Logical(Logical),
+    Array(Array),
+    Map(Map),
+    Record(Record),
+    Enum(Enum),
+    /// A union of schemas, where `null` is used to indicate an optional value.
+    Union(Vec<Schema>),
+}
+
+impl Schema {
+    pub fn primitive(name: &str) -> Schema {
+        Schema::Name(name.to_string())
+    }
+
+    /// Make the schema accept `null`, merging it into existing unions since they can't be nested.
+    pub fn optional(self) -> Schema {
+        let mut variants = vec![Schema::primitive("null")];
+
+        match self {
+            Schema::Union(rest) => variants.extend(rest),
+            other => variants.push(other),
+        }
+
+        Schema::Union(variants)
+    }
+}
+
+/// A primitive type annotated with a logical type.
+#[derive(Debug, Clone, Serialize)]
+pub struct Logical {
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    #[serde(rename = "logicalType")]
+    pub logical_type: &'static str,
+}
+
+impl From<Logical> for Schema {
+    fn from(value: Logical) -> Schema {
+        Schema::Logical(value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Array {
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub items: Box<Schema>,
+}
+
+impl Array {
+    pub fn new(items: Schema) -> Array {
+        Array {
+            ty: "array",
+            items: Box::new(items),
+        }
+    }
+}
+
+impl From<Array> for Schema {
+    fn from(value: Array) -> Schema {
+        Schema::Array(value)
+    }
+}
+
+/// A map, which always has string keys.
+#[derive(Debug, Clone, Serialize)]
+pub struct Map {
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub values: Box<Schema>,
+}
+
+impl Map {
+    pub fn new(values: Schema) -> Map {
+        Map {
+            ty: "map",
+            values: Box::new(values),
+        }
+    }
+}
+
+impl From<Map> for Schema {
+    fn from(value: Map) -> Schema {
+        Schema::Map(value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    pub fields: Vec<Field>,
+}
+
+impl Record {
+    pub fn new(name: String, namespace: Option<String>) -> Record {
+        Record {
+            ty: "record",
+            name,
+            namespace,
+            doc: None,
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl From<Record> for Schema {
+    fn from(value: Record) -> Schema {
+        Schema::Record(value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Schema,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Enum {
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    pub symbols: Vec<String>,
+}
+
+impl Enum {
+    pub fn new(name: String, namespace: Option<String>) -> Enum {
+        Enum {
+            ty: "enum",
+            name,
+            namespace,
+            doc: None,
+            symbols: Vec::new(),
+        }
+    }
+}
+
+impl From<Enum> for Schema {
+    fn from(value: Enum) -> Schema {
+        Schema::Enum(value)
+    }
+}
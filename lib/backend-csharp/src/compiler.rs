@@ -5,7 +5,7 @@ use codegen::{
     TypeFieldAdded,
 };
 use core::errors::*;
-use core::{self, Handle, Loc, RpContext, RpSubTypeStrategy};
+use core::{self, Handle, Loc, RpContext, RpDeprecated, RpSubTypeStrategy};
 use csharp_field::CsharpField;
 use csharp_file::CsharpFile;
 use flavored::{
@@ -16,13 +16,22 @@ use genco::csharp::{
     self, local, optional, using, Argument, Class, Constructor, Enum, Field, Method, Modifier,
     BOOLEAN, INT32,
 };
-use genco::{Cons, Csharp, Element, Quoted, Tokens};
+use genco::{Cons, Csharp, Element, IntoTokens, Quoted, Tokens};
 use naming::{self, Naming};
 use processor::Processor;
 use std::rc::Rc;
 use trans::Translated;
+use utils::Obsolete;
 use Options;
 
+/// Attribute prefix for a deprecated enum variant.
+fn obsolete<'el>(deprecated: Option<&RpDeprecated>) -> Tokens<'el, Csharp<'el>> {
+    match deprecated {
+        Some(deprecated) => toks![Obsolete::new(deprecated).into_tokens(), " "],
+        None => Tokens::new(),
+    }
+}
+
 pub struct Compiler {
     env: Rc<Translated<CsharpFlavor>>,
     options: Options,
@@ -268,8 +277,19 @@ impl Compiler {
     fn process_enum<'el>(&self, body: &'el RpEnumBody) -> Result<Enum<'el>> {
         let mut spec = Enum::new(body.ident.clone());
 
+        if let Some(ref deprecated) = body.deprecated {
+            spec.attribute(Obsolete::new(deprecated));
+        }
+
         let mut names = None;
 
+        if body.variants.iter().any(|v| !v.aliases.is_empty()) {
+            warn!(
+                "{}: aliases are not supported for enums, they will be rejected when decoding",
+                body.name
+            );
+        }
+
         match body.enum_type {
             csharp::INT64 | csharp::UINT64 => {
                 spec.implements.push(csharp::local("long"));
@@ -284,7 +304,7 @@ impl Compiler {
                 for v in variants {
                     let name = Rc::new(self.variant_naming.convert(v.ident()));
                     local_names.push(v.value.to_string().into());
-                    spec.variants.append(toks![name]);
+                    spec.variants.append(toks![obsolete(v.deprecated.as_ref()), name]);
                 }
 
                 names = Some(local_names);
@@ -297,7 +317,8 @@ impl Compiler {
                     _ => v.value.to_string(),
                 };
 
-                spec.variants.append(toks![name, " = ", value]);
+                spec.variants
+                    .append(toks![obsolete(v.deprecated.as_ref()), name, " = ", value]);
             },
         }
 
@@ -317,6 +338,10 @@ impl Compiler {
     fn process_tuple<'el>(&self, body: &'el RpTupleBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.ident.clone());
 
+        if let Some(ref deprecated) = body.deprecated {
+            spec.attribute(Obsolete::new(deprecated));
+        }
+
         let fields = self.fields(&body.fields)?;
 
         self.add_class(
@@ -342,6 +367,10 @@ impl Compiler {
 
     fn process_type<'el>(&self, body: &'el RpTypeBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.ident.clone());
+
+        if let Some(ref deprecated) = body.deprecated {
+            spec.attribute(Obsolete::new(deprecated));
+        }
         let fields = self.fields(&body.fields)?;
         let names: Vec<_> = fields.iter().map(|f| f.name.clone()).collect();
 
@@ -378,6 +407,11 @@ impl Compiler {
     ) -> Result<Class<'el>> {
         let mut spec = Class::new(body.ident.clone());
         spec.modifiers = vec![Modifier::Abstract, Modifier::Public];
+
+        if let Some(ref deprecated) = body.deprecated {
+            spec.attribute(Obsolete::new(deprecated));
+        }
+
         let interface_fields = self.fields(&body.fields)?;
 
         let type_field = match body.sub_type_strategy {
@@ -427,6 +461,10 @@ impl Compiler {
             let mut class = Class::new(sub_type.ident.clone());
             class.modifiers = vec![Modifier::Public];
 
+            if let Some(ref deprecated) = sub_type.deprecated {
+                class.attribute(Obsolete::new(deprecated));
+            }
+
             let sub_type_fields = self.fields(&sub_type.fields)?;
 
             class
@@ -477,6 +515,10 @@ impl Compiler {
 
     fn process_service<'el>(&self, body: &'el RpServiceBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.ident.as_str());
+
+        if let Some(ref deprecated) = body.deprecated {
+            spec.attribute(Obsolete::new(deprecated));
+        }
         spec.modifiers = vec![Modifier::Abstract, Modifier::Public];
 
         let mut extra: Vec<EndpointExtra> = Vec::new();
@@ -524,6 +566,10 @@ impl Compiler {
                     method.comments.push("</summary>".into());
                 }
 
+                if let Some(ref deprecated) = endpoint.deprecated {
+                    method.attribute(Obsolete::new(deprecated));
+                }
+
                 method.arguments.extend(arguments.iter().cloned());
 
                 method.returns = response_ty.clone();
@@ -567,6 +613,10 @@ impl Compiler {
             spec.comments.push("</summary>".into());
         }
 
+        if let Some(ref deprecated) = field.deprecated {
+            spec.attribute(Obsolete::new(deprecated));
+        }
+
         let mut block = Tokens::new();
 
         if self.options.build_getters {
@@ -644,6 +694,12 @@ impl Compiler {
 
                 container.push(spec);
             }
+            Union(ref ty) => {
+                warn!("not supported: {}", ty.name);
+            }
+            Newtype(ref ty) => {
+                warn!("not supported: {}", ty.name);
+            }
         }
 
         Ok(())
@@ -5,7 +5,7 @@
 use core::errors::Result;
 use core::{
     self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberKind,
-    RpNumberType, RpNumberValidate, RpStringType, Translate, Translator,
+    RpNumberType, RpNumberValidate, RpScalarType, RpStringType, Translate, Translator,
 };
 use genco::csharp::{self, array, struct_, using};
 use genco::{Cons, Csharp};
@@ -31,9 +31,14 @@ impl Flavor for CsharpFlavor {
 pub struct CsharpFlavorTranslator {
     packages: Rc<Packages>,
     list: Csharp<'static>,
+    set: Csharp<'static>,
     dictionary: Csharp<'static>,
     string: Csharp<'static>,
     date_time: Csharp<'static>,
+    date_only: Csharp<'static>,
+    time_only: Csharp<'static>,
+    big_integer: Csharp<'static>,
+    decimal: Csharp<'static>,
     object: Csharp<'static>,
     pub void: Csharp<'static>,
     to_upper_camel: naming::ToUpperCamel,
@@ -44,9 +49,14 @@ impl CsharpFlavorTranslator {
         Self {
             packages,
             list: using("System.Collections.Generic", "List"),
+            set: using("System.Collections.Generic", "HashSet"),
             dictionary: using("System.Collections.Generic", "Dictionary"),
             string: using("System", "String"),
             date_time: struct_(using("System", "DateTime")),
+            date_only: struct_(using("System", "DateOnly")),
+            time_only: struct_(using("System", "TimeOnly")),
+            big_integer: struct_(using("System.Numerics", "BigInteger")),
+            decimal: struct_(using("System", "Decimal")),
             object: using("System", "Object"),
             void: using("java.lang", "Void"),
             to_upper_camel: naming::to_upper_camel(),
@@ -66,6 +76,8 @@ impl FlavorTranslator for CsharpFlavorTranslator {
             RpNumberKind::I64 => Ok(csharp::INT64.into()),
             RpNumberKind::U32 => Ok(csharp::UINT32.into()),
             RpNumberKind::U64 => Ok(csharp::UINT64.into()),
+            RpNumberKind::U128 | RpNumberKind::I128 => Ok(self.big_integer.clone()),
+            RpNumberKind::Decimal => Ok(self.decimal.clone()),
         }
     }
 
@@ -89,10 +101,28 @@ impl FlavorTranslator for CsharpFlavorTranslator {
         Ok(self.date_time.clone())
     }
 
+    fn translate_date(&self) -> Result<Csharp<'static>> {
+        Ok(self.date_only.clone())
+    }
+
+    fn translate_time(&self) -> Result<Csharp<'static>> {
+        Ok(self.time_only.clone())
+    }
+
+    fn translate_duration(&self) -> Result<Csharp<'static>> {
+        // NB: `TimeSpan` is not encoded as an ISO-8601 duration, so the representation is kept
+        // as a string.
+        Ok(self.string.clone())
+    }
+
     fn translate_array(&self, inner: Csharp<'static>) -> Result<Csharp<'static>> {
         Ok(self.list.with_arguments(vec![inner]).into())
     }
 
+    fn translate_set(&self, inner: Csharp<'static>) -> Result<Csharp<'static>> {
+        Ok(self.set.with_arguments(vec![inner]).into())
+    }
+
     fn translate_map(
         &self,
         key: Csharp<'static>,
@@ -109,6 +139,18 @@ impl FlavorTranslator for CsharpFlavorTranslator {
         Ok(array(csharp::BYTE))
     }
 
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<Csharp<'static>> {
+        let ty = match scalar.mapping("csharp") {
+            Some(ty) => ty,
+            None => return self.translate_scalar_base(scalar.base),
+        };
+
+        match ty.rfind('.') {
+            Some(index) => Ok(using(ty[..index].to_string(), ty[index + 1..].to_string())),
+            None => Ok(csharp::local(ty.to_string())),
+        }
+    }
+
     fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<Csharp<'static>> {
         let package_name = Rc::new(name.package.join("."));
         let name = Rc::new(reg.ident(&name, |p| p.join("."), |c| c.join(".")));
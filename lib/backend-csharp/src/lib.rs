@@ -30,7 +30,7 @@ use codegen::Configure;
 use compiler::Compiler;
 use core::errors::Result;
 use core::{CoreFlavor, Handle};
-use manifest::{checked_modules, Lang, Manifest, NoModule, TryFromToml};
+use manifest::{checked_modules, Lang, Manifest, ModuleDoc, NoModule, TryFromToml};
 use options::Options;
 use std::any::Any;
 use std::path::Path;
@@ -40,9 +40,27 @@ use trans::Session;
 #[derive(Clone, Copy, Default, Debug)]
 pub struct CsharpLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "Json.NET",
+        description: "Annotate classes for serialization with Json.NET.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "System.Text.Json",
+        description: "Annotate classes for serialization with `System.Text.Json`.",
+        options: &[],
+    },
+];
+
 impl Lang for CsharpLang {
     lang_base!(CsharpModule, compile);
 
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+
     fn comment(&self, input: &str) -> Option<String> {
         Some(format!("// {}", input))
     }
@@ -55,92 +73,19 @@ impl Lang for CsharpLang {
         Some(Box::new(naming::to_upper_camel()))
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![
-            ("abstract", "_abstract"),
-            ("as", "_as"),
-            ("base", "_base"),
-            ("bool", "_bool"),
-            ("break", "_break"),
-            ("byte", "_byte"),
-            ("case", "_case"),
-            ("catch", "_catch"),
-            ("char", "_char"),
-            ("checked", "_checked"),
-            ("class", "_class"),
-            ("const", "_const"),
-            ("continue", "_continue"),
-            ("decimal", "_decimal"),
-            ("default", "_default"),
-            ("delegate", "_delegate"),
-            ("do", "_do"),
-            ("double", "_double"),
-            ("else", "_else"),
-            ("enum", "_enum"),
-            ("event", "_event"),
-            ("explicit", "_explicit"),
-            ("extern", "_extern"),
-            ("false", "_false"),
-            ("finally", "_finally"),
-            ("fixed", "_fixed"),
-            ("float", "_float"),
-            ("for", "_for"),
-            ("foreach", "_foreach"),
-            ("goto", "_goto"),
-            ("if", "_if"),
-            ("implicit", "_implicit"),
-            ("in", "_in"),
-            ("int", "_int"),
-            ("interface", "_interface"),
-            ("internal", "_internal"),
-            ("is", "_is"),
-            ("lock", "_lock"),
-            ("long", "_long"),
-            ("namespace", "_namespace"),
-            ("new", "_new"),
-            ("null", "_null"),
-            ("object", "_object"),
-            ("operator", "_operator"),
-            ("out", "_out"),
-            ("override", "_override"),
-            ("params", "_params"),
-            ("private", "_private"),
-            ("protected", "_protected"),
-            ("public", "_public"),
-            ("readonly", "_readonly"),
-            ("ref", "_ref"),
-            ("return", "_return"),
-            ("sbyte", "_sbyte"),
-            ("sealed", "_sealed"),
-            ("short", "_short"),
-            ("sizeof", "_sizeof"),
-            ("stackalloc", "_stackalloc"),
-            ("static", "_static"),
-            ("string", "_string"),
-            ("struct", "_struct"),
-            ("switch", "_switch"),
-            ("this", "_this"),
-            ("throw", "_throw"),
-            ("true", "_true"),
-            ("try", "_try"),
-            ("typeof", "_typeof"),
-            ("uint", "_uint"),
-            ("ulong", "_ulong"),
-            ("unchecked", "_unchecked"),
-            ("unsafe", "_unsafe"),
-            ("ushort", "_ushort"),
-            ("using", "_using"),
-            ("virtual", "_virtual"),
-            ("void", "_void"),
-            ("volatile", "_volatile"),
-            ("while", "_while"),
-        ]
+    fn keywords(&self) -> Option<&'static naming::Keywords> {
+        Some(&naming::keywords::CSHARP)
+    }
+
+    fn numbers_as_strings(&self) -> bool {
+        false
     }
 }
 
 #[derive(Debug)]
 pub enum CsharpModule {
     JsonNet,
+    SystemTextJson,
 }
 
 impl TryFromToml for CsharpModule {
@@ -149,6 +94,7 @@ impl TryFromToml for CsharpModule {
 
         let result = match id {
             "Json.NET" => JsonNet,
+            "System.Text.Json" => SystemTextJson,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -160,6 +106,7 @@ impl TryFromToml for CsharpModule {
 
         let result = match id {
             "Json.NET" => JsonNet,
+            "System.Text.Json" => SystemTextJson,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -179,6 +126,7 @@ fn setup_options<'a>(modules: Vec<CsharpModule>) -> Options {
 
         match module {
             JsonNet => module::JsonNet.initialize(c),
+            SystemTextJson => module::SystemTextJson.initialize(c),
         };
     }
 
@@ -1,3 +1,5 @@
 mod json_net;
+mod system_text_json;
 
 pub use self::json_net::Module as JsonNet;
+pub use self::system_text_json::Module as SystemTextJson;
@@ -0,0 +1,453 @@
+use codegen::{
+    ClassAdded, ClassCodegen, Configure, EnumAdded, EnumCodegen, InterfaceAdded, InterfaceCodegen,
+    TupleAdded, TupleCodegen, TypeField, TypeFieldAdded, TypeFieldCodegen,
+};
+use core::errors::Result;
+use core::{self, RpSubTypeStrategy};
+use flavored::RpInterfaceBody;
+use genco::csharp::{local, using, Argument, Class};
+use genco::{Cons, Csharp, IntoTokens, Quoted, Tokens};
+use std::rc::Rc;
+
+pub struct Module;
+
+impl Module {
+    pub fn initialize(self, e: Configure) {
+        let system_text_json = Rc::new(SystemTextJson::new());
+
+        e.options
+            .class_generators
+            .push(Box::new(Rc::clone(&system_text_json)));
+
+        e.options
+            .enum_generators
+            .push(Box::new(Rc::clone(&system_text_json)));
+
+        e.options
+            .interface_generators
+            .push(Box::new(Rc::clone(&system_text_json)));
+
+        e.options
+            .type_field_generators
+            .push(Box::new(Rc::clone(&system_text_json)));
+
+        e.options
+            .tuple_generators
+            .push(Box::new(Rc::clone(&system_text_json)));
+    }
+}
+
+/// Apply attributes and converters for System.Text.Json.
+struct SystemTextJson {
+    type_: Csharp<'static>,
+    json_exception: Csharp<'static>,
+    json_document: Csharp<'static>,
+    json_element: Csharp<'static>,
+    json_serializer: Csharp<'static>,
+    json_serializer_options: Csharp<'static>,
+    json_token_type: Csharp<'static>,
+    utf8_json_reader: Csharp<'static>,
+    utf8_json_writer: Csharp<'static>,
+}
+
+impl SystemTextJson {
+    pub fn new() -> Self {
+        Self {
+            type_: using("System", "Type").qualified(),
+            json_exception: using("System.Text.Json", "JsonException"),
+            json_document: using("System.Text.Json", "JsonDocument"),
+            json_element: using("System.Text.Json", "JsonElement"),
+            json_serializer: using("System.Text.Json", "JsonSerializer"),
+            json_serializer_options: using("System.Text.Json", "JsonSerializerOptions"),
+            json_token_type: using("System.Text.Json", "JsonTokenType"),
+            utf8_json_reader: using("System.Text.Json", "Utf8JsonReader"),
+            utf8_json_writer: using("System.Text.Json", "Utf8JsonWriter"),
+        }
+    }
+
+    /// Build a nested converter class for the given type.
+    fn converter<'el>(
+        &self,
+        spec: &mut Class<'el>,
+        read: Tokens<'el, Csharp<'el>>,
+        write: Tokens<'el, Csharp<'el>>,
+    ) {
+        let converter = Rc::new(format!("{}.System_Text_Json_Converter", spec.name().as_ref()));
+        spec.attribute(JsonConverter(local(converter)));
+
+        let ty = local(spec.name());
+
+        let mut c = Class::new("System_Text_Json_Converter");
+        c.implements = vec![
+            using("System.Text.Json.Serialization", "JsonConverter")
+                .with_arguments(vec![ty.clone()]),
+        ];
+
+        c.body.push_into(|t| {
+            push!(
+                t,
+                "public override ",
+                ty,
+                " Read(ref ",
+                self.utf8_json_reader,
+                " reader, ",
+                self.type_,
+                " typeToConvert, ",
+                self.json_serializer_options,
+                " options) {"
+            );
+            t.nested(read);
+            push!(t, "}");
+        });
+
+        c.body.push_into(|t| {
+            push!(
+                t,
+                "public override void Write(",
+                self.utf8_json_writer,
+                " writer, ",
+                ty,
+                " value, ",
+                self.json_serializer_options,
+                " options) {"
+            );
+            t.nested(write);
+            push!(t, "}");
+        });
+
+        spec.body.push(c);
+    }
+
+    /// Throw a `JsonException` with the given message.
+    fn throw<'el>(&self, message: &'el str) -> Tokens<'el, Csharp<'el>> {
+        toks!["throw new ", self.json_exception.clone(), "(", message.quoted(), ");"]
+    }
+
+    fn tagged<'el>(
+        &self,
+        spec: &mut Class<'el>,
+        body: &'el RpInterfaceBody,
+        tag: &'el str,
+    ) -> Result<()> {
+        let mut read = Tokens::new();
+
+        read.push_into(|t| {
+            push!(
+                t,
+                "using (",
+                self.json_document,
+                " document = ",
+                self.json_document,
+                ".ParseValue(ref reader)) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, self.json_element, " root = document.RootElement;");
+                push!(t, self.json_element, " tag;");
+
+                t.push_into(|t| {
+                    push!(t, "if (!root.TryGetProperty(", tag.quoted(), ", out tag)) {");
+                    t.nested(self.throw("missing tag"));
+                    push!(t, "}");
+                });
+
+                t.push_into(|t| {
+                    push!(t, "switch (tag.GetString()) {");
+
+                    for sub_type in &body.sub_types {
+                        let ty = toks![spec.name(), ".", sub_type.ident.as_str()];
+
+                        nested!(t, "case ", sub_type.name().quoted(), ":");
+
+                        t.nested_into(|t| {
+                            nested!(
+                                t,
+                                "return ",
+                                self.json_serializer,
+                                ".Deserialize<",
+                                ty,
+                                ">(root.GetRawText(), options);"
+                            );
+                        });
+                    }
+
+                    nested!(t, "default:");
+                    t.nested_into(|t| {
+                        t.nested(self.throw("bad tag"));
+                    });
+
+                    push!(t, "}");
+                });
+            });
+
+            push!(t, "}");
+        });
+
+        let write = self.write_sub_type();
+        self.converter(spec, read, write);
+        Ok(())
+    }
+
+    fn untagged<'el>(&self, spec: &mut Class<'el>, body: &'el RpInterfaceBody) -> Result<()> {
+        let mut read = Tokens::new();
+
+        read.push_into(|t| {
+            push!(
+                t,
+                "using (",
+                self.json_document,
+                " document = ",
+                self.json_document,
+                ".ParseValue(ref reader)) {"
+            );
+
+            let mut inner = Tokens::new();
+            push!(inner, self.json_element, " root = document.RootElement;");
+
+            for sub_type in &body.sub_types {
+                let ty = toks![spec.name(), ".", sub_type.ident.as_str()];
+                let mut checks = Tokens::new();
+
+                for f in sub_type.discriminating_fields() {
+                    checks.append(toks!["root.TryGetProperty(", f.name().quoted(), ", out _)"]);
+                }
+
+                inner.push_into(|t| {
+                    push!(t, "if (", checks.join(" && "), ") {");
+                    nested!(
+                        t,
+                        "return ",
+                        self.json_serializer,
+                        ".Deserialize<",
+                        ty,
+                        ">(root.GetRawText(), options);"
+                    );
+                    push!(t, "}");
+                });
+            }
+
+            inner.push(self.throw("no legal combination of fields"));
+            t.nested(inner.join_line_spacing());
+
+            push!(t, "}");
+        });
+
+        let write = self.write_sub_type();
+        self.converter(spec, read, write);
+        Ok(())
+    }
+
+    /// Sub types are serialized using their runtime type, which does not carry a converter.
+    fn write_sub_type<'el>(&self) -> Tokens<'el, Csharp<'el>> {
+        toks![
+            self.json_serializer.clone(),
+            ".Serialize(writer, value, value.GetType(), options);",
+        ]
+    }
+}
+
+impl ClassCodegen for SystemTextJson {
+    fn generate(&self, e: ClassAdded) -> Result<()> {
+        let mut type_field = e.type_field;
+        let names = &e.names;
+        let spec = e.spec;
+        let fields = e.fields;
+
+        // Annotate all constructors.
+        for c in &mut spec.constructors {
+            c.attribute(JsonConstructor);
+
+            // Modify the class to deserialize, and pass type field into the super class.
+            if let Some(&mut TypeField { ref mut field, .. }) = type_field.as_mut() {
+                c.arguments
+                    .insert(0, Argument::new(field.ty(), field.var()));
+                c.base = Some(toks!["base(", field.var(), ")"]);
+            }
+        }
+
+        // Add field attributes.
+        for (field, (spec, name)) in fields.iter().zip(spec.fields.iter_mut().zip(names.iter())) {
+            spec.attribute(JsonPropertyName(name.clone()));
+
+            if field.optional {
+                spec.attribute(JsonIgnoreWhenWritingNull);
+            } else {
+                spec.attribute(JsonRequired);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl EnumCodegen for SystemTextJson {
+    fn generate(&self, e: EnumAdded) -> Result<()> {
+        let EnumAdded {
+            body, spec, names, ..
+        } = e;
+
+        match body.variants {
+            core::RpVariants::String { .. } => {
+                spec.attribute(JsonConverter(using(
+                    "System.Text.Json.Serialization",
+                    "JsonStringEnumConverter",
+                )));
+
+                if let Some(names) = names {
+                    let mut variants = Tokens::new();
+
+                    for (v, name) in spec.variants.clone().into_iter().zip(names.iter().cloned()) {
+                        let mut annotated = Tokens::new();
+                        annotated.push(JsonStringEnumMemberName(name));
+                        annotated.push(v);
+                        variants.push(annotated);
+                    }
+
+                    spec.variants = variants;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl InterfaceCodegen for SystemTextJson {
+    fn generate(&self, InterfaceAdded { mut spec, body, .. }: InterfaceAdded) -> Result<()> {
+        match body.sub_type_strategy {
+            RpSubTypeStrategy::Tagged { ref tag, .. } => self.tagged(&mut spec, body, tag),
+            RpSubTypeStrategy::Untagged => self.untagged(&mut spec, body),
+        }
+    }
+}
+
+impl TypeFieldCodegen for SystemTextJson {
+    fn generate(&self, TypeFieldAdded { field, tag }: TypeFieldAdded) -> Result<()> {
+        field.attribute(JsonPropertyName(tag.clone()));
+        Ok(())
+    }
+}
+
+impl TupleCodegen for SystemTextJson {
+    fn generate(&self, TupleAdded { spec }: TupleAdded) -> Result<()> {
+        let cls = local(spec.name());
+
+        let mut read = Tokens::new();
+
+        read.push_into(|t| {
+            push!(t, "if (reader.TokenType != ", self.json_token_type, ".StartArray) {");
+            t.nested(self.throw("expected array"));
+            push!(t, "}");
+        });
+
+        let mut args = Tokens::new();
+
+        for f in &spec.fields {
+            read.push_into(|t| {
+                push!(t, "if (!reader.Read() || reader.TokenType == ", self.json_token_type, ".EndArray) {");
+                t.nested(self.throw("expected more items in array"));
+                push!(t, "}");
+            });
+
+            read.push(toks![
+                f.ty(),
+                " ",
+                f.var(),
+                " = ",
+                self.json_serializer.clone(),
+                ".Deserialize<",
+                f.ty(),
+                ">(ref reader, options);",
+            ]);
+
+            args.append(f.var());
+        }
+
+        read.push_into(|t| {
+            push!(t, "if (!reader.Read() || reader.TokenType != ", self.json_token_type, ".EndArray) {");
+            t.nested(self.throw("expected end of array"));
+            push!(t, "}");
+        });
+
+        read.push(toks!["return new ", cls.clone(), "(", args.join(", "), ");"]);
+
+        let mut write = Tokens::new();
+        write.push("writer.WriteStartArray();");
+
+        for f in &spec.fields {
+            write.push(toks![
+                self.json_serializer.clone(),
+                ".Serialize(writer, value.",
+                f.var(),
+                ", options);",
+            ]);
+        }
+
+        write.push("writer.WriteEndArray();");
+
+        self.converter(spec, read, write);
+        Ok(())
+    }
+}
+
+/// [JsonPropertyName(..)] attribute
+pub struct JsonPropertyName<'el>(Cons<'el>);
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for JsonPropertyName<'el> {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let property_name = using("System.Text.Json.Serialization", "JsonPropertyName");
+        toks!["[", property_name, "(", self.0.quoted(), ")]"]
+    }
+}
+
+/// [JsonRequired] attribute
+pub struct JsonRequired;
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for JsonRequired {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let required = using("System.Text.Json.Serialization", "JsonRequired");
+        toks!["[", required, "]"]
+    }
+}
+
+/// [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)] attribute
+pub struct JsonIgnoreWhenWritingNull;
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for JsonIgnoreWhenWritingNull {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let ignore = using("System.Text.Json.Serialization", "JsonIgnore");
+        let condition = using("System.Text.Json.Serialization", "JsonIgnoreCondition");
+        toks!["[", ignore, "(Condition = ", condition, ".WhenWritingNull)]"]
+    }
+}
+
+/// [JsonConstructor] attribute
+pub struct JsonConstructor;
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for JsonConstructor {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let constructor = using("System.Text.Json.Serialization", "JsonConstructor");
+        toks!["[", constructor, "]"]
+    }
+}
+
+/// [JsonStringEnumMemberName(..)] attribute
+pub struct JsonStringEnumMemberName<'el>(Cons<'el>);
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for JsonStringEnumMemberName<'el> {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let member_name = using("System.Text.Json.Serialization", "JsonStringEnumMemberName");
+        toks!["[", member_name, "(", self.0.quoted(), ")]"]
+    }
+}
+
+/// [JsonConverter(..)] attribute
+pub struct JsonConverter<'el>(Csharp<'el>);
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for JsonConverter<'el> {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let converter = using("System.Text.Json.Serialization", "JsonConverter");
+        toks!["[", converter, "(typeof(", self.0, "))]"]
+    }
+}
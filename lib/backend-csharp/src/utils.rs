@@ -1,5 +1,7 @@
+use core::RpDeprecated;
 use genco::csharp::using;
 use genco::{Cons, Csharp, IntoTokens, Quoted, Tokens};
+use std::rc::Rc;
 
 /// [DataMember(..)] attribute
 #[allow(unused)]
@@ -33,3 +35,24 @@ impl<'el> IntoTokens<'el, Csharp<'el>> for DataMember<'el> {
         toks!["[", data_member, "(", args.join(", "), ")]"]
     }
 }
+
+/// [Obsolete(..)] attribute
+pub struct Obsolete(Option<Rc<String>>);
+
+impl Obsolete {
+    /// Create a new `Obsolete` attribute from a deprecation.
+    pub fn new(deprecated: &RpDeprecated) -> Obsolete {
+        Obsolete(deprecated.note.clone().map(Rc::new))
+    }
+}
+
+impl<'el> IntoTokens<'el, Csharp<'el>> for Obsolete {
+    fn into_tokens(self) -> Tokens<'el, Csharp<'el>> {
+        let obsolete = using("System", "Obsolete");
+
+        match self.0 {
+            Some(note) => toks!["[", obsolete, "(", note.quoted(), ")]"],
+            None => toks!["[", obsolete, "]"],
+        }
+    }
+}
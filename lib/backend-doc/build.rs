@@ -158,5 +158,6 @@ fn main() {
     println!("cargo:rerun-if-changed=themes.toml");
     println!("cargo:rerun-if-changed=src/static/doc._.css.hbs");
     println!("cargo:rerun-if-changed=src/static/normalize.css");
+    println!("cargo:rerun-if-changed=src/static/search.js");
     process_colors().unwrap();
 }
@@ -0,0 +1,50 @@
+//! Processor for the package dependency graph.
+
+use core::errors::*;
+use dependency_graph::DependencyGraph;
+use doc_builder::DocBuilder;
+use macros::FormatAttribute;
+use processor::Processor;
+
+/// Highlights the dependencies of a package when hovering over it.
+const HIGHLIGHT_JS: &str = "(function() {
+  var graph = document.currentScript.previousElementSibling;
+  var edges = graph.querySelectorAll('.dependency-edge');
+  graph.querySelectorAll('.dependency-node').forEach(function(node) {
+    var p = node.getAttribute('data-package');
+    node.addEventListener('mouseenter', function() {
+      graph.classList.add('highlight');
+      edges.forEach(function(e) {
+        if (e.getAttribute('data-from') === p || e.getAttribute('data-to') === p) {
+          e.classList.add('active');
+        }
+      });
+    });
+    node.addEventListener('mouseleave', function() {
+      graph.classList.remove('highlight');
+      edges.forEach(function(e) { e.classList.remove('active'); });
+    });
+  });
+})();";
+
+pub struct Data {
+    pub graph: DependencyGraph,
+}
+
+define_processor!(DependenciesProcessor, Data, self,
+    process => {
+        self.write_doc(|| {
+            html!(self, section {id => "dependencies", class => "section-content"} => {
+                html!(self, h1 {class => "section-title"} ~ "Dependencies");
+
+                let svg = self.body.graph.to_svg(|package| self.package_url(package))?;
+                self.out().write_str(&svg)?;
+                html!(self, script {} ~ HIGHLIGHT_JS);
+            });
+
+            Ok(())
+        })
+    };
+);
+
+impl<'session> DependenciesProcessor<'session> {}
@@ -0,0 +1,238 @@
+//! Graph of dependencies between packages.
+//!
+//! A package depends on another package if any of its declarations refer to a type in it.
+
+use core::errors::*;
+use core::flavored::{RpDecl, RpField, RpType, RpVersionedPackage};
+use core::{CoreFlavor, Loc};
+use escape::Escape;
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64::consts::PI;
+use std::fmt::Write;
+use trans::Translated;
+
+/// Radius of a single node.
+const NODE_RADIUS: f64 = 8.0;
+/// Space reserved around the graph for labels.
+const MARGIN: f64 = 160.0;
+
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// All packages, and the packages that they depend on.
+    packages: BTreeMap<RpVersionedPackage, BTreeSet<RpVersionedPackage>>,
+}
+
+impl DependencyGraph {
+    /// Build the dependency graph for all packages in the session.
+    pub fn build(session: &Translated<CoreFlavor>) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+
+        for (package, file) in session.for_each_file() {
+            graph
+                .packages
+                .entry(package.clone())
+                .or_insert_with(BTreeSet::new);
+
+            for decl in file.for_each_decl() {
+                graph.decl(package, decl);
+            }
+        }
+
+        graph
+    }
+
+    /// Render the graph in the DOT language.
+    pub fn to_dot(&self) -> Result<String> {
+        let mut out = String::new();
+
+        writeln!(out, "digraph dependencies {{")?;
+
+        for package in self.packages.keys() {
+            writeln!(out, "  {};", dot_id(package))?;
+        }
+
+        for (package, dependencies) in &self.packages {
+            for dependency in dependencies {
+                writeln!(out, "  {} -> {};", dot_id(package), dot_id(dependency))?;
+            }
+        }
+
+        writeln!(out, "}}")?;
+        Ok(out)
+    }
+
+    /// Render the graph as an SVG image.
+    ///
+    /// Packages are laid out in a circle, and link to the URL provided by `package_url`.
+    pub fn to_svg<U>(&self, package_url: U) -> Result<String>
+    where
+        U: Fn(&RpVersionedPackage) -> String,
+    {
+        let count = self.packages.len();
+        let radius = f64::max(100.0, count as f64 * 20.0);
+        let size = (radius + MARGIN) * 2.0;
+
+        let positions = self
+            .packages
+            .keys()
+            .enumerate()
+            .map(|(i, package)| {
+                let angle = 2.0 * PI * i as f64 / count as f64 - PI / 2.0;
+                let x = size / 2.0 + radius * angle.cos();
+                let y = size / 2.0 + radius * angle.sin();
+                (package, (x, y))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut out = String::new();
+
+        writeln!(
+            out,
+            "<svg class=\"dependency-graph\" xmlns=\"http://www.w3.org/2000/svg\" \
+             width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">",
+            size = size
+        )?;
+
+        writeln!(out, "<defs>")?;
+        writeln!(
+            out,
+            "<marker id=\"dependency-arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+             markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">"
+        )?;
+        writeln!(out, "<path d=\"M 0 0 L 10 5 L 0 10 z\"></path>")?;
+        writeln!(out, "</marker>")?;
+        writeln!(out, "</defs>")?;
+
+        for (package, dependencies) in &self.packages {
+            let (x1, y1) = positions[package];
+
+            for dependency in dependencies {
+                let (x2, y2) = positions[dependency];
+
+                // stop edges at the border of the nodes, so that the arrows are visible.
+                let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+                let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+
+                writeln!(
+                    out,
+                    "<line class=\"dependency-edge\" data-from=\"{}\" data-to=\"{}\" \
+                     x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" \
+                     marker-end=\"url(#dependency-arrow)\"></line>",
+                    Escape(package.to_string().as_str()),
+                    Escape(dependency.to_string().as_str()),
+                    x1 + dx * NODE_RADIUS,
+                    y1 + dy * NODE_RADIUS,
+                    x2 - dx * NODE_RADIUS,
+                    y2 - dy * NODE_RADIUS,
+                )?;
+            }
+        }
+
+        for (package, &(x, y)) in &positions {
+            let name = package.to_string();
+
+            // labels are placed on the outside of the circle.
+            let anchor = if x < size / 2.0 { "end" } else { "start" };
+            let offset = if x < size / 2.0 { -12.0 } else { 12.0 };
+
+            writeln!(
+                out,
+                "<a class=\"dependency-node\" data-package=\"{}\" href=\"{}\">",
+                Escape(name.as_str()),
+                Escape(package_url(package).as_str())
+            )?;
+            writeln!(
+                out,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\"></circle>",
+                x, y, NODE_RADIUS
+            )?;
+            writeln!(
+                out,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\">{}</text>",
+                x + offset,
+                y + 4.0,
+                anchor,
+                Escape(name.as_str())
+            )?;
+            writeln!(out, "</a>")?;
+        }
+
+        writeln!(out, "</svg>")?;
+        Ok(out)
+    }
+
+    /// Register the dependencies of a single declaration.
+    fn decl(&mut self, package: &RpVersionedPackage, decl: &RpDecl) {
+        use core::RpDecl::*;
+
+        match *decl {
+            Type(ref body) => self.fields(package, &body.fields),
+            Tuple(ref body) => self.fields(package, &body.fields),
+            Interface(ref body) => {
+                self.fields(package, &body.fields);
+
+                for sub_type in &body.sub_types {
+                    self.fields(package, &sub_type.fields);
+                }
+            }
+            Union(ref body) => {
+                for variant in &body.variants {
+                    self.ty(package, &variant.ty);
+                }
+            }
+            Newtype(ref body) => {
+                self.ty(package, &body.ty);
+            }
+            Service(ref body) => {
+                for endpoint in &body.endpoints {
+                    for argument in &endpoint.arguments {
+                        self.ty(package, argument.channel.ty());
+                    }
+
+                    if let Some(ref response) = endpoint.response {
+                        self.ty(package, response.ty());
+                    }
+                }
+            }
+            Enum(..) => {}
+        }
+    }
+
+    fn fields(&mut self, package: &RpVersionedPackage, fields: &[Loc<RpField>]) {
+        for field in fields {
+            self.ty(package, &field.ty);
+        }
+    }
+
+    fn ty(&mut self, package: &RpVersionedPackage, ty: &RpType) {
+        use core::RpType::*;
+
+        match *ty {
+            Name { ref name } => {
+                if name.package == *package {
+                    return;
+                }
+
+                self.packages
+                    .entry(name.package.clone())
+                    .or_insert_with(BTreeSet::new);
+
+                self.packages
+                    .entry(package.clone())
+                    .or_insert_with(BTreeSet::new)
+                    .insert(name.package.clone());
+            }
+            Array { ref inner } | Set { ref inner } => self.ty(package, inner),
+            Map { ref key, ref value } => {
+                self.ty(package, key);
+                self.ty(package, value);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Quote a package for use as an identifier in the DOT language.
+fn dot_id(package: &RpVersionedPackage) -> String {
+    format!("\"{}\"", package.to_string().replace('"', "\\\""))
+}
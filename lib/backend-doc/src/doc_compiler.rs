@@ -1,56 +1,91 @@
 //! Compiler for generating documentation.
 
-use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME};
+use super::{
+    DEPENDENCIES_NAME, DOC_CSS_NAME, NORMALIZE_CSS_NAME, SEARCH_INDEX_NAME, SEARCH_JS_NAME,
+};
 use core::errors::*;
 use core::flavored::{RpDecl, RpFile, RpVersionedPackage};
 use core::{AsPackage, CoreFlavor};
+use dependencies_processor::{Data as DependenciesData, DependenciesProcessor};
+use dependency_graph::DependencyGraph;
 use doc_builder::DocBuilder;
 use enum_processor::EnumProcessor;
 use genco::IoFmt;
 use index_processor::{Data as IndexData, IndexProcessor};
 use interface_processor::InterfaceProcessor;
+use newtype_processor::NewtypeProcessor;
 use package_processor::{Data as PackageData, PackageProcessor};
-use processor::Processor;
+use processor::{Embedded, Processor};
+use search_index::SearchIndex;
 use service_processor::ServiceProcessor;
+use single_page_processor::{Data as SinglePageData, SinglePageProcessor};
+use source_snippet::{SourceUrl, Sources};
 use std::cell::RefCell;
+use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
+use std::str;
 use syntect::highlighting::Theme;
 use syntect::parsing::SyntaxSet;
+use theme_dir::ThemeDir;
 use trans::Translated;
 use tuple_processor::TupleProcessor;
 use type_processor::TypeProcessor;
+use union_processor::UnionProcessor;
 
 const NORMALIZE_CSS: &[u8] = include_bytes!("static/normalize.css");
+const SEARCH_JS: &[u8] = include_bytes!("static/search.js");
 
 pub struct DocCompiler<'a> {
     pub session: Translated<CoreFlavor>,
     pub out_path: PathBuf,
     pub skip_static: bool,
+    pub single_page: bool,
     pub theme_css: &'a [u8],
     pub syntax_theme: &'a Theme,
     pub syntax_set: &'a SyntaxSet,
+    pub theme_dir: &'a ThemeDir,
+    pub sources: Sources,
+    pub source_url: Option<&'a SourceUrl>,
 }
 
 impl<'a> DocCompiler<'a> {
     /// Do the compilation.
     pub fn compile(&self) -> Result<()> {
-        for (_, file) in self.session.for_each_file() {
-            for decl in file.for_each_decl() {
-                self.process_decl(decl)?;
+        if self.single_page {
+            self.write_single_page()?;
+        } else {
+            for (_, file) in self.session.for_each_file() {
+                for decl in file.for_each_decl() {
+                    self.process_decl(decl)?;
+                }
+            }
+
+            self.write_index(self.session.for_each_file())?;
+
+            for (package, file) in self.session.for_each_file() {
+                self.write_package(package, file)?;
             }
+
+            self.write_dependencies()?;
         }
 
-        self.write_index(self.session.for_each_file())?;
+        self.write_dependencies_dot()?;
 
-        for (package, file) in self.session.for_each_file() {
-            self.write_package(package, file)?;
+        // NB: stylesheets, scripts, and the search index are embedded in single page documentation.
+        if !self.single_page {
+            self.write_search_index()?;
         }
 
         if !self.skip_static {
-            self.write_stylesheets()?;
+            if !self.single_page {
+                self.write_stylesheets()?;
+                self.write_scripts()?;
+            }
+
+            self.theme_dir.write_static(&self.out_path)?;
         }
 
         Ok(())
@@ -58,8 +93,6 @@ impl<'a> DocCompiler<'a> {
 
     /// Process a single declaration.
     fn process_decl(&self, decl: &RpDecl) -> Result<()> {
-        use core::RpDecl::*;
-
         let package = decl.name().package.try_as_package()?;
 
         // maintain to know where to import static resources from.
@@ -84,43 +117,91 @@ impl<'a> DocCompiler<'a> {
         let out = path.join(format!("{}.{}.html", decl.kind(), name));
         debug!("+file: {}", out.display());
         let mut f = File::create(&out)?;
-        let mut fmt = IoFmt(&mut f);
-        let out = RefCell::new(DocBuilder::new(&mut fmt));
+        self.render_decl(&mut IoFmt(&mut f), &root, decl)
+    }
+
+    /// Render a single declaration.
+    fn render_decl(&self, fmt: &mut fmt::Write, root: &str, decl: &RpDecl) -> Result<()> {
+        use core::RpDecl::*;
+
+        let out = RefCell::new(DocBuilder::new(fmt));
 
         match *decl {
             Interface(ref body) => InterfaceProcessor {
                 out: out,
                 session: &self.session,
                 syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
                 body: body,
             }.process(),
             Type(ref body) => TypeProcessor {
                 out: out,
                 session: &self.session,
                 syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
                 body: body,
             }.process(),
             Tuple(ref body) => TupleProcessor {
                 out: out,
                 session: &self.session,
                 syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
                 body: body,
             }.process(),
             Enum(ref body) => EnumProcessor {
                 out: out,
                 session: &self.session,
                 syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
                 body: body,
             }.process(),
             Service(ref body) => ServiceProcessor {
                 out: out,
                 session: &self.session,
                 syntax: (self.syntax_theme, self.syntax_set),
-                root: &root,
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
+                body: body,
+            }.process(),
+            Union(ref body) => UnionProcessor {
+                out: out,
+                session: &self.session,
+                syntax: (self.syntax_theme, self.syntax_set),
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
+                body: body,
+            }.process(),
+            Newtype(ref body) => NewtypeProcessor {
+                out: out,
+                session: &self.session,
+                syntax: (self.syntax_theme, self.syntax_set),
+                root: root,
+                single_page: self.single_page,
+                theme_dir: self.theme_dir,
+                sources: &self.sources,
+                source_url: self.source_url,
                 body: body,
             }.process(),
         }
@@ -148,6 +229,83 @@ impl<'a> DocCompiler<'a> {
         Ok(())
     }
 
+    /// Write scripts.
+    fn write_scripts(&self) -> Result<()> {
+        if !self.out_path.is_dir() {
+            debug!("+dir: {}", self.out_path.display());
+            fs::create_dir_all(&self.out_path)?;
+        }
+
+        let search_js = self.out_path.join(SEARCH_JS_NAME);
+
+        debug!("+js: {}", search_js.display());
+        let mut f = fs::File::create(search_js)?;
+        f.write_all(SEARCH_JS)?;
+
+        Ok(())
+    }
+
+    /// Write the search index of all declarations.
+    fn write_search_index(&self) -> Result<()> {
+        if !self.out_path.is_dir() {
+            debug!("+dir: {}", self.out_path.display());
+            fs::create_dir_all(&self.out_path)?;
+        }
+
+        let index = SearchIndex::build(&self.session, self.single_page)?;
+        let search_index = self.out_path.join(SEARCH_INDEX_NAME);
+
+        debug!("+json: {}", search_index.display());
+        let mut f = fs::File::create(search_index)?;
+        f.write_all(index.to_json()?.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Write the graph of dependencies between packages in the DOT language.
+    fn write_dependencies_dot(&self) -> Result<()> {
+        if !self.out_path.is_dir() {
+            debug!("+dir: {}", self.out_path.display());
+            fs::create_dir_all(&self.out_path)?;
+        }
+
+        let graph = DependencyGraph::build(&self.session);
+        let dependencies_dot = self.out_path.join(format!("{}.dot", DEPENDENCIES_NAME));
+
+        debug!("+dot: {}", dependencies_dot.display());
+        let mut f = fs::File::create(dependencies_dot)?;
+        f.write_all(graph.to_dot()?.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Write the page visualizing the dependencies between packages.
+    fn write_dependencies(&self) -> Result<()> {
+        let dependencies_html = self.out_path.join(format!("{}.html", DEPENDENCIES_NAME));
+        let mut f = File::create(&dependencies_html)?;
+        self.render_dependencies(&mut IoFmt(&mut f))?;
+
+        debug!("+file: {}", dependencies_html.display());
+        Ok(())
+    }
+
+    /// Render the dependencies between packages.
+    fn render_dependencies(&self, fmt: &mut fmt::Write) -> Result<()> {
+        DependenciesProcessor {
+            out: RefCell::new(DocBuilder::new(fmt)),
+            session: &self.session,
+            syntax: (self.syntax_theme, self.syntax_set),
+            root: &".",
+            single_page: self.single_page,
+            theme_dir: self.theme_dir,
+            sources: &self.sources,
+            source_url: self.source_url,
+            body: &DependenciesData {
+                graph: DependencyGraph::build(&self.session),
+            },
+        }.process()
+    }
+
     /// Write the package index file index file.
     fn write_package(&self, package: &RpVersionedPackage, file: &RpFile) -> Result<()> {
         let mut path = self.out_path.to_owned();
@@ -161,20 +319,34 @@ impl<'a> DocCompiler<'a> {
 
         let index_html = path.join("index.html");
         let mut f = File::create(&index_html)?;
+        self.render_package(&mut IoFmt(&mut f), &root.join("/"), package, file)?;
+
+        debug!("+file: {}", index_html.display());
+        Ok(())
+    }
 
+    /// Render the package index.
+    fn render_package(
+        &self,
+        fmt: &mut fmt::Write,
+        root: &str,
+        package: &RpVersionedPackage,
+        file: &RpFile,
+    ) -> Result<()> {
         PackageProcessor {
-            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            out: RefCell::new(DocBuilder::new(fmt)),
             session: &self.session,
             syntax: (self.syntax_theme, self.syntax_set),
-            root: &root.join("/"),
+            root: root,
+            single_page: self.single_page,
+            theme_dir: self.theme_dir,
+            sources: &self.sources,
+            source_url: self.source_url,
             body: &PackageData {
                 package: package,
                 file: file,
             },
-        }.process()?;
-
-        debug!("+file: {}", index_html.display());
-        Ok(())
+        }.process()
     }
 
     /// Write the root index file.
@@ -184,15 +356,88 @@ impl<'a> DocCompiler<'a> {
     {
         let index_html = self.out_path.join("index.html");
         let mut f = File::create(&index_html)?;
+        self.render_index(&mut IoFmt(&mut f), entries)?;
 
+        debug!("+file: {}", index_html.display());
+        Ok(())
+    }
+
+    /// Render the root index.
+    fn render_index<'it, I>(&self, fmt: &mut fmt::Write, entries: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (&'it RpVersionedPackage, &'it RpFile)>,
+    {
         let entries = entries.into_iter().collect();
 
         IndexProcessor {
-            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            out: RefCell::new(DocBuilder::new(fmt)),
             session: &self.session,
             syntax: (self.syntax_theme, self.syntax_set),
             root: &".",
+            single_page: self.single_page,
+            theme_dir: self.theme_dir,
+            sources: &self.sources,
+            source_url: self.source_url,
             body: &IndexData { entries: entries },
+        }.process()
+    }
+
+    /// Write all documentation into a single, self-contained index file.
+    fn write_single_page(&self) -> Result<()> {
+        if !self.out_path.is_dir() {
+            debug!("+dir: {}", self.out_path.display());
+            fs::create_dir_all(&self.out_path)?;
+        }
+
+        let mut sections = Vec::new();
+
+        let mut section = String::new();
+        self.render_index(&mut section, self.session.for_each_file())?;
+        sections.push(section);
+
+        let mut section = String::new();
+        self.render_dependencies(&mut section)?;
+        sections.push(section);
+
+        for (package, file) in self.session.for_each_file() {
+            let mut section = String::new();
+            self.render_package(&mut section, ".", package, file)?;
+            sections.push(section);
+
+            for decl in file.for_each_decl() {
+                let mut section = String::new();
+                self.render_decl(&mut section, ".", decl)?;
+                sections.push(section);
+            }
+        }
+
+        let mut css = String::new();
+        css.push_str(str::from_utf8(NORMALIZE_CSS)?);
+        css.push_str(str::from_utf8(self.theme_css)?);
+
+        let embedded = Embedded {
+            css: css,
+            search_js: str::from_utf8(SEARCH_JS)?.to_string(),
+            search_index: SearchIndex::build(&self.session, self.single_page)?
+                .to_embedded_json()?,
+        };
+
+        let index_html = self.out_path.join("index.html");
+        let mut f = File::create(&index_html)?;
+
+        SinglePageProcessor {
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            session: &self.session,
+            syntax: (self.syntax_theme, self.syntax_set),
+            root: &".",
+            single_page: self.single_page,
+            theme_dir: self.theme_dir,
+            sources: &self.sources,
+            source_url: self.source_url,
+            body: &SinglePageData {
+                sections: sections,
+                embedded: embedded,
+            },
         }.process()?;
 
         debug!("+file: {}", index_html.display());
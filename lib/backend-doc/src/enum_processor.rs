@@ -11,11 +11,13 @@ use processor::Processor;
 define_processor!(EnumProcessor, RpEnumBody, self,
     process => {
         self.write_doc(|| {
-            let id = self.body.name.join("_");
+            let id = self.anchor(&self.body.name);
 
             html!(self, section {id => &id, class => "section-content section-enum"} => {
                 self.section_title("enum", &self.body.name)?;
+                self.deprecated(self.body.deprecated.as_ref())?;
                 self.doc(&self.body.comment)?;
+                self.source(&self.body.name)?;
                 self.variants(self.body.variants.iter())?;
                 self.nested_decls(self.body.decls.iter())?;
             });
@@ -39,28 +41,39 @@ impl<'p> EnumProcessor<'p> {
         }
 
         for variant in it {
-            let id = variant.name.join("_");
+            let id = self.anchor(&variant.name);
 
             html!(self, h3 {id => id} => {
                 html!(self, span {class => "kind"} ~ "variant");
                 self.full_name_without_package(&variant.name)?;
                 html!(self, span {class => "keyword"} ~ "as");
 
-                match variant.value {
-                    core::RpVariantValue::String(string) => {
-                        html!(self, span {class => "variant-ordinal"} ~
-                            Escape(format!("\"{}\"", string).as_str()));
-                    }
-                    core::RpVariantValue::Number(number) => {
-                        html!(self, span {class => "variant-ordinal"} ~
-                            Escape(number.to_string().as_str()));
-                    }
+                self.variant_value("variant-ordinal", variant.value)?;
+
+                for alias in variant.aliases.iter() {
+                    self.variant_value("variant-alias", alias)?;
                 }
             });
 
+            self.deprecated(variant.deprecated.as_ref())?;
             self.doc(variant.comment)?;
         }
 
         Ok(())
     }
+
+    fn variant_value(&self, class: &str, value: core::RpVariantValue) -> Result<()> {
+        match value {
+            core::RpVariantValue::String(string) => {
+                html!(self, span {class => class} ~
+                    Escape(format!("\"{}\"", string).as_str()));
+            }
+            core::RpVariantValue::Number(number) => {
+                html!(self, span {class => class} ~
+                    Escape(number.to_string().as_str()));
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,54 @@
+//! Output formats supported by the documentation backend.
+
+use core::errors::*;
+use std::fmt;
+
+pub const DEFAULT_FORMAT: Format = Format::Html;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One HTML file per declaration and package.
+    Html,
+    /// A single self-contained HTML file.
+    SinglePage,
+    /// A tree of Markdown files.
+    Markdown,
+}
+
+impl Format {
+    /// All available formats.
+    pub fn all() -> &'static [Format] {
+        &[Format::Html, Format::SinglePage, Format::Markdown]
+    }
+
+    /// Parse the format from its identifier.
+    pub fn parse(id: &str) -> Result<Format> {
+        for format in Self::all() {
+            if format.id() == id {
+                return Ok(*format);
+            }
+        }
+
+        let all = Self::all()
+            .iter()
+            .map(|f| format!("`{}`", f.id()))
+            .collect::<Vec<_>>();
+
+        Err(format!("unsupported doc format `{}`, expected one of: {}", id, all.join(", ")).into())
+    }
+
+    /// Identifier of the format, as used in the manifest and on the command line.
+    pub fn id(&self) -> &'static str {
+        match *self {
+            Format::Html => "html",
+            Format::SinglePage => "single-page",
+            Format::Markdown => "markdown",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
@@ -1,5 +1,6 @@
 //! Processor for service declarations.
 
+use super::DEPENDENCIES_NAME;
 use core::errors::*;
 use core::flavored::{RpFile, RpVersionedPackage};
 use doc_builder::DocBuilder;
@@ -14,7 +15,7 @@ pub struct Data<'a> {
 define_processor!(IndexProcessor, Data<'session>, self,
     process => {
         self.write_doc(|| {
-            html!(self, section {class => "section-content"} => {
+            html!(self, section {id => "index", class => "section-content"} => {
                 html!(self, h1 {class => "section-title"} ~ "Index");
 
                 html!(self, h2 {class => "kind"} ~ "Packages");
@@ -34,6 +35,9 @@ define_processor!(IndexProcessor, Data<'session>, self,
                         });
                     }
                 });
+
+                html!(self, a {class => "dependencies-link", href => self.dependencies_url()} ~
+                        "Package dependencies");
             });
 
             Ok(())
@@ -41,4 +45,13 @@ define_processor!(IndexProcessor, Data<'session>, self,
     };
 );
 
-impl<'session> IndexProcessor<'session> {}
+impl<'session> IndexProcessor<'session> {
+    /// URL to the graph of dependencies between packages.
+    fn dependencies_url(&self) -> String {
+        if self.single_page() {
+            return "#dependencies".to_string();
+        }
+
+        format!("{}/{}.html", self.root(), DEPENDENCIES_NAME)
+    }
+}
@@ -1,6 +1,7 @@
 //! Processor for service declarations.
 
 use core::errors::Result;
+use core::ExampleGenerator;
 use core::flavored::{RpInterfaceBody, RpSubType};
 use doc_builder::DocBuilder;
 use macros::FormatAttribute;
@@ -9,12 +10,14 @@ use processor::Processor;
 define_processor!(InterfaceProcessor, RpInterfaceBody, self,
     process => {
         self.write_doc(|| {
-            let id = self.body.name.join("_");
+            let id = self.anchor(&self.body.name);
 
             html!(self, section {id => &id, class => "section-content section-interface"} => {
                 self.section_title("interface", &self.body.name)?;
 
+                self.deprecated(self.body.deprecated.as_ref())?;
                 self.doc(&self.body.comment)?;
+                self.source(&self.body.name)?;
 
                 for sub_type in self.body.sub_types.iter() {
                     self.sub_type(sub_type)?;
@@ -31,18 +34,24 @@ define_processor!(InterfaceProcessor, RpInterfaceBody, self,
 );
 
 impl<'p> InterfaceProcessor<'p> {
-    fn sub_type(&self, sub_type: &RpSubType) -> Result<()> {
-        let id = sub_type.name.join("_");
+    fn sub_type(&self, sub_type: &'p RpSubType) -> Result<()> {
+        let id = self.anchor(&sub_type.name);
 
         html!(self, h2 {id => id, class => "sub-type-title"} => {
             html!(self, span {class => "kind"} ~ "subtype");
             self.full_name_without_package(&sub_type.name)?;
         });
 
-        self.doc(&self.body.comment)?;
+        self.deprecated(sub_type.deprecated.as_ref())?;
+        self.doc(&sub_type.comment)?;
 
         let fields = self.body.fields.iter().chain(sub_type.fields.iter());
         self.fields(fields)?;
+
+        let session = self.session();
+        let mut examples = ExampleGenerator::new(|n| session.lookup_decl(n));
+        self.example(Some(examples.sub_type(self.body, sub_type)?))?;
+
         self.nested_decls(sub_type.decls.iter())?;
         Ok(())
     }
@@ -8,25 +8,42 @@ extern crate reproto_backend as backend;
 extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
 extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate syntect;
 
 #[macro_use]
 mod macros;
+mod dependencies_processor;
+mod dependency_graph;
 mod doc_builder;
 mod doc_compiler;
 mod enum_processor;
 mod escape;
+mod format;
 mod index_processor;
 mod interface_processor;
+mod markdown_compiler;
+mod newtype_processor;
 mod package_processor;
 mod processor;
 mod rendering;
+mod search_index;
 mod service_processor;
+mod single_page_processor;
+mod source_snippet;
+mod theme_dir;
 mod tuple_processor;
 mod type_processor;
+mod union_processor;
 
 pub const NORMALIZE_CSS_NAME: &str = "normalize.css";
 pub const DOC_CSS_NAME: &str = "doc.css";
+pub const SEARCH_JS_NAME: &str = "search.js";
+pub const SEARCH_INDEX_NAME: &str = "search-index.json";
+pub const DEPENDENCIES_NAME: &str = "dependencies";
 pub const EXT: &str = "html";
 pub const INDEX: &str = "index";
 pub const DEFAULT_THEME: &str = "light";
@@ -36,11 +53,17 @@ use clap::{App, Arg, ArgMatches};
 use core::errors::*;
 use core::CoreFlavor;
 use doc_compiler::DocCompiler;
+use format::{Format, DEFAULT_FORMAT};
+use markdown_compiler::MarkdownCompiler;
 use manifest::Manifest;
+use source_snippet::{SourceUrl, Sources};
 use std::collections::HashMap;
+use std::env;
+use std::path::Path;
 use syntect::dumps::from_binary;
 use syntect::highlighting::{Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
+use theme_dir::ThemeDir;
 use trans::Session;
 
 include!(concat!(env!("OUT_DIR"), "/themes.rs"));
@@ -84,6 +107,13 @@ pub fn shared_options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .help("List available themes"),
     );
 
+    let out = out.arg(
+        Arg::with_name("theme-dir")
+            .long("theme-dir")
+            .takes_value(true)
+            .help("Directory with a custom stylesheet, header, and footer overriding the theme"),
+    );
+
     let out = out.arg(
         Arg::with_name("syntax-theme")
             .long("syntax-theme")
@@ -97,6 +127,27 @@ pub fn shared_options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .help("List available syntax themes"),
     );
 
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["html", "single-page", "markdown"])
+            .help("Output format of the documentation (default: html)"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("source-url")
+            .long("source-url")
+            .takes_value(true)
+            .help("URL template linking declarations to a source browser, like GitHub"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("all-versions")
+            .long("all-versions")
+            .help("Document all available versions of the required packages"),
+    );
+
     let out = out.arg(
         Arg::with_name("skip-static")
             .long("skip-static")
@@ -118,7 +169,7 @@ fn with_initialized<F>(
     f: F,
 ) -> Result<()>
 where
-    F: FnOnce(&Theme, &SyntaxSet, &[u8]) -> Result<()>,
+    F: FnOnce(&Theme, &SyntaxSet, &[u8], &ThemeDir) -> Result<()>,
 {
     let syntax_theme = matches
         .value_of("syntax-theme")
@@ -140,19 +191,38 @@ where
         &default_theme
     };
 
-    let theme = matches.value_of("theme").unwrap_or(DEFAULT_THEME);
+    let theme = matches
+        .value_of("theme")
+        .or_else(|| manifest.doc.theme.as_ref().map(String::as_str))
+        .unwrap_or(DEFAULT_THEME);
 
     let theme_css = if let Some(theme_css) = themes.get(theme) {
         theme_css
     } else {
-        warn!("No syntax theme named `{}`, falling back to default", theme);
+        warn!("No theme named `{}`, falling back to default", theme);
 
         themes
             .get(DEFAULT_THEME)
             .ok_or_else(|| format!("no such default theme: {}", DEFAULT_THEME))?
     };
 
-    f(syntax_theme, &syntax_set, theme_css)
+    let theme_dir = match matches
+        .value_of("theme-dir")
+        .map(Path::new)
+        .or_else(|| manifest.doc.theme_dir.as_ref().map(|p| p.as_path()))
+    {
+        Some(path) => ThemeDir::load(path)?,
+        None => ThemeDir::default(),
+    };
+
+    // a stylesheet in the theme directory replaces the built-in theme.
+    let theme_css = theme_dir
+        .css
+        .as_ref()
+        .map(Vec::as_slice)
+        .unwrap_or(theme_css);
+
+    f(syntax_theme, &syntax_set, theme_css, &theme_dir)
 }
 
 fn list_themes(themes: &HashMap<&'static str, &'static [u8]>) -> Result<()> {
@@ -233,18 +303,60 @@ pub fn compile(
         .ok_or("Missing `--out` or `output=`")?
         .clone();
 
+    let format = match matches
+        .value_of("format")
+        .or_else(|| manifest.doc.format.as_ref().map(String::as_str))
+    {
+        Some(format) => Format::parse(format)?,
+        None => DEFAULT_FORMAT,
+    };
+
+    if format == Format::Markdown {
+        let compiler = MarkdownCompiler {
+            session: session,
+            out_path: out.clone(),
+        };
+
+        compiler.compile()?;
+        println!("Wrote documentation in: {}", out.display());
+        return Ok(());
+    }
+
+    let source_url = match matches
+        .value_of("source-url")
+        .map(ToOwned::to_owned)
+        .or_else(|| manifest.doc.source_url.clone())
+    {
+        Some(template) => {
+            // paths in the template are relative to the manifest.
+            let root = match manifest.path.as_ref().and_then(|p| p.parent()) {
+                Some(parent) => parent.to_owned(),
+                None => env::current_dir()?,
+            };
+
+            Some(SourceUrl::new(template, root))
+        }
+        None => None,
+    };
+
+    let sources = Sources::load(&session)?;
+
     with_initialized(
         matches,
         manifest,
         &themes,
-        |syntax_theme, syntax_set, theme_css| {
+        |syntax_theme, syntax_set, theme_css, theme_dir| {
             let compiler = DocCompiler {
                 session: session,
                 out_path: out.clone(),
                 skip_static: skip_static,
+                single_page: format == Format::SinglePage,
                 theme_css: theme_css,
                 syntax_theme: syntax_theme,
                 syntax_set: syntax_set,
+                theme_dir: theme_dir,
+                sources: sources,
+                source_url: source_url.as_ref(),
             };
 
             compiler.compile()
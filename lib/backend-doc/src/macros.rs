@@ -43,6 +43,10 @@ macro_rules! define_processor {
             pub session: &'session $crate::trans::Translated<$crate::core::CoreFlavor>,
             pub syntax: (&'session ::syntect::highlighting::Theme, &'session ::syntect::parsing::SyntaxSet),
             pub root: &'session str,
+            pub single_page: bool,
+            pub theme_dir: &'session $crate::theme_dir::ThemeDir,
+            pub sources: &'session $crate::source_snippet::Sources,
+            pub source_url: Option<&'session $crate::source_snippet::SourceUrl>,
             pub body: &'session $body,
         }
 
@@ -59,6 +63,22 @@ macro_rules! define_processor {
                 self.root
             }
 
+            fn single_page(&self) -> bool {
+                self.single_page
+            }
+
+            fn theme_dir(&self) -> &'session $crate::theme_dir::ThemeDir {
+                self.theme_dir
+            }
+
+            fn sources(&self) -> &'session $crate::source_snippet::Sources {
+                self.sources
+            }
+
+            fn source_url(&self) -> Option<&'session $crate::source_snippet::SourceUrl> {
+                self.source_url
+            }
+
             fn syntax(&self) -> (
                 &'session ::syntect::highlighting::Theme,
                 &'session ::syntect::parsing::SyntaxSet,
@@ -88,6 +108,12 @@ macro_rules! define_processor {
         define_processor!(@tail $slf $($tail)*);
     );
 
+    (@tail $slf:ident embedded => $expr:expr; $($tail:tt)*) => (
+        fn embedded(&$slf) -> Option<&'session $crate::processor::Embedded> { Some($expr) }
+
+        define_processor!(@tail $slf $($tail)*);
+    );
+
     (@tail $slf:ident) => ();
 }
 
@@ -0,0 +1,576 @@
+//! Compiler for generating documentation as a tree of Markdown files.
+//!
+//! Doc comments are already Markdown, so they are embedded as-is. The output is suitable for
+//! publishing in places like GitHub wikis or mkdocs.
+
+use core::errors::*;
+use core::flavored::{
+    RpDecl, RpEndpoint, RpEnumBody, RpField, RpFile, RpInterfaceBody, RpName, RpNewtypeBody,
+    RpServiceBody, RpType, RpUnionBody, RpVersionedPackage,
+};
+use core::{self, AsPackage, CoreFlavor, Example, ExampleGenerator, Loc};
+use processor::{anchor, endpoint_anchor};
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::io::Write;
+use std::iter;
+use std::path::{Path, PathBuf};
+use trans::Translated;
+
+const EXT: &str = "md";
+
+pub struct MarkdownCompiler {
+    pub session: Translated<CoreFlavor>,
+    pub out_path: PathBuf,
+}
+
+impl MarkdownCompiler {
+    /// Do the compilation.
+    pub fn compile(&self) -> Result<()> {
+        for (_, file) in self.session.for_each_file() {
+            for decl in file.for_each_decl() {
+                self.write_decl(decl)?;
+            }
+        }
+
+        self.write_index()?;
+
+        for (package, file) in self.session.for_each_file() {
+            self.write_package(package, file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the root index file.
+    fn write_index(&self) -> Result<()> {
+        let mut out = String::new();
+
+        writeln!(out, "# Index")?;
+        writeln!(out)?;
+        writeln!(out, "## Packages")?;
+        writeln!(out)?;
+        writeln!(out, "| Package | Description |")?;
+        writeln!(out, "| --- | --- |")?;
+
+        for (package, file) in self.session.for_each_file() {
+            let url = format!("{}/index.{}", self.package_path(package).join("/"), EXT);
+
+            writeln!(
+                out,
+                "| [`{}`]({}) | {} |",
+                package,
+                url,
+                summary(&file.comment)
+            )?;
+        }
+
+        self.write_file(&self.out_path, "index", out)
+    }
+
+    /// Write the index file of a single package.
+    fn write_package(&self, package: &RpVersionedPackage, file: &RpFile) -> Result<()> {
+        use core::RpDecl::*;
+
+        let parts = self.package_path(package);
+        let root = parts.iter().map(|_| "..").collect::<Vec<_>>().join("/");
+
+        let mut out = String::new();
+
+        writeln!(out, "[Index]({}/index.{})", root, EXT)?;
+        writeln!(out)?;
+        writeln!(out, "# package `{}`", package)?;
+        writeln!(out)?;
+        self.doc(&mut out, &file.comment)?;
+
+        let mut types = Vec::new();
+        let mut interfaces = Vec::new();
+        let mut enums = Vec::new();
+        let mut tuples = Vec::new();
+        let mut services = Vec::new();
+        let mut unions = Vec::new();
+        let mut newtypes = Vec::new();
+
+        for decl in file.for_each_decl() {
+            match *decl {
+                Type(_) => types.push(decl),
+                Interface(_) => interfaces.push(decl),
+                Enum(_) => enums.push(decl),
+                Tuple(_) => tuples.push(decl),
+                Service(_) => services.push(decl),
+                Union(_) => unions.push(decl),
+                Newtype(_) => newtypes.push(decl),
+            }
+        }
+
+        self.decls_section(&mut out, &root, "Types", &types)?;
+        self.decls_section(&mut out, &root, "Interfaces", &interfaces)?;
+        self.decls_section(&mut out, &root, "Enums", &enums)?;
+        self.decls_section(&mut out, &root, "Tuples", &tuples)?;
+        self.decls_section(&mut out, &root, "Services", &services)?;
+        self.decls_section(&mut out, &root, "Unions", &unions)?;
+        self.decls_section(&mut out, &root, "Newtypes", &newtypes)?;
+
+        let mut path = self.out_path.to_owned();
+
+        for part in &parts {
+            path = path.join(part);
+        }
+
+        self.write_file(&path, "index", out)
+    }
+
+    /// Write the file of a single declaration.
+    fn write_decl<'a>(&'a self, decl: &'a RpDecl) -> Result<()> {
+        use core::RpDecl::*;
+
+        let name = decl.name();
+        let package = name.package.try_as_package()?;
+
+        let mut path = self.out_path.to_owned();
+        let mut root = Vec::new();
+
+        for part in package.parts() {
+            root.push("..");
+            path = path.join(part.as_str());
+        }
+
+        let root = root.join("/");
+
+        let mut out = String::new();
+
+        writeln!(
+            out,
+            "[Index]({root}/index.{ext}) &mdash; [Package: {package}](index.{ext})",
+            root = root,
+            ext = EXT,
+            package = name.package
+        )?;
+        writeln!(out)?;
+        writeln!(out, "# {} `{}`", decl.kind(), name)?;
+        writeln!(out)?;
+        self.doc(&mut out, decl.comment())?;
+
+        match *decl {
+            Type(ref body) => {
+                self.fields(&mut out, &root, "Fields", body.fields.iter())?;
+                self.example(&mut out, self.examples().decl(decl)?)?;
+            }
+            Tuple(ref body) => {
+                self.fields(&mut out, &root, "Fields", body.fields.iter())?;
+                self.example(&mut out, self.examples().decl(decl)?)?;
+            }
+            Interface(ref body) => {
+                self.interface(&mut out, &root, body)?;
+            }
+            Enum(ref body) => {
+                self.variants(&mut out, body)?;
+            }
+            Service(ref body) => {
+                self.endpoints(&mut out, &root, body)?;
+            }
+            Union(ref body) => {
+                self.union_variants(&mut out, &root, body)?;
+                self.example(&mut out, self.examples().decl(decl)?)?;
+            }
+            Newtype(ref body) => {
+                self.newtype(&mut out, &root, body)?;
+                self.example(&mut out, self.examples().decl(decl)?)?;
+            }
+        }
+
+        let decls = decl.decls().collect::<Vec<_>>();
+        self.decls_section(&mut out, &root, "Declarations", &decls)?;
+
+        let file_name = format!("{}.{}", decl.kind(), name.path.join("."));
+        self.write_file(&path, &file_name, out)
+    }
+
+    fn interface<'a>(
+        &'a self,
+        out: &mut String,
+        root: &str,
+        body: &'a RpInterfaceBody,
+    ) -> Result<()> {
+        self.fields(out, root, "Fields", body.fields.iter())?;
+
+        for sub_type in &body.sub_types {
+            writeln!(out, "<a id=\"{}\"></a>", anchor(&sub_type.name, false))?;
+            writeln!(out)?;
+            writeln!(out, "## subtype `{}`", sub_type.name)?;
+            writeln!(out)?;
+            self.doc(out, &sub_type.comment)?;
+
+            let fields = body.fields.iter().chain(sub_type.fields.iter());
+            self.fields(out, root, "Fields", fields)?;
+
+            let example = self.examples().sub_type(body, sub_type)?;
+            self.example(out, Some(example))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write an example JSON payload.
+    fn example(&self, out: &mut String, example: Option<Example>) -> Result<()> {
+        if let Some(example) = example {
+            writeln!(out, "## Example")?;
+            writeln!(out)?;
+            writeln!(out, "```json")?;
+            writeln!(out, "{}", example.to_json())?;
+            writeln!(out, "```")?;
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Build a generator for examples.
+    fn examples<'a>(
+        &'a self,
+    ) -> ExampleGenerator<'a, impl Fn(&RpName) -> Result<&'a RpDecl>> {
+        ExampleGenerator::new(move |name| self.session.lookup_decl(name))
+    }
+
+    fn variants(&self, out: &mut String, body: &RpEnumBody) -> Result<()> {
+        let mut it = body.variants.iter().peekable();
+
+        if it.peek().is_none() {
+            return Ok(());
+        }
+
+        writeln!(out, "## Variants")?;
+        writeln!(out)?;
+        writeln!(out, "| Variant | Value | Description |")?;
+        writeln!(out, "| --- | --- | --- |")?;
+
+        for variant in it {
+            let value = iter::once(variant.value)
+                .chain(variant.aliases.iter())
+                .map(|value| match value {
+                    core::RpVariantValue::String(string) => format!("`\"{}\"`", string),
+                    core::RpVariantValue::Number(number) => format!("`{}`", number),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(
+                out,
+                "| <a id=\"{}\"></a>`{}` | {} | {} |",
+                anchor(variant.name, false),
+                variant.ident.as_str(),
+                value,
+                summary(variant.comment)
+            )?;
+        }
+
+        writeln!(out)?;
+        Ok(())
+    }
+
+    fn endpoints(&self, out: &mut String, root: &str, body: &RpServiceBody) -> Result<()> {
+        if body.endpoints.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "## Endpoints")?;
+        writeln!(out)?;
+
+        for endpoint in &body.endpoints {
+            self.endpoint(out, root, &body.name, endpoint)?;
+        }
+
+        Ok(())
+    }
+
+    fn endpoint(
+        &self,
+        out: &mut String,
+        root: &str,
+        service: &RpName,
+        endpoint: &RpEndpoint,
+    ) -> Result<()> {
+        writeln!(
+            out,
+            "<a id=\"{}\"></a>",
+            endpoint_anchor(service, endpoint, false)
+        )?;
+        writeln!(out)?;
+        writeln!(out, "### `{}`", endpoint.ident())?;
+        writeln!(out)?;
+
+        let mut arguments = Vec::new();
+
+        for arg in &endpoint.arguments {
+            let (channel, _) = Loc::borrow_pair(&arg.channel);
+
+            let stream = if channel.is_streaming() {
+                "stream "
+            } else {
+                ""
+            };
+
+            arguments.push(format!(
+                "`{}`: {}{}",
+                arg.ident.as_str(),
+                stream,
+                self.write_type(root, channel.ty())?
+            ));
+        }
+
+        write!(out, "`{}`({})", endpoint.ident(), arguments.join(", "))?;
+
+        if let Some(response) = endpoint.response.as_ref() {
+            let stream = if response.is_streaming() {
+                "stream "
+            } else {
+                ""
+            };
+
+            write!(out, " &rarr; {}{}", stream, self.write_type(root, response.ty())?)?;
+        }
+
+        if endpoint.ident() != endpoint.name() {
+            write!(out, " as `{}`", endpoint.name())?;
+        }
+
+        writeln!(out)?;
+        writeln!(out)?;
+        self.doc(out, &endpoint.comment)?;
+
+        if endpoint.http.path.is_some() {
+            let mut media_types = Vec::new();
+
+            if endpoint.request.is_some() {
+                media_types.push(format!("accepts `{}`", endpoint.http.accepts.as_str()));
+            }
+
+            if endpoint.response.is_some() {
+                media_types.push(format!("produces `{}`", endpoint.http.produces.as_str()));
+            }
+
+            if !media_types.is_empty() {
+                writeln!(out, "_{}_", media_types.join(", "))?;
+                writeln!(out)?;
+            }
+        }
+
+        let mut documented = endpoint
+            .arguments
+            .iter()
+            .filter(|a| !a.comment.is_empty())
+            .peekable();
+
+        if documented.peek().is_some() {
+            for arg in documented {
+                let comment = summary(&arg.comment);
+                writeln!(out, "* `{}` - {}", arg.ident.as_str(), comment)?;
+            }
+
+            writeln!(out)?;
+        }
+
+        if !endpoint.errors.is_empty() {
+            for error in &endpoint.errors {
+                let (ident, status) = (error.ident(), error.status);
+                let ty = self.write_type(root, &error.ty)?;
+                writeln!(out, "* throws `{}`: {} @ {}", ident, ty, status)?;
+            }
+
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a table of the variants of a union.
+    fn union_variants(&self, out: &mut String, root: &str, body: &RpUnionBody) -> Result<()> {
+        writeln!(out, "## Variants")?;
+        writeln!(out)?;
+        writeln!(out, "| Variant | Type |")?;
+        writeln!(out, "| --- | --- |")?;
+
+        for variant in &body.variants {
+            writeln!(
+                out,
+                "| `{}` | {} |",
+                variant.ident,
+                self.write_type(root, &variant.ty)?
+            )?;
+        }
+
+        writeln!(out)?;
+        Ok(())
+    }
+
+    /// Write the type wrapped by a newtype, and how it is validated.
+    fn newtype(&self, out: &mut String, root: &str, body: &RpNewtypeBody) -> Result<()> {
+        writeln!(out, "Wraps {}.", self.write_type(root, &body.ty)?)?;
+        writeln!(out)?;
+
+        if let Some(ref pattern) = body.validate.pattern {
+            writeln!(out, "Values must match the pattern `{}`.", pattern)?;
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a table of fields.
+    fn fields<'b, I>(&self, out: &mut String, root: &str, title: &str, fields: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'b Loc<RpField>>,
+    {
+        let mut it = fields.into_iter().peekable();
+
+        if it.peek().is_none() {
+            return Ok(());
+        }
+
+        writeln!(out, "## {}", title)?;
+        writeln!(out)?;
+        writeln!(out, "| Field | Type | Description |")?;
+        writeln!(out, "| --- | --- | --- |")?;
+
+        for field in it {
+            let modifier = if field.is_optional() { "?" } else { "" };
+
+            writeln!(
+                out,
+                "| `{}{}` | {} | {} |",
+                field.name(),
+                modifier,
+                self.write_type(root, &field.ty)?,
+                summary(&field.comment)
+            )?;
+        }
+
+        writeln!(out)?;
+        Ok(())
+    }
+
+    /// Write a table linking to the given declarations.
+    fn decls_section(
+        &self,
+        out: &mut String,
+        root: &str,
+        title: &str,
+        decls: &[&RpDecl],
+    ) -> Result<()> {
+        if decls.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "## {}", title)?;
+        writeln!(out)?;
+        writeln!(out, "| Name | Description |")?;
+        writeln!(out, "| --- | --- |")?;
+
+        for decl in decls {
+            let url = self.type_url(root, decl.name())?;
+            let name = decl.name().path.join("::");
+            writeln!(out, "| [`{}`]({}) | {} |", name, url, summary(decl.comment()))?;
+        }
+
+        writeln!(out)?;
+        Ok(())
+    }
+
+    /// Render the given type as inline Markdown, linking to any referenced declarations.
+    fn write_type(&self, root: &str, ty: &RpType) -> Result<String> {
+        use core::RpType::*;
+
+        let out = match *ty {
+            Name { ref name } => format!("[`{}`]({})", name, self.type_url(root, name)?),
+            Array { ref inner } => format!("\\[{}\\]", self.write_type(root, inner)?),
+            Set { ref inner } => format!("set\\<{}\\>", self.write_type(root, inner)?),
+            Map { ref key, ref value } => format!(
+                "{{{}: {}}}",
+                self.write_type(root, key)?,
+                self.write_type(root, value)?
+            ),
+            ref ty => format!("`{}`", ty),
+        };
+
+        Ok(out)
+    }
+
+    /// Generate a URL to the given name, relative to the given root.
+    fn type_url(&self, root: &str, name: &RpName) -> Result<String> {
+        let reg = self.session.lookup(name)?;
+
+        let (fragment, path) = match *reg {
+            core::RpReg::EnumVariant | core::RpReg::SubType => {
+                let fragment = format!("#{}", anchor(name, false));
+
+                let path: Vec<_> = name
+                    .path
+                    .iter()
+                    .cloned()
+                    .take(name.path.len() - 1)
+                    .collect();
+
+                (fragment, path)
+            }
+            _ => ("".to_string(), name.path.clone()),
+        };
+
+        let package_path = name.package.try_as_package()?.join("/");
+
+        Ok(format!(
+            "{}/{}/{}.{}.{}{}",
+            root,
+            package_path,
+            reg,
+            path.join("."),
+            EXT,
+            fragment,
+        ))
+    }
+
+    fn doc(&self, out: &mut String, comment: &[String]) -> Result<()> {
+        if comment.is_empty() {
+            return Ok(());
+        }
+
+        for line in comment {
+            writeln!(out, "{}", line)?;
+        }
+
+        writeln!(out)?;
+        Ok(())
+    }
+
+    fn package_path(&self, package: &RpVersionedPackage) -> Vec<String> {
+        package
+            .clone()
+            .to_package(|v| v.to_string())
+            .parts()
+            .cloned()
+            .collect()
+    }
+
+    fn write_file(&self, path: &Path, name: &str, content: String) -> Result<()> {
+        if !path.is_dir() {
+            debug!("+dir: {}", path.display());
+            fs::create_dir_all(path)?;
+        }
+
+        let out = path.join(format!("{}.{}", name, EXT));
+        debug!("+file: {}", out.display());
+        let mut f = fs::File::create(&out)?;
+        f.write_all(content.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Summarize a comment into something that fits in a single table cell.
+fn summary(comment: &[String]) -> String {
+    let paragraph = comment
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    paragraph.join(" ").replace('|', "\\|")
+}
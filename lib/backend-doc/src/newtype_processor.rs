@@ -0,0 +1,47 @@
+//! Processor for newtype declarations.
+
+use core::errors::*;
+use core::flavored::RpNewtypeBody;
+use core::ExampleGenerator;
+use doc_builder::DocBuilder;
+use escape::Escape;
+use macros::FormatAttribute;
+use processor::Processor;
+
+define_processor!(NewtypeProcessor, RpNewtypeBody, self,
+    process => {
+        self.write_doc(|| {
+            let id = self.anchor(&self.body.name);
+
+            html!(self, section {id => &id, class => "section-content section-newtype"} => {
+                self.section_title("newtype", &self.body.name)?;
+
+                html!(self, div {class => "section-body"} => {
+                    self.deprecated(self.body.deprecated.as_ref())?;
+                    self.doc(&self.body.comment)?;
+                    self.source(&self.body.name)?;
+
+                    html!(self, h2 {class => "newtype-title"} => {
+                        html!(self, span {class => "kind"} ~ "wraps");
+                        self.write_type(&self.body.ty)?;
+                    });
+
+                    if let Some(ref pattern) = self.body.validate.pattern {
+                        html!(self, p {class => "newtype-pattern"} => {
+                            html!(self, span {class => "kind"} ~ "pattern");
+                            html!(self, code {} ~ Escape(pattern.to_string().as_str()));
+                        });
+                    }
+
+                    let session = self.session();
+                    let mut examples = ExampleGenerator::new(|n| session.lookup_decl(n));
+                    self.example(examples.decl(session.lookup_decl(&self.body.name)?)?)?;
+                });
+            });
+
+            Ok(())
+        })
+    };
+
+    current_package => &self.body.name.package;
+);
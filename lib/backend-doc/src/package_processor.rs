@@ -7,7 +7,7 @@ use core::flavored::{RpFile, RpVersionedPackage};
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
-use processor::Processor;
+use processor::{package_anchor, Processor};
 
 pub struct Data<'a> {
     pub package: &'a RpVersionedPackage,
@@ -46,6 +46,8 @@ define_processor!(PackageProcessor, Data<'session>, self,
             let mut enums = Vec::new();
             let mut tuples = Vec::new();
             let mut services = Vec::new();
+            let mut unions = Vec::new();
+            let mut newtypes = Vec::new();
 
             for decl in self.body.file.for_each_decl() {
                 match *decl {
@@ -54,10 +56,14 @@ define_processor!(PackageProcessor, Data<'session>, self,
                     Enum(ref en) => enums.push(en),
                     Tuple(ref tuple) => tuples.push(tuple),
                     Service(ref service) => services.push(service),
+                    Union(ref union) => unions.push(union),
+                    Newtype(ref newtype) => newtypes.push(newtype),
                 }
             }
 
-            html!(self, section {class => "section-content"} => {
+            let id = package_anchor(self.body.package);
+
+            html!(self, section {id => &id, class => "section-content"} => {
                 html!(self, h1 {class => "section-title"} => {
                     html!(self, span {class => "kind"} ~ "package");
                     html!(self, span {class => "name-package"} ~
@@ -71,6 +77,8 @@ define_processor!(PackageProcessor, Data<'session>, self,
                 types_section!(self, enums, "Enums");
                 types_section!(self, tuples, "Tuples");
                 types_section!(self, services, "Services");
+                types_section!(self, unions, "Unions");
+                types_section!(self, newtypes, "Newtypes");
             });
 
             Ok(())
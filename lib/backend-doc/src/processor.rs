@@ -1,18 +1,90 @@
 //! Processor trait.
 
-use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME};
+use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME, SEARCH_JS_NAME};
 use core::errors::*;
-use core::flavored::{RpDecl, RpField, RpName, RpType, RpVersionedPackage};
-use core::{self, AsPackage, CoreFlavor, Loc};
+use core::flavored::{RpDecl, RpEndpoint, RpField, RpName, RpType, RpVersionedPackage};
+use core::{self, AsPackage, CoreFlavor, Example, Loc, RpDeprecated};
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
 use rendering::markdown_to_html;
+use source_snippet::{SourceUrl, Sources};
 use std::ops::DerefMut;
 use syntect::highlighting::Theme;
 use syntect::parsing::SyntaxSet;
+use theme_dir::ThemeDir;
 use trans::Translated;
 
+/// Build the anchor used to link to the given name.
+///
+/// Anchors only have to be unique within a page, unless everything is rendered on a single page in
+/// which case they are prefixed with the package.
+pub fn anchor(name: &RpName, single_page: bool) -> String {
+    if single_page {
+        format!("{}_{}", package_anchor(&name.package), name.path.join("_"))
+    } else {
+        name.path.join("_")
+    }
+}
+
+/// Build the anchor used to link to the given package on a single page.
+pub fn package_anchor(package: &RpVersionedPackage) -> String {
+    let package = package.clone().to_package(|v| v.to_string());
+    format!("package_{}", package.join("_"))
+}
+
+/// Build the anchor used to link to an endpoint in the given service.
+pub fn endpoint_anchor(service: &RpName, endpoint: &RpEndpoint, single_page: bool) -> String {
+    let parts = endpoint.id_parts(fragment_filter).join("_");
+
+    if single_page {
+        format!("{}_{}", anchor(service, single_page), parts)
+    } else {
+        format!("{}_{}", service, parts)
+    }
+}
+
+/// Percent-encode characters which are not legal in a URL fragment.
+pub fn fragment_filter(url: &str) -> String {
+    let mut bytes = [0u8; 4];
+    let mut buffer = String::with_capacity(url.len());
+
+    for c in url.chars() {
+        let encode = match c {
+            'a'...'z' | 'A'...'Z' | '0'...'9' => false,
+            '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' => false,
+            '-' | '.' | '_' | '~' | ':' | '@' | '/' | '?' => false,
+            _ => true,
+        };
+
+        if encode {
+            let result = c.encode_utf8(&mut bytes);
+
+            for b in result.bytes() {
+                buffer.extend(format!("%{:X}", b).chars());
+            }
+
+            continue;
+        }
+
+        buffer.push(c);
+    }
+
+    buffer
+}
+
+/// Stylesheets and scripts which are embedded in a page, instead of being linked to.
+///
+/// Used when all documentation is rendered on a single page, so that the page is self-contained.
+pub struct Embedded {
+    /// Stylesheets of the page.
+    pub css: String,
+    /// The search script.
+    pub search_js: String,
+    /// The search index, as JSON.
+    pub search_index: String,
+}
+
 pub trait Processor<'session> {
     /// Access the current builder.
     fn out(&self) -> ::std::cell::RefMut<DocBuilder<'session>>;
@@ -29,17 +101,43 @@ pub trait Processor<'session> {
     /// Syntax theme.
     fn syntax(&self) -> (&'session Theme, &'session SyntaxSet);
 
+    /// If all documentation is rendered on a single page.
+    fn single_page(&self) -> bool;
+
+    /// Custom theme loaded from a directory.
+    fn theme_dir(&self) -> &'session ThemeDir;
+
+    /// Contents of the sources of all documented packages.
+    fn sources(&self) -> &'session Sources;
+
+    /// Template for links to declarations in a source browser.
+    fn source_url(&self) -> Option<&'session SourceUrl>;
+
+    /// Build the anchor used to link to the given name.
+    fn anchor(&self, name: &RpName) -> String {
+        anchor(name, self.single_page())
+    }
+
     fn current_package(&self) -> Option<&'session RpVersionedPackage> {
         None
     }
 
+    /// Stylesheets and scripts to embed in the page.
+    fn embedded(&self) -> Option<&'session Embedded> {
+        None
+    }
+
     /// Generate a type URL.
     fn type_url(&self, name: &RpName) -> Result<String> {
         let reg = self.session().lookup(name)?;
 
+        if self.single_page() {
+            return Ok(format!("#{}", self.anchor(name)));
+        }
+
         let (fragment, path) = match *reg {
             core::RpReg::EnumVariant | core::RpReg::SubType => {
-                let fragment = format!("#{}", name.path.clone().join("_"));
+                let fragment = format!("#{}", self.anchor(name));
 
                 let path: Vec<_> = name
                     .path
@@ -100,6 +198,68 @@ pub trait Processor<'session> {
         Ok(())
     }
 
+    /// Render a deprecation notice, if present.
+    fn deprecated(&self, deprecated: Option<&RpDeprecated>) -> Result<()> {
+        if let Some(deprecated) = deprecated {
+            html!(self, div {class => "deprecated"} => {
+                html!(self, span {class => "deprecated-label"} ~ "deprecated");
+
+                if let Some(note) = deprecated.note() {
+                    html!(self, span {class => "deprecated-note"} ~ Escape(note));
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Render an example JSON payload.
+    fn example(&self, example: Option<Example>) -> Result<()> {
+        if let Some(example) = example {
+            html!(self, div {class => "example"} => {
+                html!(self, h2 {class => "example-title"} ~ "Example");
+                self.markdown(&format!("```json\n{}\n```", example.to_json()))?;
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Render the source of the given declaration, which can be expanded.
+    ///
+    /// Includes a link to the declaration in a source browser if one is configured.
+    fn source(&self, name: &RpName) -> Result<()> {
+        let content = match self.sources().get(&name.package) {
+            Some(content) => content,
+            None => return Ok(()),
+        };
+
+        let span = self.session().lookup_decl(name)?.span();
+
+        let snippet = match content.snippet(span) {
+            Some(snippet) => snippet,
+            None => return Ok(()),
+        };
+
+        let url = self
+            .source_url()
+            .and_then(|u| u.url(content.source(), &snippet));
+
+        html!(self, details {class => "source"} => {
+            html!(self, summary {class => "source-title"} => {
+                html!(self, span {} ~ "Source");
+
+                if let Some(url) = url {
+                    html!(self, a {class => "source-link", href => url} ~ "view source");
+                }
+            });
+
+            html!(self, pre {class => "source-code"} ~ Escape(&snippet.content));
+        });
+
+        Ok(())
+    }
+
     fn primitive(&self, name: &str) -> Result<()> {
         html!(self, span {class => format!("type-{} type-primitive", name)} ~ name);
         Ok(())
@@ -116,8 +276,12 @@ pub trait Processor<'session> {
             Boolean => self.primitive("boolean")?,
             String(..) => self.primitive("string")?,
             DateTime => self.primitive("datetime")?,
+            Date => self.primitive("date")?,
+            Time => self.primitive("time")?,
+            Duration => self.primitive("duration")?,
             Bytes => self.primitive("bytes")?,
             Any => self.primitive("any")?,
+            Scalar(ref scalar) => self.primitive(scalar.name.as_str())?,
             Number(ref number) => self.primitive(number.to_string().as_str())?,
             Name { ref name } => {
                 html!(self, span {class => "type-rp-name"} => {
@@ -131,6 +295,13 @@ pub trait Processor<'session> {
                     html!(self, span {class => "type-array-right"} ~ "]");
                 });
             }
+            Set { ref inner } => {
+                html!(self, span {class => "type-set"} => {
+                    html!(self, span {class => "type-set-left"} ~ "set&lt;");
+                    self.write_type(inner)?;
+                    html!(self, span {class => "type-set-right"} ~ "&gt;");
+                });
+            }
             Map { ref key, ref value } => {
                 html!(self, span {class => "type-map"} => {
                     html!(self, span {class => "type-map-left"} ~ "{");
@@ -176,6 +347,7 @@ pub trait Processor<'session> {
             }
         });
 
+        self.deprecated(field.deprecated.as_ref())?;
         self.doc(&field.comment)?;
 
         Ok(())
@@ -199,6 +371,7 @@ pub trait Processor<'session> {
             self.full_name_without_package(&decl.name())?;
         });
 
+        self.deprecated(decl.deprecated())?;
         self.doc(decl.comment().iter().take(1))?;
         Ok(())
     }
@@ -225,8 +398,20 @@ pub trait Processor<'session> {
         Ok(())
     }
 
-    /// Write a complete HTML document.
+    /// Write a document, which is embedded as-is when rendering a single page.
     fn write_doc<Body>(&self, body: Body) -> Result<()>
+    where
+        Body: FnOnce() -> Result<()>,
+    {
+        if self.single_page() {
+            return body();
+        }
+
+        self.write_page(body)
+    }
+
+    /// Write a complete HTML document.
+    fn write_page<Body>(&self, body: Body) -> Result<()>
     where
         Body: FnOnce() -> Result<()>,
     {
@@ -241,69 +426,129 @@ pub trait Processor<'session> {
                 });
                 self.out().new_line()?;
 
-                html!(@open self, link {
-                    rel => "stylesheet", type => "text/css",
-                    href => format!("{}/{}", self.root(), NORMALIZE_CSS_NAME)
-                });
-                self.out().new_line()?;
+                if let Some(embedded) = self.embedded() {
+                    html!(self, style {} ~ embedded.css);
+                } else {
+                    html!(@open self, link {
+                        rel => "stylesheet", type => "text/css",
+                        href => format!("{}/{}", self.root(), NORMALIZE_CSS_NAME)
+                    });
+                    self.out().new_line()?;
 
-                html!(@open self, link {
-                    rel => "stylesheet", type => "text/css",
-                    href => format!("{}/{}", self.root(), DOC_CSS_NAME)
-                });
+                    html!(@open self, link {
+                        rel => "stylesheet", type => "text/css",
+                        href => format!("{}/{}", self.root(), DOC_CSS_NAME)
+                    });
+                }
             });
 
             html!(self, body {} => {
                 html!(self, div {class => "container"} => {
+                    if let Some(header) = self.theme_dir().header(self.root()) {
+                        self.out().write_str(&header)?;
+                        self.out().new_line_unless_empty()?;
+                    }
+
                     html!(self, nav {class => "top"} => {
-                        html!(self, a {href => format!("{}/index.html", self.root())} ~ "Index");
+                        html!(self, a {href => self.index_url()} ~ "Index");
 
                         if let Some(package) = self.current_package() {
                             let package_url = self.package_url(package);
                             html!(self, span {} ~ "&mdash;");
                             html!(self, a {href => package_url} ~ format!("Package: {}", package));
+                            self.version_switcher(package)?;
                         }
                     });
 
+                    html!(self, div {class => "search"} => {
+                        html!(@open self, input {
+                            type => "search", id => "search",
+                            placeholder => "Search...",
+                            autocomplete => "off"
+                        });
+                        self.out().new_line()?;
+
+                        html!(self, ul {id => "search-results", class => "search-results"} ~ "");
+                    });
+
                     body()?;
+
+                    if let Some(footer) = self.theme_dir().footer(self.root()) {
+                        self.out().new_line_unless_empty()?;
+                        self.out().write_str(&footer)?;
+                    }
                 });
+
+                if let Some(embedded) = self.embedded() {
+                    html!(self, script {
+                        type => "application/json",
+                        id => "search-index"
+                    } ~ embedded.search_index);
+                    html!(self, script {} ~ embedded.search_js);
+                } else {
+                    html!(self, script {src => format!("{}/{}", self.root(), SEARCH_JS_NAME)} ~ "");
+                }
             });
         });
 
         Ok(())
     }
 
+    fn index_url(&self) -> String {
+        if self.single_page() {
+            return "#index".to_string();
+        }
+
+        format!("{}/index.html", self.root())
+    }
+
     fn package_url(&self, package: &RpVersionedPackage) -> String {
+        if self.single_page() {
+            return format!("#{}", package_anchor(package));
+        }
+
         let url = package.clone().to_package(|v| v.to_string()).join("/");
         format!("{}/{}/index.html", self.root(), url)
     }
 
-    fn fragment_filter(url: &str) -> String {
-        let mut bytes = [0u8; 4];
-        let mut buffer = String::with_capacity(url.len());
-
-        for c in url.chars() {
-            let encode = match c {
-                'a'...'z' | 'A'...'Z' | '0'...'9' => false,
-                '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' => false,
-                '-' | '.' | '_' | '~' | ':' | '@' | '/' | '?' => false,
-                _ => true,
-            };
-
-            if encode {
-                let result = c.encode_utf8(&mut bytes);
+    /// Write a dropdown to switch between the versions of the given package.
+    ///
+    /// Nothing is written unless more than one version of the package is being documented.
+    fn version_switcher(&self, current: &RpVersionedPackage) -> Result<()> {
+        let mut versions = self
+            .session()
+            .for_each_file()
+            .map(|(package, _)| package)
+            .filter(|package| package.package == current.package)
+            .collect::<Vec<_>>();
+
+        if versions.len() < 2 {
+            return Ok(());
+        }
 
-                for b in result.bytes() {
-                    buffer.extend(format!("%{:X}", b).chars());
+        // newest version first.
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+
+        html!(self, select {
+            class => "version-switcher",
+            onchange => "window.location.href = this.value"
+        } => {
+            for package in versions {
+                let url = self.package_url(package);
+                let title = match package.version {
+                    Some(ref version) => version.to_string(),
+                    None => "unversioned".to_string(),
+                };
+
+                if package == current {
+                    html!(self, option {value => url, selected => "selected"} ~ title);
+                } else {
+                    html!(self, option {value => url} ~ title);
                 }
-
-                continue;
             }
+        });
 
-            buffer.push(c);
-        }
-
-        buffer
+        Ok(())
     }
 
     /// Write the full path to a name.
@@ -0,0 +1,159 @@
+//! Build a search index over all documented symbols.
+//!
+//! The index is a flat JSON array which is loaded by `search.js` in the generated documentation,
+//! or embedded in the page when all documentation is rendered on a single page.
+
+use core::errors::*;
+use core::flavored::{RpDecl, RpField, RpName};
+use core::{AsPackage, CoreFlavor, Loc};
+use processor::{anchor, endpoint_anchor};
+use trans::Translated;
+
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    /// Kind of the symbol, like `type`, `field` or `endpoint`.
+    kind: &'static str,
+    /// Package the symbol belongs to.
+    package: String,
+    /// Name of the symbol, relative to its package.
+    name: String,
+    /// URL of the symbol, relative to the root of the documentation.
+    ///
+    /// Only a fragment when all documentation is rendered on a single page.
+    url: String,
+    /// Documentation of the symbol.
+    doc: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    single_page: bool,
+    entries: Vec<Entry>,
+}
+
+impl SearchIndex {
+    /// Build a search index for all declarations in the session.
+    pub fn build(session: &Translated<CoreFlavor>, single_page: bool) -> Result<SearchIndex> {
+        let mut index = SearchIndex {
+            single_page: single_page,
+            ..SearchIndex::default()
+        };
+
+        for (_, file) in session.for_each_file() {
+            for decl in file.for_each_decl() {
+                index.decl(decl)?;
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Convert into JSON which can be embedded in a `<script>` element.
+    ///
+    /// `</` is escaped, so that the index can't close the element it is embedded in.
+    pub fn to_embedded_json(&self) -> Result<String> {
+        Ok(self.to_json()?.replace("</", "<\\/"))
+    }
+
+    /// Serialize the index to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(::serde_json::to_string(&self.entries)
+            .map_err(|e| format!("failed to serialize search index: {}", e))?)
+    }
+
+    /// Index a single declaration.
+    fn decl(&mut self, decl: &RpDecl) -> Result<()> {
+        use core::RpDecl::*;
+
+        let name = decl.name();
+        let url = self.url(decl.kind(), name, None)?;
+
+        self.push(decl.kind(), name, &url, decl.comment());
+
+        match *decl {
+            Type(ref body) => {
+                self.fields(name, &url, &body.fields);
+            }
+            Tuple(ref body) => {
+                self.fields(name, &url, &body.fields);
+            }
+            Interface(ref body) => {
+                self.fields(name, &url, &body.fields);
+
+                for sub_type in &body.sub_types {
+                    let fragment = anchor(&sub_type.name, self.single_page);
+                    let url = self.url(decl.kind(), name, Some(&fragment))?;
+                    self.push("subtype", &sub_type.name, &url, &sub_type.comment);
+                    self.fields(&sub_type.name, &url, &sub_type.fields);
+                }
+            }
+            Enum(ref body) => {
+                for variant in &body.variants {
+                    let fragment = anchor(variant.name, self.single_page);
+                    let url = self.url(decl.kind(), name, Some(&fragment))?;
+                    self.push("variant", variant.name, &url, variant.comment);
+                }
+            }
+            Service(ref body) => {
+                for endpoint in &body.endpoints {
+                    let fragment = endpoint_anchor(&body.name, endpoint, self.single_page);
+                    let url = self.url(decl.kind(), name, Some(&fragment))?;
+
+                    self.entries.push(Entry {
+                        kind: "endpoint",
+                        package: name.package.to_string(),
+                        name: format!("{}::{}", name.path.join("::"), endpoint.ident),
+                        url: url,
+                        doc: endpoint.comment.join("\n"),
+                    });
+                }
+            }
+            Union(_) | Newtype(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Index all fields, linking to the declaration they belong to.
+    fn fields(&mut self, parent: &RpName, url: &str, fields: &[Loc<RpField>]) {
+        for field in fields {
+            self.entries.push(Entry {
+                kind: "field",
+                package: parent.package.to_string(),
+                name: format!("{}.{}", parent.path.join("::"), field.ident()),
+                url: url.to_string(),
+                doc: field.comment.join("\n"),
+            });
+        }
+    }
+
+    fn push(&mut self, kind: &'static str, name: &RpName, url: &str, doc: &[String]) {
+        self.entries.push(Entry {
+            kind: kind,
+            package: name.package.to_string(),
+            name: name.path.join("::"),
+            url: url.to_string(),
+            doc: doc.join("\n"),
+        });
+    }
+
+    /// Build the URL to the page of the given declaration, relative to the documentation root.
+    fn url(&self, kind: &str, name: &RpName, fragment: Option<&str>) -> Result<String> {
+        if self.single_page {
+            let fragment = fragment.map(ToOwned::to_owned);
+            let fragment = fragment.unwrap_or_else(|| anchor(name, self.single_page));
+            return Ok(format!("#{}", fragment));
+        }
+
+        let package = name.package.try_as_package()?;
+
+        let mut url = format!("{}/{}.{}.html", package.join("/"), kind, name.path.join("."));
+
+        if let Some(fragment) = fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+
+        Ok(url)
+    }
+}
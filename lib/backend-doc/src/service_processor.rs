@@ -6,17 +6,19 @@ use core::Loc;
 use doc_builder::DocBuilder;
 use escape::Escape;
 use macros::FormatAttribute;
-use processor::Processor;
+use processor::{endpoint_anchor, Processor};
 
 define_processor!(ServiceProcessor, RpServiceBody, self,
     process => {
         self.write_doc(|| {
-            let id = self.body.name.join("_");
+            let id = self.anchor(&self.body.name);
 
             html!(self, section {id => &id, class => "section-content section-service"} => {
                 self.section_title("service", &self.body.name)?;
 
+                self.deprecated(self.body.deprecated.as_ref())?;
                 self.doc(&self.body.comment)?;
+                self.source(&self.body.name)?;
 
                 for endpoint in &self.body.endpoints {
                     self.endpoint(endpoint)?;
@@ -34,11 +36,7 @@ define_processor!(ServiceProcessor, RpServiceBody, self,
 
 impl<'p> ServiceProcessor<'p> {
     fn endpoint(&self, endpoint: &RpEndpoint) -> Result<()> {
-        let id = format!(
-            "{}_{}",
-            self.body.name,
-            endpoint.id_parts(Self::fragment_filter).join("_")
-        );
+        let id = endpoint_anchor(&self.body.name, endpoint, self.single_page);
 
         html!(self, h2 {class => "endpoint-title", id => id} => {
             self.name_until(&self.body.name)?;
@@ -82,7 +80,46 @@ impl<'p> ServiceProcessor<'p> {
             }
         });
 
+        self.deprecated(endpoint.deprecated.as_ref())?;
         self.doc(&endpoint.comment)?;
+
+        if endpoint.http.path.is_some() {
+            if endpoint.request.is_some() {
+                html!(self, div {class => "endpoint-accepts"} => {
+                    html!(self, span {class => "keyword"} ~ "accepts");
+                    html!(self, code {} ~ Escape(endpoint.http.accepts.as_str()));
+                });
+            }
+
+            if endpoint.response.is_some() {
+                html!(self, div {class => "endpoint-produces"} => {
+                    html!(self, span {class => "keyword"} ~ "produces");
+                    html!(self, code {} ~ Escape(endpoint.http.produces.as_str()));
+                });
+            }
+        }
+
+        for arg in endpoint.arguments.iter().filter(|a| !a.comment.is_empty()) {
+            html!(self, h3 {class => "argument-title"} => {
+                html!(self, span {class => "kind"} ~ "argument");
+                html!(self, span {class => "name"} ~ Escape(arg.ident.as_str()));
+            });
+
+            self.doc(&arg.comment)?;
+        }
+
+        for error in &endpoint.errors {
+            let status = format!("@ {}", error.status);
+
+            html!(self, h3 {class => "error-title"} => {
+                html!(self, span {class => "kind"} ~ "throws");
+                html!(self, span {class => "name"} ~ Escape(error.ident()));
+                html!(self, span {class => "sep"} ~ Escape(":"));
+                self.write_type(&error.ty)?;
+                html!(self, span {class => "status"} ~ Escape(status.as_str()));
+            });
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,29 @@
+//! Processor for single page documentation.
+//!
+//! Embeds already rendered sections into one complete document, together with the stylesheets
+//! and scripts of the documentation so that it doesn't depend on any other files.
+
+use core::errors::*;
+use doc_builder::DocBuilder;
+use processor::{Embedded, Processor};
+
+pub struct Data {
+    pub sections: Vec<String>,
+    pub embedded: Embedded,
+}
+
+define_processor!(SinglePageProcessor, Data, self,
+    process => {
+        self.write_page(|| {
+            for section in &self.body.sections {
+                self.out().write_str(section)?;
+            }
+
+            Ok(())
+        })
+    };
+
+    embedded => &self.body.embedded;
+);
+
+impl<'session> SinglePageProcessor<'session> {}
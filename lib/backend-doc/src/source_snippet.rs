@@ -0,0 +1,212 @@
+//! Snippets of the specifications that declarations were loaded from.
+
+use core::errors::*;
+use core::flavored::RpVersionedPackage;
+use core::{CoreFlavor, RelativePathBuf, Source, Span};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::PathBuf;
+use trans::Translated;
+
+/// A snippet of a source.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Snippet {
+    /// The content of the snippet, starting at the beginning of its first line.
+    pub content: String,
+    /// The first line of the snippet, starting at 1.
+    pub start_line: usize,
+    /// The last line of the snippet, starting at 1.
+    pub end_line: usize,
+}
+
+/// The content of a source, which is read once and shared by all declarations in it.
+pub struct SourceContent {
+    source: Source,
+    content: String,
+    /// Offsets where each line starts.
+    lines: Vec<usize>,
+}
+
+impl SourceContent {
+    pub fn new(source: Source, content: String) -> SourceContent {
+        let lines = ::std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(n, _)| n + 1))
+            .collect();
+
+        SourceContent {
+            source,
+            content,
+            lines,
+        }
+    }
+
+    /// Read the content of the given source.
+    pub fn read(source: &Source) -> Result<SourceContent> {
+        let mut content = String::new();
+        source.read()?.read_to_string(&mut content)?;
+        Ok(SourceContent::new(source.clone(), content))
+    }
+
+    /// Access the source that the content was read from.
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    /// Extract the snippet covered by the given span.
+    ///
+    /// Returns `None` if the span is empty, or doesn't fit in the source.
+    pub fn snippet(&self, span: Span) -> Option<Snippet> {
+        if span.start >= span.end {
+            return None;
+        }
+
+        self.content.get(span.start..span.end)?;
+
+        let start_line = self.line(span.start);
+        let end_line = self.line(span.end - 1);
+
+        // include the indentation of the first line.
+        let line_start = self.lines[start_line];
+
+        let start = if self.content[line_start..span.start].trim().is_empty() {
+            line_start
+        } else {
+            span.start
+        };
+
+        Some(Snippet {
+            content: self.content[start..span.end].to_string(),
+            start_line: start_line + 1,
+            end_line: end_line + 1,
+        })
+    }
+
+    /// Find the line, starting at 0, of the given offset.
+    fn line(&self, offset: usize) -> usize {
+        match self.lines.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        }
+    }
+}
+
+/// Contents of the sources of all documented packages.
+#[derive(Default)]
+pub struct Sources {
+    contents: BTreeMap<RpVersionedPackage, SourceContent>,
+}
+
+impl Sources {
+    /// Read the sources of all files in the given session.
+    pub fn load(session: &Translated<CoreFlavor>) -> Result<Sources> {
+        let mut contents = BTreeMap::new();
+
+        for (package, _) in session.for_each_file() {
+            if let Some(source) = session.source(package) {
+                contents.insert(package.clone(), SourceContent::read(source)?);
+            }
+        }
+
+        Ok(Sources { contents })
+    }
+
+    /// Access the content of the source for the given package.
+    pub fn get(&self, package: &RpVersionedPackage) -> Option<&SourceContent> {
+        self.contents.get(package)
+    }
+}
+
+/// Template for links to declarations in a source browser, like GitHub.
+///
+/// The following placeholders are replaced in the template:
+///
+/// * `{path}` - the path of the specification, relative to `root`.
+/// * `{line}` - the first line of the declaration.
+/// * `{end_line}` - the last line of the declaration.
+pub struct SourceUrl {
+    template: String,
+    root: PathBuf,
+}
+
+impl SourceUrl {
+    pub fn new(template: String, root: PathBuf) -> SourceUrl {
+        SourceUrl { template, root }
+    }
+
+    /// Build the URL to the given snippet of a source.
+    ///
+    /// Returns `None` unless the source is a file under the root directory, so specifications
+    /// which were loaded from a repository are not linked.
+    pub fn url(&self, source: &Source, snippet: &Snippet) -> Option<String> {
+        let path = source.path()?.strip_prefix(&self.root).ok()?;
+        let path = RelativePathBuf::from_path(path).ok()?;
+
+        let url = self
+            .template
+            .replace("{path}", path.as_str())
+            .replace("{line}", &snippet.start_line.to_string())
+            .replace("{end_line}", &snippet.end_line.to_string());
+
+        Some(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(content: &str) -> SourceContent {
+        SourceContent::new(Source::empty("test"), content.to_string())
+    }
+
+    fn snippet(content: &str, start_line: usize, end_line: usize) -> Option<Snippet> {
+        Some(Snippet {
+            content: content.to_string(),
+            start_line,
+            end_line,
+        })
+    }
+
+    #[test]
+    fn test_single_line() {
+        let c = content("type Foo {}\n");
+        assert_eq!(snippet("type Foo {}", 1, 1), c.snippet((0, 11).into()));
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let c = content("// header\ntype Foo {\n  a: string;\n}\ntype Bar {}\n");
+        assert_eq!(
+            snippet("type Foo {\n  a: string;\n}", 2, 4),
+            c.snippet((10, 35).into())
+        );
+        assert_eq!(snippet("type Bar {}", 5, 5), c.snippet((36, 47).into()));
+    }
+
+    #[test]
+    fn test_indentation() {
+        let c = content("type Foo {\n  type Bar {\n    a: string;\n  }\n}\n");
+        assert_eq!(
+            snippet("  type Bar {\n    a: string;\n  }", 2, 4),
+            c.snippet((13, 42).into())
+        );
+
+        // only whitespace before the span is included.
+        let c = content("a; type Foo {}\n");
+        assert_eq!(snippet("type Foo {}", 1, 1), c.snippet((3, 14).into()));
+    }
+
+    #[test]
+    fn test_end_of_file() {
+        let c = content("type Foo {}\ntype Bar {\n}");
+        assert_eq!(snippet("type Bar {\n}", 2, 3), c.snippet((12, 24).into()));
+        assert_eq!(None, c.snippet((12, 25).into()));
+    }
+
+    #[test]
+    fn test_empty_span() {
+        let c = content("type Foo {}\n");
+        assert_eq!(None, c.snippet(Span::empty()));
+        assert_eq!(None, c.snippet((4, 4).into()));
+    }
+}
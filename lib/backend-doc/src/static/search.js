@@ -0,0 +1,150 @@
+/**
+ * Search widget for reproto documentation.
+ *
+ * Loads `search-index.json` from the documentation root on first use and matches all entered
+ * terms against the name and documentation of each symbol.
+ *
+ * Single page documentation embeds the index in a `search-index` element instead.
+ */
+(function() {
+  var MAX_RESULTS = 20;
+
+  var input = document.getElementById("search");
+  var results = document.getElementById("search-results");
+
+  if (!input || !results) {
+    return;
+  }
+
+  // search.js lives in the root of the documentation.
+  var src = document.currentScript.src;
+  var root = src.substring(0, src.lastIndexOf("/"));
+  var index = null;
+  var loading = false;
+
+  function prepare(entries) {
+    index = entries.map(function(entry) {
+      entry.nameLower = (entry.package + "::" + entry.name).toLowerCase();
+      entry.docLower = entry.doc.toLowerCase();
+      return entry;
+    });
+  }
+
+  function load() {
+    if (index !== null || loading) {
+      return;
+    }
+
+    var embedded = document.getElementById("search-index");
+
+    if (embedded !== null) {
+      prepare(JSON.parse(embedded.textContent));
+      return;
+    }
+
+    loading = true;
+
+    var request = new XMLHttpRequest();
+    request.open("GET", root + "/search-index.json");
+
+    request.onload = function() {
+      loading = false;
+
+      if (request.status !== 200 && request.status !== 0) {
+        return;
+      }
+
+      prepare(JSON.parse(request.responseText));
+      search();
+    };
+
+    request.onerror = function() {
+      loading = false;
+    };
+
+    request.send();
+  }
+
+  function score(entry, terms) {
+    var total = 0;
+
+    for (var i = 0; i < terms.length; i++) {
+      var term = terms[i];
+
+      if (entry.nameLower.indexOf(term) !== -1) {
+        total += 10;
+      } else if (entry.docLower.indexOf(term) !== -1) {
+        total += 1;
+      } else {
+        return 0;
+      }
+    }
+
+    return total;
+  }
+
+  function clear() {
+    while (results.firstChild) {
+      results.removeChild(results.firstChild);
+    }
+  }
+
+  function search() {
+    clear();
+
+    var query = input.value.trim().toLowerCase();
+
+    if (index === null || query === "") {
+      return;
+    }
+
+    var terms = query.split(/\s+/);
+    var matches = [];
+
+    for (var i = 0; i < index.length; i++) {
+      var s = score(index[i], terms);
+
+      if (s > 0) {
+        matches.push({score: s, entry: index[i]});
+      }
+    }
+
+    matches.sort(function(a, b) {
+      if (a.score !== b.score) {
+        return b.score - a.score;
+      }
+
+      return a.entry.nameLower.length - b.entry.nameLower.length;
+    });
+
+    matches.slice(0, MAX_RESULTS).forEach(function(m) {
+      var li = document.createElement("li");
+
+      var kind = document.createElement("span");
+      kind.className = "kind";
+      kind.textContent = m.entry.kind;
+      li.appendChild(kind);
+
+      var a = document.createElement("a");
+      // links on a single page are only fragments.
+      a.href = m.entry.url.charAt(0) === "#" ? m.entry.url : root + "/" + m.entry.url;
+      a.textContent = m.entry.package + "::" + m.entry.name;
+      li.appendChild(a);
+
+      if (m.entry.doc !== "") {
+        var doc = document.createElement("span");
+        doc.className = "search-doc";
+        doc.textContent = m.entry.doc.split("\n")[0];
+        li.appendChild(doc);
+      }
+
+      results.appendChild(li);
+    });
+  }
+
+  input.addEventListener("focus", load);
+  input.addEventListener("input", function() {
+    load();
+    search();
+  });
+})();
@@ -0,0 +1,133 @@
+//! Custom themes, loaded at runtime from a directory.
+//!
+//! A theme directory may contain any of the following:
+//!
+//! * `doc.css` - replaces the stylesheet of the selected built-in theme.
+//! * `header.html` - inserted at the top of every page.
+//! * `footer.html` - inserted at the bottom of every page.
+//! * `static/` - copied as-is into `static/` of the output directory, e.g. for logos and fonts.
+//!
+//! Any `{{root}}` in `header.html` and `footer.html` is replaced with the relative path to the root
+//! of the documentation, so that static files can be referenced from every page.
+
+use core::errors::*;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const CSS_NAME: &str = "doc.css";
+pub const HEADER_NAME: &str = "header.html";
+pub const FOOTER_NAME: &str = "footer.html";
+pub const STATIC_NAME: &str = "static";
+
+#[derive(Debug, Default)]
+pub struct ThemeDir {
+    /// Stylesheet replacing the built-in theme.
+    pub css: Option<Vec<u8>>,
+    /// HTML inserted at the top of every page.
+    pub header: Option<String>,
+    /// HTML inserted at the bottom of every page.
+    pub footer: Option<String>,
+    /// Directory of static files to copy.
+    pub static_dir: Option<PathBuf>,
+}
+
+impl ThemeDir {
+    /// Load the theme from the given directory.
+    pub fn load(path: &Path) -> Result<ThemeDir> {
+        if !path.is_dir() {
+            return Err(format!("{}: theme directory does not exist", path.display()).into());
+        }
+
+        let css = read_optional(&path.join(CSS_NAME))?;
+        let header = read_optional_string(&path.join(HEADER_NAME))?;
+        let footer = read_optional_string(&path.join(FOOTER_NAME))?;
+
+        let static_dir = path.join(STATIC_NAME);
+        let static_dir = if static_dir.is_dir() {
+            Some(static_dir)
+        } else {
+            None
+        };
+
+        Ok(ThemeDir {
+            css,
+            header,
+            footer,
+            static_dir,
+        })
+    }
+
+    /// Render the header for a page at the given root.
+    pub fn header(&self, root: &str) -> Option<String> {
+        self.header.as_ref().map(|h| h.replace("{{root}}", root))
+    }
+
+    /// Render the footer for a page at the given root.
+    pub fn footer(&self, root: &str) -> Option<String> {
+        self.footer.as_ref().map(|f| f.replace("{{root}}", root))
+    }
+
+    /// Copy all static files into the given output directory.
+    pub fn write_static(&self, out_path: &Path) -> Result<()> {
+        if let Some(static_dir) = self.static_dir.as_ref() {
+            copy_dir(static_dir, &out_path.join(STATIC_NAME))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the given file, if it exists.
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let mut content = Vec::new();
+
+    File::open(path)
+        .and_then(|mut f| f.read_to_end(&mut content))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    Ok(Some(content))
+}
+
+/// Read the given file as a string, if it exists.
+fn read_optional_string(path: &Path) -> Result<Option<String>> {
+    match read_optional(path)? {
+        Some(content) => Ok(Some(
+            String::from_utf8(content).map_err(|e| format!("{}: {}", path.display(), e))?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Recursively copy the content of one directory into another.
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    if !target.is_dir() {
+        debug!("+dir: {}", target.display());
+        fs::create_dir_all(target)?;
+    }
+
+    for entry in fs::read_dir(source)? {
+        let path = entry?.path();
+
+        let name = match path.file_name() {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let target = target.join(name);
+
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+            continue;
+        }
+
+        debug!("+file: {}", target.display());
+        fs::copy(&path, &target).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
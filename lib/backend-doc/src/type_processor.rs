@@ -1,6 +1,7 @@
 //! Processor for service declarations.
 
 use core::errors::*;
+use core::ExampleGenerator;
 use core::flavored::RpTypeBody;
 use doc_builder::DocBuilder;
 use macros::FormatAttribute;
@@ -9,14 +10,21 @@ use processor::Processor;
 define_processor!(TypeProcessor, RpTypeBody, self,
     process => {
         self.write_doc(|| {
-            let id = self.body.name.join("_");
+            let id = self.anchor(&self.body.name);
 
             html!(self, section {id => &id, class => "section-content section-type"} => {
                 self.section_title("type", &self.body.name)?;
 
                 html!(self, div {class => "section-body"} => {
+                    self.deprecated(self.body.deprecated.as_ref())?;
                     self.doc(&self.body.comment)?;
+                    self.source(&self.body.name)?;
                     self.fields(self.body.fields.iter())?;
+
+                    let session = self.session();
+                    let mut examples = ExampleGenerator::new(|n| session.lookup_decl(n));
+                    self.example(examples.decl(session.lookup_decl(&self.body.name)?)?)?;
+
                     self.nested_decls(self.body.decls.iter())?;
                 });
             });
@@ -0,0 +1,54 @@
+//! Processor for union declarations.
+
+use core::errors::*;
+use core::flavored::{RpUnionBody, RpUnionVariant};
+use core::ExampleGenerator;
+use doc_builder::DocBuilder;
+use escape::Escape;
+use macros::FormatAttribute;
+use processor::Processor;
+
+define_processor!(UnionProcessor, RpUnionBody, self,
+    process => {
+        self.write_doc(|| {
+            let id = self.anchor(&self.body.name);
+
+            html!(self, section {id => &id, class => "section-content section-union"} => {
+                self.section_title("union", &self.body.name)?;
+
+                html!(self, div {class => "section-body"} => {
+                    self.deprecated(self.body.deprecated.as_ref())?;
+                    self.doc(&self.body.comment)?;
+                    self.source(&self.body.name)?;
+                    self.variants(self.body.variants.iter())?;
+
+                    let session = self.session();
+                    let mut examples = ExampleGenerator::new(|n| session.lookup_decl(n));
+                    self.example(examples.decl(session.lookup_decl(&self.body.name)?)?)?;
+                });
+            });
+
+            Ok(())
+        })
+    };
+
+    current_package => &self.body.name.package;
+);
+
+impl<'p> UnionProcessor<'p> {
+    fn variants<'b, I>(&self, variants: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'b RpUnionVariant>,
+    {
+        for variant in variants {
+            html!(self, h2 {class => "variant-title"} => {
+                html!(self, span {class => "kind"} ~ "variant");
+                html!(self, span {class => "variant-id"} ~ Escape(variant.ident.as_str()));
+                html!(self, span {} ~ ":");
+                self.write_type(&variant.ty)?;
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -1,15 +1,21 @@
 //! Backend for Go
 
-use backend::PackageProcessor;
+use backend::{PackageProcessor, UNKNOWN_VARIANT};
 use core::errors::*;
-use core::{Handle, Loc, RelativePathBuf};
+use core::{self, Handle, Loc, RelativePath, RelativePathBuf, RpDeprecated};
 use flavored::{
-    GoFlavor, GoName, RpEnumBody, RpField, RpInterfaceBody, RpPackage, RpTupleBody, RpTypeBody,
+    GoFlavor, GoName, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpPackage,
+    RpServiceBody, RpTupleBody, RpTypeBody,
 };
-use genco::go::Go;
-use genco::{IntoTokens, Tokens};
+use genco::go::{imported, Go};
+use genco::{IntoTokens, Quoted, Tokens};
+use std::io::Write;
+use std::rc::Rc;
 use trans::{self, Translated};
-use {EnumAdded, FieldAdded, FileSpec, InterfaceAdded, Options, Tags, TupleAdded, EXT};
+use {
+    EnumAdded, FieldAdded, FileSpec, InterfaceAdded, NewtypeAdded, Options, ServiceAdded, Tags,
+    TupleAdded, EXT, GO_MOD,
+};
 
 /// Documentation comments.
 pub struct Comments<'el, S: 'el>(pub &'el [S]);
@@ -26,10 +32,52 @@ impl<'el, S: 'el + AsRef<str>> IntoTokens<'el, Go<'el>> for Comments<'el, S> {
     }
 }
 
+/// Deprecation notice, following the `Deprecated: ` convention of Go.
+pub struct Deprecated<'el> {
+    deprecated: &'el Option<RpDeprecated>,
+    /// Separate the notice from preceding documentation with an empty line.
+    separate: bool,
+}
+
+impl<'el> Deprecated<'el> {
+    /// Build a deprecation notice which follows the given documentation.
+    pub fn after<S>(comment: &[S], deprecated: &'el Option<RpDeprecated>) -> Deprecated<'el> {
+        Deprecated {
+            deprecated,
+            separate: !comment.is_empty(),
+        }
+    }
+}
+
+impl<'el> IntoTokens<'el, Go<'el>> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+
+        let deprecated = match *self.deprecated {
+            Some(ref deprecated) => deprecated,
+            None => return t,
+        };
+
+        if self.separate {
+            t.push("//");
+        }
+
+        match deprecated.note() {
+            Some(note) => t.push(toks!["// Deprecated: ", note]),
+            None => t.push("// Deprecated: Do not use."),
+        }
+
+        t
+    }
+}
+
 pub struct Compiler<'el> {
     pub env: &'el Translated<GoFlavor>,
     options: Options,
     handle: &'el Handle,
+    new_error: Go<'static>,
+    itoa: Go<'static>,
+    must_compile: Go<'static>,
 }
 
 impl<'el> Compiler<'el> {
@@ -42,6 +90,9 @@ impl<'el> Compiler<'el> {
             env,
             options,
             handle,
+            new_error: imported("errors", "New"),
+            itoa: imported("strconv", "Itoa"),
+            must_compile: imported("regexp", "MustCompile"),
         };
 
         Ok(c)
@@ -51,6 +102,7 @@ impl<'el> Compiler<'el> {
         &self,
         name: &'el GoName,
         comment: &'el [String],
+        deprecated: &'el Option<RpDeprecated>,
         fields: I,
     ) -> Result<Tokens<'el, Go<'el>>>
     where
@@ -59,6 +111,7 @@ impl<'el> Compiler<'el> {
         let mut t = Tokens::new();
 
         t.push(Comments(comment));
+        t.push_unless_empty(Deprecated::after(comment, deprecated));
         t.push(toks!["type ", name, " struct {"]);
 
         t.nested({
@@ -85,6 +138,7 @@ impl<'el> Compiler<'el> {
 
                 t.push_into(|t| {
                     t.push(Comments(&f.comment));
+                    t.push_unless_empty(Deprecated::after(&f.comment, &f.deprecated));
                     t.push(base.join_spacing());
                 });
             }
@@ -96,10 +150,157 @@ impl<'el> Compiler<'el> {
         Ok(t)
     }
 
+    /// Build the `String()` method of an enum, which returns the value of string variants and the
+    /// name of numeric variants.
+    fn enum_string(&self, body: &'el RpEnumBody, unknown_variant: bool) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "func (this ", &body.name, ") String() string {");
+
+        t.nested_into(|t| {
+            t.push("switch this {");
+
+            match body.variants {
+                core::RpVariants::String { ref variants } => {
+                    for v in variants {
+                        t.push_into(|t| {
+                            push!(t, "case ", &body.name, "_", v.ident.as_str(), ":");
+                            nested!(t, "return ", v.value.as_str().quoted());
+                        });
+                    }
+                }
+                core::RpVariants::Number { ref variants } => {
+                    for v in variants {
+                        t.push_into(|t| {
+                            push!(t, "case ", &body.name, "_", v.ident.as_str(), ":");
+                            nested!(t, "return ", v.ident.as_str().quoted());
+                        });
+                    }
+                }
+            }
+
+            if unknown_variant {
+                t.push_into(|t| {
+                    push!(t, "case ", &body.name, "_", UNKNOWN_VARIANT, ":");
+                    nested!(t, "return ", UNKNOWN_VARIANT.quoted());
+                });
+            }
+
+            t.push_into(|t| {
+                let prefix = Rc::new(format!("{}(", body.name.name)).quoted();
+
+                push!(t, "default:");
+                nested!(
+                    t,
+                    "return ",
+                    prefix,
+                    " + ",
+                    self.itoa.clone(),
+                    "(int(this)) + ",
+                    ")".quoted()
+                );
+            });
+
+            t.push("}");
+        });
+
+        t.push("}");
+        t
+    }
+
+    /// Build the `Parse<Name>` function of an enum, which is the inverse of `String()`.
+    ///
+    /// Aliases of string variants are also accepted.
+    fn enum_parse(&self, body: &'el RpEnumBody, unknown_variant: bool) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "func Parse",
+            &body.name,
+            "(s string) (",
+            &body.name,
+            ", error) {"
+        );
+
+        t.nested_into(|t| {
+            t.push("switch s {");
+
+            match body.variants {
+                core::RpVariants::String { ref variants } => {
+                    for v in variants {
+                        let mut values = Tokens::new();
+                        values.append(v.value.as_str().quoted());
+
+                        for alias in &v.aliases {
+                            values.append(alias.as_str().quoted());
+                        }
+
+                        t.push_into(|t| {
+                            push!(t, "case ", values.join(", "), ":");
+                            nested!(t, "return ", &body.name, "_", v.ident.as_str(), ", nil");
+                        });
+                    }
+                }
+                core::RpVariants::Number { ref variants } => {
+                    for v in variants {
+                        t.push_into(|t| {
+                            push!(t, "case ", v.ident.as_str().quoted(), ":");
+                            nested!(t, "return ", &body.name, "_", v.ident.as_str(), ", nil");
+                        });
+                    }
+                }
+            }
+
+            t.push_into(|t| {
+                push!(t, "default:");
+
+                if unknown_variant {
+                    nested!(t, "return ", &body.name, "_", UNKNOWN_VARIANT, ", nil");
+                } else {
+                    let message = Rc::new(format!("bad {}: ", body.name.name)).quoted();
+                    nested!(
+                        t,
+                        "return 0, ",
+                        self.new_error.clone(),
+                        "(",
+                        message,
+                        " + s)"
+                    );
+                }
+            });
+
+            t.push("}");
+        });
+
+        t.push("}");
+        t
+    }
+
     pub fn compile(&self) -> Result<()> {
         let files = self.populate_files()?;
+        self.write_go_mod()?;
         self.write_files(files)
     }
+
+    /// Write the `go.mod` file in the root of the output directory, if configured.
+    fn write_go_mod(&self) -> Result<()> {
+        let (module_path, go) = match (&self.options.module_path, &self.options.go_mod) {
+            (&Some(ref module_path), &Some(ref go)) => (module_path, go),
+            _ => return Ok(()),
+        };
+
+        let path = RelativePath::new(GO_MOD);
+
+        debug!("+go.mod: {}", path.display());
+
+        let mut f = self.handle.create(path)?;
+        writeln!(f, "module {}", module_path)?;
+        writeln!(f)?;
+        writeln!(f, "go {}", go)?;
+        f.flush()?;
+        Ok(())
+    }
 }
 
 impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
@@ -132,6 +333,7 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
         out.0.push(self.process_struct(
             &body.name,
             &body.comment,
+            &body.deprecated,
             body.fields.iter().map(Loc::borrow),
         )?);
 
@@ -141,6 +343,7 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
     fn process_tuple(&self, out: &mut Self::Out, body: &'el RpTupleBody) -> Result<()> {
         out.0.try_push_into::<Error, _>(|t| {
             t.push(Comments(&body.comment));
+            t.push_unless_empty(Deprecated::after(&body.comment, &body.deprecated));
             t.push(toks!["type ", &body.name, " struct {"]);
 
             t.nested({
@@ -160,6 +363,7 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
 
                     t.push_into(|t| {
                         t.push(Comments(&f.comment));
+                        t.push_unless_empty(Deprecated::after(&f.comment, &f.deprecated));
                         t.push(base.join_spacing());
                     });
                 }
@@ -183,11 +387,21 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
     }
 
     fn process_enum(&self, out: &mut Self::Out, body: &'el RpEnumBody) -> Result<()> {
+        let unknown_variant = self.options.unknown_variant;
+
+        if unknown_variant && body.variants.iter().any(|v| v.ident() == UNKNOWN_VARIANT) {
+            return Err(format!(
+                "{}: variant `{}` conflicts with the generated unknown variant",
+                body.name, UNKNOWN_VARIANT
+            ).into());
+        }
+
         out.0.push({
             let mut t = Tokens::new();
 
             t.push_into(|t| {
                 t.push(Comments(&body.comment));
+                t.push_unless_empty(Deprecated::after(&body.comment, &body.deprecated));
                 t.push(toks!["type ", &body.name, " int"])
             });
 
@@ -197,6 +411,8 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
                     let mut it = body.variants.iter();
 
                     if let Some(v) = it.next() {
+                        t.push_unless_empty(Comments(v.comment));
+                        t.push_unless_empty(Deprecated::after(v.comment, v.deprecated));
                         t.push(toks![
                             &body.name,
                             "_",
@@ -208,12 +424,21 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
                     }
 
                     while let Some(v) = it.next() {
+                        t.push_unless_empty(Comments(v.comment));
+                        t.push_unless_empty(Deprecated::after(v.comment, v.deprecated));
                         t.push(toks![&body.name, "_", v.ident.as_str(),]);
                     }
+
+                    if unknown_variant {
+                        t.push(toks![&body.name, "_", UNKNOWN_VARIANT]);
+                    }
                 });
                 t.push(")");
             });
 
+            t.push(self.enum_string(body, unknown_variant));
+            t.push(self.enum_parse(body, unknown_variant));
+
             t.join_line_spacing()
         });
 
@@ -222,6 +447,7 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
                 container: &mut out.0,
                 name: &body.name,
                 body: body,
+                unknown_variant: unknown_variant,
             })?;
         }
 
@@ -234,6 +460,7 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
 
             t.try_push_into::<Error, _>(|t| {
                 t.push_unless_empty(Comments(&body.comment));
+                t.push_unless_empty(Deprecated::after(&body.comment, &body.deprecated));
                 push!(t, "type ", &body.name, " struct {");
 
                 t.nested_into(|t| {
@@ -254,6 +481,7 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
                         self.process_struct(
                             &sub_type.name,
                             &sub_type.comment,
+                            &sub_type.deprecated,
                             body.fields
                                 .iter()
                                 .chain(sub_type.fields.iter())
@@ -283,4 +511,131 @@ impl<'el> PackageProcessor<'el, GoFlavor, GoName> for Compiler<'el> {
 
         Ok(())
     }
+
+    fn process_newtype(&self, out: &mut Self::Out, body: &'el RpNewtypeBody) -> Result<()> {
+        let name = &body.name;
+        let ty = Loc::borrow(&body.ty);
+
+        out.0.push({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                t.push(Comments(&body.comment));
+                t.push_unless_empty(Deprecated::after(&body.comment, &body.deprecated));
+                push!(t, "type ", name, " struct {");
+                nested!(t, "value ", ty.clone());
+                push!(t, "}");
+            });
+
+            if let Some(ref pattern) = body.validate.pattern {
+                let pattern = Rc::new(pattern.to_string());
+
+                t.push(toks![
+                    "var pattern",
+                    name,
+                    " = ",
+                    self.must_compile.clone(),
+                    "(",
+                    pattern.quoted(),
+                    ")",
+                ]);
+            }
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "// New",
+                    name,
+                    " wraps the given value, after it has been validated."
+                );
+                push!(
+                    t,
+                    "func New",
+                    name,
+                    "(value ",
+                    ty.clone(),
+                    ") (",
+                    name,
+                    ", error) {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "if err := Validate", name, "(value); err != nil {");
+                    nested!(t, "return ", name, "{}, err");
+                    push!(t, "}");
+                    push!(t, "return ", name, "{value: value}, nil");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "// Validate",
+                    name,
+                    " validates a value before it is wrapped."
+                );
+
+                match body.validate.pattern {
+                    Some(ref pattern) => {
+                        let error = Rc::new(format!("value does not match pattern `{}`", pattern));
+
+                        push!(t, "func Validate", name, "(value ", ty.clone(), ") error {");
+
+                        t.nested_into(|t| {
+                            push!(t, "if !pattern", name, ".MatchString(value) {");
+                            nested!(
+                                t,
+                                "return ",
+                                self.new_error.clone(),
+                                "(",
+                                error.quoted(),
+                                ")"
+                            );
+                            push!(t, "}");
+                            push!(t, "return nil");
+                        });
+                    }
+                    None => {
+                        push!(t, "func Validate", name, "(_ ", ty.clone(), ") error {");
+                        nested!(t, "return nil");
+                    }
+                }
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "// Value accesses the wrapped value.");
+                push!(t, "func (this ", name, ") Value() ", ty.clone(), " {");
+                nested!(t, "return this.value");
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        for g in &self.options.newtype_gens {
+            g.generate(NewtypeAdded {
+                container: &mut out.0,
+                name: name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_service(&self, out: &mut Self::Out, body: &'el RpServiceBody) -> Result<()> {
+        for g in &self.options.service_gens {
+            g.generate(ServiceAdded {
+                container: &mut out.0,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
 }
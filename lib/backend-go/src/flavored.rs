@@ -6,7 +6,7 @@ use backend::package_processor;
 use core::errors::Result;
 use core::{
     self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberKind,
-    RpNumberType, RpStringType, Translate, Translator,
+    RpNumberType, RpScalarType, RpStringType, Translate, Translator,
 };
 use genco::go::{array, imported, interface, local, map, Go};
 use genco::{Cons, Element};
@@ -17,6 +17,20 @@ use std::rc::Rc;
 use trans::Packages;
 use TYPE_SEP;
 
+#[derive(Debug, Clone)]
+pub struct GoEndpoint {
+    pub endpoint: RpEndpoint,
+    pub http1: Option<RpEndpointHttp1>,
+}
+
+impl Deref for GoEndpoint {
+    type Target = RpEndpoint;
+
+    fn deref(&self) -> &Self::Target {
+        &self.endpoint
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GoFlavor;
 
@@ -24,7 +38,7 @@ impl Flavor for GoFlavor {
     type Type = Go<'static>;
     type Name = GoName;
     type Field = RpField;
-    type Endpoint = RpEndpoint;
+    type Endpoint = GoEndpoint;
     type Package = RpPackage;
     type EnumType = Go<'static>;
 }
@@ -56,11 +70,15 @@ impl package_processor::Name<GoFlavor> for GoName {
 /// Responsible for translating RpType -> Go type.
 pub struct GoFlavorTranslator {
     package_translator: Rc<Packages>,
+    module_path: Option<String>,
 }
 
 impl GoFlavorTranslator {
-    pub fn new(package_translator: Rc<Packages>) -> Self {
-        Self { package_translator }
+    pub fn new(package_translator: Rc<Packages>, module_path: Option<String>) -> Self {
+        Self {
+            package_translator,
+            module_path,
+        }
     }
 }
 
@@ -68,7 +86,7 @@ impl FlavorTranslator for GoFlavorTranslator {
     type Source = CoreFlavor;
     type Target = GoFlavor;
 
-    translator_defaults!(Self, field, endpoint);
+    translator_defaults!(Self, field);
 
     fn translate_number(&self, number: RpNumberType) -> Result<Go<'static>> {
         match number.kind {
@@ -76,6 +94,11 @@ impl FlavorTranslator for GoFlavorTranslator {
             RpNumberKind::U64 => Ok(local("uint64")),
             RpNumberKind::I32 => Ok(local("int32")),
             RpNumberKind::I64 => Ok(local("int64")),
+            // NB: Go doesn't have native 128-bit or decimal numbers, `json.Number` retains the
+            // exact number as it was encoded.
+            RpNumberKind::U128 | RpNumberKind::I128 | RpNumberKind::Decimal => {
+                Ok(imported("encoding/json", "Number"))
+            }
         }
     }
 
@@ -99,10 +122,28 @@ impl FlavorTranslator for GoFlavorTranslator {
         Ok(local("string"))
     }
 
+    fn translate_date(&self) -> Result<Go<'static>> {
+        Ok(local("string"))
+    }
+
+    fn translate_time(&self) -> Result<Go<'static>> {
+        Ok(local("string"))
+    }
+
+    fn translate_duration(&self) -> Result<Go<'static>> {
+        // NB: time.Duration is encoded as a number of nanoseconds by encoding/json.
+        Ok(local("string"))
+    }
+
     fn translate_array(&self, argument: Go<'static>) -> Result<Go<'static>> {
         Ok(array(argument))
     }
 
+    fn translate_set(&self, argument: Go<'static>) -> Result<Go<'static>> {
+        // NB: Go doesn't have a set type which encodes as a JSON array, so use a slice.
+        Ok(array(argument))
+    }
+
     fn translate_map(&self, key: Go<'static>, value: Go<'static>) -> Result<Go<'static>> {
         Ok(map(key, value))
     }
@@ -112,7 +153,24 @@ impl FlavorTranslator for GoFlavorTranslator {
     }
 
     fn translate_bytes(&self) -> Result<Go<'static>> {
-        Ok(local("string"))
+        // NB: encoding/json marshals byte slices as base64 strings.
+        Ok(array(local("byte")))
+    }
+
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<Go<'static>> {
+        let ty = match scalar.mapping("go") {
+            Some(ty) => ty,
+            None => return self.translate_scalar_base(scalar.base),
+        };
+
+        // NB: the type is qualified by the path of its module, like `github.com/google/uuid.UUID`.
+        match ty.rfind('.') {
+            Some(index) => Ok(imported(
+                ty[..index].to_string(),
+                ty[index + 1..].to_string(),
+            )),
+            None => Ok(local(ty.to_string())),
+        }
     }
 
     fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<Go<'static>> {
@@ -121,7 +179,11 @@ impl FlavorTranslator for GoFlavorTranslator {
         // imported
         if let Some(_) = name.prefix {
             let module = name.package.join(TYPE_SEP);
-            let module = format!("../{}", module);
+
+            let module = match self.module_path {
+                Some(ref module_path) => format!("{}/{}", module_path, module),
+                None => format!("../{}", module),
+            };
 
             return Ok(imported(module, ident));
         }
@@ -152,6 +214,21 @@ impl FlavorTranslator for GoFlavorTranslator {
         });
     }
 
+    fn translate_endpoint<T>(
+        &self,
+        translator: &T,
+        diag: &mut Diagnostics,
+        endpoint: core::RpEndpoint<CoreFlavor>,
+    ) -> Result<GoEndpoint>
+    where
+        T: Translator<Source = CoreFlavor, Target = GoFlavor>,
+    {
+        let endpoint = endpoint.translate(diag, translator)?;
+        let http1 = RpEndpointHttp1::from_endpoint(&endpoint);
+
+        Ok(GoEndpoint { endpoint, http1 })
+    }
+
     fn translate_package(&self, source: RpVersionedPackage) -> Result<RpPackage> {
         self.package_translator.translate_package(source)
     }
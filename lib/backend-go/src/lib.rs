@@ -23,23 +23,64 @@ use backend::{Initializer, IntoBytes};
 use compiler::Compiler;
 use core::errors::Result;
 use core::{CoreFlavor, Handle};
-use flavored::{GoName, RpEnumBody, RpField, RpInterfaceBody, RpPackage, RpTupleBody};
+use flavored::{
+    GoName, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpPackage, RpServiceBody,
+    RpTupleBody,
+};
 use genco::go::{self, Go};
 use genco::{Element, IntoTokens, Tokens};
-use manifest::{Lang, Manifest, NoModule, TryFromToml};
-use naming::Naming;
+use manifest::{Lang, Manifest, ModuleDoc, ModuleOption, NoModule, TryFromToml};
+use naming::{Keywords, Naming};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::rc::Rc;
 use trans::Session;
 
 const TYPE_SEP: &str = "_";
 const EXT: &str = "go";
+const GO_MOD: &str = "go.mod";
 
 #[derive(Clone, Copy, Default, Debug)]
 pub struct GoLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "encoding/json",
+        description: "Implement JSON encoding and decoding using `encoding/json`.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "go_mod",
+        description: "Generate a `go.mod` for the generated code.",
+        options: &[
+            ModuleOption {
+                name: "module_path",
+                ty: "string",
+                default: None,
+                description: "Path of the Go module, like `github.com/example/models` (required).",
+            },
+            ModuleOption {
+                name: "go",
+                ty: "string",
+                default: Some("1.11"),
+                description: "Go version to declare in `go.mod`.",
+            },
+        ],
+    },
+    ModuleDoc {
+        name: "net/http",
+        description: "Generate HTTP clients for services using `net/http`.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "unknown_variant",
+        description: "Add a catch-all constant to enums.",
+        options: &[],
+    },
+];
+
 impl Lang for GoLang {
     lang_base!(GoModule, compile);
 
@@ -51,22 +92,29 @@ impl Lang for GoLang {
         true
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![]
+    fn keywords(&self) -> Option<&'static Keywords> {
+        Some(&naming::keywords::GO)
     }
 
     fn field_ident_naming(&self) -> Option<Box<Naming>> {
         Some(Box::new(naming::to_upper_camel()))
     }
 
-    fn modules(&self) -> Option<String> {
-        Some(String::from("encodingJson"))
+    fn endpoint_ident_naming(&self) -> Option<Box<Naming>> {
+        Some(Box::new(naming::to_upper_camel()))
+    }
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
     }
 }
 
 #[derive(Debug)]
 pub enum GoModule {
     EncodingJson,
+    GoMod(module::GoModConfig),
+    NetHttp,
+    UnknownVariant,
 }
 
 impl TryFromToml for GoModule {
@@ -75,6 +123,9 @@ impl TryFromToml for GoModule {
 
         let result = match id {
             "encoding/json" => EncodingJson,
+            "go_mod" => GoMod(module::GoModConfig::default()),
+            "net/http" => NetHttp,
+            "unknown_variant" => UnknownVariant,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -86,6 +137,9 @@ impl TryFromToml for GoModule {
 
         let result = match id {
             "encoding/json" => EncodingJson,
+            "go_mod" => GoMod(value.try_into()?),
+            "net/http" => NetHttp,
+            "unknown_variant" => UnknownVariant,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -98,6 +152,16 @@ pub struct Options {
     pub enum_gens: Vec<Box<EnumCodegen>>,
     pub tuple_gens: Vec<Box<TupleCodegen>>,
     pub interface_gens: Vec<Box<InterfaceCodegen>>,
+    pub newtype_gens: Vec<Box<NewtypeCodegen>>,
+    pub service_gens: Vec<Box<ServiceCodegen>>,
+    /// Path of the Go module that packages are generated into.
+    ///
+    /// If set, packages are imported by their full module path instead of relative paths.
+    pub module_path: Option<String>,
+    /// Go version to declare in the generated `go.mod`, if one should be generated.
+    pub go_mod: Option<String>,
+    /// Generate an `Unknown` constant for enums, which is used for unknown values when decoding.
+    pub unknown_variant: bool,
 }
 
 impl Options {
@@ -107,6 +171,11 @@ impl Options {
             enum_gens: Vec::new(),
             tuple_gens: Vec::new(),
             interface_gens: Vec::new(),
+            newtype_gens: Vec::new(),
+            service_gens: Vec::new(),
+            module_path: None,
+            go_mod: None,
+            unknown_variant: false,
         }
     }
 }
@@ -121,6 +190,9 @@ pub fn options(modules: Vec<GoModule>) -> Result<Options> {
 
         let initializer: Box<Initializer<Options = Options>> = match m {
             EncodingJson => Box::new(module::EncodingJson::new()),
+            GoMod(config) => Box::new(module::GoMod::new(config)),
+            NetHttp => Box::new(module::NetHttp::new()),
+            UnknownVariant => Box::new(module::UnknownVariant::new()),
         };
 
         initializer.initialize(&mut options)?;
@@ -176,6 +248,8 @@ pub struct EnumAdded<'a, 'el: 'a> {
     pub container: &'a mut Tokens<'el, Go<'el>>,
     pub name: &'el GoName,
     pub body: &'el RpEnumBody,
+    /// If an `Unknown` constant has been generated for unknown values.
+    pub unknown_variant: bool,
 }
 
 codegen!(EnumCodegen, EnumAdded);
@@ -198,6 +272,24 @@ pub struct InterfaceAdded<'a, 'el: 'a> {
 
 codegen!(InterfaceCodegen, InterfaceAdded);
 
+/// Event emitted when a newtype has been added.
+pub struct NewtypeAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Go<'el>>,
+    pub name: &'el GoName,
+    pub body: &'el RpNewtypeBody,
+}
+
+codegen!(NewtypeCodegen, NewtypeAdded);
+
+/// Event emitted when a service has been added.
+pub struct ServiceAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Go<'el>>,
+    pub name: &'el GoName,
+    pub body: &'el RpServiceBody,
+}
+
+codegen!(ServiceCodegen, ServiceAdded);
+
 pub enum TagValue {
     String(String),
 }
@@ -211,14 +303,16 @@ impl From<TagValue> for Element<'static, Go<'static>> {
 }
 
 /// Structure for Tags - a type of Go metadata
+///
+/// Tags are emitted ordered by key, so that output is stable between runs.
 pub struct Tags {
-    values: HashMap<String, Vec<TagValue>>,
+    values: BTreeMap<String, Vec<TagValue>>,
 }
 
 impl Tags {
     pub fn new() -> Self {
         Self {
-            values: HashMap::new(),
+            values: BTreeMap::new(),
         }
     }
 
@@ -275,12 +369,17 @@ impl<'el> IntoTokens<'el, Go<'el>> for Tags {
 }
 
 fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let modules = manifest::checked_modules(manifest.modules)?;
+    let options = options(modules)?;
+
     let packages = session.packages()?;
 
-    let translator = session.translator(flavored::GoFlavorTranslator::new(packages))?;
+    let translator = session.translator(flavored::GoFlavorTranslator::new(
+        packages,
+        options.module_path.clone(),
+    ))?;
+
     let session = session.translate(translator)?;
 
-    let modules = manifest::checked_modules(manifest.modules)?;
-    let options = options(modules)?;
     Compiler::new(&session, options, handle)?.compile()
 }
@@ -1,15 +1,16 @@
 //! encoding/json module for Go
 
-use backend::Initializer;
+use backend::{Initializer, UNKNOWN_VARIANT};
 use core;
 use core::errors::{Error, Result};
+use core::Loc;
 use flavored::{GoName, RpEnumBody, RpInterfaceBody, RpSubType, RpTupleBody};
 use genco::go::{imported, Go};
 use genco::{Quoted, Tokens};
 use std::rc::Rc;
 use {
-    EnumAdded, EnumCodegen, FieldAdded, FieldCodegen, InterfaceAdded, InterfaceCodegen, Options,
-    TupleAdded, TupleCodegen,
+    EnumAdded, EnumCodegen, FieldAdded, FieldCodegen, InterfaceAdded, InterfaceCodegen,
+    NewtypeAdded, NewtypeCodegen, Options, TupleAdded, TupleCodegen,
 };
 
 pub struct Module {}
@@ -29,6 +30,7 @@ impl Initializer for Module {
         options.enum_gens.push(Box::new(codegen.clone()));
         options.tuple_gens.push(Box::new(codegen.clone()));
         options.interface_gens.push(Box::new(codegen.clone()));
+        options.newtype_gens.push(Box::new(codegen.clone()));
         Ok(())
     }
 }
@@ -61,6 +63,10 @@ impl FieldCodegen for Codegen {
             tags.push_str("json", "omitempty");
         }
 
+        if field.is_number_as_string() {
+            tags.push_str("json", "string");
+        }
+
         return Ok(());
     }
 }
@@ -71,10 +77,11 @@ impl EnumCodegen for Codegen {
             container,
             name,
             body,
+            unknown_variant,
             ..
         } = e;
 
-        container.push(unmarshal_json(self, name, body));
+        container.push(unmarshal_json(self, name, body, unknown_variant));
         container.push(marshal_json(self, name, body));
 
         return Ok(());
@@ -83,6 +90,7 @@ impl EnumCodegen for Codegen {
             c: &Codegen,
             name: &'el GoName,
             body: &'el RpEnumBody,
+            unknown_variant: bool,
         ) -> Tokens<'el, Go<'el>> {
             let mut t = Tokens::new();
 
@@ -104,14 +112,29 @@ impl EnumCodegen for Codegen {
 
                     match body.variants {
                         core::RpVariants::String { ref variants } => for v in variants {
+                            // NB: aliases are only accepted when decoding.
+                            let mut values = Tokens::new();
+                            values.append(v.value.as_str().quoted());
+
+                            for alias in &v.aliases {
+                                values.append(alias.as_str().quoted());
+                            }
+
                             t.push_into(|t| {
-                                push!(t, "case ", v.value.as_str().quoted(), ":");
+                                push!(t, "case ", values.join(", "), ":");
                                 nested!(t, "*this = ", name, "_", v.ident.as_str());
                             });
                         },
                         core::RpVariants::Number { ref variants } => for v in variants {
+                            let mut values = Tokens::new();
+                            values.append(v.value.to_string());
+
+                            for alias in &v.aliases {
+                                values.append(alias.to_string());
+                            }
+
                             t.push_into(|t| {
-                                push!(t, "case ", v.value.to_string(), ":");
+                                push!(t, "case ", values.join(", "), ":");
                                 nested!(t, "*this = ", name, "_", v.ident.as_str());
                             });
                         },
@@ -119,7 +142,12 @@ impl EnumCodegen for Codegen {
 
                     t.push_into(|t| {
                         push!(t, "default:");
-                        nested!(t, "return ", c.new_error, "(", "bad value".quoted(), ")");
+
+                        if unknown_variant {
+                            nested!(t, "*this = ", name, "_", UNKNOWN_VARIANT);
+                        } else {
+                            nested!(t, "return ", c.new_error, "(", "bad value".quoted(), ")");
+                        }
                     });
 
                     t.push("}");
@@ -337,6 +365,59 @@ impl TupleCodegen for Codegen {
     }
 }
 
+impl NewtypeCodegen for Codegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded {
+            container,
+            name,
+            body,
+        } = e;
+
+        container.push({
+            let mut t = Tokens::new();
+
+            push!(t, "func (this *", name, ") UnmarshalJSON(b []byte) error {");
+
+            t.nested_into(|t| {
+                // NB: unmarshal through the constructor, so that values are always validated.
+                push!(t, "var value ", Loc::borrow(&body.ty).clone());
+
+                let unmarshal = self.unmarshal.clone();
+
+                t.push_into(|t| {
+                    push!(t, "if err := ", unmarshal, "(b, &value); err != nil {");
+                    nested!(t, "return err");
+                    push!(t, "}");
+                });
+
+                t.push_into(|t| {
+                    push!(t, "v, err := New", name, "(value)");
+                    push!(t, "if err != nil {");
+                    nested!(t, "return err");
+                    push!(t, "}");
+                });
+
+                push!(t, "*this = v");
+                push!(t, "return nil");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        container.push({
+            let mut t = Tokens::new();
+
+            push!(t, "func (this ", name, ") MarshalJSON() ([]byte, error) {");
+            nested!(t, "return ", self.marshal.clone(), "(this.value)");
+            push!(t, "}");
+            t
+        });
+
+        Ok(())
+    }
+}
+
 impl InterfaceCodegen for Codegen {
     fn generate(&self, e: InterfaceAdded) -> Result<()> {
         let InterfaceAdded {
@@ -0,0 +1,49 @@
+//! Module that lays out generated packages as a proper Go module.
+
+use backend::Initializer;
+use core::errors::Result;
+use Options;
+
+/// Go version declared in `go.mod` unless configured.
+const DEFAULT_GO_VERSION: &str = "1.11";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// The path of the Go module, like `github.com/example/models`.
+    module_path: Option<String>,
+    /// The Go version to declare in `go.mod`.
+    go: Option<String>,
+}
+
+pub struct Module {
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        let module_path = self
+            .config
+            .module_path
+            .as_ref()
+            .ok_or_else(|| "go_mod: missing required option `module_path`")?;
+
+        let go = self
+            .config
+            .go
+            .as_ref()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_GO_VERSION);
+
+        options.module_path = Some(module_path.trim_right_matches('/').to_string());
+        options.go_mod = Some(go.to_string());
+        Ok(())
+    }
+}
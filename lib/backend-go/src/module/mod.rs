@@ -1,3 +1,9 @@
 mod encoding_json;
+mod go_mod;
+mod net_http;
+mod unknown_variant;
 
 pub use self::encoding_json::Module as EncodingJson;
+pub use self::go_mod::{Config as GoModConfig, Module as GoMod};
+pub use self::net_http::Module as NetHttp;
+pub use self::unknown_variant::Module as UnknownVariant;
@@ -0,0 +1,753 @@
+//! net/http module for Go
+//!
+//! Generates an interface for every service, together with an `http.Handler` serving it and a
+//! client calling it.
+
+use backend::Initializer;
+use compiler::{Comments, Deprecated};
+use core::errors::Result;
+use core::{RpAccept, RpDeprecated};
+use flavored::{GoEndpoint, GoName, RpChannel, RpEndpointArgument, RpEndpointHttp1, RpPathPart};
+use genco::go::{imported, local, Go};
+use genco::{Quoted, Tokens};
+use {Options, ServiceAdded, ServiceCodegen};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        options.service_gens.push(Box::new(Codegen::new()));
+        Ok(())
+    }
+}
+
+struct Codegen {
+    context: Go<'static>,
+    handler: Go<'static>,
+    new_serve_mux: Go<'static>,
+    response_writer: Go<'static>,
+    request: Go<'static>,
+    new_request: Go<'static>,
+    client: Go<'static>,
+    error: Go<'static>,
+    status_bad_request: Go<'static>,
+    status_internal_server_error: Go<'static>,
+    status_no_content: Go<'static>,
+    new_decoder: Go<'static>,
+    new_encoder: Go<'static>,
+    buffer: Go<'static>,
+    read_all: Go<'static>,
+    new_reader: Go<'static>,
+    new_error: Go<'static>,
+    errorf: Go<'static>,
+    sprint: Go<'static>,
+    path_escape: Go<'static>,
+    values: Go<'static>,
+    trim_right: Go<'static>,
+    parse_int: Go<'static>,
+    parse_uint: Go<'static>,
+    parse_float: Go<'static>,
+    parse_bool: Go<'static>,
+    string: Go<'static>,
+}
+
+impl Codegen {
+    pub fn new() -> Codegen {
+        Self {
+            context: imported("context", "Context"),
+            handler: imported("net/http", "Handler"),
+            new_serve_mux: imported("net/http", "NewServeMux"),
+            response_writer: imported("net/http", "ResponseWriter"),
+            request: imported("net/http", "Request"),
+            new_request: imported("net/http", "NewRequestWithContext"),
+            client: imported("net/http", "Client"),
+            error: imported("net/http", "Error"),
+            status_bad_request: imported("net/http", "StatusBadRequest"),
+            status_internal_server_error: imported("net/http", "StatusInternalServerError"),
+            status_no_content: imported("net/http", "StatusNoContent"),
+            new_decoder: imported("encoding/json", "NewDecoder"),
+            new_encoder: imported("encoding/json", "NewEncoder"),
+            buffer: imported("bytes", "Buffer"),
+            read_all: imported("io/ioutil", "ReadAll"),
+            new_reader: imported("strings", "NewReader"),
+            new_error: imported("errors", "New"),
+            errorf: imported("fmt", "Errorf"),
+            sprint: imported("fmt", "Sprint"),
+            path_escape: imported("net/url", "PathEscape"),
+            values: imported("net/url", "Values"),
+            trim_right: imported("strings", "TrimRight"),
+            parse_int: imported("strconv", "ParseInt"),
+            parse_uint: imported("strconv", "ParseUint"),
+            parse_float: imported("strconv", "ParseFloat"),
+            parse_bool: imported("strconv", "ParseBool"),
+            string: local("string"),
+        }
+    }
+}
+
+impl ServiceCodegen for Codegen {
+    fn generate(&self, e: ServiceAdded) -> Result<()> {
+        let ServiceAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        let mut endpoints = Vec::new();
+
+        for e in &body.endpoints {
+            let http = match e.http1.as_ref() {
+                Some(http) => http,
+                None => continue,
+            };
+
+            endpoints.push(Endpoint { e, http });
+        }
+
+        let all = body.endpoints.iter().map(|e| &**e).collect::<Vec<_>>();
+
+        container.push(self.interface(name, &body.comment, &body.deprecated, &all));
+        container.push(self.handler(name, &endpoints)?);
+        container.push(self.client(name, &all, &endpoints));
+        Ok(())
+    }
+}
+
+/// An endpoint which can be served over HTTP/1.1.
+struct Endpoint<'el> {
+    e: &'el GoEndpoint,
+    http: &'el RpEndpointHttp1,
+}
+
+impl Codegen {
+    /// Build the type of a channel, streams are represented as receive-only channels.
+    fn channel_type<'el>(&self, channel: &'el RpChannel) -> Tokens<'el, Go<'el>> {
+        match *channel {
+            RpChannel::Unary { ref ty } => toks![ty.clone()],
+            RpChannel::Streaming { ref ty } => toks!["<-chan ", ty.clone()],
+        }
+    }
+
+    /// Build the signature of the method implementing the endpoint.
+    fn signature<'el>(&self, e: &'el GoEndpoint) -> Tokens<'el, Go<'el>> {
+        let mut args = Tokens::new();
+
+        args.append(toks!["ctx_ ", self.context.clone()]);
+
+        for a in &e.arguments {
+            args.append(toks![a.safe_ident(), " ", self.channel_type(&a.channel)]);
+        }
+
+        let ret = match e.response {
+            Some(ref response) => toks!["(", self.channel_type(response), ", error)"],
+            None => toks!["error"],
+        };
+
+        toks![e.safe_ident(), "(", args.join(", "), ") ", ret]
+    }
+
+    /// Build the interface for the service.
+    fn interface<'el>(
+        &self,
+        name: &'el GoName,
+        comment: &'el [String],
+        deprecated: &'el Option<RpDeprecated>,
+        endpoints: &[&'el GoEndpoint],
+    ) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(comment));
+        t.push_unless_empty(Deprecated::after(comment, deprecated));
+        push!(t, "type ", name, " interface {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            for e in endpoints {
+                t.push_into(|t| {
+                    t.push_unless_empty(Comments(&e.comment));
+                    t.push_unless_empty(Deprecated::after(&e.comment, &e.deprecated));
+                    t.push(self.signature(e));
+                });
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the function setting up an `http.Handler` for the service.
+    fn handler<'el>(
+        &self,
+        name: &'el GoName,
+        endpoints: &[Endpoint<'el>],
+    ) -> Result<Tokens<'el, Go<'el>>> {
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "// New",
+            name,
+            "Handler builds an http.Handler which serves the given service."
+        );
+
+        push!(
+            t,
+            "func New",
+            name,
+            "Handler(service_ ",
+            name,
+            ") ",
+            self.handler,
+            " {"
+        );
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(t, "mux_ := ", self.new_serve_mux, "()");
+
+            for endpoint in endpoints {
+                t.push(self.handle_endpoint(endpoint)?);
+            }
+
+            push!(t, "return mux_");
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        Ok(t)
+    }
+
+    /// Register the handler for a single endpoint with the mux.
+    fn handle_endpoint<'el>(&self, endpoint: &Endpoint<'el>) -> Result<Tokens<'el, Go<'el>>> {
+        let Endpoint { e, http } = *endpoint;
+
+        let mut pattern = String::new();
+
+        pattern.push_str(http.method.as_str());
+        pattern.push(' ');
+
+        for step in &http.path.steps {
+            pattern.push('/');
+
+            for part in &step.parts {
+                match *part {
+                    RpPathPart::Variable(ref arg) => {
+                        if step.parts.len() != 1 {
+                            return Err(format!(
+                                "{}: path variable `{}` must be a full path segment",
+                                e.ident(),
+                                arg.ident()
+                            ).into());
+                        }
+
+                        pattern.push('{');
+                        pattern.push_str(arg.safe_ident());
+                        pattern.push('}');
+                    }
+                    RpPathPart::Segment(ref s) => {
+                        pattern.push_str(s.as_str());
+                    }
+                }
+            }
+        }
+
+        if http.path.steps.is_empty() {
+            pattern.push('/');
+        }
+
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "mux_.HandleFunc(",
+            pattern.quoted(),
+            ", func(w_ ",
+            self.response_writer,
+            ", r_ *",
+            self.request,
+            ") {"
+        );
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            for var in http.path.vars() {
+                let value = toks!["r_.PathValue(", var.safe_ident().quoted(), ")"];
+                t.push(self.decode_var(e, var, value, "path variable")?);
+            }
+
+            for p in &http.query {
+                let value = toks!["r_.URL.Query().Get(", p.name.as_str().quoted(), ")"];
+                t.push(self.decode_var(e, &p.argument, value, "query parameter")?);
+            }
+
+            for h in &http.headers {
+                let value = toks!["r_.Header.Get(", h.name.as_str().quoted(), ")"];
+                t.push(self.decode_var(e, &h.argument, value, "header")?);
+            }
+
+            match e.request {
+                Some(ref request) if http.accepts == RpAccept::Text => {
+                    t.push_into(|t| {
+                        push!(t, "body_, err_ := ", self.read_all, "(r_.Body)");
+                        push!(t, "if err_ != nil {");
+                        t.nested(self.http_error(self.status_bad_request.clone()));
+                        push!(t, "}");
+                        push!(t, request.safe_ident(), " := string(body_)");
+                    });
+                }
+                Some(ref request) => {
+                    t.push_into(|t| {
+                        push!(t, "var ", request.safe_ident(), " ", request.channel.ty());
+                        push!(
+                            t,
+                            "if err_ := ",
+                            self.new_decoder,
+                            "(r_.Body).Decode(&",
+                            request.safe_ident(),
+                            "); err_ != nil {"
+                        );
+                        t.nested(self.http_error(self.status_bad_request.clone()));
+                        push!(t, "}");
+                    });
+                }
+                None => {}
+            }
+
+            let mut args = Tokens::new();
+
+            args.append("r_.Context()");
+
+            for a in &e.arguments {
+                args.append(a.safe_ident());
+            }
+
+            let call = toks!["service_.", e.safe_ident(), "(", args.join(", "), ")"];
+
+            match http.response {
+                Some(_) => {
+                    t.push_into(|t| {
+                        push!(t, "response_, err_ := ", call);
+                        push!(t, "if err_ != nil {");
+                        t.nested(self.http_error(self.status_internal_server_error.clone()));
+                        push!(t, "}");
+                    });
+
+                    t.push_into(|t| {
+                        push!(
+                            t,
+                            "w_.Header().Set(",
+                            "Content-Type".quoted(),
+                            ", ",
+                            http.produces.as_str().quoted(),
+                            ")"
+                        );
+
+                        match http.produces {
+                            RpAccept::Json => {
+                                push!(t, self.new_encoder, "(w_).Encode(response_)");
+                            }
+                            RpAccept::Text => {
+                                push!(t, "w_.Write([]byte(response_))");
+                            }
+                        }
+                    });
+                }
+                None => {
+                    t.push_into(|t| {
+                        push!(t, "if err_ := ", call, "; err_ != nil {");
+                        t.nested(self.http_error(self.status_internal_server_error.clone()));
+                        push!(t, "}");
+                    });
+
+                    push!(t, "w_.WriteHeader(", self.status_no_content, ")");
+                }
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "})");
+
+        Ok(t)
+    }
+
+    /// Decode a string value, like a path variable, into a local variable with the same name.
+    fn decode_var<'el>(
+        &self,
+        e: &'el GoEndpoint,
+        var: &'el RpEndpointArgument,
+        value: Tokens<'el, Go<'el>>,
+        kind: &str,
+    ) -> Result<Tokens<'el, Go<'el>>> {
+        let ty = var.channel.ty();
+
+        let mut t = Tokens::new();
+
+        if *ty == self.string {
+            push!(t, var.safe_ident(), " := ", value);
+            return Ok(t);
+        }
+
+        // parse functions for each supported type, and the arguments passed to them.
+        let parsers = [
+            ("uint32", &self.parse_uint, ", 10, 32"),
+            ("uint64", &self.parse_uint, ", 10, 64"),
+            ("int32", &self.parse_int, ", 10, 32"),
+            ("int64", &self.parse_int, ", 10, 64"),
+            ("float32", &self.parse_float, ", 32"),
+            ("float64", &self.parse_float, ", 64"),
+            ("bool", &self.parse_bool, ""),
+        ];
+
+        let parse = parsers
+            .iter()
+            .find(|&&(name, _, _)| *ty == local(name))
+            .map(|&(_, parse, args)| toks![parse.clone(), "(", value, args, ")"]);
+
+        let parse = match parse {
+            Some(parse) => parse,
+            None => {
+                return Err(format!(
+                    "{}: {} `{}` has a type which can't be decoded",
+                    e.ident(),
+                    kind,
+                    var.ident()
+                ).into());
+            }
+        };
+
+        let parsed = format!("{}_", var.safe_ident());
+
+        push!(t, parsed.clone(), ", err_ := ", parse);
+        push!(t, "if err_ != nil {");
+        t.nested(self.http_error(self.status_bad_request.clone()));
+        push!(t, "}");
+        push!(t, var.safe_ident(), " := ", ty.clone(), "(", parsed, ")");
+
+        Ok(t)
+    }
+
+    /// Respond with the current error and the given status.
+    fn http_error<'el>(&self, status: Go<'static>) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+        push!(t, self.error, "(w_, err_.Error(), ", status, ")");
+        push!(t, "return");
+        t
+    }
+
+    /// Build the client for the service.
+    fn client<'el>(
+        &self,
+        name: &'el GoName,
+        all: &[&'el GoEndpoint],
+        endpoints: &[Endpoint<'el>],
+    ) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+
+        t.push_into(|t| {
+            push!(
+                t,
+                "// ",
+                name,
+                "Client is a client for the service, sending requests using net/http."
+            );
+            push!(t, "type ", name, "Client struct {");
+
+            t.nested_into(|t| {
+                push!(t, "client *", self.client);
+                push!(t, "baseURL string");
+            });
+
+            push!(t, "}");
+        });
+
+        t.push_into(|t| {
+            push!(
+                t,
+                "// New",
+                name,
+                "Client builds a new client, sending requests relative to baseURL."
+            );
+            push!(
+                t,
+                "func New",
+                name,
+                "Client(client *",
+                self.client,
+                ", baseURL string) *",
+                name,
+                "Client {"
+            );
+            nested!(
+                t,
+                "return &",
+                name,
+                "Client{client: client, baseURL: ",
+                self.trim_right,
+                "(baseURL, ",
+                "/".quoted(),
+                ")}"
+            );
+            push!(t, "}");
+        });
+
+        for e in all {
+            match endpoints
+                .iter()
+                .find(|endpoint| endpoint.e.ident() == e.ident())
+            {
+                Some(endpoint) => t.push(self.client_endpoint(name, endpoint)),
+                None => t.push(self.client_unsupported(name, e)),
+            }
+        }
+
+        t.join_line_spacing()
+    }
+
+    /// Build the client method for an endpoint which can't be called over HTTP/1.1.
+    fn client_unsupported<'el>(
+        &self,
+        name: &'el GoName,
+        e: &'el GoEndpoint,
+    ) -> Tokens<'el, Go<'el>> {
+        let message = format!("{}.{}: not available over HTTP", name, e.safe_ident());
+        let err = toks![self.new_error.clone(), "(", message.quoted(), ")"];
+
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(&e.comment));
+        t.push_unless_empty(Deprecated::after(&e.comment, &e.deprecated));
+        push!(t, "func (c_ *", name, "Client) ", self.signature(e), " {");
+
+        match e.response {
+            Some(ref response) => {
+                nested!(t, "var response_ ", self.channel_type(response));
+                nested!(t, "return response_, ", err);
+            }
+            None => {
+                nested!(t, "return ", err);
+            }
+        }
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the client method for a single endpoint.
+    fn client_endpoint<'el>(
+        &self,
+        name: &'el GoName,
+        endpoint: &Endpoint<'el>,
+    ) -> Tokens<'el, Go<'el>> {
+        let Endpoint { e, http } = *endpoint;
+
+        // how to return the given error from the method.
+        let ret = |err: &'static str| -> Tokens<'el, Go<'el>> {
+            match http.response {
+                Some(_) => toks!["return response_, ", err],
+                None => toks!["return ", err],
+            }
+        };
+
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(&e.comment));
+        t.push_unless_empty(Deprecated::after(&e.comment, &e.deprecated));
+        push!(t, "func (c_ *", name, "Client) ", self.signature(e), " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            if let Some(ref response) = http.response {
+                push!(t, "var response_ ", response);
+            }
+
+            push!(t, "path_ := ", self.path(http));
+
+            if !http.query.is_empty() {
+                t.push_into(|t| {
+                    push!(t, "query_ := ", self.values, "{}");
+
+                    for p in &http.query {
+                        let value = self.to_string(&p.argument);
+                        push!(t, "query_.Set(", p.name.as_str().quoted(), ", ", value, ")");
+                    }
+
+                    push!(t, "path_ += ", "?".quoted(), " + query_.Encode()");
+                });
+            }
+
+            let body = match e.request {
+                Some(ref request) if http.accepts == RpAccept::Text => {
+                    toks![self.new_reader.clone(), "(", request.safe_ident(), ")"]
+                }
+                Some(ref request) => {
+                    t.push_into(|t| {
+                        push!(t, "body_ := new(", self.buffer, ")");
+                        push!(
+                            t,
+                            "if err_ := ",
+                            self.new_encoder,
+                            "(body_).Encode(",
+                            request.safe_ident(),
+                            "); err_ != nil {"
+                        );
+                        nested!(t, ret("err_"));
+                        push!(t, "}");
+                    });
+
+                    toks!["body_"]
+                }
+                None => toks!["nil"],
+            };
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "req_, err_ := ",
+                    self.new_request,
+                    "(ctx_, ",
+                    http.method.as_str().quoted(),
+                    ", c_.baseURL+path_, ",
+                    body,
+                    ")"
+                );
+                push!(t, "if err_ != nil {");
+                nested!(t, ret("err_"));
+                push!(t, "}");
+
+                if e.request.is_some() {
+                    let (header, content_type) =
+                        ("Content-Type".quoted(), http.accepts.as_str().quoted());
+                    push!(t, "req_.Header.Set(", header, ", ", content_type, ")");
+                }
+
+                if http.response.is_some() {
+                    let accept = http.produces.as_str().quoted();
+                    push!(t, "req_.Header.Set(", "Accept".quoted(), ", ", accept, ")");
+                }
+
+                for h in &http.headers {
+                    let name = h.name.as_str().quoted();
+                    let value = self.to_string(&h.argument);
+                    push!(t, "req_.Header.Set(", name, ", ", value, ")");
+                }
+            });
+
+            t.push_into(|t| {
+                push!(t, "resp_, err_ := c_.client.Do(req_)");
+                push!(t, "if err_ != nil {");
+                nested!(t, ret("err_"));
+                push!(t, "}");
+                push!(t, "defer resp_.Body.Close()");
+            });
+
+            t.push_into(|t| {
+                let message = format!("{}.{}: bad status: %s", name, e.safe_ident());
+
+                push!(t, "if resp_.StatusCode < 200 || resp_.StatusCode >= 300 {");
+                nested!(
+                    t,
+                    ret(""),
+                    self.errorf,
+                    "(",
+                    message.quoted(),
+                    ", resp_.Status)"
+                );
+                push!(t, "}");
+            });
+
+            match http.response {
+                Some(_) if http.produces == RpAccept::Text => {
+                    push!(t, "body_, err_ := ", self.read_all, "(resp_.Body)");
+                    push!(t, "if err_ != nil {");
+                    nested!(t, ret("err_"));
+                    push!(t, "}");
+                    push!(t, "return string(body_), nil");
+                }
+                Some(_) => {
+                    push!(
+                        t,
+                        "err_ = ",
+                        self.new_decoder,
+                        "(resp_.Body).Decode(&response_)"
+                    );
+                    push!(t, "return response_, err_");
+                }
+                None => {
+                    push!(t, "return nil");
+                }
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build an expression converting the value of an argument into a string.
+    fn to_string<'el>(&self, arg: &'el RpEndpointArgument) -> Tokens<'el, Go<'el>> {
+        if *arg.channel.ty() == self.string {
+            toks![arg.safe_ident()]
+        } else {
+            toks![self.sprint.clone(), "(", arg.safe_ident(), ")"]
+        }
+    }
+
+    /// Build an expression for the path of the request.
+    fn path<'el>(&self, http: &'el RpEndpointHttp1) -> Tokens<'el, Go<'el>> {
+        let mut t = Tokens::new();
+        let mut segment = String::new();
+
+        for step in &http.path.steps {
+            segment.push('/');
+
+            for part in &step.parts {
+                match *part {
+                    RpPathPart::Variable(ref arg) => {
+                        if !segment.is_empty() {
+                            t.append(segment.clone().quoted());
+                            segment.clear();
+                        }
+
+                        let value = self.to_string(arg);
+                        t.append(toks![self.path_escape.clone(), "(", value, ")"]);
+                    }
+                    RpPathPart::Segment(ref s) => {
+                        segment.push_str(s.as_str());
+                    }
+                }
+            }
+        }
+
+        if !segment.is_empty() || t.is_empty() {
+            if segment.is_empty() {
+                segment.push('/');
+            }
+
+            t.append(segment.quoted());
+        }
+
+        t.join(" + ")
+    }
+}
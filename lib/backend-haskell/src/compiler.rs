@@ -0,0 +1,396 @@
+//! Backend for Haskell
+
+use backend::PackageProcessor;
+use core::errors::*;
+use core::{Handle, Loc, RpDeprecated};
+use flavored::{
+    HaskellFlavor, HaskellName, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpTupleBody,
+    RpTypeBody, RpUnionBody,
+};
+use genco::{IntoTokens, Quoted, Tokens};
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use trans::{self, Translated};
+use {
+    field_name, sub_type_constructor, union_constructor, EnumAdded, FileSpec, Haskell,
+    InterfaceAdded, NewtypeAdded, Options, TupleAdded, TypeAdded, UnionAdded, EXT,
+};
+
+/// Documentation comments, in the style of Haddock.
+pub struct Comments<'el, S: 'el> {
+    comment: &'el [S],
+    /// Marker of the comment, `|` for comments preceding a declaration and `^` for comments
+    /// following it.
+    marker: &'static str,
+}
+
+impl<'el, S: 'el> Comments<'el, S> {
+    /// Comments preceding a declaration.
+    pub fn before(comment: &'el [S]) -> Comments<'el, S> {
+        Comments {
+            comment,
+            marker: "|",
+        }
+    }
+
+    /// Comments following a declaration, like a field or a constructor.
+    pub fn after(comment: &'el [S]) -> Comments<'el, S> {
+        Comments {
+            comment,
+            marker: "^",
+        }
+    }
+}
+
+impl<'el, S: 'el + AsRef<str>> IntoTokens<'el, Haskell> for Comments<'el, S> {
+    fn into_tokens(self) -> Tokens<'el, Haskell> {
+        let mut t = Tokens::new();
+
+        let mut it = self.comment.iter();
+
+        if let Some(c) = it.next() {
+            t.push(toks!["-- ", self.marker, " ", c.as_ref()]);
+        }
+
+        for c in it {
+            t.push(toks!["--   ", c.as_ref()]);
+        }
+
+        t
+    }
+}
+
+/// Deprecation pragmas for the given names.
+pub struct Deprecated<'el> {
+    deprecated: Vec<(String, &'el RpDeprecated)>,
+}
+
+impl<'el> Deprecated<'el> {
+    pub fn new() -> Deprecated<'el> {
+        Deprecated {
+            deprecated: Vec::new(),
+        }
+    }
+
+    /// Mark the given name as deprecated, if it is.
+    pub fn push(&mut self, name: String, deprecated: &'el Option<RpDeprecated>) {
+        if let Some(ref deprecated) = *deprecated {
+            self.deprecated.push((name, deprecated));
+        }
+    }
+}
+
+impl<'el> IntoTokens<'el, Haskell> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Haskell> {
+        let mut t = Tokens::new();
+
+        for (name, deprecated) in self.deprecated {
+            let note = Rc::new(deprecated.note().unwrap_or("Do not use.").to_string());
+            t.push(toks!["{-# DEPRECATED ", name, " ", note.quoted(), " #-}"]);
+        }
+
+        t
+    }
+}
+
+pub struct Compiler<'el> {
+    pub env: &'el Translated<HaskellFlavor>,
+    pub options: Options,
+    handle: &'el Handle,
+}
+
+impl<'el> Compiler<'el> {
+    pub fn new(
+        env: &'el Translated<HaskellFlavor>,
+        options: Options,
+        handle: &'el Handle,
+    ) -> Result<Compiler<'el>> {
+        Ok(Compiler {
+            env,
+            options,
+            handle,
+        })
+    }
+
+    /// Build the type of a field, registering any modules it references.
+    fn field_type(&self, modules: &mut BTreeSet<Rc<String>>, field: &RpField) -> String {
+        field.ty.modules(modules);
+
+        if field.is_optional() {
+            format!("Maybe {}", field.ty.argument())
+        } else {
+            field.ty.to_string()
+        }
+    }
+
+    /// Build a record with a single constructor of the same name.
+    fn process_record<I>(
+        &self,
+        modules: &mut BTreeSet<Rc<String>>,
+        name: &'el HaskellName,
+        comment: &'el [String],
+        deprecated: &'el Option<RpDeprecated>,
+        fields: I,
+    ) -> Tokens<'el, Haskell>
+    where
+        I: IntoIterator<Item = &'el RpField>,
+    {
+        let mut t = Tokens::new();
+        let mut d = Deprecated::new();
+
+        d.push(name.to_string(), deprecated);
+
+        t.push_unless_empty(Comments::before(comment));
+        t.push(toks!["data ", name, " = ", name]);
+
+        t.nested_into(|t| {
+            let mut separator = "{ ";
+
+            for f in fields {
+                let field = field_name(name, f);
+                let ty = self.field_type(modules, f);
+
+                d.push(field.clone(), &f.deprecated);
+
+                t.push(toks![separator, field, " :: ", ty]);
+
+                if !f.comment.is_empty() {
+                    t.nested(Comments::after(&f.comment));
+                }
+
+                separator = ", ";
+            }
+
+            if separator != "{ " {
+                t.push("}");
+            }
+
+            t.push("deriving (Show, Eq)");
+        });
+
+        t.push_unless_empty(d);
+        t
+    }
+
+    pub fn compile(&self) -> Result<()> {
+        let files = self.populate_files()?;
+        self.write_files(files)
+    }
+}
+
+impl<'el> PackageProcessor<'el, HaskellFlavor, HaskellName> for Compiler<'el> {
+    type Out = FileSpec<'el>;
+    type DeclIter = trans::translated::DeclIter<'el, HaskellFlavor>;
+
+    fn ext(&self) -> &str {
+        EXT
+    }
+
+    fn decl_iter(&self) -> Self::DeclIter {
+        self.env.decl_iter()
+    }
+
+    fn handle(&self) -> &'el Handle {
+        self.handle
+    }
+
+    fn process_type(&self, out: &mut Self::Out, body: &'el RpTypeBody) -> Result<()> {
+        out.body.push(self.process_record(
+            &mut out.modules,
+            &body.name,
+            &body.comment,
+            &body.deprecated,
+            body.fields.iter().map(Loc::borrow),
+        ));
+
+        for g in &self.options.type_gens {
+            g.generate(TypeAdded {
+                container: &mut out.body,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_tuple(&self, out: &mut Self::Out, body: &'el RpTupleBody) -> Result<()> {
+        out.body.push(self.process_record(
+            &mut out.modules,
+            &body.name,
+            &body.comment,
+            &body.deprecated,
+            body.fields.iter().map(Loc::borrow),
+        ));
+
+        for g in &self.options.tuple_gens {
+            g.generate(TupleAdded {
+                container: &mut out.body,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_enum(&self, out: &mut Self::Out, body: &'el RpEnumBody) -> Result<()> {
+        out.body.push_into(|t| {
+            let mut d = Deprecated::new();
+            d.push(body.name.to_string(), &body.deprecated);
+
+            t.push_unless_empty(Comments::before(&body.comment));
+            t.push(toks!["data ", &body.name]);
+
+            t.nested_into(|t| {
+                let mut separator = "= ";
+
+                for v in body.variants.iter() {
+                    let constructor = format!("{}_{}", body.name, v.ident.as_str());
+                    d.push(constructor.clone(), v.deprecated);
+
+                    t.push(toks![separator, constructor]);
+
+                    if !v.comment.is_empty() {
+                        t.nested(Comments::after(v.comment));
+                    }
+
+                    separator = "| ";
+                }
+
+                t.push("deriving (Show, Eq, Ord, Enum, Bounded)");
+            });
+
+            t.push_unless_empty(d);
+        });
+
+        for g in &self.options.enum_gens {
+            g.generate(EnumAdded {
+                container: &mut out.body,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_interface(&self, out: &mut Self::Out, body: &'el RpInterfaceBody) -> Result<()> {
+        out.body.push_into(|t| {
+            let mut d = Deprecated::new();
+            d.push(body.name.to_string(), &body.deprecated);
+
+            t.push_unless_empty(Comments::before(&body.comment));
+            t.push(toks!["data ", &body.name]);
+
+            t.nested_into(|t| {
+                let mut separator = "= ";
+
+                for s in &body.sub_types {
+                    let constructor = sub_type_constructor(body, s);
+                    d.push(constructor.clone(), &s.deprecated);
+
+                    t.push(toks![separator, constructor, " ", &s.name]);
+                    separator = "| ";
+                }
+
+                t.push("deriving (Show, Eq)");
+            });
+
+            t.push_unless_empty(d);
+        });
+
+        for s in &body.sub_types {
+            let fields = body.fields.iter().chain(s.fields.iter()).map(Loc::borrow);
+
+            out.body.push(self.process_record(
+                &mut out.modules,
+                &s.name,
+                &s.comment,
+                &s.deprecated,
+                fields,
+            ));
+        }
+
+        for g in &self.options.interface_gens {
+            g.generate(InterfaceAdded {
+                container: &mut out.body,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_union(&self, out: &mut Self::Out, body: &'el RpUnionBody) -> Result<()> {
+        {
+            let modules = &mut out.modules;
+
+            out.body.push_into(|t| {
+                let mut d = Deprecated::new();
+                d.push(body.name.to_string(), &body.deprecated);
+
+                t.push_unless_empty(Comments::before(&body.comment));
+                t.push(toks!["data ", &body.name]);
+
+                t.nested_into(|t| {
+                    let mut separator = "= ";
+
+                    for v in &body.variants {
+                        v.ty.modules(modules);
+
+                        let constructor = union_constructor(body, v);
+                        t.push(toks![separator, constructor, " ", v.ty.argument()]);
+                        separator = "| ";
+                    }
+
+                    t.push("deriving (Show, Eq)");
+                });
+
+                t.push_unless_empty(d);
+            });
+        }
+
+        for g in &self.options.union_gens {
+            g.generate(UnionAdded {
+                container: &mut out.body,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_newtype(&self, out: &mut Self::Out, body: &'el RpNewtypeBody) -> Result<()> {
+        body.ty.modules(&mut out.modules);
+
+        out.body.push_into(|t| {
+            let mut d = Deprecated::new();
+            d.push(body.name.to_string(), &body.deprecated);
+
+            t.push_unless_empty(Comments::before(&body.comment));
+            t.push(toks![
+                "newtype ",
+                &body.name,
+                " = ",
+                &body.name,
+                " ",
+                body.ty.argument(),
+            ]);
+            t.nested("deriving (Show, Eq)");
+            t.push_unless_empty(d);
+        });
+
+        for g in &self.options.newtype_gens {
+            g.generate(NewtypeAdded {
+                container: &mut out.body,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+}
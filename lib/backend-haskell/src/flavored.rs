@@ -0,0 +1,292 @@
+//! Haskell flavor.
+
+#![allow(unused)]
+
+use backend::package_processor;
+use core::errors::Result;
+use core::{
+    self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberKind,
+    RpNumberType, RpScalarType, RpStringType, Translate, Translator,
+};
+use genco::Element;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::rc::Rc;
+use trans::Packages;
+use {Haskell, TYPE_SEP};
+
+/// A Haskell type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HaskellType {
+    /// A type without arguments, like `Bool` or `Text.Text`.
+    Simple(&'static str),
+    /// A declared type, qualified by its module if it is imported.
+    Name {
+        module: Option<Rc<String>>,
+        name: Rc<String>,
+    },
+    /// A list, like `[Int32]`.
+    List(Box<HaskellType>),
+    /// A set, like `Set.Set Int32`.
+    Set(Box<HaskellType>),
+    /// A map, like `Map.Map Text.Text Int32`.
+    Map(Box<HaskellType>, Box<HaskellType>),
+}
+
+impl HaskellType {
+    /// Collect all modules which needs to be imported to use this type.
+    pub fn modules(&self, out: &mut BTreeSet<Rc<String>>) {
+        use self::HaskellType::*;
+
+        match *self {
+            Simple(_) => {}
+            Name { ref module, .. } => {
+                out.extend(module.iter().cloned());
+            }
+            List(ref argument) | Set(ref argument) => {
+                argument.modules(out);
+            }
+            Map(ref key, ref value) => {
+                key.modules(out);
+                value.modules(out);
+            }
+        }
+    }
+
+    /// Format the type as an argument to another type, which requires parenthesis if the type
+    /// has arguments of its own.
+    pub fn argument(&self) -> String {
+        use self::HaskellType::*;
+
+        match *self {
+            Set(_) | Map(_, _) => format!("({})", self),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HaskellType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::HaskellType::*;
+
+        match *self {
+            Simple(name) => fmt.write_str(name),
+            Name {
+                ref module,
+                ref name,
+            } => {
+                if let Some(ref module) = *module {
+                    write!(fmt, "{}.", module)?;
+                }
+
+                fmt.write_str(name.as_str())
+            }
+            List(ref argument) => write!(fmt, "[{}]", argument),
+            Set(ref argument) => write!(fmt, "Set.Set {}", argument.argument()),
+            Map(ref key, ref value) => {
+                write!(fmt, "Map.Map {} {}", key.argument(), value.argument())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HaskellFlavor;
+
+impl Flavor for HaskellFlavor {
+    type Type = HaskellType;
+    type Name = HaskellName;
+    type Field = RpField;
+    type Endpoint = RpEndpoint;
+    type Package = RpPackage;
+    type EnumType = HaskellType;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HaskellName {
+    pub name: Rc<String>,
+    pub package: RpPackage,
+}
+
+impl fmt::Display for HaskellName {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.name.as_str())
+    }
+}
+
+impl<'el> From<&'el HaskellName> for Element<'el, Haskell> {
+    fn from(value: &'el HaskellName) -> Element<'el, Haskell> {
+        Element::Literal(value.name.clone().into())
+    }
+}
+
+impl package_processor::Name<HaskellFlavor> for HaskellName {
+    fn package(&self) -> &RpPackage {
+        &self.package
+    }
+}
+
+/// Responsible for translating RpType -> Haskell type.
+pub struct HaskellFlavorTranslator {
+    package_translator: Rc<Packages>,
+}
+
+impl HaskellFlavorTranslator {
+    pub fn new(package_translator: Rc<Packages>) -> Self {
+        Self { package_translator }
+    }
+}
+
+impl FlavorTranslator for HaskellFlavorTranslator {
+    type Source = CoreFlavor;
+    type Target = HaskellFlavor;
+
+    translator_defaults!(Self, field, endpoint);
+
+    fn translate_number(&self, number: RpNumberType) -> Result<HaskellType> {
+        let ty = match number.kind {
+            RpNumberKind::U32 => "Word32",
+            RpNumberKind::U64 => "Word64",
+            RpNumberKind::I32 => "Int32",
+            RpNumberKind::I64 => "Int64",
+            RpNumberKind::U128 | RpNumberKind::I128 => "Integer",
+            RpNumberKind::Decimal => "Scientific",
+        };
+
+        Ok(HaskellType::Simple(ty))
+    }
+
+    fn translate_float(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Float"))
+    }
+
+    fn translate_double(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Double"))
+    }
+
+    fn translate_boolean(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Bool"))
+    }
+
+    fn translate_string(&self, _: RpStringType) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Text.Text"))
+    }
+
+    fn translate_datetime(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Time.UTCTime"))
+    }
+
+    fn translate_date(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Time.Day"))
+    }
+
+    fn translate_time(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Time.TimeOfDay"))
+    }
+
+    fn translate_duration(&self) -> Result<HaskellType> {
+        // NB: aeson encodes `NominalDiffTime` as a number of seconds, so the ISO-8601
+        // representation is kept as text.
+        Ok(HaskellType::Simple("Text.Text"))
+    }
+
+    fn translate_array(&self, argument: HaskellType) -> Result<HaskellType> {
+        Ok(HaskellType::List(Box::new(argument)))
+    }
+
+    fn translate_set(&self, argument: HaskellType) -> Result<HaskellType> {
+        Ok(HaskellType::Set(Box::new(argument)))
+    }
+
+    fn translate_map(&self, key: HaskellType, value: HaskellType) -> Result<HaskellType> {
+        Ok(HaskellType::Map(Box::new(key), Box::new(value)))
+    }
+
+    fn translate_any(&self) -> Result<HaskellType> {
+        Ok(HaskellType::Simple("Aeson.Value"))
+    }
+
+    fn translate_bytes(&self) -> Result<HaskellType> {
+        // NB: bytes are encoded as base64 strings.
+        Ok(HaskellType::Simple("Text.Text"))
+    }
+
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<HaskellType> {
+        let ty = match scalar.mapping("haskell") {
+            Some(ty) => ty,
+            None => return self.translate_scalar_base(scalar.base),
+        };
+
+        let (module, name) = match ty.rfind('.') {
+            Some(index) => (Some(Rc::new(ty[..index].to_string())), &ty[index + 1..]),
+            None => (None, ty),
+        };
+
+        Ok(HaskellType::Name {
+            module,
+            name: Rc::new(name.to_string()),
+        })
+    }
+
+    fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<HaskellType> {
+        let ident = Rc::new(reg.ident(&name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP)));
+
+        // imported
+        if let Some(_) = name.prefix {
+            return Ok(HaskellType::Name {
+                module: Some(Rc::new(name.package.join("."))),
+                name: ident,
+            });
+        }
+
+        // same package
+        return Ok(HaskellType::Name {
+            module: None,
+            name: ident,
+        });
+    }
+
+    fn translate_local_name<T>(
+        &self,
+        translator: &T,
+        diag: &mut Diagnostics,
+        reg: RpReg,
+        name: Loc<core::RpName<CoreFlavor>>,
+    ) -> Result<HaskellName>
+    where
+        T: Translator<Source = Self::Source, Target = Self::Target>,
+    {
+        let (name, _) = Loc::take_pair(name);
+
+        let ident = reg.ident(&name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP));
+        let package = self.translate_package(name.package)?;
+
+        Ok(HaskellName {
+            name: Rc::new(ident),
+            package: package,
+        })
+    }
+
+    fn translate_package(&self, source: RpVersionedPackage) -> Result<RpPackage> {
+        self.package_translator.translate_package(source)
+    }
+
+    fn translate_enum_type<T>(
+        &self,
+        translator: &T,
+        diag: &mut Diagnostics,
+        enum_type: core::RpEnumType,
+    ) -> Result<HaskellType>
+    where
+        T: Translator<Source = Self::Source, Target = Self::Target>,
+    {
+        use core::RpEnumType::*;
+
+        match enum_type {
+            String(string) => self.translate_string(string),
+            Number(number) => self.translate_number(number),
+        }
+    }
+}
+
+decl_flavor!(HaskellFlavor, core);
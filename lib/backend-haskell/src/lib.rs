@@ -0,0 +1,363 @@
+#[macro_use]
+extern crate genco;
+#[macro_use]
+extern crate log;
+extern crate reproto_backend as backend;
+#[macro_use]
+extern crate reproto_core as core;
+#[macro_use]
+extern crate reproto_manifest as manifest;
+extern crate reproto_naming as naming;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[allow(unused)]
+#[macro_use]
+extern crate serde_derive;
+extern crate toml;
+
+mod compiler;
+mod flavored;
+mod module;
+
+use backend::{Initializer, IntoBytes};
+use compiler::Compiler;
+use core::errors::Result;
+use core::{CoreFlavor, Handle};
+use flavored::{
+    HaskellName, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpPackage, RpSubType,
+    RpTupleBody, RpTypeBody, RpUnionBody, RpUnionVariant,
+};
+use genco::{Custom, Formatter, Tokens};
+use manifest::{Lang, Manifest, ModuleDoc, NoModule, TryFromToml};
+use naming::{Keywords, Naming};
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+use trans::Session;
+
+const TYPE_SEP: &str = "_";
+const EXT: &str = "hs";
+
+/// Imports which are part of every generated module.
+const IMPORTS: &[&str] = &[
+    "import qualified Data.Aeson as Aeson",
+    "import Data.Int (Int32, Int64)",
+    "import qualified Data.Map as Map",
+    "import Data.Scientific (Scientific)",
+    "import qualified Data.Set as Set",
+    "import qualified Data.Text as Text",
+    "import qualified Data.Time as Time",
+    "import Data.Word (Word32, Word64)",
+];
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct HaskellLang;
+
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[ModuleDoc {
+    name: "aeson",
+    description: "Implement JSON encoding and decoding using `aeson`.",
+    options: &[],
+}];
+
+impl Lang for HaskellLang {
+    lang_base!(HaskellModule, compile);
+
+    fn comment(&self, input: &str) -> Option<String> {
+        Some(format!("-- {}", input))
+    }
+
+    fn package_naming(&self) -> Option<Box<Naming>> {
+        Some(Box::new(naming::to_upper_camel()))
+    }
+
+    fn field_ident_naming(&self) -> Option<Box<Naming>> {
+        Some(Box::new(naming::to_upper_camel()))
+    }
+
+    fn keywords(&self) -> Option<&'static Keywords> {
+        Some(&naming::keywords::HASKELL)
+    }
+
+    fn numbers_as_strings(&self) -> bool {
+        false
+    }
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+}
+
+#[derive(Debug)]
+pub enum HaskellModule {
+    Aeson,
+}
+
+impl TryFromToml for HaskellModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
+        use self::HaskellModule::*;
+
+        let result = match id {
+            "aeson" => Aeson,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        use self::HaskellModule::*;
+
+        let result = match id {
+            "aeson" => Aeson,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+}
+
+pub struct Options {
+    /// Additional imports for every generated module.
+    pub imports: Vec<&'static str>,
+    pub type_gens: Vec<Box<TypeCodegen>>,
+    pub tuple_gens: Vec<Box<TupleCodegen>>,
+    pub enum_gens: Vec<Box<EnumCodegen>>,
+    pub interface_gens: Vec<Box<InterfaceCodegen>>,
+    pub union_gens: Vec<Box<UnionCodegen>>,
+    pub newtype_gens: Vec<Box<NewtypeCodegen>>,
+}
+
+impl Options {
+    pub fn new() -> Options {
+        Options {
+            imports: Vec::new(),
+            type_gens: Vec::new(),
+            tuple_gens: Vec::new(),
+            enum_gens: Vec::new(),
+            interface_gens: Vec::new(),
+            union_gens: Vec::new(),
+            newtype_gens: Vec::new(),
+        }
+    }
+}
+
+pub fn options(modules: Vec<HaskellModule>) -> Result<Options> {
+    use self::HaskellModule::*;
+
+    let mut options = Options::new();
+
+    for m in modules {
+        debug!("+module: {:?}", m);
+
+        let initializer: Box<Initializer<Options = Options>> = match m {
+            Aeson => Box::new(module::Aeson::new()),
+        };
+
+        initializer.initialize(&mut options)?;
+    }
+
+    Ok(options)
+}
+
+/// Haskell language support for genco.
+#[derive(Clone)]
+pub enum Haskell {}
+
+impl Custom for Haskell {
+    type Extra = ();
+
+    fn quote_string(out: &mut Formatter, input: &str) -> fmt::Result {
+        out.write_char('"')?;
+
+        for c in input.chars() {
+            match c {
+                '\t' => out.write_str("\\t")?,
+                '\n' => out.write_str("\\n")?,
+                '\r' => out.write_str("\\r")?,
+                '"' => out.write_str("\\\"")?,
+                '\\' => out.write_str("\\\\")?,
+                c => out.write_char(c)?,
+            }
+        }
+
+        out.write_char('"')?;
+
+        Ok(())
+    }
+}
+
+pub struct FileSpec<'el> {
+    /// Modules which are referenced by the declarations in the file.
+    pub modules: BTreeSet<Rc<String>>,
+    pub body: Tokens<'el, Haskell>,
+}
+
+impl<'el> Default for FileSpec<'el> {
+    fn default() -> Self {
+        FileSpec {
+            modules: BTreeSet::new(),
+            body: Tokens::new(),
+        }
+    }
+}
+
+impl<'el> IntoBytes<Compiler<'el>> for FileSpec<'el> {
+    fn into_bytes(self, compiler: &Compiler<'el>, package: &RpPackage) -> Result<Vec<u8>> {
+        let mut t = Tokens::new();
+
+        t.push("{-# LANGUAGE OverloadedStrings #-}");
+        t.push(toks!["module ", package.join("."), " where"]);
+
+        t.push({
+            let mut t = Tokens::new();
+
+            let mut imports = IMPORTS.to_vec();
+            imports.extend(compiler.options.imports.iter().cloned());
+            imports.sort_by(|a, b| module_of(a).cmp(module_of(b)));
+            imports.dedup();
+
+            for i in imports {
+                t.push(i);
+            }
+
+            t
+        });
+
+        t.push_unless_empty({
+            let mut t = Tokens::new();
+
+            for m in self.modules {
+                t.push(format!("import qualified {}", m));
+            }
+
+            t
+        });
+
+        t.push_unless_empty(self.body.join_line_spacing());
+
+        let out = t.join_line_spacing().to_file()?;
+        return Ok(out.into_bytes());
+
+        /// The module of an import, which is what imports are ordered by.
+        fn module_of(import: &str) -> &str {
+            import
+                .trim_left_matches("import ")
+                .trim_left_matches("qualified ")
+        }
+    }
+}
+
+/// The name of the accessor for the given field in a record.
+///
+/// Record fields share a namespace with everything else in the module, so they are prefixed with
+/// the name of the record, like `entryFoo`.
+pub fn field_name(record: &HaskellName, field: &RpField) -> String {
+    let mut chars = record.name.chars();
+
+    let mut out = match chars.next() {
+        Some(c) => c.to_lowercase().collect::<String>(),
+        None => String::new(),
+    };
+
+    out.push_str(chars.as_str());
+    out.push_str(field.safe_ident());
+    out
+}
+
+/// The name of the constructor for a sub-type, like `TaggedFoo` for `Tagged::Foo`.
+pub fn sub_type_constructor(body: &RpInterfaceBody, sub_type: &RpSubType) -> String {
+    format!("{}{}", body.name, sub_type.ident)
+}
+
+/// The name of the constructor for a union variant, like `Union_Foo`.
+pub fn union_constructor(body: &RpUnionBody, variant: &RpUnionVariant) -> String {
+    format!("{}{}{}", body.name, TYPE_SEP, variant.ident)
+}
+
+/// Build codegen hooks.
+macro_rules! codegen {
+    ($c:tt, $e:ty) => {
+        pub trait $c {
+            fn generate(&self, e: $e) -> Result<()>;
+        }
+
+        impl<T> $c for Rc<T>
+        where
+            T: $c,
+        {
+            fn generate(&self, e: $e) -> Result<()> {
+                self.as_ref().generate(e)
+            }
+        }
+    };
+}
+
+/// Event emitted when a type has been added.
+pub struct TypeAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Haskell>,
+    pub name: &'el HaskellName,
+    pub body: &'el RpTypeBody,
+}
+
+codegen!(TypeCodegen, TypeAdded);
+
+/// Event emitted when a tuple has been added.
+pub struct TupleAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Haskell>,
+    pub name: &'el HaskellName,
+    pub body: &'el RpTupleBody,
+}
+
+codegen!(TupleCodegen, TupleAdded);
+
+/// Event emitted when an enum has been added.
+pub struct EnumAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Haskell>,
+    pub name: &'el HaskellName,
+    pub body: &'el RpEnumBody,
+}
+
+codegen!(EnumCodegen, EnumAdded);
+
+/// Event emitted when an interface has been added.
+pub struct InterfaceAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Haskell>,
+    pub name: &'el HaskellName,
+    pub body: &'el RpInterfaceBody,
+}
+
+codegen!(InterfaceCodegen, InterfaceAdded);
+
+/// Event emitted when a union has been added.
+pub struct UnionAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Haskell>,
+    pub name: &'el HaskellName,
+    pub body: &'el RpUnionBody,
+}
+
+codegen!(UnionCodegen, UnionAdded);
+
+/// Event emitted when a newtype has been added.
+pub struct NewtypeAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Haskell>,
+    pub name: &'el HaskellName,
+    pub body: &'el RpNewtypeBody,
+}
+
+codegen!(NewtypeCodegen, NewtypeAdded);
+
+fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let modules = manifest::checked_modules(manifest.modules)?;
+    let options = options(modules)?;
+
+    let packages = session.packages()?;
+
+    let translator = session.translator(flavored::HaskellFlavorTranslator::new(packages))?;
+
+    let session = session.translate(translator)?;
+
+    Compiler::new(&session, options, handle)?.compile()
+}
@@ -0,0 +1,543 @@
+//! aeson module for Haskell
+
+use backend::Initializer;
+use core;
+use core::errors::Result;
+use core::{Loc, RpSubTypeStrategy};
+use flavored::{HaskellName, RpField};
+use genco::{Quoted, Tokens};
+use std::rc::Rc;
+use {
+    field_name, sub_type_constructor, union_constructor, EnumAdded, EnumCodegen, Haskell,
+    InterfaceAdded, InterfaceCodegen, NewtypeAdded, NewtypeCodegen, Options, TupleAdded,
+    TupleCodegen, TypeAdded, TypeCodegen, UnionAdded, UnionCodegen,
+};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        options.imports.extend(&[
+            "import Control.Applicative ((<|>))",
+            "import Data.Aeson ((.:), (.:?), (.=))",
+            "import Data.Maybe (catMaybes)",
+            "import qualified Data.Vector as Vector",
+        ]);
+
+        let codegen = Rc::new(Codegen::new());
+        options.type_gens.push(Box::new(codegen.clone()));
+        options.tuple_gens.push(Box::new(codegen.clone()));
+        options.enum_gens.push(Box::new(codegen.clone()));
+        options.interface_gens.push(Box::new(codegen.clone()));
+        options.union_gens.push(Box::new(codegen.clone()));
+        options.newtype_gens.push(Box::new(codegen.clone()));
+        Ok(())
+    }
+}
+
+/// Build a list literal with one item per line.
+fn list<'el>(items: Vec<Tokens<'el, Haskell>>) -> Tokens<'el, Haskell> {
+    let mut t = Tokens::new();
+
+    if items.is_empty() {
+        t.push("[]");
+        return t;
+    }
+
+    let mut separator = "[ ";
+
+    for item in items {
+        t.push(toks![separator, item]);
+        separator = ", ";
+    }
+
+    t.push("]");
+    t
+}
+
+/// Build alternative parsers, which are attempted in order.
+fn alternatives<'el>(parsers: Vec<Tokens<'el, Haskell>>, error: &'el str) -> Tokens<'el, Haskell> {
+    let mut t = Tokens::new();
+    let mut prefix = "";
+
+    for p in parsers {
+        t.push(toks![prefix, "(", p, ")"]);
+        prefix = "<|> ";
+    }
+
+    t.push(toks![prefix, "fail ", error.quoted()]);
+    t
+}
+
+struct Codegen {}
+
+impl Codegen {
+    pub fn new() -> Codegen {
+        Codegen {}
+    }
+
+    /// Instances for a record, which is encoded as an object.
+    ///
+    /// If a tag is specified, it is added to the encoded object.
+    fn record<'el, I>(
+        &self,
+        name: &'el HaskellName,
+        tag: Option<(&'el str, &'el str)>,
+        fields: I,
+    ) -> Tokens<'el, Haskell>
+    where
+        I: IntoIterator<Item = &'el RpField>,
+    {
+        let fields = fields.into_iter().collect::<Vec<_>>();
+
+        let mut t = Tokens::new();
+
+        t.push_into(|t| {
+            push!(t, "instance Aeson.FromJSON ", name, " where");
+
+            t.nested_into(|t| {
+                let ty = name.name.clone().quoted();
+
+                if fields.is_empty() {
+                    push!(
+                        t,
+                        "parseJSON = Aeson.withObject ",
+                        ty,
+                        " $ \\_ -> pure ",
+                        name
+                    );
+                    return;
+                }
+
+                push!(t, "parseJSON = Aeson.withObject ", ty, " $ \\o ->");
+
+                t.nested_into(|t| {
+                    t.push(toks![name]);
+
+                    t.nested_into(|t| {
+                        let mut operator = "<$> ";
+
+                        for f in &fields {
+                            let get = if f.is_optional() { " .:? " } else { " .: " };
+                            push!(t, operator, "o", get, f.name().quoted());
+                            operator = "<*> ";
+                        }
+                    });
+                });
+            });
+        });
+
+        t.push_into(|t| {
+            push!(t, "instance Aeson.ToJSON ", name, " where");
+
+            t.nested_into(|t| {
+                let mut pairs = Vec::new();
+
+                if let Some((tag, value)) = tag {
+                    pairs.push(toks![
+                        "Just (",
+                        tag.quoted(),
+                        " .= (",
+                        value.quoted(),
+                        " :: Text.Text))",
+                    ]);
+                }
+
+                for f in &fields {
+                    let accessor = field_name(name, f);
+
+                    if f.is_optional() {
+                        pairs.push(toks!["(", f.name().quoted(), " .=) <$> ", accessor, " v"]);
+                    } else {
+                        pairs.push(toks!["Just (", f.name().quoted(), " .= ", accessor, " v)",]);
+                    }
+                }
+
+                if pairs.is_empty() {
+                    push!(t, "toJSON _ = Aeson.object []");
+                    return;
+                }
+
+                push!(t, "toJSON v =");
+
+                t.nested_into(|t| {
+                    push!(t, "Aeson.object $");
+
+                    t.nested_into(|t| {
+                        push!(t, "catMaybes");
+                        t.nested(list(pairs));
+                    });
+                });
+            });
+        });
+
+        t.join_line_spacing()
+    }
+}
+
+impl TypeCodegen for Codegen {
+    fn generate(&self, e: TypeAdded) -> Result<()> {
+        let TypeAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        container.push(self.record(name, None, body.fields.iter().map(Loc::borrow)));
+        Ok(())
+    }
+}
+
+impl TupleCodegen for Codegen {
+    fn generate(&self, e: TupleAdded) -> Result<()> {
+        let TupleAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.FromJSON ", name, " where");
+
+            t.nested_into(|t| {
+                push!(
+                    t,
+                    "parseJSON = Aeson.withArray ",
+                    name.name.clone().quoted(),
+                    " $ \\a ->"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "case Vector.toList a of");
+
+                    t.nested_into(|t| {
+                        let mut values = Tokens::new();
+                        let mut parse = toks![name];
+                        let mut operator = " <$> ";
+
+                        for (i, _) in body.fields.iter().enumerate() {
+                            let value = format!("a{}", i);
+                            values.append(value.clone());
+                            parse.append(toks![operator, "Aeson.parseJSON ", value]);
+                            operator = " <*> ";
+                        }
+
+                        if body.fields.is_empty() {
+                            parse = toks!["pure ", name];
+                        }
+
+                        push!(t, "[", values.join(", "), "] -> ", parse);
+
+                        let error = Rc::new(format!(
+                            "expected array with {} elements",
+                            body.fields.len()
+                        ));
+
+                        push!(t, "_ -> fail ", error.quoted());
+                    });
+                });
+            });
+        });
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.ToJSON ", name, " where");
+
+            t.nested_into(|t| {
+                let values = body
+                    .fields
+                    .iter()
+                    .map(|f| toks!["Aeson.toJSON (", field_name(name, f), " v)"])
+                    .collect::<Vec<_>>();
+
+                if values.is_empty() {
+                    push!(t, "toJSON _ = Aeson.toJSON ([] :: [Aeson.Value])");
+                    return;
+                }
+
+                push!(t, "toJSON v =");
+
+                t.nested_into(|t| {
+                    push!(t, "Aeson.toJSON");
+                    t.nested(list(values));
+                });
+            });
+        });
+
+        Ok(())
+    }
+}
+
+impl EnumCodegen for Codegen {
+    fn generate(&self, e: EnumAdded) -> Result<()> {
+        let EnumAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.FromJSON ", name, " where");
+
+            t.nested_into(|t| match body.variants {
+                core::RpVariants::String { ref variants } => {
+                    push!(
+                        t,
+                        "parseJSON = Aeson.withText ",
+                        name.name.clone().quoted(),
+                        " $ \\s ->"
+                    );
+
+                    t.nested_into(|t| {
+                        push!(t, "case s of");
+
+                        t.nested_into(|t| {
+                            for v in variants {
+                                let constructor = format!("{}_{}", name, v.ident.as_str());
+
+                                // NB: aliases are only accepted when decoding.
+                                let aliases = v.aliases.iter().map(|a| a.as_str());
+
+                                for value in Some(v.value.as_str()).into_iter().chain(aliases) {
+                                    push!(t, value.quoted(), " -> pure ", constructor);
+                                }
+                            }
+
+                            push!(
+                                t,
+                                "_ -> fail (",
+                                "unknown variant: ".quoted(),
+                                " ++ Text.unpack s)"
+                            );
+                        });
+                    });
+                }
+                core::RpVariants::Number { ref variants } => {
+                    push!(t, "parseJSON v = do");
+
+                    t.nested_into(|t| {
+                        push!(t, "n <- Aeson.parseJSON v");
+                        push!(t, "case (n :: Integer) of");
+
+                        t.nested_into(|t| {
+                            for v in variants {
+                                let constructor = format!("{}_{}", name, v.ident.as_str());
+
+                                let aliases = v.aliases.iter().map(|a| a.to_string());
+
+                                for value in Some(v.value.to_string()).into_iter().chain(aliases) {
+                                    push!(t, value, " -> pure ", constructor);
+                                }
+                            }
+
+                            push!(
+                                t,
+                                "_ -> fail (",
+                                "unknown variant: ".quoted(),
+                                " ++ show n)"
+                            );
+                        });
+                    });
+                }
+            });
+        });
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.ToJSON ", name, " where");
+
+            t.nested_into(|t| match body.variants {
+                core::RpVariants::String { ref variants } => {
+                    for v in variants {
+                        push!(
+                            t,
+                            "toJSON ",
+                            name,
+                            "_",
+                            v.ident.as_str(),
+                            " = Aeson.String ",
+                            v.value.as_str().quoted()
+                        );
+                    }
+                }
+                core::RpVariants::Number { ref variants } => {
+                    for v in variants {
+                        push!(
+                            t,
+                            "toJSON ",
+                            name,
+                            "_",
+                            v.ident.as_str(),
+                            " = Aeson.toJSON (",
+                            v.value.to_string(),
+                            " :: Integer)"
+                        );
+                    }
+                }
+            });
+        });
+
+        Ok(())
+    }
+}
+
+impl InterfaceCodegen for Codegen {
+    fn generate(&self, e: InterfaceAdded) -> Result<()> {
+        let InterfaceAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.FromJSON ", name, " where");
+
+            t.nested_into(|t| match body.sub_type_strategy {
+                RpSubTypeStrategy::Tagged { ref tag } => {
+                    push!(
+                        t,
+                        "parseJSON = Aeson.withObject ",
+                        name.name.clone().quoted(),
+                        " $ \\o -> do"
+                    );
+
+                    t.nested_into(|t| {
+                        push!(t, "tag <- o .: ", tag.as_str().quoted());
+                        push!(t, "case (tag :: Text.Text) of");
+
+                        t.nested_into(|t| {
+                            for s in &body.sub_types {
+                                push!(
+                                    t,
+                                    s.name().quoted(),
+                                    " -> ",
+                                    sub_type_constructor(body, s),
+                                    " <$> Aeson.parseJSON (Aeson.Object o)"
+                                );
+                            }
+
+                            push!(
+                                t,
+                                "_ -> fail (",
+                                "unknown type: ".quoted(),
+                                " ++ Text.unpack tag)"
+                            );
+                        });
+                    });
+                }
+                RpSubTypeStrategy::Untagged => {
+                    push!(t, "parseJSON v =");
+
+                    let parsers = body
+                        .sub_types
+                        .iter()
+                        .map(|s| toks![sub_type_constructor(body, s), " <$> Aeson.parseJSON v"])
+                        .collect();
+
+                    t.nested(alternatives(parsers, "no matching sub-type"));
+                }
+            });
+        });
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.ToJSON ", name, " where");
+
+            t.nested_into(|t| {
+                for s in &body.sub_types {
+                    push!(
+                        t,
+                        "toJSON (",
+                        sub_type_constructor(body, s),
+                        " v) = Aeson.toJSON v"
+                    );
+                }
+            });
+        });
+
+        for s in &body.sub_types {
+            let tag = match body.sub_type_strategy {
+                RpSubTypeStrategy::Tagged { ref tag } => Some((tag.as_str(), s.name())),
+                RpSubTypeStrategy::Untagged => None,
+            };
+
+            let fields = body.fields.iter().chain(s.fields.iter()).map(Loc::borrow);
+            container.push(self.record(&s.name, tag, fields));
+        }
+
+        Ok(())
+    }
+}
+
+impl UnionCodegen for Codegen {
+    fn generate(&self, e: UnionAdded) -> Result<()> {
+        let UnionAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.FromJSON ", name, " where");
+
+            t.nested_into(|t| {
+                push!(t, "parseJSON v =");
+
+                let parsers = body
+                    .variants
+                    .iter()
+                    .map(|v| toks![union_constructor(body, v), " <$> Aeson.parseJSON v"])
+                    .collect();
+
+                t.nested(alternatives(parsers, "no matching variant"));
+            });
+        });
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.ToJSON ", name, " where");
+
+            t.nested_into(|t| {
+                for v in &body.variants {
+                    push!(
+                        t,
+                        "toJSON (",
+                        union_constructor(body, v),
+                        " v) = Aeson.toJSON v"
+                    );
+                }
+            });
+        });
+
+        Ok(())
+    }
+}
+
+impl NewtypeCodegen for Codegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded {
+            container, name, ..
+        } = e;
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.FromJSON ", name, " where");
+            nested!(t, "parseJSON v = ", name, " <$> Aeson.parseJSON v");
+        });
+
+        container.push_into(|t| {
+            push!(t, "instance Aeson.ToJSON ", name, " where");
+            nested!(t, "toJSON (", name, " v) = Aeson.toJSON v");
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+mod aeson;
+
+pub use self::aeson::Module as Aeson;
@@ -1,9 +1,9 @@
 //! Code generator for the given path.
 
 use core::errors::Result;
-use core::Handle;
-use flavored::{RpEnumBody, RpInterfaceBody, RpServiceBody};
-use genco::java::{Class, Enum, Interface, Method};
+use core::{Handle, Loc};
+use flavored::{JavaField, RpEnumBody, RpInterfaceBody, RpNewtypeBody, RpServiceBody, RpUnionBody};
+use genco::java::{Class, Constructor, Enum, Interface, Method};
 use std::rc::Rc;
 use Options;
 
@@ -28,6 +28,7 @@ pub struct GetterAdded<'a, 'el: 'a> {
 
 pub struct ClassAdded<'a, 'el: 'a> {
     pub names: &'a [&'el str],
+    pub fields: &'a [&'el Loc<JavaField<'static>>],
     pub spec: &'a mut Class<'el>,
     pub interface: Option<&'a RpInterfaceBody>,
 }
@@ -48,6 +49,19 @@ pub struct InterfaceAdded<'a, 'el: 'a> {
     pub spec: &'a mut Interface<'el>,
 }
 
+pub struct UnionAdded<'a, 'el: 'a> {
+    pub body: &'el RpUnionBody,
+    pub spec: &'a mut Interface<'el>,
+    pub getter: &'a mut Method<'el>,
+}
+
+pub struct NewtypeAdded<'a, 'el: 'a> {
+    pub body: &'el RpNewtypeBody,
+    pub spec: &'a mut Class<'el>,
+    pub constructor: &'a mut Constructor<'el>,
+    pub getter: &'a mut Method<'el>,
+}
+
 pub struct ServiceAdded<'a, 'el: 'a> {
     pub body: &'el RpServiceBody,
     pub spec: &'a mut Interface<'el>,
@@ -103,3 +117,17 @@ pub trait EnumCodegen {
 }
 
 codegen!(EnumCodegen, EnumAdded);
+
+/// Generate union-based code.
+pub trait UnionCodegen {
+    fn generate(&self, e: UnionAdded) -> Result<()>;
+}
+
+codegen!(UnionCodegen, UnionAdded);
+
+/// Generate newtype-based code.
+pub trait NewtypeCodegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()>;
+}
+
+codegen!(NewtypeCodegen, NewtypeAdded);
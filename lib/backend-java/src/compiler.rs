@@ -1,24 +1,38 @@
 //! Java backend for reproto
 
-use codegen::{ClassAdded, EnumAdded, GetterAdded, InterfaceAdded, ServiceAdded, TupleAdded};
+use backend::UNKNOWN_VARIANT;
+use codegen::{
+    ClassAdded, EnumAdded, GetterAdded, InterfaceAdded, NewtypeAdded, ServiceAdded, TupleAdded,
+    UnionAdded,
+};
 use core::errors::*;
 use core::{self, Handle, Loc};
 use flavored::{
-    JavaField, JavaFlavor, RpCode, RpDecl, RpEnumBody, RpInterfaceBody, RpServiceBody, RpTupleBody,
-    RpTypeBody,
+    JavaField, JavaFlavor, RpCode, RpDecl, RpEnumBody, RpInterfaceBody, RpNewtypeBody,
+    RpServiceBody, RpTupleBody, RpTypeBody, RpUnionBody,
 };
 use genco::java::{
     self, imported, local, Argument, Class, Constructor, Enum, Field, Interface, Method, Modifier,
-    BOOLEAN, INTEGER,
+    BOOLEAN, INTEGER, VOID,
 };
 use genco::{Cons, Element, Java, Quoted, Tokens};
 use java_file::JavaFile;
 use naming::{self, Naming};
+use std::collections::HashMap;
 use std::rc::Rc;
 use trans::{Packages, Translated};
-use utils::{Observer, Override};
+use utils::{error_exception_class, union_variant_class, Deprecated, Javadoc, Observer, Override};
 use Options;
 
+/// Prefix to use for deprecated enum variants.
+fn deprecated_variant<V>(variant: &core::RpVariant<JavaFlavor, V>) -> &'static str {
+    if variant.deprecated.is_some() {
+        "@Deprecated "
+    } else {
+        ""
+    }
+}
+
 /// Helper macro to implement listeners opt loop.
 fn code<'el>(codes: &'el [Loc<RpCode>]) -> Tokens<'el, Java<'el>> {
     let mut t = Tokens::new();
@@ -68,6 +82,7 @@ pub struct Compiler<'el> {
     string: Java<'static>,
     pub optional: Java<'static>,
     illegal_argument: Java<'static>,
+    runtime_exception: Java<'static>,
 }
 
 impl<'el> Compiler<'el> {
@@ -84,6 +99,7 @@ impl<'el> Compiler<'el> {
             string: imported("java.lang", "String"),
             optional: imported("java.util", "Optional"),
             illegal_argument: imported("java.lang", "IllegalArgumentException"),
+            runtime_exception: imported("java.lang", "RuntimeException"),
         }
     }
 
@@ -130,6 +146,69 @@ impl<'el> Compiler<'el> {
         field
     }
 
+    /// Check if values of the given field might be `null`.
+    fn is_nullable(&self, field: &JavaField<'static>) -> bool {
+        match field.spec.ty() {
+            Java::Primitive { .. } => false,
+            _ => {
+                self.options.nullable
+                    || (field.is_optional() && self.options.optional_strategy.is_nullable())
+            }
+        }
+    }
+
+    /// Nullability annotation for values of the given field, if enabled.
+    fn nullability(&self, field: &JavaField<'static>) -> Option<Tokens<'el, Java<'el>>> {
+        let annotations = self.options.nullability.as_ref()?;
+
+        if let Java::Primitive { .. } = field.spec.ty() {
+            return None;
+        }
+
+        let annotation = if self.is_nullable(field) {
+            annotations.nullable.clone()
+        } else {
+            annotations.non_null.clone()
+        };
+
+        Some(toks!["@", annotation])
+    }
+
+    /// Build the field spec for the given field.
+    fn field_spec(&self, field: &JavaField<'static>) -> Field<'el> {
+        let mut spec = field.spec.clone();
+
+        if let Some(nullability) = self.nullability(field) {
+            spec.annotation(nullability);
+        }
+
+        spec
+    }
+
+    /// Build a getter for the given field.
+    fn getter(&self, field: &JavaField<'static>) -> Method<'el> {
+        let mut getter = field.getter();
+
+        if let Some(nullability) = self.nullability(field) {
+            getter.annotation(nullability);
+        }
+
+        getter
+    }
+
+    /// Build a setter for the given field, unless the field is immutable.
+    fn setter(&self, field: &JavaField<'static>) -> Option<Method<'el>> {
+        let mut setter = field.setter()?;
+
+        if let Some(nullability) = self.nullability(field) {
+            for argument in &mut setter.arguments {
+                argument.annotation(nullability.clone());
+            }
+        }
+
+        Some(setter)
+    }
+
     fn build_constructor<F>(&self, fields: F) -> Constructor<'el>
     where
         F: IntoIterator<Item = &'el Loc<JavaField<'static>>>,
@@ -139,9 +218,13 @@ impl<'el> Compiler<'el> {
         for field in fields {
             let spec = &field.spec;
 
-            let argument = Argument::new(spec.ty(), spec.var());
+            let mut argument = Argument::new(spec.ty(), spec.var());
+
+            if let Some(nullability) = self.nullability(field) {
+                argument.annotation(nullability);
+            }
 
-            if !self.options.nullable {
+            if !self.is_nullable(field) {
                 if let Some(non_null) = self.require_non_null(spec, &argument, field.name()) {
                     c.body.push(non_null);
                 }
@@ -186,6 +269,7 @@ impl<'el> Compiler<'el> {
         hash_code.body.push("int result = 1;");
 
         for field in fields {
+            let nullable = self.is_nullable(field);
             let field = &field.spec;
 
             let field_toks = toks!["this.", field.var()];
@@ -201,11 +285,8 @@ impl<'el> Compiler<'el> {
                 _ => toks![field_toks.clone(), ".hashCode()"],
             };
 
-            let value = if self.options.nullable {
-                match field.ty() {
-                    Java::Primitive { .. } => value,
-                    _ => toks!["(", field_toks.clone(), " != null ? 0 : ", value, ")"],
-                }
+            let value = if nullable {
+                toks!["(", field_toks.clone(), " == null ? 0 : ", value, ")"]
             } else {
                 value
             };
@@ -242,7 +323,9 @@ impl<'el> Compiler<'el> {
             equals.body.push(null_check);
         }
 
-        // check that argument is expected type.
+        let pattern_matching = self.options.pattern_matching_instanceof();
+
+        // check that argument is expected type, binding it to `o` if supported.
         equals.body.push({
             let mut t = Tokens::new();
 
@@ -251,7 +334,7 @@ impl<'el> Compiler<'el> {
                 argument.var(),
                 " instanceof ",
                 name.clone(),
-                ")) {",
+                if pattern_matching { " o)) {" } else { ")) {" },
             ]);
             t.nested("return false;");
             t.push("}");
@@ -259,31 +342,34 @@ impl<'el> Compiler<'el> {
         });
 
         // cast argument.
-        equals.body.push({
-            let mut t = Tokens::new();
-
-            t.push(toks![
-                "@",
-                self.suppress_warnings.clone(),
-                "(",
-                "unchecked".quoted(),
-                ")",
-            ]);
+        if !pattern_matching {
+            equals.body.push({
+                let mut t = Tokens::new();
 
-            t.push(toks![
-                "final ",
-                name.clone(),
-                " o = (",
-                name.clone(),
-                ") ",
-                argument.var(),
-                ";",
-            ]);
+                t.push(toks![
+                    "@",
+                    self.suppress_warnings.clone(),
+                    "(",
+                    "unchecked".quoted(),
+                    ")",
+                ]);
+
+                t.push(toks![
+                    "final ",
+                    name.clone(),
+                    " o = (",
+                    name.clone(),
+                    ") ",
+                    argument.var(),
+                    ";",
+                ]);
 
-            t
-        });
+                t
+            });
+        }
 
         for field in fields {
+            let nullable = self.is_nullable(field);
             let field = &field.spec;
             let field_toks = toks!["this.", field.var()];
             let o = toks!["o.", field.var()];
@@ -299,7 +385,7 @@ impl<'el> Compiler<'el> {
             equals_check.nested("return false;");
             equals_check.push("}");
 
-            if self.options.nullable {
+            if nullable {
                 let mut null_check = Tokens::new();
 
                 null_check.push(toks!["if (", o, " != null) {"]);
@@ -355,7 +441,7 @@ impl<'el> Compiler<'el> {
                 _ => {
                     let format = toks![field_toks.clone(), ".toString()"];
 
-                    if self.options.nullable {
+                    if self.is_nullable(field) {
                         toks![
                             field_toks.clone(),
                             " == null ? ",
@@ -435,7 +521,8 @@ impl<'el> Compiler<'el> {
             let mut argument = Argument::new(field.ty(), field.var());
             argument.modifiers = vec![Final];
 
-            if !self.options.nullable {
+            // the unknown variant has a `null` value.
+            if !self.options.nullable && !self.options.unknown_variant {
                 if let Some(non_null) = self.require_non_null(&field, &argument, "value".into()) {
                     c.body.push(non_null);
                 }
@@ -450,13 +537,55 @@ impl<'el> Compiler<'el> {
         c
     }
 
-    fn enum_from_value_method(&self, name: Cons<'el>, field: &Field<'static>) -> Method<'el> {
+    fn enum_from_value_method(
+        &self,
+        name: Cons<'el>,
+        field: &Field<'static>,
+        body: &'el RpEnumBody,
+    ) -> Method<'el> {
         use self::Modifier::*;
 
         let argument = Argument::new(field.ty(), field.var());
 
+        // check aliases, which are only accepted when decoding.
+        let mut alias_checks = Tokens::new();
+
+        for variant in body.variants.iter() {
+            let variant_name = self.variant_naming.convert(variant.ident());
+
+            for alias in variant.aliases.iter() {
+                let value = match alias {
+                    core::RpVariantValue::String(string) => toks![string.quoted()],
+                    core::RpVariantValue::Number(number) => match body.enum_type {
+                        java::LONG => toks![format!("{}L", number)],
+                        _ => toks![number.to_string()],
+                    },
+                };
+
+                let cond = match field.ty() {
+                    Java::Primitive { .. } => toks![argument.var(), " == ", value],
+                    _ => toks![argument.var(), ".equals(", value, ")"],
+                };
+
+                alias_checks.push_into(|t| {
+                    push!(t, "if (", cond, ") {");
+                    nested!(t, "return ", variant_name.clone(), ";");
+                    push!(t, "}");
+                });
+            }
+        }
+
         // use naming convention that won't be generated
         let cond = match field.ty() {
+            // the unknown variant has a `null` value.
+            _ if self.options.unknown_variant => toks![
+                self.objects.clone(),
+                ".equals(v_value.",
+                field.var(),
+                ", ",
+                argument.var(),
+                ")"
+            ],
             Java::Primitive { .. } => toks!["v_value.", field.var(), " == ", argument.var()],
             _ => toks!["v_value.", field.var(), ".equals(", argument.var(), ")"],
         };
@@ -484,16 +613,24 @@ impl<'el> Compiler<'el> {
         from_value.modifiers = vec![Public, Static];
         from_value.returns = local(name.clone());
 
-        let throw = toks![
-            "throw new ",
-            self.illegal_argument.clone(),
-            "(",
-            argument.var().quoted(),
-            ");",
-        ];
+        let fallback = if self.options.unknown_variant {
+            let unknown = self.variant_naming.convert(UNKNOWN_VARIANT);
+            toks!["return ", unknown, ";"]
+        } else {
+            toks![
+                "throw new ",
+                self.illegal_argument.clone(),
+                "(",
+                argument.var().quoted(),
+                ");",
+            ]
+        };
 
         from_value.body.push(value_loop);
-        from_value.body.push(throw);
+        from_value
+            .body
+            .push_unless_empty(alias_checks.join_line_spacing());
+        from_value.body.push(fallback);
         from_value.body = from_value.body.join_line_spacing();
 
         from_value.arguments.push(argument);
@@ -511,19 +648,42 @@ impl<'el> Compiler<'el> {
     fn process_enum(&self, body: &'el RpEnumBody) -> Result<Enum<'el>> {
         let mut spec = Enum::new(body.ident.clone());
 
-        spec.fields
-            .push(self.new_field_spec(&body.enum_type, "value"));
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
+        // the unknown variant needs a nullable value.
+        let enum_type = if self.options.unknown_variant {
+            if body.variants.iter().any(|v| v.ident() == UNKNOWN_VARIANT) {
+                return Err(format!(
+                    "{}: variant `{}` conflicts with the generated unknown variant",
+                    body.name, UNKNOWN_VARIANT
+                ).into());
+            }
+
+            body.enum_type.as_boxed()
+        } else {
+            body.enum_type.clone()
+        };
+
+        spec.fields.push(self.new_field_spec(&enum_type, "value"));
 
         match body.variants {
             core::RpVariants::String { ref variants } => for variant in variants {
                 let name = self.variant_naming.convert(variant.ident());
-                push!(
-                    spec.variants,
-                    name,
-                    "(",
-                    variant.value.clone().quoted(),
-                    ")"
-                );
+                let deprecated = deprecated_variant(&variant);
+
+                spec.variants.push_into(|t| {
+                    t.push_unless_empty(Javadoc(&variant.comment));
+                    push!(
+                        t,
+                        deprecated,
+                        name,
+                        "(",
+                        variant.value.clone().quoted(),
+                        ")"
+                    );
+                });
             },
             core::RpVariants::Number { ref variants } => for variant in variants {
                 let name = self.variant_naming.convert(variant.ident());
@@ -533,16 +693,26 @@ impl<'el> Compiler<'el> {
                     _ => variant.value.to_string(),
                 };
 
-                push!(spec.variants, name, "(", value, ")");
+                let deprecated = deprecated_variant(&variant);
+
+                spec.variants.push_into(|t| {
+                    t.push_unless_empty(Javadoc(&variant.comment));
+                    push!(t, deprecated, name, "(", value, ")");
+                });
             },
         }
 
+        if self.options.unknown_variant {
+            let name = self.variant_naming.convert(UNKNOWN_VARIANT);
+            push!(spec.variants, name, "(null)");
+        }
+
         spec.constructors
             .push(self.build_enum_constructor(&spec.fields));
 
-        let variant_field = java::Field::new(body.enum_type.clone(), "value");
+        let variant_field = java::Field::new(enum_type, "value");
 
-        let mut from_value = self.enum_from_value_method(spec.name(), &variant_field);
+        let mut from_value = self.enum_from_value_method(spec.name(), &variant_field, body);
         let mut to_value = self.enum_to_value_method(&variant_field);
 
         call_codegen!(
@@ -565,6 +735,10 @@ impl<'el> Compiler<'el> {
     fn process_tuple(&self, body: &'el RpTupleBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.ident.clone());
 
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
         self.add_class(
             spec.name(),
             &body.fields,
@@ -574,7 +748,7 @@ impl<'el> Compiler<'el> {
 
         for field in &body.fields {
             if self.options.build_getters {
-                let mut getter = field.getter();
+                let mut getter = self.getter(field);
 
                 call_codegen!(
                     &self.options.getter_generators,
@@ -588,12 +762,12 @@ impl<'el> Compiler<'el> {
             }
 
             if self.options.build_setters {
-                if let Some(setter) = field.setter() {
+                if let Some(setter) = self.setter(field) {
                     spec.methods.push(setter);
                 }
             }
 
-            spec.fields.push(field.spec.clone());
+            spec.fields.push(self.field_spec(field));
         }
 
         spec.body.push_unless_empty(code(&body.codes));
@@ -608,13 +782,18 @@ impl<'el> Compiler<'el> {
 
     fn process_type(&self, body: &'el RpTypeBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.ident.clone());
+        let fields: Vec<_> = body.fields.iter().collect();
         let names: Vec<_> = body.fields.iter().map(|f| f.name()).collect();
 
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
         for field in &body.fields {
-            spec.fields.push(field.spec.clone());
+            spec.fields.push(self.field_spec(field));
 
             if self.options.build_getters {
-                let mut getter = field.getter();
+                let mut getter = self.getter(field);
 
                 call_codegen!(
                     &self.options.getter_generators,
@@ -628,7 +807,7 @@ impl<'el> Compiler<'el> {
             }
 
             if self.options.build_setters {
-                if let Some(setter) = field.setter() {
+                if let Some(setter) = self.setter(field) {
                     spec.methods.push(setter);
                 }
             }
@@ -646,6 +825,7 @@ impl<'el> Compiler<'el> {
         for generator in &self.options.class_generators {
             generator.generate(ClassAdded {
                 names: &names,
+                fields: &fields,
                 spec: &mut spec,
                 interface: None,
             })?;
@@ -662,9 +842,17 @@ impl<'el> Compiler<'el> {
         use self::Modifier::*;
         let mut spec = Interface::new(body.ident.clone());
 
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
         for field in &body.fields {
             let mut m = field.getter_without_body();
             m.modifiers = vec![];
+
+            if let Some(nullability) = self.nullability(field) {
+                m.annotation(nullability);
+            }
             spec.methods.push(m);
         }
 
@@ -674,6 +862,10 @@ impl<'el> Compiler<'el> {
             let mut class = Class::new(sub_type.ident.clone());
             class.modifiers = vec![Public, Static];
 
+            if sub_type.deprecated.is_some() {
+                class.annotation(Deprecated);
+            }
+
             class.body.push_unless_empty(code(&sub_type.codes));
 
             class.implements = vec![local(spec.name())];
@@ -681,7 +873,7 @@ impl<'el> Compiler<'el> {
             // override methods for interface fields.
             for field in &body.fields {
                 if self.options.build_getters {
-                    let mut getter = field.getter();
+                    let mut getter = self.getter(field);
                     getter.annotation(Override);
 
                     call_codegen!(
@@ -696,7 +888,7 @@ impl<'el> Compiler<'el> {
                 }
 
                 if self.options.build_setters {
-                    if let Some(mut setter) = field.setter() {
+                    if let Some(mut setter) = self.setter(field) {
                         setter.annotation(Override);
                         class.methods.push(setter);
                     }
@@ -705,7 +897,7 @@ impl<'el> Compiler<'el> {
 
             for field in &sub_type.fields {
                 if self.options.build_getters {
-                    let mut getter = field.getter();
+                    let mut getter = self.getter(field);
 
                     call_codegen!(
                         &self.options.getter_generators,
@@ -719,7 +911,7 @@ impl<'el> Compiler<'el> {
                 }
 
                 if self.options.build_setters {
-                    if let Some(setter) = field.setter() {
+                    if let Some(setter) = self.setter(field) {
                         class.methods.push(setter);
                     }
                 }
@@ -729,11 +921,13 @@ impl<'el> Compiler<'el> {
             fields.extend(sub_type.fields.iter());
             let names: Vec<_> = fields.iter().map(|f| f.name()).collect();
 
-            class.fields.extend(fields.iter().map(|f| f.spec.clone()));
+            class
+                .fields
+                .extend(fields.iter().map(|f| self.field_spec(f)));
 
             self.add_class(
                 class.name(),
-                fields,
+                fields.iter().cloned(),
                 &mut class.methods,
                 &mut class.constructors,
             )?;
@@ -741,6 +935,7 @@ impl<'el> Compiler<'el> {
             for generator in &self.options.class_generators {
                 generator.generate(ClassAdded {
                     names: &names,
+                    fields: &fields,
                     spec: &mut class,
                     interface: Some(body),
                 })?;
@@ -764,9 +959,296 @@ impl<'el> Compiler<'el> {
         Ok(spec)
     }
 
+    fn process_union(&self, body: &'el RpUnionBody) -> Result<Interface<'el>> {
+        use self::Modifier::*;
+
+        let mut spec = Interface::new(body.ident.clone());
+
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
+        let mut getter = Method::new("getValue");
+        getter.modifiers = vec![];
+        getter.returns = self.object.clone();
+
+        for variant in &body.variants {
+            let ty = variant.ty.as_boxed();
+
+            let mut class = Class::new(Rc::new(union_variant_class(variant.ident.as_str())));
+            let name = class.name();
+            class.modifiers = vec![Public, Static];
+            class.implements = vec![local(spec.name())];
+
+            let mut field = Field::new(ty.clone(), "value");
+            field.modifiers = vec![Private, Final];
+
+            let argument = Argument::new(ty.clone(), "value");
+
+            let mut c = Constructor::new();
+            c.arguments.push(argument.clone());
+
+            if let Some(non_null) = self.require_non_null(&field, &argument, "value") {
+                c.body.push(non_null);
+            }
+
+            push!(c.body, "this.value = ", argument.var(), ";");
+            class.constructors.push(c);
+
+            let mut m = Method::new("getValue");
+            m.annotation(Override);
+            m.returns = ty.clone();
+            m.body.push("return this.value;");
+            class.methods.push(m);
+
+            if self.options.build_hash_code {
+                let mut m = Method::new("hashCode");
+                m.annotation(Override);
+                m.returns = INTEGER;
+                m.body.push("return this.value.hashCode();");
+                class.methods.push(m);
+            }
+
+            if self.options.build_equals {
+                let other = Argument::new(self.object.clone(), "other");
+
+                let mut m = Method::new("equals");
+                m.annotation(Override);
+                m.returns = BOOLEAN;
+                m.arguments.push(other.clone());
+
+                m.body.push_into(|t| {
+                    let test = toks![other.var(), " instanceof ", name.clone()];
+                    push!(t, "if (!(", test, ")) {");
+                    nested!(t, "return false;");
+                    push!(t, "}");
+                });
+
+                m.body.push(toks![
+                    "return this.value.equals(((",
+                    name.clone(),
+                    ") ",
+                    other.var(),
+                    ").value);",
+                ]);
+
+                m.body = m.body.join_line_spacing();
+                class.methods.push(m);
+            }
+
+            if self.options.build_to_string {
+                let prefix = Rc::new(format!("{}.{}(", body.ident, variant.ident)).quoted();
+
+                let mut m = Method::new("toString");
+                m.annotation(Override);
+                m.returns = self.string.clone();
+                m.body.push(toks![
+                    "return ",
+                    prefix,
+                    " + this.value + ",
+                    ")".quoted(),
+                    ";",
+                ]);
+                class.methods.push(m);
+            }
+
+            class.fields.push(field);
+            spec.body.push(class);
+        }
+
+        call_codegen!(
+            &self.options.union_generators,
+            UnionAdded {
+                body: body,
+                spec: &mut spec,
+                getter: &mut getter,
+            }
+        );
+
+        spec.methods.push(getter);
+        Ok(spec)
+    }
+
+    fn process_newtype(&self, body: &'el RpNewtypeBody) -> Result<Class<'el>> {
+        use self::Modifier::*;
+
+        let ty = body.ty.as_boxed();
+
+        let mut spec = Class::new(body.ident.clone());
+        let name = spec.name();
+
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
+        let mut field = Field::new(ty.clone(), "value");
+        field.modifiers = vec![Private, Final];
+
+        let argument = Argument::new(ty.clone(), "value");
+
+        let mut validate = Method::new("validate");
+        validate.modifiers = vec![Public, Static];
+        validate.returns = VOID;
+        validate.arguments.push(argument.clone());
+
+        if let Some(non_null) = self.require_non_null(&field, &argument, "value") {
+            validate.body.push(non_null);
+        }
+
+        if let Some(ref pattern) = body.validate.pattern {
+            let pattern_class = imported("java.util.regex", "Pattern");
+
+            let mut pattern_field = Field::new(pattern_class.clone(), "PATTERN");
+            pattern_field.modifiers = vec![Private, Static, Final];
+            pattern_field.initializer(toks![
+                pattern_class,
+                ".compile(",
+                Rc::new(pattern.to_string()).quoted(),
+                ")"
+            ]);
+
+            let error = Rc::new(format!("value does not match pattern `{}`", pattern)).quoted();
+            let illegal_argument = self.illegal_argument.clone();
+
+            validate.body.push_into(|t| {
+                push!(t, "if (!PATTERN.matcher(", argument.var(), ").find()) {");
+                nested!(t, "throw new ", illegal_argument, "(", error, ");");
+                push!(t, "}");
+            });
+
+            spec.fields.push(pattern_field);
+        }
+
+        let mut c = Constructor::new();
+        c.arguments.push(argument.clone());
+        push!(c.body, "validate(", argument.var(), ");");
+        push!(c.body, "this.value = ", argument.var(), ";");
+
+        let mut getter = Method::new("getValue");
+        getter.returns = ty.clone();
+        getter.body.push("return this.value;");
+
+        call_codegen!(
+            &self.options.newtype_generators,
+            NewtypeAdded {
+                body: body,
+                spec: &mut spec,
+                constructor: &mut c,
+                getter: &mut getter,
+            }
+        );
+
+        spec.constructors.push(c);
+        spec.methods.push(validate);
+        spec.methods.push(getter);
+
+        if self.options.build_hash_code {
+            let mut m = Method::new("hashCode");
+            m.annotation(Override);
+            m.returns = INTEGER;
+            m.body.push("return this.value.hashCode();");
+            spec.methods.push(m);
+        }
+
+        if self.options.build_equals {
+            let other = Argument::new(self.object.clone(), "other");
+
+            let mut m = Method::new("equals");
+            m.annotation(Override);
+            m.returns = BOOLEAN;
+            m.arguments.push(other.clone());
+
+            m.body.push_into(|t| {
+                let test = toks![other.var(), " instanceof ", name.clone()];
+                push!(t, "if (!(", test, ")) {");
+                nested!(t, "return false;");
+                push!(t, "}");
+            });
+
+            m.body.push(toks![
+                "return this.value.equals(((",
+                name.clone(),
+                ") ",
+                other.var(),
+                ").value);",
+            ]);
+
+            m.body = m.body.join_line_spacing();
+            spec.methods.push(m);
+        }
+
+        if self.options.build_to_string {
+            let prefix = Rc::new(format!("{}(", body.ident)).quoted();
+
+            let mut m = Method::new("toString");
+            m.annotation(Override);
+            m.returns = self.string.clone();
+            m.body.push(toks![
+                "return ",
+                prefix,
+                " + this.value + ",
+                ")".quoted(),
+                ";",
+            ]);
+            spec.methods.push(m);
+        }
+
+        spec.fields.push(field);
+        Ok(spec)
+    }
+
     fn process_service(&self, body: &'el RpServiceBody) -> Result<Interface<'el>> {
         let mut spec = Interface::new(body.ident.as_str());
 
+        if body.deprecated.is_some() {
+            spec.annotation(Deprecated);
+        }
+
+        if self.options.build_service_methods && !self.options.suppress_service_methods {
+            for e in &body.endpoints {
+                let mut m = Method::new(e.safe_ident());
+                m.modifiers = vec![];
+                m.returns = e.response.clone().unwrap_or(VOID);
+                m.arguments.extend(e.arguments.iter().cloned());
+
+                if !e.comment.is_empty() {
+                    m.comments.push("<pre>".into());
+                    m.comments.extend(e.comment.iter().map(|c| Cons::from(c.as_str())));
+                    m.comments.push("</pre>".into());
+                }
+
+                for a in &e.endpoint.arguments {
+                    if !a.comment.is_empty() {
+                        let param = format!("@param {} {}", a.safe_ident(), a.comment.join(" "));
+                        m.comments.push(Cons::from(param));
+                    }
+                }
+
+                for error in &e.errors {
+                    let throws = format!(
+                        "@throws {} responded with status {}",
+                        error_exception_class(error.ident()),
+                        error.status
+                    );
+                    m.comments.push(Cons::from(throws));
+                }
+
+                if let Some(ref deprecated) = e.deprecated {
+                    m.annotation(Deprecated);
+
+                    if let Some(note) = deprecated.note() {
+                        m.comments.push(Cons::from(format!("@deprecated {}", note)));
+                    }
+                }
+
+                spec.methods.push(m);
+            }
+        }
+
+        for class in self.process_service_errors(body)? {
+            spec.body.push(class);
+        }
+
         for generator in &self.options.service_generators {
             generator.generate(ServiceAdded {
                 body: body,
@@ -777,6 +1259,53 @@ impl<'el> Compiler<'el> {
         Ok(spec)
     }
 
+    /// Build the exceptions for errors which the endpoints of a service respond with.
+    fn process_service_errors(&self, body: &'el RpServiceBody) -> Result<Vec<Class<'el>>> {
+        use self::Modifier::*;
+
+        let mut out = Vec::new();
+        let mut seen = HashMap::new();
+
+        for error in body.endpoints.iter().flat_map(|e| e.errors.iter()) {
+            if let Some(other) = seen.insert(error.ident(), &error.ty) {
+                if *other != error.ty {
+                    return Err(format!(
+                        "{}: error `{}` is declared with different types",
+                        body.ident,
+                        error.ident()
+                    ).into());
+                }
+
+                continue;
+            }
+
+            let mut spec = Class::new(error_exception_class(error.ident()));
+            spec.modifiers = vec![Public, Static];
+            spec.extends = Some(self.runtime_exception.clone());
+
+            let mut field = Field::new(error.ty.clone(), "body");
+            field.modifiers = vec![Private, Final];
+
+            let argument = Argument::new(error.ty.clone(), "body");
+
+            let mut c = Constructor::new();
+            c.arguments.push(argument.clone());
+            push!(c.body, "super(", error.ident().quoted(), ");");
+            push!(c.body, "this.body = ", argument.var(), ";");
+
+            let mut getter = Method::new("getBody");
+            getter.returns = error.ty.clone();
+            getter.body.push("return this.body;");
+
+            spec.fields.push(field);
+            spec.constructors.push(c);
+            spec.methods.push(getter);
+            out.push(spec);
+        }
+
+        Ok(out)
+    }
+
     pub fn process_decl(
         &self,
         decl: &'el RpDecl,
@@ -833,6 +1362,26 @@ impl<'el> Compiler<'el> {
 
                 container.push(spec);
             }
+            Union(ref ty) => {
+                let mut spec = self.process_union(ty)?;
+
+                // Inner classes should be static.
+                if depth > 0 {
+                    spec.modifiers.push(Modifier::Static);
+                }
+
+                container.push(spec);
+            }
+            Newtype(ref ty) => {
+                let mut spec = self.process_newtype(ty)?;
+
+                // Inner classes should be static.
+                if depth > 0 {
+                    spec.modifiers.push(Modifier::Static);
+                }
+
+                container.push(spec);
+            }
             Service(ref ty) => {
                 let mut spec = self.process_service(ty)?;
 
@@ -6,18 +6,20 @@ use backend::package_processor;
 use core::errors::Result;
 use core::{
     self, CoreFlavor, Diagnostics, Flavor, FlavorField, FlavorTranslator, Loc, PackageTranslator,
-    RpNumberKind, RpNumberType, RpStringType, Translate, Translator,
+    RpNumberKind, RpNumberType, RpScalarType, RpStringType, Translate, Translator,
 };
 use genco::java::{
     self, Argument, Field, Method, Modifier, BOOLEAN, DOUBLE, FLOAT, INTEGER, LONG, VOID,
 };
 use genco::{Cons, Element, Java};
 use naming::{self, Naming};
+use options::OptionalStrategy;
 use std::collections::HashMap;
 use std::fmt;
 use std::ops::Deref;
 use std::rc::Rc;
 use trans::Packages;
+use utils::Deprecated;
 
 #[derive(Debug, Clone)]
 pub struct JavaHttp<'el> {
@@ -30,7 +32,10 @@ pub struct JavaHttp<'el> {
 #[derive(Debug, Clone)]
 pub struct JavaEndpoint<'el> {
     pub endpoint: RpEndpoint,
+    /// Arguments of the endpoint, where streaming arguments are represented as an `Iterator`.
     pub arguments: Vec<Argument<'el>>,
+    /// Response of the endpoint, where a streaming response is represented as an `Iterator`.
+    pub response: Option<Java<'el>>,
     pub http1: Option<RpEndpointHttp1>,
 }
 
@@ -73,6 +78,10 @@ impl<'el> JavaField<'el> {
         let argument = Argument::new(self.spec.ty(), self.spec.var());
         let mut m = Method::new(Rc::new(format!("set{}", self.field_accessor)));
 
+        if self.field.deprecated.is_some() {
+            m.annotation(Deprecated);
+        }
+
         m.arguments.push(argument.clone());
 
         m.body
@@ -92,6 +101,11 @@ impl<'el> JavaField<'el> {
         let mut method = Method::new(Rc::new(format!("get{}", field_accessor)));
         method.comments = self.spec.comments.clone();
         method.returns = self.spec.ty().as_field();
+
+        if self.field.deprecated.is_some() {
+            method.annotation(Deprecated);
+        }
+
         method
     }
 
@@ -143,29 +157,57 @@ impl Flavor for JavaFlavor {
 pub struct JavaFlavorTranslator {
     packages: Rc<Packages>,
     list: Java<'static>,
+    set: Java<'static>,
     map: Java<'static>,
     string: Java<'static>,
     instant: Java<'static>,
+    local_date: Java<'static>,
+    local_time: Java<'static>,
+    duration: Java<'static>,
     object: Java<'static>,
     byte_buffer: Java<'static>,
     optional: Java<'static>,
+    iterator: Java<'static>,
+    big_integer: Java<'static>,
+    big_decimal: Java<'static>,
     to_upper_camel: naming::ToUpperCamel,
     to_lower_camel: naming::ToLowerCamel,
+    optional_strategy: OptionalStrategy,
 }
 
 impl JavaFlavorTranslator {
-    pub fn new(packages: Rc<Packages>) -> Self {
+    pub fn new(packages: Rc<Packages>, optional_strategy: OptionalStrategy) -> Self {
         Self {
             packages,
             list: java::imported("java.util", "List"),
+            set: java::imported("java.util", "Set"),
             map: java::imported("java.util", "Map"),
             string: java::imported("java.lang", "String"),
             instant: java::imported("java.time", "Instant"),
+            local_date: java::imported("java.time", "LocalDate"),
+            local_time: java::imported("java.time", "LocalTime"),
+            duration: java::imported("java.time", "Duration"),
             object: java::imported("java.lang", "Object"),
             byte_buffer: java::imported("java.nio", "ByteBuffer"),
             optional: java::imported("java.util", "Optional"),
+            iterator: java::imported("java.util", "Iterator"),
+            big_integer: java::imported("java.math", "BigInteger"),
+            big_decimal: java::imported("java.math", "BigDecimal"),
             to_upper_camel: naming::to_upper_camel(),
             to_lower_camel: naming::to_lower_camel(),
+            optional_strategy,
+        }
+    }
+}
+
+impl JavaFlavorTranslator {
+    /// Translate the type of a channel, streams are represented as an `Iterator`.
+    fn channel_type(&self, channel: &RpChannel) -> Java<'static> {
+        match *channel {
+            core::RpChannel::Unary { ref ty } => ty.clone(),
+            core::RpChannel::Streaming { ref ty } => {
+                self.iterator.with_arguments(vec![ty.as_boxed()])
+            }
         }
     }
 }
@@ -180,6 +222,8 @@ impl FlavorTranslator for JavaFlavorTranslator {
         let out = match number.kind {
             RpNumberKind::U32 | RpNumberKind::I32 => INTEGER.into(),
             RpNumberKind::U64 | RpNumberKind::I64 => LONG.into(),
+            RpNumberKind::U128 | RpNumberKind::I128 => self.big_integer.clone(),
+            RpNumberKind::Decimal => self.big_decimal.clone(),
             ty => return Err(format!("unsupported number type: {}", ty).into()),
         };
 
@@ -206,10 +250,26 @@ impl FlavorTranslator for JavaFlavorTranslator {
         Ok(self.instant.clone().into())
     }
 
+    fn translate_date(&self) -> Result<Java<'static>> {
+        Ok(self.local_date.clone().into())
+    }
+
+    fn translate_time(&self) -> Result<Java<'static>> {
+        Ok(self.local_time.clone().into())
+    }
+
+    fn translate_duration(&self) -> Result<Java<'static>> {
+        Ok(self.duration.clone().into())
+    }
+
     fn translate_array(&self, argument: Java<'static>) -> Result<Java<'static>> {
         Ok(self.list.with_arguments(vec![argument]))
     }
 
+    fn translate_set(&self, argument: Java<'static>) -> Result<Java<'static>> {
+        Ok(self.set.with_arguments(vec![argument]))
+    }
+
     fn translate_map(&self, key: Java<'static>, value: Java<'static>) -> Result<Java<'static>> {
         Ok(self.map.with_arguments(vec![key, value]))
     }
@@ -222,6 +282,21 @@ impl FlavorTranslator for JavaFlavorTranslator {
         Ok(self.byte_buffer.clone())
     }
 
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<Java<'static>> {
+        let ty = match scalar.mapping("java") {
+            Some(ty) => ty,
+            None => return self.translate_scalar_base(scalar.base),
+        };
+
+        match ty.rfind('.') {
+            Some(index) => Ok(java::imported(
+                ty[..index].to_string(),
+                ty[index + 1..].to_string(),
+            )),
+            None => Ok(java::local(ty.to_string())),
+        }
+    }
+
     fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<Java<'static>> {
         let ident = Rc::new(reg.ident(&name, |p| p.join("."), |c| c.join(".")));
         let package = name.package.join(".");
@@ -242,10 +317,15 @@ impl FlavorTranslator for JavaFlavorTranslator {
         let field_accessor = Rc::new(self.to_upper_camel.convert(field.ident()));
 
         let java_type = if field.is_optional() {
-            java::optional(
-                field.ty.clone(),
-                self.optional.with_arguments(vec![field.ty.clone()]),
-            )
+            // nullable fields are still marked as optional, so that they can be told apart from
+            // required fields.
+            let field_type = if self.optional_strategy.is_nullable() {
+                field.ty.as_boxed()
+            } else {
+                self.optional.with_arguments(vec![field.ty.clone()])
+            };
+
+            java::optional(field.ty.clone(), field_type)
         } else {
             field.ty.clone()
         };
@@ -259,6 +339,14 @@ impl FlavorTranslator for JavaFlavorTranslator {
             spec.comments.push("</pre>".into());
         }
 
+        if let Some(ref deprecated) = field.deprecated {
+            spec.annotation(Deprecated);
+
+            if let Some(note) = deprecated.note() {
+                spec.comments.push(Cons::from(format!("@deprecated {}", note)));
+            }
+        }
+
         Ok(JavaField {
             field,
             field_accessor: field_accessor,
@@ -280,10 +368,12 @@ impl FlavorTranslator for JavaFlavorTranslator {
         let mut arguments = Vec::new();
 
         for arg in &endpoint.arguments {
-            let ty = arg.channel.ty().clone();
+            let ty = self.channel_type(&arg.channel);
             arguments.push(Argument::new(ty, arg.safe_ident().to_string()));
         }
 
+        let response = endpoint.response.as_ref().map(|r| self.channel_type(r));
+
         let http1 = RpEndpointHttp1::from_endpoint(&endpoint);
 
         // Used by Closeable implementations.
@@ -297,6 +387,7 @@ impl FlavorTranslator for JavaFlavorTranslator {
         return Ok(JavaEndpoint {
             endpoint: endpoint,
             arguments: arguments,
+            response: response,
             http1: http1,
         });
     }
@@ -27,9 +27,9 @@ use codegen::Configure;
 use compiler::Compiler;
 use core::errors::Result;
 use core::{CoreFlavor, Handle};
-use manifest::{checked_modules, Lang, Manifest, NoModule, TryFromToml};
-use naming::Naming;
-use options::Options;
+use manifest::{checked_modules, Lang, Manifest, ModuleDoc, ModuleOption, NoModule, TryFromToml};
+use naming::{Keywords, Naming};
+use options::{OptionalStrategy, Options};
 use std::any::Any;
 use std::path::Path;
 use std::rc::Rc;
@@ -38,6 +38,118 @@ use trans::Session;
 #[derive(Clone, Copy, Default, Debug)]
 pub struct JavaLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "jackson",
+        description: "Annotate classes for serialization with Jackson.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "lombok",
+        description: "Annotate classes with Lombok instead of generating getters and setters.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "grpc",
+        description: "Generate gRPC services.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "builder",
+        description: "Generate builders for classes.",
+        options: &[ModuleOption {
+            name: "staged",
+            ty: "boolean",
+            default: Some("false"),
+            description:
+                "Generate staged builders, where every required field is set in its own step.",
+        }],
+    },
+    ModuleDoc {
+        name: "constructor_properties",
+        description: "Annotate constructors with `@ConstructorProperties`.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "mutable",
+        description: "Generate classes with setters and non-final fields.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "nullable",
+        description: "Represent optional fields as nullable values instead of `Optional`.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "nullability",
+        description: "Annotate values with their nullability.",
+        options: &[
+            ModuleOption {
+                name: "annotations",
+                ty: "\"jspecify\" | \"jsr305\"",
+                default: Some("jspecify"),
+                description: "Set of annotations to use.",
+            },
+            ModuleOption {
+                name: "nullable",
+                ty: "string",
+                default: None,
+                description: "Fully qualified name of a custom annotation for nullable values.",
+            },
+            ModuleOption {
+                name: "non_null",
+                ty: "string",
+                default: None,
+                description: "Fully qualified name of a custom annotation for non-null values.",
+            },
+        ],
+    },
+    ModuleDoc {
+        name: "okhttp",
+        description: "Generate HTTP clients for services using OkHttp.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "http_client",
+        description: "Generate HTTP clients for services using `java.net.http`.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "service_methods",
+        description: "Declare a method for every endpoint in service interfaces.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "target",
+        description: "Configure the version of Java to generate code for.",
+        options: &[
+            ModuleOption {
+                name: "version",
+                ty: "integer",
+                default: Some("8"),
+                description: "Version of Java to generate code for.",
+            },
+            ModuleOption {
+                name: "optional",
+                ty: "\"optional\" | \"nullable\" | \"annotated\"",
+                default: Some("optional"),
+                description: "How optional fields are represented.",
+            },
+        ],
+    },
+    ModuleDoc {
+        name: "unknown_variant",
+        description: "Add a catch-all variant to enums.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "binary",
+        description: "Add encoding and decoding using the binary format.",
+        options: &[],
+    },
+];
+
 impl Lang for JavaLang {
     lang_base!(JavaModule, compile);
 
@@ -53,66 +165,12 @@ impl Lang for JavaLang {
         Some(Box::new(naming::to_lower_camel()))
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![
-            ("abstract", "_abstract"),
-            ("assert", "_assert"),
-            ("boolean", "_boolean"),
-            ("break", "_break"),
-            ("byte", "_byte"),
-            ("case", "_case"),
-            ("catch", "_catch"),
-            ("char", "_char"),
-            ("class", "_class"),
-            ("const", "_const"),
-            ("continue", "_continue"),
-            ("default", "_default"),
-            ("do", "_do"),
-            ("double", "_double"),
-            ("else", "_else"),
-            ("enum", "_enum"),
-            ("extends", "_extends"),
-            ("false", "_false"),
-            ("final", "_final"),
-            ("finally", "_finally"),
-            ("float", "_float"),
-            ("for", "_for"),
-            ("goto", "_goto"),
-            ("if", "_if"),
-            ("implements", "_implements"),
-            ("import", "_import"),
-            ("instanceof", "_instanceof"),
-            ("int", "_int"),
-            ("interface", "_interface"),
-            ("long", "_long"),
-            ("native", "_native"),
-            ("new", "_new"),
-            ("null", "_null"),
-            ("package", "_package"),
-            ("private", "_private"),
-            ("protected", "_protected"),
-            ("public", "_public"),
-            ("return", "_return"),
-            ("short", "_short"),
-            ("static", "_static"),
-            ("strictfp", "_strictfp"),
-            ("super", "_super"),
-            ("switch", "_switch"),
-            ("synchronized", "_synchronized"),
-            ("this", "_this"),
-            ("throw", "_throw"),
-            ("throws", "_throws"),
-            ("transient", "_transient"),
-            ("true", "_true"),
-            ("try", "_try"),
-            ("void", "_void"),
-            ("volatile", "_volatile"),
-            ("while", "_while"),
-        ]
+    fn keywords(&self) -> Option<&'static Keywords> {
+        Some(&naming::keywords::JAVA)
     }
 
-    fn modules(&self) -> Option<String> {
-        Some(String::from("jackson, lombok"))
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
     }
 }
 
@@ -121,11 +179,17 @@ pub enum JavaModule {
     Jackson,
     Lombok,
     Grpc,
-    Builder,
+    Builder(module::BuilderConfig),
     ConstructorProperties,
     Mutable,
     Nullable,
+    Nullability(module::NullabilityConfig),
     OkHttp(module::OkHttpConfig),
+    HttpClient(module::HttpClientConfig),
+    ServiceMethods,
+    Target(module::TargetConfig),
+    UnknownVariant,
+    Binary,
 }
 
 impl TryFromToml for JavaModule {
@@ -136,11 +200,17 @@ impl TryFromToml for JavaModule {
             "jackson" => Jackson,
             "lombok" => Lombok,
             "grpc" => Grpc,
-            "builder" => Builder,
+            "builder" => Builder(module::BuilderConfig::default()),
             "constructor_properties" => ConstructorProperties,
             "mutable" => Mutable,
             "nullable" => Nullable,
+            "nullability" => Nullability(module::NullabilityConfig::default()),
             "okhttp" => OkHttp(module::OkHttpConfig::default()),
+            "http_client" => HttpClient(module::HttpClientConfig::default()),
+            "service_methods" => ServiceMethods,
+            "target" => Target(module::TargetConfig::default()),
+            "unknown_variant" => UnknownVariant,
+            "binary" => Binary,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -154,11 +224,17 @@ impl TryFromToml for JavaModule {
             "jackson" => Jackson,
             "lombok" => Lombok,
             "grpc" => Grpc,
-            "builder" => Builder,
+            "builder" => Builder(value.try_into()?),
             "constructor_properties" => ConstructorProperties,
             "mutable" => Mutable,
             "nullable" => Nullable,
+            "nullability" => Nullability(value.try_into()?),
             "okhttp" => OkHttp(value.try_into()?),
+            "http_client" => HttpClient(value.try_into()?),
+            "service_methods" => ServiceMethods,
+            "target" => Target(value.try_into()?),
+            "unknown_variant" => UnknownVariant,
+            "binary" => Binary,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -181,6 +257,9 @@ fn setup_options<'a>(modules: Vec<JavaModule>) -> Result<Options> {
             Jackson => {
                 module::Jackson::prepare(c)?;
             }
+            Target(ref config) => {
+                module::Target::prepare(config, c)?;
+            }
             _ => {}
         }
     }
@@ -194,29 +273,52 @@ fn setup_options<'a>(modules: Vec<JavaModule>) -> Result<Options> {
             Jackson => module::Jackson.initialize(c),
             Lombok => module::Lombok.initialize(c),
             Grpc => module::Grpc.initialize(c),
-            Builder => module::Builder.initialize(c),
+            Builder(config) => module::Builder::new(config).initialize(c),
             ConstructorProperties => module::ConstructorProperties.initialize(c),
             Mutable => module::Mutable.initialize(c),
             Nullable => module::Nullable.initialize(c),
+            Nullability(config) => module::Nullability::new(config).initialize(c)?,
             OkHttp(config) => {
                 let serialization = c.options.get_serialization()?;
                 module::OkHttp::new(config).initialize(c, serialization);
             }
+            HttpClient(config) => {
+                let serialization = c.options.get_serialization()?;
+                module::HttpClient::new(config).initialize(c, serialization);
+            }
+            ServiceMethods => module::ServiceMethods.initialize(c),
+            Target(_) => {}
+            UnknownVariant => module::UnknownVariant.initialize(c),
+            Binary => {
+                if c.options.optional_strategy.is_nullable() {
+                    return Err("binary: optional fields must be represented as `Optional`".into());
+                }
+
+                module::Binary.initialize(c)
+            }
         };
     }
 
+    if options.optional_strategy == OptionalStrategy::Annotated && options.nullability.is_none() {
+        options.nullability = Some(module::NullabilityAnnotations::default());
+    }
+
     Ok(options)
 }
 
 fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let modules = checked_modules(manifest.modules)?;
+    let options = setup_options(modules)?;
+
     let packages = session.packages()?;
-    let translator = session.translator(flavored::JavaFlavorTranslator::new(packages.clone()))?;
+    let translator = session.translator(flavored::JavaFlavorTranslator::new(
+        packages.clone(),
+        options.optional_strategy,
+    ))?;
 
     let session = session.translate(translator)?;
 
     let session = Rc::new(session);
-    let modules = checked_modules(manifest.modules)?;
-    let options = setup_options(modules)?;
 
     let compiler = Compiler::new(&session, options);
 
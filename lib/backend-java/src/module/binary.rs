@@ -0,0 +1,1070 @@
+//! Module that adds encoding and decoding using the binary format to generated classes.
+//!
+//! Support is provided through the `io.reproto.BinaryEncoder` and `io.reproto.BinaryDecoder`
+//! classes.
+
+use codegen::{
+    ClassAdded, ClassCodegen, Codegen, Configure, EnumAdded, EnumCodegen, InterfaceAdded,
+    InterfaceCodegen, NewtypeAdded, NewtypeCodegen, TupleAdded, TupleCodegen, UnionAdded,
+    UnionCodegen,
+};
+use core::errors::Result;
+use core::{Handle, Loc};
+use flavored::RpPackage;
+use genco::java::{
+    self, local, Argument, Class, Field, Method, Modifier, BOOLEAN, DOUBLE, FLOAT, INTEGER, LONG,
+    VOID,
+};
+use genco::{Cons, Java, Quoted, Tokens};
+use java_file::JavaFile;
+use std::rc::Rc;
+use utils::union_variant_class;
+
+pub struct Module;
+
+impl Module {
+    pub fn initialize(self, e: Configure) {
+        let binary = Rc::new(Binary::new());
+        e.options.class_generators.push(Box::new(binary.clone()));
+        e.options.tuple_generators.push(Box::new(binary.clone()));
+        e.options
+            .interface_generators
+            .push(Box::new(binary.clone()));
+        e.options.enum_generators.push(Box::new(binary.clone()));
+        e.options.union_generators.push(Box::new(binary.clone()));
+        e.options.newtype_generators.push(Box::new(binary.clone()));
+        e.options
+            .root_generators
+            .push(Box::new(BinarySupport::new()));
+    }
+}
+
+struct Binary {
+    encoder: Java<'static>,
+    decoder: Java<'static>,
+    string: Java<'static>,
+    instant: Java<'static>,
+    local_date: Java<'static>,
+    local_time: Java<'static>,
+    duration: Java<'static>,
+    byte_buffer: Java<'static>,
+    big_integer: Java<'static>,
+    big_decimal: Java<'static>,
+    object: Java<'static>,
+    list: Java<'static>,
+    set: Java<'static>,
+    map: Java<'static>,
+}
+
+impl Binary {
+    pub fn new() -> Binary {
+        Binary {
+            encoder: java::imported("io.reproto", "BinaryEncoder"),
+            decoder: java::imported("io.reproto", "BinaryDecoder"),
+            string: java::imported("java.lang", "String"),
+            instant: java::imported("java.time", "Instant"),
+            local_date: java::imported("java.time", "LocalDate"),
+            local_time: java::imported("java.time", "LocalTime"),
+            duration: java::imported("java.time", "Duration"),
+            byte_buffer: java::imported("java.nio", "ByteBuffer"),
+            big_integer: java::imported("java.math", "BigInteger"),
+            big_decimal: java::imported("java.math", "BigDecimal"),
+            object: java::imported("java.lang", "Object"),
+            list: java::imported("java.util", "List"),
+            set: java::imported("java.util", "Set"),
+            map: java::imported("java.util", "Map"),
+        }
+    }
+
+    /// Build an expression which encodes the given value.
+    ///
+    /// The depth is used to name variables in nested lambdas.
+    fn encode<'el>(
+        &self,
+        ty: &Java<'el>,
+        value: Tokens<'el, Java<'el>>,
+        depth: usize,
+    ) -> Result<Tokens<'el, Java<'el>>> {
+        if let Java::Optional(_) = *ty {
+            let var = Rc::new(format!("v{}", depth));
+            let item = self.encode(&ty.as_value(), toks![var.clone()], depth + 1)?;
+
+            return Ok(toks![
+                "encoder.writeOptional(",
+                value,
+                ", ",
+                var,
+                " -> ",
+                item,
+                ")"
+            ]);
+        }
+
+        let write = match *ty {
+            BOOLEAN => "writeBoolean",
+            INTEGER => "writeInt",
+            LONG => "writeLong",
+            FLOAT => "writeFloat",
+            DOUBLE => "writeDouble",
+            Java::Class { .. } => {
+                let base = ty.with_arguments(vec![]);
+                let arguments = ty.arguments().map(|a| a.to_vec()).unwrap_or_default();
+
+                if base == self.string {
+                    "writeString"
+                } else if base == self.instant {
+                    "writeInstant"
+                } else if base == self.byte_buffer {
+                    "writeBytes"
+                } else if base == self.big_integer {
+                    "writeBigInteger"
+                } else if base == self.list || base == self.set {
+                    let write = if base == self.list {
+                        "writeList"
+                    } else {
+                        "writeSet"
+                    };
+
+                    let var = Rc::new(format!("v{}", depth));
+                    let item = argument(&arguments, 0)?;
+                    let item = self.encode(item, toks![var.clone()], depth + 1)?;
+
+                    return Ok(toks![
+                        "encoder.", write, "(", value, ", ", var, " -> ", item, ")"
+                    ]);
+                } else if base == self.map {
+                    let k = Rc::new(format!("k{}", depth));
+                    let v = Rc::new(format!("v{}", depth));
+                    let key = self.encode(argument(&arguments, 0)?, toks![k.clone()], depth + 1)?;
+                    let item =
+                        self.encode(argument(&arguments, 1)?, toks![v.clone()], depth + 1)?;
+
+                    return Ok(toks![
+                        "encoder.writeMap(",
+                        value,
+                        ", ",
+                        k,
+                        " -> ",
+                        key,
+                        ", ",
+                        v,
+                        " -> ",
+                        item,
+                        ")"
+                    ]);
+                } else if base == self.big_decimal {
+                    return Err("`decimal` is not supported by the binary module".into());
+                } else if base == self.local_date {
+                    return Err("`date` is not supported by the binary module".into());
+                } else if base == self.local_time {
+                    return Err("`time` is not supported by the binary module".into());
+                } else if base == self.duration {
+                    return Err("`duration` is not supported by the binary module".into());
+                } else if base == self.object {
+                    return Err("`any` is not supported by the binary module".into());
+                } else {
+                    // generated type
+                    return Ok(toks![value, ".encodeBinary(encoder)"]);
+                }
+            }
+            _ => return Err(format!("type not supported by the binary module: {:?}", ty).into()),
+        };
+
+        Ok(toks!["encoder.", write, "(", value, ")"])
+    }
+
+    /// Build an expression which decodes a value of the given type.
+    fn decode<'el>(&self, ty: &Java<'el>) -> Result<Tokens<'el, Java<'el>>> {
+        if let Java::Optional(_) = *ty {
+            let item = self.decode(&ty.as_value())?;
+            return Ok(toks!["decoder.readOptional(() -> ", item, ")"]);
+        }
+
+        let read = match *ty {
+            BOOLEAN => "readBoolean",
+            INTEGER => "readInt",
+            LONG => "readLong",
+            FLOAT => "readFloat",
+            DOUBLE => "readDouble",
+            Java::Class { .. } => {
+                let base = ty.with_arguments(vec![]);
+                let arguments = ty.arguments().map(|a| a.to_vec()).unwrap_or_default();
+
+                if base == self.string {
+                    "readString"
+                } else if base == self.instant {
+                    "readInstant"
+                } else if base == self.byte_buffer {
+                    "readBytes"
+                } else if base == self.big_integer {
+                    "readBigInteger"
+                } else if base == self.list || base == self.set {
+                    let read = if base == self.list {
+                        "readList"
+                    } else {
+                        "readSet"
+                    };
+
+                    let item = self.decode(argument(&arguments, 0)?)?;
+                    return Ok(toks!["decoder.", read, "(() -> ", item, ")"]);
+                } else if base == self.map {
+                    let key = self.decode(argument(&arguments, 0)?)?;
+                    let item = self.decode(argument(&arguments, 1)?)?;
+
+                    return Ok(toks!["decoder.readMap(() -> ", key, ", () -> ", item, ")"]);
+                } else if base == self.big_decimal {
+                    return Err("`decimal` is not supported by the binary module".into());
+                } else if base == self.local_date {
+                    return Err("`date` is not supported by the binary module".into());
+                } else if base == self.local_time {
+                    return Err("`time` is not supported by the binary module".into());
+                } else if base == self.duration {
+                    return Err("`duration` is not supported by the binary module".into());
+                } else if base == self.object {
+                    return Err("`any` is not supported by the binary module".into());
+                } else {
+                    // generated type
+                    return Ok(toks![ty.clone(), ".decodeBinary(decoder)"]);
+                }
+            }
+            _ => return Err(format!("type not supported by the binary module: {:?}", ty).into()),
+        };
+
+        Ok(toks!["decoder.", read, "()"])
+    }
+
+    /// Build the `encodeBinary` method, optionally prefixed by the index of the sub-type.
+    fn encode_fields<'el>(
+        &self,
+        fields: &[Field<'el>],
+        index: Option<usize>,
+    ) -> Result<Method<'el>> {
+        let mut m = Method::new("encodeBinary");
+        m.returns = VOID;
+        m.arguments
+            .push(Argument::new(self.encoder.clone(), "encoder"));
+
+        if let Some(index) = index {
+            m.body
+                .push(toks!["encoder.writeInt(", index.to_string(), ");"]);
+        }
+
+        for field in fields {
+            let value = toks!["this.", field.var()];
+            m.body.push(toks![self.encode(&field.ty(), value, 0)?, ";"]);
+        }
+
+        Ok(m)
+    }
+
+    /// Build a static method which decodes all fields and passes them to the constructor.
+    fn decode_fields<'el>(
+        &self,
+        name: &'static str,
+        class: Cons<'el>,
+        fields: &[Field<'el>],
+    ) -> Result<Method<'el>> {
+        let mut m = Method::new(name);
+        m.modifiers = vec![Modifier::Public, Modifier::Static];
+        m.returns = local(class.clone());
+        m.arguments
+            .push(Argument::new(self.decoder.clone(), "decoder"));
+
+        let mut arguments = Tokens::new();
+
+        for field in fields {
+            let var = Rc::new(format!("v_{}", field.var()));
+
+            m.body.push(toks![
+                "final ",
+                field.ty(),
+                " ",
+                var.clone(),
+                " = ",
+                self.decode(&field.ty())?,
+                ";"
+            ]);
+
+            arguments.append(var);
+        }
+
+        m.body
+            .push(toks!["return new ", class, "(", arguments.join(", "), ");"]);
+
+        Ok(m)
+    }
+}
+
+/// Get the type argument at the given position.
+fn argument<'a, 'el>(arguments: &'a [Java<'el>], index: usize) -> Result<&'a Java<'el>> {
+    arguments
+        .get(index)
+        .ok_or_else(|| "missing type argument".into())
+}
+
+impl ClassCodegen for Binary {
+    fn generate(&self, e: ClassAdded) -> Result<()> {
+        let ClassAdded {
+            spec, interface, ..
+        } = e;
+
+        let name = spec.name();
+
+        let (index, decode) = match interface {
+            // sub-types are prefixed by their index, which is read by the interface.
+            Some(body) => {
+                let index = body
+                    .sub_types
+                    .iter()
+                    .position(|s| s.ident.as_str() == &*name)
+                    .ok_or_else(|| format!("{}: not a sub-type", name))?;
+
+                (Some(index), "decodeBinaryFields")
+            }
+            None => (None, "decodeBinary"),
+        };
+
+        let encode = self.encode_fields(&spec.fields, index)?;
+        let decode = self.decode_fields(decode, name, &spec.fields)?;
+
+        spec.methods.push(encode);
+        spec.methods.push(decode);
+        Ok(())
+    }
+}
+
+impl TupleCodegen for Binary {
+    fn generate(&self, e: TupleAdded) -> Result<()> {
+        let TupleAdded { spec } = e;
+
+        let encode = self.encode_fields(&spec.fields, None)?;
+        let decode = self.decode_fields("decodeBinary", spec.name(), &spec.fields)?;
+
+        spec.methods.push(encode);
+        spec.methods.push(decode);
+        Ok(())
+    }
+}
+
+impl EnumCodegen for Binary {
+    fn generate(&self, e: EnumAdded) -> Result<()> {
+        let EnumAdded { body, spec, .. } = e;
+
+        let mut encode = Method::new("encodeBinary");
+        encode.returns = VOID;
+        encode
+            .arguments
+            .push(Argument::new(self.encoder.clone(), "encoder"));
+        encode.body.push(toks![
+            self.encode(&body.enum_type, toks!["this.value"], 0)?,
+            ";"
+        ]);
+
+        let mut decode = Method::new("decodeBinary");
+        decode.modifiers = vec![Modifier::Public, Modifier::Static];
+        decode.returns = local(spec.name());
+        decode
+            .arguments
+            .push(Argument::new(self.decoder.clone(), "decoder"));
+        decode.body.push(toks![
+            "return fromValue(",
+            self.decode(&body.enum_type)?,
+            ");"
+        ]);
+
+        spec.methods.push(encode);
+        spec.methods.push(decode);
+        Ok(())
+    }
+}
+
+impl InterfaceCodegen for Binary {
+    fn generate(&self, e: InterfaceAdded) -> Result<()> {
+        let InterfaceAdded { body, spec } = e;
+
+        let mut encode = Method::new("encodeBinary");
+        encode.modifiers = vec![];
+        encode.returns = VOID;
+        encode
+            .arguments
+            .push(Argument::new(self.encoder.clone(), "encoder"));
+
+        let mut decode = Method::new("decodeBinary");
+        decode.modifiers = vec![Modifier::Public, Modifier::Static];
+        decode.returns = local(spec.name());
+        decode
+            .arguments
+            .push(Argument::new(self.decoder.clone(), "decoder"));
+
+        let mut cases = Tokens::new();
+
+        for (index, sub_type) in body.sub_types.iter().enumerate() {
+            cases.push(toks!["case ", index.to_string(), ":"]);
+            cases.nested(toks![
+                "return ",
+                sub_type.ident.as_str(),
+                ".decodeBinaryFields(decoder);"
+            ]);
+        }
+
+        decode.body.push(switch(spec.name(), "sub-type", cases));
+
+        spec.methods.push(encode);
+        spec.methods.push(decode);
+        Ok(())
+    }
+}
+
+impl UnionCodegen for Binary {
+    fn generate(&self, e: UnionAdded) -> Result<()> {
+        let UnionAdded { body, spec, .. } = e;
+
+        let mut encode = Tokens::new();
+        let mut cases = Tokens::new();
+
+        push!(
+            encode,
+            "default void encodeBinary(final ",
+            self.encoder.clone(),
+            " encoder) {"
+        );
+
+        for (index, variant) in body.variants.iter().enumerate() {
+            let class = Rc::new(union_variant_class(variant.ident.as_str()));
+            let ty = Loc::borrow(&variant.ty);
+
+            encode.nested({
+                let mut t = Tokens::new();
+
+                let value = toks!["((", class.clone(), ") this).getValue()"];
+
+                push!(t, "if (this instanceof ", class.clone(), ") {");
+                nested!(t, "encoder.writeInt(", index.to_string(), ");");
+                nested!(t, self.encode(ty, value, 0)?, ";");
+                nested!(t, "return;");
+                push!(t, "}");
+
+                t
+            });
+
+            cases.push(toks!["case ", index.to_string(), ":"]);
+            cases.nested(toks!["return new ", class, "(", self.decode(ty)?, ");"]);
+        }
+
+        encode.nested(toks![
+            "throw new IllegalStateException(",
+            "unknown variant".quoted(),
+            ");"
+        ]);
+        push!(encode, "}");
+
+        let mut decode = Method::new("decodeBinary");
+        decode.modifiers = vec![Modifier::Public, Modifier::Static];
+        decode.returns = local(spec.name());
+        decode
+            .arguments
+            .push(Argument::new(self.decoder.clone(), "decoder"));
+        decode.body.push(switch(spec.name(), "variant", cases));
+
+        spec.body.push(encode);
+        spec.methods.push(decode);
+        Ok(())
+    }
+}
+
+impl NewtypeCodegen for Binary {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded { body, spec, .. } = e;
+        let ty = Loc::borrow(&body.ty);
+
+        let mut encode = Method::new("encodeBinary");
+        encode.returns = VOID;
+        encode
+            .arguments
+            .push(Argument::new(self.encoder.clone(), "encoder"));
+        encode
+            .body
+            .push(toks![self.encode(ty, toks!["this.value"], 0)?, ";"]);
+
+        let mut decode = Method::new("decodeBinary");
+        decode.modifiers = vec![Modifier::Public, Modifier::Static];
+        decode.returns = local(spec.name());
+        decode
+            .arguments
+            .push(Argument::new(self.decoder.clone(), "decoder"));
+        decode.body.push(toks![
+            "return new ",
+            spec.name(),
+            "(",
+            self.decode(ty)?,
+            ");"
+        ]);
+
+        spec.methods.push(encode);
+        spec.methods.push(decode);
+        Ok(())
+    }
+}
+
+/// Switch over an index read from the decoder.
+fn switch<'el>(
+    name: Cons<'el>,
+    what: &'static str,
+    cases: Tokens<'el, Java<'el>>,
+) -> Tokens<'el, Java<'el>> {
+    let mut t = Tokens::new();
+
+    push!(t, "final int index = decoder.readInt();");
+    push!(t, "switch (index) {");
+    t.nested(cases);
+    nested!(t, "default:");
+
+    t.nested({
+        let message = Rc::new(format!("{}: unknown {}: ", name, what)).quoted();
+        let mut t = Tokens::new();
+        t.nested(toks![
+            "throw new IllegalArgumentException(",
+            message,
+            " + index);"
+        ]);
+        t
+    });
+
+    push!(t, "}");
+    t
+}
+
+struct BinarySupport {}
+
+impl BinarySupport {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn encoder<'el>(&self) -> Class<'el> {
+        let output = java::imported("java.io", "ByteArrayOutputStream");
+        let big_integer = java::imported("java.math", "BigInteger");
+        let byte_buffer = java::imported("java.nio", "ByteBuffer");
+        let utf8 = java::imported("java.nio.charset", "StandardCharsets");
+        let instant = java::imported("java.time", "Instant");
+        let optional = java::imported("java.util", "Optional");
+        let list = java::imported("java.util", "List");
+        let set = java::imported("java.util", "Set");
+        let map = java::imported("java.util", "Map");
+        let consumer = java::imported("java.util.function", "Consumer");
+
+        let mut c = Class::new("BinaryEncoder");
+
+        c.body.push(toks![
+            "private final ",
+            output.clone(),
+            " output = new ",
+            output,
+            "();"
+        ]);
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public byte[] toByteArray() {");
+            nested!(t, "return this.output.toByteArray();");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeBoolean(final boolean value) {");
+            nested!(t, "this.output.write(value ? 1 : 0);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeInt(final int value) {");
+            nested!(t, "this.writeFixed(value, 4);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeLong(final long value) {");
+            nested!(t, "this.writeFixed(value, 8);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeFloat(final float value) {");
+            nested!(t, "this.writeInt(Float.floatToIntBits(value));");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeDouble(final double value) {");
+            nested!(t, "this.writeLong(Double.doubleToLongBits(value));");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "public void writeBigInteger(final ",
+                big_integer,
+                " value) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "if (value.bitLength() > 128) {");
+                nested!(
+                    t,
+                    "throw new IllegalArgumentException(",
+                    "value does not fit in 128 bits: ".quoted(),
+                    " + value);"
+                );
+                push!(t, "}");
+            });
+
+            t.nested_into(|t| {
+                push!(t, "final byte[] bytes = value.toByteArray();");
+                push!(t, "final int sign = value.signum() < 0 ? 0xff : 0;");
+                push!(t, "for (int i = 0; i < 16; i++) {");
+                nested!(
+                    t,
+                    "this.output.write(i < bytes.length ? bytes[bytes.length - 1 - i] : sign);"
+                );
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeString(final String value) {");
+            nested!(t, "this.writeRaw(value.getBytes(", utf8, ".UTF_8));");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeBytes(final ", byte_buffer, " value) {");
+            nested!(t, "final byte[] bytes = new byte[value.remaining()];");
+            nested!(t, "value.duplicate().get(bytes);");
+            nested!(t, "this.writeRaw(bytes);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public void writeInstant(final ", instant, " value) {");
+            nested!(t, "this.writeLong(value.toEpochMilli());");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "public <T> void writeOptional(final ",
+                optional,
+                "<T> value, final ",
+                consumer.clone(),
+                "<T> item) {"
+            );
+            nested!(t, "this.writeBoolean(value.isPresent());");
+            nested!(t, "value.ifPresent(item);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "public <T> void writeList(final ",
+                list,
+                "<T> value, final ",
+                consumer.clone(),
+                "<T> item) {"
+            );
+            nested!(t, "this.writeInt(value.size());");
+            nested!(t, "value.forEach(item);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "public <T> void writeSet(final ",
+                set,
+                "<T> value, final ",
+                consumer.clone(),
+                "<T> item) {"
+            );
+            nested!(t, "this.writeInt(value.size());");
+            nested!(t, "value.forEach(item);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "public <K, V> void writeMap(final ",
+                map.clone(),
+                "<K, V> value, final ",
+                consumer.clone(),
+                "<K> key, final ",
+                consumer,
+                "<V> item) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "this.writeInt(value.size());");
+                push!(
+                    t,
+                    "for (final ",
+                    map,
+                    ".Entry<K, V> e : value.entrySet()) {"
+                );
+                nested!(t, "key.accept(e.getKey());");
+                nested!(t, "item.accept(e.getValue());");
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "private void writeRaw(final byte[] bytes) {");
+            nested!(t, "this.writeInt(bytes.length);");
+            nested!(t, "this.output.write(bytes, 0, bytes.length);");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "private void writeFixed(final long value, final int size) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "for (int i = 0; i < size; i++) {");
+                nested!(t, "this.output.write((int) (value >>> (8 * i)));");
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body = c.body.join_line_spacing();
+        c
+    }
+
+    fn decoder<'el>(&self) -> Class<'el> {
+        let big_integer = java::imported("java.math", "BigInteger");
+        let byte_buffer = java::imported("java.nio", "ByteBuffer");
+        let byte_order = java::imported("java.nio", "ByteOrder");
+        let coding_exception = java::imported("java.nio.charset", "CharacterCodingException");
+        let utf8 = java::imported("java.nio.charset", "StandardCharsets");
+        let instant = java::imported("java.time", "Instant");
+        let optional = java::imported("java.util", "Optional");
+        let list = java::imported("java.util", "List");
+        let array_list = java::imported("java.util", "ArrayList");
+        let set = java::imported("java.util", "Set");
+        let hash_set = java::imported("java.util", "HashSet");
+        let map = java::imported("java.util", "Map");
+        let hash_map = java::imported("java.util", "HashMap");
+        let supplier = java::imported("java.util.function", "Supplier");
+
+        let mut c = Class::new("BinaryDecoder");
+
+        c.body
+            .push(toks!["private final ", byte_buffer.clone(), " input;"]);
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public BinaryDecoder(final byte[] input) {");
+            nested!(
+                t,
+                "this.input = ",
+                byte_buffer.clone(),
+                ".wrap(input).order(",
+                byte_order,
+                ".LITTLE_ENDIAN);"
+            );
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public boolean readBoolean() {");
+
+            t.nested_into(|t| {
+                push!(t, "switch (this.input.get()) {");
+                nested!(t, "case 0:");
+                t.nested_into(|t| {
+                    nested!(t, "return false;");
+                });
+                nested!(t, "case 1:");
+                t.nested_into(|t| {
+                    nested!(t, "return true;");
+                });
+                nested!(t, "default:");
+                t.nested_into(|t| {
+                    nested!(
+                        t,
+                        "throw new IllegalArgumentException(",
+                        "invalid boolean".quoted(),
+                        ");"
+                    );
+                });
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        for &(ty, name, get) in &[
+            ("int", "readInt", "getInt"),
+            ("long", "readLong", "getLong"),
+            ("float", "readFloat", "getFloat"),
+            ("double", "readDouble", "getDouble"),
+        ] {
+            let mut t = Tokens::new();
+            push!(t, "public ", ty, " ", name, "() {");
+            nested!(t, "return this.input.", get, "();");
+            push!(t, "}");
+            c.body.push(t);
+        }
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public ", big_integer.clone(), " readBigInteger() {");
+
+            t.nested_into(|t| {
+                push!(t, "final byte[] bytes = new byte[16];");
+                push!(t, "for (int i = 15; i >= 0; i--) {");
+                nested!(t, "bytes[i] = this.input.get();");
+                push!(t, "}");
+                push!(t, "return new ", big_integer, "(bytes);");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public String readString() {");
+
+            t.nested_into(|t| {
+                push!(
+                    t,
+                    "final ",
+                    byte_buffer.clone(),
+                    " bytes = this.readBytes();"
+                );
+                push!(t, "try {");
+                nested!(
+                    t,
+                    "return ",
+                    utf8,
+                    ".UTF_8.newDecoder().decode(bytes).toString();"
+                );
+                push!(t, "} catch (final ", coding_exception, " e) {");
+                nested!(
+                    t,
+                    "throw new IllegalArgumentException(",
+                    "invalid UTF-8".quoted(),
+                    ", e);"
+                );
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public ", byte_buffer.clone(), " readBytes() {");
+
+            t.nested_into(|t| {
+                push!(t, "final int length = this.readLength();");
+                push!(t, "if (length > this.input.remaining()) {");
+                nested!(
+                    t,
+                    "throw new IllegalArgumentException(",
+                    "unexpected end of input".quoted(),
+                    ");"
+                );
+                push!(t, "}");
+                push!(t, "final byte[] bytes = new byte[length];");
+                push!(t, "this.input.get(bytes);");
+                push!(t, "return ", byte_buffer, ".wrap(bytes);");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "public ", instant.clone(), " readInstant() {");
+            nested!(t, "return ", instant, ".ofEpochMilli(this.readLong());");
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(
+                t,
+                "public <T> ",
+                optional.clone(),
+                "<T> readOptional(final ",
+                supplier.clone(),
+                "<T> item) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "if (this.readBoolean()) {");
+                nested!(t, "return ", optional.clone(), ".of(item.get());");
+                push!(t, "}");
+                push!(t, "return ", optional, ".empty();");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        for &(name, ty, imp) in &[
+            ("readList", &list, &array_list),
+            ("readSet", &set, &hash_set),
+        ] {
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "public <T> ",
+                ty.clone(),
+                "<T> ",
+                name,
+                "(final ",
+                supplier.clone(),
+                "<T> item) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "final int length = this.readLength();");
+                push!(
+                    t,
+                    "final ",
+                    ty.clone(),
+                    "<T> out = new ",
+                    imp.clone(),
+                    "<>();"
+                );
+                push!(t, "for (int i = 0; i < length; i++) {");
+                nested!(t, "out.add(item.get());");
+                push!(t, "}");
+                push!(t, "return out;");
+            });
+
+            push!(t, "}");
+            c.body.push(t);
+        }
+
+        c.body.push({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "public <K, V> ",
+                map.clone(),
+                "<K, V> readMap(final ",
+                supplier.clone(),
+                "<K> key, final ",
+                supplier,
+                "<V> item) {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "final int length = this.readLength();");
+                push!(t, "final ", map, "<K, V> out = new ", hash_map, "<>();");
+                push!(t, "for (int i = 0; i < length; i++) {");
+                nested!(t, "final K k = key.get();");
+                nested!(t, "out.put(k, item.get());");
+                push!(t, "}");
+                push!(t, "return out;");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body.push({
+            let mut t = Tokens::new();
+            push!(t, "private int readLength() {");
+
+            t.nested_into(|t| {
+                push!(t, "final int length = this.readInt();");
+                push!(t, "if (length < 0) {");
+                nested!(
+                    t,
+                    "throw new IllegalArgumentException(",
+                    "length too large: ".quoted(),
+                    " + Integer.toUnsignedString(length));"
+                );
+                push!(t, "}");
+                push!(t, "return length;");
+            });
+
+            push!(t, "}");
+            t
+        });
+
+        c.body = c.body.join_line_spacing();
+        c
+    }
+}
+
+impl Codegen for BinarySupport {
+    fn generate(&self, handle: &Handle) -> Result<()> {
+        let package = RpPackage::parse("io.reproto");
+
+        JavaFile::new(package.clone(), "BinaryEncoder", |out| {
+            out.push(self.encoder());
+            Ok(())
+        }).process(handle)?;
+
+        JavaFile::new(package, "BinaryDecoder", |out| {
+            out.push(self.decoder());
+            Ok(())
+        }).process(handle)?;
+
+        Ok(())
+    }
+}
@@ -1,29 +1,58 @@
-//! Module that adds fasterxml annotations to generated classes.
+//! Module that adds builders to generated classes.
 
 use codegen::{ClassAdded, ClassCodegen, Configure};
 use core::errors::*;
-use genco::java::{imported, local, Argument, Class, Field, Method, Modifier};
+use genco::java::{imported, local, Argument, Class, Field, Interface, Method, Modifier};
 use genco::{Java, Quoted, Tokens};
+use naming::{self, Naming};
 use std::rc::Rc;
+use utils::Override;
+use Options;
 
-pub struct Module;
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Generate staged builders, where every required field has to be set in its own step.
+    #[serde(default)]
+    staged: bool,
+}
+
+pub struct Module {
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
 
 impl Module {
     pub fn initialize(self, e: Configure) {
-        e.options.class_generators.push(Box::new(Builder::new()));
+        let builder = Builder::new(self.config.staged, e.options);
+        e.options.class_generators.push(Box::new(builder));
     }
 }
 
 pub struct Builder {
+    staged: bool,
+    /// Optional fields are nullable, instead of being represented as `Optional`.
+    nullable_optional: bool,
+    /// Declare local variables using `var`.
+    local_variable_type_inference: bool,
     optional: Java<'static>,
     runtime_exception: Java<'static>,
+    to_upper_camel: naming::ToUpperCamel,
 }
 
 impl Builder {
-    pub fn new() -> Builder {
+    pub fn new(staged: bool, options: &Options) -> Builder {
         Builder {
+            staged: staged,
+            nullable_optional: options.optional_strategy.is_nullable(),
+            local_variable_type_inference: options.local_variable_type_inference(),
             optional: imported("java.util", "Optional"),
             runtime_exception: imported("java.lang", "RuntimeException"),
+            to_upper_camel: naming::to_upper_camel(),
         }
     }
 }
@@ -33,6 +62,9 @@ impl Builder {
         use self::Modifier::*;
 
         let ty = match field.ty() {
+            ref optional @ Java::Optional(_) if self.nullable_optional => self
+                .optional
+                .with_arguments(vec![optional.as_value().as_boxed()]),
             optional @ Java::Optional(_) => optional,
             other => self.optional.with_arguments(vec![other]),
         };
@@ -64,10 +96,37 @@ impl Builder {
 
         setter
     }
-}
 
-impl ClassCodegen for Builder {
-    fn generate(&self, e: ClassAdded) -> Result<()> {
+    /// Value of an optional field, as expected by the constructor of the class.
+    fn optional_value<'el>(&self, field: &Field<'el>) -> Tokens<'el, Java<'el>> {
+        if self.nullable_optional {
+            toks!["this.", field.var(), ".orElse(null)"]
+        } else {
+            toks!["this.", field.var()]
+        }
+    }
+
+    /// Name of the step which sets the given required field.
+    fn step_name(&self, field: &Field) -> String {
+        let name = field.var();
+        let name = self
+            .to_upper_camel
+            .convert(name.as_ref().trim_left_matches('_'));
+        format!("{}Step", name)
+    }
+
+    /// Method of a step, which sets the given field and continues to the next step.
+    fn step_method<'el>(&self, field: &Field<'el>, next: Java<'el>) -> Method<'el> {
+        let mut m = Method::new(field.var());
+        m.modifiers = vec![];
+        m.returns = next;
+        m.arguments
+            .push(Argument::new(field.ty().as_value(), field.var()));
+        m
+    }
+
+    /// Generate a simple builder, which checks that required fields are set when building.
+    fn simple(&self, e: ClassAdded) -> Result<()> {
         use self::Modifier::*;
 
         let mut builder = Class::new("Builder");
@@ -86,11 +145,16 @@ impl ClassCodegen for Builder {
 
                 toks!["this.", field.var(), ".orElseThrow(() -> ", throw_toks, ")"]
             } else {
-                toks!["this.", field.var()]
+                self.optional_value(field)
+            };
+
+            let ty: Tokens<Java> = if self.local_variable_type_inference {
+                toks!["var"]
+            } else {
+                toks![field.ty()]
             };
 
-            let assign: Tokens<Java> =
-                toks!["final ", field.ty(), " ", field.var(), " = ", value, ";"];
+            let assign: Tokens<Java> = toks!["final ", ty, " ", field.var(), " = ", value, ";"];
 
             build_variable_assign.push(assign);
             build_constructor_arguments.append(field.var());
@@ -117,4 +181,128 @@ impl ClassCodegen for Builder {
         e.spec.body.push(builder);
         Ok(())
     }
+
+    /// Generate a staged builder.
+    ///
+    /// Every required field is set in its own step, in the order that they are declared, followed
+    /// by a final step where optional fields can be set before building.
+    /// Forgetting to set a required field is therefore a compile time error.
+    fn staged(&self, e: ClassAdded) -> Result<()> {
+        use self::Modifier::*;
+
+        let build_step = local("BuildStep");
+
+        let required = e
+            .spec
+            .fields
+            .iter()
+            .filter(|f| !f.ty().is_optional())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let names = required
+            .iter()
+            .map(|f| self.step_name(f))
+            .collect::<Vec<_>>();
+
+        let steps = names.iter().cloned().map(local).collect::<Vec<_>>();
+
+        let mut builder = Class::new("Builder");
+        builder.modifiers = vec![Private, Static];
+        builder.implements = steps.clone();
+        builder.implements.push(build_step.clone());
+
+        for (i, field) in required.iter().enumerate() {
+            let next = steps
+                .get(i + 1)
+                .cloned()
+                .unwrap_or_else(|| build_step.clone());
+
+            let mut step = Interface::new(names[i].clone());
+            step.methods.push(self.step_method(field, next.clone()));
+            e.spec.body.push(step);
+
+            let mut f = Field::new(field.ty(), field.var());
+            f.modifiers = vec![Private];
+            builder.fields.push(f);
+
+            let mut setter = self.step_method(field, next);
+            setter.modifiers = vec![Public];
+            setter.annotation(Override);
+            setter
+                .body
+                .push(toks!["this.", field.var(), " = ", field.var(), ";"]);
+            setter.body.push("return this;");
+            builder.methods.push(setter);
+        }
+
+        let mut step = Interface::new("BuildStep");
+        let mut build_arguments = Tokens::new();
+
+        for field in &e.spec.fields {
+            if !field.ty().is_optional() {
+                build_arguments.append(toks!["this.", field.var()]);
+                continue;
+            }
+
+            build_arguments.append(self.optional_value(field));
+
+            step.methods
+                .push(self.step_method(field, build_step.clone()));
+
+            builder.fields.push(self.builder_field(field));
+
+            let mut setter = self.setter_method(field);
+            setter.returns = build_step.clone();
+            setter.annotation(Override);
+            builder.methods.push(setter);
+        }
+
+        step.methods.push({
+            let mut build = Method::new("build");
+            build.modifiers = vec![];
+            build.returns = local(e.spec.name());
+            build
+        });
+
+        e.spec.body.push(step);
+
+        builder.methods.push({
+            let mut build = Method::new("build");
+            build.annotation(Override);
+            build.returns = local(e.spec.name());
+
+            build.body.push(toks![
+                "return new ",
+                e.spec.name(),
+                "(",
+                build_arguments.join(", "),
+                ");",
+            ]);
+
+            build
+        });
+
+        e.spec.body.push(builder);
+
+        e.spec.methods.push({
+            let mut m = Method::new("builder");
+            m.modifiers = vec![Public, Static];
+            m.returns = steps.first().cloned().unwrap_or(build_step);
+            m.body.push("return new Builder();");
+            m
+        });
+
+        Ok(())
+    }
+}
+
+impl ClassCodegen for Builder {
+    fn generate(&self, e: ClassAdded) -> Result<()> {
+        if self.staged {
+            self.staged(e)
+        } else {
+            self.simple(e)
+        }
+    }
 }
@@ -0,0 +1,593 @@
+//! Module that generates HTTP clients for services using `java.net.http`.
+
+use codegen::{Configure, ServiceAdded, ServiceCodegen};
+use core::errors::*;
+use core::{self, Loc};
+use flavored::{JavaEndpoint, RpEndpointArgument, RpEndpointHttp1, RpPathStep};
+use genco::java::{self, Argument, Class, Constructor, Field, Method, Modifier, VOID};
+use genco::{IntoTokens, Java, Quoted, Tokens};
+use serialization::Serialization;
+use utils::error_exception_class;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {}
+
+pub struct Module {
+    #[allow(dead_code)]
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Module {
+    pub fn initialize(self, e: Configure, serialization: Serialization) {
+        e.options
+            .service_generators
+            .push(Box::new(HttpClientServiceCodegen::new(serialization)));
+    }
+}
+
+/// Model for a nested `HttpBuilder` class.
+struct Builder<'a, 'el> {
+    ty: Java<'el>,
+    optional: Java<'el>,
+    uri: Java<'el>,
+    client: Field<'el>,
+    base_url: Field<'el>,
+    default_base_url: Option<&'el str>,
+    ser: Field<'el>,
+    serialization: &'a Serialization,
+}
+
+impl<'a, 'el> Builder<'a, 'el> {
+    fn build(&self) -> Method<'el> {
+        let mut build = Method::new("build");
+        build.returns = self.ty.clone();
+
+        let base_url = match self.default_base_url {
+            Some(default_base_url) => {
+                let default = toks![self.uri.clone(), ".create(", default_base_url.quoted(), ")"];
+                or_else_get(&self.base_url, default)
+            }
+            None => required(&self.base_url),
+        };
+
+        push!(
+            build.body,
+            "final ",
+            self.base_url.ty().as_value(),
+            " ",
+            self.base_url.var(),
+            " = ",
+            base_url,
+            ";"
+        );
+
+        let ser = match self.serialization.default_builder() {
+            Some(default_builder) => or_else_get(&self.ser, default_builder),
+            None => required(&self.ser),
+        };
+
+        push!(
+            build.body,
+            "final ",
+            self.ser.ty().as_value(),
+            " ",
+            self.ser.var(),
+            " = ",
+            ser,
+            ";"
+        );
+
+        let mut args = Tokens::new();
+        args.append(toks!["this.", self.client.var()]);
+        args.append(self.base_url.var());
+        args.append(self.ser.var());
+
+        push!(
+            build.body,
+            "return new ",
+            self.ty,
+            "(",
+            args.join(", "),
+            ");"
+        );
+
+        return build;
+
+        fn required<'el>(field: &Field<'el>) -> Tokens<'el, Java<'el>> {
+            let exc = java::imported("java.lang", "RuntimeException");
+            let msg = format!("{}: is a required field", field.var());
+
+            toks![
+                "this.",
+                field.var(),
+                ".orElseThrow(() -> new ",
+                exc,
+                "(",
+                msg.quoted(),
+                "))"
+            ]
+        }
+
+        fn or_else_get<'el>(
+            field: &Field<'el>,
+            default: Tokens<'el, Java<'el>>,
+        ) -> Tokens<'el, Java<'el>> {
+            toks!["this.", field.var(), ".orElseGet(() -> ", default, ")"]
+        }
+    }
+
+    fn field(&self, field: &Field<'el>) -> Field<'el> {
+        let mut f = Field::new(
+            self.optional.with_arguments(vec![field.ty()]),
+            field.var(),
+        );
+        f.initializer(toks![self.optional.clone(), ".empty()"]);
+        f.modifiers = vec![Modifier::Private];
+        f
+    }
+
+    fn setter(&self, field: &Field<'el>) -> Method<'el> {
+        let arg = Argument::new(field.ty().as_value(), field.var());
+
+        let mut m = Method::new(field.var());
+
+        push!(
+            m.body,
+            "this.",
+            field.var(),
+            " = ",
+            self.optional,
+            ".of(",
+            arg.var(),
+            ");"
+        );
+        push!(m.body, "return this;");
+
+        m.returns = java::local("HttpBuilder");
+        m.arguments.push(arg);
+        m
+    }
+}
+
+impl<'a, 'el> IntoTokens<'el, Java<'el>> for Builder<'a, 'el> {
+    fn into_tokens(self) -> Tokens<'el, Java<'el>> {
+        let mut builder = Class::new("HttpBuilder");
+        builder.modifiers = vec![Modifier::Static, Modifier::Public];
+
+        builder.fields.push(self.client.clone());
+        builder.fields.push(self.field(&self.base_url));
+        builder.fields.push(self.field(&self.ser));
+
+        builder.constructors.push({
+            let mut c = Constructor::new();
+            let client = Argument::new(self.client.ty(), self.client.var());
+
+            push!(c.body, "this.", self.client.var(), " = ", client.var(), ";");
+
+            c.arguments.push(client);
+            c
+        });
+
+        builder.methods.push(self.setter(&self.base_url));
+        builder.methods.push(self.setter(&self.ser));
+        builder.methods.push(self.build());
+
+        builder.into_tokens()
+    }
+}
+
+pub struct HttpClientServiceCodegen {
+    serialization: Serialization,
+    client: Java<'static>,
+    request: Java<'static>,
+    response: Java<'static>,
+    uri: Java<'static>,
+    url_encoder: Java<'static>,
+    charsets: Java<'static>,
+    io_exc: Java<'static>,
+    completion_exc: Java<'static>,
+    optional: Java<'static>,
+    completable_future: Java<'static>,
+}
+
+impl HttpClientServiceCodegen {
+    pub fn new(serialization: Serialization) -> Self {
+        Self {
+            serialization,
+            client: java::imported("java.net.http", "HttpClient"),
+            request: java::imported("java.net.http", "HttpRequest"),
+            response: java::imported("java.net.http", "HttpResponse"),
+            uri: java::imported("java.net", "URI"),
+            url_encoder: java::imported("java.net", "URLEncoder"),
+            charsets: java::imported("java.nio.charset", "StandardCharsets"),
+            io_exc: java::imported("java.io", "IOException"),
+            completion_exc: java::imported("java.util.concurrent", "CompletionException"),
+            optional: java::imported("java.util", "Optional"),
+            completable_future: java::imported("java.util.concurrent", "CompletableFuture"),
+        }
+    }
+}
+
+impl HttpClientServiceCodegen {
+    fn request<'el>(
+        &self,
+        mut method: Method<'el>,
+        e: &'el Loc<JavaEndpoint>,
+        http: &'el RpEndpointHttp1,
+        client: &Field<'el>,
+        base_url: &Field<'el>,
+        ser: &Field<'el>,
+    ) -> Result<Method<'el>> {
+        method.body.push({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "final StringBuilder url_ = new StringBuilder(this.",
+                base_url.var(),
+                ".toString().replaceAll(",
+                "/+$".quoted(),
+                ", ",
+                "".quoted(),
+                "));"
+            );
+
+            for step in &http.path.steps {
+                let args = step_args(step)?;
+
+                push!(
+                    t,
+                    "url_.append(",
+                    "/".quoted(),
+                    ").append(encodePathSegment_(",
+                    args.join(" + "),
+                    "));"
+                );
+            }
+
+            let mut sep = "?";
+
+            for p in &http.query {
+                let key = format!("{}{}=", sep, p.name);
+                let value = to_string(&p.argument);
+
+                push!(
+                    t,
+                    "url_.append(",
+                    key.quoted(),
+                    ").append(encodePathSegment_(",
+                    value,
+                    "));"
+                );
+
+                sep = "&";
+            }
+
+            t
+        });
+
+        let body = match e.request.as_ref() {
+            Some(r) if http.accepts == core::RpAccept::Text => {
+                let publisher = ".BodyPublishers.ofString(";
+                Some(toks![self.request.clone(), publisher, r.safe_ident(), ")"])
+            }
+            Some(r) => {
+                let completable_future = self.completable_future.clone();
+
+                method.body.push(self.serialization.encode(
+                    ser,
+                    toks![r.safe_ident()],
+                    "body_",
+                    |e| {
+                        let mut t = Tokens::new();
+                        push!(t, "return ", completable_future, ".failedFuture(", e, ");");
+                        Ok(t)
+                    },
+                )?);
+
+                Some(toks![self.request.clone(), ".BodyPublishers.ofByteArray(body_)"])
+            }
+            None => None,
+        };
+
+        method.body.push({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "final ",
+                self.request,
+                " req_ = ",
+                self.request,
+                ".newBuilder(",
+                self.uri,
+                ".create(url_.toString()))"
+            );
+
+            nested!(
+                t,
+                ".header(",
+                "Accept".quoted(),
+                ", ",
+                http.produces.as_str().quoted(),
+                ")"
+            );
+
+            for h in &http.headers {
+                let value = to_string(&h.argument);
+                nested!(t, ".header(", h.name.as_str().quoted(), ", ", value, ")");
+            }
+
+            let publisher = match body {
+                Some(body) => {
+                    nested!(
+                        t,
+                        ".header(",
+                        "Content-Type".quoted(),
+                        ", ",
+                        http.accepts.as_str().quoted(),
+                        ")"
+                    );
+
+                    body
+                }
+                None => toks![self.request.clone(), ".BodyPublishers.noBody()"],
+            };
+
+            let method = http.method.as_str();
+
+            nested!(t, ".method(", method.quoted(), ", ", publisher, ")");
+            nested!(t, ".build();");
+
+            t
+        });
+
+        method.body.push({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "return this.",
+                client.var(),
+                ".sendAsync(req_, ",
+                self.response,
+                ".BodyHandlers.ofInputStream()).thenApply(response_ -> {"
+            );
+
+            t.nested({
+                let mut t = Tokens::new();
+
+                for error in &e.errors {
+                    let completion_exc = self.completion_exc.clone();
+
+                    let decode = self.serialization.decode(
+                        ser,
+                        &error.ty,
+                        "response_.body()",
+                        "error_",
+                        |e| {
+                            let mut t = Tokens::new();
+                            push!(t, "throw new ", completion_exc, "(", e, ");");
+                            Ok(t)
+                        },
+                    )?;
+
+                    let exc = java::local(error_exception_class(error.ident()));
+                    let status = error.status.to_string();
+
+                    t.push_into(|t| {
+                        push!(t, "if (response_.statusCode() == ", status, ") {");
+                        t.nested(decode);
+                        nested!(
+                            t,
+                            "throw new ",
+                            self.completion_exc,
+                            "(new ",
+                            exc,
+                            "(error_));"
+                        );
+                        push!(t, "}");
+                    });
+                }
+
+                t.push_into(|t| {
+                    let exc = toks![
+                        "new ",
+                        self.completion_exc.clone(),
+                        "(new ",
+                        self.io_exc.clone(),
+                        "(",
+                        "bad response: ".quoted(),
+                        " + response_.statusCode()))",
+                    ];
+
+                    push!(
+                        t,
+                        "if (response_.statusCode() < 200 || response_.statusCode() >= 300) {"
+                    );
+                    nested!(t, "throw ", exc, ";");
+                    push!(t, "}");
+                });
+
+                let var = match e.response.as_ref() {
+                    Some(_) if http.produces == core::RpAccept::Text => {
+                        let charsets = java::imported("java.nio.charset", "StandardCharsets");
+
+                        t.push_into(|t| {
+                            push!(t, "final String body;");
+                            push!(t, "try {");
+                            nested!(
+                                t,
+                                "body = new String(response_.body().readAllBytes(), ",
+                                charsets,
+                                ".UTF_8);"
+                            );
+                            push!(t, "} catch(final ", self.io_exc, " e) {");
+                            nested!(t, "throw new ", self.completion_exc, "(e);");
+                            push!(t, "}");
+                        });
+
+                        "body"
+                    }
+                    Some(r) => {
+                        let completion_exc = self.completion_exc.clone();
+
+                        t.push(self.serialization.decode(
+                            ser,
+                            r.ty(),
+                            "response_.body()",
+                            "body",
+                            |e| {
+                                let mut t = Tokens::new();
+                                push!(t, "throw new ", completion_exc, "(", e, ");");
+                                Ok(t)
+                            },
+                        )?);
+
+                        "body"
+                    }
+                    None => "null",
+                };
+
+                push!(t, "return ", var, ";");
+
+                t.join_line_spacing()
+            });
+
+            push!(t, "});");
+
+            t
+        });
+
+        method.body = method.body.join_line_spacing();
+        return Ok(method);
+
+        fn step_args<'el>(step: &'el RpPathStep) -> Result<Tokens<'el, Java<'el>>> {
+            let mut args = Tokens::new();
+
+            for part in &step.parts {
+                match *part {
+                    core::RpPathPart::Variable(ref arg) => {
+                        args.append(to_string(arg));
+                    }
+                    core::RpPathPart::Segment(ref s) => {
+                        args.append(s.to_string().quoted());
+                    }
+                }
+            }
+
+            Ok(args)
+        }
+
+        /// Convert the value of an argument into a string.
+        fn to_string<'el>(arg: &'el RpEndpointArgument) -> Tokens<'el, Java<'el>> {
+            let ty = arg.channel.ty();
+
+            if ty.is_primitive() {
+                toks![ty.as_boxed(), ".toString(", arg.safe_ident(), ")"]
+            } else {
+                toks!["String.valueOf(", arg.safe_ident(), ")"]
+            }
+        }
+    }
+
+    /// Helper method to encode a single segment of a path.
+    fn encode_path_segment<'el>(&self) -> Method<'el> {
+        let segment = Argument::new(java::imported("java.lang", "String"), "segment");
+
+        let mut m = Method::new("encodePathSegment_");
+        m.modifiers = vec![Modifier::Private, Modifier::Static];
+        m.returns = java::imported("java.lang", "String");
+
+        push!(
+            m.body,
+            "return ",
+            self.url_encoder,
+            ".encode(",
+            segment.var(),
+            ", ",
+            self.charsets,
+            ".UTF_8).replace(",
+            "+".quoted(),
+            ", ",
+            "%20".quoted(),
+            ");"
+        );
+
+        m.arguments.push(segment);
+        m
+    }
+}
+
+impl ServiceCodegen for HttpClientServiceCodegen {
+    fn generate(&self, e: ServiceAdded) -> Result<()> {
+        let ServiceAdded { body, spec, .. } = e;
+
+        let client = Field::new(self.client.clone(), "client");
+        let base_url = Field::new(self.uri.clone(), "baseUrl");
+        let ser = self.serialization.field();
+
+        let http = {
+            let mut c = Class::new("Http");
+
+            c.fields.push(client.clone());
+            c.fields.push(base_url.clone());
+            c.fields.push(ser.clone());
+
+            c.constructors.push({
+                let mut c = Constructor::new();
+                let client_arg = Argument::new(client.ty(), client.var());
+                let base_arg = Argument::new(base_url.ty(), base_url.var());
+                let ser_arg = Argument::new(ser.ty(), ser.var());
+
+                push!(c.body, "this.", client.var(), " = ", client_arg.var(), ";");
+                push!(c.body, "this.", base_url.var(), " = ", base_arg.var(), ";");
+                push!(c.body, "this.", ser.var(), " = ", ser_arg.var(), ";");
+
+                c.arguments.push(client_arg);
+                c.arguments.push(base_arg);
+                c.arguments.push(ser_arg);
+                c
+            });
+
+            for e in &body.endpoints {
+                if let Some(http) = e.http1.as_ref() {
+                    let mut m = Method::new(e.safe_ident());
+                    m.returns = self
+                        .completable_future
+                        .with_arguments(vec![http.response.as_ref().unwrap_or(&VOID).clone()]);
+                    m.arguments.extend(e.arguments.iter().cloned());
+
+                    c.methods
+                        .push(self.request(m, e, http, &client, &base_url, &ser)?);
+                }
+            }
+
+            c.methods.push(self.encode_path_segment());
+            c
+        };
+
+        let default_base_url = body.http.url.as_ref().map(|s| s.as_str());
+
+        spec.body.push(http);
+
+        spec.body.push(Builder {
+            ty: java::local("Http"),
+            optional: self.optional.clone(),
+            uri: self.uri.clone(),
+            client: client,
+            base_url: base_url,
+            default_base_url: default_base_url,
+            ser: ser,
+            serialization: &self.serialization,
+        });
+
+        Ok(())
+    }
+}
@@ -2,11 +2,12 @@
 
 use codegen::{
     ClassAdded, ClassCodegen, Codegen, Configure, EnumAdded, EnumCodegen, GetterAdded,
-    GetterCodegen, InterfaceAdded, InterfaceCodegen, TupleAdded, TupleCodegen,
+    GetterCodegen, InterfaceAdded, InterfaceCodegen, NewtypeAdded, NewtypeCodegen, TupleAdded,
+    TupleCodegen, UnionAdded, UnionCodegen,
 };
 use core::errors::Result;
-use core::{Handle, RpSubTypeStrategy};
-use flavored::{RpInterfaceBody, RpPackage};
+use core::{Handle, Loc, RpSubTypeStrategy};
+use flavored::{JavaField, RpInterfaceBody, RpPackage, RpUnionBody};
 use genco::java::{
     self, Argument, Class, Field, Interface, Method, Modifier, DOUBLE, FLOAT, INTEGER, LONG, SHORT,
 };
@@ -14,7 +15,7 @@ use genco::{Cons, Element, IntoTokens, Java, Quoted, Tokens};
 use java_file::JavaFile;
 use serialization::Serialization;
 use std::rc::Rc;
-use utils::Override;
+use utils::{union_variant_class, Override};
 
 pub struct Module;
 
@@ -33,6 +34,8 @@ impl Module {
             .interface_generators
             .push(Box::new(jackson.clone()));
         e.options.enum_generators.push(Box::new(jackson.clone()));
+        e.options.union_generators.push(Box::new(jackson.clone()));
+        e.options.newtype_generators.push(Box::new(jackson.clone()));
         e.options
             .root_generators
             .push(Box::new(JacksonSupport::new()));
@@ -327,6 +330,77 @@ impl Jackson {
         Ok((token, reader))
     }
 
+    /// Deserialize implementation for unions, which tries each variant in the order that they
+    /// are declared.
+    fn union_deserialize<'el>(
+        &self,
+        name: Cons<'el>,
+        body: &'el RpUnionBody,
+    ) -> Result<Class<'el>> {
+        let tree = java::imported("com.fasterxml.jackson.databind", "JsonNode");
+        let processing = java::imported("com.fasterxml.jackson.core", "JsonProcessingException");
+        let ttparser = java::imported(
+            "com.fasterxml.jackson.databind.node",
+            "TreeTraversingParser",
+        );
+
+        let parser = Argument::new(self.parser.clone(), "parser");
+        let context = Argument::new(self.deserialization_context.clone(), "context");
+
+        let mut des = Method::new("deserialize");
+        des.annotation(Override);
+        des.arguments.push(parser.clone());
+        des.arguments.push(context.clone());
+        des.throws = Some(self.io_exception.clone().into());
+        des.returns = java::local(name.clone());
+
+        des.body.push({
+            let mut t = Tokens::new();
+
+            let read = toks![parser.var(), ".readValueAsTree()"];
+            push!(t, "final ", tree, " tree = ", read, ";");
+
+            for variant in &body.variants {
+                let ty = variant.ty.as_boxed();
+                let is_empty = ty.arguments().map(|a| a.is_empty()).unwrap_or(true);
+
+                let argument = if is_empty {
+                    toks![ty, ".class"]
+                } else {
+                    toks!["new ", self.type_reference.with_arguments(vec![ty]), "(){}"]
+                };
+
+                let class = java::local(format!(
+                    "{}.{}",
+                    name,
+                    union_variant_class(variant.ident.as_str())
+                ));
+
+                t.push_into(|t| {
+                    let p = toks!["new ", ttparser.clone(), "(tree, parser.getCodec())"];
+                    let value = toks![p, ".readValueAs(", argument, ")"];
+
+                    push!(t, "try {");
+                    nested!(t, "return new ", class, "(", value, ");");
+                    push!(t, "} catch (final ", processing, " e) {");
+                    nested!(t, "// try the next variant");
+                    push!(t, "}");
+                });
+            }
+
+            let m = "no variant of the union matched".quoted();
+            push!(t, "throw ", context.var(), ".mappingException(", m, ");");
+
+            t.join_line_spacing()
+        });
+
+        let mut c = Class::new("Deserializer");
+        c.modifiers.push(Modifier::Static);
+        c.extends = Some(self.deserializer.with_arguments(vec![java::local(name)]));
+        c.methods.push(des);
+        Ok(c)
+    }
+
     /// Deserialize implementation for tuples.
     fn tuple_deserializer<'el>(
         &self,
@@ -430,7 +504,12 @@ impl Jackson {
         })
     }
 
-    fn add_class_annotations<'el>(&self, names: &[&'el str], spec: &mut Class<'el>) -> Result<()> {
+    fn add_class_annotations<'el>(
+        &self,
+        names: &[&'el str],
+        fields: &[&'el Loc<JavaField<'static>>],
+        spec: &mut Class<'el>,
+    ) -> Result<()> {
         // Annotate all constructors.
         for c in &mut spec.constructors {
             c.annotation(toks!["@", self.creator.clone()]);
@@ -441,10 +520,15 @@ impl Jackson {
         }
 
         // Also add field annotations, since they are used during serialization!
-        for (field, name) in spec.fields.iter_mut().zip(names.iter().cloned()) {
+        for ((field, name), f) in spec
+            .fields
+            .iter_mut()
+            .zip(names.iter().cloned())
+            .zip(fields)
+        {
             field.annotation(JsonProperty(name.into()));
 
-            if field.ty().as_value() == self.instant {
+            if field.ty().as_value() == self.instant || f.is_number_as_string() {
                 field.annotation(JsonFormat);
             }
         }
@@ -502,7 +586,7 @@ impl GetterCodegen for Jackson {
 
 impl ClassCodegen for Jackson {
     fn generate(&self, e: ClassAdded) -> Result<()> {
-        self.add_class_annotations(&e.names, e.spec)?;
+        self.add_class_annotations(&e.names, &e.fields, e.spec)?;
 
         if let Some(interface) = e.interface {
             match interface.sub_type_strategy {
@@ -669,6 +753,33 @@ impl InterfaceCodegen for Jackson {
     }
 }
 
+impl UnionCodegen for Jackson {
+    fn generate(&self, UnionAdded { spec, body, getter }: UnionAdded) -> Result<()> {
+        getter.annotation(toks!["@", self.value.clone()]);
+
+        let c = self.union_deserialize(spec.name(), body)?;
+        let n = java::local(format!("{}.{}", spec.name(), c.name()));
+        spec.annotation(Deserialize(n));
+        spec.body.push(c);
+        Ok(())
+    }
+}
+
+impl NewtypeCodegen for Jackson {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        // NB: delegating creator, so that the wrapped value is validated when deserialized.
+        e.constructor.annotation(toks![
+            "@",
+            self.creator.clone(),
+            "(mode = ",
+            self.creator.clone(),
+            ".Mode.DELEGATING)"
+        ]);
+        e.getter.annotation(toks!["@", self.value.clone()]);
+        Ok(())
+    }
+}
+
 struct JacksonSupport {}
 
 impl JacksonSupport {
@@ -1,17 +1,31 @@
+mod binary;
 mod builder;
 mod constructor_properties;
 mod grpc;
+mod http_client;
 mod jackson;
 mod lombok;
 mod mutable;
+mod nullability;
 mod nullable;
 mod okhttp;
+mod service_methods;
+mod target;
+mod unknown_variant;
 
-pub use self::builder::Module as Builder;
+pub use self::binary::Module as Binary;
+pub use self::builder::{Config as BuilderConfig, Module as Builder};
 pub use self::constructor_properties::Module as ConstructorProperties;
 pub use self::grpc::Module as Grpc;
+pub use self::http_client::{Config as HttpClientConfig, Module as HttpClient};
 pub use self::jackson::Module as Jackson;
 pub use self::lombok::Module as Lombok;
 pub use self::mutable::Module as Mutable;
+pub use self::nullability::{
+    Annotations as NullabilityAnnotations, Config as NullabilityConfig, Module as Nullability,
+};
 pub use self::nullable::Module as Nullable;
 pub use self::okhttp::{Config as OkHttpConfig, Module as OkHttp};
+pub use self::service_methods::Module as ServiceMethods;
+pub use self::target::{Config as TargetConfig, Module as Target};
+pub use self::unknown_variant::Module as UnknownVariant;
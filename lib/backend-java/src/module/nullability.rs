@@ -0,0 +1,108 @@
+//! Module that adds nullability annotations to fields, getters, setters, and constructor
+//! arguments.
+
+use codegen::Configure;
+use core::errors::*;
+use genco::java::imported;
+use genco::Java;
+
+/// Well-known sets of nullability annotations.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// `org.jspecify.annotations`
+    Jspecify,
+    /// `javax.annotation` from JSR-305.
+    Jsr305,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Preset::Jspecify
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Set of annotations to use.
+    #[serde(default)]
+    annotations: Preset,
+    /// Fully qualified name of a custom annotation for nullable values.
+    nullable: Option<String>,
+    /// Fully qualified name of a custom annotation for non-null values.
+    non_null: Option<String>,
+}
+
+/// The annotations used to mark values as nullable or non-null.
+#[derive(Debug, Clone)]
+pub struct Annotations {
+    pub nullable: Java<'static>,
+    pub non_null: Java<'static>,
+}
+
+impl Annotations {
+    /// Annotations of the given preset.
+    pub fn preset(preset: Preset) -> Annotations {
+        match preset {
+            Preset::Jspecify => Annotations {
+                nullable: imported("org.jspecify.annotations", "Nullable"),
+                non_null: imported("org.jspecify.annotations", "NonNull"),
+            },
+            Preset::Jsr305 => Annotations {
+                nullable: imported("javax.annotation", "Nullable"),
+                non_null: imported("javax.annotation", "Nonnull"),
+            },
+        }
+    }
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        Annotations::preset(Preset::default())
+    }
+}
+
+pub struct Module {
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Module {
+    pub fn initialize(self, e: Configure) -> Result<()> {
+        let preset = Annotations::preset(self.config.annotations);
+
+        let nullable = match self.config.nullable {
+            Some(name) => annotation(name)?,
+            None => preset.nullable,
+        };
+
+        let non_null = match self.config.non_null {
+            Some(name) => annotation(name)?,
+            None => preset.non_null,
+        };
+
+        e.options.nullability = Some(Annotations { nullable, non_null });
+        Ok(())
+    }
+}
+
+/// Parse the fully qualified name of an annotation.
+fn annotation(name: String) -> Result<Java<'static>> {
+    let (package, ident) = match name.rfind('.') {
+        Some(index) => (name[..index].to_string(), name[index + 1..].to_string()),
+        None => {
+            return Err(format!(
+                "nullability: annotation must be fully qualified, like `com.example.Nullable`: {}",
+                name
+            )
+            .into())
+        }
+    };
+
+    Ok(imported(package, ident))
+}
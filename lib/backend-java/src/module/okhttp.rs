@@ -3,11 +3,11 @@
 use codegen::{Configure, ServiceAdded, ServiceCodegen};
 use core::errors::*;
 use core::{self, Loc};
-use flavored::{JavaEndpoint, RpEndpointHttp1, RpPathStep};
+use flavored::{JavaEndpoint, RpEndpointArgument, RpEndpointHttp1, RpPathStep};
 use genco::java::{self, Argument, Class, Constructor, Field, Method, Modifier, VOID};
 use genco::{Cons, IntoTokens, Java, Quoted, Tokens};
 use serialization::Serialization;
-use utils::Override;
+use utils::{error_exception_class, Override};
 
 #[derive(Debug, Default, Deserialize)]
 pub struct Config {}
@@ -220,6 +220,8 @@ pub struct OkHttpServiceCodegen {
     io_exc: Java<'static>,
     optional: Java<'static>,
     completable_future: Java<'static>,
+    media_type: Java<'static>,
+    request_body: Java<'static>,
 }
 
 impl OkHttpServiceCodegen {
@@ -235,6 +237,8 @@ impl OkHttpServiceCodegen {
             io_exc: java::imported("java.io", "IOException"),
             optional: java::imported("java.util", "Optional"),
             completable_future: java::imported("java.util.concurrent", "CompletableFuture"),
+            media_type: java::imported("okhttp3", "MediaType"),
+            request_body: java::imported("okhttp3", "RequestBody"),
         }
     }
 }
@@ -249,12 +253,6 @@ impl OkHttpServiceCodegen {
         base_url: Field<'el>,
         ser: &Field<'el>,
     ) -> Result<Method<'el>> {
-        let request_var = e
-            .request
-            .as_ref()
-            .map(|r| toks![ser.clone(), ".encode(", r.safe_ident(), ")"])
-            .unwrap_or_else(|| toks!["null"]);
-
         method.body.push({
             let mut t = Tokens::new();
 
@@ -271,11 +269,48 @@ impl OkHttpServiceCodegen {
                 t.nested(toks![".addPathSegment(", args.join(" + "), ")"]);
             }
 
+            for p in &http.query {
+                let name = p.name.as_str().quoted();
+                let value = to_string(&p.argument);
+                nested!(t, ".addQueryParameter(", name, ", ", value, ")");
+            }
+
             t.nested(".build();");
 
             t
         });
 
+        let request_var = match e.request.as_ref() {
+            Some(r) => {
+                let media_type = http.accepts.as_str().quoted();
+                let media_type = toks![self.media_type.clone(), ".parse(", media_type, ")"];
+
+                let content = match http.accepts {
+                    core::RpAccept::Json => {
+                        let completable_future = self.completable_future.clone();
+
+                        method.body.push(self.serialization.encode(
+                            ser,
+                            toks![r.safe_ident()],
+                            "body_",
+                            |e| {
+                                let mut t = Tokens::new();
+                                push!(t, "return ", completable_future, ".failedFuture(", e, ");");
+                                Ok(t)
+                            },
+                        )?);
+
+                        toks!["body_"]
+                    }
+                    core::RpAccept::Text => toks![r.safe_ident()],
+                };
+
+                let request_body = self.request_body.clone();
+                toks![request_body, ".create(", media_type, ", ", content, ")"]
+            }
+            None => toks!["null"],
+        };
+
         method.body.push({
             let mut t = Tokens::new();
 
@@ -291,7 +326,17 @@ impl OkHttpServiceCodegen {
             let method = http.method.as_str();
 
             nested!(t, ".url(url_)");
-            // TODO: actually provide the body
+
+            for h in &http.headers {
+                let value = to_string(&h.argument);
+                nested!(t, ".addHeader(", h.name.as_str().quoted(), ", ", value, ")");
+            }
+
+            if e.response.is_some() {
+                let accept = http.produces.as_str().quoted();
+                nested!(t, ".addHeader(", "Accept".quoted(), ", ", accept, ")");
+            }
+
             nested!(t, ".method(", method.quoted(), ", ", request_var, ")");
             nested!(t, ".build();");
 
@@ -335,6 +380,12 @@ impl OkHttpServiceCodegen {
 
                     let mut m = Method::new("onResponse");
                     m.annotation(Override);
+
+                    // NB: reading the body as a string might fail.
+                    if http.produces == core::RpAccept::Text {
+                        m.throws = Some(toks![self.io_exc.clone()]);
+                    }
+
                     m.arguments.push(Argument::new(self.call.clone(), "call"));
                     m.arguments.push(response.clone());
 
@@ -349,6 +400,32 @@ impl OkHttpServiceCodegen {
                             " + response)",
                         ];
 
+                        for error in &e.errors {
+                            let decode = self.serialization.decode(
+                                ser,
+                                &error.ty,
+                                "response.body().byteStream()",
+                                "error_",
+                                |e| {
+                                    let mut t = Tokens::new();
+                                    push!(t, "future_.completeExceptionally(", e, ");");
+                                    push!(t, "return;");
+                                    Ok(t)
+                                },
+                            )?;
+
+                            let exc = java::local(error_exception_class(error.ident()));
+                            let status = error.status.to_string();
+
+                            t.push_into(|t| {
+                                push!(t, "if (response.code() == ", status, ") {");
+                                t.nested(decode);
+                                nested!(t, "future_.completeExceptionally(new ", exc, "(error_));");
+                                nested!(t, "return;");
+                                push!(t, "}");
+                            });
+                        }
+
                         t.push_into(|t| {
                             t.push("if (!response.isSuccessful()) {");
                             nested!(t, "future_.completeExceptionally(", exc, ");");
@@ -361,23 +438,28 @@ impl OkHttpServiceCodegen {
 
                             let input = "response.body().byteStream()";
 
-                            let var = if let Some(r) = e.response.as_ref() {
-                                t.push(self.serialization.decode(
-                                    ser,
-                                    r.ty(),
-                                    input,
-                                    "body",
-                                    |e| {
-                                        let mut t = Tokens::new();
-                                        push!(t, "future_.completeExceptionally(", e, ");");
-                                        push!(t, "return;");
-                                        Ok(t)
-                                    },
-                                )?);
-
-                                "body"
-                            } else {
-                                "null"
+                            let var = match e.response.as_ref() {
+                                Some(_) if http.produces == core::RpAccept::Text => {
+                                    push!(t, "final String body = response.body().string();");
+                                    "body"
+                                }
+                                Some(r) => {
+                                    t.push(self.serialization.decode(
+                                        ser,
+                                        r.ty(),
+                                        input,
+                                        "body",
+                                        |e| {
+                                            let mut t = Tokens::new();
+                                            push!(t, "future_.completeExceptionally(", e, ");");
+                                            push!(t, "return;");
+                                            Ok(t)
+                                        },
+                                    )?);
+
+                                    "body"
+                                }
+                                None => "null",
                             };
 
                             push!(t, "future_.complete(", var, ");");
@@ -410,13 +492,7 @@ impl OkHttpServiceCodegen {
             for part in &step.parts {
                 match *part {
                     core::RpPathPart::Variable(ref arg) => {
-                        let ty = arg.channel.ty();
-
-                        if ty.is_primitive() {
-                            args.append(toks![ty.as_boxed(), ".toString(", arg.safe_ident(), ")"]);
-                        } else {
-                            args.append(arg.safe_ident());
-                        }
+                        args.append(to_string(arg));
                     }
                     core::RpPathPart::Segment(ref s) => {
                         args.append(s.to_string().quoted());
@@ -426,6 +502,17 @@ impl OkHttpServiceCodegen {
 
             Ok(args)
         }
+
+        /// Convert the value of an argument into a string.
+        fn to_string<'el>(arg: &'el RpEndpointArgument) -> Tokens<'el, Java<'el>> {
+            let ty = arg.channel.ty();
+
+            if ty.is_primitive() {
+                toks![ty.as_boxed(), ".toString(", arg.safe_ident(), ")"]
+            } else {
+                toks![arg.safe_ident()]
+            }
+        }
     }
 }
 
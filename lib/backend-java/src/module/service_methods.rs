@@ -0,0 +1,11 @@
+//! Module that declares a method for every endpoint in service interfaces.
+
+use codegen::Configure;
+
+pub struct Module;
+
+impl Module {
+    pub fn initialize(self, e: Configure) {
+        e.options.build_service_methods = true;
+    }
+}
@@ -0,0 +1,42 @@
+//! Module that configures which version of Java to target, and how optional fields are
+//! represented.
+
+use codegen::Configure;
+use core::errors::*;
+use options::OptionalStrategy;
+
+/// Oldest supported version of Java.
+const MIN_VERSION: u32 = 8;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Version of Java to generate code for.
+    version: Option<u32>,
+    /// How optional fields are represented.
+    optional: Option<OptionalStrategy>,
+}
+
+pub struct Module;
+
+impl Module {
+    /// Configure the target before any other module is initialized, since they depend on it.
+    pub fn prepare(config: &Config, e: Configure) -> Result<()> {
+        if let Some(version) = config.version {
+            if version < MIN_VERSION {
+                return Err(format!(
+                    "target: unsupported Java version {}, must be at least {}",
+                    version, MIN_VERSION
+                )
+                .into());
+            }
+
+            e.options.java_version = version;
+        }
+
+        if let Some(optional) = config.optional {
+            e.options.optional_strategy = optional;
+        }
+
+        Ok(())
+    }
+}
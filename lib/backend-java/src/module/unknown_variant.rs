@@ -0,0 +1,9 @@
+use codegen::Configure;
+
+pub struct Module;
+
+impl Module {
+    pub fn initialize(self, e: Configure) {
+        e.options.unknown_variant = true;
+    }
+}
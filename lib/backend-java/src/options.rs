@@ -1,17 +1,52 @@
 //! Options for java code generation.
 
 use codegen::{
-    ClassCodegen, Codegen, EnumCodegen, GetterCodegen, InterfaceCodegen, ServiceCodegen,
-    TupleCodegen,
+    ClassCodegen, Codegen, EnumCodegen, GetterCodegen, InterfaceCodegen, NewtypeCodegen,
+    ServiceCodegen, TupleCodegen, UnionCodegen,
 };
 use core::errors::Result;
 use genco::Java;
+use module::NullabilityAnnotations;
 use serialization::Serialization;
 use std::mem;
 
+/// How optional fields are represented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionalStrategy {
+    /// Fields, getters, and arguments are `Optional<T>`.
+    Optional,
+    /// Fields, getters, and arguments are `T`, which is `null` if the field is absent.
+    Nullable,
+    /// Like `Nullable`, but values are also annotated with their nullability.
+    Annotated,
+}
+
+impl OptionalStrategy {
+    /// Check if optional fields are represented as nullable values.
+    pub fn is_nullable(&self) -> bool {
+        match *self {
+            OptionalStrategy::Optional => false,
+            OptionalStrategy::Nullable | OptionalStrategy::Annotated => true,
+        }
+    }
+}
+
+impl Default for OptionalStrategy {
+    fn default() -> Self {
+        OptionalStrategy::Optional
+    }
+}
+
 pub struct Options {
+    /// Version of Java to generate code for.
+    pub java_version: u32,
+    /// How optional fields are represented.
+    pub optional_strategy: OptionalStrategy,
     /// Should fields be nullable?
     pub nullable: bool,
+    /// Annotations to mark values as nullable or non-null with.
+    pub nullability: Option<NullabilityAnnotations>,
     /// Should the type be immutable?
     pub immutable: bool,
     /// Build setters?
@@ -32,8 +67,12 @@ pub struct Options {
     pub serialization: Option<Serialization>,
     /// Container to use for asynchronous operations.
     pub async_container: Option<Java<'static>>,
+    /// Build methods for each endpoint in service interfaces?
+    pub build_service_methods: bool,
     /// Do not generate methods in service interface.
     pub suppress_service_methods: bool,
+    /// Generate an `UNKNOWN` variant for enums, which is used for unknown values when decoding.
+    pub unknown_variant: bool,
     /// Hook to generate code called in the root of the declarations.
     pub root_generators: Vec<Box<Codegen>>,
     /// Hook to run getter generators.
@@ -48,12 +87,19 @@ pub struct Options {
     pub interface_generators: Vec<Box<InterfaceCodegen>>,
     /// Hook to run enum generators.
     pub enum_generators: Vec<Box<EnumCodegen>>,
+    /// Hook to run union generators.
+    pub union_generators: Vec<Box<UnionCodegen>>,
+    /// Hook to run newtype generators.
+    pub newtype_generators: Vec<Box<NewtypeCodegen>>,
 }
 
 impl Options {
     pub fn new() -> Self {
         Self {
+            java_version: 8,
+            optional_strategy: OptionalStrategy::default(),
             nullable: false,
+            nullability: None,
             immutable: true,
             build_setters: true,
             build_getters: true,
@@ -64,7 +110,9 @@ impl Options {
             uses_observer: false,
             serialization: None,
             async_container: None,
+            build_service_methods: false,
             suppress_service_methods: false,
+            unknown_variant: false,
             root_generators: Vec::new(),
             getter_generators: Vec::new(),
             class_generators: Vec::new(),
@@ -72,6 +120,8 @@ impl Options {
             tuple_generators: Vec::new(),
             interface_generators: Vec::new(),
             enum_generators: Vec::new(),
+            union_generators: Vec::new(),
+            newtype_generators: Vec::new(),
         }
     }
 
@@ -87,6 +137,16 @@ impl Options {
         Ok(())
     }
 
+    /// Check if local variables can be declared using `var`.
+    pub fn local_variable_type_inference(&self) -> bool {
+        self.java_version >= 10
+    }
+
+    /// Check if `instanceof` can bind a variable.
+    pub fn pattern_matching_instanceof(&self) -> bool {
+        self.java_version >= 16
+    }
+
     pub fn get_serialization(&self) -> Result<Serialization> {
         match self.serialization {
             Some(s) => Ok(s),
@@ -35,6 +35,42 @@ impl Serialization {
         }
     }
 
+    /// Encode the given value into a byte array.
+    pub fn encode<'el, E>(
+        &self,
+        m: &Field<'el>,
+        i: Tokens<'el, Java<'el>>,
+        o: &'el str,
+        exc: E,
+    ) -> Result<Tokens<'el, Java<'el>>>
+    where
+        E: FnOnce(&'el str) -> Result<Tokens<'el, Java<'el>>>,
+    {
+        use self::Serialization::*;
+
+        match *self {
+            Jackson => {
+                let mut t = Tokens::new();
+
+                push!(t, "final byte[] ", o, ";");
+
+                t.push({
+                    let mut t = Tokens::new();
+
+                    push!(t, "try {");
+                    nested!(t, o, " = ", m.var(), ".writeValueAsBytes(", i, ");");
+                    push!(t, "} catch(final Exception e) {");
+                    t.nested(exc("e")?);
+                    push!(t, "}");
+
+                    t
+                });
+
+                Ok(t.join_line_spacing())
+            }
+        }
+    }
+
     /// Decode argument for the given type.
     pub fn decode<'el, E>(
         &self,
@@ -1,6 +1,18 @@
 use genco::java::{imported, local, Argument, Interface, Method};
 use genco::{IntoTokens, Java, Tokens};
 
+/// Name of the class holding the value of the given union variant.
+///
+/// Suffixed to avoid clashing with the name of the type it holds, like `String`.
+pub fn union_variant_class(ident: &str) -> String {
+    format!("{}Value", ident)
+}
+
+/// Name of the exception thrown for the given endpoint error.
+pub fn error_exception_class(ident: &str) -> String {
+    format!("{}Exception", ident)
+}
+
 /// @Override annotation
 pub struct Override;
 
@@ -10,6 +22,43 @@ impl<'el> IntoTokens<'el, Java<'el>> for Override {
     }
 }
 
+/// @Deprecated annotation
+pub struct Deprecated;
+
+impl<'el> IntoTokens<'el, Java<'el>> for Deprecated {
+    fn into_tokens(self) -> Tokens<'el, Java<'el>> {
+        toks!["@Deprecated"]
+    }
+}
+
+/// Javadoc holding documentation, which is empty unless there is something to document.
+pub struct Javadoc<'el>(pub &'el [String]);
+
+impl<'el> IntoTokens<'el, Java<'el>> for Javadoc<'el> {
+    fn into_tokens(self) -> Tokens<'el, Java<'el>> {
+        let mut t = Tokens::new();
+
+        if self.0.is_empty() {
+            return t;
+        }
+
+        t.push("/**");
+        t.push(" * <pre>");
+
+        for line in self.0 {
+            if line.is_empty() {
+                t.push(" *");
+            } else {
+                t.push(toks![" * ", line.as_str()]);
+            }
+        }
+
+        t.push(" * </pre>");
+        t.push(" */");
+        t
+    }
+}
+
 /// Observer interface used for bidirectional streaming communication.
 pub struct Observer;
 
@@ -1,19 +1,21 @@
 use backend::PackageProcessor;
 use core::errors::*;
-use core::{self, Handle, Loc};
+use core::{self, Handle, Loc, RpDeprecated};
 use flavored::{
-    JavaScriptFlavor, JavaScriptName, RpEnumBody, RpField, RpInterfaceBody, RpTupleBody, RpTypeBody,
+    JavaScriptFlavor, JavaScriptName, RpEnumBody, RpField, RpInterfaceBody, RpServiceBody,
+    RpTupleBody, RpTypeBody,
 };
-use genco::{Element, JavaScript, Quoted, Tokens};
+use genco::{Cons, Element, IntoTokens, JavaScript, Quoted, Tokens};
 use naming::{self, Naming};
 use std::rc::Rc;
 use trans::{self, Translated};
-use utils::{is_defined, is_not_defined};
-use {FileSpec, Options, EXT};
+use utils::{is_defined, is_not_defined, Deprecated, JsDoc};
+use {FileSpec, Options, Service, EXT};
 
 pub struct Compiler<'el> {
     pub env: &'el Translated<JavaScriptFlavor>,
     variant_field: &'el Loc<RpField>,
+    options: Options,
     handle: &'el Handle,
     to_lower_snake: naming::ToLowerSnake,
     values: Tokens<'static, JavaScript<'static>>,
@@ -24,12 +26,13 @@ impl<'el> Compiler<'el> {
     pub fn new(
         env: &'el Translated<JavaScriptFlavor>,
         variant_field: &'el Loc<RpField>,
-        _: Options,
+        options: Options,
         handle: &'el Handle,
     ) -> Compiler<'el> {
         Compiler {
             env,
             variant_field,
+            options,
             handle,
             to_lower_snake: naming::to_lower_snake(),
             values: "values".into(),
@@ -42,6 +45,25 @@ impl<'el> Compiler<'el> {
         self.write_files(files)
     }
 
+    /// Documentation of a declaration, which only includes a deprecation notice unless JSDoc is
+    /// enabled.
+    fn doc(
+        &self,
+        comment: &'el [String],
+        deprecated: &'el Option<RpDeprecated>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        if !self.options.jsdoc {
+            return Deprecated(deprecated).into_tokens();
+        }
+
+        JsDoc {
+            comment,
+            deprecated: deprecated.as_ref(),
+            tags: Vec::new(),
+        }
+        .into_tokens()
+    }
+
     /// Build a function that throws an exception if the given value `toks` is None.
     fn throw_if_null<S>(&self, toks: S, field: &Loc<RpField>) -> Tokens<'el, JavaScript<'el>>
     where
@@ -127,9 +149,10 @@ impl<'el> Compiler<'el> {
 
     fn decode_enum_method(
         &self,
-        name: &'el JavaScriptName,
+        body: &'el RpEnumBody,
         ident: &'el str,
     ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let name = &body.name;
         let members = toks![name, ".", self.values.clone()];
         let loop_init = toks!["let i = 0, l = ", members.clone(), ".length"];
         let match_member = toks!["member.", ident, " === data"];
@@ -141,13 +164,28 @@ impl<'el> Compiler<'el> {
         let mut member_loop = Tokens::new();
         member_loop.push(js![for loop_init; "i < l"; "i++", loop_body.join_line_spacing()]);
 
-        let mut body = Tokens::new();
-        body.push(member_loop);
-        body.push(js![throw "no matching value: ".quoted(), " + data"]);
+        let mut decode_body = Tokens::new();
+        decode_body.push(member_loop);
+
+        // aliases are only accepted when decoding.
+        for v in body.variants.iter() {
+            for alias in v.aliases.iter() {
+                let alias = match alias {
+                    core::RpVariantValue::String(string) => toks![string.quoted()],
+                    core::RpVariantValue::Number(number) => toks![number.to_string()],
+                };
+
+                let match_alias = toks!["data === ", alias];
+                let member = toks!["return ", name, ".", v.ident(), ";"];
+                decode_body.push(js![if match_alias, member]);
+            }
+        }
+
+        decode_body.push(js![throw "no matching value: ".quoted(), " + data"]);
 
         let mut decode = Tokens::new();
         decode.push("static decode(data) {");
-        decode.nested(body.join_line_spacing());
+        decode.nested(decode_body.join_line_spacing());
         decode.push("}");
         Ok(decode)
     }
@@ -229,6 +267,7 @@ impl<'el> Compiler<'el> {
     {
         let mut arguments = Tokens::new();
         let mut assignments = Tokens::new();
+        let mut params = Vec::new();
 
         for field in fields {
             arguments.append(field.safe_ident());
@@ -239,9 +278,20 @@ impl<'el> Compiler<'el> {
                 field.safe_ident(),
                 ";",
             ]);
+
+            if self.options.jsdoc {
+                params.push(param_doc(field));
+            }
         }
 
         let mut ctor = Tokens::new();
+
+        ctor.push_unless_empty(JsDoc {
+            comment: &[],
+            deprecated: None,
+            tags: params,
+        });
+
         ctor.push(toks!["constructor(", arguments.join(", "), ") {"]);
         ctor.nested(assignments);
         ctor.push("}");
@@ -280,7 +330,7 @@ impl<'el> Compiler<'el> {
     fn enum_encode_decode(
         &self,
         field: &'el Loc<RpField>,
-        name: &'el JavaScriptName,
+        body: &'el RpEnumBody,
     ) -> Result<Tokens<'el, JavaScript<'el>>> {
         let mut elements = Tokens::new();
 
@@ -292,7 +342,7 @@ impl<'el> Compiler<'el> {
             encode
         });
 
-        let decode = self.decode_enum_method(name, field.safe_ident())?;
+        let decode = self.decode_enum_method(body, field.safe_ident())?;
         elements.push(decode);
         return Ok(elements.into());
     }
@@ -319,6 +369,24 @@ impl<'el> Compiler<'el> {
     }
 }
 
+/// JSDoc parameter for a field.
+fn param_doc<'el>(field: &'el Loc<RpField>) -> Tokens<'el, JavaScript<'el>> {
+    let ty = if field.is_optional() {
+        toks!["{?", field.ty.doc(), "}"]
+    } else {
+        toks!["{", field.ty.doc(), "}"]
+    };
+
+    let mut t = toks!["@param ", ty, " ", field.safe_ident()];
+
+    if !field.comment.is_empty() {
+        t.append(" ");
+        t.append(Cons::from(field.comment.join(" ")));
+    }
+
+    t
+}
+
 impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'el> {
     type Out = FileSpec<'el>;
     type DeclIter = trans::translated::DeclIter<'el, JavaScriptFlavor>;
@@ -354,6 +422,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
 
         let mut class = Tokens::new();
 
+        class.push_unless_empty(self.doc(&body.comment, &body.deprecated));
         class.push(toks!["export class ", &body.name, " {"]);
         class.nested(class_body.join_line_spacing());
         class.push("}");
@@ -368,7 +437,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
         let mut members = Tokens::new();
 
         class_body.push(self.build_enum_constructor(self.variant_field));
-        class_body.push(self.enum_encode_decode(self.variant_field, &body.name)?);
+        class_body.push(self.enum_encode_decode(self.variant_field, body)?);
 
         let mut values = Tokens::new();
 
@@ -389,6 +458,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
             let args = js![new & body.name, args];
             let member = toks![&body.name, ".", v.ident()];
 
+            values.push_unless_empty(self.doc(v.comment, v.deprecated));
             values.push(js![= member.clone(), args]);
             members.append(member);
         }
@@ -399,6 +469,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
 
         let mut class = Tokens::new();
 
+        class.push_unless_empty(self.doc(&body.comment, &body.deprecated));
         class.push(toks!["export class ", &body.name, " {"]);
         class.nested(class_body.join_line_spacing());
         class.push("}");
@@ -436,6 +507,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
 
         let mut class = Tokens::new();
 
+        class.push_unless_empty(self.doc(&body.comment, &body.deprecated));
         class.push(toks!["export class ", &body.name, " {"]);
         class.nested(class_body.join_line_spacing());
         class.push("}");
@@ -444,6 +516,17 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
         Ok(())
     }
 
+    fn process_service(&self, out: &mut Self::Out, body: &'el RpServiceBody) -> Result<()> {
+        for s in &self.options.service {
+            s.generate(Service {
+                body: body,
+                container: &mut out.0,
+            })?;
+        }
+
+        Ok(())
+    }
+
     fn process_interface(&self, out: &mut Self::Out, body: &'el RpInterfaceBody) -> Result<()> {
         let mut classes = Tokens::new();
 
@@ -464,6 +547,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
         classes.push({
             let mut tokens = Tokens::new();
 
+            tokens.push_unless_empty(self.doc(&body.comment, &body.deprecated));
             tokens.push(toks!["export class ", &body.name, " {"]);
             tokens.nested(interface_body.join_line_spacing());
             tokens.push("}");
@@ -512,6 +596,7 @@ impl<'el> PackageProcessor<'el, JavaScriptFlavor, JavaScriptName> for Compiler<'
             classes.push({
                 let mut tokens = Tokens::new();
 
+                tokens.push_unless_empty(self.doc(&sub_type.comment, &sub_type.deprecated));
                 tokens.push(toks!["export class ", &sub_type.name, " {"]);
                 tokens.nested(class_body.join_line_spacing());
                 tokens.push("}");
@@ -20,10 +20,17 @@ use {Options, TYPE_SEP};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum JavaScriptType<'el> {
-    Native,
+    /// A native value, with the name of its type as used in JSDoc.
+    Native(&'static str),
+    /// Bytes are encoded as base64 strings, and represented as an `Uint8Array`.
+    Bytes,
     Array {
         argument: Box<JavaScriptType<'el>>,
     },
+    /// Sets are encoded as arrays, so they are never native.
+    Set {
+        argument: Box<JavaScriptType<'el>>,
+    },
     Map {
         key: Box<JavaScriptType<'el>>,
         value: Box<JavaScriptType<'el>>,
@@ -39,12 +46,21 @@ impl<'el> JavaScriptType<'el> {
         use self::JavaScriptType::*;
 
         match *self {
-            Native => toks![var],
+            Native(_) => toks![var],
             ref v if v.is_native() => toks![var],
+            Bytes => toks![
+                "Uint8Array.from(atob(",
+                var,
+                "), function(c) { return c.charCodeAt(0); })"
+            ],
             Array { ref argument } => {
                 let a = argument.decode("v".into());
                 toks![var, ".map(function(v) { return ", a, "; })"]
             }
+            Set { ref argument } => {
+                let a = argument.decode("v".into());
+                toks!["new Set(", var, ".map(function(v) { return ", a, "; }))"]
+            }
             Map { ref key, ref value } => {
                 let k = key.decode("k".into());
                 let v = value.decode("data[k]".into());
@@ -70,12 +86,21 @@ impl<'el> JavaScriptType<'el> {
         use self::JavaScriptType::*;
 
         match *self {
-            Native => toks![var],
+            Native(_) => toks![var],
             ref v if v.is_native() => toks![var],
+            Bytes => toks![
+                "btoa(Array.from(",
+                var,
+                ", function(b) { return String.fromCharCode(b); }).join(\"\"))"
+            ],
             Array { ref argument } => {
                 let v = argument.encode("v".into());
                 toks![var, ".map(function(v) { return ", v, "; })"]
             }
+            Set { ref argument } => {
+                let v = argument.encode("v".into());
+                toks!["Array.from(", var, ", function(v) { return ", v, "; })"]
+            }
             Map { ref key, ref value } => {
                 let k = key.encode("k".into());
                 let v = value.encode("data[k]".into());
@@ -96,12 +121,26 @@ impl<'el> JavaScriptType<'el> {
         }
     }
 
+    /// Build the type as used in JSDoc.
+    pub fn doc(&self) -> Tokens<'el, JavaScript<'el>> {
+        use self::JavaScriptType::*;
+
+        match *self {
+            Native(name) => toks![name],
+            Bytes => toks!["Uint8Array"],
+            Array { ref argument } => toks!["Array<", argument.doc(), ">"],
+            Set { ref argument } => toks!["Set<", argument.doc(), ">"],
+            Map { ref key, ref value } => toks!["Object<", key.doc(), ", ", value.doc(), ">"],
+            Name { ref js } => toks![js.clone()],
+        }
+    }
+
     /// Check if the current type is completely native.
     fn is_native(&self) -> bool {
         use self::JavaScriptType::*;
 
         match *self {
-            Native => true,
+            Native(_) => true,
             Array { ref argument } => argument.is_native(),
             Map { ref key, ref value } => key.is_native() && value.is_native(),
             _ => false,
@@ -163,27 +202,39 @@ impl FlavorTranslator for JavaScriptFlavorTranslator {
     translator_defaults!(Self, field, endpoint, enum_type);
 
     fn translate_number(&self, number: RpNumberType) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("number"))
     }
 
     fn translate_float(&self) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("number"))
     }
 
     fn translate_double(&self) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("number"))
     }
 
     fn translate_boolean(&self) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("boolean"))
     }
 
     fn translate_string(&self, _: RpStringType) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("string"))
     }
 
     fn translate_datetime(&self) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("string"))
+    }
+
+    fn translate_date(&self) -> Result<JavaScriptType<'static>> {
+        Ok(JavaScriptType::Native("string"))
+    }
+
+    fn translate_time(&self) -> Result<JavaScriptType<'static>> {
+        Ok(JavaScriptType::Native("string"))
+    }
+
+    fn translate_duration(&self) -> Result<JavaScriptType<'static>> {
+        Ok(JavaScriptType::Native("string"))
     }
 
     fn translate_array(
@@ -195,6 +246,12 @@ impl FlavorTranslator for JavaScriptFlavorTranslator {
         })
     }
 
+    fn translate_set(&self, argument: JavaScriptType<'static>) -> Result<JavaScriptType<'static>> {
+        Ok(JavaScriptType::Set {
+            argument: Box::new(argument),
+        })
+    }
+
     fn translate_map(
         &self,
         key: JavaScriptType<'static>,
@@ -207,11 +264,11 @@ impl FlavorTranslator for JavaScriptFlavorTranslator {
     }
 
     fn translate_any(&self) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Native("*"))
     }
 
     fn translate_bytes(&self) -> Result<JavaScriptType<'static>> {
-        Ok(JavaScriptType::Native)
+        Ok(JavaScriptType::Bytes)
     }
 
     fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<JavaScriptType<'static>> {
@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate genco;
 #[macro_use]
+extern crate log;
+#[macro_use]
 extern crate reproto_backend as backend;
 #[macro_use]
 extern crate reproto_core as core;
@@ -18,8 +20,9 @@ extern crate toml;
 mod utils;
 mod compiler;
 mod flavored;
+mod module;
 
-use backend::IntoBytes;
+use backend::{Initializer, IntoBytes};
 use compiler::Compiler;
 use core::errors::Result;
 use core::{
@@ -27,7 +30,8 @@ use core::{
     Translate,
 };
 use genco::{JavaScript, Tokens};
-use manifest::{Lang, Manifest, NoModule, TryFromToml};
+use manifest::{Lang, Manifest, ModuleDoc, NoModule, TryFromToml};
+use naming::{Keywords, Naming};
 use std::any::Any;
 use std::path::Path;
 use trans::Session;
@@ -38,6 +42,25 @@ const EXT: &str = "js";
 #[derive(Clone, Copy, Default, Debug)]
 pub struct JsLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "websocket",
+        description: "Generate WebSocket clients for services.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "fetch",
+        description: "Generate HTTP clients for services using the `fetch` API.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "jsdoc",
+        description: "Add JSDoc type annotations to generated code.",
+        options: &[],
+    },
+];
+
 impl Lang for JsLang {
     lang_base!(JsModule, compile);
 
@@ -50,90 +73,64 @@ impl Lang for JsLang {
         true
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![
-            ("abstract", "_abstract"),
-            ("await", "_await"),
-            ("boolean", "_boolean"),
-            ("break", "_break"),
-            ("byte", "_byte"),
-            ("case", "_case"),
-            ("catch", "_catch"),
-            ("char", "_char"),
-            ("class", "_class"),
-            ("const", "_const"),
-            ("continue", "_continue"),
-            ("debugger", "_debugger"),
-            ("default", "_default"),
-            ("delete", "_delete"),
-            ("do", "_do"),
-            ("double", "_double"),
-            ("else", "_else"),
-            ("enum", "_enum"),
-            ("export", "_export"),
-            ("extends", "_extends"),
-            ("false", "_false"),
-            ("final", "_final"),
-            ("finally", "_finally"),
-            ("float", "_float"),
-            ("for", "_for"),
-            ("function", "_function"),
-            ("goto", "_goto"),
-            ("if", "_if"),
-            ("implements", "_implements"),
-            ("import", "_import"),
-            ("in", "_in"),
-            ("instanceof", "_instanceof"),
-            ("int", "_int"),
-            ("interface", "_interface"),
-            ("let", "_let"),
-            ("long", "_long"),
-            ("native", "_native"),
-            ("new", "_new"),
-            ("null", "_null"),
-            ("package", "_package"),
-            ("private", "_private"),
-            ("protected", "_protected"),
-            ("public", "_public"),
-            ("return", "_return"),
-            ("short", "_short"),
-            ("static", "_static"),
-            ("super", "_super"),
-            ("switch", "_switch"),
-            ("synchronized", "_synchronized"),
-            ("this", "_this"),
-            ("throw", "_throw"),
-            ("throws", "_throws"),
-            ("transient", "_transient"),
-            ("true", "_true"),
-            ("try", "_try"),
-            ("typeof", "_typeof"),
-            ("var", "_var"),
-            ("void", "_void"),
-            ("volatile", "_volatile"),
-            ("while", "_while"),
-            ("with", "_with"),
-            ("yield", "_yield"),
-        ]
+    fn endpoint_ident_naming(&self) -> Option<Box<Naming>> {
+        Some(Box::new(naming::to_lower_camel()))
+    }
+
+    fn keywords(&self) -> Option<&'static Keywords> {
+        Some(&naming::keywords::JS)
+    }
+
+    fn numbers_as_strings(&self) -> bool {
+        false
+    }
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
     }
 }
 
 #[derive(Debug)]
-pub enum JsModule {}
+pub enum JsModule {
+    WebSocket,
+    Fetch,
+    JsDoc,
+}
 
 impl TryFromToml for JsModule {
     fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
-        NoModule::illegal(path, id, value)
+        use self::JsModule::*;
+
+        let result = match id {
+            "websocket" => WebSocket,
+            "fetch" => Fetch,
+            "jsdoc" => JsDoc,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
     }
 
     fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
-        NoModule::illegal(path, id, value)
+        use self::JsModule::*;
+
+        let result = match id {
+            "websocket" => WebSocket,
+            "fetch" => Fetch,
+            "jsdoc" => JsDoc,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
     }
 }
 
 pub struct Options {
     pub build_getters: bool,
     pub build_constructor: bool,
+    /// Document generated classes using JSDoc.
+    pub jsdoc: bool,
+    pub service: Vec<Box<ServiceCodegen>>,
 }
 
 impl Options {
@@ -141,10 +138,42 @@ impl Options {
         Options {
             build_getters: false,
             build_constructor: true,
+            jsdoc: false,
+            service: Vec::new(),
         }
     }
 }
 
+pub struct Service<'a, 'el: 'a> {
+    body: &'el flavored::RpServiceBody,
+    container: &'a mut Tokens<'el, JavaScript<'el>>,
+}
+
+pub trait ServiceCodegen {
+    /// Generate service code.
+    fn generate(&self, service: Service) -> Result<()>;
+}
+
+fn options(modules: Vec<JsModule>) -> Result<Options> {
+    use self::JsModule::*;
+
+    let mut options = Options::new();
+
+    for m in modules {
+        debug!("+module: {:?}", m);
+
+        let initializer: Box<Initializer<Options = Options>> = match m {
+            WebSocket => Box::new(module::WebSocket::new()),
+            Fetch => Box::new(module::Fetch::new()),
+            JsDoc => Box::new(module::JsDoc::new()),
+        };
+
+        initializer.initialize(&mut options)?;
+    }
+
+    Ok(options)
+}
+
 pub struct FileSpec<'el>(pub Tokens<'el, JavaScript<'el>>);
 
 impl<'el> Default for FileSpec<'el> {
@@ -174,8 +203,8 @@ fn compile(handle: &Handle, env: Session<CoreFlavor>, manifest: Manifest) -> Res
 
     let env = env.translate(translator)?;
 
-    let _modules: Vec<JsModule> = manifest::checked_modules(manifest.modules)?;
-    let options = Options::new();
+    let modules: Vec<JsModule> = manifest::checked_modules(manifest.modules)?;
+    let options = options(modules)?;
 
     Compiler::new(&env, &variant_field, options, handle).compile()
 }
@@ -0,0 +1,251 @@
+//! fetch module for JavaScript.
+//!
+//! Generates a client for every service, which sends requests using the `fetch` API.
+
+use backend::Initializer;
+use core::errors::Result;
+use core::{self, RpAccept, RpHttpMethod};
+use flavored::RpEndpoint;
+use genco::{Cons, JavaScript, Quoted, Tokens};
+use utils::JsDoc;
+use {Options, Service, ServiceCodegen};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.service.push(Box::new(FetchService::new()));
+        Ok(())
+    }
+}
+
+struct FetchService {}
+
+impl FetchService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ServiceCodegen for FetchService {
+    fn generate(&self, service: Service) -> Result<()> {
+        let Service { body, container } = service;
+
+        let name = Cons::from(format!("{}FetchClient", body.name));
+
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(JsDoc {
+            comment: &body.comment,
+            deprecated: body.deprecated.as_ref(),
+            tags: Vec::new(),
+        });
+
+        push!(t, "export class ", name, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                let url = match body.http.url {
+                    Some(ref url) => toks!["url = ", url.as_str().quoted()],
+                    None => toks!["url"],
+                };
+
+                push!(t, "/**");
+                push!(t, " * @param {string} url");
+                push!(t, " * @param {typeof fetch} fetchFn");
+                push!(t, " */");
+                push!(t, "constructor(", url, ", fetchFn = globalThis.fetch) {");
+                nested!(t, "this.url = url;");
+                nested!(t, "this.fetchFn = fetchFn;");
+                push!(t, "}");
+            });
+
+            for e in &body.endpoints {
+                if !e.has_http_support() {
+                    continue;
+                }
+
+                t.push(self.endpoint(e));
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        container.push(t);
+        Ok(())
+    }
+}
+
+impl FetchService {
+    /// Build a single endpoint method of the client.
+    fn endpoint<'el>(&self, e: &'el RpEndpoint) -> Tokens<'el, JavaScript<'el>> {
+        let mut params = Tokens::new();
+
+        for a in &e.arguments {
+            params.append(a.safe_ident());
+        }
+
+        let mut t = Tokens::new();
+
+        let mut tags = Vec::new();
+
+        for a in &e.arguments {
+            let mut tag = toks!["@param {", a.channel.ty().doc(), "} ", a.safe_ident()];
+
+            if !a.comment.is_empty() {
+                tag.append(" ");
+                tag.append(Cons::from(a.comment.join(" ")));
+            }
+
+            tags.push(tag);
+        }
+
+        t.push_unless_empty(JsDoc {
+            comment: &e.comment,
+            deprecated: e.deprecated.as_ref(),
+            tags: tags,
+        });
+
+        push!(t, "async ", e.safe_ident(), "(", params.join(", "), ") {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "const url = ", self.url(e), ";");
+                push!(t, "const fetchFn = this.fetchFn;");
+            });
+
+            t.push_into(|t| {
+                let method = e.http.method.as_ref().unwrap_or(&RpHttpMethod::Get);
+
+                push!(t, "const res = await fetchFn(url, {");
+                nested!(t, "method: ", method.as_str().quoted(), ",");
+
+                let body = e.http.body.as_ref().or(e.request.as_ref());
+
+                let mut headers = Tokens::new();
+
+                if body.is_some() {
+                    let content_type = e.http.accepts.as_str().quoted();
+                    headers.append(toks!["Content-Type".quoted(), ": ", content_type]);
+                }
+
+                if e.response.is_some() {
+                    let accept = e.http.produces.as_str().quoted();
+                    headers.append(toks!["Accept".quoted(), ": ", accept]);
+                }
+
+                for h in &e.http.headers {
+                    let value = toks!["String(", h.argument.safe_ident(), ")"];
+                    headers.append(toks![h.name.as_str().quoted(), ": ", value]);
+                }
+
+                if !headers.is_empty() {
+                    nested!(t, "headers: {", headers.join(", "), "},");
+                }
+
+                if let Some(body) = body {
+                    match e.http.accepts {
+                        RpAccept::Json => {
+                            let body = body.channel.ty().encode(toks![body.safe_ident()]);
+                            nested!(t, "body: JSON.stringify(", body, "),");
+                        }
+                        RpAccept::Text => {
+                            nested!(t, "body: ", body.safe_ident(), ",");
+                        }
+                    }
+                }
+
+                push!(t, "});");
+            });
+
+            t.push_into(|t| {
+                let error = format!("{}: bad response status: ", e.safe_ident());
+
+                push!(t, "if (!res.ok) {");
+                nested!(t, "throw new Error(", error.quoted(), " + res.status);");
+                push!(t, "}");
+            });
+
+            if let Some(ref response) = e.response {
+                match e.http.produces {
+                    RpAccept::Json => {
+                        let res = response.ty().decode(toks!["await res.json()"]);
+                        push!(t, "return ", res, ";");
+                    }
+                    RpAccept::Text => {
+                        push!(t, "return await res.text();");
+                    }
+                }
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build an expression for the URL of the endpoint, with path variables and query parameters
+    /// encoded.
+    fn url<'el>(&self, e: &'el RpEndpoint) -> Tokens<'el, JavaScript<'el>> {
+        let mut parts = Tokens::new();
+        parts.append("this.url");
+
+        let mut segment = String::new();
+
+        if let Some(ref path) = e.http.path {
+            for step in &path.steps {
+                segment.push('/');
+
+                for part in &step.parts {
+                    match *part {
+                        core::RpPathPart::Variable(ref arg) => {
+                            if !segment.is_empty() {
+                                parts.append(segment.clone().quoted());
+                                segment.clear();
+                            }
+
+                            parts.append(toks!["encodeURIComponent(", arg.safe_ident(), ")"]);
+                        }
+                        core::RpPathPart::Segment(ref s) => {
+                            segment.push_str(s.as_str());
+                        }
+                    }
+                }
+            }
+        }
+
+        if !segment.is_empty() {
+            parts.append(segment.quoted());
+        }
+
+        if !e.http.query.is_empty() {
+            let mut query = Tokens::new();
+
+            for p in &e.http.query {
+                let value = toks!["String(", p.argument.safe_ident(), ")"];
+                query.append(toks![p.name.as_str().quoted(), ": ", value]);
+            }
+
+            parts.append("?".quoted());
+            parts.append(toks!["new URLSearchParams({", query.join(", "), "})"]);
+        }
+
+        parts.join(" + ")
+    }
+}
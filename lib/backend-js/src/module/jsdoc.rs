@@ -0,0 +1,24 @@
+//! JSDoc module for JavaScript.
+//!
+//! Documents generated classes and their constructors using JSDoc.
+
+use backend::Initializer;
+use core::errors::Result;
+use Options;
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.jsdoc = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+mod fetch;
+mod jsdoc;
+mod websocket;
+
+pub use self::fetch::Module as Fetch;
+pub use self::jsdoc::Module as JsDoc;
+pub use self::websocket::Module as WebSocket;
@@ -0,0 +1,676 @@
+//! WebSocket module for JavaScript.
+//!
+//! Generates a client and a server function for every service, which exchange calls as JSON
+//! frames over a WebSocket.
+
+use backend::Initializer;
+use core::errors::Result;
+use core::RpChannel;
+use flavored::RpEndpoint;
+use genco::{Cons, JavaScript, Quoted, Tokens};
+use utils::Deprecated;
+use {Options, Service, ServiceCodegen};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.service.push(Box::new(WebSocketService::new()));
+        Ok(())
+    }
+}
+
+struct WebSocketService {}
+
+impl WebSocketService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ServiceCodegen for WebSocketService {
+    fn generate(&self, service: Service) -> Result<()> {
+        let Service { body, container } = service;
+
+        for e in &body.endpoints {
+            if e.arguments.iter().filter(|a| a.channel.is_streaming()).count() > 1 {
+                return Err(format!(
+                    "{}: at most one streaming argument is supported over websockets",
+                    e.safe_ident()
+                ).into());
+            }
+        }
+
+        let queue = Cons::from(format!("{}WebSocketQueue", body.name));
+        let client = Cons::from(format!("{}WebSocketClient", body.name));
+        let serve = Cons::from(format!("serve{}", body.name));
+
+        container.push(self.queue(queue.clone()));
+        container.push(self.client(client, queue.clone(), &body.endpoints));
+        container.push(self.serve(serve, queue, &body.endpoints));
+
+        Ok(())
+    }
+}
+
+impl WebSocketService {
+    /// Build a queue of values, which can be consumed as an async iterator.
+    fn queue<'el>(&self, name: Cons<'el>) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "class ", name, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "constructor() {");
+                nested!(t, "this.entries = [];");
+                nested!(t, "this.waiting = null;");
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "push(entry) {");
+
+                t.nested_into(|t| {
+                    push!(t, "if (this.waiting !== null) {");
+                    nested!(t, "const waiting = this.waiting;");
+                    nested!(t, "this.waiting = null;");
+                    nested!(t, "waiting(entry);");
+                    push!(t, "} else {");
+                    nested!(t, "this.entries.push(entry);");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "[Symbol.asyncIterator]() {");
+                nested!(t, "return this;");
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "next() {");
+
+                t.nested({
+                    let mut t = Tokens::new();
+
+                    t.push_into(|t| {
+                        push!(t, "const entry = this.entries.length > 0");
+                        nested!(t, "? Promise.resolve(this.entries.shift())");
+                        nested!(t, ": new Promise((resolve) => { this.waiting = resolve; });");
+                    });
+
+                    t.push_into(|t| {
+                        push!(t, "return entry.then((entry) => {");
+
+                        t.nested_into(|t| {
+                            push!(t, "if (entry.error !== undefined) {");
+                            nested!(t, "throw entry.error;");
+                            push!(t, "}");
+                            push!(t, "return entry;");
+                        });
+
+                        push!(t, "});");
+                    });
+
+                    t.join_line_spacing()
+                });
+
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the client class.
+    fn client<'el>(
+        &self,
+        name: Cons<'el>,
+        queue: Cons<'el>,
+        endpoints: &'el [RpEndpoint],
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "export class ", name, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push(self.client_constructor());
+            t.push(self.client_call());
+
+            t.push_into(|t| {
+                push!(t, "_unary(endpoint, args, items, decode) {");
+
+                t.nested_into(|t| {
+                    push!(t, "return new Promise((resolve, reject) => {");
+
+                    t.nested_into(|t| {
+                        push!(t, "this._call(endpoint, args, items, (frame) => {");
+
+                        t.nested({
+                            let mut t = Tokens::new();
+
+                            t.push_into(|t| {
+                                push!(t, "if (frame.error !== undefined) {");
+                                nested!(t, "reject(new Error(frame.error));");
+                                nested!(t, "return;");
+                                push!(t, "}");
+                            });
+
+                            push!(t, "resolve(decode(frame.body));");
+
+                            t.join_line_spacing()
+                        });
+
+                        push!(t, "});");
+                    });
+
+                    push!(t, "});");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "_stream(endpoint, args, items, decode) {");
+
+                t.nested({
+                    let mut t = Tokens::new();
+
+                    push!(t, "const queue = new ", queue, "();");
+
+                    t.push_into(|t| {
+                        push!(t, "this._call(endpoint, args, items, (frame) => {");
+
+                        t.nested({
+                            let mut t = Tokens::new();
+
+                            t.push_into(|t| {
+                                push!(t, "if (frame.error !== undefined) {");
+                                nested!(t, "queue.push({error: new Error(frame.error)});");
+                                nested!(t, "return;");
+                                push!(t, "}");
+                            });
+
+                            t.push_into(|t| {
+                                push!(t, "if (frame.body !== undefined) {");
+                                nested!(t, "queue.push({value: decode(frame.body), done: false});");
+                                push!(t, "}");
+                            });
+
+                            t.push_into(|t| {
+                                push!(t, "if (frame.end) {");
+                                nested!(t, "queue.push({value: undefined, done: true});");
+                                push!(t, "}");
+                            });
+
+                            t.join_line_spacing()
+                        });
+
+                        push!(t, "});");
+                    });
+
+                    push!(t, "return queue;");
+
+                    t.join_line_spacing()
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "async *_items(items, encode) {");
+
+                t.nested_into(|t| {
+                    push!(t, "for await (const v of items) {");
+                    nested!(t, "yield encode(v);");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            for e in endpoints {
+                t.push(self.client_endpoint(e));
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    fn client_constructor<'el>(&self) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "constructor(socket) {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "this.socket = socket;");
+                push!(t, "this.nextId = 0;");
+                push!(t, "this.calls = {};");
+            });
+
+            t.push_into(|t| {
+                push!(t, "socket.addEventListener(", "message".quoted(), ", (event) => {");
+
+                t.nested({
+                    let mut t = Tokens::new();
+
+                    t.push_into(|t| {
+                        push!(t, "const frame = JSON.parse(event.data);");
+                        push!(t, "const call = this.calls[frame.id];");
+                    });
+
+                    t.push_into(|t| {
+                        push!(t, "if (call === undefined) {");
+                        nested!(t, "return;");
+                        push!(t, "}");
+                    });
+
+                    t.push_into(|t| {
+                        push!(t, "if (frame.end) {");
+                        nested!(t, "delete this.calls[frame.id];");
+                        push!(t, "}");
+                    });
+
+                    push!(t, "call(frame);");
+
+                    t.join_line_spacing()
+                });
+
+                push!(t, "});");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    fn client_call<'el>(&self) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "_call(endpoint, args, items, handle) {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "const id = this.nextId++;");
+                push!(t, "this.calls[id] = handle;");
+                push!(
+                    t,
+                    "this.socket.send(JSON.stringify({id: id, endpoint: endpoint, body: args, end: items === null}));"
+                );
+            });
+
+            t.push_into(|t| {
+                push!(t, "if (items !== null) {");
+
+                t.nested_into(|t| {
+                    push!(t, "(async () => {");
+
+                    t.nested({
+                        let mut t = Tokens::new();
+
+                        t.push_into(|t| {
+                            push!(t, "for await (const item of items) {");
+                            nested!(
+                                t,
+                                "this.socket.send(JSON.stringify({id: id, endpoint: endpoint, body: item}));"
+                            );
+                            push!(t, "}");
+                        });
+
+                        push!(
+                            t,
+                            "this.socket.send(JSON.stringify({id: id, endpoint: endpoint, end: true}));"
+                        );
+
+                        t.join_line_spacing()
+                    });
+
+                    push!(t, "})();");
+                });
+
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build a single endpoint method of the client.
+    fn client_endpoint<'el>(&self, e: &'el RpEndpoint) -> Tokens<'el, JavaScript<'el>> {
+        let mut params = Tokens::new();
+        let mut args = Tokens::new();
+        let mut items = toks!["null"];
+
+        for a in &e.arguments {
+            params.append(a.safe_ident());
+
+            match *a.channel {
+                RpChannel::Unary { ref ty } => {
+                    args.append(toks![
+                        a.ident().quoted(),
+                        ": ",
+                        ty.encode(toks![a.safe_ident()])
+                    ]);
+                }
+                RpChannel::Streaming { ref ty } => {
+                    items = toks![
+                        "this._items(",
+                        a.safe_ident(),
+                        ", (v) => ",
+                        ty.encode(toks!["v"]),
+                        ")"
+                    ];
+                }
+            }
+        }
+
+        let (call, decode) = match e.response {
+            Some(ref response) => {
+                let call = if response.is_streaming() {
+                    "this._stream"
+                } else {
+                    "this._unary"
+                };
+
+                (call, toks!["(v) => ", response.ty().decode(toks!["v"])])
+            }
+            None => ("this._unary", toks!["() => undefined"]),
+        };
+
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Deprecated(&e.deprecated));
+        push!(t, e.safe_ident(), "(", params.join(", "), ") {");
+        nested!(
+            t,
+            "return ",
+            call,
+            "(",
+            e.name().quoted(),
+            ", {",
+            args.join(", "),
+            "}, ",
+            items,
+            ", ",
+            decode,
+            ");"
+        );
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the function serving a service over a socket.
+    fn serve<'el>(
+        &self,
+        name: Cons<'el>,
+        queue: Cons<'el>,
+        endpoints: &'el [RpEndpoint],
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "export function ", name, "(service, socket) {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(t, "const streams = {};");
+
+            t.push_into(|t| {
+                push!(t, "function send(frame) {");
+                nested!(t, "socket.send(JSON.stringify(frame));");
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "function fail(frame, e) {");
+                nested!(
+                    t,
+                    "const error = e !== null && e !== undefined && e.message !== undefined ? e.message : String(e);"
+                );
+                nested!(
+                    t,
+                    "send({id: frame.id, endpoint: frame.endpoint, error: error, end: true});"
+                );
+                push!(t, "}");
+            });
+
+            t.push(self.serve_items(queue));
+
+            t.push_into(|t| {
+                push!(t, "async function unary(frame, call, encode) {");
+
+                t.nested_into(|t| {
+                    push!(t, "try {");
+                    nested!(t, "const body = encode(await call());");
+                    nested!(
+                        t,
+                        "send({id: frame.id, endpoint: frame.endpoint, body: body, end: true});"
+                    );
+                    push!(t, "} catch (e) {");
+                    nested!(t, "fail(frame, e);");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "async function stream(frame, call, encode) {");
+
+                t.nested_into(|t| {
+                    push!(t, "try {");
+
+                    t.nested({
+                        let mut t = Tokens::new();
+
+                        t.push_into(|t| {
+                            push!(t, "for await (const v of call()) {");
+                            nested!(
+                                t,
+                                "send({id: frame.id, endpoint: frame.endpoint, body: encode(v)});"
+                            );
+                            push!(t, "}");
+                        });
+
+                        push!(
+                            t,
+                            "send({id: frame.id, endpoint: frame.endpoint, end: true});"
+                        );
+
+                        t.join_line_spacing()
+                    });
+
+                    push!(t, "} catch (e) {");
+                    nested!(t, "fail(frame, e);");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push(self.serve_dispatch(endpoints));
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the function collecting the streamed items of a call.
+    fn serve_items<'el>(&self, queue: Cons<'el>) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "function items(frame, decode) {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(t, "const queue = new ", queue, "();");
+
+            t.push_into(|t| {
+                push!(t, "if (frame.end) {");
+                nested!(t, "queue.push({value: undefined, done: true});");
+                nested!(t, "return queue;");
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "streams[frame.id] = (item) => {");
+
+                t.nested({
+                    let mut t = Tokens::new();
+
+                    t.push_into(|t| {
+                        push!(t, "if (item.body !== undefined) {");
+                        nested!(t, "queue.push({value: decode(item.body), done: false});");
+                        push!(t, "}");
+                    });
+
+                    t.push_into(|t| {
+                        push!(t, "if (item.end) {");
+                        nested!(t, "delete streams[item.id];");
+                        nested!(t, "queue.push({value: undefined, done: true});");
+                        push!(t, "}");
+                    });
+
+                    t.join_line_spacing()
+                });
+
+                push!(t, "};");
+            });
+
+            push!(t, "return queue;");
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the listener dispatching incoming frames to the service.
+    fn serve_dispatch<'el>(&self, endpoints: &'el [RpEndpoint]) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "socket.addEventListener(", "message".quoted(), ", (event) => {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "const frame = JSON.parse(event.data);");
+                push!(t, "const pending = streams[frame.id];");
+            });
+
+            t.push_into(|t| {
+                push!(t, "if (pending !== undefined) {");
+                nested!(t, "pending(frame);");
+                nested!(t, "return;");
+                push!(t, "}");
+            });
+
+            push!(
+                t,
+                "const args = frame.body !== undefined && frame.body !== null ? frame.body : {};"
+            );
+
+            t.push_into(|t| {
+                push!(t, "switch (frame.endpoint) {");
+
+                for e in endpoints {
+                    push!(t, "case ", e.name().quoted(), ":");
+                    nested!(t, self.serve_call(e));
+                    nested!(t, "break;");
+                }
+
+                push!(t, "default:");
+                nested!(
+                    t,
+                    "fail(frame, ",
+                    "no such endpoint: ".quoted(),
+                    " + frame.endpoint);"
+                );
+                nested!(t, "break;");
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "});");
+
+        t
+    }
+
+    /// Build the call to the service for a single endpoint.
+    fn serve_call<'el>(&self, e: &'el RpEndpoint) -> Tokens<'el, JavaScript<'el>> {
+        let mut args = Tokens::new();
+
+        for a in &e.arguments {
+            match *a.channel {
+                RpChannel::Unary { ref ty } => {
+                    args.append(ty.decode(toks!["args[", a.ident().quoted(), "]"]));
+                }
+                RpChannel::Streaming { ref ty } => {
+                    args.append(toks!["items(frame, (v) => ", ty.decode(toks!["v"]), ")"]);
+                }
+            }
+        }
+
+        let call = toks!["() => service.", e.safe_ident(), "(", args.join(", "), ")"];
+
+        let (function, encode) = match e.response {
+            Some(ref response) => {
+                let function = if response.is_streaming() {
+                    "stream"
+                } else {
+                    "unary"
+                };
+
+                (function, toks!["(v) => ", response.ty().encode(toks!["v"])])
+            }
+            None => ("unary", toks!["() => undefined"]),
+        };
+
+        toks![function, "(frame, ", call, ", ", encode, ");"]
+    }
+}
@@ -1,3 +1,6 @@
+use core::RpDeprecated;
+use genco::{IntoTokens, JavaScript, Tokens};
+
 pub fn is_defined<'el, S>(toks: S) -> ::genco::Tokens<'el, ::genco::JavaScript<'el>>
 where
     S: Into<::genco::Tokens<'el, ::genco::JavaScript<'el>>>,
@@ -14,6 +17,72 @@ where
     toks![s.clone(), " === null || ", s, " === undefined"]
 }
 
+/// JSDoc deprecation notice, which is empty unless something is deprecated.
+pub struct Deprecated<'el>(pub &'el Option<RpDeprecated>);
+
+impl<'el> IntoTokens<'el, JavaScript<'el>> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, JavaScript<'el>> {
+        let deprecated = match *self.0 {
+            Some(ref deprecated) => deprecated,
+            None => return Tokens::new(),
+        };
+
+        match deprecated.note() {
+            Some(note) => toks!["/** @deprecated ", note, " */"],
+            None => toks!["/** @deprecated */"],
+        }
+    }
+}
+
+/// JSDoc comment with documentation, tags, and a deprecation notice, which is empty unless there
+/// is something to document.
+pub struct JsDoc<'el> {
+    pub comment: &'el [String],
+    pub deprecated: Option<&'el RpDeprecated>,
+    pub tags: Vec<Tokens<'el, JavaScript<'el>>>,
+}
+
+impl<'el> IntoTokens<'el, JavaScript<'el>> for JsDoc<'el> {
+    fn into_tokens(self) -> Tokens<'el, JavaScript<'el>> {
+        let JsDoc {
+            comment,
+            deprecated,
+            tags,
+        } = self;
+
+        let mut t = Tokens::new();
+
+        if comment.is_empty() && deprecated.is_none() && tags.is_empty() {
+            return t;
+        }
+
+        push!(t, "/**");
+
+        for line in comment {
+            if line.is_empty() {
+                push!(t, " *");
+            } else {
+                push!(t, " * ", line.as_str());
+            }
+        }
+
+        for tag in tags {
+            push!(t, " * ", tag);
+        }
+
+        if let Some(deprecated) = deprecated {
+            match deprecated.note() {
+                Some(note) => push!(t, " * @deprecated ", note),
+                None => push!(t, " * @deprecated"),
+            }
+        }
+
+        push!(t, " */");
+
+        t
+    }
+}
+
 #[macro_export]
 macro_rules! js {
     ([ $arguments:expr ]) => {{
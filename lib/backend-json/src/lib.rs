@@ -8,38 +8,105 @@ extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
 extern crate reproto_trans as trans;
 extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 extern crate serde_json;
 extern crate toml;
 
 use core::errors::*;
-use core::{CoreFlavor, Handle, RelativePathBuf};
-use manifest::{Lang, Manifest, NoModule, TryFromToml};
+use core::flavored::{RpField, RpFile, RpVersionedPackage};
+use core::{CoreFlavor, Handle, Loc, RelativePathBuf, Span};
+use manifest::{checked_modules, Lang, Manifest, ModuleDoc, NoModule, TryFromToml};
 use std::any::Any;
 use std::path::Path;
 use trans::Session;
 
+/// Version of the generated model.
+///
+/// Fields might be added without changing the version, but it is bumped whenever existing fields
+/// are changed or removed.
+pub const MODEL_VERSION: u32 = 1;
+
 #[derive(Clone, Copy, Default, Debug)]
 pub struct JsonLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[ModuleDoc {
+    name: "spans",
+    description: "Include the location of every declaration, field, and endpoint in the model.",
+    options: &[],
+}];
+
 impl Lang for JsonLang {
     lang_base!(JsonModule, compile);
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
 }
 
 #[derive(Debug)]
-pub enum JsonModule {}
+pub enum JsonModule {
+    Spans,
+}
 
 impl TryFromToml for JsonModule {
     fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
-        NoModule::illegal(path, id, value)
+        use self::JsonModule::*;
+
+        let result = match id {
+            "spans" => Spans,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
     }
 
     fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
-        NoModule::illegal(path, id, value)
+        use self::JsonModule::*;
+
+        let result = match id {
+            "spans" => Spans,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
     }
 }
 
-fn compile(handle: &Handle, session: Session<CoreFlavor>, _manifest: Manifest) -> Result<()> {
+/// The model generated for a single package.
+#[derive(Debug, Serialize)]
+struct Model<'a> {
+    /// Version of the model, see `MODEL_VERSION`.
+    model_version: u32,
+    package: &'a RpVersionedPackage,
+    file: &'a RpFile,
+    /// Locations of everything in the specification, if enabled through the `spans` module.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spans: Option<Vec<SpanEntry<'a>>>,
+}
+
+/// Location of a declaration, field, or endpoint in the specification of the package.
+#[derive(Debug, Serialize)]
+struct SpanEntry<'a> {
+    /// Kind of the spanned item, like `type`, `field`, or `endpoint`.
+    kind: String,
+    /// Path to the declaration.
+    path: &'a [String],
+    /// Identifier of fields and endpoints, in the declaration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ident: Option<&'a str>,
+    /// Byte offsets in the specification.
+    span: Span,
+}
+
+fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
     let session = session.translate_default()?;
+    let modules: Vec<JsonModule> = checked_modules(manifest.modules)?;
+
+    let spans = modules.iter().any(|m| match *m {
+        JsonModule::Spans => true,
+    });
 
     let root = RelativePathBuf::from(".");
 
@@ -70,13 +137,78 @@ fn compile(handle: &Handle, session: Session<CoreFlavor>, _manifest: Manifest) -
             path.with_extension("json")
         };
 
+        let model = Model {
+            model_version: MODEL_VERSION,
+            package: package,
+            file: file,
+            spans: if spans { Some(file_spans(file)) } else { None },
+        };
+
         debug!("+file: {}", path.display());
         writeln!(
             handle.create(&path)?,
             "{}",
-            serde_json::to_string_pretty(file)?,
+            serde_json::to_string_pretty(&model)?,
         )?;
     }
 
     Ok(())
 }
+
+/// Collect the locations of everything in the given file.
+fn file_spans(file: &RpFile) -> Vec<SpanEntry> {
+    use core::RpDecl::*;
+
+    let mut out = Vec::new();
+
+    for decl in &file.decls {
+        for (name, span, reg) in decl.to_reg() {
+            out.push(SpanEntry {
+                kind: reg.to_string(),
+                path: &name.path,
+                ident: None,
+                span: span,
+            });
+        }
+    }
+
+    for decl in file.for_each_decl() {
+        let path = &decl.name().path;
+
+        match *decl {
+            Type(ref body) => fields_spans(&mut out, path, &body.fields),
+            Tuple(ref body) => fields_spans(&mut out, path, &body.fields),
+            Interface(ref body) => {
+                fields_spans(&mut out, path, &body.fields);
+
+                for sub_type in &body.sub_types {
+                    fields_spans(&mut out, &sub_type.name.path, &sub_type.fields);
+                }
+            }
+            Service(ref body) => {
+                for endpoint in &body.endpoints {
+                    out.push(SpanEntry {
+                        kind: "endpoint".to_string(),
+                        path: path,
+                        ident: Some(endpoint.ident()),
+                        span: Loc::span(endpoint),
+                    });
+                }
+            }
+            Enum(..) | Union(..) | Newtype(..) => {}
+        }
+    }
+
+    out
+}
+
+fn fields_spans<'a>(out: &mut Vec<SpanEntry<'a>>, path: &'a [String], fields: &'a [Loc<RpField>]) {
+    for field in fields {
+        out.push(SpanEntry {
+            kind: "field".to_string(),
+            path: path,
+            ident: Some(field.ident()),
+            span: Loc::span(field),
+        });
+    }
+}
@@ -0,0 +1,145 @@
+#[macro_use]
+extern crate log;
+#[allow(unused)]
+#[macro_use]
+extern crate reproto_backend as backend;
+extern crate reproto_core as core;
+extern crate reproto_json_schema as json_schema;
+#[macro_use]
+extern crate reproto_manifest as manifest;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate linked_hash_map;
+extern crate serde_json as json;
+extern crate toml;
+
+mod spec;
+
+use core::errors::*;
+use core::{CoreFlavor, Handle, Loc, RelativePathBuf};
+use json_schema::{schema_path, Ids, Queued, SchemaBuilder};
+use manifest::{checked_modules, Lang, Manifest, ModuleDoc, ModuleOption, NoModule, TryFromToml};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::path::Path;
+use trans::Session;
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct JsonSchemaLang;
+
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[ModuleDoc {
+    name: "id",
+    description: "Configure the `$id` of every schema.",
+    options: &[ModuleOption {
+        name: "base",
+        ty: "string",
+        default: None,
+        description: "URL that schemas are published under, defaults to using `urn:reproto:` ids.",
+    }],
+}];
+
+impl Lang for JsonSchemaLang {
+    lang_base!(JsonSchemaModule, compile);
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+}
+
+#[derive(Debug)]
+pub enum JsonSchemaModule {
+    Id(IdConfig),
+}
+
+/// Configure the `$id` of every schema.
+#[derive(Debug, Default, Deserialize)]
+pub struct IdConfig {
+    /// URL that schemas are published under.
+    base: Option<String>,
+}
+
+impl TryFromToml for JsonSchemaModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
+        use self::JsonSchemaModule::*;
+
+        let result = match id {
+            "id" => Id(IdConfig::default()),
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        use self::JsonSchemaModule::*;
+
+        let result = match id {
+            "id" => Id(value.try_into()?),
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+}
+
+fn compile(handle: &Handle, env: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let env = env.translate_default()?;
+    let modules: Vec<JsonSchemaModule> = checked_modules(manifest.modules)?;
+
+    let mut id = IdConfig::default();
+
+    for module in modules {
+        match module {
+            JsonSchemaModule::Id(config) => {
+                id = config;
+            }
+        }
+    }
+
+    let ids = Ids::new(id.base.as_ref().map(|b| b.as_str()));
+
+    for (package, file) in env.for_each_file() {
+        let path = RelativePathBuf::from(schema_path(package)?);
+
+        if let Some(parent) = path.parent() {
+            if !handle.is_dir(parent) {
+                debug!("+dir: {}", parent.display());
+                handle.create_dir_all(parent)?;
+            }
+        }
+
+        let builder = SchemaBuilder::for_package(&env, package, &ids);
+
+        let mut queue = VecDeque::new();
+
+        for decl in file.for_each_decl() {
+            queue.push_back(Queued::Named(Loc::borrow(decl.name())));
+        }
+
+        let document = spec::Document {
+            schema: JSON_SCHEMA_DIALECT,
+            id: ids.id(package)?,
+            title: package.to_string(),
+            description: doc(&file.comment),
+            defs: builder.process_components(queue)?,
+        };
+
+        debug!("+file: {}", path.display());
+        json::to_writer_pretty(handle.create(&path)?, &document)?;
+    }
+
+    Ok(())
+}
+
+fn doc(comment: &[String]) -> Option<String> {
+    if comment.is_empty() {
+        return None;
+    }
+
+    Some(comment.join("\n"))
+}
@@ -0,0 +1,18 @@
+//! Data structures for JSON Schema documents.
+
+use json_schema::spec::Schema;
+use linked_hash_map::LinkedHashMap;
+
+/// A single JSON Schema document, describing every declaration in a package.
+#[derive(Debug, Serialize)]
+pub struct Document<'a> {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    #[serde(rename = "$id")]
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "$defs", skip_serializing_if = "LinkedHashMap::is_empty")]
+    pub defs: LinkedHashMap<String, Schema<'a>>,
+}
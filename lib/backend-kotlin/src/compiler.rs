@@ -0,0 +1,485 @@
+//! Backend for Kotlin
+
+use backend::PackageProcessor;
+use core::errors::*;
+use core::{Handle, RpDeprecated, RpVariantValue};
+use flavored::{
+    KotlinFlavor, KotlinName, KotlinType, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody,
+    RpTupleBody, RpTypeBody,
+};
+use genco::{IntoTokens, Quoted, Tokens};
+use trans::{self, Translated};
+use {
+    EnumAdded, FieldAdded, FileSpec, InterfaceAdded, Kotlin, NewtypeAdded, Options, SubTypeAdded,
+    TupleAdded, TypeAdded, VariantAdded, EXT,
+};
+
+/// Documentation comments, in the style of KDoc.
+pub struct Comments<'el, S: 'el>(pub &'el [S]);
+
+impl<'el, S: 'el + AsRef<str>> IntoTokens<'el, Kotlin> for Comments<'el, S> {
+    fn into_tokens(self) -> Tokens<'el, Kotlin> {
+        let mut t = Tokens::new();
+
+        if self.0.is_empty() {
+            return t;
+        }
+
+        t.push("/**");
+
+        for c in self.0.iter() {
+            let c = c.as_ref();
+
+            if c.is_empty() {
+                t.push(" *");
+            } else {
+                t.push(toks![" * ", c]);
+            }
+        }
+
+        t.push(" */");
+        t
+    }
+}
+
+/// Deprecated annotation, which is empty unless something is deprecated.
+pub struct Deprecated<'el>(pub &'el Option<RpDeprecated>);
+
+impl<'el> IntoTokens<'el, Kotlin> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Kotlin> {
+        match *self.0 {
+            Some(ref deprecated) => {
+                let note = deprecated.note().unwrap_or("Do not use.");
+                toks!["@Deprecated(", note.quoted(), ")"]
+            }
+            None => Tokens::new(),
+        }
+    }
+}
+
+/// How a field is declared in a class.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Property {
+    /// A constructor parameter, like `val foo: Int`.
+    Val,
+    /// A constructor parameter implementing an abstract property, like `override val foo: Int`.
+    Override,
+    /// An abstract property, like `abstract val foo: Int`.
+    Abstract,
+}
+
+pub struct Compiler<'el> {
+    pub env: &'el Translated<KotlinFlavor>,
+    pub options: Options,
+    handle: &'el Handle,
+}
+
+impl<'el> Compiler<'el> {
+    pub fn new(
+        env: &'el Translated<KotlinFlavor>,
+        options: Options,
+        handle: &'el Handle,
+    ) -> Result<Compiler<'el>> {
+        Ok(Compiler {
+            env,
+            options,
+            handle,
+        })
+    }
+
+    /// Build the declaration of a field.
+    fn field(
+        &self,
+        file: &mut FileSpec<'el>,
+        field: &'el RpField,
+        property: Property,
+    ) -> Result<Tokens<'el, Kotlin>> {
+        field.ty.imports(&mut file.imports);
+
+        let mut t = Tokens::new();
+        t.push_unless_empty(Comments(&field.comment));
+        t.push_unless_empty(Deprecated(&field.deprecated));
+
+        let modifier = match property {
+            Property::Val => "val ",
+            Property::Override => "override val ",
+            Property::Abstract => "abstract val ",
+        };
+
+        let mut declaration = toks![modifier, field.safe_ident(), ": ", field.ty.to_string()];
+
+        if field.is_optional() {
+            declaration.append("?");
+
+            // NB: abstract properties can't have default values.
+            if property != Property::Abstract {
+                declaration.append(" = null");
+            }
+        }
+
+        if property == Property::Abstract {
+            t.push(declaration);
+            return Ok(t);
+        }
+
+        let mut annotations = Tokens::new();
+
+        for g in &self.options.field_gens {
+            g.generate(FieldAdded {
+                file: file,
+                annotations: &mut annotations,
+                field: field,
+            })?;
+        }
+
+        t.push_unless_empty(annotations);
+        t.push(declaration);
+        Ok(t)
+    }
+
+    /// Build a class declaration.
+    ///
+    /// Classes with properties are declared as data classes, where every property is a
+    /// constructor parameter.
+    fn class(
+        &self,
+        comment: &'el [String],
+        deprecated: &'el Option<RpDeprecated>,
+        annotations: Tokens<'el, Kotlin>,
+        name: &'el KotlinName,
+        properties: Vec<Tokens<'el, Kotlin>>,
+        extends: Option<&'el KotlinName>,
+        members: Tokens<'el, Kotlin>,
+    ) -> Tokens<'el, Kotlin> {
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(comment));
+        t.push_unless_empty(Deprecated(deprecated));
+        t.push_unless_empty(annotations);
+
+        let mut close = Tokens::new();
+
+        if properties.is_empty() {
+            close.append(toks!["class ", name]);
+        } else {
+            let mut params = Tokens::new();
+
+            for mut p in properties {
+                p.append(",");
+                params.push(p);
+            }
+
+            push!(t, "data class ", name, "(");
+            t.nested(params);
+
+            close.append(")");
+        }
+
+        if let Some(extends) = extends {
+            close.append(toks![" : ", extends, "()"]);
+        }
+
+        if members.is_empty() {
+            t.push(close);
+        } else {
+            push!(t, close, " {");
+            t.nested(members.join_line_spacing());
+            push!(t, "}");
+        }
+
+        t
+    }
+
+    pub fn compile(&self) -> Result<()> {
+        let files = self.populate_files()?;
+        self.write_files(files)
+    }
+}
+
+impl<'el> PackageProcessor<'el, KotlinFlavor, KotlinName> for Compiler<'el> {
+    type Out = FileSpec<'el>;
+    type DeclIter = trans::translated::DeclIter<'el, KotlinFlavor>;
+
+    fn ext(&self) -> &str {
+        EXT
+    }
+
+    fn decl_iter(&self) -> Self::DeclIter {
+        self.env.decl_iter()
+    }
+
+    fn handle(&self) -> &'el Handle {
+        self.handle
+    }
+
+    fn process_type(&self, out: &mut Self::Out, body: &'el RpTypeBody) -> Result<()> {
+        let mut properties = Vec::new();
+
+        for f in &body.fields {
+            properties.push(self.field(out, f, Property::Val)?);
+        }
+
+        let mut annotations = Tokens::new();
+        let mut members = Tokens::new();
+
+        for g in &self.options.type_gens {
+            g.generate(TypeAdded {
+                file: out,
+                annotations: &mut annotations,
+                members: &mut members,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        out.body.push(self.class(
+            &body.comment,
+            &body.deprecated,
+            annotations,
+            &body.name,
+            properties,
+            None,
+            members,
+        ));
+
+        Ok(())
+    }
+
+    fn process_tuple(&self, out: &mut Self::Out, body: &'el RpTupleBody) -> Result<()> {
+        let mut properties = Vec::new();
+
+        for f in &body.fields {
+            properties.push(self.field(out, f, Property::Val)?);
+        }
+
+        let mut annotations = Tokens::new();
+        let mut members = Tokens::new();
+
+        for g in &self.options.tuple_gens {
+            g.generate(TupleAdded {
+                file: out,
+                annotations: &mut annotations,
+                members: &mut members,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        out.body.push(self.class(
+            &body.comment,
+            &body.deprecated,
+            annotations,
+            &body.name,
+            properties,
+            None,
+            members,
+        ));
+
+        Ok(())
+    }
+
+    fn process_enum(&self, out: &mut Self::Out, body: &'el RpEnumBody) -> Result<()> {
+        body.enum_type.imports(&mut out.imports);
+
+        let mut variants = Vec::new();
+
+        for v in body.variants.iter() {
+            let value = match v.value {
+                RpVariantValue::String(string) => toks![string.quoted()],
+                RpVariantValue::Number(number) => {
+                    let suffix = match body.enum_type {
+                        KotlinType::Simple("Long") => "L",
+                        KotlinType::Simple("UInt") => "u",
+                        KotlinType::Simple("ULong") => "uL",
+                        _ => "",
+                    };
+
+                    toks![number.to_string(), suffix]
+                }
+            };
+
+            let mut annotations = Tokens::new();
+
+            for g in &self.options.variant_gens {
+                g.generate(VariantAdded {
+                    file: out,
+                    annotations: &mut annotations,
+                    variant: v,
+                })?;
+            }
+
+            let mut t = Tokens::new();
+            t.push_unless_empty(Comments(v.comment));
+            t.push_unless_empty(Deprecated(v.deprecated));
+            t.push_unless_empty(annotations);
+            t.push(toks![v.ident(), "(", value, ")"]);
+            variants.push(t);
+        }
+
+        let mut annotations = Tokens::new();
+        let mut members = Tokens::new();
+
+        for g in &self.options.enum_gens {
+            g.generate(EnumAdded {
+                file: out,
+                annotations: &mut annotations,
+                members: &mut members,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        let mut entries = Tokens::new();
+        let last = variants.len().saturating_sub(1);
+
+        for (i, mut v) in variants.into_iter().enumerate() {
+            v.append(if i == last { ";" } else { "," });
+            entries.push(v);
+        }
+
+        let mut inner = Tokens::new();
+        inner.push(entries);
+        inner.push_unless_empty(members.join_line_spacing());
+
+        let mut t = Tokens::new();
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+        t.push_unless_empty(annotations);
+
+        push!(
+            t,
+            "enum class ",
+            &body.name,
+            "(val value: ",
+            body.enum_type.to_string(),
+            ") {"
+        );
+
+        t.nested(inner.join_line_spacing());
+        push!(t, "}");
+
+        out.body.push(t);
+        Ok(())
+    }
+
+    fn process_interface(&self, out: &mut Self::Out, body: &'el RpInterfaceBody) -> Result<()> {
+        let mut properties = Tokens::new();
+
+        for f in &body.fields {
+            properties.push(self.field(out, f, Property::Abstract)?);
+        }
+
+        let mut annotations = Tokens::new();
+        let mut members = Tokens::new();
+
+        for g in &self.options.interface_gens {
+            g.generate(InterfaceAdded {
+                file: out,
+                annotations: &mut annotations,
+                members: &mut members,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        let mut inner = Tokens::new();
+        inner.push_unless_empty(properties);
+        inner.push_unless_empty(members.join_line_spacing());
+
+        let mut t = Tokens::new();
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+        t.push_unless_empty(annotations);
+
+        if inner.is_empty() {
+            push!(t, "sealed class ", &body.name);
+        } else {
+            push!(t, "sealed class ", &body.name, " {");
+            t.nested(inner.join_line_spacing());
+            push!(t, "}");
+        }
+
+        out.body.push(t);
+
+        for s in &body.sub_types {
+            let mut properties = Vec::new();
+
+            for f in &body.fields {
+                properties.push(self.field(out, f, Property::Override)?);
+            }
+
+            for f in &s.fields {
+                properties.push(self.field(out, f, Property::Val)?);
+            }
+
+            let mut annotations = Tokens::new();
+            let mut members = Tokens::new();
+
+            for g in &self.options.sub_type_gens {
+                g.generate(SubTypeAdded {
+                    file: out,
+                    annotations: &mut annotations,
+                    members: &mut members,
+                    name: &s.name,
+                    interface: body,
+                    sub_type: s,
+                })?;
+            }
+
+            out.body.push(self.class(
+                &s.comment,
+                &s.deprecated,
+                annotations,
+                &s.name,
+                properties,
+                Some(&body.name),
+                members,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn process_newtype(&self, out: &mut Self::Out, body: &'el RpNewtypeBody) -> Result<()> {
+        body.ty.imports(&mut out.imports);
+        out.imports.insert("kotlin.jvm.JvmInline".to_string());
+
+        let mut annotations = Tokens::new();
+        let mut members = Tokens::new();
+
+        for g in &self.options.newtype_gens {
+            g.generate(NewtypeAdded {
+                file: out,
+                annotations: &mut annotations,
+                members: &mut members,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        let mut t = Tokens::new();
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+        t.push_unless_empty(annotations);
+        push!(t, "@JvmInline");
+
+        let declaration = toks![
+            "value class ",
+            &body.name,
+            "(val value: ",
+            body.ty.to_string(),
+            ")"
+        ];
+
+        if members.is_empty() {
+            t.push(declaration);
+        } else {
+            push!(t, declaration, " {");
+            t.nested(members.join_line_spacing());
+            push!(t, "}");
+        }
+
+        out.body.push(t);
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! Kotlin flavor.
+
+#![allow(unused)]
+
+use backend::package_processor;
+use core::errors::Result;
+use core::{
+    self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberKind,
+    RpNumberType, RpScalarType, RpStringType, Translate, Translator,
+};
+use genco::Element;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::rc::Rc;
+use trans::Packages;
+use {Kotlin, TYPE_SEP};
+
+/// A Kotlin type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KotlinType {
+    /// A type which is always available, like `String` or `Int`.
+    Simple(&'static str),
+    /// A type which has to be imported, like `kotlinx.datetime.Instant`.
+    Imported {
+        package: &'static str,
+        name: &'static str,
+    },
+    /// A declared type, qualified by its package if it is declared in another package.
+    Name {
+        package: Option<Rc<String>>,
+        name: Rc<String>,
+    },
+    /// A list, like `List<Int>`.
+    List(Box<KotlinType>),
+    /// A set, like `Set<Int>`.
+    Set(Box<KotlinType>),
+    /// A map, like `Map<String, Int>`.
+    Map(Box<KotlinType>, Box<KotlinType>),
+}
+
+impl KotlinType {
+    /// Collect all imports which are needed to use this type.
+    pub fn imports(&self, out: &mut BTreeSet<String>) {
+        use self::KotlinType::*;
+
+        match *self {
+            Simple(_) | Name { .. } => {}
+            Imported { package, name } => {
+                out.insert(format!("{}.{}", package, name));
+            }
+            List(ref argument) | Set(ref argument) => {
+                argument.imports(out);
+            }
+            Map(ref key, ref value) => {
+                key.imports(out);
+                value.imports(out);
+            }
+        }
+    }
+}
+
+impl fmt::Display for KotlinType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::KotlinType::*;
+
+        match *self {
+            Simple(name) => fmt.write_str(name),
+            Imported { name, .. } => fmt.write_str(name),
+            Name {
+                ref package,
+                ref name,
+            } => {
+                if let Some(ref package) = *package {
+                    write!(fmt, "{}.", package)?;
+                }
+
+                fmt.write_str(name.as_str())
+            }
+            List(ref argument) => write!(fmt, "List<{}>", argument),
+            Set(ref argument) => write!(fmt, "Set<{}>", argument),
+            Map(ref key, ref value) => write!(fmt, "Map<{}, {}>", key, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KotlinFlavor;
+
+impl Flavor for KotlinFlavor {
+    type Type = KotlinType;
+    type Name = KotlinName;
+    type Field = RpField;
+    type Endpoint = RpEndpoint;
+    type Package = RpPackage;
+    type EnumType = KotlinType;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KotlinName {
+    pub name: Rc<String>,
+    pub package: RpPackage,
+}
+
+impl fmt::Display for KotlinName {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.name.as_str())
+    }
+}
+
+impl<'el> From<&'el KotlinName> for Element<'el, Kotlin> {
+    fn from(value: &'el KotlinName) -> Element<'el, Kotlin> {
+        Element::Literal(value.name.clone().into())
+    }
+}
+
+impl package_processor::Name<KotlinFlavor> for KotlinName {
+    fn package(&self) -> &RpPackage {
+        &self.package
+    }
+}
+
+/// Responsible for translating RpType -> Kotlin type.
+pub struct KotlinFlavorTranslator {
+    package_translator: Rc<Packages>,
+}
+
+impl KotlinFlavorTranslator {
+    pub fn new(package_translator: Rc<Packages>) -> Self {
+        Self { package_translator }
+    }
+}
+
+impl FlavorTranslator for KotlinFlavorTranslator {
+    type Source = CoreFlavor;
+    type Target = KotlinFlavor;
+
+    translator_defaults!(Self, field, endpoint);
+
+    fn translate_number(&self, number: RpNumberType) -> Result<KotlinType> {
+        let ty = match number.kind {
+            RpNumberKind::U32 => "UInt",
+            RpNumberKind::U64 => "ULong",
+            RpNumberKind::I32 => "Int",
+            RpNumberKind::I64 => "Long",
+            ty => return Err(format!("unsupported number type: {}", ty).into()),
+        };
+
+        Ok(KotlinType::Simple(ty))
+    }
+
+    fn translate_float(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Simple("Float"))
+    }
+
+    fn translate_double(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Simple("Double"))
+    }
+
+    fn translate_boolean(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Simple("Boolean"))
+    }
+
+    fn translate_string(&self, _: RpStringType) -> Result<KotlinType> {
+        Ok(KotlinType::Simple("String"))
+    }
+
+    fn translate_datetime(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Imported {
+            package: "kotlinx.datetime",
+            name: "Instant",
+        })
+    }
+
+    fn translate_date(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Imported {
+            package: "kotlinx.datetime",
+            name: "LocalDate",
+        })
+    }
+
+    fn translate_time(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Imported {
+            package: "kotlinx.datetime",
+            name: "LocalTime",
+        })
+    }
+
+    fn translate_duration(&self) -> Result<KotlinType> {
+        // NB: encoded as an ISO-8601 string by the serializer in kotlinx.serialization.
+        Ok(KotlinType::Imported {
+            package: "kotlin.time",
+            name: "Duration",
+        })
+    }
+
+    fn translate_array(&self, argument: KotlinType) -> Result<KotlinType> {
+        Ok(KotlinType::List(Box::new(argument)))
+    }
+
+    fn translate_set(&self, argument: KotlinType) -> Result<KotlinType> {
+        Ok(KotlinType::Set(Box::new(argument)))
+    }
+
+    fn translate_map(&self, key: KotlinType, value: KotlinType) -> Result<KotlinType> {
+        Ok(KotlinType::Map(Box::new(key), Box::new(value)))
+    }
+
+    fn translate_any(&self) -> Result<KotlinType> {
+        Ok(KotlinType::Imported {
+            package: "kotlinx.serialization.json",
+            name: "JsonElement",
+        })
+    }
+
+    fn translate_bytes(&self) -> Result<KotlinType> {
+        // NB: bytes are encoded as base64 strings.
+        Ok(KotlinType::Simple("String"))
+    }
+
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<KotlinType> {
+        let ty = match scalar.mapping("kotlin") {
+            Some(ty) => ty,
+            None => return self.translate_scalar_base(scalar.base),
+        };
+
+        let (package, name) = match ty.rfind('.') {
+            Some(index) => (Some(Rc::new(ty[..index].to_string())), &ty[index + 1..]),
+            None => (None, ty),
+        };
+
+        Ok(KotlinType::Name {
+            package,
+            name: Rc::new(name.to_string()),
+        })
+    }
+
+    fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<KotlinType> {
+        let ident = Rc::new(reg.ident(&name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP)));
+
+        // imported
+        if let Some(_) = name.prefix {
+            return Ok(KotlinType::Name {
+                package: Some(Rc::new(name.package.join("."))),
+                name: ident,
+            });
+        }
+
+        // same package
+        return Ok(KotlinType::Name {
+            package: None,
+            name: ident,
+        });
+    }
+
+    fn translate_local_name<T>(
+        &self,
+        translator: &T,
+        diag: &mut Diagnostics,
+        reg: RpReg,
+        name: Loc<core::RpName<CoreFlavor>>,
+    ) -> Result<KotlinName>
+    where
+        T: Translator<Source = Self::Source, Target = Self::Target>,
+    {
+        let (name, _) = Loc::take_pair(name);
+
+        let ident = reg.ident(&name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP));
+        let package = self.translate_package(name.package)?;
+
+        Ok(KotlinName {
+            name: Rc::new(ident),
+            package: package,
+        })
+    }
+
+    fn translate_package(&self, source: RpVersionedPackage) -> Result<RpPackage> {
+        self.package_translator.translate_package(source)
+    }
+
+    fn translate_enum_type<T>(
+        &self,
+        translator: &T,
+        diag: &mut Diagnostics,
+        enum_type: core::RpEnumType,
+    ) -> Result<KotlinType>
+    where
+        T: Translator<Source = Self::Source, Target = Self::Target>,
+    {
+        use core::RpEnumType::*;
+
+        match enum_type {
+            String(string) => self.translate_string(string),
+            Number(number) => self.translate_number(number),
+        }
+    }
+}
+
+decl_flavor!(KotlinFlavor, core);
@@ -0,0 +1,377 @@
+#[macro_use]
+extern crate genco;
+#[macro_use]
+extern crate log;
+extern crate reproto_backend as backend;
+#[macro_use]
+extern crate reproto_core as core;
+#[macro_use]
+extern crate reproto_manifest as manifest;
+extern crate reproto_naming as naming;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[allow(unused)]
+#[macro_use]
+extern crate serde_derive;
+extern crate toml;
+
+mod compiler;
+mod flavored;
+mod module;
+
+use backend::{Initializer, IntoBytes};
+use compiler::Compiler;
+use core::errors::Result;
+use core::{CoreFlavor, Handle};
+use flavored::{
+    KotlinName, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpPackage, RpSubType,
+    RpTupleBody, RpTypeBody, RpVariantRef,
+};
+use genco::{Custom, Formatter, Tokens};
+use manifest::{Lang, Manifest, ModuleDoc, NoModule, TryFromToml};
+use naming::{Keywords, Naming};
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+use trans::Session;
+
+const TYPE_SEP: &str = "_";
+const EXT: &str = "kt";
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct KotlinLang;
+
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[ModuleDoc {
+    name: "kotlinx_serialization",
+    description: "Implement serialization using `kotlinx.serialization`, which works in Kotlin \
+                  Multiplatform projects.",
+    options: &[],
+}];
+
+impl Lang for KotlinLang {
+    lang_base!(KotlinModule, compile);
+
+    fn comment(&self, input: &str) -> Option<String> {
+        Some(format!("// {}", input))
+    }
+
+    fn field_ident_naming(&self) -> Option<Box<Naming>> {
+        Some(Box::new(naming::to_lower_camel()))
+    }
+
+    fn keywords(&self) -> Option<&'static Keywords> {
+        Some(&naming::keywords::KOTLIN)
+    }
+
+    fn numbers_as_strings(&self) -> bool {
+        false
+    }
+
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+}
+
+#[derive(Debug)]
+pub enum KotlinModule {
+    KotlinxSerialization,
+}
+
+impl TryFromToml for KotlinModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> Result<Self> {
+        use self::KotlinModule::*;
+
+        let result = match id {
+            "kotlinx_serialization" => KotlinxSerialization,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        use self::KotlinModule::*;
+
+        let result = match id {
+            "kotlinx_serialization" => KotlinxSerialization,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+}
+
+pub struct Options {
+    pub field_gens: Vec<Box<FieldCodegen>>,
+    pub type_gens: Vec<Box<TypeCodegen>>,
+    pub tuple_gens: Vec<Box<TupleCodegen>>,
+    pub enum_gens: Vec<Box<EnumCodegen>>,
+    pub variant_gens: Vec<Box<VariantCodegen>>,
+    pub interface_gens: Vec<Box<InterfaceCodegen>>,
+    pub sub_type_gens: Vec<Box<SubTypeCodegen>>,
+    pub newtype_gens: Vec<Box<NewtypeCodegen>>,
+}
+
+impl Options {
+    pub fn new() -> Options {
+        Options {
+            field_gens: Vec::new(),
+            type_gens: Vec::new(),
+            tuple_gens: Vec::new(),
+            enum_gens: Vec::new(),
+            variant_gens: Vec::new(),
+            interface_gens: Vec::new(),
+            sub_type_gens: Vec::new(),
+            newtype_gens: Vec::new(),
+        }
+    }
+}
+
+pub fn options(modules: Vec<KotlinModule>) -> Result<Options> {
+    use self::KotlinModule::*;
+
+    let mut options = Options::new();
+
+    for m in modules {
+        debug!("+module: {:?}", m);
+
+        let initializer: Box<Initializer<Options = Options>> = match m {
+            KotlinxSerialization => Box::new(module::KotlinxSerialization::new()),
+        };
+
+        initializer.initialize(&mut options)?;
+    }
+
+    Ok(options)
+}
+
+/// Kotlin language support for genco.
+#[derive(Clone)]
+pub enum Kotlin {}
+
+impl Custom for Kotlin {
+    type Extra = ();
+
+    fn quote_string(out: &mut Formatter, input: &str) -> fmt::Result {
+        out.write_char('"')?;
+
+        for c in input.chars() {
+            match c {
+                '\t' => out.write_str("\\t")?,
+                '\n' => out.write_str("\\n")?,
+                '\r' => out.write_str("\\r")?,
+                '"' => out.write_str("\\\"")?,
+                '\\' => out.write_str("\\\\")?,
+                '$' => out.write_str("\\$")?,
+                c => out.write_char(c)?,
+            }
+        }
+
+        out.write_char('"')?;
+
+        Ok(())
+    }
+}
+
+pub struct FileSpec<'el> {
+    /// Imports which are needed by the declarations in the file.
+    pub imports: BTreeSet<String>,
+    pub body: Tokens<'el, Kotlin>,
+    /// Registrations of polymorphic types, which are collected into a `SerializersModule` for the
+    /// package.
+    pub polymorphic: Tokens<'el, Kotlin>,
+}
+
+impl<'el> Default for FileSpec<'el> {
+    fn default() -> Self {
+        FileSpec {
+            imports: BTreeSet::new(),
+            body: Tokens::new(),
+            polymorphic: Tokens::new(),
+        }
+    }
+}
+
+impl<'el> IntoBytes<Compiler<'el>> for FileSpec<'el> {
+    fn into_bytes(self, _: &Compiler<'el>, package: &RpPackage) -> Result<Vec<u8>> {
+        let mut imports = self.imports;
+        let mut t = Tokens::new();
+
+        t.push(toks!["package ", package.join(".")]);
+
+        let polymorphic = if !self.polymorphic.is_empty() {
+            imports.insert("kotlinx.serialization.modules.SerializersModule".to_string());
+            imports.insert("kotlinx.serialization.modules.polymorphic".to_string());
+            imports.insert("kotlinx.serialization.modules.subclass".to_string());
+
+            let mut t = Tokens::new();
+            push!(t, "/**");
+            push!(
+                t,
+                " * Serializers for the polymorphic types in this package."
+            );
+            push!(t, " */");
+            push!(
+                t,
+                "val serializersModule: SerializersModule = SerializersModule {"
+            );
+            t.nested(self.polymorphic);
+            push!(t, "}");
+            Some(t)
+        } else {
+            None
+        };
+
+        t.push_unless_empty({
+            let mut t = Tokens::new();
+
+            for i in imports {
+                t.push(toks!["import ", i]);
+            }
+
+            t
+        });
+
+        t.push_unless_empty(self.body.join_line_spacing());
+
+        if let Some(polymorphic) = polymorphic {
+            t.push(polymorphic);
+        }
+
+        let out = t.join_line_spacing().to_file()?;
+        Ok(out.into_bytes())
+    }
+}
+
+/// Build codegen hooks.
+macro_rules! codegen {
+    ($c:tt, $e:ty) => {
+        pub trait $c {
+            fn generate(&self, e: $e) -> Result<()>;
+        }
+
+        impl<T> $c for Rc<T>
+        where
+            T: $c,
+        {
+            fn generate(&self, e: $e) -> Result<()> {
+                self.as_ref().generate(e)
+            }
+        }
+    };
+}
+
+/// Event emitted when a field is added to a class.
+pub struct FieldAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the field.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    pub field: &'el RpField,
+}
+
+codegen!(FieldCodegen, FieldAdded);
+
+/// Event emitted when a type has been added.
+pub struct TypeAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the class.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    /// Members of the class.
+    pub members: &'a mut Tokens<'el, Kotlin>,
+    pub name: &'el KotlinName,
+    pub body: &'el RpTypeBody,
+}
+
+codegen!(TypeCodegen, TypeAdded);
+
+/// Event emitted when a tuple has been added.
+pub struct TupleAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the class.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    /// Members of the class.
+    pub members: &'a mut Tokens<'el, Kotlin>,
+    pub name: &'el KotlinName,
+    pub body: &'el RpTupleBody,
+}
+
+codegen!(TupleCodegen, TupleAdded);
+
+/// Event emitted when an enum has been added.
+pub struct EnumAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the enum class.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    /// Members of the enum class.
+    pub members: &'a mut Tokens<'el, Kotlin>,
+    pub name: &'el KotlinName,
+    pub body: &'el RpEnumBody,
+}
+
+codegen!(EnumCodegen, EnumAdded);
+
+/// Event emitted when an enum variant is added.
+pub struct VariantAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the enum entry.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    pub variant: RpVariantRef<'el>,
+}
+
+codegen!(VariantCodegen, VariantAdded);
+
+/// Event emitted when an interface has been added.
+pub struct InterfaceAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the sealed class.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    /// Members of the sealed class.
+    pub members: &'a mut Tokens<'el, Kotlin>,
+    pub name: &'el KotlinName,
+    pub body: &'el RpInterfaceBody,
+}
+
+codegen!(InterfaceCodegen, InterfaceAdded);
+
+/// Event emitted when a sub-type of an interface has been added.
+pub struct SubTypeAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the class.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    /// Members of the class.
+    pub members: &'a mut Tokens<'el, Kotlin>,
+    pub name: &'el KotlinName,
+    pub interface: &'el RpInterfaceBody,
+    pub sub_type: &'el RpSubType,
+}
+
+codegen!(SubTypeCodegen, SubTypeAdded);
+
+/// Event emitted when a newtype has been added.
+pub struct NewtypeAdded<'a, 'el: 'a> {
+    pub file: &'a mut FileSpec<'el>,
+    /// Annotations of the value class.
+    pub annotations: &'a mut Tokens<'el, Kotlin>,
+    /// Members of the value class.
+    pub members: &'a mut Tokens<'el, Kotlin>,
+    pub name: &'el KotlinName,
+    pub body: &'el RpNewtypeBody,
+}
+
+codegen!(NewtypeCodegen, NewtypeAdded);
+
+fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let modules = manifest::checked_modules(manifest.modules)?;
+    let options = options(modules)?;
+
+    let packages = session.packages()?;
+
+    let translator = session.translator(flavored::KotlinFlavorTranslator::new(packages))?;
+
+    let session = session.translate(translator)?;
+
+    Compiler::new(&session, options, handle)?.compile()
+}
@@ -0,0 +1,515 @@
+//! Module that implements serialization using `kotlinx.serialization`.
+
+use backend::Initializer;
+use core::errors::Result;
+use core::{Loc, RpSubTypeStrategy, RpVariantValue};
+use flavored::{KotlinName, KotlinType, RpField, RpInterfaceBody};
+use genco::{Quoted, Tokens};
+use std::rc::Rc;
+use {
+    EnumAdded, EnumCodegen, FieldAdded, FieldCodegen, FileSpec, InterfaceAdded, InterfaceCodegen,
+    Kotlin, NewtypeAdded, NewtypeCodegen, Options, SubTypeAdded, SubTypeCodegen, TupleAdded,
+    TupleCodegen, TypeAdded, TypeCodegen, VariantAdded, VariantCodegen,
+};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        let codegen = Rc::new(Codegen::new());
+        options.field_gens.push(Box::new(codegen.clone()));
+        options.type_gens.push(Box::new(codegen.clone()));
+        options.tuple_gens.push(Box::new(codegen.clone()));
+        options.enum_gens.push(Box::new(codegen.clone()));
+        options.variant_gens.push(Box::new(codegen.clone()));
+        options.interface_gens.push(Box::new(codegen.clone()));
+        options.sub_type_gens.push(Box::new(codegen.clone()));
+        options.newtype_gens.push(Box::new(codegen.clone()));
+        Ok(())
+    }
+}
+
+struct Codegen {}
+
+impl Codegen {
+    pub fn new() -> Codegen {
+        Codegen {}
+    }
+
+    /// Import the given names from `kotlinx.serialization`.
+    fn import(file: &mut FileSpec, names: &[&str]) {
+        for name in names {
+            file.imports
+                .insert(format!("kotlinx.serialization.{}", name));
+        }
+    }
+
+    /// Annotation which uses the nested `Serializer` object of the given class.
+    fn serializable_with<'el>(name: &'el KotlinName) -> Tokens<'el, Kotlin> {
+        toks!["@Serializable(with = ", name, ".Serializer::class)"]
+    }
+
+    /// Fully qualified name of the given class, used as the name of serial descriptors.
+    fn serial_name(name: &KotlinName) -> Rc<String> {
+        Rc::new(format!("{}.{}", name.package.join("."), name.name))
+    }
+
+    /// Type of the given field, as used in explicit type arguments.
+    fn field_type(field: &RpField) -> String {
+        if field.is_optional() {
+            format!("{}?", field.ty)
+        } else {
+            field.ty.to_string()
+        }
+    }
+
+    /// Serializer of a tuple, which is encoded as a JSON array.
+    fn tuple_serializer<'el>(
+        name: &'el KotlinName,
+        fields: &[&'el RpField],
+    ) -> Tokens<'el, Kotlin> {
+        let mut t = Tokens::new();
+
+        push!(t, "object Serializer : KSerializer<", name, "> {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "override val descriptor: SerialDescriptor = JsonArray.serializer().descriptor"
+            );
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "override fun serialize(encoder: Encoder, value: ",
+                    name,
+                    ") {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "val json = (encoder as JsonEncoder).json");
+                    push!(t, "encoder.encodeJsonElement(JsonArray(listOf(");
+
+                    t.nested_into(|t| {
+                        for &f in fields {
+                            push!(
+                                t,
+                                "json.encodeToJsonElement<",
+                                Self::field_type(f),
+                                ">(value.",
+                                f.safe_ident(),
+                                "),"
+                            );
+                        }
+                    });
+
+                    push!(t, ")))");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "override fun deserialize(decoder: Decoder): ",
+                    name,
+                    " {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "val json = (decoder as JsonDecoder).json");
+                    push!(t, "val array = decoder.decodeJsonElement().jsonArray");
+                    push!(t, "return ", name, "(");
+
+                    t.nested_into(|t| {
+                        for (i, &f) in fields.iter().enumerate() {
+                            let ty = Self::field_type(f);
+
+                            if f.is_optional() {
+                                push!(
+                                    t,
+                                    "array.getOrNull(",
+                                    i.to_string(),
+                                    ")?.let { json.decodeFromJsonElement<",
+                                    ty,
+                                    ">(it) },"
+                                );
+                            } else {
+                                push!(
+                                    t,
+                                    "json.decodeFromJsonElement<",
+                                    ty,
+                                    ">(array[",
+                                    i.to_string(),
+                                    "]),"
+                                );
+                            }
+                        }
+                    });
+
+                    push!(t, ")");
+                });
+
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+        t
+    }
+
+    /// Serializer of an enum, which is encoded as the number value of each variant.
+    fn number_enum_serializer<'el>(name: &'el KotlinName) -> Tokens<'el, Kotlin> {
+        let mut t = Tokens::new();
+
+        push!(t, "object Serializer : KSerializer<", name, "> {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "override val descriptor: SerialDescriptor =");
+                nested!(
+                    t,
+                    "PrimitiveSerialDescriptor(",
+                    Self::serial_name(name).quoted(),
+                    ", PrimitiveKind.LONG)"
+                );
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "override fun serialize(encoder: Encoder, value: ",
+                    name,
+                    ") {"
+                );
+                nested!(t, "encoder.encodeLong(value.value.toLong())");
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "override fun deserialize(decoder: Decoder): ",
+                    name,
+                    " {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "val value = decoder.decodeLong()");
+                    push!(
+                        t,
+                        "return ",
+                        name,
+                        ".values().firstOrNull { it.value.toLong() == value }"
+                    );
+                    nested!(
+                        t,
+                        "?: throw SerializationException(\"unknown variant: $value\")"
+                    );
+                });
+
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+        t
+    }
+
+    /// Serializer of an untagged interface, which picks the sub-type based on which fields are
+    /// present.
+    fn untagged_serializer<'el>(
+        name: &'el KotlinName,
+        body: &'el RpInterfaceBody,
+    ) -> Tokens<'el, Kotlin> {
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "object Serializer : JsonContentPolymorphicSerializer<",
+            name,
+            ">(",
+            name,
+            "::class) {"
+        );
+
+        t.nested_into(|t| {
+            push!(
+                t,
+                "override fun selectDeserializer(element: JsonElement): DeserializationStrategy<",
+                name,
+                "> {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "val keys = element.jsonObject.keys");
+                push!(t, "return when {");
+
+                t.nested_into(|t| {
+                    for s in &body.sub_types {
+                        let mut keys = Tokens::new();
+
+                        for f in s.discriminating_fields() {
+                            keys.append(f.name().quoted());
+                        }
+
+                        let condition = if keys.is_empty() {
+                            toks!["true"]
+                        } else {
+                            toks!["keys.containsAll(listOf(", keys.join(", "), "))"]
+                        };
+
+                        push!(t, condition, " -> ", &s.name, ".serializer()");
+                    }
+
+                    push!(
+                        t,
+                        "else -> throw SerializationException(",
+                        "no matching sub-type".quoted(),
+                        ")"
+                    );
+                });
+
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+        });
+
+        push!(t, "}");
+        t
+    }
+}
+
+impl FieldCodegen for Codegen {
+    fn generate(&self, e: FieldAdded) -> Result<()> {
+        let FieldAdded {
+            file,
+            annotations,
+            field,
+        } = e;
+
+        if field.name() != field.safe_ident() {
+            Self::import(file, &["SerialName"]);
+            annotations.push(toks!["@SerialName(", field.name().quoted(), ")"]);
+        }
+
+        Ok(())
+    }
+}
+
+impl TypeCodegen for Codegen {
+    fn generate(&self, e: TypeAdded) -> Result<()> {
+        let TypeAdded {
+            file, annotations, ..
+        } = e;
+
+        Self::import(file, &["Serializable"]);
+        annotations.push("@Serializable");
+        Ok(())
+    }
+}
+
+impl TupleCodegen for Codegen {
+    fn generate(&self, e: TupleAdded) -> Result<()> {
+        let TupleAdded {
+            file,
+            annotations,
+            members,
+            name,
+            body,
+        } = e;
+
+        Self::import(
+            file,
+            &[
+                "KSerializer",
+                "Serializable",
+                "descriptors.SerialDescriptor",
+                "encoding.Decoder",
+                "encoding.Encoder",
+                "json.JsonArray",
+                "json.JsonDecoder",
+                "json.JsonEncoder",
+                "json.decodeFromJsonElement",
+                "json.encodeToJsonElement",
+                "json.jsonArray",
+            ],
+        );
+
+        let fields = body.fields.iter().map(Loc::borrow).collect::<Vec<_>>();
+
+        annotations.push(Self::serializable_with(name));
+        members.push(Self::tuple_serializer(name, &fields));
+        Ok(())
+    }
+}
+
+impl EnumCodegen for Codegen {
+    fn generate(&self, e: EnumAdded) -> Result<()> {
+        let EnumAdded {
+            file,
+            annotations,
+            members,
+            name,
+            body,
+        } = e;
+
+        if body.enum_type == KotlinType::Simple("String") {
+            Self::import(file, &["Serializable"]);
+            annotations.push("@Serializable");
+            return Ok(());
+        }
+
+        Self::import(
+            file,
+            &[
+                "KSerializer",
+                "Serializable",
+                "SerializationException",
+                "descriptors.PrimitiveKind",
+                "descriptors.PrimitiveSerialDescriptor",
+                "descriptors.SerialDescriptor",
+                "encoding.Decoder",
+                "encoding.Encoder",
+            ],
+        );
+
+        annotations.push(Self::serializable_with(name));
+        members.push(Self::number_enum_serializer(name));
+        Ok(())
+    }
+}
+
+impl VariantCodegen for Codegen {
+    fn generate(&self, e: VariantAdded) -> Result<()> {
+        let VariantAdded {
+            file,
+            annotations,
+            variant,
+        } = e;
+
+        if let RpVariantValue::String(value) = variant.value {
+            Self::import(file, &["SerialName"]);
+            annotations.push(toks!["@SerialName(", value.quoted(), ")"]);
+        }
+
+        Ok(())
+    }
+}
+
+impl InterfaceCodegen for Codegen {
+    fn generate(&self, e: InterfaceAdded) -> Result<()> {
+        let InterfaceAdded {
+            file,
+            annotations,
+            members,
+            name,
+            body,
+        } = e;
+
+        Self::import(file, &["Serializable"]);
+
+        match body.sub_type_strategy {
+            RpSubTypeStrategy::Tagged { ref tag } => {
+                Self::import(
+                    file,
+                    &[
+                        "ExperimentalSerializationApi",
+                        "json.JsonClassDiscriminator",
+                    ],
+                );
+
+                annotations.push("@Serializable");
+                annotations.push("@OptIn(ExperimentalSerializationApi::class)");
+                annotations.push(toks![
+                    "@JsonClassDiscriminator(",
+                    tag.as_str().quoted(),
+                    ")"
+                ]);
+            }
+            RpSubTypeStrategy::Untagged => {
+                Self::import(
+                    file,
+                    &[
+                        "DeserializationStrategy",
+                        "SerializationException",
+                        "json.JsonContentPolymorphicSerializer",
+                        "json.JsonElement",
+                        "json.jsonObject",
+                    ],
+                );
+
+                annotations.push(Self::serializable_with(name));
+                members.push(Self::untagged_serializer(name, body));
+            }
+        }
+
+        // NB: register all sub-types, since serializers of untagged interfaces rely on the
+        // registration to find the serializer of a value on all platforms.
+        file.polymorphic.push_into(|t| {
+            push!(t, "polymorphic(", name, "::class) {");
+
+            t.nested_into(|t| {
+                for s in &body.sub_types {
+                    push!(t, "subclass(", &s.name, "::class)");
+                }
+            });
+
+            push!(t, "}");
+        });
+
+        Ok(())
+    }
+}
+
+impl SubTypeCodegen for Codegen {
+    fn generate(&self, e: SubTypeAdded) -> Result<()> {
+        let SubTypeAdded {
+            file,
+            annotations,
+            interface,
+            sub_type,
+            ..
+        } = e;
+
+        Self::import(file, &["Serializable"]);
+        annotations.push("@Serializable");
+
+        if let RpSubTypeStrategy::Tagged { .. } = interface.sub_type_strategy {
+            Self::import(file, &["SerialName"]);
+            annotations.push(toks!["@SerialName(", sub_type.name().quoted(), ")"]);
+        }
+
+        Ok(())
+    }
+}
+
+impl NewtypeCodegen for Codegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded {
+            file, annotations, ..
+        } = e;
+
+        Self::import(file, &["Serializable"]);
+        annotations.push("@Serializable");
+        Ok(())
+    }
+}
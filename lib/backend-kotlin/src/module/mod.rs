@@ -0,0 +1,3 @@
+mod kotlinx_serialization;
+
+pub use self::kotlinx_serialization::Module as KotlinxSerialization;
@@ -0,0 +1,227 @@
+//! Backend which delegates code generation to an external plugin.
+//!
+//! A plugin named `<name>` is an executable called `reproto-gen-<name>`, which is looked up in
+//! `PATH`.
+//! The plugin is sent a `Request` as JSON on stdin, and must respond with a `Response` as JSON on
+//! stdout before exiting successfully.
+//! Anything written to stderr is passed through to the user.
+//!
+//! The whole request is written before the response is read, so plugins must read all of stdin
+//! before writing to stdout.
+
+#[macro_use]
+extern crate log;
+extern crate reproto_core as core;
+extern crate reproto_manifest as manifest;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json as json;
+extern crate toml;
+
+use core::errors::*;
+use core::flavored::{RpFile, RpVersionedPackage};
+use core::{CoreFlavor, Handle, RelativePathBuf};
+use manifest::{checked_modules, Lang, Manifest, TryFromToml};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use trans::Session;
+
+/// Version of the protocol spoken with plugins.
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Prefix of the executable implementing a plugin.
+pub const PROGRAM_PREFIX: &str = "reproto-gen-";
+
+#[derive(Clone, Debug)]
+pub struct PluginLang {
+    name: String,
+}
+
+impl PluginLang {
+    pub fn new(name: String) -> PluginLang {
+        PluginLang { name }
+    }
+
+    /// Name of the executable implementing the plugin.
+    pub fn program(&self) -> String {
+        format!("{}{}", PROGRAM_PREFIX, self.name)
+    }
+
+    /// Run the plugin with the given request.
+    fn invoke(&self, request: &Request) -> Result<Response> {
+        let program = self.program();
+
+        debug!("plugin: {}", program);
+
+        let mut child = Command::new(&program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("{}: failed to start plugin: {}", program, e))?;
+
+        {
+            let stdin = child
+                .stdin
+                .take()
+                .ok_or_else(|| format!("{}: no stdin", program))?;
+
+            json::to_writer(stdin, request)
+                .map_err(|e| format!("{}: failed to send request: {}", program, e))?;
+        }
+
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(format!("{}: plugin failed: {}", program, output.status).into());
+        }
+
+        let response = json::from_slice(&output.stdout)
+            .map_err(|e| format!("{}: bad response: {}", program, e))?;
+
+        Ok(response)
+    }
+}
+
+impl Lang for PluginLang {
+    fn copy(&self) -> Box<Lang> {
+        Box::new(self.clone())
+    }
+
+    fn module_specs(
+        &self,
+        path: &Path,
+        input: Option<toml::Value>,
+    ) -> Result<Option<Vec<Box<Any + Send>>>> {
+        manifest::parse_section_any::<PluginModule>(path, input)
+    }
+
+    fn string_spec(&self, path: &Path, input: &str) -> Result<Box<Any + Send>> {
+        manifest::parse_string_any::<PluginModule>(path, input)
+    }
+
+    fn compile(
+        &self,
+        handle: &Handle,
+        session: Session<CoreFlavor>,
+        manifest: Manifest,
+    ) -> Result<()> {
+        let session = session.translate_default()?;
+        let modules: Vec<PluginModule> = checked_modules(manifest.modules)?;
+
+        let mut request = Request {
+            version: PROTOCOL_VERSION,
+            plugin: self.name.as_str(),
+            modules: BTreeMap::new(),
+            files: Vec::new(),
+        };
+
+        for module in modules {
+            request
+                .modules
+                .insert(module.id, json::to_value(&module.value)?);
+        }
+
+        for (package, file) in session.for_each_file() {
+            request.files.push(RequestFile { package, file });
+        }
+
+        let response = self.invoke(&request)?;
+
+        if !response.errors.is_empty() {
+            let errors = response.errors.into_iter().map(Error::from).collect();
+            return Err(
+                Error::new(format!("{}: plugin reported errors", self.program()))
+                    .with_suppressed(errors),
+            );
+        }
+
+        for file in response.files {
+            if file.path.as_str().split('/').any(|part| part == "..") {
+                return Err(format!(
+                    "{}: refusing to write outside of output directory: {}",
+                    self.program(),
+                    file.path.display()
+                )
+                .into());
+            }
+
+            if let Some(parent) = file.path.parent() {
+                if !handle.is_dir(parent) {
+                    debug!("+dir: {}", parent.display());
+                    handle.create_dir_all(parent)?;
+                }
+            }
+
+            debug!("+file: {}", file.path.display());
+            handle
+                .create(&file.path)?
+                .write_all(file.content.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration of a module, which is passed as-is to the plugin.
+#[derive(Debug)]
+pub struct PluginModule {
+    id: String,
+    value: toml::Value,
+}
+
+impl TryFromToml for PluginModule {
+    fn try_from_string(_: &Path, id: &str, value: String) -> Result<Self> {
+        Ok(PluginModule {
+            id: id.to_string(),
+            value: toml::Value::String(value),
+        })
+    }
+
+    fn try_from_value(_: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        Ok(PluginModule {
+            id: id.to_string(),
+            value,
+        })
+    }
+}
+
+/// Request sent to plugins.
+#[derive(Debug, Serialize)]
+pub struct Request<'a> {
+    /// Version of the protocol, see `PROTOCOL_VERSION`.
+    pub version: u32,
+    /// Name of the plugin.
+    pub plugin: &'a str,
+    /// Configuration of all modules enabled in the manifest.
+    pub modules: BTreeMap<String, json::Value>,
+    /// All files being built, in the same format as `file` in the model of the `json` backend.
+    pub files: Vec<RequestFile<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestFile<'a> {
+    pub package: &'a RpVersionedPackage,
+    pub file: &'a RpFile,
+}
+
+/// Response expected from plugins.
+#[derive(Debug, Deserialize)]
+pub struct Response {
+    /// Files to write, relative to the output directory.
+    #[serde(default)]
+    pub files: Vec<ResponseFile>,
+    /// Errors which should fail the build.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseFile {
+    pub path: RelativePathBuf,
+    pub content: String,
+}
@@ -15,6 +15,7 @@ use std::collections::BTreeMap;
 use std::iter;
 use std::rc::Rc;
 use trans::{self, Translated};
+use utils::{BlockComment, Codec, Deprecated, Model};
 use {FileSpec, Options, EXT, INIT_PY};
 
 pub struct Compiler<'el> {
@@ -23,7 +24,17 @@ pub struct Compiler<'el> {
     to_lower_snake: naming::ToLowerSnake,
     dict: Element<'static, Python<'static>>,
     enum_enum: Python<'static>,
+    dataclass: Python<'static>,
+    optional: Python<'static>,
+    class_var: Python<'static>,
+    base_model: Python<'static>,
+    pydantic_field: Python<'static>,
+    pydantic_validator: Python<'static>,
+    b64decode: Python<'static>,
+    b64encode: Python<'static>,
     service_generators: Vec<Box<ServiceCodegen>>,
+    model: Model,
+    codec: Codec,
     handle: &'el Handle,
 }
 
@@ -40,7 +51,17 @@ impl<'el> Compiler<'el> {
             to_lower_snake: naming::to_lower_snake(),
             dict: "dict".into(),
             enum_enum: imported("enum").name("Enum"),
+            dataclass: imported("dataclasses").name("dataclass"),
+            optional: imported("typing").name("Optional"),
+            class_var: imported("typing").name("ClassVar"),
+            base_model: imported("pydantic").name("BaseModel"),
+            pydantic_field: imported("pydantic").name("Field"),
+            pydantic_validator: imported("pydantic").name("validator"),
+            b64decode: imported("base64").name("b64decode"),
+            b64encode: imported("base64").name("b64encode"),
             service_generators: options.service_generators,
+            model: options.model,
+            codec: options.codec,
             handle,
         }
     }
@@ -110,7 +131,7 @@ impl<'el> Compiler<'el> {
         encode_body.push(toks!["return data"]);
 
         let mut encode = Tokens::new();
-        encode.push("def encode(self):");
+        encode.push(toks!["def ", self.codec.encode, "(self):"]);
         encode.nested(encode_body.join_line_spacing());
         Ok(encode)
     }
@@ -131,7 +152,7 @@ impl<'el> Compiler<'el> {
         encode_body.push(toks!["return (", values.join(", "), ")"]);
 
         let mut encode = Tokens::new();
-        encode.push("def encode(self):");
+        encode.push(toks!["def ", self.codec.encode, "(self):"]);
         encode.nested(encode_body.join_line_spacing());
         Ok(encode)
     }
@@ -227,7 +248,7 @@ impl<'el> Compiler<'el> {
         Ok({
             let mut m = Tokens::new();
             m.push("@staticmethod");
-            m.push("def decode(data):");
+            m.push(toks!["def ", self.codec.decode, "(data):"]);
             m.nested(t.join_line_spacing());
             m
         })
@@ -265,6 +286,49 @@ impl<'el> Compiler<'el> {
         constructor
     }
 
+    /// Build annotated field declarations.
+    ///
+    /// For pydantic models, every field is also given a default and the name used in JSON as its
+    /// alias.
+    fn build_fields<I>(&self, fields: I) -> Tokens<'el, Python<'el>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        let mut t = Tokens::new();
+
+        for field in fields {
+            let ty = field.ty.annotation(self.model == Model::Pydantic);
+
+            let ty = if field.is_optional() {
+                toks![self.optional.clone(), "[", ty, "]"]
+            } else {
+                ty
+            };
+
+            if self.model != Model::Pydantic {
+                t.push(toks![field.safe_ident(), ": ", ty]);
+                continue;
+            }
+
+            let default = if field.is_optional() { "None" } else { "..." };
+
+            t.push(toks![
+                field.safe_ident(),
+                ": ",
+                ty,
+                " = ",
+                self.pydantic_field.clone(),
+                "(",
+                default,
+                ", alias=",
+                field.name().quoted(),
+                ")",
+            ]);
+        }
+
+        t
+    }
+
     fn build_getters<I>(&self, fields: I) -> Result<Vec<Tokens<'el, Python<'el>>>>
     where
         I: IntoIterator<Item = &'el Loc<RpField>>,
@@ -279,13 +343,14 @@ impl<'el> Compiler<'el> {
             body.nested({
                 let mut t = Tokens::new();
 
-                if !field.comment.is_empty() {
+                if !field.comment.is_empty() || field.deprecated.is_some() {
                     t.push("\"\"\"");
 
                     for c in &field.comment {
                         t.push(Element::from(c.clone()));
                     }
 
+                    t.push_unless_empty(Deprecated(&field.deprecated));
                     t.push("\"\"\"");
                 }
 
@@ -349,6 +414,159 @@ impl<'el> Compiler<'el> {
 
         class
     }
+
+    /// Build a class according to the model in use.
+    ///
+    /// `fields` are the fields of the class.
+    fn as_model_class<I>(
+        &self,
+        name: &'el PythonName,
+        mut body: Tokens<'el, Python<'el>>,
+        fields: I,
+    ) -> Tokens<'el, Python<'el>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        match self.model {
+            Model::Plain => self.as_class(name, body),
+            Model::Dataclasses => {
+                let mut t = Tokens::new();
+                t.push(toks!["@", self.dataclass.clone()]);
+                t.push(self.as_class(name, body));
+                t
+            }
+            Model::Pydantic => {
+                let bytes = fields.into_iter().any(|f| f.ty.contains_bytes());
+
+                body.push({
+                    let mut t = Tokens::new();
+                    t.push("class Config:");
+                    t.nested("allow_population_by_field_name = True");
+
+                    // NB: encode bytes as base64 from `json()`, like `to_dict` does.
+                    if bytes {
+                        t.nested(toks![
+                            "json_encoders = {bytes: lambda v: ",
+                            self.b64encode.clone(),
+                            "(v).decode(\"ascii\")}",
+                        ]);
+                    }
+
+                    t
+                });
+
+                let mut class = Tokens::new();
+                class.push(toks!["class ", name, "(", self.base_model.clone(), "):"]);
+                class.nested(body.join_line_spacing());
+                class
+            }
+        }
+    }
+
+    /// Build the fields of a class, either through annotations or a constructor with getters.
+    fn build_members<I>(&self, fields: I) -> Result<Vec<Tokens<'el, Python<'el>>>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>> + Clone,
+    {
+        if self.model != Model::Plain {
+            let mut result = Vec::new();
+            let annotated = self.build_fields(fields.clone());
+
+            if !annotated.is_empty() {
+                result.push(annotated);
+            }
+
+            if self.model == Model::Pydantic {
+                result.extend(self.bytes_validator(fields));
+            }
+
+            return Ok(result);
+        }
+
+        let mut result = Vec::new();
+        result.push(self.build_constructor(fields.clone()));
+        result.extend(self.build_getters(fields)?);
+        Ok(result)
+    }
+
+    /// Build a pydantic validator which decodes the base64 strings of all fields containing bytes.
+    ///
+    /// Values which already are `bytes` are kept as they are.
+    fn bytes_validator<I>(&self, fields: I) -> Option<Tokens<'el, Python<'el>>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        let mut names = Tokens::new();
+
+        for field in fields {
+            if field.ty.contains_bytes() {
+                names.append(field.safe_ident().quoted());
+            }
+        }
+
+        if names.is_empty() {
+            return None;
+        }
+
+        let mut t = Tokens::new();
+
+        // NB: `each_item` applies the validator to the elements of arrays, sets, and maps.
+        t.push(toks![
+            "@",
+            self.pydantic_validator.clone(),
+            "(",
+            names.join(", "),
+            ", pre=True, each_item=True)",
+        ]);
+
+        t.push("def _decode_bytes(cls, v):");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                t.push("if isinstance(v, str):");
+                t.nested(toks!["return ", self.b64decode.clone(), "(v)"]);
+            });
+
+            t.push("return v");
+            t
+        });
+
+        Some(t)
+    }
+
+    /// Build a `parse_obj` override for pydantic tuples, which are represented as JSON arrays.
+    fn tuple_parse_obj<I>(&self, fields: I) -> Tokens<'el, Python<'el>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        let mut names = Tokens::new();
+
+        for field in fields {
+            names.append(field.safe_ident().quoted());
+        }
+
+        let names = toks!["(", names.join(", "), ",)"];
+
+        let mut t = Tokens::new();
+        t.push("@classmethod");
+        t.push(toks!["def ", self.codec.decode, "(cls, data):"]);
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                t.push("if isinstance(data, list):");
+                t.nested(toks!["data = dict(zip(", names, ", data))"]);
+            });
+
+            t.push(toks!["return super().", self.codec.decode, "(data)"]);
+            t.join_line_spacing()
+        });
+
+        t
+    }
 }
 
 impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
@@ -370,24 +588,33 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
     fn process_tuple(&self, out: &mut Self::Out, body: &'el RpTupleBody) -> Result<()> {
         let mut tuple_body = Tokens::new();
 
-        tuple_body.push(self.build_constructor(&body.fields));
+        if !body.comment.is_empty() || body.deprecated.is_some() {
+            tuple_body.push(BlockComment(&body.comment, &body.deprecated));
+        }
 
-        for getter in self.build_getters(&body.fields)? {
-            tuple_body.push(getter);
+        for member in self.build_members(&body.fields)? {
+            tuple_body.push(member);
         }
 
         tuple_body.push_unless_empty(code!(&body.codes, core::RpContext::Python));
 
-        let decode = self.decode_method(&body.name, &body.fields, |i, _| i.to_string().into())?;
-        tuple_body.push(decode);
+        if self.model == Model::Pydantic {
+            tuple_body.push(self.tuple_parse_obj(&body.fields));
+        } else {
+            let decode =
+                self.decode_method(&body.name, &body.fields, |i, _| i.to_string().into())?;
+            tuple_body.push(decode);
+        }
 
         let encode = self.encode_tuple_method(&body.fields)?;
         tuple_body.push(encode);
 
-        let repr_method = self.repr_method(&body.name, &body.fields);
-        tuple_body.push(repr_method);
+        if self.model == Model::Plain {
+            let repr_method = self.repr_method(&body.name, &body.fields);
+            tuple_body.push(repr_method);
+        }
 
-        let class = self.as_class(&body.name, tuple_body);
+        let class = self.as_model_class(&body.name, tuple_body, &body.fields);
 
         out.0.push(class);
         Ok(())
@@ -396,6 +623,10 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
     fn process_enum(&self, out: &mut Self::Out, body: &'el RpEnumBody) -> Result<()> {
         let mut class_body = Tokens::new();
 
+        if !body.comment.is_empty() || body.deprecated.is_some() {
+            class_body.push(BlockComment(&body.comment, &body.deprecated));
+        }
+
         class_body.push(self.build_constructor(iter::once(self.variant_field)));
 
         for getter in self.build_getters(iter::once(self.variant_field))? {
@@ -404,8 +635,8 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
 
         class_body.push_unless_empty(code!(&body.codes, core::RpContext::Python));
 
-        class_body.push(encode_method(self.variant_field)?);
-        class_body.push(decode_method(self.variant_field)?);
+        class_body.push(encode_method(self.codec, self.variant_field)?);
+        class_body.push(decode_method(self.codec, body, self.variant_field)?);
 
         let repr_method = self.repr_method(&body.name, iter::once(self.variant_field));
         class_body.push(repr_method);
@@ -414,14 +645,21 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
         out.0.push(class);
         return Ok(());
 
-        fn encode_method<'el>(field: &'el Loc<RpField>) -> Result<Tokens<'el, Python<'el>>> {
+        fn encode_method<'el>(
+            codec: Codec,
+            field: &'el Loc<RpField>,
+        ) -> Result<Tokens<'el, Python<'el>>> {
             let mut m = Tokens::new();
-            m.push("def encode(self):");
+            m.push(toks!["def ", codec.encode, "(self):"]);
             m.nested(toks!["return self.", field.safe_ident()]);
             Ok(m)
         }
 
-        fn decode_method<'el>(field: &'el Loc<RpField>) -> Result<Tokens<'el, Python<'el>>> {
+        fn decode_method<'el>(
+            codec: Codec,
+            body: &'el RpEnumBody,
+            field: &'el Loc<RpField>,
+        ) -> Result<Tokens<'el, Python<'el>>> {
             let mut decode_body = Tokens::new();
 
             let mut check = Tokens::new();
@@ -434,6 +672,22 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
             member_loop.nested(check);
 
             decode_body.push(member_loop);
+
+            // aliases are only accepted when decoding.
+            for v in &body.variants {
+                for alias in v.aliases.iter() {
+                    let alias = match alias {
+                        core::RpVariantValue::String(string) => toks![string.quoted()],
+                        core::RpVariantValue::Number(number) => toks![number.to_string()],
+                    };
+
+                    decode_body.push_into(|t| {
+                        push!(t, "if data == ", alias, ":");
+                        nested!(t, "return cls.__members__[", v.ident().quoted(), "]");
+                    });
+                }
+            }
+
             decode_body.push(toks![
                 "raise Exception(",
                 "data does not match enum".quoted(),
@@ -442,7 +696,7 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
 
             let mut m = Tokens::new();
             m.push("@classmethod");
-            m.push("def decode(cls, data):");
+            m.push(toks!["def ", codec.decode, "(cls, data):"]);
             m.nested(decode_body.join_line_spacing());
             Ok(m)
         }
@@ -451,44 +705,67 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
     fn process_type(&self, out: &mut Self::Out, body: &'el RpTypeBody) -> Result<()> {
         let mut class_body = Tokens::new();
 
-        let constructor = self.build_constructor(&body.fields);
-        class_body.push(constructor);
+        if !body.comment.is_empty() || body.deprecated.is_some() {
+            class_body.push(BlockComment(&body.comment, &body.deprecated));
+        }
 
-        for getter in self.build_getters(&body.fields)? {
-            class_body.push(getter);
+        for member in self.build_members(&body.fields)? {
+            class_body.push(member);
         }
 
-        let decode = self.decode_method(&body.name, &body.fields, |_, field| {
-            toks!(field.name().quoted())
-        })?;
+        // NB: pydantic models are decoded through `parse_obj`.
+        if self.model != Model::Pydantic {
+            let decode = self.decode_method(&body.name, &body.fields, |_, field| {
+                toks!(field.name().quoted())
+            })?;
 
-        class_body.push(decode);
+            class_body.push(decode);
+        }
 
         let encode = self.encode_method(&body.fields, self.dict.clone().into(), None)?;
 
         class_body.push(encode);
 
-        let repr_method = self.repr_method(&body.name, &body.fields);
-        class_body.push(repr_method);
+        if self.model == Model::Plain {
+            let repr_method = self.repr_method(&body.name, &body.fields);
+            class_body.push(repr_method);
+        }
+
         class_body.push_unless_empty(code!(&body.codes, core::RpContext::Python));
 
-        out.0.push(self.as_class(&body.name, class_body));
+        out.0
+            .push(self.as_model_class(&body.name, class_body, &body.fields));
         Ok(())
     }
 
     fn process_interface(&self, out: &mut Self::Out, body: &'el RpInterfaceBody) -> Result<()> {
         let mut type_body = Tokens::new();
 
+        if !body.comment.is_empty() || body.deprecated.is_some() {
+            type_body.push(BlockComment(&body.comment, &body.deprecated));
+        }
+
         match body.sub_type_strategy {
             core::RpSubTypeStrategy::Tagged { ref tag, .. } => {
                 let tk = tag.as_str().quoted().into();
-                type_body.push(decode_from_tag(&body, &tk)?);
+                type_body.push(decode_from_tag(self.codec, &body, &tk)?);
             }
             core::RpSubTypeStrategy::Untagged => {
-                type_body.push(decode_from_untagged(&body)?);
+                type_body.push(decode_from_untagged(self.codec, &body)?);
             }
         }
 
+        // Let pydantic validate fields referencing the interface through its decoder.
+        if self.model == Model::Pydantic {
+            type_body.push({
+                let mut t = Tokens::new();
+                t.push("@classmethod");
+                t.push("def __get_validators__(cls):");
+                t.nested(toks!["yield cls.", self.codec.decode]);
+                t
+            });
+        }
+
         type_body.push_unless_empty(code!(&body.codes, core::RpContext::Python));
 
         out.0.push(self.as_class(&body.name, type_body));
@@ -496,24 +773,36 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
         for sub_type in &body.sub_types {
             let mut sub_type_body = Tokens::new();
 
-            sub_type_body.push(toks!["TYPE = ", sub_type.name().quoted()]);
+            if !sub_type.comment.is_empty() || sub_type.deprecated.is_some() {
+                sub_type_body.push(BlockComment(&sub_type.comment, &sub_type.deprecated));
+            }
+
+            if self.model == Model::Pydantic {
+                sub_type_body.push(toks![
+                    "TYPE: ",
+                    self.class_var.clone(),
+                    "[str] = ",
+                    sub_type.name().quoted(),
+                ]);
+            } else {
+                sub_type_body.push(toks!["TYPE = ", sub_type.name().quoted()]);
+            }
 
             let fields: Vec<&Loc<RpField>> =
                 body.fields.iter().chain(sub_type.fields.iter()).collect();
 
-            let constructor = self.build_constructor(fields.iter().cloned());
-            sub_type_body.push(constructor);
-
-            for getter in self.build_getters(fields.iter().cloned())? {
-                sub_type_body.push(getter);
+            for member in self.build_members(fields.iter().cloned())? {
+                sub_type_body.push(member);
             }
 
-            let decode =
-                self.decode_method(&sub_type.name, fields.iter().cloned(), |_, field| {
-                    toks!(field.ident.clone().quoted())
-                })?;
+            if self.model != Model::Pydantic {
+                let decode =
+                    self.decode_method(&sub_type.name, fields.iter().cloned(), |_, field| {
+                        toks!(field.ident.clone().quoted())
+                    })?;
 
-            sub_type_body.push(decode);
+                sub_type_body.push(decode);
+            }
 
             match body.sub_type_strategy {
                 core::RpSubTypeStrategy::Tagged { ref tag, .. } => {
@@ -535,16 +824,21 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
                 }
             }
 
-            let repr_method = self.repr_method(&sub_type.name, fields.iter().cloned());
-            sub_type_body.push(repr_method);
+            if self.model == Model::Plain {
+                let repr_method = self.repr_method(&sub_type.name, fields.iter().cloned());
+                sub_type_body.push(repr_method);
+            }
+
             sub_type_body.push_unless_empty(code!(&sub_type.codes, core::RpContext::Python));
 
-            out.0.push(self.as_class(&sub_type.name, sub_type_body));
+            out.0
+                .push(self.as_model_class(&sub_type.name, sub_type_body, fields.iter().cloned()));
         }
 
         return Ok(());
 
         fn decode_from_tag<'el>(
+            codec: Codec,
             body: &'el RpInterfaceBody,
             tag: &Tokens<'el, Python<'el>>,
         ) -> Result<Tokens<'el, Python<'el>>> {
@@ -557,7 +851,7 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
             for sub_type in body.sub_types.iter() {
                 t.push_into(|t| {
                     push!(t, "if ", f_tag, " == ", sub_type.name().quoted(), ":");
-                    nested!(t, "return ", &sub_type.name, ".decode(data)");
+                    nested!(t, "return ", &sub_type.name, ".", codec.decode, "(data)");
                 });
             }
 
@@ -573,13 +867,14 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
             Ok({
                 let mut decode = Tokens::new();
                 decode.push("@staticmethod");
-                decode.push(toks!("def decode(", data, "):"));
+                decode.push(toks!("def ", codec.decode, "(", data, "):"));
                 decode.nested(t.join_line_spacing());
                 decode
             })
         }
 
         fn decode_from_untagged<'el>(
+            codec: Codec,
             body: &'el RpInterfaceBody,
         ) -> Result<Tokens<'el, Python<'el>>> {
             let mut t = Tokens::new();
@@ -595,7 +890,7 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
 
                 t.push_into(|t| {
                     push!(t, "if ", keys, " >= ", discriminating, ":");
-                    nested!(t, "return ", &sub_type.name, ".decode(data)");
+                    nested!(t, "return ", &sub_type.name, ".", codec.decode, "(data)");
                 });
             }
 
@@ -611,7 +906,7 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
             Ok({
                 let mut decode = Tokens::new();
                 decode.push("@staticmethod");
-                decode.push(toks!("def decode(", data, "):"));
+                decode.push(toks!("def ", codec.decode, "(", data, "):"));
                 decode.nested(t.join_line_spacing());
                 decode
             })
@@ -654,10 +949,17 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
 
     fn populate_files(&self) -> Result<BTreeMap<RpPackage, FileSpec<'el>>> {
         let mut enums = Vec::new();
+        let mut models = Vec::new();
 
         let mut files = self.do_populate_files(|decl| {
-            if let core::RpDecl::Enum(ref body) = *decl {
-                enums.push(body);
+            match *decl {
+                core::RpDecl::Enum(ref body) => enums.push(body),
+                core::RpDecl::Type(ref body) => models.push(&body.name),
+                core::RpDecl::Tuple(ref body) => models.push(&body.name),
+                core::RpDecl::Interface(ref body) => {
+                    models.extend(body.sub_types.iter().map(|s| &s.name))
+                }
+                _ => {}
             }
 
             Ok(())
@@ -674,6 +976,18 @@ impl<'el> PackageProcessor<'el, PythonFlavor, PythonName> for Compiler<'el> {
             }
         }
 
+        // Forward references in pydantic models have to be resolved once all declarations in the
+        // module are available.
+        if self.model == Model::Pydantic {
+            for name in models {
+                if let Some(ref mut file_spec) = files.get_mut(&name.package) {
+                    file_spec.0.push(toks![name, ".update_forward_refs()"]);
+                } else {
+                    return Err(format!("missing file for package: {}", &name.package).into());
+                }
+            }
+        }
+
         Ok(files)
     }
 
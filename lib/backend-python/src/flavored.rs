@@ -5,8 +5,8 @@
 use backend::package_processor;
 use core::errors::Result;
 use core::{
-    self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberType,
-    RpStringType, Translate, Translator,
+    self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberKind,
+    RpNumberType, RpStringType, Translate, Translator,
 };
 use genco::python::{self, Python};
 use genco::{Cons, Element, IntoTokens, Tokens};
@@ -17,7 +17,7 @@ use std::fmt;
 use std::ops::Deref;
 use std::rc::Rc;
 use trans::Packages;
-use utils::{Exception, VersionHelper};
+use utils::{Codec, Exception, VersionHelper};
 use {Options, TYPE_SEP};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,11 +25,19 @@ pub enum PythonKind<'el> {
     Native,
     Integer,
     Float,
+    /// Decimals are decoded into `decimal.Decimal` to retain their precision.
+    Decimal,
     Boolean,
     String,
+    /// Bytes are encoded as base64 strings, and decoded into `bytes`.
+    Bytes,
     Array {
         argument: Box<PythonType<'el>>,
     },
+    /// Sets are encoded as lists, so they are never native.
+    Set {
+        argument: Box<PythonType<'el>>,
+    },
     Map {
         key: Box<PythonType<'el>>,
         value: Box<PythonType<'el>>,
@@ -57,6 +65,7 @@ impl<'el> PythonKind<'el> {
 #[derive(Debug, Clone)]
 pub struct PythonType<'el> {
     helper: Rc<Box<VersionHelper>>,
+    codec: Codec,
     kind: PythonKind<'el>,
 }
 
@@ -95,6 +104,27 @@ impl<'el> PythonType<'el> {
                 nested!(t, "raise ", Exception("not a float"));
                 Some(t)
             }
+            Decimal => {
+                let decimal = python::imported("decimal");
+
+                let mut t = Tokens::new();
+
+                t.push_into(|t| {
+                    push!(
+                        t,
+                        "if not isinstance(",
+                        var,
+                        ", (int, float, ",
+                        decimal.name("Decimal"),
+                        ")):"
+                    );
+                    nested!(t, "raise ", Exception("not a decimal"));
+                });
+
+                // NB: go through the string representation to avoid the binary expansion of floats.
+                push!(t, var, " = ", decimal.name("Decimal"), "(str(", var, "))");
+                Some(t)
+            }
             Boolean => {
                 let mut t = Tokens::new();
                 push!(t, "if not isinstance(", var, ", bool):");
@@ -109,6 +139,27 @@ impl<'el> PythonType<'el> {
                 nested!(t, "raise ", Exception("not a string"));
                 Some(t)
             }
+            Bytes => {
+                let test = self.helper.is_string(var.clone());
+
+                let mut t = Tokens::new();
+
+                t.push_into(|t| {
+                    push!(t, "if not ", test, ":");
+                    nested!(t, "raise ", Exception("not a string"));
+                });
+
+                push!(
+                    t,
+                    var,
+                    " = ",
+                    python::imported("base64").name("b64decode"),
+                    "(",
+                    var,
+                    ")"
+                );
+                Some(t)
+            }
             Native => None,
             Array { ref argument } => {
                 let mut t = Tokens::new();
@@ -138,6 +189,34 @@ impl<'el> PythonType<'el> {
                 push!(t, var, " = ", a);
                 Some(t.join_line_spacing())
             }
+            Set { ref argument } => {
+                let mut t = Tokens::new();
+
+                let v = Rc::new(format!("_v{}", l));
+                let s = Rc::new(format!("_s{}", l));
+
+                t.push_into(|t| {
+                    push!(t, "if not isinstance(", var, ", list):");
+                    nested!(t, "raise ", Exception("not an array"));
+                });
+
+                push!(t, s, " = set()");
+
+                t.push_into(|t| {
+                    push!(t, "for ", v, " in ", var, ":");
+
+                    t.nested_into(|mut t| {
+                        if let Some(d) = argument.decode(v.clone(), l + 1) {
+                            t.push(d);
+                        }
+
+                        push!(t, s, ".add(", v, ")");
+                    });
+                });
+
+                push!(t, var, " = ", s);
+                Some(t.join_line_spacing())
+            }
             Map { ref key, ref value } => {
                 let mut t = Tokens::new();
 
@@ -175,7 +254,9 @@ impl<'el> PythonType<'el> {
                 var.clone(),
                 " = ",
                 python.clone(),
-                ".decode(",
+                ".",
+                self.codec.decode,
+                "(",
                 var.clone(),
                 ")"
             )),
@@ -187,18 +268,90 @@ impl<'el> PythonType<'el> {
         use self::PythonKind::*;
 
         match self.kind {
-            Integer | Float | Boolean | Native | String => toks![var],
+            Integer | Float | Decimal | Boolean | Native | String => toks![var],
             ref v if v.is_native() => toks![var],
+            Bytes => toks![
+                python::imported("base64").name("b64encode"),
+                "(",
+                var,
+                ").decode(\"ascii\")"
+            ],
             Array { ref argument } => {
                 let v = argument.encode("v".into());
                 toks!["[", v, " for v in ", var, "]"]
             }
+            Set { ref argument } => {
+                let v = argument.encode("v".into());
+                toks!["[", v, " for v in ", var, "]"]
+            }
             Map { ref key, ref value } => {
                 let k = key.encode("k".into());
                 let v = value.encode("v".into());
                 toks!["dict((", k, ", ", v, ") for (k, v) in ", var, ".items())",]
             }
-            Name { ref python } => toks![var, ".encode()"],
+            Name { ref python } => toks![var, ".", self.codec.encode, "()"],
+        }
+    }
+
+    /// Check if this type contains bytes, which are decoded from base64 strings.
+    ///
+    /// Keys of maps are not considered.
+    pub fn contains_bytes(&self) -> bool {
+        use self::PythonKind::*;
+
+        match self.kind {
+            Bytes => true,
+            Array { ref argument } | Set { ref argument } => argument.contains_bytes(),
+            Map { ref value, .. } => value.contains_bytes(),
+            _ => false,
+        }
+    }
+
+    /// Build a PEP 484 type annotation for this type.
+    ///
+    /// If `strict` is set, primitives are annotated with pydantic's strict types so that values
+    /// are not coerced when validated.
+    ///
+    /// Named types are emitted as forward references, since they might be declared later in the
+    /// module.
+    pub fn annotation(&self, strict: bool) -> Tokens<'el, Python<'el>> {
+        use self::PythonKind::*;
+
+        let pydantic = python::imported("pydantic");
+
+        match self.kind {
+            Native => toks![python::imported("typing").name("Any")],
+            Integer if strict => toks![pydantic.name("StrictInt")],
+            Float if strict => toks![pydantic.name("StrictFloat")],
+            Boolean if strict => toks![pydantic.name("StrictBool")],
+            String if strict => toks![pydantic.name("StrictStr")],
+            Integer => toks!["int"],
+            Float => toks!["float"],
+            Decimal => toks![python::imported("decimal").name("Decimal")],
+            Boolean => toks!["bool"],
+            String => toks!["str"],
+            Bytes => toks!["bytes"],
+            Array { ref argument } => toks![
+                python::imported("typing").name("List"),
+                "[",
+                argument.annotation(strict),
+                "]"
+            ],
+            Set { ref argument } => toks![
+                python::imported("typing").name("Set"),
+                "[",
+                argument.annotation(strict),
+                "]"
+            ],
+            Map { ref key, ref value } => toks![
+                python::imported("typing").name("Dict"),
+                "[",
+                key.annotation(strict),
+                ", ",
+                value.annotation(strict),
+                "]"
+            ],
+            Name { ref python } => toks!["\"", python.clone(), "\""],
         }
     }
 }
@@ -243,16 +396,22 @@ impl Flavor for PythonFlavor {
 pub struct PythonFlavorTranslator {
     packages: Rc<Packages>,
     helper: Rc<Box<VersionHelper>>,
+    codec: Codec,
 }
 
 impl PythonFlavorTranslator {
-    pub fn new(packages: Rc<Packages>, helper: Rc<Box<VersionHelper>>) -> Self {
-        Self { packages, helper }
+    pub fn new(packages: Rc<Packages>, helper: Rc<Box<VersionHelper>>, codec: Codec) -> Self {
+        Self {
+            packages,
+            helper,
+            codec,
+        }
     }
 
     fn ty(&self, kind: PythonKind<'static>) -> PythonType<'static> {
         PythonType {
             helper: self.helper.clone(),
+            codec: self.codec,
             kind: kind,
         }
     }
@@ -264,8 +423,11 @@ impl FlavorTranslator for PythonFlavorTranslator {
 
     translator_defaults!(Self, field, endpoint, enum_type);
 
-    fn translate_number(&self, _: RpNumberType) -> Result<PythonType<'static>> {
-        Ok(self.ty(PythonKind::Integer))
+    fn translate_number(&self, number: RpNumberType) -> Result<PythonType<'static>> {
+        match number.kind {
+            RpNumberKind::Decimal => Ok(self.ty(PythonKind::Decimal)),
+            _ => Ok(self.ty(PythonKind::Integer)),
+        }
     }
 
     fn translate_float(&self) -> Result<PythonType<'static>> {
@@ -288,12 +450,30 @@ impl FlavorTranslator for PythonFlavorTranslator {
         Ok(self.ty(PythonKind::String))
     }
 
+    fn translate_date(&self) -> Result<PythonType<'static>> {
+        Ok(self.ty(PythonKind::String))
+    }
+
+    fn translate_time(&self) -> Result<PythonType<'static>> {
+        Ok(self.ty(PythonKind::String))
+    }
+
+    fn translate_duration(&self) -> Result<PythonType<'static>> {
+        Ok(self.ty(PythonKind::String))
+    }
+
     fn translate_array(&self, argument: PythonType<'static>) -> Result<PythonType<'static>> {
         Ok(self.ty(PythonKind::Array {
             argument: Box::new(argument),
         }))
     }
 
+    fn translate_set(&self, argument: PythonType<'static>) -> Result<PythonType<'static>> {
+        Ok(self.ty(PythonKind::Set {
+            argument: Box::new(argument),
+        }))
+    }
+
     fn translate_map(
         &self,
         key: PythonType<'static>,
@@ -310,7 +490,7 @@ impl FlavorTranslator for PythonFlavorTranslator {
     }
 
     fn translate_bytes(&self) -> Result<PythonType<'static>> {
-        Ok(self.ty(PythonKind::String))
+        Ok(self.ty(PythonKind::Bytes))
     }
 
     fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<PythonType<'static>> {
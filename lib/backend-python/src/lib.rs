@@ -30,12 +30,12 @@ use core::{
     Translate,
 };
 use genco::{Cons, Python, Tokens};
-use manifest::{Lang, Manifest, NoModule, TryFromToml};
+use manifest::{Lang, Manifest, ModuleDoc, ModuleOption, NoModule, TryFromToml};
 use std::any::Any;
 use std::path::Path;
 use std::rc::Rc;
 use trans::Session;
-use utils::VersionHelper;
+use utils::{Codec, Model, VersionHelper};
 
 const TYPE_SEP: &str = "_";
 const INIT_PY: &str = "__init__.py";
@@ -44,49 +44,52 @@ const EXT: &str = "py";
 #[derive(Clone, Copy, Default, Debug)]
 pub struct PythonLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "requests",
+        description: "Generate HTTP clients for services.",
+        options: &[ModuleOption {
+            name: "client",
+            ty: "\"requests\" | \"httpx\"",
+            default: Some("requests"),
+            description: "Library used by generated clients.",
+        }],
+    },
+    ModuleDoc {
+        name: "python2",
+        description: "Generate code which is compatible with Python 2.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "dataclasses",
+        description: "Generate `@dataclass` classes with type annotations.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "pydantic",
+        description: "Generate pydantic models.",
+        options: &[],
+    },
+];
+
 impl Lang for PythonLang {
     lang_base!(PythonModule, compile);
 
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+
     fn comment(&self, input: &str) -> Option<String> {
         Some(format!("# {}", input))
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        // NB: combined set of keywords for Python 2/3 to avoid having two codegen implementations.
-        vec![
-            ("and", "_and"),
-            ("as", "_as"),
-            ("assert", "_assert"),
-            ("break", "_break"),
-            ("class", "_class"),
-            ("continue", "_continue"),
-            ("def", "_def"),
-            ("del", "_del"),
-            ("elif", "_elif"),
-            ("else", "_else"),
-            ("except", "_except"),
-            ("exec", "_exec"),
-            ("finally", "_finally"),
-            ("for", "_for"),
-            ("from", "_from"),
-            ("global", "_global"),
-            ("if", "_if"),
-            ("import", "_import"),
-            ("in", "_in"),
-            ("is", "_is"),
-            ("lambda", "_lambda"),
-            ("nonlocal", "_nonlocal"),
-            ("not", "_not"),
-            ("or", "_or"),
-            ("pass", "_pass"),
-            ("print", "_print"),
-            ("raise", "_raise"),
-            ("return", "_return"),
-            ("try", "_try"),
-            ("while", "_while"),
-            ("with", "_with"),
-            ("yield", "_yield"),
-        ]
+    fn keywords(&self) -> Option<&'static naming::Keywords> {
+        Some(&naming::keywords::PYTHON)
+    }
+
+    fn numbers_as_strings(&self) -> bool {
+        false
     }
 }
 
@@ -94,6 +97,8 @@ impl Lang for PythonLang {
 pub enum PythonModule {
     Requests(module::RequestsConfig),
     Python2(module::Python2Config),
+    Dataclasses(module::DataclassesConfig),
+    Pydantic(module::PydanticConfig),
 }
 
 impl TryFromToml for PythonModule {
@@ -103,6 +108,8 @@ impl TryFromToml for PythonModule {
         let result = match id {
             "requests" => Requests(module::RequestsConfig::default()),
             "python2" => Python2(module::Python2Config::default()),
+            "dataclasses" => Dataclasses(module::DataclassesConfig::default()),
+            "pydantic" => Pydantic(module::PydanticConfig::default()),
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -115,6 +122,8 @@ impl TryFromToml for PythonModule {
         let result = match id {
             "requests" => Requests(value.try_into()?),
             "python2" => Python2(value.try_into()?),
+            "dataclasses" => Dataclasses(value.try_into()?),
+            "pydantic" => Pydantic(value.try_into()?),
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -127,6 +136,10 @@ pub struct Options {
     pub build_constructor: bool,
     pub service_generators: Vec<Box<ServiceCodegen>>,
     pub version_helper: Rc<Box<VersionHelper>>,
+    /// How classes are modelled.
+    pub model: Model,
+    /// Names of the methods used to decode and encode objects.
+    pub codec: Codec,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -145,6 +158,8 @@ impl Options {
             build_constructor: true,
             service_generators: Vec::new(),
             version_helper: Rc::new(Box::new(Python3VersionHelper {})),
+            model: Model::Plain,
+            codec: Codec::default(),
         }
     }
 }
@@ -169,10 +184,37 @@ pub fn setup_options(modules: Vec<PythonModule>) -> Result<Options> {
 
     let mut options = Options::new();
 
+    let mut python2 = false;
+    let mut dataclasses = false;
+    let mut pydantic = false;
+
+    for module in &modules {
+        match *module {
+            Python2(_) => python2 = true,
+            Dataclasses(_) => dataclasses = true,
+            Pydantic(_) => pydantic = true,
+            _ => {}
+        }
+    }
+
+    if dataclasses && pydantic {
+        return Err("the `dataclasses` and `pydantic` modules can't be used together".into());
+    }
+
+    if python2 && dataclasses {
+        return Err("the `dataclasses` module requires Python 3, and can't use `python2`".into());
+    }
+
+    if python2 && pydantic {
+        return Err("the `pydantic` module requires Python 3, and can't use `python2`".into());
+    }
+
     for module in modules {
         let initializer: Box<Initializer<Options = Options>> = match module {
             Requests(config) => Box::new(module::Requests::new(config)),
             Python2(config) => Box::new(module::Python2::new(config)),
+            Dataclasses(config) => Box::new(module::Dataclasses::new(config)),
+            Pydantic(config) => Box::new(module::Pydantic::new(config)),
         };
 
         initializer.initialize(&mut options)?;
@@ -188,7 +230,11 @@ fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) ->
     let packages = session.packages()?;
 
     let helper = options.version_helper.clone();
-    let translator = session.translator(flavored::PythonFlavorTranslator::new(packages, helper))?;
+    let translator = session.translator(flavored::PythonFlavorTranslator::new(
+        packages,
+        helper,
+        options.codec,
+    ))?;
 
     // NOTE: avoid doing translation.
     let mut diag = Diagnostics::new(Source::empty("no diagnostics"));
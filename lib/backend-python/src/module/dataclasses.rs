@@ -0,0 +1,35 @@
+//! Module that generates `@dataclass` classes with type annotations.
+
+use backend::Initializer;
+use core::errors::Result;
+use utils::{Codec, Model};
+use Options;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {}
+
+pub struct Module {
+    #[allow(dead_code)]
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.model = Model::Dataclasses;
+
+        options.codec = Codec {
+            decode: "from_dict",
+            encode: "to_dict",
+        };
+
+        Ok(())
+    }
+}
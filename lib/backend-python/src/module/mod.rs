@@ -1,5 +1,9 @@
+mod dataclasses;
+mod pydantic;
 mod python2;
 mod requests;
 
+pub use self::dataclasses::{Config as DataclassesConfig, Module as Dataclasses};
+pub use self::pydantic::{Config as PydanticConfig, Module as Pydantic};
 pub use self::python2::{Config as Python2Config, Module as Python2};
 pub use self::requests::{Config as RequestsConfig, Module as Requests};
@@ -0,0 +1,36 @@
+//! Module that generates pydantic models.
+
+use backend::Initializer;
+use core::errors::Result;
+use utils::{Codec, Model};
+use Options;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {}
+
+pub struct Module {
+    #[allow(dead_code)]
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.model = Model::Pydantic;
+
+        // NB: decoding is provided by pydantic through `parse_obj`.
+        options.codec = Codec {
+            decode: "parse_obj",
+            encode: "to_dict",
+        };
+
+        Ok(())
+    }
+}
@@ -1,19 +1,38 @@
-//! Module that adds fasterxml annotations to generated classes.
+//! Module that generates HTTP clients for services, using either requests or httpx.
 
 use backend::Initializer;
 use codegen::{ServiceAdded, ServiceCodegen};
 use core;
 use core::errors::Result;
+use flavored::RpHttpParam;
 use genco::python::imported;
-use genco::{Python, Quoted, Tokens};
-use utils::{BlockComment, IfNoneRaise, IfNoneThen};
+use genco::{IntoTokens, Python, Quoted, Tokens};
+use utils::{BlockComment, EndpointComment, IfNoneRaise, IfNoneThen};
 use Options;
 
+/// Library used by generated clients.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Client {
+    /// Blocking client using `requests`.
+    Requests,
+    /// Async client using `httpx`.
+    Httpx,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::Requests
+    }
+}
+
 #[derive(Debug, Default, Deserialize)]
-pub struct Config {}
+pub struct Config {
+    #[serde(default)]
+    client: Client,
+}
 
 pub struct Module {
-    #[allow(dead_code)]
     config: Config,
 }
 
@@ -24,13 +43,33 @@ impl Module {
 }
 
 struct RequestsServiceCodegen {
-    requests: Python<'static>,
+    client: Client,
+    module: Python<'static>,
 }
 
 impl RequestsServiceCodegen {
-    pub fn new() -> RequestsServiceCodegen {
-        Self {
-            requests: imported("requests"),
+    pub fn new(client: Client) -> RequestsServiceCodegen {
+        let module = match client {
+            Client::Requests => imported("requests"),
+            Client::Httpx => imported("httpx"),
+        };
+
+        Self { client, module }
+    }
+
+    /// Suffix of the generated class.
+    fn suffix(&self) -> &'static str {
+        match self.client {
+            Client::Requests => "_Requests",
+            Client::Httpx => "_Httpx",
+        }
+    }
+
+    /// Session used if none is provided.
+    fn default_session<'el>(&self) -> Tokens<'el, Python<'el>> {
+        match self.client {
+            Client::Requests => toks![self.module.clone()],
+            Client::Httpx => toks![self.module.clone(), ".AsyncClient()"],
         }
     }
 }
@@ -42,10 +81,19 @@ impl ServiceCodegen for RequestsServiceCodegen {
             body, type_body, ..
         }: ServiceAdded,
     ) -> Result<()> {
-        type_body.push(toks!["class ", &body.name, "_Requests:"]);
+        let is_async = match self.client {
+            Client::Requests => false,
+            Client::Httpx => true,
+        };
+
+        type_body.push(toks!["class ", &body.name, self.suffix(), ":"]);
         type_body.nested({
             let mut t = Tokens::new();
 
+            if !body.comment.is_empty() || body.deprecated.is_some() {
+                t.push(BlockComment(&body.comment, &body.deprecated));
+            }
+
             t.push({
                 let mut args = Tokens::new();
                 args.append("self");
@@ -58,7 +106,7 @@ impl ServiceCodegen for RequestsServiceCodegen {
                     args.append("url");
                 }
 
-                args.append(toks!["session=", self.requests.clone()]);
+                args.append(toks!["session=", self.default_session()]);
 
                 let mut t = Tokens::new();
 
@@ -75,7 +123,7 @@ impl ServiceCodegen for RequestsServiceCodegen {
                     }
 
                     t.push(toks!["session = kw.pop(", "session".quoted(), ", None)"]);
-                    t.push(IfNoneThen("session", self.requests.clone()));
+                    t.push(IfNoneThen("session", self.default_session()));
 
                     t.push({
                         let mut t = Tokens::new();
@@ -142,8 +190,10 @@ impl ServiceCodegen for RequestsServiceCodegen {
                     args.append("self");
                     args.extend(e.arguments.iter().map(|a| a.safe_ident().into()));
 
-                    t.push(toks!["def ", e.safe_ident(), "(", args.join(", "), "):"]);
-                    t.nested(BlockComment(&e.comment));
+                    let def = if is_async { "async def " } else { "def " };
+
+                    t.push(toks![def, e.safe_ident(), "(", args.join(", "), "):"]);
+                    t.nested(EndpointComment(e));
 
                     t.nested({
                         let mut t = Tokens::new();
@@ -160,15 +210,50 @@ impl ServiceCodegen for RequestsServiceCodegen {
                             args.append("url");
                         };
 
-                        if let Some(ref body) = e.http.body {
-                            args.append(toks!["json=", body.safe_ident(), ".encode()"]);
+                        if !e.http.query.is_empty() {
+                            args.append(toks!["params=", Dict(&[], &e.http.query)]);
+                        }
+
+                        let body = e.http.body.as_ref().or(e.request.as_ref());
+
+                        let mut headers = Vec::new();
+
+                        if body.is_some() {
+                            headers.push(("Content-Type", e.http.accepts.as_str()));
+                        }
+
+                        if e.response.is_some() {
+                            headers.push(("Accept", e.http.produces.as_str()));
                         }
 
-                        t.push(toks!["r = self.session.request(", args.join(", "), ")"]);
+                        if !headers.is_empty() || !e.http.headers.is_empty() {
+                            args.append(toks!["headers=", Dict(&headers, &e.http.headers)]);
+                        }
+
+                        if let Some(body) = body {
+                            let var = toks![body.safe_ident()];
+
+                            match e.http.accepts {
+                                core::RpAccept::Json => {
+                                    args.append(toks!["json=", body.channel.ty().encode(var)]);
+                                }
+                                core::RpAccept::Text => {
+                                    args.append(toks!["data=", var]);
+                                }
+                            }
+                        }
+
+                        let call = toks!["self.session.request(", args.join(", "), ")"];
+
+                        if is_async {
+                            t.push(toks!["r = await ", call]);
+                        } else {
+                            t.push(toks!["r = ", call]);
+                        }
                         t.push(toks!["r.raise_for_status()"]);
 
                         if let Some(res) = e.response.as_ref() {
-                            match e.http.accept {
+                            match e.http.produces {
                                 core::RpAccept::Json => {
                                     push!(t, "data = r.json()");
 
@@ -198,13 +283,33 @@ impl ServiceCodegen for RequestsServiceCodegen {
     }
 }
 
+/// A dict literal, with the given fixed entries and the string values of the given parameters.
+struct Dict<'a, 'el>(&'a [(&'static str, &'static str)], &'el [RpHttpParam]);
+
+impl<'a, 'el> IntoTokens<'el, Python<'el>> for Dict<'a, 'el> {
+    fn into_tokens(self) -> Tokens<'el, Python<'el>> {
+        let mut entries = Tokens::new();
+
+        for &(name, value) in self.0 {
+            entries.append(toks![name.quoted(), ": ", value.quoted()]);
+        }
+
+        for p in self.1 {
+            let value = toks!["str(", p.argument.safe_ident(), ")"];
+            entries.append(toks![p.name.as_str().quoted(), ": ", value]);
+        }
+
+        toks!["{", entries.join(", "), "}"]
+    }
+}
+
 impl Initializer for Module {
     type Options = Options;
 
     fn initialize(&self, options: &mut Options) -> Result<()> {
         options
             .service_generators
-            .push(Box::new(RequestsServiceCodegen::new()));
+            .push(Box::new(RequestsServiceCodegen::new(self.config.client)));
 
         Ok(())
     }
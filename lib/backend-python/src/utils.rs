@@ -1,16 +1,64 @@
+use core::RpDeprecated;
+use flavored::RpEndpoint;
 use genco::{Cons, Element, IntoTokens, Python, Quoted, Tokens};
 use std::fmt;
 
-pub struct BlockComment<'el>(pub &'el [String]);
+pub struct BlockComment<'el>(pub &'el [String], pub &'el Option<RpDeprecated>);
 
 impl<'el> IntoTokens<'el, Python<'el>> for BlockComment<'el> {
     fn into_tokens(self) -> Tokens<'el, Python<'el>> {
-        let c: Tokens<'el, Python<'el>> = self
+        let mut c: Tokens<'el, Python<'el>> = self
             .0
             .iter()
             .map(|c| Element::Literal(c.as_str().into()))
             .collect();
 
+        c.append_unless_empty(Deprecated(self.1));
+
+        let mut toks = Tokens::new();
+
+        toks.push("\"\"\"");
+        toks.push(c.join(Element::Line));
+        toks.push("\"\"\"");
+
+        toks
+    }
+}
+
+/// Docstring of an endpoint, which documents its arguments in an `Args:` section.
+pub struct EndpointComment<'el>(pub &'el RpEndpoint);
+
+impl<'el> IntoTokens<'el, Python<'el>> for EndpointComment<'el> {
+    fn into_tokens(self) -> Tokens<'el, Python<'el>> {
+        let e = self.0;
+
+        let mut c: Tokens<'el, Python<'el>> = e
+            .comment
+            .iter()
+            .map(|c| Element::Literal(c.as_str().into()))
+            .collect();
+
+        let arguments = e
+            .arguments
+            .iter()
+            .filter(|a| !a.comment.is_empty())
+            .collect::<Vec<_>>();
+
+        if !arguments.is_empty() {
+            if !c.is_empty() {
+                c.append("");
+            }
+
+            c.append("Args:");
+
+            for a in arguments {
+                let line = format!("    {}: {}", a.safe_ident(), a.comment.join(" "));
+                c.append(Cons::from(line));
+            }
+        }
+
+        c.append_unless_empty(Deprecated(&e.deprecated));
+
         let mut toks = Tokens::new();
 
         toks.push("\"\"\"");
@@ -21,6 +69,23 @@ impl<'el> IntoTokens<'el, Python<'el>> for BlockComment<'el> {
     }
 }
 
+/// Deprecation notice for docstrings, which is empty unless something is deprecated.
+pub struct Deprecated<'el>(pub &'el Option<RpDeprecated>);
+
+impl<'el> IntoTokens<'el, Python<'el>> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Python<'el>> {
+        let deprecated = match *self.0 {
+            Some(ref deprecated) => deprecated,
+            None => return Tokens::new(),
+        };
+
+        match deprecated.note() {
+            Some(note) => toks!["Deprecated: ", note],
+            None => toks!["Deprecated."],
+        }
+    }
+}
+
 pub struct IfNoneThen<C, D>(pub C, pub D);
 
 impl<'el, C, D> IntoTokens<'el, Python<'el>> for IfNoneThen<C, D>
@@ -79,3 +144,32 @@ pub trait VersionHelper: fmt::Debug {
     /// In Python 2, strings would be `unicode` objects.
     fn is_string<'el>(&self, var: Cons<'el>) -> Tokens<'el, Python<'el>>;
 }
+
+/// How classes for declarations are modelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// Plain classes with constructors and getters.
+    Plain,
+    /// `@dataclass` classes with type annotations.
+    Dataclasses,
+    /// pydantic `BaseModel` subclasses with validated fields.
+    Pydantic,
+}
+
+/// Names of the methods used to decode and encode generated objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Codec {
+    /// Static method which decodes an object from its JSON representation.
+    pub decode: &'static str,
+    /// Method which encodes an object into its JSON representation.
+    pub encode: &'static str,
+}
+
+impl Default for Codec {
+    fn default() -> Self {
+        Codec {
+            decode: "decode",
+            encode: "encode",
+        }
+    }
+}
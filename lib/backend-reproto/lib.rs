@@ -12,13 +12,17 @@ extern crate toml;
 
 use core::errors::Result;
 use core::flavored::{
-    RpDecl, RpEndpoint, RpEnumBody, RpField, RpInterfaceBody, RpServiceBody, RpTupleBody,
-    RpTypeBody, RpVariantRef,
+    RpChannel, RpCode, RpDecl, RpEndpoint, RpEndpointHttp, RpEnumBody, RpField, RpFile,
+    RpHttpParam, RpInterfaceBody, RpName, RpNewtypeBody, RpPathSpec, RpServiceBody, RpTupleBody,
+    RpType, RpTypeBody, RpUnionBody, RpVariantRef, RpVersionedPackage,
+};
+use core::{
+    CoreFlavor, Handle, Loc, RelativePathBuf, RpAccept, RpDeprecated, RpStringValidate, DEFAULT_TAG,
 };
-use core::{CoreFlavor, Handle, RelativePathBuf, DEFAULT_TAG};
 use genco::{Custom, Formatter, IntoTokens, IoFmt, Quoted, Tokens, WriteTokens};
 use manifest::{Lang, Manifest, NoModule, TryFromToml};
 use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{self, Write};
 use std::path::Path;
 use trans::Session;
@@ -46,6 +50,235 @@ where
     }
 }
 
+pub struct PackageComments<'el, S: 'el>(&'el [S]);
+
+impl<'el, S> IntoTokens<'el, Reproto> for PackageComments<'el, S>
+where
+    S: AsRef<str>,
+{
+    fn into_tokens(self) -> Tokens<'el, Reproto> {
+        let mut t = Tokens::new();
+
+        for line in self.0 {
+            let line = line.as_ref();
+
+            if line.is_empty() {
+                t.push("//!");
+            } else {
+                t.push(toks!["//! ", line]);
+            }
+        }
+
+        t
+    }
+}
+
+pub struct Deprecated<'el>(&'el Option<RpDeprecated>);
+
+impl<'el> IntoTokens<'el, Reproto> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Reproto> {
+        let deprecated = match *self.0 {
+            Some(ref deprecated) => deprecated,
+            None => return Tokens::new(),
+        };
+
+        match deprecated.note() {
+            Some(note) => toks!["#[deprecated(note = ", note.quoted(), ")]"],
+            None => toks!["#[deprecated]"],
+        }
+    }
+}
+
+pub struct Validate<'el>(&'el RpStringValidate);
+
+impl<'el> IntoTokens<'el, Reproto> for Validate<'el> {
+    fn into_tokens(self) -> Tokens<'el, Reproto> {
+        match self.0.pattern {
+            Some(ref pattern) => toks!["#[validate(pattern = ", pattern.to_string().quoted(), ")]"],
+            None => Tokens::new(),
+        }
+    }
+}
+
+/// Prefixes of the packages imported by a single file.
+///
+/// Imports are pinned to the exact version that they resolved to. Each imported package is
+/// prefixed by its last part, unless that is ambiguous in which case the whole package and its
+/// version is used, like `bar_1_0_0`.
+pub struct Imports {
+    prefixes: BTreeMap<RpVersionedPackage, String>,
+}
+
+impl Imports {
+    /// Collect the imports of every package referenced from the given file.
+    pub fn new(package: &RpVersionedPackage, file: &RpFile) -> Imports {
+        let mut packages = BTreeSet::new();
+
+        for decl in file.for_each_decl() {
+            referenced_packages(decl, &mut packages);
+        }
+
+        packages.remove(package);
+
+        let mut counts = HashMap::new();
+
+        for p in &packages {
+            *counts.entry(p.package.last()).or_insert(0) += 1;
+        }
+
+        let prefixes = packages
+            .iter()
+            .map(|p| {
+                let prefix = match p.package.last() {
+                    Some(last) if counts[&Some(last)] == 1 => last.to_string(),
+                    _ => unique_prefix(p),
+                };
+
+                ((*p).clone(), prefix)
+            })
+            .collect();
+
+        return Imports { prefixes };
+
+        /// A prefix which is unique to the package and its version.
+        fn unique_prefix(package: &RpVersionedPackage) -> String {
+            let mut parts = package.package.parts().cloned().collect::<Vec<_>>();
+
+            if let Some(ref version) = package.version {
+                let version = version
+                    .to_string()
+                    .to_lowercase()
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+
+                parts.push(version);
+            }
+
+            parts.join("_")
+        }
+    }
+
+    /// The use declarations of the file, one for each imported package.
+    pub fn uses<'el>(&self) -> Tokens<'el, Reproto> {
+        let mut t = Tokens::new();
+
+        for (package, prefix) in &self.prefixes {
+            t.push_into(|t| {
+                t.append("use ");
+                t.append(package.package.to_string());
+
+                if let Some(ref version) = package.version {
+                    t.append(" ");
+                    t.append(format!("={}", version).quoted());
+                }
+
+                if package.package.last() != Some(prefix.as_str()) {
+                    t.append(" as ");
+                    t.append(prefix.clone());
+                }
+
+                t.append(";");
+            });
+        }
+
+        t
+    }
+
+    /// Format a name, prefixed by its package unless it belongs to the current file.
+    pub fn name(&self, name: &RpName) -> String {
+        let path = name.path.join("::");
+
+        match self.prefixes.get(&name.package) {
+            Some(prefix) => format!("{}::{}", prefix, path),
+            None => path,
+        }
+    }
+
+    /// Format a type.
+    pub fn ty(&self, ty: &RpType) -> String {
+        use core::RpType::*;
+
+        match *ty {
+            Name { ref name } => self.name(name),
+            Array { ref inner } => format!("[{}]", self.ty(inner)),
+            Set { ref inner } => format!("set<{}>", self.ty(inner)),
+            Map { ref key, ref value } => format!("{{{}: {}}}", self.ty(key), self.ty(value)),
+            ref ty => ty.to_string(),
+        }
+    }
+
+    /// Format a channel.
+    pub fn channel(&self, channel: &RpChannel) -> String {
+        if channel.is_streaming() {
+            format!("stream {}", self.ty(channel.ty()))
+        } else {
+            self.ty(channel.ty())
+        }
+    }
+}
+
+/// Collect every package referenced by types in the given declaration.
+fn referenced_packages<'a>(decl: &'a RpDecl, out: &mut BTreeSet<&'a RpVersionedPackage>) {
+    use core::RpDecl::*;
+
+    match *decl {
+        Type(ref body) => fields(&body.fields, out),
+        Tuple(ref body) => fields(&body.fields, out),
+        Interface(ref body) => {
+            fields(&body.fields, out);
+
+            for sub_type in &body.sub_types {
+                fields(&sub_type.fields, out);
+            }
+        }
+        Union(ref body) => {
+            for variant in &body.variants {
+                ty(&variant.ty, out);
+            }
+        }
+        Newtype(ref body) => ty(&body.ty, out),
+        Service(ref body) => {
+            for endpoint in &body.endpoints {
+                for argument in &endpoint.arguments {
+                    ty(argument.channel.ty(), out);
+                }
+
+                if let Some(ref response) = endpoint.response {
+                    ty(response.ty(), out);
+                }
+
+                for error in &endpoint.errors {
+                    ty(&error.ty, out);
+                }
+            }
+        }
+        Enum(..) => {}
+    }
+
+    fn fields<'a>(fields: &'a [Loc<RpField>], out: &mut BTreeSet<&'a RpVersionedPackage>) {
+        for field in fields {
+            ty(&field.ty, out);
+        }
+    }
+
+    fn ty<'a>(input: &'a RpType, out: &mut BTreeSet<&'a RpVersionedPackage>) {
+        use core::RpType::*;
+
+        match *input {
+            Name { ref name } => {
+                out.insert(&name.package);
+            }
+            Array { ref inner } | Set { ref inner } => ty(inner, out),
+            Map { ref key, ref value } => {
+                ty(key, out);
+                ty(value, out);
+            }
+            _ => {}
+        }
+    }
+}
+
 #[derive(Clone, Copy, Default, Debug)]
 pub struct ReprotoLang;
 
@@ -132,10 +365,15 @@ fn compile(handle: &Handle, env: Session<CoreFlavor>, _manifest: Manifest) -> Re
             path.with_extension("reproto")
         };
 
+        let imports = Imports::new(package, file);
+
         let mut body = Tokens::new();
 
+        body.push_unless_empty(PackageComments(&file.comment));
+        body.push_unless_empty(imports.uses());
+
         for decl in &file.decls {
-            body.push(format(decl)?);
+            body.push(format(&imports, decl)?);
         }
 
         let body = body.join_line_spacing();
@@ -148,32 +386,41 @@ fn compile(handle: &Handle, env: Session<CoreFlavor>, _manifest: Manifest) -> Re
 }
 
 /// Format a single declaration as a reproto specification.
-pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
+pub fn format<'el>(imports: &Imports, decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
     let result = match *decl {
-        core::RpDecl::Type(ref type_) => format_type(type_),
-        core::RpDecl::Interface(ref interface) => format_interface(interface),
-        core::RpDecl::Tuple(ref tuple) => format_tuple(tuple),
-        core::RpDecl::Enum(ref en) => format_enum(en),
-        core::RpDecl::Service(ref service) => format_service(service),
+        core::RpDecl::Type(ref type_) => format_type(imports, type_),
+        core::RpDecl::Interface(ref interface) => format_interface(imports, interface),
+        core::RpDecl::Tuple(ref tuple) => format_tuple(imports, tuple),
+        core::RpDecl::Enum(ref en) => format_enum(imports, en),
+        core::RpDecl::Service(ref service) => format_service(imports, service),
+        core::RpDecl::Union(ref union) => format_union(imports, union),
+        core::RpDecl::Newtype(ref newtype) => format_newtype(imports, newtype),
     };
 
     return result;
 
-    fn format_type<'el>(body: &'el RpTypeBody) -> Result<Tokens<'el, Reproto>> {
+    fn format_type<'el>(imports: &Imports, body: &'el RpTypeBody) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
         t.push(toks!["type ", body.ident.as_str(), " {"]);
 
         t.nested({
             let mut t = Tokens::new();
 
+            t.push_unless_empty(format_reserved(&body.reserved));
+
             for f in &body.fields {
-                t.push(format_field(f)?);
+                t.push(format_field(imports, f)?);
+            }
+
+            for c in &body.codes {
+                t.push(format_code(c));
             }
 
             for d in &body.decls {
-                t.push(format(d)?);
+                t.push(format(imports, d)?);
             }
 
             t.join_line_spacing()
@@ -184,9 +431,15 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
         Ok(t)
     }
 
-    fn format_interface<'el>(body: &'el RpInterfaceBody) -> Result<Tokens<'el, Reproto>> {
+    fn format_interface<'el>(
+        imports: &Imports,
+        body: &'el RpInterfaceBody,
+    ) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
 
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+
         match body.sub_type_strategy {
             core::RpSubTypeStrategy::Tagged { ref tag, .. } => {
                 if tag != DEFAULT_TAG {
@@ -204,16 +457,28 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
             }
         }
 
-        t.push_unless_empty(Comments(&body.comment));
         t.push(toks!["interface ", body.ident.as_str(), " {"]);
 
         t.nested({
             let mut t = Tokens::new();
 
+            t.push_unless_empty(format_reserved(&body.reserved));
+
+            for f in &body.fields {
+                t.push(format_field(imports, f)?);
+            }
+
+            for c in &body.codes {
+                t.push(format_code(c));
+            }
+
             for sub_type in body.sub_types.iter() {
                 t.push({
                     let mut t = Tokens::new();
 
+                    t.push_unless_empty(Comments(&sub_type.comment));
+                    t.push_unless_empty(Deprecated(&sub_type.deprecated));
+
                     if let Some(ref alias) = sub_type.sub_type_name {
                         t.push(toks![
                             sub_type.ident.as_str(),
@@ -228,12 +493,18 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
                     t.nested({
                         let mut t = Tokens::new();
 
+                        t.push_unless_empty(format_reserved(&sub_type.reserved));
+
                         for f in &sub_type.fields {
-                            t.push(format_field(f)?);
+                            t.push(format_field(imports, f)?);
+                        }
+
+                        for c in &sub_type.codes {
+                            t.push(format_code(c));
                         }
 
                         for d in &sub_type.decls {
-                            t.push(format(d)?);
+                            t.push(format(imports, d)?);
                         }
 
                         t.join_line_spacing()
@@ -246,7 +517,7 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
             }
 
             for d in &body.decls {
-                t.push(format(d)?);
+                t.push(format(imports, d)?);
             }
 
             t.join_line_spacing()
@@ -257,21 +528,29 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
         Ok(t)
     }
 
-    fn format_tuple<'el>(body: &'el RpTupleBody) -> Result<Tokens<'el, Reproto>> {
+    fn format_tuple<'el>(
+        imports: &Imports,
+        body: &'el RpTupleBody,
+    ) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
         t.push(toks!["tuple ", body.ident.as_str(), " {"]);
 
         t.nested({
             let mut t = Tokens::new();
 
             for f in &body.fields {
-                t.push(format_field(f)?);
+                t.push(format_field(imports, f)?);
+            }
+
+            for c in &body.codes {
+                t.push(format_code(c));
             }
 
             for d in &body.decls {
-                t.push(format(d)?);
+                t.push(format(imports, d)?);
             }
 
             t.join_line_spacing()
@@ -282,10 +561,53 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
         Ok(t)
     }
 
-    fn format_enum<'el>(body: &'el RpEnumBody) -> Result<Tokens<'el, Reproto>> {
+    fn format_union<'el>(
+        imports: &Imports,
+        body: &'el RpUnionBody,
+    ) -> Result<Tokens<'el, Reproto>> {
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+
+        let variants = body
+            .variants
+            .iter()
+            .map(|v| imports.ty(&v.ty))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        t.push(toks!["type ", body.ident.as_str(), " = ", variants, ";"]);
+
+        Ok(t)
+    }
+
+    fn format_newtype<'el>(
+        imports: &Imports,
+        body: &'el RpNewtypeBody,
+    ) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+        t.push_unless_empty(Validate(&body.validate));
+
+        t.push(toks![
+            "type ",
+            body.ident.as_str(),
+            "(",
+            imports.ty(&body.ty),
+            ");"
+        ]);
+
+        Ok(t)
+    }
+
+    fn format_enum<'el>(imports: &Imports, body: &'el RpEnumBody) -> Result<Tokens<'el, Reproto>> {
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
 
         t.push(toks![
             "enum ",
@@ -302,6 +624,14 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
                 t.push(format_variant(v)?);
             }
 
+            for c in &body.codes {
+                t.push(format_code(c));
+            }
+
+            for d in &body.decls {
+                t.push(format(imports, d)?);
+            }
+
             t.join_line_spacing()
         });
 
@@ -310,17 +640,29 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
         Ok(t)
     }
 
-    fn format_service<'el>(body: &'el RpServiceBody) -> Result<Tokens<'el, Reproto>> {
+    fn format_service<'el>(
+        imports: &Imports,
+        body: &'el RpServiceBody,
+    ) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+
+        if let Some(ref url) = body.http.url {
+            t.push(toks!["#[http(url = ", url.as_str().quoted(), ")]"]);
+        }
+
         t.push(toks!["service ", body.ident.as_str(), " {"]);
 
         t.nested({
             let mut t = Tokens::new();
 
             for e in &body.endpoints {
-                t.push_unless_empty(Comments(&e.comment));
-                t.push(format_endpoint(e)?);
+                t.push(format_endpoint(imports, e)?);
+            }
+
+            for d in &body.decls {
+                t.push(format(imports, d)?);
             }
 
             t.join_line_spacing()
@@ -330,38 +672,263 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
 
         return Ok(t);
 
-        fn format_endpoint<'el>(e: &'el RpEndpoint) -> Result<Tokens<'el, Reproto>> {
+        fn format_endpoint<'el>(
+            imports: &Imports,
+            e: &'el RpEndpoint,
+        ) -> Result<Tokens<'el, Reproto>> {
             let mut t = Tokens::new();
 
-            t.append(e.ident.as_str());
-            t.append("(");
-            t.append({
-                let mut t = Tokens::new();
+            t.push_unless_empty(Comments(&e.comment));
+            t.push_unless_empty(Deprecated(&e.deprecated));
+            t.push_unless_empty(format_http(&e.http));
 
-                for a in &e.arguments {
-                    t.append({
-                        let mut t = Tokens::new();
+            // documented arguments are written one per line, preceded by their comments.
+            if e.arguments.iter().any(|a| !a.comment.is_empty()) {
+                t.push(toks![e.ident.as_str(), "("]);
 
-                        t.append(a.ident.as_str());
-                        t.append(": ");
-                        t.append(a.channel.to_string());
+                t.nested({
+                    let mut t = Tokens::new();
+                    let mut it = e.arguments.iter().peekable();
 
-                        t
-                    });
-                }
+                    while let Some(a) = it.next() {
+                        t.push_unless_empty(Comments(&a.comment));
+
+                        let sep = if it.peek().is_some() { "," } else { "" };
+                        t.push(toks![
+                            a.ident.as_str(),
+                            ": ",
+                            imports.channel(&a.channel),
+                            sep
+                        ]);
+                    }
+
+                    t
+                });
+
+                t.push(toks![")", format_signature_end(imports, e)]);
+                return Ok(t);
+            }
+
+            t.push_into(|t| {
+                t.append(e.ident.as_str());
+                t.append("(");
+                t.append({
+                    let mut t = Tokens::new();
 
-                t.join(", ")
+                    for a in &e.arguments {
+                        t.append({
+                            let mut t = Tokens::new();
+
+                            t.append(a.ident.as_str());
+                            t.append(": ");
+                            t.append(imports.channel(&a.channel));
+
+                            t
+                        });
+                    }
+
+                    t.join(", ")
+                });
+                t.append(")");
+                t.append(format_signature_end(imports, e));
             });
-            t.append(")");
 
             Ok(t)
         }
+
+        /// Format everything following the arguments of an endpoint.
+        fn format_signature_end<'el>(
+            imports: &Imports,
+            e: &'el RpEndpoint,
+        ) -> Tokens<'el, Reproto> {
+            let mut t = Tokens::new();
+
+            if let Some(ref response) = e.response {
+                t.append(" -> ");
+                t.append(imports.channel(response));
+            }
+
+            if !e.errors.is_empty() {
+                t.append(" throws ");
+                t.append({
+                    let mut t = Tokens::new();
+
+                    for error in &e.errors {
+                        t.append(format!(
+                            "{}: {} @ {}",
+                            error.ident(),
+                            imports.ty(&error.ty),
+                            error.status
+                        ));
+                    }
+
+                    t.join(", ")
+                });
+            }
+
+            if let Some(ref name) = e.name {
+                t.append(" as ");
+                t.append(name.as_str().quoted());
+            }
+
+            t.append(";");
+            t
+        }
+
+        fn format_http<'el>(http: &'el RpEndpointHttp) -> Tokens<'el, Reproto> {
+            let mut args = Tokens::new();
+
+            if let Some(ref path) = http.path {
+                args.append(toks!["path = ", format_path(path).quoted()]);
+            }
+
+            if let Some(ref method) = http.method {
+                args.append(toks!["method = ", method.as_str().quoted()]);
+            }
+
+            if !http.query.is_empty() {
+                args.append(toks!["query = ", format_params(&http.query)]);
+            }
+
+            if !http.headers.is_empty() {
+                args.append(toks!["headers = ", format_params(&http.headers)]);
+            }
+
+            if http.accepts != RpAccept::default() {
+                args.append(toks!["accepts = ", http.accepts.as_str().quoted()]);
+            }
+
+            if http.produces != RpAccept::default() {
+                args.append(toks!["produces = ", http.produces.as_str().quoted()]);
+            }
+
+            if args.is_empty() {
+                return args;
+            }
+
+            toks!["#[http(", args.join(", "), ")]"]
+        }
+
+        /// Format a path, referencing variables by the identifier of their argument.
+        fn format_path(path: &RpPathSpec) -> String {
+            let mut out = String::new();
+
+            for step in &path.steps {
+                out.push('/');
+
+                for part in &step.parts {
+                    match *part {
+                        core::RpPathPart::Segment(ref segment) => out.push_str(segment),
+                        core::RpPathPart::Variable(ref var) => {
+                            out.push('{');
+                            out.push_str(var.ident());
+                            out.push('}');
+                        }
+                    }
+                }
+            }
+
+            out
+        }
+
+        /// Format parameters, like `(limit, (token, "X-Token"))`.
+        fn format_params<'el>(params: &'el [RpHttpParam]) -> Tokens<'el, Reproto> {
+            let mut t = Tokens::new();
+
+            for p in params {
+                if p.name == p.argument.ident() {
+                    t.append(p.argument.ident());
+                } else {
+                    t.append(toks![
+                        "(",
+                        p.argument.ident(),
+                        ", ",
+                        p.name.as_str().quoted(),
+                        ")"
+                    ]);
+                }
+            }
+
+            toks!["(", t.join(", "), ")"]
+        }
+    }
+
+    fn format_code<'el>(code: &'el RpCode) -> Tokens<'el, Reproto> {
+        use core::RpContext::*;
+
+        let mut t = Tokens::new();
+
+        let context = match code.context {
+            Csharp {} => "csharp",
+            Go {} => "go",
+            Java { ref imports } => {
+                if !imports.is_empty() {
+                    let mut args = Tokens::new();
+
+                    for import in imports {
+                        args.append(import.as_str().quoted());
+                    }
+
+                    t.push(toks!["#[import(", args.join(", "), ")]"]);
+                }
+
+                "java"
+            }
+            Js {} => "js",
+            Json {} => "json",
+            Python {} => "python",
+            Reproto {} => "reproto",
+            Rust {} => "rust",
+            Swift {} => "swift",
+        };
+
+        t.push(toks![context, " {{"]);
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            for line in &code.lines {
+                t.push(line.as_str());
+            }
+
+            t
+        });
+
+        t.push("}}");
+        t
+    }
+
+    fn format_reserved<'el>(reserved: &'el [Loc<String>]) -> Tokens<'el, Reproto> {
+        let mut t = Tokens::new();
+
+        if reserved.is_empty() {
+            return t;
+        }
+
+        t.append("reserved ");
+        t.append({
+            let mut t = Tokens::new();
+
+            for r in reserved {
+                t.append(r.as_str().quoted());
+            }
+
+            t.join(", ")
+        });
+        t.append(";");
+
+        t
     }
 
-    fn format_field<'el>(field: &'el RpField) -> Result<Tokens<'el, Reproto>> {
+    fn format_field<'el>(imports: &Imports, field: &'el RpField) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&field.comment));
+        t.push_unless_empty(Deprecated(&field.deprecated));
+
+        if let Some(id) = field.id {
+            t.push(toks!["#[id(", id.to_string(), ")]"]);
+        }
 
         let field_name = field.safe_ident();
 
@@ -372,10 +939,14 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
             None => field_name,
         };
 
+        if let core::RpType::String(ref string) = field.ty {
+            t.push_unless_empty(Validate(&string.validate));
+        }
+
         if field.is_optional() {
-            t.push(toks![field_name, "?: ", field.ty.to_string()]);
+            t.push(toks![field_name, "?: ", imports.ty(&field.ty)]);
         } else {
-            t.push(toks![field_name, ": ", field.ty.to_string()]);
+            t.push(toks![field_name, ": ", imports.ty(&field.ty)]);
         }
 
         if let Some(ref field_as) = field.field_as {
@@ -391,6 +962,7 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&variant.comment));
+        t.push_unless_empty(Deprecated(variant.deprecated));
 
         t.push_into(|t| {
             t.append(variant.ident());
@@ -406,6 +978,15 @@ pub fn format<'el>(decl: &'el RpDecl) -> Result<Tokens<'el, Reproto>> {
                 }
             }
 
+            for alias in variant.aliases.iter() {
+                t.append(", ");
+
+                match alias {
+                    core::RpVariantValue::String(string) => t.append(string.quoted()),
+                    core::RpVariantValue::Number(number) => t.append(number.to_string()),
+                }
+            }
+
             t.append(";");
         });
 
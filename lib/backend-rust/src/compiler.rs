@@ -1,11 +1,11 @@
 //! Backend for Rust
 
-use backend::PackageProcessor;
+use backend::{PackageProcessor, UNKNOWN_VARIANT};
 use core::errors::*;
 use core::{self, Handle, Loc, RelativePath, RelativePathBuf};
 use flavored::{
-    RpEnumBody, RpField, RpInterfaceBody, RpName, RpPackage, RpServiceBody, RpTupleBody,
-    RpTypeBody, RpVariant, RustFlavor,
+    Attributes, RpEnumBody, RpField, RpInterfaceBody, RpName, RpNewtypeBody, RpPackage,
+    RpServiceBody, RpTupleBody, RpTypeBody, RpUnionBody, RpVariant, RustFlavor,
 };
 use genco::rust;
 use genco::{Cons, IntoTokens, Quoted, Rust, Tokens};
@@ -14,8 +14,8 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::rc::Rc;
 use trans::{self, Translated};
-use utils::Comments;
-use {Options, Root, Service, EXT, LIB, MOD, TYPE_SEP};
+use utils::{Comments, Deprecated};
+use {Decl, Options, Root, Service, EXT, LIB, MOD, TYPE_SEP};
 
 /// #[allow(non_camel_case_types)] attribute.
 pub struct AllowNonCamelCaseTypes;
@@ -27,19 +27,54 @@ impl<'a> IntoTokens<'a, Rust<'a>> for AllowNonCamelCaseTypes {
 }
 
 /// Serializer derives.
-pub struct Derives;
+const DERIVES: &[&str] = &["Clone", "Debug", "PartialEq", "Serialize", "Deserialize"];
 
-impl<'a> IntoTokens<'a, Rust<'a>> for Derives {
-    fn into_tokens(self) -> Tokens<'a, Rust<'a>> {
-        "#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]".into()
+/// Derives of numeric enums.
+const ENUM_DERIVES: &[&str] = &["Clone", "Copy", "Debug", "PartialEq", "Eq"];
+
+/// Derive the given traits.
+///
+/// Extra traits to derive can be requested with the `#[rust(derive = (<trait>, ...))]` attribute.
+pub struct Derives(Vec<String>);
+
+impl Derives {
+    pub fn new(derives: &[&str], attributes: &Attributes) -> Result<Derives> {
+        let mut out = derives.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+
+        let derive = match attributes.selection("rust").and_then(|s| s.get("derive")) {
+            Some(derive) => derive,
+            None => return Ok(Derives(out)),
+        };
+
+        // NB: a single trait doesn't have to be wrapped in a list.
+        let values = match *Loc::borrow(derive) {
+            core::RpValue::Array(ref values) => values.iter().map(Loc::borrow).collect(),
+            ref value => vec![value],
+        };
+
+        for value in values {
+            match *value {
+                core::RpValue::Identifier(ref derive) => {
+                    if !out.contains(derive) {
+                        out.push(derive.to_string());
+                    }
+                }
+                ref value => {
+                    return Err(format!(
+                        "#[rust(derive = ..)]: expected a trait like `Hash`, but got `{}`",
+                        value
+                    ).into());
+                }
+            }
+        }
+
+        Ok(Derives(out))
     }
 }
 
-pub struct EnumDerives;
-
-impl<'a> IntoTokens<'a, Rust<'a>> for EnumDerives {
+impl<'a> IntoTokens<'a, Rust<'a>> for Derives {
     fn into_tokens(self) -> Tokens<'a, Rust<'a>> {
-        "#[derive(Clone, Copy, Debug, PartialEq, Eq)]".into()
+        toks![format!("#[derive({})]", self.0.join(", "))]
     }
 }
 
@@ -52,6 +87,15 @@ impl<'a> IntoTokens<'a, Rust<'a>> for Rename<'a> {
     }
 }
 
+/// A serde alias annotation.
+pub struct Alias<'a>(&'a str);
+
+impl<'a> IntoTokens<'a, Rust<'a>> for Alias<'a> {
+    fn into_tokens(self) -> Tokens<'a, Rust<'a>> {
+        toks!["#[serde(alias = ", self.0.quoted(), ")]"]
+    }
+}
+
 /// Tag attribute.
 pub struct Tag<'a>(&'a str);
 
@@ -137,7 +181,13 @@ impl<'el> Compiler<'el> {
         match_decl.nested(match_body);
         match_decl.push("}");
 
-        push!(value_fn, "pub fn value(&self) -> ", body.enum_type, " {");
+        // unknown string values are owned by the variant.
+        let value_type = match body.variants {
+            core::RpVariants::String { .. } if self.options.unknown_variant => toks!["&str"],
+            _ => toks![body.enum_type.clone()],
+        };
+
+        push!(value_fn, "pub fn value(&self) -> ", value_type, " {");
         value_fn.nested(toks!["use self::", name, "::*;"]);
         value_fn.nested(match_decl);
         value_fn.push("}");
@@ -160,6 +210,23 @@ impl<'el> Compiler<'el> {
             t.push(Rename(field.name()));
         }
 
+        // NB: encoded through the `serde_with` crate, which has to be a dependency of the crate.
+        if field.is_number_as_string() {
+            if field.is_optional() {
+                t.push(toks![
+                    "#[serde(default, with = ",
+                    "::serde_with::As::<Option<::serde_with::DisplayFromStr>>".quoted(),
+                    ")]",
+                ]);
+            } else {
+                t.push(toks![
+                    "#[serde(with = ",
+                    "::serde_with::As::<::serde_with::DisplayFromStr>".quoted(),
+                    ")]",
+                ]);
+            }
+        }
+
         t.push_into(|t| {
             if is_pub {
                 t.append("pub ");
@@ -174,8 +241,28 @@ impl<'el> Compiler<'el> {
     pub fn compile(&self) -> Result<()> {
         let mut files = self.populate_files()?;
 
+        for decl in self.env.decl_iter() {
+            let (name, _) = self.convert_type_name(decl.name());
+
+            let out = files
+                .entry(decl.name().package.clone())
+                .or_insert_with(RustFileSpec::default);
+
+            for g in &self.options.decl {
+                g.generate(Decl {
+                    decl,
+                    container: &mut out.0,
+                    name: name.clone(),
+                    unknown_variant: self.options.unknown_variant,
+                })?;
+            }
+        }
+
         for g in &self.options.root {
-            g.generate(Root { files: &mut files })?;
+            g.generate(Root {
+                files: &mut files,
+                datetime: self.options.datetime.as_ref(),
+            })?;
         }
 
         self.write_mod_files(&files)?;
@@ -282,8 +369,9 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
         t.push_unless_empty(attributes);
-        t.push(Derives);
+        t.push(Derives::new(DERIVES, &body.attributes)?);
         t.push(toks!["pub struct ", name, "(", fields.join(", "), ");",]);
 
         out.0.push(t);
@@ -302,13 +390,14 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
             // TODO: commented out, see: https://github.com/rust-lang/rust/issues/49973
             // enable through option?
             // attributes.push(Repr(body.enum_type.clone()));
-            attributes.push(EnumDerives);
+            attributes.push(Derives::new(ENUM_DERIVES, &body.attributes)?);
         } else {
-            attributes.push(Derives);
+            attributes.push(Derives::new(DERIVES, &body.attributes)?);
         }
 
         for v in body.variants.iter() {
             vars.push_unless_empty(Comments(&v.comment));
+            vars.push_unless_empty(Deprecated(v.deprecated));
 
             match v.value {
                 core::RpVariantValue::String(string) => {
@@ -316,6 +405,12 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                         vars.push(Rename(string));
                     }
 
+                    for alias in v.aliases.iter() {
+                        if let core::RpVariantValue::String(alias) = alias {
+                            vars.push(Alias(alias));
+                        }
+                    }
+
                     push!(vars, v.ident(), ",");
                     push!(match_body, v.ident(), " => ", string.quoted(), ",");
                 }
@@ -326,10 +421,33 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
             }
         }
 
+        if self.options.unknown_variant {
+            if body.variants.iter().any(|v| v.ident() == UNKNOWN_VARIANT) {
+                return Err(format!(
+                    "{}: variant `{}` conflicts with the generated unknown variant",
+                    body.name, UNKNOWN_VARIANT
+                ).into());
+            }
+
+            match body.variants {
+                core::RpVariants::String { .. } => {
+                    vars.push(Untagged);
+                    push!(vars, UNKNOWN_VARIANT, "(String),");
+                    let value = "(ref value) => value.as_str(),";
+                    push!(match_body, UNKNOWN_VARIANT, value);
+                }
+                core::RpVariants::Number { .. } => {
+                    push!(vars, UNKNOWN_VARIANT, "(", body.enum_type, "),");
+                    push!(match_body, UNKNOWN_VARIANT, "(value) => value,");
+                }
+            }
+        }
+
         out.0.push({
             let mut t = Tokens::new();
 
             t.push_unless_empty(Comments(&body.comment));
+            t.push_unless_empty(Deprecated(&body.deprecated));
             t.push_unless_empty(attributes);
             t.push(toks!["pub enum ", name.clone(), " {"]);
             t.nested(vars);
@@ -357,8 +475,11 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
 
         // Serialize impl for numerics.
         if let core::RpVariants::Number { ref variants } = body.variants {
-            out.0.push(numeric_serialize(body, &name, variants));
-            out.0.push(numeric_deserialize(body, &name, variants));
+            let unknown = self.options.unknown_variant;
+            let serialize = numeric_serialize(body, &name, variants, unknown);
+            let deserialize = numeric_deserialize(body, &name, variants, unknown);
+            out.0.push(serialize);
+            out.0.push(deserialize);
         }
 
         return Ok(());
@@ -368,6 +489,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
             body: &'el RpEnumBody,
             name: &Rc<String>,
             variants: &'el Vec<Loc<RpVariant<T>>>,
+            unknown: bool,
         ) -> Tokens<'el, Rust<'el>>
         where
             T: fmt::Display,
@@ -396,6 +518,10 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                         nested!(t, v.ident(), " => ", v.value.to_string(), *ty, ",");
                     }
 
+                    if unknown {
+                        nested!(t, UNKNOWN_VARIANT, "(value) => value,");
+                    }
+
                     push!(t, "};");
                 });
 
@@ -413,6 +539,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
             body: &'el RpEnumBody,
             name: &Rc<String>,
             variants: &'el Vec<Loc<RpVariant<T>>>,
+            unknown: bool,
         ) -> Tokens<'el, Rust<'el>>
         where
             T: fmt::Display,
@@ -437,7 +564,9 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                 t.nested({
                     let mut t = Tokens::new();
 
-                    t.push(numeric_visitor_deserialize(body, name, variants, "Visitor"));
+                    t.push(numeric_visitor_deserialize(
+                        body, name, variants, unknown, "Visitor",
+                    ));
                     push!(t, "d.deserialize_", body.enum_type, "(Visitor)");
 
                     t.join_line_spacing()
@@ -456,6 +585,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
             body: &'el RpEnumBody,
             parent: &Rc<String>,
             variants: &'el Vec<Loc<RpVariant<T>>>,
+            unknown: bool,
             name: &'el str,
         ) -> Tokens<'el, Rust<'el>>
         where
@@ -475,7 +605,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                 push!(t, "type Value = ", *parent, ";");
 
                 t.push(numeric_expecting(parent, variants));
-                t.push(numeric_visit(&body.enum_type, parent, variants));
+                t.push(numeric_visit(&body.enum_type, parent, variants, unknown));
 
                 if body.enum_type == rust::local("i32") {
                     t.push(forward(&rust::local("i64"), &body.enum_type, parent));
@@ -528,6 +658,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
             ty: &'el Rust<'static>,
             parent: &Rc<String>,
             variants: &'el Vec<Loc<RpVariant<T>>>,
+            unknown: bool,
         ) -> Tokens<'el, Rust<'el>>
         where
             T: fmt::Display,
@@ -547,16 +678,26 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                 for v in variants {
                     let val = v.value.to_string();
                     nested!(t, val, *ty, " => Ok(", *parent, "::", v.ident(), "),");
+
+                    for alias in &v.aliases {
+                        let val = alias.to_string();
+                        nested!(t, val, *ty, " => Ok(", *parent, "::", v.ident(), "),");
+                    }
                 }
 
-                let fmt = format!("{}: unknown value: {{}}", parent.as_str());
-                let mut args = Tokens::new();
-                args.append(fmt.quoted());
-                args.append("value");
+                if unknown {
+                    let variant = toks![*parent, "::", UNKNOWN_VARIANT];
+                    nested!(t, "value => Ok(", variant, "(value)),");
+                } else {
+                    let fmt = format!("{}: unknown value: {{}}", parent.as_str());
+                    let mut args = Tokens::new();
+                    args.append(fmt.quoted());
+                    args.append("value");
 
-                let m = toks!["format!(", args.join(", "), ")"];
+                    let m = toks!["format!(", args.join(", "), ")"];
 
-                nested!(t, "value => Err(E::custom(", m, ")),");
+                    nested!(t, "value => Err(E::custom(", m, ")),");
+                }
 
                 push!(t, "}");
             });
@@ -591,8 +732,9 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
         t.push_unless_empty(attributes);
-        t.push(Derives);
+        t.push(Derives::new(DERIVES, &body.attributes)?);
         t.push(toks!["pub struct ", name.clone(), " {"]);
 
         // fields
@@ -603,6 +745,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                 t.push({
                     let mut t = Tokens::new();
                     t.push_unless_empty(Comments(&field.comment));
+                    t.push_unless_empty(Deprecated(&field.deprecated));
                     t.push(self.field_element(field, true)?);
                     t
                 });
@@ -631,8 +774,9 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
         t.push_unless_empty(attributes);
-        t.push(Derives);
+        t.push(Derives::new(DERIVES, &body.attributes)?);
 
         match body.sub_type_strategy {
             core::RpSubTypeStrategy::Tagged { ref tag, .. } => {
@@ -650,6 +794,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                 let mut t = Tokens::new();
 
                 t.push_unless_empty(Comments(&s.comment));
+                t.push_unless_empty(Deprecated(&s.deprecated));
 
                 // TODO: clone should not be needed
                 if let Some(ref name) = s.sub_type_name {
@@ -667,6 +812,7 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
                         t.nested({
                             let mut t = Tokens::new();
                             t.push_unless_empty(Comments(&field.comment));
+                            t.push_unless_empty(Deprecated(&field.deprecated));
                             t.push(self.field_element(field, false)?);
                             t
                         });
@@ -694,8 +840,158 @@ impl<'el> PackageProcessor<'el, RustFlavor, Loc<RpName>> for Compiler<'el> {
         Ok(())
     }
 
-    fn process_service(&self, out: &mut Self::Out, body: &'el RpServiceBody) -> Result<()> {
+    fn process_union(&self, out: &mut Self::Out, body: &'el RpUnionBody) -> Result<()> {
+        let (name, attributes) = self.convert_type_name(&body.name);
+
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+        t.push_unless_empty(attributes);
+        t.push(Derives::new(DERIVES, &body.attributes)?);
+        t.push(Untagged);
+        t.push(toks!["pub enum ", name, " {"]);
+
+        for variant in &body.variants {
+            let ty = Loc::borrow(&variant.ty).clone();
+            nested!(t, variant.ident.as_str(), "(", ty, "),");
+        }
+
+        t.push("}");
+
+        out.0.push(t);
+        Ok(())
+    }
+
+    fn process_newtype(&self, out: &mut Self::Out, body: &'el RpNewtypeBody) -> Result<()> {
         let (name, attributes) = self.convert_type_name(&body.name);
+        let ty = Loc::borrow(&body.ty).clone();
+
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(Comments(&body.comment));
+        t.push_unless_empty(Deprecated(&body.deprecated));
+        t.push_unless_empty(attributes);
+        t.push("#[derive(Clone, Debug, PartialEq, Serialize)]");
+        t.push("#[serde(transparent)]");
+        push!(t, "pub struct ", name, "(", ty.clone(), ");");
+
+        out.0.push(t);
+
+        let mut impl_body = Tokens::new();
+
+        impl_body.push({
+            let mut t = Tokens::new();
+            push!(t, "/// Wrap the given value, after it has been validated.");
+            push!(
+                t,
+                "pub fn new(value: ",
+                ty.clone(),
+                ") -> Result<",
+                name.clone(),
+                ", String> {"
+            );
+            nested!(t, "Self::validate(&value)?;");
+            nested!(t, "Ok(", name.clone(), "(value))");
+            push!(t, "}");
+            t
+        });
+
+        impl_body.push({
+            let mut t = Tokens::new();
+            push!(t, "/// Validate a value before it is wrapped.");
+
+            match body.validate.pattern {
+                Some(ref pattern) => {
+                    let pattern = Rc::new(pattern.to_string());
+                    let error = Rc::new(format!("value does not match pattern `{}`", pattern));
+                    let regex = rust::imported("regex", "Regex");
+
+                    push!(
+                        t,
+                        "pub fn validate(value: &",
+                        ty.clone(),
+                        ") -> Result<(), String> {"
+                    );
+                    t.nested_into(|t| {
+                        let pattern = toks![regex, "::new(", pattern.quoted(), ")"];
+                        push!(t, "let pattern = ", pattern, ".expect(\"bad pattern\");");
+                        push!(t, "if !pattern.is_match(value) {");
+                        nested!(t, "return Err(", error.quoted(), ".to_string());");
+                        push!(t, "}");
+                        push!(t, "Ok(())");
+                    });
+                }
+                None => {
+                    push!(
+                        t,
+                        "pub fn validate(_: &",
+                        ty.clone(),
+                        ") -> Result<(), String> {"
+                    );
+                    nested!(t, "Ok(())");
+                }
+            }
+
+            push!(t, "}");
+            t
+        });
+
+        impl_body.push({
+            let mut t = Tokens::new();
+            push!(t, "/// Access the wrapped value.");
+            push!(t, "pub fn value(&self) -> &", ty.clone(), " {");
+            nested!(t, "&self.0");
+            push!(t, "}");
+            t
+        });
+
+        impl_body.push({
+            let mut t = Tokens::new();
+            push!(t, "/// Take the wrapped value.");
+            push!(t, "pub fn into_value(self) -> ", ty.clone(), " {");
+            nested!(t, "self.0");
+            push!(t, "}");
+            t
+        });
+
+        out.0
+            .push(self.build_impl(name.clone(), impl_body.join_line_spacing()));
+
+        let des = rust::imported("serde", "Deserialize");
+        let deserializer = rust::imported("serde", "Deserializer");
+        let error = rust::imported("serde::de", "Error");
+
+        let mut t = Tokens::new();
+
+        // NB: deserialize through the constructor, so that values are always validated.
+        push!(t, "impl<'de> ", des.clone(), "<'de> for ", name, " {");
+
+        t.nested_into(|t| {
+            push!(t, "fn deserialize<D>(d: D) -> Result<", name, ", D::Error>");
+            nested!(t, "where D: ", deserializer, "<'de>");
+            push!(t, "{");
+            nested!(
+                t,
+                "let value = <",
+                ty,
+                " as ",
+                des,
+                "<'de>>::deserialize(d)?;"
+            );
+            nested!(t, name, "::new(value).map_err(", error, "::custom)");
+            push!(t, "}");
+        });
+
+        push!(t, "}");
+
+        out.0.push(t);
+        Ok(())
+    }
+
+    fn process_service(&self, out: &mut Self::Out, body: &'el RpServiceBody) -> Result<()> {
+        let (name, mut attributes) = self.convert_type_name(&body.name);
+        attributes.push_unless_empty(Deprecated(&body.deprecated));
 
         for s in &self.options.service {
             s.generate(Service {
@@ -5,7 +5,7 @@
 use core::errors::Result;
 use core::{
     self, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Loc, PackageTranslator, RpNumberKind,
-    RpNumberType, RpStringType, Translate, Translator,
+    RpNumberType, RpScalarType, RpStringType, Translate, Translator,
 };
 use genco::rust;
 use genco::{Cons, Rust};
@@ -45,17 +45,33 @@ impl Flavor for RustFlavor {
 pub struct RustFlavorTranslator {
     packages: Rc<Packages>,
     map: Rust<'static>,
+    set: Rust<'static>,
     json_value: Rust<'static>,
+    decimal: Rust<'static>,
     datetime: Option<Rust<'static>>,
+    date: Option<Rust<'static>>,
+    time: Option<Rust<'static>>,
+    binary: bool,
 }
 
 impl RustFlavorTranslator {
-    pub fn new(packages: Rc<Packages>, datetime: Option<Rust<'static>>) -> Self {
+    pub fn new(
+        packages: Rc<Packages>,
+        datetime: Option<Rust<'static>>,
+        date: Option<Rust<'static>>,
+        time: Option<Rust<'static>>,
+        binary: bool,
+    ) -> Self {
         Self {
             packages,
             map: rust::imported("std::collections", "HashMap"),
+            set: rust::imported("std::collections", "HashSet"),
             json_value: rust::imported("serde_json", "Value").alias("json"),
+            decimal: rust::imported("rust_decimal", "Decimal"),
             datetime: datetime,
+            date: date,
+            time: time,
+            binary: binary,
         }
     }
 }
@@ -72,6 +88,12 @@ impl FlavorTranslator for RustFlavorTranslator {
             RpNumberKind::U64 => rust::local("u64"),
             RpNumberKind::I32 => rust::local("i32"),
             RpNumberKind::I64 => rust::local("i64"),
+            RpNumberKind::U128 => rust::local("u128"),
+            RpNumberKind::I128 => rust::local("i128"),
+            RpNumberKind::Decimal if self.binary => {
+                return Err("`decimal` is not supported by the binary module".into())
+            }
+            RpNumberKind::Decimal => self.decimal.clone(),
         };
 
         Ok(out)
@@ -101,22 +123,81 @@ impl FlavorTranslator for RustFlavorTranslator {
         Err("Missing implementation for `datetime`, try: -m chrono".into())
     }
 
+    fn translate_date(&self) -> Result<Rust<'static>> {
+        if self.binary {
+            return Err("`date` is not supported by the binary module".into());
+        }
+
+        if let Some(ref date) = self.date {
+            return Ok(date.clone());
+        }
+
+        Err("Missing implementation for `date`, try: -m chrono".into())
+    }
+
+    fn translate_time(&self) -> Result<Rust<'static>> {
+        if self.binary {
+            return Err("`time` is not supported by the binary module".into());
+        }
+
+        if let Some(ref time) = self.time {
+            return Ok(time.clone());
+        }
+
+        Err("Missing implementation for `time`, try: -m chrono".into())
+    }
+
+    fn translate_duration(&self) -> Result<Rust<'static>> {
+        // NB: chrono durations can't be serialized, so the ISO-8601 representation is kept as a
+        // string.
+        Ok(rust::local("String"))
+    }
+
     fn translate_array(&self, argument: Rust<'static>) -> Result<Rust<'static>> {
         Ok(rust::local("Vec").with_arguments(vec![argument]))
     }
 
+    fn translate_set(&self, argument: Rust<'static>) -> Result<Rust<'static>> {
+        Ok(self.set.clone().with_arguments(vec![argument]))
+    }
+
     fn translate_map(&self, key: Rust<'static>, value: Rust<'static>) -> Result<Rust<'static>> {
         Ok(self.map.clone().with_arguments(vec![key, value]))
     }
 
     fn translate_any(&self) -> Result<Rust<'static>> {
+        if self.binary {
+            return Err("`any` is not supported by the binary module".into());
+        }
+
         Ok(self.json_value.clone())
     }
 
     fn translate_bytes(&self) -> Result<Rust<'static>> {
+        // bytes are represented as base64-encoded strings, which can't be told apart from other
+        // strings when encoding.
+        if self.binary {
+            return Err("`bytes` is not supported by the binary module".into());
+        }
+
         Ok(rust::local("String"))
     }
 
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<Rust<'static>> {
+        let ty = match scalar.mapping("rust") {
+            Some(ty) => ty,
+            None => return self.translate_scalar_base(scalar.base),
+        };
+
+        match ty.rfind("::") {
+            Some(index) => Ok(rust::imported(
+                ty[..index].to_string(),
+                ty[index + 2..].to_string(),
+            )),
+            None => Ok(rust::local(ty.to_string())),
+        }
+    }
+
     fn translate_name(&self, reg: RpReg, name: Loc<RpName>) -> Result<Rust<'static>> {
         let ident = reg.ident(&name, |p| p.join(TYPE_SEP), |c| c.join(SCOPE_SEP));
 
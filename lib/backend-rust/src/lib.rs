@@ -8,6 +8,7 @@ extern crate reproto_backend as backend;
 extern crate reproto_core as core;
 #[macro_use]
 extern crate reproto_manifest as manifest;
+extern crate reproto_naming as naming;
 extern crate reproto_trans as trans;
 extern crate serde;
 #[allow(unused)]
@@ -27,7 +28,7 @@ use core::errors::*;
 use core::{CoreFlavor, Handle};
 use flavored::RpPackage;
 use genco::{Cons, Rust, Tokens};
-use manifest::{Lang, Manifest, NoModule, TryFromToml};
+use manifest::{Lang, Manifest, ModuleDoc, NoModule, TryFromToml};
 use rust_file_spec::RustFileSpec;
 use std::any::Any;
 use std::collections::BTreeMap;
@@ -44,6 +45,45 @@ const SCOPE_SEP: &'static str = "::";
 #[derive(Clone, Copy, Default, Debug)]
 pub struct RustLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "chrono",
+        description: "Represent `datetime`, `date`, and `time` fields using chrono.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "grpc",
+        description: "Generate gRPC services.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "reqwest",
+        description: "Generate HTTP clients for services using reqwest.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "axum",
+        description: "Generate HTTP servers for services using axum.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "websocket",
+        description: "Generate WebSocket clients for services.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "unknown_variant",
+        description: "Add a catch-all variant to enums.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "binary",
+        description: "Add encoding and decoding using the binary format.",
+        options: &[],
+    },
+];
+
 impl Lang for RustLang {
     lang_base!(RustModule, compile);
 
@@ -51,64 +91,12 @@ impl Lang for RustLang {
         Some(format!("// {}", input))
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![
-            ("as", "_as"),
-            ("break", "_break"),
-            ("const", "_const"),
-            ("continue", "_continue"),
-            ("crate", "_crate"),
-            ("else", "_else"),
-            ("enum", "_enum"),
-            ("extern", "_extern"),
-            ("false", "_false"),
-            ("fn", "_fn"),
-            ("for", "_for"),
-            ("if", "_if"),
-            ("impl", "_impl"),
-            ("in", "_in"),
-            ("let", "_let"),
-            ("loop", "_loop"),
-            ("match", "_match"),
-            ("mod", "_mod"),
-            ("move", "_move"),
-            ("mut", "_mut"),
-            ("pub", "_pub"),
-            ("ref", "_ref"),
-            ("return", "_return"),
-            ("self", "_self"),
-            ("static", "_static"),
-            ("struct", "_struct"),
-            ("super", "_super"),
-            ("trait", "_trait"),
-            ("true", "_true"),
-            ("type", "_type"),
-            ("unsafe", "_unsafe"),
-            ("use", "_use"),
-            ("where", "_where"),
-            ("while", "_while"),
-            ("abstract", "_abstract"),
-            ("alignof", "_alignof"),
-            ("become", "_become"),
-            ("box", "_box"),
-            ("do", "_do"),
-            ("final", "_final"),
-            ("macro", "_macro"),
-            ("offsetof", "_offsetof"),
-            ("override", "_override"),
-            ("priv", "_priv"),
-            ("proc", "_proc"),
-            ("pure", "_pure"),
-            ("sizeof", "_sizeof"),
-            ("typeof", "_typeof"),
-            ("unsized", "_unsized"),
-            ("virtual", "_virtual"),
-            ("yield", "_yield"),
-        ]
+    fn keywords(&self) -> Option<&'static naming::Keywords> {
+        Some(&naming::keywords::RUST)
     }
 
-    fn modules(&self) -> Option<String> {
-        Some(String::from("Chrono, grpc, reqwest"))
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
     }
 }
 
@@ -117,6 +105,10 @@ pub enum RustModule {
     Chrono,
     Grpc,
     Reqwest,
+    Axum,
+    WebSocket,
+    UnknownVariant,
+    Binary,
 }
 
 impl TryFromToml for RustModule {
@@ -127,6 +119,10 @@ impl TryFromToml for RustModule {
             "chrono" => Chrono,
             "grpc" => Grpc,
             "reqwest" => Reqwest,
+            "axum" => Axum,
+            "websocket" => WebSocket,
+            "unknown_variant" => UnknownVariant,
+            "binary" => Binary,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -140,6 +136,10 @@ impl TryFromToml for RustModule {
             "chrono" => Chrono,
             "grpc" => Grpc,
             "reqwest" => Reqwest,
+            "axum" => Axum,
+            "websocket" => WebSocket,
+            "unknown_variant" => UnknownVariant,
+            "binary" => Binary,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -149,13 +149,22 @@ impl TryFromToml for RustModule {
 
 pub struct Options {
     pub datetime: Option<Rust<'static>>,
+    pub date: Option<Rust<'static>>,
+    pub time: Option<Rust<'static>>,
+    /// Generate an `Unknown` variant for enums, which holds unknown values when decoding.
+    pub unknown_variant: bool,
+    /// Types are encoded using the binary format, which can't represent `any`, `bytes`, and
+    /// `decimal`.
+    pub binary: bool,
     pub root: Vec<Box<RootCodegen>>,
+    pub decl: Vec<Box<DeclCodegen>>,
     pub service: Vec<Box<ServiceCodegen>>,
     pub packages: Rc<Packages>,
 }
 
 pub struct Root<'a, 'el: 'a> {
     files: &'a mut BTreeMap<RpPackage, RustFileSpec<'el>>,
+    datetime: Option<&'a Rust<'static>>,
 }
 
 pub trait RootCodegen {
@@ -163,6 +172,18 @@ pub trait RootCodegen {
     fn generate(&self, root: Root) -> Result<()>;
 }
 
+pub struct Decl<'a, 'el: 'a> {
+    decl: &'el flavored::RpDecl,
+    container: &'a mut Tokens<'el, Rust<'el>>,
+    name: Rc<String>,
+    unknown_variant: bool,
+}
+
+pub trait DeclCodegen {
+    /// Generate code for a declaration, after all declarations have been generated.
+    fn generate(&self, decl: Decl) -> Result<()>;
+}
+
 pub struct Service<'a, 'el: 'a> {
     body: &'el flavored::RpServiceBody,
     container: &'a mut Tokens<'el, Rust<'el>>,
@@ -180,7 +201,12 @@ fn options(modules: Vec<RustModule>, packages: Rc<Packages>) -> Result<Options>
 
     let mut options = Options {
         datetime: None,
+        date: None,
+        time: None,
+        unknown_variant: false,
+        binary: false,
         root: Vec::new(),
+        decl: Vec::new(),
         service: Vec::new(),
         packages: packages,
     };
@@ -192,6 +218,10 @@ fn options(modules: Vec<RustModule>, packages: Rc<Packages>) -> Result<Options>
             Chrono => Box::new(module::Chrono::new()),
             Grpc => Box::new(module::Grpc::new()),
             Reqwest => Box::new(module::Reqwest::new()),
+            Axum => Box::new(module::Axum::new()),
+            WebSocket => Box::new(module::WebSocket::new()),
+            UnknownVariant => Box::new(module::UnknownVariant::new()),
+            Binary => Box::new(module::Binary::new()),
         };
 
         initializer.initialize(&mut options)?;
@@ -208,6 +238,9 @@ fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) ->
     let translator = session.translator(flavored::RustFlavorTranslator::new(
         packages.clone(),
         options.datetime.clone(),
+        options.date.clone(),
+        options.time.clone(),
+        options.binary,
     ))?;
     let session = session.translate(translator)?;
 
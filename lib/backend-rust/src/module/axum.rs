@@ -0,0 +1,435 @@
+//! axum module for Rust.
+//!
+//! Generates a server-side trait for every service, with a provided method that wires up an axum
+//! router for it.
+
+use backend::Initializer;
+use core::errors::Result;
+use core::{self, RpHttpMethod};
+use flavored::{RpEndpointHttp1, RpHttpParam, RustEndpoint};
+use genco::rust::imported;
+use genco::{Cons, IntoTokens, Quoted, Rust, Tokens};
+use utils::{Deprecated, EndpointComments};
+use {Options, Service, ServiceCodegen};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.service.push(Box::new(AxumService::new()));
+        Ok(())
+    }
+}
+
+struct AxumService {
+    router: Rust<'static>,
+    state: Rust<'static>,
+    path: Rust<'static>,
+    query: Rust<'static>,
+    header_map: Rust<'static>,
+    status_code: Rust<'static>,
+    hash_map: Rust<'static>,
+    json: Rust<'static>,
+    into_response: Rust<'static>,
+    future: Rust<'static>,
+    arc: Rust<'static>,
+}
+
+impl AxumService {
+    pub fn new() -> Self {
+        Self {
+            router: imported("axum", "Router"),
+            state: imported("axum::extract", "State"),
+            path: imported("axum::extract", "Path"),
+            query: imported("axum::extract", "Query"),
+            header_map: imported("axum::http", "HeaderMap"),
+            status_code: imported("axum::http", "StatusCode"),
+            hash_map: imported("std::collections", "HashMap"),
+            json: imported("axum", "Json"),
+            into_response: imported("axum::response", "IntoResponse"),
+            future: imported("std::future", "Future"),
+            arc: imported("std::sync", "Arc"),
+        }
+    }
+}
+
+impl ServiceCodegen for AxumService {
+    fn generate(&self, service: Service) -> Result<()> {
+        let Service {
+            body,
+            container,
+            name,
+            attributes,
+            ..
+        } = service;
+
+        let name = Cons::from(format!("{}_Axum", name));
+
+        let mut endpoints = Vec::new();
+
+        for e in &body.endpoints {
+            let http = match e.http1.as_ref() {
+                Some(http) => http,
+                None => continue,
+            };
+
+            if let RpHttpMethod::Update = http.method {
+                return Err(format!(
+                    "{}: the UPDATE method is not supported by axum",
+                    e.safe_ident()
+                ).into());
+            }
+
+            endpoints.push((e, http));
+        }
+
+        container.push({
+            let mut t = Tokens::new();
+
+            t.push_unless_empty(attributes.clone());
+            push!(t, "pub trait ", name, ": Send + Sync + Sized + 'static {");
+
+            t.nested({
+                let mut t = Tokens::new();
+
+                t.push_into(|t| {
+                    push!(t, "/// Error returned by the service.");
+                    push!(t, "type Error: ", self.into_response, " + Send;");
+                });
+
+                for &(e, http) in &endpoints {
+                    t.push({
+                        let mut t = Tokens::new();
+                        t.push_unless_empty(EndpointComments(e));
+                        t.push_unless_empty(Deprecated(&e.deprecated));
+                        t.push(self.endpoint(e, http));
+                        t
+                    });
+                }
+
+                t.push(self.into_router(&endpoints));
+
+                t.join_line_spacing()
+            });
+
+            push!(t, "}");
+
+            t
+        });
+
+        Ok(())
+    }
+}
+
+impl AxumService {
+    /// Build the declaration of an endpoint in the service trait.
+    fn endpoint<'el>(
+        &self,
+        e: &'el RustEndpoint,
+        http: &'el RpEndpointHttp1,
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut args = Tokens::new();
+
+        args.append("&self");
+
+        for a in &e.arguments {
+            args.append(toks![a.safe_ident(), ": ", a.channel.ty()]);
+        }
+
+        let res = match http.response {
+            Some(ref res) => toks![res],
+            None => toks!["()"],
+        };
+
+        let output = toks![
+            "impl ",
+            self.future.clone(),
+            "<Output = Result<",
+            res,
+            ", Self::Error>> + Send"
+        ];
+
+        toks![
+            "fn ",
+            e.safe_ident(),
+            "(",
+            args.join(", "),
+            ") -> ",
+            output,
+            ";"
+        ]
+    }
+
+    /// Parse a query parameter or header from an optional string, or reject the request.
+    fn param<'el>(
+        &self,
+        param: &'el RpHttpParam,
+        value: Tokens<'el, Rust<'el>>,
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        let a = &param.argument;
+        let reject = toks![
+            self.into_response.clone(),
+            "::into_response(",
+            self.status_code.clone(),
+            "::BAD_REQUEST)"
+        ];
+
+        push!(
+            t,
+            "let ",
+            a.safe_ident(),
+            ": ",
+            a.channel.ty(),
+            " = match ",
+            value,
+            ".and_then(|v| v.parse().ok()) {"
+        );
+        nested!(t, "Some(v) => v,");
+        nested!(t, "None => return Err(", reject, "),");
+        push!(t, "};");
+
+        t
+    }
+
+    /// Build the provided method which sets up a router for the service.
+    fn into_router<'el>(
+        &self,
+        endpoints: &[(&'el RustEndpoint, &'el RpEndpointHttp1)],
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "/// Build a router which serves this service.");
+        push!(t, "fn into_router(self) -> ", self.router, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            let state = toks![self.router.clone(), "<", self.arc.clone(), "<Self>>"];
+
+            push!(
+                t,
+                "let mut router_: ",
+                state,
+                " = ",
+                self.router,
+                "::new();"
+            );
+
+            for &(e, http) in endpoints {
+                t.push(Route {
+                    service: self,
+                    e,
+                    http,
+                });
+            }
+
+            push!(t, "router_.with_state(", self.arc, "::new(self))");
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+}
+
+/// Register a single endpoint with the router.
+struct Route<'a, 'el: 'a> {
+    service: &'a AxumService,
+    e: &'el RustEndpoint,
+    http: &'el RpEndpointHttp1,
+}
+
+impl<'a, 'el: 'a> IntoTokens<'el, Rust<'el>> for Route<'a, 'el> {
+    fn into_tokens(self) -> Tokens<'el, Rust<'el>> {
+        use core::RpHttpMethod::*;
+
+        let Route { service, e, http } = self;
+
+        let mut path = String::new();
+
+        for step in &http.path.steps {
+            path.push('/');
+
+            for part in &step.parts {
+                match *part {
+                    core::RpPathPart::Variable(ref arg) => {
+                        path.push('{');
+                        path.push_str(arg.safe_ident());
+                        path.push('}');
+                    }
+                    core::RpPathPart::Segment(ref s) => {
+                        path.push_str(s.as_str());
+                    }
+                }
+            }
+        }
+
+        if path.is_empty() {
+            path.push('/');
+        }
+
+        let method = match http.method {
+            Get => "get",
+            Post => "post",
+            Put => "put",
+            // NB: rejected when generating the service.
+            Update => "any",
+            Delete => "delete",
+            Patch => "patch",
+            Head => "head",
+        };
+
+        let method = imported("axum::routing", method);
+
+        // extractors passed into the handler.
+        let mut extractors = Tokens::new();
+
+        extractors.append(toks![
+            service.state.clone(),
+            "(service_): ",
+            service.state.clone(),
+            "<",
+            service.arc.clone(),
+            "<Self>>"
+        ]);
+
+        let vars = http.path.vars().collect::<Vec<_>>();
+
+        if !vars.is_empty() {
+            let mut pattern = Tokens::new();
+            let mut types = Tokens::new();
+
+            for var in &vars {
+                pattern.append(var.safe_ident());
+                types.append(var.channel.ty());
+            }
+
+            // NB: a trailing comma is needed to make single element tuples.
+            let (pattern, types) = if vars.len() == 1 {
+                (toks![pattern, ","], toks![types, ","])
+            } else {
+                (pattern.join(", "), types.join(", "))
+            };
+
+            extractors.append(toks![
+                service.path.clone(),
+                "((",
+                pattern,
+                ")): ",
+                service.path.clone(),
+                "<(",
+                types,
+                ")>"
+            ]);
+        }
+
+        // query parameters and headers are parsed in the handler, rejecting the request if they
+        // are missing or malformed.
+        let mut params = Tokens::new();
+
+        if !http.query.is_empty() {
+            extractors.append(toks![
+                service.query.clone(),
+                "(query_): ",
+                service.query.clone(),
+                "<",
+                service.hash_map.clone(),
+                "<String, String>>"
+            ]);
+
+            for p in &http.query {
+                let value = toks!["query_.get(", p.name.as_str().quoted(), ")"];
+                params.push(service.param(p, value));
+            }
+        }
+
+        if !http.headers.is_empty() {
+            extractors.append(toks!["headers_: ", service.header_map.clone()]);
+
+            for h in &http.headers {
+                let value = toks![
+                    "headers_.get(",
+                    h.name.as_str().quoted(),
+                    ").and_then(|v| v.to_str().ok())"
+                ];
+
+                params.push(service.param(h, value));
+            }
+        }
+
+        // NB: text bodies are extracted from, and responded with a `String`.
+        if let Some(ref request) = e.request {
+            if http.accepts == core::RpAccept::Text {
+                extractors.append(toks![request.safe_ident(), ": ", request.channel.ty()]);
+            } else {
+                extractors.append(toks![
+                    service.json.clone(),
+                    "(",
+                    request.safe_ident(),
+                    "): ",
+                    service.json.clone(),
+                    "<",
+                    request.channel.ty(),
+                    ">"
+                ]);
+            }
+        }
+
+        let mut args = Tokens::new();
+
+        for a in &e.arguments {
+            args.append(a.safe_ident());
+        }
+
+        let call = toks!["service_.", e.safe_ident(), "(", args.join(", "), ").await"];
+
+        let call = match http.response {
+            Some(_) if http.produces == core::RpAccept::Json => {
+                toks![call, ".map(", service.json.clone(), ")"]
+            }
+            _ => call,
+        };
+
+        // NB: rejections and errors from the service are both turned into responses.
+        let call = if params.is_empty() {
+            call
+        } else {
+            let into_response = toks![service.into_response.clone(), "::into_response"];
+            toks![call, ".map_err(", into_response, ")"]
+        };
+
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "router_ = router_.route(",
+            path.quoted(),
+            ", ",
+            method,
+            "(|",
+            extractors.join(", "),
+            "| async move {"
+        );
+        t.nested({
+            let mut t = Tokens::new();
+            t.push_unless_empty(params);
+            t.push(call);
+            t
+        });
+        push!(t, "}));");
+
+        t
+    }
+}
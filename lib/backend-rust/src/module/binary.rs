@@ -0,0 +1,818 @@
+//! Binary module for Rust.
+//!
+//! Implements encoding and decoding of all types using the binary format, through a `Binary`
+//! trait which is generated in the `reproto_binary` module.
+
+use backend::{Initializer, UNKNOWN_VARIANT};
+use core::errors::Result;
+use core::{self, Loc, RpVariantValue};
+use flavored::{RpDecl, RpEnumBody, RpField, RpInterfaceBody, RpPackage, RpUnionBody};
+use genco::rust::imported;
+use genco::{Quoted, Rust, Tokens};
+use std::rc::Rc;
+use {Decl, DeclCodegen, Options, Root, RootCodegen, RustFileSpec, SCOPE_SEP};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        let utils_package = options.packages.new("reproto_binary")?;
+        let utils = Rc::new(utils_package.join(SCOPE_SEP));
+
+        options.binary = true;
+
+        // referenced through the module, since the names might conflict with declarations.
+        options.decl.push(Box::new(BinaryDecl {
+            binary: imported(utils.clone(), "Binary").alias("binary"),
+            result: imported(utils.clone(), "Result").alias("binary"),
+            error: imported(utils.clone(), "Error").alias("binary"),
+            encode_str: imported(utils.clone(), "encode_str").alias("binary"),
+        }));
+
+        options.root.push(Box::new(BinaryUtils::new(utils_package)));
+
+        Ok(())
+    }
+}
+
+/// Numeric types encoded as fixed-size little-endian values, and their size in bytes.
+const NUMBERS: &[(&str, usize)] = &[
+    ("u32", 4),
+    ("u64", 8),
+    ("i32", 4),
+    ("i64", 8),
+    ("u128", 16),
+    ("i128", 16),
+    ("f32", 4),
+    ("f64", 8),
+];
+
+struct BinaryUtils {
+    utils_package: RpPackage,
+}
+
+impl BinaryUtils {
+    pub fn new(utils_package: RpPackage) -> Self {
+        Self { utils_package }
+    }
+
+    fn reproto_binary<'el>(&self, datetime: Option<&Rust<'static>>) -> RustFileSpec<'el> {
+        let mut f = RustFileSpec::default();
+
+        let fmt = imported("std", "fmt");
+        let hash = imported("std::hash", "Hash");
+        let hash_map = imported("std::collections", "HashMap");
+        let hash_set = imported("std::collections", "HashSet");
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "/// Errors raised when decoding.");
+            push!(t, "#[derive(Debug)]");
+            push!(t, "pub enum Error {");
+            nested!(t, "/// The input ended before the value was decoded.");
+            nested!(t, "Eof,");
+            nested!(t, "/// A string which is not valid UTF-8.");
+            nested!(t, "Utf8,");
+            nested!(t, "/// An invalid value, like an unknown enum value.");
+            nested!(t, "Invalid(String),");
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "impl ", fmt.clone(), "::Display for Error {");
+
+            t.nested_into(|t| {
+                push!(
+                    t,
+                    "fn fmt(&self, fmt: &mut ",
+                    fmt.clone(),
+                    "::Formatter) -> ",
+                    fmt,
+                    "::Result {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "match *self {");
+                    nested!(
+                        t,
+                        "Error::Eof => fmt.write_str(\"unexpected end of input\"),"
+                    );
+                    nested!(t, "Error::Utf8 => fmt.write_str(\"invalid UTF-8\"),");
+                    nested!(t, "Error::Invalid(ref message) => fmt.write_str(message),");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push(toks![
+            "impl ",
+            imported("std::error", "Error").alias("error"),
+            " for Error {}"
+        ]);
+
+        f.0.push(toks![
+            "pub type Result<T> = ",
+            imported("std::result", "Result"),
+            "<T, Error>;"
+        ]);
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "/// Types which can be encoded using the binary format.");
+            push!(t, "pub trait Binary: Sized {");
+            nested!(t, "/// Encode the value, appending it to the output.");
+            nested!(t, "fn encode(&self, out: &mut Vec<u8>);");
+            nested!(t, "/// Decode a value, advancing the input past it.");
+            nested!(t, "fn decode(input: &mut &[u8]) -> Result<Self>;");
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "/// Take the given number of bytes from the input.");
+            push!(
+                t,
+                "pub fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "if input.len() < n {");
+                nested!(t, "return Err(Error::Eof);");
+                push!(t, "}");
+            });
+
+            nested!(t, "let (head, tail) = input.split_at(n);");
+            nested!(t, "*input = tail;");
+            nested!(t, "Ok(head)");
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "/// Encode the length of a string or a collection.");
+            push!(t, "pub fn encode_len(len: usize, out: &mut Vec<u8>) {");
+            nested!(t, "(len as u32).encode(out);");
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "/// Decode the length of a string or a collection.");
+            push!(t, "pub fn decode_len(input: &mut &[u8]) -> Result<usize> {");
+            nested!(t, "Ok(u32::decode(input)? as usize)");
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "/// Encode a string slice, like the value of a string enum."
+            );
+            push!(t, "pub fn encode_str(value: &str, out: &mut Vec<u8>) {");
+            nested!(t, "encode_len(value.len(), out);");
+            nested!(t, "out.extend_from_slice(value.as_bytes());");
+            push!(t, "}");
+
+            t
+        });
+
+        for &(ty, size) in NUMBERS {
+            f.0.push({
+                let mut t = Tokens::new();
+
+                push!(t, "impl Binary for ", ty, " {");
+
+                t.nested_into(|t| {
+                    push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+                    nested!(t, "out.extend_from_slice(&self.to_le_bytes());");
+                    push!(t, "}");
+                });
+
+                t.nested_into(|t| {
+                    push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+                    nested!(t, "let mut bytes = [0u8; ", size.to_string(), "];");
+                    nested!(
+                        t,
+                        "bytes.copy_from_slice(take(input, ",
+                        size.to_string(),
+                        ")?);"
+                    );
+                    nested!(t, "Ok(", ty, "::from_le_bytes(bytes))");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+
+                t
+            });
+        }
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "impl Binary for bool {");
+
+            t.nested_into(|t| {
+                push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+                nested!(t, "out.push(*self as u8);");
+                push!(t, "}");
+            });
+
+            t.nested_into(|t| {
+                push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+
+                t.nested_into(|t| {
+                    push!(t, "match take(input, 1)?[0] {");
+                    nested!(t, "0 => Ok(false),");
+                    nested!(t, "1 => Ok(true),");
+                    nested!(
+                        t,
+                        "b => Err(Error::Invalid(format!(\"invalid boolean: {}\", b))),"
+                    );
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "impl Binary for String {");
+
+            t.nested_into(|t| {
+                push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+                nested!(t, "encode_str(self, out);");
+                push!(t, "}");
+            });
+
+            t.nested_into(|t| {
+                push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+                nested!(t, "let len = decode_len(input)?;");
+                nested!(t, "let bytes = take(input, len)?;");
+                nested!(
+                    t,
+                    "String::from_utf8(bytes.to_vec()).map_err(|_| Error::Utf8)"
+                );
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(t, "impl<T: Binary> Binary for Option<T> {");
+
+            t.nested_into(|t| {
+                push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+
+                t.nested_into(|t| {
+                    push!(t, "match *self {");
+
+                    t.nested_into(|t| {
+                        push!(t, "Some(ref value) => {");
+                        nested!(t, "true.encode(out);");
+                        nested!(t, "value.encode(out);");
+                        push!(t, "}");
+                        push!(t, "None => false.encode(out),");
+                    });
+
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.nested_into(|t| {
+                push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+
+                t.nested_into(|t| {
+                    push!(t, "if !bool::decode(input)? {");
+                    nested!(t, "return Ok(None);");
+                    push!(t, "}");
+                });
+
+                nested!(t, "Ok(Some(T::decode(input)?))");
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+
+            t
+        });
+
+        f.0.push(collection(
+            toks!["impl<T: Binary> Binary for Vec<T> {"],
+            toks!["Vec::new()"],
+            toks!["out.push(T::decode(input)?);"],
+        ));
+
+        f.0.push(collection(
+            toks![
+                "impl<T: Binary + Eq + ",
+                hash.clone(),
+                "> Binary for ",
+                hash_set.clone(),
+                "<T> {"
+            ],
+            toks![hash_set.clone(), "::new()"],
+            toks!["out.insert(T::decode(input)?);"],
+        ));
+
+        f.0.push({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "impl<K: Binary + Eq + ",
+                hash,
+                ", V: Binary> Binary for ",
+                hash_map.clone(),
+                "<K, V> {"
+            );
+
+            t.nested_into(|t| {
+                push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+                nested!(t, "encode_len(self.len(), out);");
+
+                t.nested_into(|t| {
+                    push!(t, "for (key, value) in self {");
+                    nested!(t, "key.encode(out);");
+                    nested!(t, "value.encode(out);");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.nested_into(|t| {
+                push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+                nested!(t, "let len = decode_len(input)?;");
+                nested!(t, "let mut out = ", hash_map, "::new();");
+
+                t.nested_into(|t| {
+                    push!(t, "for _ in 0..len {");
+                    nested!(t, "let key = K::decode(input)?;");
+                    nested!(t, "out.insert(key, V::decode(input)?);");
+                    push!(t, "}");
+                });
+
+                nested!(t, "Ok(out)");
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+
+            t
+        });
+
+        // datetimes are encoded as milliseconds since the unix epoch.
+        if let Some(datetime) = datetime {
+            let utc = imported("chrono::offset", "Utc");
+            let time_zone = imported("chrono", "TimeZone");
+
+            f.0.push({
+                let mut t = Tokens::new();
+
+                push!(t, "impl Binary for ", datetime.clone(), " {");
+
+                t.nested_into(|t| {
+                    push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+                    nested!(t, "self.timestamp_millis().encode(out);");
+                    push!(t, "}");
+                });
+
+                t.nested_into(|t| {
+                    push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+                    nested!(t, "let millis = i64::decode(input)?;");
+
+                    t.nested_into(|t| {
+                        push!(
+                            t,
+                            "<",
+                            utc.clone(),
+                            " as ",
+                            time_zone,
+                            ">::timestamp_millis_opt(&",
+                            utc,
+                            ", millis)"
+                        );
+
+                        t.nested_into(|t| {
+                            push!(t, ".single()");
+                            push!(
+                                t,
+                                ".ok_or_else(|| Error::Invalid(format!(\"invalid datetime: {}\", \
+                                 millis)))"
+                            );
+                        });
+                    });
+
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+
+                t
+            });
+        }
+
+        return f;
+
+        /// Build the implementation for a collection of values.
+        fn collection<'el>(
+            header: Tokens<'el, Rust<'el>>,
+            new: Tokens<'el, Rust<'el>>,
+            insert: Tokens<'el, Rust<'el>>,
+        ) -> Tokens<'el, Rust<'el>> {
+            let mut t = Tokens::new();
+
+            t.push(header);
+
+            t.nested_into(|t| {
+                push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+                nested!(t, "encode_len(self.len(), out);");
+
+                t.nested_into(|t| {
+                    push!(t, "for value in self {");
+                    nested!(t, "value.encode(out);");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.nested_into(|t| {
+                push!(t, "fn decode(input: &mut &[u8]) -> Result<Self> {");
+                nested!(t, "let len = decode_len(input)?;");
+                nested!(t, "let mut out = ", new, ";");
+
+                t.nested_into(|t| {
+                    push!(t, "for _ in 0..len {");
+                    nested!(t, insert);
+                    push!(t, "}");
+                });
+
+                nested!(t, "Ok(out)");
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+
+            t
+        }
+    }
+}
+
+impl RootCodegen for BinaryUtils {
+    fn generate(&self, root: Root) -> Result<()> {
+        let Root {
+            files, datetime, ..
+        } = root;
+
+        files.insert(self.utils_package.clone(), self.reproto_binary(datetime));
+        Ok(())
+    }
+}
+
+struct BinaryDecl {
+    binary: Rust<'static>,
+    result: Rust<'static>,
+    error: Rust<'static>,
+    encode_str: Rust<'static>,
+}
+
+impl DeclCodegen for BinaryDecl {
+    fn generate(&self, decl: Decl) -> Result<()> {
+        let Decl {
+            decl,
+            container,
+            name,
+            unknown_variant,
+        } = decl;
+
+        let (encode, decode) = match *decl {
+            RpDecl::Type(ref body) => {
+                let mut encode = Tokens::new();
+
+                for field in &body.fields {
+                    encode.push(self.encode(toks!["&self.", field.safe_ident()]));
+                }
+
+                let mut decode = Tokens::new();
+                push!(decode, "Ok(", name.clone(), " {");
+                decode.nested(self.fields_decode(body.fields.iter()));
+                push!(decode, "})");
+
+                (encode, decode)
+            }
+            RpDecl::Tuple(ref body) => {
+                let mut encode = Tokens::new();
+                let mut args = Tokens::new();
+
+                for (i, _) in body.fields.iter().enumerate() {
+                    encode.push(self.encode(toks!["&self.", i.to_string()]));
+                    args.append(self.decode(toks!["_"]));
+                }
+
+                let decode = toks!["Ok(", name.clone(), "(", args.join(", "), "))"];
+                (encode, decode)
+            }
+            RpDecl::Enum(ref body) => self.enum_codec(&name, body, unknown_variant),
+            RpDecl::Interface(ref body) => self.interface_codec(&name, body),
+            RpDecl::Union(ref body) => self.union_codec(&name, body),
+            RpDecl::Newtype(_) => {
+                let encode = self.encode(toks!["&self.0"]);
+                let value = self.decode(toks!["_"]);
+                let error = toks![self.error.clone(), "::Invalid"];
+                let decode = toks![name.clone(), "::new(", value, ").map_err(", error, ")"];
+                (encode, decode)
+            }
+            RpDecl::Service(_) => return Ok(()),
+        };
+
+        let mut t = Tokens::new();
+
+        push!(t, "impl ", self.binary, " for ", name, " {");
+
+        t.nested_into(|t| {
+            push!(t, "fn encode(&self, out: &mut Vec<u8>) {");
+            t.nested(encode);
+            push!(t, "}");
+        });
+
+        t.nested_into(|t| {
+            push!(
+                t,
+                "fn decode(input: &mut &[u8]) -> ",
+                self.result,
+                "<Self> {"
+            );
+            t.nested(decode);
+            push!(t, "}");
+        });
+
+        push!(t, "}");
+
+        container.push(t);
+        Ok(())
+    }
+}
+
+impl BinaryDecl {
+    /// Encode the value behind the given reference.
+    fn encode<'el>(&self, value: Tokens<'el, Rust<'el>>) -> Tokens<'el, Rust<'el>> {
+        toks![self.binary.clone(), "::encode(", value, ", out);"]
+    }
+
+    /// Decode a value of the given type.
+    fn decode<'el>(&self, ty: Tokens<'el, Rust<'el>>) -> Tokens<'el, Rust<'el>> {
+        toks!["<", ty, " as ", self.binary.clone(), ">::decode(input)?"]
+    }
+
+    /// Error raised for invalid values.
+    fn invalid<'el>(
+        &self,
+        name: &Rc<String>,
+        what: &str,
+        value: &'el str,
+    ) -> Tokens<'el, Rust<'el>> {
+        let m = format!("{}: unknown {}: {{}}", name, what).quoted();
+        toks![
+            "Err(",
+            self.error.clone(),
+            "::Invalid(format!(",
+            m,
+            ", ",
+            value,
+            ")))"
+        ]
+    }
+
+    /// Decode the given fields into the fields of a struct literal.
+    fn fields_decode<'el, I>(&self, fields: I) -> Tokens<'el, Rust<'el>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        let mut t = Tokens::new();
+
+        for field in fields {
+            push!(t, field.safe_ident(), ": ", self.decode(toks!["_"]), ",");
+        }
+
+        t
+    }
+
+    /// Enums are encoded using their value.
+    fn enum_codec<'el>(
+        &self,
+        name: &Rc<String>,
+        body: &'el RpEnumBody,
+        unknown_variant: bool,
+    ) -> (Tokens<'el, Rust<'el>>, Tokens<'el, Rust<'el>>) {
+        let mut encode = Tokens::new();
+        let mut decode = Tokens::new();
+
+        let mut arms = Tokens::new();
+
+        for v in body.variants.iter() {
+            let values = Some(v.value).into_iter().chain(v.aliases.iter());
+
+            for value in values {
+                let pattern = match value {
+                    RpVariantValue::String(string) => toks![string.quoted()],
+                    RpVariantValue::Number(number) => {
+                        toks![number.to_string(), body.enum_type.clone()]
+                    }
+                };
+
+                push!(
+                    arms,
+                    pattern,
+                    " => Ok(",
+                    name.clone(),
+                    "::",
+                    v.ident(),
+                    "),"
+                );
+            }
+        }
+
+        if unknown_variant {
+            let variant = toks![name.clone(), "::", UNKNOWN_VARIANT];
+            push!(arms, "_ => Ok(", variant, "(value)),");
+        } else {
+            push!(arms, "_ => ", self.invalid(name, "value", "value"), ",");
+        }
+
+        match body.variants {
+            core::RpVariants::String { .. } => {
+                push!(encode, self.encode_str.clone(), "(self.value(), out);");
+                push!(decode, "let value = ", self.decode(toks!["String"]), ";");
+                push!(decode, "match value.as_str() {");
+            }
+            core::RpVariants::Number { .. } => {
+                encode.push(self.encode(toks!["&self.value()"]));
+                let ty = toks![body.enum_type.clone()];
+                push!(decode, "let value = ", self.decode(ty), ";");
+                push!(decode, "match value {");
+            }
+        }
+
+        decode.nested(arms);
+        push!(decode, "}");
+
+        (encode, decode)
+    }
+
+    /// Interfaces are encoded using the index of the sub-type, followed by all of its fields.
+    fn interface_codec<'el>(
+        &self,
+        name: &Rc<String>,
+        body: &'el RpInterfaceBody,
+    ) -> (Tokens<'el, Rust<'el>>, Tokens<'el, Rust<'el>>) {
+        let mut encode = Tokens::new();
+        let mut decode = Tokens::new();
+
+        push!(encode, "match *self {");
+        push!(decode, "match ", self.decode(toks!["u32"]), " {");
+
+        for (index, s) in body.sub_types.iter().enumerate() {
+            let fields = body
+                .fields
+                .iter()
+                .chain(s.fields.iter())
+                .collect::<Vec<_>>();
+            let variant = toks![name.clone(), "::", s.ident.as_str()];
+
+            // fields are bound to local names, since they might conflict with `out`.
+            let mut bindings = Tokens::new();
+
+            for (i, field) in fields.iter().enumerate() {
+                bindings.append(toks![field.safe_ident(), ": ref f", i.to_string()]);
+            }
+
+            encode.nested({
+                let mut t = Tokens::new();
+
+                push!(t, variant.clone(), " { ", bindings.join(", "), " } => {");
+                t.nested(self.encode(toks!["&", index.to_string(), "u32"]));
+
+                for i in 0..fields.len() {
+                    t.nested(self.encode(toks!["f", i.to_string()]));
+                }
+
+                push!(t, "}");
+
+                t
+            });
+
+            decode.nested({
+                let mut t = Tokens::new();
+
+                push!(t, index.to_string(), " => Ok(", variant, " {");
+                t.nested(self.fields_decode(fields));
+                push!(t, "}),");
+
+                t
+            });
+        }
+
+        push!(encode, "}");
+
+        nested!(
+            decode,
+            "index => ",
+            self.invalid(name, "sub-type", "index"),
+            ","
+        );
+        push!(decode, "}");
+
+        (encode, decode)
+    }
+
+    /// Unions are encoded using the index of the variant, followed by its value.
+    fn union_codec<'el>(
+        &self,
+        name: &Rc<String>,
+        body: &'el RpUnionBody,
+    ) -> (Tokens<'el, Rust<'el>>, Tokens<'el, Rust<'el>>) {
+        let mut encode = Tokens::new();
+        let mut decode = Tokens::new();
+
+        push!(encode, "match *self {");
+        push!(decode, "match ", self.decode(toks!["u32"]), " {");
+
+        for (index, variant) in body.variants.iter().enumerate() {
+            let v = toks![name.clone(), "::", variant.ident.as_str()];
+
+            encode.nested({
+                let mut t = Tokens::new();
+
+                push!(t, v.clone(), "(ref value) => {");
+                t.nested(self.encode(toks!["&", index.to_string(), "u32"]));
+                t.nested(self.encode(toks!["value"]));
+                push!(t, "}");
+
+                t
+            });
+
+            let value = self.decode(toks!["_"]);
+            nested!(decode, index.to_string(), " => Ok(", v, "(", value, ")),");
+        }
+
+        push!(encode, "}");
+
+        nested!(
+            decode,
+            "index => ",
+            self.invalid(name, "variant", "index"),
+            ","
+        );
+        push!(decode, "}");
+
+        (encode, decode)
+    }
+}
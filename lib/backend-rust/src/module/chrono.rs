@@ -20,6 +20,8 @@ impl Initializer for Module {
         options.datetime = Some(
             imported("chrono", "DateTime").with_arguments(vec![imported("chrono::offset", "Utc")]),
         );
+        options.date = Some(imported("chrono", "NaiveDate"));
+        options.time = Some(imported("chrono", "NaiveTime"));
 
         Ok(())
     }
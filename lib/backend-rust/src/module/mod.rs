@@ -1,7 +1,15 @@
+mod axum;
+mod binary;
 mod chrono;
 mod grpc;
 mod reqwest;
+mod unknown_variant;
+mod websocket;
 
+pub use self::axum::Module as Axum;
+pub use self::binary::Module as Binary;
 pub use self::chrono::Module as Chrono;
 pub use self::grpc::Module as Grpc;
 pub use self::reqwest::Module as Reqwest;
+pub use self::unknown_variant::Module as UnknownVariant;
+pub use self::websocket::Module as WebSocket;
@@ -1,13 +1,19 @@
-//! gRPC module for Rust.
+//! reqwest module for Rust.
+//!
+//! Generates an async client for every service, which sends requests using [`reqwest`].
+//!
+//! [`reqwest`]: https://docs.rs/reqwest
 
 use backend::Initializer;
 use core::errors::Result;
 use core::{self, Loc};
-use flavored::{RpEndpointHttp1, RpPackage, RpPathSpec, RpServiceBody, RustEndpoint};
+use flavored::{
+    RpEndpointError, RpEndpointHttp1, RpPackage, RpPathSpec, RpServiceBody, RustEndpoint,
+};
 use genco::rust::{imported, local};
 use genco::{Cons, IntoTokens, Quoted, Rust, Tokens};
 use std::rc::Rc;
-use utils::Comments;
+use utils::{Deprecated, EndpointComments};
 use {Options, Root, RootCodegen, RustFileSpec, Service, ServiceCodegen, SCOPE_SEP};
 
 pub struct Module {}
@@ -26,11 +32,12 @@ impl Initializer for Module {
 
         let imported_utils_package = Rc::new(utils_package.join(SCOPE_SEP));
         let result = imported(imported_utils_package.clone(), "Result");
+        let error = imported(imported_utils_package.clone(), "Error");
         let path_encode = imported(imported_utils_package.clone(), "PathEncode");
 
         options
             .service
-            .push(Box::new(ReqwestService::new(result, path_encode)));
+            .push(Box::new(ReqwestService::new(result, error, path_encode)));
 
         options
             .root
@@ -40,6 +47,15 @@ impl Initializer for Module {
     }
 }
 
+/// Errors which can happen when sending a request, and the variants of `Error` holding them.
+fn client_errors() -> Vec<(Rust<'static>, &'static str)> {
+    vec![
+        (imported("reqwest", "Error"), "ReqwestError"),
+        (imported("url", "ParseError"), "UrlError"),
+        (imported("std::fmt", "Error"), "FormatError"),
+    ]
+}
+
 struct ReqwestUtils {
     utils_package: RpPackage,
 }
@@ -52,10 +68,9 @@ impl ReqwestUtils {
     fn reproto<'el>(&self) -> Result<RustFileSpec<'el>> {
         let mut f = RustFileSpec::default();
 
-        let mut errors = Vec::new();
-        errors.push((imported("reqwest", "Error"), "ReqwestError"));
-        errors.push((imported("reqwest", "UrlError"), "UrlError"));
-        errors.push((imported("std::fmt", "Error"), "FormatError"));
+        let errors = client_errors();
+
+        let status_code = imported("reqwest", "StatusCode");
 
         f.0.push({
             let mut t = Tokens::new();
@@ -67,6 +82,9 @@ impl ReqwestUtils {
                 nested!(t, variant.clone(), "(", ty.clone(), "),");
             }
 
+            nested!(t, "/// The server responded with a non-successful status.");
+            nested!(t, "Status(", status_code, "),");
+
             push!(t, "}");
 
             t
@@ -130,6 +148,13 @@ impl ReqwestUtils {
                         nested!(t, "Error::", variant.clone(), "(ref e) => e.fmt(fmt),");
                     }
 
+                    nested!(
+                        t,
+                        "Error::Status(ref s) => write!(fmt, ",
+                        "unexpected status: {}".quoted(),
+                        ", s),"
+                    );
+
                     push!(t, "}");
                 });
 
@@ -141,13 +166,20 @@ impl ReqwestUtils {
             t
         });
 
+        f.0.push({
+            let mut t = Tokens::new();
+            push!(t, "impl ", imported("std::error", "Error"), " for Error {}");
+            t
+        });
+
         f.0.push({
             let mut t = Tokens::new();
 
             let display = imported("std::fmt", "Display");
             let fmt = imported("std::fmt", "Formatter");
             let result = imported("std::fmt", "Result");
-            let encode = imported("reqwest::header::parsing", "http_percent_encode");
+            let encode = imported("percent_encoding", "utf8_percent_encode");
+            let non_alphanumeric = imported("percent_encoding", "NON_ALPHANUMERIC");
 
             push!(t, "pub struct PathEncode<T>(pub T);");
 
@@ -163,7 +195,17 @@ impl ReqwestUtils {
                     let mut t = Tokens::new();
 
                     push!(t, "fn fmt(&self, fmt: &mut ", fmt, ") -> ", result, " {");
-                    nested!(t, encode, "(fmt, self.0.to_string().as_bytes())");
+                    nested!(t, "let value = self.0.to_string();");
+                    nested!(
+                        t,
+                        "write!(fmt, ",
+                        "{}".quoted(),
+                        ", ",
+                        encode,
+                        "(&value, ",
+                        non_alphanumeric,
+                        "))"
+                    );
                     push!(t, "}");
 
                     t
@@ -191,14 +233,16 @@ impl RootCodegen for ReqwestUtils {
 
 struct ReqwestService {
     result: Rust<'static>,
+    error: Rust<'static>,
     path_encode: Rust<'static>,
     client: Rust<'static>,
 }
 
 impl ReqwestService {
-    pub fn new(result: Rust<'static>, path_encode: Rust<'static>) -> Self {
+    pub fn new(result: Rust<'static>, error: Rust<'static>, path_encode: Rust<'static>) -> Self {
         Self {
             result,
+            error,
             path_encode,
             client: imported("reqwest", "Client"),
         }
@@ -215,9 +259,61 @@ impl ServiceCodegen for ReqwestService {
             ..
         } = service;
 
+        let service_error = Cons::from(format!("{}_Error", name));
         let name = Cons::from(format!("{}_Reqwest", name));
         let url_ty = imported("reqwest", "Url");
 
+        let mut endpoints = Vec::new();
+
+        for e in &body.endpoints {
+            let http = match e.http1.as_ref() {
+                Some(http) => http,
+                None => continue,
+            };
+
+            if let core::RpHttpMethod::Update = http.method {
+                return Err(format!(
+                    "{}: the UPDATE method is not supported by reqwest",
+                    e.safe_ident()
+                )
+                .into());
+            }
+
+            endpoints.push((e, http));
+        }
+
+        let mut errors: Vec<&RpEndpointError> = Vec::new();
+
+        for &(e, _) in &endpoints {
+            for error in &e.errors {
+                if let Some(existing) = errors
+                    .iter()
+                    .find(|existing| existing.ident() == error.ident())
+                {
+                    if existing.ty != error.ty {
+                        return Err(format!(
+                            "{}: error `{}` is declared with different types in the same service",
+                            e.safe_ident(),
+                            error.ident()
+                        )
+                        .into());
+                    }
+
+                    continue;
+                }
+
+                errors.push(error);
+            }
+        }
+
+        if !errors.is_empty() {
+            container.push(ServiceError {
+                name: service_error.clone(),
+                error: &self.error,
+                errors: &errors,
+            });
+        }
+
         container.push({
             let mut t = Tokens::new();
 
@@ -247,18 +343,16 @@ impl ServiceCodegen for ReqwestService {
                 });
 
                 // endpoint methods.
-                for e in &body.endpoints {
-                    let http = match e.http1.as_ref() {
-                        Some(http) => http,
-                        None => continue,
-                    };
-
+                for &(e, http) in &endpoints {
                     t.nested({
                         let mut t = Tokens::new();
 
-                        t.push_unless_empty(Comments(&e.comment));
+                        t.push_unless_empty(EndpointComments(e));
+                        t.push_unless_empty(Deprecated(&e.deprecated));
                         t.push(Endpoint {
                             result: &self.result,
+                            error: &self.error,
+                            service_error: &service_error,
                             path_encode: &self.path_encode,
                             e,
                             http,
@@ -280,6 +374,111 @@ impl ServiceCodegen for ReqwestService {
     }
 }
 
+/// Builds the error type for endpoints of a service which declare errors.
+struct ServiceError<'a, 'el: 'a> {
+    name: Cons<'el>,
+    error: &'a Rust<'static>,
+    errors: &'a [&'el RpEndpointError],
+}
+
+impl<'a, 'el: 'a> IntoTokens<'el, Rust<'el>> for ServiceError<'a, 'el> {
+    fn into_tokens(self) -> Tokens<'el, Rust<'el>> {
+        let ServiceError {
+            name,
+            error,
+            errors,
+        } = self;
+
+        let mut t = Tokens::new();
+
+        t.push_into(|t| {
+            push!(t, "#[derive(Debug)]");
+            push!(t, "pub enum ", name.clone(), " {");
+
+            for e in errors {
+                nested!(t, e.ident(), "(", e.ty.clone(), "),");
+            }
+
+            nested!(
+                t,
+                "/// The request failed, or the server responded with an undeclared error."
+            );
+            nested!(t, "Client(", error.clone(), "),");
+            push!(t, "}");
+        });
+
+        t.push_into(|t| {
+            push!(t, "impl From<", error.clone(), "> for ", name.clone(), " {");
+
+            t.nested_into(|t| {
+                push!(t, "fn from(value: ", error.clone(), ") -> Self {");
+                nested!(t, name.clone(), "::Client(value)");
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+        });
+
+        for (ty, _) in client_errors() {
+            t.push_into(|t| {
+                push!(t, "impl From<", ty.clone(), "> for ", name.clone(), " {");
+
+                t.nested_into(|t| {
+                    push!(t, "fn from(value: ", ty, ") -> Self {");
+                    nested!(t, name.clone(), "::Client(value.into())");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+        }
+
+        t.push_into(|t| {
+            let display = imported("std::fmt", "Display");
+            let formatter = imported("std::fmt", "Formatter");
+            let result = imported("std::fmt", "Result");
+
+            push!(t, "impl ", display, " for ", name.clone(), " {");
+
+            t.nested_into(|t| {
+                push!(
+                    t,
+                    "fn fmt(&self, fmt: &mut ",
+                    formatter,
+                    ") -> ",
+                    result,
+                    " {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "match *self {");
+
+                    for e in errors {
+                        let ident = e.ident();
+                        let message = ident.quoted();
+                        let variant = toks![name.clone(), "::", ident];
+                        nested!(t, variant, "(_) => fmt.write_str(", message, "),");
+                    }
+
+                    nested!(t, name.clone(), "::Client(ref e) => e.fmt(fmt),");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+        });
+
+        t.push_into(|t| {
+            let std_error = imported("std::error", "Error");
+            push!(t, "impl ", std_error, " for ", name.clone(), " {}");
+        });
+
+        t.join_line_spacing()
+    }
+}
+
 /// Builds a constructor for the service struct.
 struct Constructor<'a, 'el: 'a> {
     body: &'el RpServiceBody,
@@ -389,6 +588,8 @@ impl<'a, 'el: 'a> IntoTokens<'el, Rust<'el>> for WritePath<'a, 'el> {
 /// Build an endpoint method for the service struct.
 struct Endpoint<'a, 'el: 'a> {
     result: &'a Rust<'static>,
+    error: &'a Rust<'static>,
+    service_error: &'a Cons<'el>,
     path_encode: &'a Rust<'static>,
     e: &'el RustEndpoint,
     http: &'el RpEndpointHttp1,
@@ -400,6 +601,8 @@ impl<'a, 'el: 'a> IntoTokens<'el, Rust<'el>> for Endpoint<'a, 'el> {
 
         let Endpoint {
             result,
+            error,
+            service_error,
             path_encode,
             e,
             http,
@@ -428,13 +631,28 @@ impl<'a, 'el: 'a> IntoTokens<'el, Rust<'el>> for Endpoint<'a, 'el> {
 
         let args = args.join(", ");
 
-        let res = if let Some(ref res) = http.response {
+        let res = match http.response {
+            Some(ref res) => toks![res],
+            None => toks!["()"],
+        };
+
+        let res = if e.errors.is_empty() {
             toks![result.clone(), "<", res, ">"]
         } else {
-            toks![result.clone(), "<()>"]
+            let std_result = imported("std::result", "Result");
+            toks![std_result, "<", res, ", ", service_error.clone(), ">"]
         };
 
-        push!(t, "pub fn ", e.safe_ident(), "(", args, ") -> ", res, " {");
+        push!(
+            t,
+            "pub async fn ",
+            e.safe_ident(),
+            "(",
+            args,
+            ") -> ",
+            res,
+            " {"
+        );
 
         t.nested({
             let mut t = Tokens::new();
@@ -457,31 +675,100 @@ impl<'a, 'el: 'a> IntoTokens<'el, Rust<'el>> for Endpoint<'a, 'el> {
             }
 
             let method = match http.method {
-                Get => "Get",
-                Post => "Post",
-                Put => "Put",
-                Update => "Update",
-                Delete => "Delete",
-                Patch => "Patch",
-                Head => "Head",
+                Get => "GET",
+                Post => "POST",
+                Put => "PUT",
+                // NB: rejected when generating the service.
+                Update => "GET",
+                Delete => "DELETE",
+                Patch => "PATCH",
+                Head => "HEAD",
             };
 
             let m = toks![imported("reqwest", "Method"), "::", method];
 
             let req = toks!["self.client.request(", m, ", url_)"];
 
-            push!(t, "let mut req_ = ", req, ";");
+            t.push_into(|t| {
+                push!(t, "let req_ = ", req, ";");
 
-            if let Some(ref req) = e.request {
-                push!(t, "req_.json(&", req.safe_ident(), ");");
-            }
+                if !http.query.is_empty() {
+                    let mut query = Tokens::new();
+
+                    for p in &http.query {
+                        let value = toks![p.argument.safe_ident(), ".to_string()"];
+                        query.append(toks!["(", p.name.as_str().quoted(), ", ", value, ")"]);
+                    }
+
+                    push!(t, "let req_ = req_.query(&[", query.join(", "), "]);");
+                }
+
+                for h in &http.headers {
+                    let name = h.name.as_str().quoted();
+                    let value = toks![h.argument.safe_ident(), ".to_string()"];
+                    push!(t, "let req_ = req_.header(", name, ", ", value, ");");
+                }
+
+                if e.response.is_some() {
+                    let (header, accept) = ("Accept".quoted(), http.produces.as_str().quoted());
+                    push!(t, "let req_ = req_.header(", header, ", ", accept, ");");
+                }
+
+                if let Some(ref req) = e.request {
+                    match http.accepts {
+                        core::RpAccept::Json => {
+                            push!(t, "let req_ = req_.json(&", req.safe_ident(), ");");
+                        }
+                        core::RpAccept::Text => {
+                            let content_type = "Content-Type".quoted();
+                            let text = "text/plain".quoted();
+                            push!(t, "let req_ = req_.header(", content_type, ", ", text, ");");
+                            push!(t, "let req_ = req_.body(", req.safe_ident(), ");");
+                        }
+                    }
+                }
+            });
+
+            t.push_into(|t| {
+                push!(t, "let res_ = req_.send().await?;");
+                push!(t, "let status_ = res_.status();");
+            });
+
+            t.push_into(|t| {
+                push!(t, "if !status_.is_success() {");
+
+                if e.errors.is_empty() {
+                    nested!(t, "return Err(", error.clone(), "::Status(status_));");
+                } else {
+                    t.nested_into(|t| {
+                        push!(t, "match status_.as_u16() {");
+
+                        for err in &e.errors {
+                            let variant = toks![service_error.clone(), "::", err.ident()];
+                            let status = err.status.to_string();
+                            let body = "(res_.json().await?)),";
+                            nested!(t, status, " => return Err(", variant, body);
+                        }
+
+                        nested!(t, "_ => {}");
+                        push!(t, "}");
+
+                        let status = toks![error.clone(), "::Status(status_).into()"];
+                        push!(t, "return Err(", status, ");");
+                    });
+                }
+
+                push!(t, "}");
+            });
 
             if e.response.is_some() {
-                push!(t, "let mut res_ = req_.send()?;");
-                push!(t, "let body_ = res_.json()?;");
+                match http.produces {
+                    core::RpAccept::Json => push!(t, "let body_ = res_.json().await?;"),
+                    core::RpAccept::Text => push!(t, "let body_ = res_.text().await?;"),
+                }
+
                 push!(t, "Ok(body_)");
             } else {
-                push!(t, "req_.send()?;");
                 push!(t, "Ok(())");
             }
 
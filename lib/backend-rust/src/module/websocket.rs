@@ -0,0 +1,1367 @@
+//! WebSocket module for Rust.
+//!
+//! Generates a frame type, a server-side trait and a client for every service, which exchange
+//! calls as JSON frames over any socket of text messages.
+
+use backend::Initializer;
+use core::errors::Result;
+use core::RpChannel;
+use flavored::RustEndpoint;
+use genco::rust::imported;
+use genco::{Cons, Quoted, Rust, Tokens};
+use utils::{Deprecated, EndpointComments};
+use {Options, Service, ServiceCodegen};
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Options) -> Result<()> {
+        options.service.push(Box::new(WebSocketService::new()));
+        Ok(())
+    }
+}
+
+struct WebSocketService {
+    json: Rust<'static>,
+    json_map: Rust<'static>,
+    json_error: Rust<'static>,
+    serialize: Rust<'static>,
+    display: Rust<'static>,
+    error: Rust<'static>,
+    arc: Rust<'static>,
+    hash_map: Rust<'static>,
+    atomic_u64: Rust<'static>,
+    ordering: Rust<'static>,
+    mpsc: Rust<'static>,
+    stream: Rust<'static>,
+    sink: Rust<'static>,
+    box_future: Rust<'static>,
+    box_stream: Rust<'static>,
+    futures_unordered: Rust<'static>,
+    select_all: Rust<'static>,
+}
+
+impl WebSocketService {
+    pub fn new() -> Self {
+        Self {
+            json: imported("serde_json", "Value").alias("json"),
+            json_map: imported("serde_json", "Map").alias("json"),
+            json_error: imported("serde_json", "Error").alias("json"),
+            serialize: imported("serde", "Serialize"),
+            display: imported("std::fmt", "Display"),
+            error: imported("std::error", "Error"),
+            arc: imported("std::sync", "Arc"),
+            hash_map: imported("std::collections", "HashMap"),
+            atomic_u64: imported("std::sync::atomic", "AtomicU64"),
+            ordering: imported("std::sync::atomic", "Ordering"),
+            mpsc: imported("futures::channel", "mpsc"),
+            stream: imported("futures::stream", "Stream"),
+            sink: imported("futures::sink", "Sink"),
+            box_future: imported("futures::future", "BoxFuture"),
+            box_stream: imported("futures::stream", "BoxStream"),
+            futures_unordered: imported("futures::stream", "FuturesUnordered"),
+            select_all: imported("futures::stream", "SelectAll"),
+        }
+    }
+}
+
+impl ServiceCodegen for WebSocketService {
+    fn generate(&self, service: Service) -> Result<()> {
+        let Service {
+            body,
+            container,
+            name,
+            attributes,
+            ..
+        } = service;
+
+        for e in &body.endpoints {
+            if e.arguments.iter().filter(|a| a.channel.is_streaming()).count() > 1 {
+                return Err(format!(
+                    "{}: at most one streaming argument is supported over websockets",
+                    e.safe_ident()
+                ).into());
+            }
+        }
+
+        let names = Names {
+            frame: Cons::from(format!("{}_Frame", name)),
+            service: Cons::from(format!("{}_WebSocket", name)),
+            client: Cons::from(format!("{}_WebSocketClient", name)),
+        };
+
+        container.push(self.frame(&names, attributes));
+        container.push(self.frame_impl(&names));
+        container.push(self.service(&names, attributes, &body.endpoints));
+        container.push(self.client(&names, attributes));
+        container.push(self.client_impl(&names, &body.endpoints));
+
+        Ok(())
+    }
+}
+
+/// Names of the items generated for a single service.
+struct Names<'el> {
+    frame: Cons<'el>,
+    service: Cons<'el>,
+    client: Cons<'el>,
+}
+
+impl WebSocketService {
+    /// Type of a socket of text messages.
+    fn socket<'el>(&self) -> Tokens<'el, Rust<'el>> {
+        toks![
+            self.stream.clone(),
+            "<Item = Result<String, E>> + ",
+            self.sink.clone(),
+            "<String, Error = E> + Send + 'static"
+        ]
+    }
+
+    /// Build the declaration of the frame exchanged over the socket.
+    fn frame<'el>(
+        &self,
+        names: &Names<'el>,
+        attributes: &Tokens<'el, Rust<'el>>,
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "/// Frame exchanged over a socket when calling the service.");
+        t.push_unless_empty(attributes.clone());
+        push!(t, "#[derive(Clone, Debug, Serialize, Deserialize)]");
+        push!(t, "pub struct ", names.frame, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "/// Identifier of the call that the frame belongs to.");
+                push!(t, "pub id: u64,");
+            });
+
+            t.push_into(|t| {
+                push!(t, "/// Name of the called endpoint.");
+                push!(t, "pub endpoint: String,");
+            });
+
+            t.push_into(|t| {
+                push!(t, "/// Arguments of the call, or a streamed item.");
+                push!(
+                    t,
+                    "#[serde(default, skip_serializing_if = ",
+                    "Option::is_none".quoted(),
+                    ")]"
+                );
+                push!(t, "pub body: Option<", self.json, ">,");
+            });
+
+            t.push_into(|t| {
+                push!(t, "/// Set on the last frame sent for a call in one direction.");
+                push!(t, "#[serde(default)]");
+                push!(t, "pub end: bool,");
+            });
+
+            t.push_into(|t| {
+                push!(t, "/// Error which caused the call to fail.");
+                push!(
+                    t,
+                    "#[serde(default, skip_serializing_if = ",
+                    "Option::is_none".quoted(),
+                    ")]"
+                );
+                push!(t, "pub error: Option<String>,");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the helper functions to construct frames.
+    fn frame_impl<'el>(&self, names: &Names<'el>) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "impl ", names.frame, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "fn new(id: u64, endpoint: &str, body: Option<",
+                    self.json,
+                    ">, end: bool) -> Self {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, names.frame, " {");
+                    nested!(t, "id,");
+                    nested!(t, "endpoint: endpoint.to_string(),");
+                    nested!(t, "body,");
+                    nested!(t, "end,");
+                    nested!(t, "error: None,");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "fn item<T: ",
+                    self.serialize,
+                    ">(id: u64, endpoint: &str, item: &T, end: bool) -> Self {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "match json::to_value(item) {");
+                    nested!(t, "Ok(body) => Self::new(id, endpoint, Some(body), end),");
+                    nested!(t, "Err(e) => Self::error(id, endpoint, e),");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "fn error<E: ",
+                    self.display,
+                    ">(id: u64, endpoint: &str, error: E) -> Self {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, names.frame, " {");
+                    nested!(t, "id,");
+                    nested!(t, "endpoint: endpoint.to_string(),");
+                    nested!(t, "body: None,");
+                    nested!(t, "end: true,");
+                    nested!(t, "error: Some(error.to_string()),");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the server-side trait of the service.
+    fn service<'el>(
+        &self,
+        names: &Names<'el>,
+        attributes: &Tokens<'el, Rust<'el>>,
+        endpoints: &'el [RustEndpoint],
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        t.push_unless_empty(attributes.clone());
+        push!(t, "pub trait ", names.service, ": Send + Sync + Sized + 'static {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "/// Error returned by the service.");
+                push!(t, "type Error: ", self.display, " + Send;");
+            });
+
+            for e in endpoints {
+                t.push({
+                    let mut t = Tokens::new();
+                    t.push_unless_empty(EndpointComments(e));
+                    t.push_unless_empty(Deprecated(&e.deprecated));
+                    t.push(self.service_endpoint(e));
+                    t
+                });
+            }
+
+            t.push(self.serve(names, endpoints));
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the declaration of an endpoint in the service trait.
+    fn service_endpoint<'el>(&self, e: &'el RustEndpoint) -> Tokens<'el, Rust<'el>> {
+        let mut args = Tokens::new();
+
+        args.append("&self");
+
+        for a in &e.arguments {
+            let ty = match *a.channel {
+                RpChannel::Unary { ref ty } => toks![ty],
+                RpChannel::Streaming { ref ty } => {
+                    toks![self.box_stream.clone(), "<'static, ", ty, ">"]
+                }
+            };
+
+            args.append(toks![a.safe_ident(), ": ", ty]);
+        }
+
+        let output = match e.response {
+            Some(ref response) => match **response {
+                RpChannel::Unary { ref ty } => toks![
+                    self.box_future.clone(),
+                    "<'static, Result<",
+                    ty,
+                    ", Self::Error>>"
+                ],
+                RpChannel::Streaming { ref ty } => toks![
+                    self.box_stream.clone(),
+                    "<'static, Result<",
+                    ty,
+                    ", Self::Error>>"
+                ],
+            },
+            None => toks![
+                self.box_future.clone(),
+                "<'static, Result<(), Self::Error>>"
+            ],
+        };
+
+        toks![
+            "fn ",
+            e.safe_ident(),
+            "(",
+            args.join(", "),
+            ") -> ",
+            output,
+            ";"
+        ]
+    }
+
+    /// Build the provided method which serves the service over a socket.
+    fn serve<'el>(
+        &self,
+        names: &Names<'el>,
+        endpoints: &'el [RustEndpoint],
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "/// Serve the service over a socket of text messages.");
+        push!(
+            t,
+            "fn serve<S, E>(self: ",
+            self.arc,
+            "<Self>, socket: S) -> ",
+            self.box_future,
+            "<'static, Result<(), E>>"
+        );
+        push!(t, "where");
+        nested!(t, "S: ", self.socket(), ",");
+        nested!(t, "E: From<", self.json_error, "> + Send + 'static,");
+        push!(t, "{");
+
+        t.nested_into(|t| {
+            push!(t, "Box::pin(async move {");
+
+            t.nested({
+                let mut t = Tokens::new();
+
+                push!(t, "use futures::{SinkExt, StreamExt};");
+
+                t.push_into(|t| {
+                    push!(t, "let (mut sink_, stream_) = socket.split();");
+                    push!(t, "let mut stream_ = stream_.fuse();");
+                    push!(
+                        t,
+                        "let (tx_, mut rx_) = ",
+                        self.mpsc,
+                        "::unbounded::<",
+                        names.frame,
+                        ">();"
+                    );
+                    push!(
+                        t,
+                        "let mut calls_ = ",
+                        self.futures_unordered,
+                        "::<",
+                        self.box_future,
+                        "<'static, ()>>::new();"
+                    );
+                    push!(
+                        t,
+                        "let mut streams_ = ",
+                        self.hash_map,
+                        "::<u64, ",
+                        self.mpsc,
+                        "::UnboundedSender<",
+                        self.json,
+                        ">>::new();"
+                    );
+                });
+
+                t.push_into(|t| {
+                    push!(t, "loop {");
+
+                    t.nested_into(|t| {
+                        push!(t, "futures::select! {");
+
+                        t.nested_into(|t| {
+                            push!(t, "message_ = stream_.next() => {");
+                            t.nested(self.serve_message(names, endpoints));
+                            push!(t, "}");
+
+                            push!(t, "frame_ = rx_.select_next_some() => {");
+                            nested!(t, "sink_.send(json::to_string(&frame_)?).await?;");
+                            push!(t, "}");
+
+                            push!(t, "_ = calls_.select_next_some() => {}");
+                        });
+
+                        push!(t, "}");
+                    });
+
+                    push!(t, "}");
+                });
+
+                push!(t, "Ok::<(), E>(())");
+
+                t.join_line_spacing()
+            });
+
+            push!(t, "})");
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the handling of an incoming message in the server.
+    fn serve_message<'el>(
+        &self,
+        names: &Names<'el>,
+        endpoints: &'el [RustEndpoint],
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        t.push_into(|t| {
+            push!(t, "let message_ = match message_ {");
+            nested!(t, "Some(message_) => message_?,");
+            nested!(t, "None => break,");
+            push!(t, "};");
+        });
+
+        push!(
+            t,
+            "let frame_: ",
+            names.frame,
+            " = json::from_str(&message_)?;"
+        );
+
+        t.push_into(|t| {
+            push!(t, "if let Some(items_) = streams_.get(&frame_.id) {");
+
+            t.nested({
+                let mut t = Tokens::new();
+
+                t.push_into(|t| {
+                    push!(t, "if let Some(body_) = frame_.body {");
+                    nested!(t, "let _ = items_.unbounded_send(body_);");
+                    push!(t, "}");
+                });
+
+                t.push_into(|t| {
+                    push!(t, "if frame_.end {");
+                    nested!(t, "streams_.remove(&frame_.id);");
+                    push!(t, "}");
+                });
+
+                push!(t, "continue;");
+
+                t.join_line_spacing()
+            });
+
+            push!(t, "}");
+        });
+
+        t.push_into(|t| {
+            push!(t, "let id_ = frame_.id;");
+            push!(t, "let tx_ = tx_.clone();");
+        });
+
+        let has_args = endpoints
+            .iter()
+            .flat_map(|e| e.arguments.iter())
+            .any(|a| !a.channel.is_streaming());
+
+        if has_args {
+            t.push_into(|t| {
+                push!(t, "let mut args_ = match frame_.body {");
+                nested!(t, "Some(", self.json, "::Object(args_)) => args_,");
+                nested!(t, "_ => ", self.json_map, "::new(),");
+                push!(t, "};");
+            });
+        }
+
+        t.push_into(|t| {
+            push!(t, "match frame_.endpoint.as_str() {");
+
+            for e in endpoints {
+                push!(t, e.name().quoted(), " => {");
+                t.nested(self.serve_endpoint(names, e));
+                push!(t, "}");
+            }
+
+            push!(t, "endpoint_ => {");
+            nested!(
+                t,
+                "let message_ = format!(",
+                "no such endpoint: {}".quoted(),
+                ", endpoint_);"
+            );
+            nested!(
+                t,
+                "let _ = tx_.unbounded_send(",
+                names.frame,
+                "::error(id_, endpoint_, message_));"
+            );
+            push!(t, "}");
+
+            push!(t, "}");
+        });
+
+        t.join_line_spacing()
+    }
+
+    /// Build the call to a single endpoint in the server.
+    fn serve_endpoint<'el>(
+        &self,
+        names: &Names<'el>,
+        e: &'el RustEndpoint,
+    ) -> Tokens<'el, Rust<'el>> {
+        let endpoint = e.name().quoted();
+
+        let mut t = Tokens::new();
+        let mut args = Tokens::new();
+
+        for a in &e.arguments {
+            args.append(a.safe_ident());
+
+            match *a.channel {
+                RpChannel::Unary { ref ty } => {
+                    t.push_into(|t| {
+                        push!(
+                            t,
+                            "let ",
+                            a.safe_ident(),
+                            ": ",
+                            ty,
+                            " = match json::from_value(args_.remove(",
+                            a.ident().quoted(),
+                            ").unwrap_or(",
+                            self.json,
+                            "::Null)) {"
+                        );
+                        nested!(t, "Ok(value_) => value_,");
+                        nested!(t, "Err(e_) => {");
+                        t.nested_into(|t| {
+                            nested!(
+                                t,
+                                "let _ = tx_.unbounded_send(",
+                                names.frame,
+                                "::error(id_, ",
+                                endpoint.clone(),
+                                ", e_));"
+                            );
+                            nested!(t, "continue;");
+                        });
+                        nested!(t, "}");
+                        push!(t, "};");
+                    });
+                }
+                RpChannel::Streaming { ref ty } => {
+                    t.push_into(|t| {
+                        push!(
+                            t,
+                            "let (items_tx_, items_rx_) = ",
+                            self.mpsc,
+                            "::unbounded::<",
+                            self.json,
+                            ">();"
+                        );
+                    });
+
+                    t.push_into(|t| {
+                        push!(t, "if !frame_.end {");
+                        nested!(t, "streams_.insert(id_, items_tx_);");
+                        push!(t, "}");
+                    });
+
+                    t.push_into(|t| {
+                        push!(
+                            t,
+                            "let ",
+                            a.safe_ident(),
+                            ": ",
+                            self.box_stream,
+                            "<'static, ",
+                            ty,
+                            "> = Box::pin(items_rx_.filter_map(|item_| {"
+                        );
+                        nested!(
+                            t,
+                            "futures::future::ready(json::from_value(item_).ok())"
+                        );
+                        push!(t, "}));");
+                    });
+                }
+            }
+        }
+
+        let streaming = e
+            .response
+            .as_ref()
+            .map(|r| r.is_streaming())
+            .unwrap_or(false);
+
+        let response = if streaming {
+            "let mut response_ = self."
+        } else {
+            "let response_ = self."
+        };
+
+        push!(t, response, e.safe_ident(), "(", args.join(", "), ");");
+
+        t.push_into(|t| {
+            push!(t, "calls_.push(Box::pin(async move {");
+
+            if streaming {
+                t.nested(self.serve_stream(names, endpoint.clone()));
+            } else {
+                t.nested(self.serve_unary(names, e, endpoint.clone()));
+            }
+
+            push!(t, "}));");
+        });
+
+        t.join_line_spacing()
+    }
+
+    /// Build the response of a unary endpoint in the server.
+    fn serve_unary<'el>(
+        &self,
+        names: &Names<'el>,
+        e: &'el RustEndpoint,
+        endpoint: Tokens<'el, Rust<'el>>,
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        let ok = match e.response {
+            Some(_) => toks![
+                "Ok(response_) => ",
+                names.frame.clone(),
+                "::item(id_, ",
+                endpoint.clone(),
+                ", &response_, true),"
+            ],
+            None => toks![
+                "Ok(()) => ",
+                names.frame.clone(),
+                "::new(id_, ",
+                endpoint.clone(),
+                ", None, true),"
+            ],
+        };
+
+        t.push_into(|t| {
+            push!(t, "let frame_ = match response_.await {");
+            nested!(t, ok);
+            nested!(
+                t,
+                "Err(e_) => ",
+                names.frame,
+                "::error(id_, ",
+                endpoint,
+                ", e_),"
+            );
+            push!(t, "};");
+        });
+
+        push!(t, "let _ = tx_.unbounded_send(frame_);");
+
+        t.join_line_spacing()
+    }
+
+    /// Build the response of a streaming endpoint in the server.
+    fn serve_stream<'el>(
+        &self,
+        names: &Names<'el>,
+        endpoint: Tokens<'el, Rust<'el>>,
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        t.push_into(|t| {
+            push!(t, "while let Some(item_) = response_.next().await {");
+
+            t.nested_into(|t| {
+                push!(t, "match item_ {");
+
+                t.nested_into(|t| {
+                    push!(t, "Ok(item_) => {");
+                    nested!(
+                        t,
+                        "let _ = tx_.unbounded_send(",
+                        names.frame,
+                        "::item(id_, ",
+                        endpoint.clone(),
+                        ", &item_, false));"
+                    );
+                    push!(t, "}");
+                    push!(t, "Err(e_) => {");
+                    nested!(
+                        t,
+                        "let _ = tx_.unbounded_send(",
+                        names.frame,
+                        "::error(id_, ",
+                        endpoint.clone(),
+                        ", e_));"
+                    );
+                    nested!(t, "return;");
+                    push!(t, "}");
+                });
+
+                push!(t, "}");
+            });
+
+            push!(t, "}");
+        });
+
+        push!(
+            t,
+            "let _ = tx_.unbounded_send(",
+            names.frame,
+            "::new(id_, ",
+            endpoint,
+            ", None, true));"
+        );
+
+        t.join_line_spacing()
+    }
+
+    /// Type of calls sent from the client to the connection.
+    fn client_call<'el>(&self, names: &Names<'el>) -> Tokens<'el, Rust<'el>> {
+        toks![
+            "(u64, ",
+            self.box_stream.clone(),
+            "<'static, ",
+            names.frame.clone(),
+            ">, ",
+            self.mpsc.clone(),
+            "::UnboundedSender<",
+            names.frame.clone(),
+            ">)"
+        ]
+    }
+
+    /// Error returned by the client.
+    fn client_error<'el>(&self) -> Tokens<'el, Rust<'el>> {
+        toks!["Box<dyn ", self.error.clone(), " + Send + Sync>"]
+    }
+
+    /// Build the declaration of the client.
+    fn client<'el>(
+        &self,
+        names: &Names<'el>,
+        attributes: &Tokens<'el, Rust<'el>>,
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "/// Client calling the service over a socket of text messages.");
+        t.push_unless_empty(attributes.clone());
+        push!(t, "#[derive(Clone)]");
+        push!(t, "pub struct ", names.client, " {");
+        nested!(
+            t,
+            "tx: ",
+            self.mpsc,
+            "::UnboundedSender<",
+            self.client_call(names),
+            ">,"
+        );
+        nested!(t, "next_id: ", self.arc, "<", self.atomic_u64, ">,");
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the implementation of the client.
+    fn client_impl<'el>(
+        &self,
+        names: &Names<'el>,
+        endpoints: &'el [RustEndpoint],
+    ) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "impl ", names.client, " {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push(self.client_new(names));
+            t.push(self.client_call_fn(names));
+
+            for e in endpoints {
+                t.push({
+                    let mut t = Tokens::new();
+                    t.push_unless_empty(EndpointComments(e));
+                    t.push_unless_empty(Deprecated(&e.deprecated));
+                    t.push(self.client_endpoint(e));
+                    t
+                });
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the constructor of the client, which also sets up the connection.
+    fn client_new<'el>(&self, names: &Names<'el>) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "/// Build a new client over a socket of text messages.");
+        push!(t, "///");
+        push!(
+            t,
+            "/// The returned future drives the connection, and must be spawned for calls to make progress."
+        );
+        push!(
+            t,
+            "pub fn new<S, E>(socket: S) -> (Self, ",
+            self.box_future,
+            "<'static, Result<(), E>>)"
+        );
+        push!(t, "where");
+        nested!(t, "S: ", self.socket(), ",");
+        nested!(t, "E: From<", self.json_error, "> + Send + 'static,");
+        push!(t, "{");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(
+                t,
+                "let (tx, mut rx) = ",
+                self.mpsc,
+                "::unbounded::<",
+                self.client_call(names),
+                ">();"
+            );
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "let connection: ",
+                    self.box_future,
+                    "<'static, Result<(), E>> = Box::pin(async move {"
+                );
+
+                t.nested({
+                    let mut t = Tokens::new();
+
+                    push!(t, "use futures::{SinkExt, StreamExt};");
+
+                    t.push_into(|t| {
+                        push!(t, "let (mut sink_, stream_) = socket.split();");
+                        push!(t, "let mut stream_ = stream_.fuse();");
+                        push!(
+                            t,
+                            "let mut outgoing_ = ",
+                            self.select_all,
+                            "::<",
+                            self.box_stream,
+                            "<'static, ",
+                            names.frame,
+                            ">>::new();"
+                        );
+                        push!(
+                            t,
+                            "let mut calls_ = ",
+                            self.hash_map,
+                            "::<u64, ",
+                            self.mpsc,
+                            "::UnboundedSender<",
+                            names.frame,
+                            ">>::new();"
+                        );
+                    });
+
+                    t.push_into(|t| {
+                        push!(t, "loop {");
+
+                        t.nested_into(|t| {
+                            push!(t, "futures::select! {");
+
+                            t.nested_into(|t| {
+                                push!(t, "message_ = stream_.next() => {");
+                                t.nested(self.client_message(names));
+                                push!(t, "}");
+
+                                push!(t, "call_ = rx.select_next_some() => {");
+                                nested!(t, "let (id_, frames_, responses_) = call_;");
+                                nested!(t, "calls_.insert(id_, responses_);");
+                                nested!(t, "outgoing_.push(frames_);");
+                                push!(t, "}");
+
+                                push!(t, "frame_ = outgoing_.select_next_some() => {");
+                                nested!(t, "sink_.send(json::to_string(&frame_)?).await?;");
+                                push!(t, "}");
+                            });
+
+                            push!(t, "}");
+                        });
+
+                        push!(t, "}");
+                    });
+
+                    push!(t, "Ok::<(), E>(())");
+
+                    t.join_line_spacing()
+                });
+
+                push!(t, "});");
+            });
+
+            t.push_into(|t| {
+                push!(t, "let client = ", names.client, " {");
+                nested!(t, "tx,");
+                nested!(t, "next_id: ", self.arc, "::new(", self.atomic_u64, "::new(0)),");
+                push!(t, "};");
+            });
+
+            push!(t, "(client, connection)");
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the handling of an incoming message in the client.
+    fn client_message<'el>(&self, names: &Names<'el>) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        t.push_into(|t| {
+            push!(t, "let message_ = match message_ {");
+            nested!(t, "Some(message_) => message_?,");
+            nested!(t, "None => break,");
+            push!(t, "};");
+        });
+
+        t.push_into(|t| {
+            push!(
+                t,
+                "let frame_: ",
+                names.frame,
+                " = json::from_str(&message_)?;"
+            );
+            push!(t, "let id_ = frame_.id;");
+            push!(t, "let end_ = frame_.end;");
+        });
+
+        t.push_into(|t| {
+            push!(t, "if let Some(responses_) = calls_.get(&id_) {");
+            nested!(t, "let _ = responses_.unbounded_send(frame_);");
+            push!(t, "}");
+        });
+
+        t.push_into(|t| {
+            push!(t, "if end_ {");
+            nested!(t, "calls_.remove(&id_);");
+            push!(t, "}");
+        });
+
+        t.join_line_spacing()
+    }
+
+    /// Build the function which sends a call over the connection.
+    fn client_call_fn<'el>(&self, names: &Names<'el>) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "fn call_(");
+        nested!(t, "&self,");
+        nested!(t, "endpoint: &'static str,");
+        nested!(
+            t,
+            "args: Result<",
+            self.json_map,
+            "<String, ",
+            self.json,
+            ">, ",
+            self.json_error,
+            ">,"
+        );
+        nested!(
+            t,
+            "items: Option<",
+            self.box_stream,
+            "<'static, ",
+            self.json,
+            ">>,"
+        );
+        push!(
+            t,
+            ") -> ",
+            self.mpsc,
+            "::UnboundedReceiver<",
+            names.frame,
+            "> {"
+        );
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(t, "use futures::StreamExt;");
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "let id = self.next_id.fetch_add(1, ",
+                    self.ordering,
+                    "::SeqCst);"
+                );
+                push!(t, "let (responses_tx, responses) = ", self.mpsc, "::unbounded();");
+            });
+
+            t.push_into(|t| {
+                push!(t, "let args = match args {");
+                nested!(t, "Ok(args) => args,");
+                nested!(t, "Err(e) => {");
+                t.nested_into(|t| {
+                    nested!(
+                        t,
+                        "let _ = responses_tx.unbounded_send(",
+                        names.frame,
+                        "::error(id, endpoint, e));"
+                    );
+                    nested!(t, "return responses;");
+                });
+                nested!(t, "}");
+                push!(t, "};");
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "let first = ",
+                    names.frame,
+                    "::new(id, endpoint, Some(",
+                    self.json,
+                    "::Object(args)), items.is_none());"
+                );
+                push!(
+                    t,
+                    "let first = futures::stream::once(futures::future::ready(first));"
+                );
+            });
+
+            t.push_into(|t| {
+                push!(
+                    t,
+                    "let frames: ",
+                    self.box_stream,
+                    "<'static, ",
+                    names.frame,
+                    "> = match items {"
+                );
+
+                t.nested_into(|t| {
+                    push!(t, "Some(items) => {");
+
+                    t.nested_into(|t| {
+                        push!(
+                            t,
+                            "let items = items.map(move |item| ",
+                            names.frame,
+                            "::new(id, endpoint, Some(item), false));"
+                        );
+                        push!(
+                            t,
+                            "let end = ",
+                            names.frame,
+                            "::new(id, endpoint, None, true);"
+                        );
+                        push!(
+                            t,
+                            "let end = futures::stream::once(futures::future::ready(end));"
+                        );
+                        push!(t, "first.chain(items).chain(end).boxed()");
+                    });
+
+                    push!(t, "}");
+                    push!(t, "None => first.boxed(),");
+                });
+
+                push!(t, "};");
+            });
+
+            push!(t, "let _ = self.tx.unbounded_send((id, frames, responses_tx));");
+            push!(t, "responses");
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build a single endpoint method of the client.
+    fn client_endpoint<'el>(&self, e: &'el RustEndpoint) -> Tokens<'el, Rust<'el>> {
+        let mut params = Tokens::new();
+        let mut inserts = Tokens::new();
+        let mut items = toks!["None"];
+
+        params.append("&self");
+
+        for a in &e.arguments {
+            match *a.channel {
+                RpChannel::Unary { ref ty } => {
+                    params.append(toks![a.safe_ident(), ": ", ty]);
+
+                    inserts.push(toks![
+                        "args_.insert(",
+                        a.ident().quoted(),
+                        ".to_string(), json::to_value(&",
+                        a.safe_ident(),
+                        ")?);"
+                    ]);
+                }
+                RpChannel::Streaming { ref ty } => {
+                    params.append(toks![
+                        a.safe_ident(),
+                        ": ",
+                        self.box_stream.clone(),
+                        "<'static, ",
+                        ty,
+                        ">"
+                    ]);
+
+                    items = toks![
+                        "Some(",
+                        a.safe_ident(),
+                        ".filter_map(|item_| futures::future::ready(json::to_value(&item_).ok())).boxed())"
+                    ];
+                }
+            }
+        }
+
+        let error = self.client_error();
+
+        let output = match e.response {
+            Some(ref response) => match **response {
+                RpChannel::Unary { ref ty } => toks![
+                    self.box_future.clone(),
+                    "<'static, Result<",
+                    ty,
+                    ", ",
+                    error,
+                    ">>"
+                ],
+                RpChannel::Streaming { ref ty } => toks![
+                    self.box_stream.clone(),
+                    "<'static, Result<",
+                    ty,
+                    ", ",
+                    error,
+                    ">>"
+                ],
+            },
+            None => toks![
+                self.box_future.clone(),
+                "<'static, Result<(), ",
+                error,
+                ">>"
+            ],
+        };
+
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "pub fn ",
+            e.safe_ident(),
+            "(",
+            params.join(", "),
+            ") -> ",
+            output,
+            " {"
+        );
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(t, "use futures::StreamExt;");
+
+            if inserts.is_empty() {
+                push!(t, "let args_ = Ok(", self.json_map, "::new());");
+            } else {
+                t.push_into(|t| {
+                    push!(
+                        t,
+                        "let args_ = (|| -> Result<",
+                        self.json_map,
+                        "<String, ",
+                        self.json,
+                        ">, ",
+                        self.json_error,
+                        "> {"
+                    );
+                    nested!(t, "let mut args_ = ", self.json_map, "::new();");
+                    nested!(t, inserts);
+                    nested!(t, "Ok(args_)");
+                    push!(t, "})();");
+                });
+            }
+
+            let streaming = e
+                .response
+                .as_ref()
+                .map(|r| r.is_streaming())
+                .unwrap_or(false);
+
+            let responses = if streaming {
+                "let responses_ = self.call_("
+            } else {
+                "let mut responses_ = self.call_("
+            };
+
+            push!(
+                t,
+                responses,
+                e.name().quoted(),
+                ", args_, ",
+                items,
+                ");"
+            );
+
+            if streaming {
+                t.push(self.client_stream());
+            } else {
+                t.push(self.client_unary(e));
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+
+        t
+    }
+
+    /// Build the handling of a unary response in the client.
+    fn client_unary<'el>(&self, e: &'el RustEndpoint) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(t, "Box::pin(async move {");
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "let frame_ = match responses_.next().await {");
+                nested!(t, "Some(frame_) => frame_,");
+                nested!(
+                    t,
+                    "None => return Err(",
+                    "connection closed".quoted(),
+                    ".into()),"
+                );
+                push!(t, "};");
+            });
+
+            t.push_into(|t| {
+                push!(t, "if let Some(error_) = frame_.error {");
+                nested!(t, "return Err(error_.into());");
+                push!(t, "}");
+            });
+
+            match e.response {
+                Some(_) => push!(
+                    t,
+                    "Ok(json::from_value(frame_.body.unwrap_or(",
+                    self.json,
+                    "::Null))?)"
+                ),
+                None => push!(t, "Ok(())"),
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "})");
+
+        t
+    }
+
+    /// Build the handling of a streaming response in the client.
+    fn client_stream<'el>(&self) -> Tokens<'el, Rust<'el>> {
+        let mut t = Tokens::new();
+
+        push!(
+            t,
+            "Box::pin(futures::stream::unfold(Some(responses_), |responses_| async move {"
+        );
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            t.push_into(|t| {
+                push!(t, "let mut responses_ = responses_?;");
+                push!(t, "let frame_ = responses_.next().await?;");
+            });
+
+            t.push_into(|t| {
+                push!(t, "if let Some(error_) = frame_.error {");
+                nested!(t, "return Some((Err(error_.into()), None));");
+                push!(t, "}");
+            });
+
+            t.push_into(|t| {
+                push!(t, "let item_ = json::from_value(frame_.body?).map_err(Into::into);");
+                push!(
+                    t,
+                    "let next_ = if frame_.end { None } else { Some(responses_) };"
+                );
+                push!(t, "Some((item_, next_))");
+            });
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}))");
+
+        t
+    }
+}
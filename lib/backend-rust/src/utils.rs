@@ -1,4 +1,6 @@
-use genco::{IntoTokens, Rust, Tokens};
+use core::RpDeprecated;
+use flavored::RpEndpoint;
+use genco::{Cons, IntoTokens, Quoted, Rust, Tokens};
 
 /// Documentation comments.
 pub struct Comments<'el, S: 'el>(pub &'el [S]);
@@ -21,6 +23,58 @@ impl<'el, S: 'el + AsRef<str>> IntoTokens<'el, Rust<'el>> for Comments<'el, S> {
     }
 }
 
+/// Documentation comments of an endpoint, including the documentation of its arguments.
+pub struct EndpointComments<'el>(pub &'el RpEndpoint);
+
+impl<'el> IntoTokens<'el, Rust<'el>> for EndpointComments<'el> {
+    fn into_tokens(self) -> Tokens<'el, Rust<'el>> {
+        let e = self.0;
+
+        let mut t = Comments(&e.comment).into_tokens();
+
+        let arguments = e
+            .arguments
+            .iter()
+            .filter(|a| !a.comment.is_empty())
+            .collect::<Vec<_>>();
+
+        if arguments.is_empty() {
+            return t;
+        }
+
+        if !t.is_empty() {
+            t.push("///");
+        }
+
+        t.push("/// # Arguments");
+        t.push("///");
+
+        for a in arguments {
+            let comment = Cons::from(a.comment.join(" "));
+            t.push(toks!["/// * `", a.safe_ident(), "` - ", comment]);
+        }
+
+        t
+    }
+}
+
+/// Deprecated attribute, which is empty unless something is deprecated.
+pub struct Deprecated<'el>(pub &'el Option<RpDeprecated>);
+
+impl<'el> IntoTokens<'el, Rust<'el>> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Rust<'el>> {
+        let deprecated = match *self.0 {
+            Some(ref deprecated) => deprecated,
+            None => return Tokens::new(),
+        };
+
+        match deprecated.note() {
+            Some(note) => toks!["#[deprecated(note = ", note.quoted(), ")]"],
+            None => toks!["#[deprecated]"],
+        }
+    }
+}
+
 /// Repr attribute.
 #[allow(unused)]
 pub struct Repr<S>(pub S);
@@ -1,17 +1,21 @@
 //! Backend for Swift
 
-use backend::PackageProcessor;
+use backend::{PackageProcessor, UNKNOWN_VARIANT};
 use core::errors::*;
-use core::{Handle, Loc};
+use core::RpChannel;
+use core::{Handle, Loc, RelativePath, RelativePathBuf, RpDeprecated};
 use flavored::{
-    RpEnumBody, RpField, RpInterfaceBody, RpTupleBody, RpTypeBody, SwiftFlavor, SwiftName,
+    RpEndpoint, RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpPackage, RpServiceBody,
+    RpTupleBody, RpTypeBody, SwiftFlavor, SwiftName,
 };
-use genco::swift::Swift;
-use genco::{IntoTokens, Tokens};
+use genco::swift::{imported, Swift};
+use genco::{IntoTokens, Quoted, Tokens};
+use std::io::Write;
+use std::rc::Rc;
 use trans::{self, Packages, Translated};
 use {
-    EnumAdded, FileSpec, InterfaceAdded, InterfaceModelAdded, Options, PackageAdded,
-    StructModelAdded, TupleAdded, TypeAdded, EXT,
+    EnumAdded, FileSpec, InterfaceAdded, InterfaceModelAdded, NewtypeAdded, Options, PackageAdded,
+    StructModelAdded, TupleAdded, TypeAdded, EXT, PACKAGE_SWIFT,
 };
 
 /// Documentation comments.
@@ -29,6 +33,23 @@ impl<'el, S: 'el + AsRef<str>> IntoTokens<'el, Swift<'el>> for Comments<'el, S>
     }
 }
 
+/// Deprecated attribute, which is empty unless something is deprecated.
+pub struct Deprecated<'el>(pub &'el Option<RpDeprecated>);
+
+impl<'el> IntoTokens<'el, Swift<'el>> for Deprecated<'el> {
+    fn into_tokens(self) -> Tokens<'el, Swift<'el>> {
+        let deprecated = match *self.0 {
+            Some(ref deprecated) => deprecated,
+            None => return Tokens::new(),
+        };
+
+        match deprecated.note() {
+            Some(note) => toks!["@available(*, deprecated, message: ", note.quoted(), ")"],
+            None => toks!["@available(*, deprecated)"],
+        }
+    }
+}
+
 pub struct Compiler<'el> {
     pub env: &'el Translated<SwiftFlavor>,
     options: Options,
@@ -63,6 +84,7 @@ impl<'el> Compiler<'el> {
         &self,
         name: &SwiftName,
         comment: &'a [String],
+        deprecated: &'a Option<RpDeprecated>,
         fields: F,
         extends: bool,
     ) -> Result<Tokens<'a, Swift<'a>>>
@@ -74,6 +96,7 @@ impl<'el> Compiler<'el> {
         let mut t = Tokens::new();
 
         t.push_unless_empty(Comments(comment));
+        t.push_unless_empty(Deprecated(deprecated));
 
         if self.options.struct_model_extends.is_empty() || !extends {
             t.push(toks!["public struct ", name.name.clone(), " {"]);
@@ -97,6 +120,7 @@ impl<'el> Compiler<'el> {
 
                 for field in fields.iter() {
                     t.push_unless_empty(Comments(&field.comment));
+                    t.push_unless_empty(Deprecated(&field.deprecated));
                     let ty = self.into_field(field)?;
                     t.push(toks!["let ", field.safe_ident(), ": ", ty]);
                 }
@@ -123,6 +147,7 @@ impl<'el> Compiler<'el> {
         &self,
         name: &'a SwiftName,
         comment: &'a [String],
+        deprecated: &'a Option<RpDeprecated>,
         fields: F,
     ) -> Result<Tokens<'a, Swift<'a>>>
     where
@@ -132,7 +157,13 @@ impl<'el> Compiler<'el> {
 
         let mut tokens = Tokens::new();
 
-        tokens.push(self.model_struct(name, comment, fields.iter().cloned(), true)?);
+        tokens.push(self.model_struct(
+            name,
+            comment,
+            deprecated,
+            fields.iter().cloned(),
+            true,
+        )?);
 
         for g in &self.options.type_gens {
             g.generate(TypeAdded {
@@ -146,6 +177,41 @@ impl<'el> Compiler<'el> {
         return Ok(tokens);
     }
 
+    /// Build the declaration of a single endpoint in a service protocol.
+    fn endpoint<'a>(&self, e: &'a RpEndpoint) -> Tokens<'a, Swift<'a>> {
+        let mut args = Tokens::new();
+
+        for a in &e.arguments {
+            let ty = match *a.channel {
+                RpChannel::Unary { ref ty } => toks![ty.ty()],
+                RpChannel::Streaming { ref ty } => toks!["AsyncStream<", ty.ty(), ">"],
+            };
+
+            args.append(toks![a.safe_ident(), ": ", ty]);
+        }
+
+        let mut t = toks![
+            "func ",
+            e.safe_ident(),
+            "(",
+            args.join(", "),
+            ") async throws"
+        ];
+
+        if let Some(ref response) = e.response {
+            let ty = match **response {
+                RpChannel::Unary { ref ty } => toks![ty.ty()],
+                RpChannel::Streaming { ref ty } => {
+                    toks!["AsyncThrowingStream<", ty.ty(), ", Error>"]
+                }
+            };
+
+            t.append(toks![" -> ", ty]);
+        }
+
+        t
+    }
+
     pub fn compile(&self, packages: &Packages) -> Result<()> {
         let mut files = self.populate_files()?;
 
@@ -158,8 +224,54 @@ impl<'el> Compiler<'el> {
             }
         }
 
+        self.write_package_swift()?;
         self.write_files(files)
     }
+
+    /// Write `Package.swift` in the root of the output directory, if configured.
+    fn write_package_swift(&self) -> Result<()> {
+        let (name, tools_version) = match (
+            &self.options.package_name,
+            &self.options.swift_tools_version,
+        ) {
+            (&Some(ref name), &Some(ref tools_version)) => (name, tools_version),
+            _ => return Ok(()),
+        };
+
+        let path = RelativePath::new(PACKAGE_SWIFT);
+
+        debug!("+Package.swift: {}", path.display());
+
+        let mut f = self.handle.create(path)?;
+        writeln!(f, "// swift-tools-version:{}", tools_version)?;
+        writeln!(f, "import PackageDescription")?;
+        writeln!(f)?;
+        writeln!(f, "let package = Package(")?;
+        writeln!(f, "    name: \"{}\",", name)?;
+
+        // services are generated using async/await.
+        if self.options.build_service_methods {
+            writeln!(f, "    platforms: [.macOS(.v10_15), .iOS(.v13)],")?;
+        }
+
+        writeln!(f, "    products: [")?;
+        writeln!(
+            f,
+            "        .library(name: \"{name}\", targets: [\"{name}\"]),",
+            name = name
+        )?;
+        writeln!(f, "    ],")?;
+        writeln!(f, "    targets: [")?;
+        writeln!(
+            f,
+            "        .target(name: \"{name}\", path: \"Sources/{name}\"),",
+            name = name
+        )?;
+        writeln!(f, "    ]")?;
+        writeln!(f, ")")?;
+        f.flush()?;
+        Ok(())
+    }
 }
 
 impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
@@ -178,6 +290,18 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
         self.handle
     }
 
+    fn resolve_full_path(&self, package: &RpPackage) -> Result<RelativePathBuf> {
+        // sources of a Swift package belong to its only target.
+        let root = match self.options.package_name {
+            Some(ref name) => RelativePathBuf::from("Sources").join(name.as_str()),
+            None => RelativePathBuf::new(),
+        };
+
+        let mut full_path = package.parts().fold(root, |a, b| a.join(b));
+        full_path.set_extension(self.ext());
+        Ok(full_path)
+    }
+
     fn default_process(&self, _out: &mut Self::Out, _: &SwiftName) -> Result<()> {
         Ok(())
     }
@@ -186,6 +310,7 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
         out.0.extend(self.model_type(
             &body.name,
             &body.comment,
+            &body.deprecated,
             body.fields.iter().map(Loc::borrow),
         )?);
 
@@ -201,6 +326,7 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
             tokens.push(self.model_struct(
                 &body.name,
                 &body.comment,
+                &body.deprecated,
                 fields.iter().cloned(),
                 false,
             )?);
@@ -221,13 +347,31 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
     }
 
     fn process_enum(&self, out: &mut Self::Out, body: &'el RpEnumBody) -> Result<()> {
+        let unknown_variant = self.options.unknown_variant;
+
+        if unknown_variant && body.variants.iter().any(|v| v.ident() == UNKNOWN_VARIANT) {
+            return Err(format!(
+                "{}: variant `{}` conflicts with the generated unknown variant",
+                body.name, UNKNOWN_VARIANT
+            ).into());
+        }
+
         out.0.push({
             let mut t = Tokens::new();
 
+            t.push_unless_empty(Deprecated(&body.deprecated));
             t.push(toks!["public enum ", body.name.name.clone(), " {"]);
 
             for v in &body.variants {
-                nested!(t, "case ", v.ident());
+                t.nested_into(|t| {
+                    t.push_unless_empty(Comments(v.comment));
+                    t.push_unless_empty(Deprecated(v.deprecated));
+                    push!(t, "case ", v.ident());
+                });
+            }
+
+            if unknown_variant {
+                nested!(t, "case ", UNKNOWN_VARIANT, "(", body.enum_type.ty(), ")");
             }
 
             t.push("}");
@@ -240,6 +384,7 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
                 container: &mut out.0,
                 name: &body.name,
                 body: body,
+                unknown_variant: unknown_variant,
             })?;
         }
 
@@ -251,6 +396,7 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
             let mut t = Tokens::new();
 
             t.push_unless_empty(Comments(&body.comment));
+            t.push_unless_empty(Deprecated(&body.deprecated));
             t.push(toks!["public enum ", body.name.name.clone(), " {"]);
 
             t.push({
@@ -299,10 +445,134 @@ impl<'el> PackageProcessor<'el, SwiftFlavor, SwiftName> for Compiler<'el> {
                 .chain(sub_type.fields.iter())
                 .map(Loc::borrow);
 
-            out.0
-                .push(self.model_type(&sub_type.name, &sub_type.comment, fields)?);
+            out.0.push(self.model_type(
+                &sub_type.name,
+                &sub_type.comment,
+                &sub_type.deprecated,
+                fields,
+            )?);
         }
 
         return Ok(());
     }
+
+    fn process_newtype(&self, out: &mut Self::Out, body: &'el RpNewtypeBody) -> Result<()> {
+        let ty = body.ty.ty();
+
+        out.0.push({
+            let mut t = Tokens::new();
+
+            t.push_unless_empty(Comments(&body.comment));
+            t.push_unless_empty(Deprecated(&body.deprecated));
+            t.push(toks!["public struct ", body.name.name.clone(), " {"]);
+
+            t.push({
+                let mut t = Tokens::new();
+
+                nested!(t, "public let value: ", ty.clone());
+
+                t.nested_into(|t| {
+                    push!(t, "public init(_ value: ", ty.clone(), ") throws {");
+                    nested!(t, "try ", body.name.name.clone(), ".validate(value)");
+                    nested!(t, "self.value = value");
+                    push!(t, "}");
+                });
+
+                t.nested_into(|t| {
+                    push!(t, "/// Validate a value before it is wrapped.");
+                    push!(
+                        t,
+                        "public static func validate(_ value: ",
+                        ty.clone(),
+                        ") throws {"
+                    );
+
+                    if let Some(ref pattern) = body.validate.pattern {
+                        let pattern = Rc::new(pattern.to_string());
+                        let error = Rc::new(format!("value does not match pattern `{}`", pattern));
+                        let options = toks![
+                            imported("Foundation", "NSString"),
+                            ".CompareOptions.regularExpression"
+                        ];
+
+                        t.nested_into(|t| {
+                            push!(
+                                t,
+                                "if value.range(of: ",
+                                pattern.quoted(),
+                                ", options: ",
+                                options,
+                                ") == nil {"
+                            );
+                            nested!(t, "throw ValidationError.invalid(", error.quoted(), ")");
+                            push!(t, "}");
+                        });
+                    }
+
+                    push!(t, "}");
+                });
+
+                t.nested_into(|t| {
+                    push!(t, "public enum ValidationError: Error {");
+                    nested!(t, "case invalid(String)");
+                    push!(t, "}");
+                });
+
+                t.join_line_spacing()
+            });
+
+            t.push("}");
+            t
+        });
+
+        for g in &self.options.newtype_gens {
+            g.generate(NewtypeAdded {
+                container: &mut out.0,
+                name: &body.name,
+                body: body,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn process_service(&self, out: &mut Self::Out, body: &'el RpServiceBody) -> Result<()> {
+        if !self.options.build_service_methods {
+            return Ok(());
+        }
+
+        out.0.push({
+            let mut t = Tokens::new();
+
+            t.push_unless_empty(Comments(&body.comment));
+            t.push_unless_empty(Deprecated(&body.deprecated));
+            t.push(toks!["public protocol ", body.name.name.clone(), " {"]);
+
+            t.nested({
+                let mut t = Tokens::new();
+
+                for e in &body.endpoints {
+                    t.push_into(|t| {
+                        t.push_unless_empty(Comments(&e.comment));
+
+                        for a in e.arguments.iter().filter(|a| !a.comment.is_empty()) {
+                            let comment = Rc::new(a.comment.join(" "));
+                            t.push(toks!["// - Parameter ", a.safe_ident(), ": ", comment]);
+                        }
+
+                        t.push_unless_empty(Deprecated(&e.deprecated));
+                        t.push(self.endpoint(e));
+                    });
+                }
+
+                t.join_line_spacing()
+            });
+
+            t.push("}");
+
+            t
+        });
+
+        Ok(())
+    }
 }
@@ -86,6 +86,7 @@ pub struct SwiftFlavorTranslator {
     packages: Rc<Packages>,
     data: Swift<'static>,
     date: Swift<'static>,
+    decimal: Swift<'static>,
     any: Swift<'static>,
     to_upper_camel: naming::ToUpperCamel,
 }
@@ -113,6 +114,7 @@ impl SwiftFlavorTranslator {
             packages,
             data: swift::imported("Foundation", "Data"),
             date: swift::imported("Foundation", "Date"),
+            decimal: swift::imported("Foundation", "Decimal"),
             any,
             to_upper_camel: naming::to_upper_camel(),
         })
@@ -131,6 +133,7 @@ impl FlavorTranslator for SwiftFlavorTranslator {
             RpNumberKind::U64 => swift::local("UInt64"),
             RpNumberKind::I32 => swift::local("Int32"),
             RpNumberKind::I64 => swift::local("Int64"),
+            RpNumberKind::Decimal => self.decimal.clone(),
             ty => return Err(format!("unsupported number type: {}", ty).into()),
         };
 
@@ -160,6 +163,22 @@ impl FlavorTranslator for SwiftFlavorTranslator {
         })
     }
 
+    // NB: Foundation has no type which encodes as an ISO-8601 date, time of day, or duration;
+    // `DateComponents` encodes as an object and `TimeInterval` as a number of seconds. The
+    // ISO-8601 representation is kept as a string instead.
+
+    fn translate_date(&self) -> Result<SwiftType<'static>> {
+        Ok(SwiftType::from_type(swift::local("String")))
+    }
+
+    fn translate_time(&self) -> Result<SwiftType<'static>> {
+        Ok(SwiftType::from_type(swift::local("String")))
+    }
+
+    fn translate_duration(&self) -> Result<SwiftType<'static>> {
+        Ok(SwiftType::from_type(swift::local("String")))
+    }
+
     fn translate_array(&self, argument: SwiftType<'static>) -> Result<SwiftType<'static>> {
         Ok(SwiftType {
             simple: Simple::Array {
@@ -169,6 +188,11 @@ impl FlavorTranslator for SwiftFlavorTranslator {
         })
     }
 
+    fn translate_set(&self, argument: SwiftType<'static>) -> Result<SwiftType<'static>> {
+        // NB: `Set` requires elements to be `Hashable`, which generated types are not.
+        self.translate_array(argument)
+    }
+
     fn translate_map(
         &self,
         key: SwiftType<'static>,
@@ -23,24 +23,94 @@ use backend::{Initializer, IntoBytes};
 use compiler::Compiler;
 use core::errors::Result;
 use core::{CoreFlavor, Handle};
-use flavored::{RpEnumBody, RpField, RpInterfaceBody, RpPackage, SwiftName};
+use flavored::{RpEnumBody, RpField, RpInterfaceBody, RpNewtypeBody, RpPackage, SwiftName};
 use genco::swift::Swift;
 use genco::Tokens;
-use manifest::{Lang, Manifest, NoModule, TryFromToml};
+use manifest::{Lang, Manifest, ModuleDoc, ModuleOption, NoModule, TryFromToml};
 use std::any::Any;
 use std::path::Path;
 use std::rc::Rc;
 use trans::Session;
 
 const EXT: &str = "swift";
+const PACKAGE_SWIFT: &str = "Package.swift";
 const TYPE_SEP: &'static str = "_";
 
 #[derive(Clone, Copy, Default, Debug)]
 pub struct SwiftLang;
 
+/// Modules supported by the language.
+const MODULES: &[ModuleDoc] = &[
+    ModuleDoc {
+        name: "grpc",
+        description: "Generate gRPC services.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "simple",
+        description: "Generate `decode` and `encode` methods for every type.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "codable",
+        description: "Implement `Codable` for every type.",
+        options: &[
+            ModuleOption {
+                name: "datetime",
+                ty: "\"iso8601\" | \"seconds_since_epoch\" | \"milliseconds_since_epoch\" | \"formatted\"",
+                default: None,
+                description: "How `datetime` fields are represented in JSON.",
+            },
+            ModuleOption {
+                name: "datetime_format",
+                ty: "string",
+                default: None,
+                description: "Date format to use with the `formatted` strategy.",
+            },
+        ],
+    },
+    ModuleDoc {
+        name: "objc",
+        description: "Generate Objective-C compatible wrapper classes for every type.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "service_methods",
+        description: "Generate a protocol declaring every endpoint of a service.",
+        options: &[],
+    },
+    ModuleDoc {
+        name: "swift_package",
+        description: "Lay out generated sources as a Swift package.",
+        options: &[
+            ModuleOption {
+                name: "name",
+                ty: "string",
+                default: None,
+                description: "Name of the package, its target, and its library (required).",
+            },
+            ModuleOption {
+                name: "tools_version",
+                ty: "string",
+                default: Some("5.5"),
+                description: "Swift tools version to declare in `Package.swift`.",
+            },
+        ],
+    },
+    ModuleDoc {
+        name: "unknown_variant",
+        description: "Add a catch-all case to enums.",
+        options: &[],
+    },
+];
+
 impl Lang for SwiftLang {
     lang_base!(SwiftModule, compile);
 
+    fn modules(&self) -> &'static [ModuleDoc] {
+        MODULES
+    }
+
     fn comment(&self, input: &str) -> Option<String> {
         Some(format!("// {}", input))
     }
@@ -49,86 +119,16 @@ impl Lang for SwiftLang {
         Some(Box::new(naming::to_upper_camel()))
     }
 
+    fn endpoint_ident_naming(&self) -> Option<Box<naming::Naming>> {
+        Some(Box::new(naming::to_lower_camel()))
+    }
+
     fn safe_packages(&self) -> bool {
         true
     }
 
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![
-            ("as", "as_"),
-            ("associatedtype", "associatedtype_"),
-            ("associativity", "associativity_"),
-            ("break", "break_"),
-            ("case", "case_"),
-            ("catch", "catch_"),
-            ("class", "class_"),
-            ("continue", "continue_"),
-            ("convenience", "convenience_"),
-            ("default", "default_"),
-            ("defer", "defer_"),
-            ("deinit", "deinit_"),
-            ("do", "do_"),
-            ("dynamic", "dynamic_"),
-            ("else", "else_"),
-            ("enum", "enum_"),
-            ("extension", "extension_"),
-            ("fallthrough", "fallthrough_"),
-            ("false", "false_"),
-            ("fileprivate", "fileprivate_"),
-            ("final", "final_"),
-            ("for", "for_"),
-            ("func", "func_"),
-            ("get", "get_"),
-            ("guard", "guard_"),
-            ("if", "if_"),
-            ("import", "import_"),
-            ("in", "in_"),
-            ("indirect", "indirect_"),
-            ("infix", "infix_"),
-            ("init", "init_"),
-            ("inout", "inout_"),
-            ("internal", "internal_"),
-            ("is", "is_"),
-            ("lazy", "lazy_"),
-            ("left", "left_"),
-            ("let", "let_"),
-            ("mutating", "mutating_"),
-            ("nil", "nil_"),
-            ("none", "none_"),
-            ("nonmutating", "nonmutating_"),
-            ("open", "open_"),
-            ("operator", "operator_"),
-            ("optional", "optional_"),
-            ("override", "override_"),
-            ("postfix", "postfix_"),
-            ("precedence", "precedence_"),
-            ("prefix", "prefix_"),
-            ("private", "private_"),
-            ("protocol", "protocol_"),
-            ("public", "public_"),
-            ("repeat", "repeat_"),
-            ("required", "required_"),
-            ("rethrows", "rethrows_"),
-            ("return", "return_"),
-            ("right", "right_"),
-            ("self", "self_"),
-            ("set", "set_"),
-            ("static", "static_"),
-            ("struct", "struct_"),
-            ("subscript", "subscript_"),
-            ("super", "super_"),
-            ("switch", "switch_"),
-            ("throw", "throw_"),
-            ("throws", "throws_"),
-            ("true", "true_"),
-            ("try", "try_"),
-            ("typealias", "typealias_"),
-            ("unowned", "unowned_"),
-            ("var", "var_"),
-            ("weak", "weak_"),
-            ("where", "where_"),
-            ("while", "while_"),
-        ]
+    fn keywords(&self) -> Option<&'static naming::Keywords> {
+        Some(&naming::keywords::SWIFT)
     }
 }
 
@@ -136,7 +136,11 @@ impl Lang for SwiftLang {
 pub enum SwiftModule {
     Grpc,
     Simple,
-    Codable,
+    Codable(module::CodableConfig),
+    Objc,
+    ServiceMethods,
+    SwiftPackage(module::SwiftPackageConfig),
+    UnknownVariant,
 }
 
 impl TryFromToml for SwiftModule {
@@ -146,7 +150,11 @@ impl TryFromToml for SwiftModule {
         let result = match id {
             "grpc" => Grpc,
             "simple" => Simple,
-            "codable" => Codable,
+            "codable" => Codable(module::CodableConfig::default()),
+            "objc" => Objc,
+            "service_methods" => ServiceMethods,
+            "swift_package" => SwiftPackage(module::SwiftPackageConfig::default()),
+            "unknown_variant" => UnknownVariant,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -159,7 +167,11 @@ impl TryFromToml for SwiftModule {
         let result = match id {
             "grpc" => Grpc,
             "simple" => Simple,
-            "codable" => Codable,
+            "codable" => Codable(value.try_into()?),
+            "objc" => Objc,
+            "service_methods" => ServiceMethods,
+            "swift_package" => SwiftPackage(value.try_into()?),
+            "unknown_variant" => UnknownVariant,
             _ => return NoModule::illegal(path, id, value),
         };
 
@@ -176,9 +188,18 @@ pub struct Options {
     pub enum_gens: Vec<Box<EnumCodegen>>,
     pub interface_gens: Vec<Box<InterfaceCodegen>>,
     pub interface_model_gens: Vec<Box<InterfaceModelCodegen>>,
+    pub newtype_gens: Vec<Box<NewtypeCodegen>>,
     pub package_gens: Vec<Box<PackageCodegen>>,
     /// The provided Any type that should be used in structs.
     pub any_type: Vec<(&'static str, Swift<'static>)>,
+    /// Build a protocol declaring every endpoint for services.
+    pub build_service_methods: bool,
+    /// Generate an `Unknown` case for enums, which holds unknown values when decoding.
+    pub unknown_variant: bool,
+    /// Name of the Swift package to lay out generated sources as.
+    pub package_name: Option<String>,
+    /// Swift tools version of the package.
+    pub swift_tools_version: Option<String>,
 }
 
 impl Options {
@@ -191,8 +212,13 @@ impl Options {
             interface_gens: Vec::new(),
             interface_model_gens: Vec::new(),
             enum_gens: Vec::new(),
+            newtype_gens: Vec::new(),
             package_gens: Vec::new(),
             any_type: Vec::new(),
+            build_service_methods: false,
+            unknown_variant: false,
+            package_name: None,
+            swift_tools_version: None,
         }
     }
 }
@@ -208,7 +234,11 @@ pub fn options(modules: Vec<SwiftModule>) -> Result<Options> {
         let initializer: Box<Initializer<Options = Options>> = match m {
             Grpc => Box::new(module::Grpc::new()),
             Simple => Box::new(module::Simple::new()),
-            Codable => Box::new(module::Codable::new()),
+            Codable(config) => Box::new(module::Codable::new(config)),
+            Objc => Box::new(module::Objc::new()),
+            ServiceMethods => Box::new(module::ServiceMethods::new()),
+            SwiftPackage(config) => Box::new(module::SwiftPackage::new(config)),
+            UnknownVariant => Box::new(module::UnknownVariant::new()),
         };
 
         initializer.initialize(&mut options)?;
@@ -283,6 +313,8 @@ pub struct EnumAdded<'a, 'el: 'a> {
     pub container: &'a mut Tokens<'el, Swift<'el>>,
     pub name: &'el SwiftName,
     pub body: &'el RpEnumBody,
+    /// If an `Unknown` case holding unknown values has been generated.
+    pub unknown_variant: bool,
 }
 
 codegen!(EnumCodegen, EnumAdded);
@@ -305,6 +337,15 @@ pub struct InterfaceModelAdded<'a, 'el: 'a> {
 
 codegen!(InterfaceModelCodegen, InterfaceModelAdded);
 
+/// Event emitted when a newtype has been added.
+pub struct NewtypeAdded<'a, 'el: 'a> {
+    pub container: &'a mut Tokens<'el, Swift<'el>>,
+    pub name: &'el SwiftName,
+    pub body: &'el RpNewtypeBody,
+}
+
+codegen!(NewtypeCodegen, NewtypeAdded);
+
 /// Event emitted when an interface model has been added.
 pub struct PackageAdded<'a, 'el: 'a> {
     pub files: &'a mut Vec<(RpPackage, FileSpec<'el>)>,
@@ -1,24 +1,50 @@
 //! gRPC module for Rust.
 
-use backend::Initializer;
+use backend::{Initializer, UNKNOWN_VARIANT};
 use core;
 use core::errors::{Error, Result};
 use flavored::{RpEnumBody, RpField, RpInterfaceBody, RpPackage, SwiftName};
-use genco::swift::{local, Swift};
+use genco::swift::{imported, local, Swift};
 use genco::{Quoted, Tokens};
 use std::collections::BTreeSet;
 use std::rc::Rc;
 use {
     EnumAdded, EnumCodegen, FileSpec, InterfaceAdded, InterfaceCodegen, InterfaceModelAdded,
-    InterfaceModelCodegen, Options, PackageAdded, PackageCodegen, StructModelAdded,
-    StructModelCodegen, TupleAdded, TupleCodegen,
+    InterfaceModelCodegen, NewtypeAdded, NewtypeCodegen, Options, PackageAdded, PackageCodegen,
+    StructModelAdded, StructModelCodegen, TupleAdded, TupleCodegen,
 };
 
-pub struct Module {}
+/// Strategy used to encode and decode `datetime` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateTimeStrategy {
+    /// ISO 8601 formatted strings.
+    Iso8601,
+    /// Number of seconds since the unix epoch.
+    SecondsSinceEpoch,
+    /// Number of milliseconds since the unix epoch.
+    MillisecondsSinceEpoch,
+    /// Strings formatted according to `datetime_format`.
+    Formatted,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// How `datetime` fields are represented in JSON.
+    ///
+    /// If set, configured `JSONDecoder` and `JSONEncoder` factories are generated.
+    datetime: Option<DateTimeStrategy>,
+    /// Date format to use with the `formatted` strategy.
+    datetime_format: Option<String>,
+}
+
+pub struct Module {
+    config: Config,
+}
 
 impl Module {
-    pub fn new() -> Module {
-        Module {}
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
     }
 }
 
@@ -26,22 +52,61 @@ impl Initializer for Module {
     type Options = Options;
 
     fn initialize(&self, options: &mut Self::Options) -> Result<()> {
-        let codegen = Rc::new(Codegen);
+        let datetime = match self.config.datetime {
+            Some(DateTimeStrategy::Formatted) => {
+                let format = self.config.datetime_format.clone().ok_or_else(|| {
+                    "codable: `datetime_format` is required by the `formatted` strategy"
+                })?;
+
+                Some(DateTime::Formatted(format))
+            }
+            Some(DateTimeStrategy::Iso8601) => Some(DateTime::Iso8601),
+            Some(DateTimeStrategy::SecondsSinceEpoch) => Some(DateTime::SecondsSinceEpoch),
+            Some(DateTimeStrategy::MillisecondsSinceEpoch) => {
+                Some(DateTime::MillisecondsSinceEpoch)
+            }
+            None => None,
+        };
+
+        let codegen = Rc::new(Codegen::new(datetime));
         options.struct_model_extends.append("Codable");
         options.tuple_gens.push(Box::new(codegen.clone()));
         options.struct_model_gens.push(Box::new(codegen.clone()));
         options.enum_gens.push(Box::new(codegen.clone()));
         options.interface_gens.push(Box::new(codegen.clone()));
         options.interface_model_gens.push(Box::new(codegen.clone()));
+        options.newtype_gens.push(Box::new(codegen.clone()));
         options.any_type.push(("codable", local("AnyCodable")));
         options.package_gens.push(Box::new(codegen.clone()));
         Ok(())
     }
 }
 
-struct Codegen;
+/// Realized datetime strategy.
+enum DateTime {
+    Iso8601,
+    SecondsSinceEpoch,
+    MillisecondsSinceEpoch,
+    Formatted(String),
+}
+
+struct Codegen {
+    datetime: Option<DateTime>,
+    json_decoder: Swift<'static>,
+    json_encoder: Swift<'static>,
+    date_formatter: Swift<'static>,
+}
 
 impl Codegen {
+    pub fn new(datetime: Option<DateTime>) -> Codegen {
+        Codegen {
+            datetime,
+            json_decoder: imported("Foundation", "JSONDecoder"),
+            json_encoder: imported("Foundation", "JSONEncoder"),
+            date_formatter: imported("Foundation", "DateFormatter"),
+        }
+    }
+
     fn utils_package(&self) -> RpPackage {
         RpPackage::parse("reproto_codable")
     }
@@ -51,6 +116,10 @@ impl Codegen {
 
         out.0.push(any_codable()?);
 
+        if let Some(ref datetime) = self.datetime {
+            out.0.push(self.coders(datetime));
+        }
+
         return Ok(out);
 
         fn any_codable<'el>() -> Result<Tokens<'el, Swift<'el>>> {
@@ -654,6 +723,85 @@ impl Codegen {
     }
 }
 
+impl Codegen {
+    /// Build extensions that construct coders configured for the given datetime strategy.
+    fn coders<'el>(&self, datetime: &DateTime) -> Tokens<'el, Swift<'el>> {
+        let mut t = Tokens::new();
+
+        t.push({
+            let mut t = Tokens::new();
+
+            t.push(toks!["public extension ", self.json_decoder.clone(), " {"]);
+
+            t.nested_into(|t| {
+                push!(t, "/// Construct a decoder configured for the generated models.");
+                push!(t, "static func reproto() -> JSONDecoder {");
+
+                t.nested_into(|t| {
+                    push!(t, "let decoder = JSONDecoder()");
+                    t.push(self.strategy(datetime, "decoder", "dateDecodingStrategy"));
+                    push!(t, "return decoder");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push("}");
+            t
+        });
+
+        t.push({
+            let mut t = Tokens::new();
+
+            t.push(toks!["public extension ", self.json_encoder.clone(), " {"]);
+
+            t.nested_into(|t| {
+                push!(t, "/// Construct an encoder configured for the generated models.");
+                push!(t, "static func reproto() -> JSONEncoder {");
+
+                t.nested_into(|t| {
+                    push!(t, "let encoder = JSONEncoder()");
+                    t.push(self.strategy(datetime, "encoder", "dateEncodingStrategy"));
+                    push!(t, "return encoder");
+                });
+
+                push!(t, "}");
+            });
+
+            t.push("}");
+            t
+        });
+
+        t.join_line_spacing()
+    }
+
+    /// Assign the given datetime strategy to a coder.
+    fn strategy<'el>(
+        &self,
+        datetime: &DateTime,
+        var: &'el str,
+        field: &'el str,
+    ) -> Tokens<'el, Swift<'el>> {
+        let mut t = Tokens::new();
+
+        let strategy = match *datetime {
+            DateTime::Iso8601 => ".iso8601",
+            DateTime::SecondsSinceEpoch => ".secondsSinceEpoch",
+            DateTime::MillisecondsSinceEpoch => ".millisecondsSinceEpoch",
+            DateTime::Formatted(ref format) => {
+                t.push(toks!["let formatter = ", self.date_formatter.clone(), "()"]);
+                push!(t, "formatter.locale = Locale(identifier: \"en_US_POSIX\")");
+                push!(t, "formatter.timeZone = TimeZone(secondsFromGMT: 0)");
+                t.push(toks!["formatter.dateFormat = ", format.to_string().quoted()]);
+                ".formatted(formatter)"
+            }
+        };
+
+        t.push(toks![var, ".", field, " = ", strategy]);
+        t
+    }
+}
+
 impl TupleCodegen for Codegen {
     fn generate(&self, e: TupleAdded) -> Result<()> {
         let TupleAdded {
@@ -785,26 +933,31 @@ impl EnumCodegen for Codegen {
             container,
             name,
             body,
+            unknown_variant,
             ..
         } = e;
 
-        container.push(decodable(name, body)?);
-        container.push(encodable(name, body)?);
+        container.push(decodable(name, body, unknown_variant)?);
+        container.push(encodable(name, body, unknown_variant)?);
 
         return Ok(());
 
         fn decodable<'a>(
             name: &'a SwiftName,
             body: &'a RpEnumBody,
+            unknown_variant: bool,
         ) -> Result<Tokens<'a, Swift<'a>>> {
             let mut t = Tokens::new();
             t.push(toks!["extension ", name, ": Decodable {"]);
-            t.nested(init(body)?);
+            t.nested(init(body, unknown_variant)?);
             t.push("}");
 
             return Ok(t);
 
-            fn init<'a>(body: &'a RpEnumBody) -> Result<Tokens<'a, Swift<'a>>> {
+            fn init<'a>(
+                body: &'a RpEnumBody,
+                unknown_variant: bool,
+            ) -> Result<Tokens<'a, Swift<'a>>> {
                 let mut t = Tokens::new();
 
                 t.push("public init(from decoder: Decoder) throws {");
@@ -826,14 +979,29 @@ impl EnumCodegen for Codegen {
 
                         match body.variants {
                             core::RpVariants::String { ref variants } => for v in variants {
+                                // NB: aliases are only accepted when decoding.
+                                let mut values = Tokens::new();
+                                values.append(v.value.to_string().quoted());
+
+                                for alias in &v.aliases {
+                                    values.append(alias.to_string().quoted());
+                                }
+
                                 t.push_into(|t| {
-                                    push!(t, "case ", v.value.to_string().quoted(), ":");
+                                    push!(t, "case ", values.join(", "), ":");
                                     nested!(t, "self = .", v.ident());
                                 });
                             },
                             core::RpVariants::Number { ref variants } => for v in variants {
+                                let mut values = Tokens::new();
+                                values.append(v.value.to_string());
+
+                                for alias in &v.aliases {
+                                    values.append(alias.to_string());
+                                }
+
                                 t.push_into(|t| {
-                                    push!(t, "case ", v.value.to_string(), ":");
+                                    push!(t, "case ", values.join(", "), ":");
                                     nested!(t, "self = .", v.ident());
                                 });
                             },
@@ -842,13 +1010,18 @@ impl EnumCodegen for Codegen {
                         t.push({
                             let mut t = Tokens::new();
 
-                            let mut a = Tokens::new();
-                            a.append("codingPath: decoder.codingPath");
-                            a.append(toks!["debugDescription: ", "enum variant".quoted()]);
+                            if unknown_variant {
+                                t.push("case let raw:");
+                                nested!(t, "self = .", UNKNOWN_VARIANT, "(raw)");
+                            } else {
+                                let mut a = Tokens::new();
+                                a.append("codingPath: decoder.codingPath");
+                                a.append(toks!["debugDescription: ", "enum variant".quoted()]);
 
-                            t.push("default:");
-                            nested!(t, "let context = DecodingError.Context(", a.join(", "), ")");
-                            t.nested("throw DecodingError.dataCorrupted(context)");
+                                t.push("default:");
+                                nested!(t, "let context = DecodingError.Context(", a.join(", "), ")");
+                                t.nested("throw DecodingError.dataCorrupted(context)");
+                            }
 
                             t
                         });
@@ -869,13 +1042,14 @@ impl EnumCodegen for Codegen {
         fn encodable<'a>(
             name: &'a SwiftName,
             body: &'a RpEnumBody,
+            unknown_variant: bool,
         ) -> Result<Tokens<'a, Swift<'a>>> {
             let mut t = Tokens::new();
             t.push(toks!["extension ", name, ": Encodable {"]);
 
             t.push({
                 let mut t = Tokens::new();
-                t.nested(encode(body)?);
+                t.nested(encode(body, unknown_variant)?);
                 t.join_line_spacing()
             });
 
@@ -883,7 +1057,10 @@ impl EnumCodegen for Codegen {
 
             return Ok(t);
 
-            fn encode<'a>(body: &'a RpEnumBody) -> Result<Tokens<'a, Swift<'a>>> {
+            fn encode<'a>(
+                body: &'a RpEnumBody,
+                unknown_variant: bool,
+            ) -> Result<Tokens<'a, Swift<'a>>> {
                 let mut t = Tokens::new();
 
                 t.push("public func encode(to encoder: Encoder) throws {");
@@ -915,6 +1092,13 @@ impl EnumCodegen for Codegen {
                             },
                         }
 
+                        if unknown_variant {
+                            t.push_into(|t| {
+                                push!(t, "case .", UNKNOWN_VARIANT, "(let raw):");
+                                t.nested("try value.encode(raw)");
+                            });
+                        }
+
                         t.push("}");
                     });
 
@@ -929,6 +1113,53 @@ impl EnumCodegen for Codegen {
     }
 }
 
+impl NewtypeCodegen for Codegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded {
+            container,
+            name,
+            body,
+        } = e;
+
+        let ty = body.ty.ty();
+
+        container.push({
+            let mut t = Tokens::new();
+
+            t.push(toks!["extension ", name, ": Decodable {"]);
+
+            t.nested_into(|t| {
+                // NB: decode through the throwing initializer, so that values are validated.
+                push!(t, "public init(from decoder: Decoder) throws {");
+                nested!(t, "let value = try decoder.singleValueContainer()");
+                nested!(t, "try self.init(value.decode(", ty.clone(), ".self))");
+                push!(t, "}");
+            });
+
+            t.push("}");
+            t
+        });
+
+        container.push({
+            let mut t = Tokens::new();
+
+            t.push(toks!["extension ", name, ": Encodable {"]);
+
+            t.nested_into(|t| {
+                push!(t, "public func encode(to encoder: Encoder) throws {");
+                nested!(t, "var value = encoder.singleValueContainer()");
+                nested!(t, "try value.encode(self.value)");
+                push!(t, "}");
+            });
+
+            t.push("}");
+            t
+        });
+
+        Ok(())
+    }
+}
+
 impl StructModelCodegen for Codegen {
     fn generate(&self, e: StructModelAdded) -> Result<()> {
         let StructModelAdded {
@@ -958,7 +1189,151 @@ impl StructModelCodegen for Codegen {
             t
         });
 
-        Ok(())
+        // NB: 64-bit integers encoded as strings can't use the synthesized implementations.
+        if fields.iter().any(|f| f.is_number_as_string()) {
+            container.push(memberwise_init(fields));
+            container.push(init(fields));
+            container.push(encode(fields));
+        }
+
+        return Ok(());
+
+        /// Providing a decoder removes the synthesized memberwise initializer, so build our own.
+        fn memberwise_init<'a>(fields: &[&'a RpField]) -> Tokens<'a, Swift<'a>> {
+            let mut t = Tokens::new();
+
+            let mut args = Tokens::new();
+
+            for field in fields {
+                let ty = if field.is_optional() {
+                    toks![field.ty.ty(), "?"]
+                } else {
+                    toks![field.ty.ty()]
+                };
+
+                args.append(toks![field.safe_ident(), ": ", ty]);
+            }
+
+            t.push(toks!["init(", args.join(", "), ") {"]);
+
+            for field in fields {
+                let ident = field.safe_ident();
+                t.nested(toks!["self.", ident, " = ", ident]);
+            }
+
+            t.push("}");
+            t
+        }
+
+        fn init<'a>(fields: &[&'a RpField]) -> Tokens<'a, Swift<'a>> {
+            let mut t = Tokens::new();
+
+            t.push("public init(from decoder: Decoder) throws {");
+
+            t.nested_into(|t| {
+                t.push("let values = try decoder.container(keyedBy: CodingKeys.self)");
+
+                for field in fields {
+                    let ident = field.safe_ident();
+                    let key = toks![".", ident];
+                    let ty = field.ty.ty();
+
+                    if !field.is_number_as_string() {
+                        let decode = if field.is_optional() {
+                            "decodeIfPresent"
+                        } else {
+                            "decode"
+                        };
+
+                        push!(
+                            t,
+                            "self.",
+                            ident,
+                            " = try values.",
+                            decode,
+                            "(",
+                            ty,
+                            ".self, forKey: ",
+                            key,
+                            ")"
+                        );
+
+                        continue;
+                    }
+
+                    let error = toks![
+                        "throw DecodingError.dataCorruptedError(forKey: ",
+                        key.clone(),
+                        ", in: values, debugDescription: ",
+                        "expected integer string".quoted(),
+                        ")",
+                    ];
+
+                    if field.is_optional() {
+                        t.push_into(|t| {
+                            push!(
+                                t,
+                                "if let value = try values.decodeIfPresent(String.self, forKey: ",
+                                key,
+                                ") {"
+                            );
+                            t.nested_into(|t| {
+                                push!(t, "guard let number = ", ty, "(value) else {");
+                                t.nested(error);
+                                push!(t, "}");
+                                push!(t, "self.", ident, " = number");
+                            });
+                            push!(t, "} else {");
+                            nested!(t, "self.", ident, " = nil");
+                            push!(t, "}");
+                        });
+                    } else {
+                        t.push_into(|t| {
+                            let value = toks!["try values.decode(String.self, forKey: ", key, ")"];
+                            push!(t, "guard let ", ident, " = ", ty, "(", value, ") else {");
+                            t.nested(error);
+                            push!(t, "}");
+                            push!(t, "self.", ident, " = ", ident);
+                        });
+                    }
+                }
+            });
+
+            t.push("}");
+            t
+        }
+
+        fn encode<'a>(fields: &[&'a RpField]) -> Tokens<'a, Swift<'a>> {
+            let mut t = Tokens::new();
+
+            t.push("public func encode(to encoder: Encoder) throws {");
+
+            t.nested_into(|t| {
+                t.push("var values = encoder.container(keyedBy: CodingKeys.self)");
+
+                for field in fields {
+                    let ident = field.safe_ident();
+                    let key = toks![".", ident];
+
+                    let value = match (field.is_number_as_string(), field.is_optional()) {
+                        (true, true) => toks!["self.", ident, ".map { String($0) }"],
+                        (true, false) => toks!["String(self.", ident, ")"],
+                        (false, _) => toks!["self.", ident],
+                    };
+
+                    let encode = if field.is_optional() {
+                        "encodeIfPresent"
+                    } else {
+                        "encode"
+                    };
+
+                    push!(t, "try values.", encode, "(", value, ", forKey: ", key, ")");
+                }
+            });
+
+            t.push("}");
+            t
+        }
     }
 }
 
@@ -1,7 +1,15 @@
 mod codable;
 mod grpc;
+mod objc;
+mod service_methods;
 pub mod simple;
+mod swift_package;
+mod unknown_variant;
 
-pub use self::codable::Module as Codable;
+pub use self::codable::{Config as CodableConfig, Module as Codable};
 pub use self::grpc::Module as Grpc;
+pub use self::objc::Module as Objc;
+pub use self::service_methods::Module as ServiceMethods;
 pub use self::simple::Module as Simple;
+pub use self::swift_package::{Config as SwiftPackageConfig, Module as SwiftPackage};
+pub use self::unknown_variant::Module as UnknownVariant;
@@ -0,0 +1,397 @@
+//! Module that generates Objective-C compatible wrappers for all types.
+
+use backend::Initializer;
+use compiler::{Comments, Deprecated};
+use core::errors::Result;
+use flavored::{RpField, SwiftName};
+use genco::swift::{imported, local, Swift};
+use genco::Tokens;
+use module::simple::Simple;
+use naming::{self, Naming};
+use std::rc::Rc;
+use {
+    EnumAdded, EnumCodegen, InterfaceAdded, InterfaceCodegen, NewtypeAdded, NewtypeCodegen,
+    Options, TupleAdded, TupleCodegen, TypeAdded, TypeCodegen,
+};
+
+/// Members of `NSObject` which can't be used as names of properties.
+const NS_OBJECT_MEMBERS: &[&str] = &["description", "debugDescription", "hash", "superclass"];
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        let codegen = Rc::new(Codegen::new());
+        options.type_gens.push(Box::new(codegen.clone()));
+        options.tuple_gens.push(Box::new(codegen.clone()));
+        options.enum_gens.push(Box::new(codegen.clone()));
+        options.interface_gens.push(Box::new(codegen.clone()));
+        options.newtype_gens.push(Box::new(codegen.clone()));
+        Ok(())
+    }
+}
+
+/// How a Swift value is converted into a value which is representable in Objective-C.
+#[derive(Clone)]
+enum Convert<'el> {
+    /// The value is representable as it is.
+    Identity,
+    /// The value is passed to the given constructor.
+    Wrap(Tokens<'el, Swift<'el>>),
+    /// The value is converted using the given method, like `map`.
+    Map(&'static str, Box<Convert<'el>>),
+}
+
+impl<'el> Convert<'el> {
+    /// Convert each element of a collection using the given method.
+    fn map(method: &'static str, inner: Convert<'el>) -> Convert<'el> {
+        match inner {
+            Convert::Identity => Convert::Identity,
+            inner => Convert::Map(method, Box::new(inner)),
+        }
+    }
+
+    /// Apply the conversion to the given expression.
+    fn apply(&self, expr: Tokens<'el, Swift<'el>>) -> Tokens<'el, Swift<'el>> {
+        match *self {
+            Convert::Identity => expr,
+            Convert::Wrap(ref ctor) => toks![ctor.clone(), expr, ")"],
+            Convert::Map(method, ref inner) => {
+                toks![expr, ".", method, " { ", inner.apply("$0".into()), " }"]
+            }
+        }
+    }
+}
+
+struct Codegen {
+    ns_object: Swift<'static>,
+    ns_number: Swift<'static>,
+    date: Swift<'static>,
+    data: Swift<'static>,
+    string: Swift<'static>,
+    /// Types which are representable as they are, unless they are optional.
+    scalars: Vec<Swift<'static>>,
+    to_lower_camel: naming::ToLowerCamel,
+}
+
+impl Codegen {
+    pub fn new() -> Codegen {
+        Codegen {
+            ns_object: imported("Foundation", "NSObject"),
+            ns_number: imported("Foundation", "NSNumber"),
+            date: imported("Foundation", "Date"),
+            data: imported("Foundation", "Data"),
+            string: local("String"),
+            scalars: vec![
+                local("Bool"),
+                local("Float"),
+                local("Double"),
+                local("Int32"),
+                local("Int64"),
+                local("UInt32"),
+                local("UInt64"),
+            ],
+            to_lower_camel: naming::to_lower_camel(),
+        }
+    }
+
+    /// Test if the given type is a scalar.
+    fn is_scalar(&self, ty: &Swift) -> bool {
+        self.scalars.iter().any(|s| s == ty)
+    }
+
+    /// Name of the wrapper class for the given type.
+    fn wrapper<'el>(name: Swift<'el>) -> Tokens<'el, Swift<'el>> {
+        toks![name, "Objc"]
+    }
+
+    /// Find how to represent a value of the given type in Objective-C.
+    ///
+    /// Returns `None` if the value is not representable.
+    fn bridge<'el>(&self, simple: &Simple<'el>) -> Option<(Tokens<'el, Swift<'el>>, Convert<'el>)> {
+        use self::Simple::*;
+
+        let out = match *simple {
+            Type { ref ty } if *ty == self.string || self.is_scalar(ty) => {
+                (toks![ty.clone()], Convert::Identity)
+            }
+            DateTime => (toks![self.date.clone()], Convert::Identity),
+            Bytes => (toks![self.data.clone()], Convert::Identity),
+            Array { ref argument } => {
+                let (ty, convert) = self.bridge(argument)?;
+                (toks!["[", ty, "]"], Convert::map("map", convert))
+            }
+            Map { ref key, ref value } => {
+                match **key {
+                    Type { ref ty } if *ty == self.string => {}
+                    _ => return None,
+                }
+
+                let (ty, convert) = self.bridge(value)?;
+                let ty = toks!["[", self.string.clone(), ": ", ty, "]"];
+                (ty, Convert::map("mapValues", convert))
+            }
+            Name { ref name } => {
+                let ty = Self::wrapper(name.clone());
+                (ty.clone(), Convert::Wrap(toks![ty, "("]))
+            }
+            Type { .. } | Any { .. } => return None,
+        };
+
+        Some(out)
+    }
+
+    /// Find how to represent an optional value of the given type in Objective-C.
+    ///
+    /// Optional scalars are not representable, so they are boxed in an `NSNumber`.
+    fn bridge_optional<'el>(
+        &self,
+        simple: &Simple<'el>,
+    ) -> Option<(Tokens<'el, Swift<'el>>, Convert<'el>)> {
+        if let Simple::Type { ref ty } = *simple {
+            if self.is_scalar(ty) {
+                let convert = Convert::Wrap(toks![self.ns_number.clone(), "(value: "]);
+                return Some((
+                    toks![self.ns_number.clone(), "?"],
+                    Convert::map("map", convert),
+                ));
+            }
+        }
+
+        let (ty, convert) = self.bridge(simple)?;
+        Some((toks![ty, "?"], Convert::map("map", convert)))
+    }
+
+    /// Name of the property for the given field, avoiding members of `NSObject`.
+    fn property<'el>(field: &'el RpField) -> Tokens<'el, Swift<'el>> {
+        let ident = field.safe_ident();
+
+        if NS_OBJECT_MEMBERS.iter().any(|m| *m == ident) {
+            return toks![ident, "_"];
+        }
+
+        toks![ident]
+    }
+
+    /// Build a read-only property, forwarding to the wrapped value.
+    fn getter<'el>(
+        name: Tokens<'el, Swift<'el>>,
+        ty: Tokens<'el, Swift<'el>>,
+        value: Tokens<'el, Swift<'el>>,
+    ) -> Tokens<'el, Swift<'el>> {
+        let mut t = Tokens::new();
+        push!(t, "@objc public var ", name, ": ", ty, " {");
+        nested!(t, "return ", value);
+        push!(t, "}");
+        t
+    }
+
+    /// Build the wrapper class for the given type, with the given members.
+    fn class<'el>(
+        &self,
+        name: &'el SwiftName,
+        members: Vec<Tokens<'el, Swift<'el>>>,
+    ) -> Tokens<'el, Swift<'el>> {
+        let wrapper = toks![name.name.clone(), "Objc"];
+
+        let mut t = Tokens::new();
+
+        push!(t, "/// Objective-C compatible wrapper of `", name, "`.");
+        push!(
+            t,
+            "@objc public class ",
+            wrapper,
+            ": ",
+            self.ns_object.clone(),
+            " {"
+        );
+
+        t.nested({
+            let mut t = Tokens::new();
+
+            push!(t, "public let swiftValue: ", name);
+
+            t.push_into(|t| {
+                push!(t, "public init(_ swiftValue: ", name, ") {");
+                nested!(t, "self.swiftValue = swiftValue");
+                push!(t, "}");
+            });
+
+            for m in members {
+                t.push(m);
+            }
+
+            t.join_line_spacing()
+        });
+
+        push!(t, "}");
+        t
+    }
+
+    /// Build the wrapper class of a struct.
+    fn process_struct<'el>(
+        &self,
+        name: &'el SwiftName,
+        fields: &[&'el RpField],
+    ) -> Tokens<'el, Swift<'el>> {
+        let mut members = Vec::new();
+
+        for field in fields {
+            let bridge = if field.is_optional() {
+                self.bridge_optional(field.ty().simple())
+            } else {
+                self.bridge(field.ty().simple())
+            };
+
+            let (ty, convert) = match bridge {
+                Some(bridge) => bridge,
+                None => {
+                    debug!(
+                        "{}: not representable in Objective-C: {}",
+                        name,
+                        field.ident()
+                    );
+                    continue;
+                }
+            };
+
+            let value = convert.apply(toks!["self.swiftValue.", field.safe_ident()]);
+
+            let mut t = Tokens::new();
+            t.push_unless_empty(Comments(&field.comment));
+            t.push_unless_empty(Deprecated(&field.deprecated));
+            t.push(Self::getter(Self::property(field), ty, value));
+            members.push(t);
+        }
+
+        self.class(name, members)
+    }
+}
+
+impl TypeCodegen for Codegen {
+    fn generate(&self, e: TypeAdded) -> Result<()> {
+        let TypeAdded {
+            container,
+            name,
+            fields,
+            ..
+        } = e;
+
+        container.push(self.process_struct(name, fields));
+        Ok(())
+    }
+}
+
+impl TupleCodegen for Codegen {
+    fn generate(&self, e: TupleAdded) -> Result<()> {
+        let TupleAdded {
+            container,
+            name,
+            fields,
+            ..
+        } = e;
+
+        container.push(self.process_struct(name, fields));
+        Ok(())
+    }
+}
+
+impl EnumCodegen for Codegen {
+    fn generate(&self, e: EnumAdded) -> Result<()> {
+        let EnumAdded {
+            container, name, ..
+        } = e;
+
+        // NB: Objective-C enums can't hold unknown values, so expose the variant by name.
+        let name_getter = Self::getter(
+            toks!["name"],
+            toks![self.string.clone()],
+            toks!["String(describing: self.swiftValue)"],
+        );
+
+        container.push(self.class(name, vec![name_getter]));
+        Ok(())
+    }
+}
+
+impl InterfaceCodegen for Codegen {
+    fn generate(&self, e: InterfaceAdded) -> Result<()> {
+        let InterfaceAdded {
+            container,
+            name,
+            body,
+            ..
+        } = e;
+
+        let mut members = Vec::new();
+
+        // one optional property for each sub-type, which is set if it is the one in use.
+        for sub_type in &body.sub_types {
+            let property = self.to_lower_camel.convert(sub_type.ident.as_str());
+            let wrapper = toks![sub_type.name.name.clone(), "Objc"];
+
+            let mut t = Tokens::new();
+            t.push_unless_empty(Comments(&sub_type.comment));
+            t.push_unless_empty(Deprecated(&sub_type.deprecated));
+            push!(
+                t,
+                "@objc public var ",
+                property,
+                ": ",
+                wrapper.clone(),
+                "? {"
+            );
+
+            t.nested_into(|t| {
+                push!(
+                    t,
+                    "if case .",
+                    sub_type.ident.as_str(),
+                    "(let value) = self.swiftValue {"
+                );
+                nested!(t, "return ", wrapper, "(value)");
+                push!(t, "}");
+                push!(t, "return nil");
+            });
+
+            push!(t, "}");
+            members.push(t);
+        }
+
+        container.push(self.class(name, members));
+        Ok(())
+    }
+}
+
+impl NewtypeCodegen for Codegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded {
+            container,
+            name,
+            body,
+        } = e;
+
+        let mut members = Vec::new();
+
+        match self.bridge(body.ty.simple()) {
+            Some((ty, convert)) => {
+                let value = convert.apply(toks!["self.swiftValue.value"]);
+                members.push(Self::getter(toks!["value"], ty, value));
+            }
+            None => {
+                debug!("{}: not representable in Objective-C", name);
+            }
+        }
+
+        container.push(self.class(name, members));
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+//! Module that generates a protocol declaring every endpoint of a service.
+
+use backend::Initializer;
+use core::errors::Result;
+use Options;
+
+pub struct Module {}
+
+impl Module {
+    pub fn new() -> Module {
+        Module {}
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        options.build_service_methods = true;
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 //! gRPC module for Rust.
 
-use backend::Initializer;
+use backend::{Initializer, UNKNOWN_VARIANT};
 use compiler::Comments;
 use core::errors::Result;
 use core::{self, Loc};
@@ -9,8 +9,9 @@ use genco::swift::{imported, Swift};
 use genco::{Cons, IntoTokens, Quoted, Tokens};
 use std::rc::Rc;
 use {
-    Compiler, EnumAdded, EnumCodegen, FileSpec, InterfaceAdded, InterfaceCodegen, Options,
-    PackageAdded, PackageCodegen, TupleAdded, TupleCodegen, TypeAdded, TypeCodegen,
+    Compiler, EnumAdded, EnumCodegen, FileSpec, InterfaceAdded, InterfaceCodegen, NewtypeAdded,
+    NewtypeCodegen, Options, PackageAdded, PackageCodegen, TupleAdded, TupleCodegen, TypeAdded,
+    TypeCodegen,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -188,6 +189,7 @@ impl Initializer for Module {
         options.tuple_gens.push(Box::new(codegen.clone()));
         options.enum_gens.push(Box::new(codegen.clone()));
         options.interface_gens.push(Box::new(codegen.clone()));
+        options.newtype_gens.push(Box::new(codegen.clone()));
         options.package_gens.push(Box::new(codegen.clone()));
         Ok(())
     }
@@ -782,6 +784,7 @@ impl EnumCodegen for Codegen {
             container,
             name,
             body,
+            unknown_variant,
             ..
         } = e;
 
@@ -794,8 +797,8 @@ impl EnumCodegen for Codegen {
                 let mut t = Tokens::new();
 
                 // decode function
-                t.nested(decode(body, name)?);
-                t.nested(encode(body)?);
+                t.nested(decode(body, name, unknown_variant)?);
+                t.nested(encode(body, unknown_variant)?);
 
                 t.join_line_spacing()
             });
@@ -806,7 +809,11 @@ impl EnumCodegen for Codegen {
 
         return Ok(());
 
-        fn decode<'a>(body: &'a RpEnumBody, name: &'a SwiftName) -> Result<Tokens<'a, Swift<'a>>> {
+        fn decode<'a>(
+            body: &'a RpEnumBody,
+            name: &'a SwiftName,
+            unknown_variant: bool,
+        ) -> Result<Tokens<'a, Swift<'a>>> {
             let mut t = Tokens::new();
 
             t.push(toks![
@@ -828,14 +835,29 @@ impl EnumCodegen for Codegen {
 
                     match body.variants {
                         core::RpVariants::String { ref variants } => for v in variants {
+                            // NB: aliases are only accepted when decoding.
+                            let mut values = Tokens::new();
+                            values.append(v.value.to_string().quoted());
+
+                            for alias in &v.aliases {
+                                values.append(alias.to_string().quoted());
+                            }
+
                             t.nested_into(|t| {
-                                push!(t, "case ", v.value.to_string().quoted(), ":");
+                                push!(t, "case ", values.join(", "), ":");
                                 nested!(t, "return ", name, ".", v.ident());
                             });
                         },
                         core::RpVariants::Number { ref variants } => for v in variants {
+                            let mut values = Tokens::new();
+                            values.append(v.value.to_string());
+
+                            for alias in &v.aliases {
+                                values.append(alias.to_string());
+                            }
+
                             t.nested_into(|t| {
-                                push!(t, "case ", v.value.to_string(), ":");
+                                push!(t, "case ", values.join(", "), ":");
                                 nested!(t, "return ", name, ".", v.ident());
                             });
                         },
@@ -843,8 +865,15 @@ impl EnumCodegen for Codegen {
 
                     t.nested({
                         let mut t = Tokens::new();
-                        t.push("default:");
-                        t.nested("throw SerializationError.bad_value()");
+
+                        if unknown_variant {
+                            t.push("case let raw:");
+                            nested!(t, "return ", name, ".", UNKNOWN_VARIANT, "(raw)");
+                        } else {
+                            t.push("default:");
+                            t.nested("throw SerializationError.bad_value()");
+                        }
+
                         t
                     });
 
@@ -861,7 +890,10 @@ impl EnumCodegen for Codegen {
             Ok(t)
         }
 
-        fn encode<'a>(body: &'a RpEnumBody) -> Result<Tokens<'a, Swift<'a>>> {
+        fn encode<'a>(
+            body: &'a RpEnumBody,
+            unknown_variant: bool,
+        ) -> Result<Tokens<'a, Swift<'a>>> {
             let mut t = Tokens::new();
 
             push!(t, "func encode() throws -> ", body.enum_type.ty(), " {");
@@ -885,6 +917,13 @@ impl EnumCodegen for Codegen {
                     },
                 }
 
+                if unknown_variant {
+                    t.nested_into(|t| {
+                        push!(t, "case .", UNKNOWN_VARIANT, "(let raw):");
+                        t.nested("return raw");
+                    });
+                }
+
                 t.push("}");
 
                 t
@@ -896,6 +935,49 @@ impl EnumCodegen for Codegen {
     }
 }
 
+impl NewtypeCodegen for Codegen {
+    fn generate(&self, e: NewtypeAdded) -> Result<()> {
+        let NewtypeAdded {
+            container,
+            name,
+            body,
+        } = e;
+
+        let simple = body.ty.simple();
+        let decode = simple.decode_value(self, "value".into(), toks!["json"])?;
+        let encode = simple.encode_value(self, "value", toks!["self.value"])?;
+
+        container.push({
+            let mut t = Tokens::new();
+
+            t.push(toks!["public extension ", name, " {"]);
+
+            t.push({
+                let mut t = Tokens::new();
+
+                t.nested_into(|t| {
+                    push!(t, "static func decode(json: Any) throws -> ", name, " {");
+                    nested!(t, "return try ", name, "(", decode, ")");
+                    push!(t, "}");
+                });
+
+                t.nested_into(|t| {
+                    push!(t, "func encode() throws -> Any {");
+                    nested!(t, "return ", encode);
+                    push!(t, "}");
+                });
+
+                t.join_line_spacing()
+            });
+
+            t.push("}");
+            t
+        });
+
+        Ok(())
+    }
+}
+
 impl InterfaceCodegen for Codegen {
     fn generate(&self, e: InterfaceAdded) -> Result<()> {
         let InterfaceAdded {
@@ -0,0 +1,57 @@
+//! Module that lays out generated sources as a Swift package.
+
+use backend::Initializer;
+use core::errors::Result;
+use Options;
+
+/// Swift tools version declared in `Package.swift` unless configured.
+const DEFAULT_TOOLS_VERSION: &str = "5.5";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Name of the package, which is also used for its only target and library.
+    name: Option<String>,
+    /// The Swift tools version to declare in `Package.swift`.
+    tools_version: Option<String>,
+}
+
+pub struct Module {
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Initializer for Module {
+    type Options = Options;
+
+    fn initialize(&self, options: &mut Self::Options) -> Result<()> {
+        let name = self
+            .config
+            .name
+            .as_ref()
+            .ok_or_else(|| "swift_package: missing required option `name`")?;
+
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!("swift_package: illegal package name: {}", name).into());
+        }
+
+        let tools_version = self
+            .config
+            .tools_version
+            .as_ref()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_TOOLS_VERSION);
+
+        options.package_name = Some(name.to_string());
+        options.swift_tools_version = Some(tools_version.to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+//! Backend which renders user-provided Handlebars templates.
+//!
+//! Every template is declared as a module in the manifest, and is rendered either once, once per
+//! package, or once per declaration depending on `each`.
+
+extern crate handlebars;
+#[macro_use]
+extern crate log;
+extern crate reproto_core as core;
+#[macro_use]
+extern crate reproto_manifest as manifest;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json as json;
+extern crate toml;
+
+use core::errors::*;
+use core::flavored::{RpDecl, RpFile, RpVersionedPackage};
+use core::{CoreFlavor, Handle, RelativePath, RelativePathBuf};
+use handlebars::Handlebars;
+use manifest::{checked_modules, Lang, Manifest, TryFromToml};
+use std::any::Any;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use trans::Session;
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TemplateLang;
+
+impl Lang for TemplateLang {
+    lang_base!(TemplateModule, compile);
+}
+
+/// How many times a template is rendered.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Each {
+    /// Once, with all files.
+    All,
+    /// Once for every package.
+    Package,
+    /// Once for every declaration, including nested ones.
+    Decl,
+}
+
+impl Default for Each {
+    fn default() -> Self {
+        Each::All
+    }
+}
+
+/// A single template to render.
+#[derive(Debug)]
+pub struct TemplateModule {
+    id: String,
+    /// Path to the template.
+    template: PathBuf,
+    /// Template for the path of the generated file.
+    output: String,
+    each: Each,
+}
+
+impl TryFromToml for TemplateModule {
+    fn try_from_string(_: &Path, id: &str, _: String) -> Result<Self> {
+        Err(format!("{}: template modules require `template` and `output`", id).into())
+    }
+
+    fn try_from_value(base: &Path, id: &str, value: toml::Value) -> Result<Self> {
+        let config: Config = value
+            .try_into()
+            .map_err(|e| format!("{}: bad template module: {}", id, e))?;
+
+        return Ok(TemplateModule {
+            id: id.to_string(),
+            template: config.template.to_path(base),
+            output: config.output,
+            each: config.each,
+        });
+
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            template: RelativePathBuf,
+            output: String,
+            #[serde(default)]
+            each: Each,
+        }
+    }
+}
+
+fn compile(handle: &Handle, session: Session<CoreFlavor>, manifest: Manifest) -> Result<()> {
+    let session = session.translate_default()?;
+    let modules: Vec<TemplateModule> = checked_modules(manifest.modules)?;
+
+    if modules.is_empty() {
+        return Err("no templates to render, declare them as modules in the manifest".into());
+    }
+
+    let files = session.for_each_file().collect::<Vec<_>>();
+
+    // generated files are not HTML, so nothing should be escaped.
+    let mut reg = Handlebars::new();
+    reg.register_escape_fn(handlebars::no_escape);
+
+    for module in &modules {
+        let mut source = String::new();
+
+        File::open(&module.template)
+            .and_then(|mut f| f.read_to_string(&mut source))
+            .map_err(|e| format!("{}: {}", module.template.display(), e))?;
+
+        reg.register_template_string(&module.id, source)
+            .map_err(|e| format!("{}: {}", module.template.display(), e))?;
+
+        match module.each {
+            Each::All => {
+                let files = files
+                    .iter()
+                    .map(|&(package, file)| package_context(package, file))
+                    .collect::<Vec<_>>();
+
+                render(handle, &reg, module, &json!({ "files": files }))?;
+            }
+            Each::Package => {
+                for &(package, file) in &files {
+                    render(handle, &reg, module, &package_context(package, file))?;
+                }
+            }
+            Each::Decl => {
+                for &(package, file) in &files {
+                    for decl in file.for_each_decl() {
+                        render(handle, &reg, module, &decl_context(package, decl))?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a single template, and write it to the path rendered from `output`.
+fn render(
+    handle: &Handle,
+    reg: &Handlebars,
+    module: &TemplateModule,
+    context: &json::Value,
+) -> Result<()> {
+    let path = reg
+        .render_template(&module.output, context)
+        .map_err(|e| format!("{}: bad output: {}", module.id, e))?;
+
+    let path = RelativePath::new(path.trim());
+
+    if path.as_str().is_empty() || path.as_str().split('/').any(|part| part == "..") {
+        return Err(format!("{}: bad output path: `{}`", module.id, path.display()).into());
+    }
+
+    let content = reg
+        .render(&module.id, context)
+        .map_err(|e| format!("{}: {}", module.template.display(), e))?;
+
+    if let Some(parent) = path.parent() {
+        if !handle.is_dir(parent) {
+            debug!("+dir: {}", parent.display());
+            handle.create_dir_all(parent)?;
+        }
+    }
+
+    debug!("+file: {}", path.display());
+    handle.create(path)?.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Context for rendering a package.
+fn package_context(package: &RpVersionedPackage, file: &RpFile) -> json::Value {
+    json!({
+        "package": package,
+        "package_path": package_path(package),
+        "file": file,
+    })
+}
+
+/// Context for rendering a declaration.
+fn decl_context(package: &RpVersionedPackage, decl: &RpDecl) -> json::Value {
+    json!({
+        "package": package,
+        "package_path": package_path(package),
+        "kind": decl.kind(),
+        "name": decl.name().path.join("."),
+        "decl": decl,
+    })
+}
+
+/// The package as a path, like `io/reproto/toystore`.
+fn package_path(package: &RpVersionedPackage) -> String {
+    package
+        .package
+        .parts()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -17,3 +17,6 @@ pub mod package_processor;
 pub use self::initializer::Initializer;
 pub use self::into_bytes::IntoBytes;
 pub use self::package_processor::PackageProcessor;
+
+/// Identifier of the catch-all variant generated for enums, used when decoding unknown values.
+pub const UNKNOWN_VARIANT: &str = "Unknown";
@@ -1,7 +1,7 @@
 use core::errors::*;
 use core::{
     Flavor, Handle, Loc, RelativePath, RelativePathBuf, RpDecl, RpEnumBody, RpInterfaceBody,
-    RpName, RpPackage, RpServiceBody, RpTupleBody, RpTypeBody,
+    RpName, RpNewtypeBody, RpPackage, RpServiceBody, RpTupleBody, RpTypeBody, RpUnionBody,
 };
 use std::cmp;
 use std::collections::BTreeMap;
@@ -69,10 +69,22 @@ where
         self.default_process(out, &body.name)
     }
 
+    fn process_union(&self, out: &mut Self::Out, body: &'el RpUnionBody<F>) -> Result<()> {
+        self.default_process(out, &body.name)
+    }
+
+    fn process_newtype(&self, out: &mut Self::Out, body: &'el RpNewtypeBody<F>) -> Result<()> {
+        self.default_process(out, &body.name)
+    }
+
     fn populate_files(&self) -> Result<BTreeMap<F::Package, Self::Out>> {
         self.do_populate_files(|_| Ok(()))
     }
 
+    /// Process all declarations into files.
+    ///
+    /// Declarations are processed in the order they are declared in, and files are ordered by
+    /// package, so the output is stable between runs.
     fn do_populate_files<C>(&self, mut callback: C) -> Result<BTreeMap<F::Package, Self::Out>>
     where
         C: FnMut(&'el RpDecl<F>) -> Result<()>,
@@ -94,6 +106,8 @@ where
                     Tuple(ref b) => self.process_tuple(&mut out, b),
                     Enum(ref b) => self.process_enum(&mut out, b),
                     Service(ref b) => self.process_service(&mut out, b),
+                    Union(ref b) => self.process_union(&mut out, b),
+                    Newtype(ref b) => self.process_newtype(&mut out, b),
                 }
             })?;
         }
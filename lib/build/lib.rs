@@ -0,0 +1,234 @@
+//! Helper for building reproto specifications from cargo build scripts.
+//!
+//! Specifications are built according to the `reproto.toml` manifest of the crate, into
+//! `OUT_DIR/reproto` unless configured otherwise.
+//! Every loaded specification is registered with `cargo:rerun-if-changed`, so that the build
+//! script is re-run whenever any of them change.
+
+extern crate reproto_core as core;
+extern crate reproto_env as env;
+extern crate reproto_manifest as manifest;
+
+use core::errors::{Error, Result};
+use core::{Diagnostic, Filesystem, RealFilesystem, Reported, Source};
+use manifest::{Language, Manifest};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Directory in `OUT_DIR` where specifications are built by default.
+pub const OUT_DIR_NAME: &'static str = "reproto";
+
+/// Build specifications from a build script.
+pub struct Build {
+    manifest_path: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+    lang: Option<Language>,
+}
+
+impl Build {
+    /// Build a new configuration using the defaults.
+    pub fn new() -> Build {
+        Build {
+            manifest_path: None,
+            out_dir: None,
+            lang: None,
+        }
+    }
+
+    /// Set the path to the manifest.
+    ///
+    /// Defaults to `reproto.toml` in the directory of the crate being built.
+    pub fn manifest_path<P: AsRef<Path>>(self, path: P) -> Self {
+        Self {
+            manifest_path: Some(path.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Set the directory to build into.
+    ///
+    /// Defaults to `reproto` in `OUT_DIR`.
+    pub fn out_dir<P: AsRef<Path>>(self, path: P) -> Self {
+        Self {
+            out_dir: Some(path.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Set the language to build for.
+    ///
+    /// Defaults to the language in the manifest, or Rust if none is specified.
+    pub fn lang(self, lang: Language) -> Self {
+        Self {
+            lang: Some(lang),
+            ..self
+        }
+    }
+
+    /// Build all targets in the manifest.
+    pub fn compile(self) -> Result<Generated> {
+        let manifest_path = match self.manifest_path {
+            Some(path) => path,
+            None => env_path("CARGO_MANIFEST_DIR")?.join(env::MANIFEST_NAME),
+        };
+
+        let out_dir = match self.out_dir {
+            Some(out_dir) => out_dir,
+            None => env_path("OUT_DIR")?.join(OUT_DIR_NAME),
+        };
+
+        println!("cargo:rerun-if-changed={}", manifest_path.display());
+
+        let mut manifest = Manifest::default();
+        manifest.path = Some(manifest_path.clone());
+
+        if let Some(lang) = self.lang.as_ref() {
+            manifest.lang = Some(env::convert_lang(lang.clone()));
+        }
+
+        manifest.from_yaml(File::open(&manifest_path)?, env::convert_lang)?;
+
+        if manifest.lang.is_none() {
+            manifest.lang = Some(env::convert_lang(Language::Rust));
+        }
+
+        for target in manifest.into_targets(self.lang.as_ref())? {
+            build_target(target, &out_dir)?;
+        }
+
+        Ok(Generated { out_dir })
+    }
+}
+
+/// Information about the built specifications.
+pub struct Generated {
+    out_dir: PathBuf,
+}
+
+impl Generated {
+    /// The directory that specifications were built into.
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    /// Path to the root module of the generated code when building for Rust.
+    ///
+    /// This can be included in the crate using `include!`.
+    pub fn root_module(&self) -> PathBuf {
+        self.out_dir.join("mod.rs")
+    }
+}
+
+/// Build a single target into the given directory.
+fn build_target(mut manifest: Manifest, out_dir: &Path) -> Result<()> {
+    let lang = manifest.lang().ok_or_else(|| "no language to build for")?;
+    manifest.output = Some(out_dir.to_owned());
+
+    // directories are registered, so that added specifications are picked up.
+    for path in &manifest.paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    let mut resolver = env::resolver(&manifest)?;
+    let mut reporter: Vec<Reported> = Vec::new();
+
+    let result = {
+        let session = lang.into_session(
+            manifest.package_prefix.clone(),
+            &mut reporter,
+            resolver.as_mut(),
+        )?;
+
+        let mut session = manifest
+            .naming
+            .apply(session)
+            .with_scalars(manifest.scalars.clone())
+            .with_numbers_as_strings(manifest.numbers_as_strings)
+            .with_path_hook(|path| {
+                println!("cargo:rerun-if-changed={}", path.display());
+                Ok(())
+            });
+
+        let mut errors: Vec<Error> = Vec::new();
+
+        for manifest::Source { package, source } in manifest.resolve(session.resolver)? {
+            if let Err(e) = session.import_source(source, Some(package)) {
+                errors.push(e.into());
+            }
+        }
+
+        if let Err(e) = session.verify() {
+            errors.push(e.into());
+        }
+
+        if errors.is_empty() {
+            let fs = RealFilesystem::new();
+            let handle = fs.open_root(Some(out_dir))?;
+            lang.compile(handle.as_ref(), session, manifest)
+        } else {
+            Err(Error::new("error when building").with_suppressed(errors))
+        }
+    };
+
+    report(&reporter)?;
+    result
+}
+
+/// Print reported diagnostics, as errors would otherwise be invisible.
+///
+/// Cargo only shows the output of build scripts when they fail, except for warnings.
+fn report(reporter: &[Reported]) -> Result<()> {
+    for reported in reporter {
+        match *reported {
+            Reported::Diagnostics(ref diagnostics) => {
+                for item in diagnostics.items() {
+                    print_diagnostic(&diagnostics.source, item)?;
+                }
+            }
+            Reported::SourceDiagnostics(ref diagnostics) => {
+                for &(ref source, ref item) in diagnostics.items() {
+                    print_diagnostic(source, item)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_diagnostic(source: &Source, item: &Diagnostic) -> Result<()> {
+    let (level, span, message) = match *item {
+        Diagnostic::Error {
+            ref span,
+            ref message,
+            ..
+        } => ("error", span, message),
+        Diagnostic::Warning {
+            ref span,
+            ref message,
+        } => ("warning", span, message),
+        Diagnostic::Info {
+            ref span,
+            ref message,
+        } => ("note", span, message),
+        Diagnostic::Symbol { .. } => return Ok(()),
+    };
+
+    let (_, line, (col, _)) = core::utils::find_line(source.read()?, (span.start, span.end))?;
+    let location = format!("{}:{}:{}", source, line + 1, col + 1);
+
+    if let Diagnostic::Warning { .. } = *item {
+        println!("cargo:warning={}: {}", location, message);
+    }
+
+    eprintln!("{}: {}: {}", level, location, message);
+    Ok(())
+}
+
+/// Get a path from the environment, as set by cargo for build scripts.
+fn env_path(name: &str) -> Result<PathBuf> {
+    match std::env::var_os(name) {
+        Some(value) => Ok(PathBuf::from(value)),
+        None => Err(format!("{}: not set, is this running from a build script?", name).into()),
+    }
+}
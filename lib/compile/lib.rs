@@ -1,10 +1,18 @@
 extern crate reproto_ast as ast;
 extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
+extern crate toml;
 
-use core::{RelativePath, Reporter, Resolver, RpPackage, RpVersionedPackage, Source};
+use core::errors::Result;
+use core::{
+    Filesystem, RelativePath, RelativePathBuf, Reporter, Resolver, RpPackage, RpVersionedPackage,
+    Source,
+};
 use manifest::Lang;
 use std::any::Any;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
 use std::str;
 
 /// Input to the compiler.
@@ -51,16 +59,202 @@ impl<'a, 'input> SimpleCompile<'a, 'input> {
     }
 }
 
+/// Compiler for using reproto as a library, like from build scripts.
+///
+/// Generated files are captured in memory and returned as an [`Output`].
+///
+/// ```ignore
+/// let output = Compiler::new(lang)
+///     .source(Source::from_path("proto/foo.reproto"), None)
+///     .module("jackson")?
+///     .compile(&mut reporter)?;
+///
+/// output.write_to(Path::new("target/generated"))?;
+/// ```
+///
+/// [`Output`]: struct.Output.html
+pub struct Compiler<'a, 'input> {
+    lang: Box<Lang>,
+    inputs: Vec<Input<'input>>,
+    modules: Vec<Box<Any + Send>>,
+    package_prefix: Option<RpPackage>,
+    resolver: Option<&'a mut Resolver>,
+}
+
+impl<'a, 'input> Compiler<'a, 'input> {
+    /// Build a new compiler for the given language.
+    pub fn new(lang: Box<Lang>) -> Compiler<'a, 'input> {
+        Compiler {
+            lang: lang,
+            inputs: Vec::new(),
+            modules: Vec::new(),
+            package_prefix: None,
+            resolver: None,
+        }
+    }
+
+    /// Add an input to compile.
+    pub fn input(mut self, input: Input<'input>) -> Self {
+        self.inputs.push(input);
+        self
+    }
+
+    /// Add a source to compile, optionally as the given package.
+    pub fn source(self, source: Source, package: Option<RpVersionedPackage>) -> Self {
+        self.input(Input::Source(source, package))
+    }
+
+    /// Enable the module with the given name, using its default configuration.
+    pub fn module(mut self, name: &str) -> Result<Self> {
+        let module = self.lang.string_spec(Path::new("."), name)?;
+        self.modules.push(module);
+        Ok(self)
+    }
+
+    /// Enable the module with the given name, using the given configuration.
+    ///
+    /// The configuration has the same structure as the module section in the manifest.
+    pub fn module_config(mut self, name: &str, config: toml::Value) -> Result<Self> {
+        let mut section = toml::value::Table::new();
+        section.insert(name.to_string(), config);
+
+        let modules = self
+            .lang
+            .module_specs(Path::new("."), Some(toml::Value::Table(section)))?;
+
+        self.modules.extend(modules.unwrap_or_default());
+        Ok(self)
+    }
+
+    /// Set package prefix.
+    pub fn package_prefix(self, package: RpPackage) -> Self {
+        Self {
+            package_prefix: Some(package),
+            ..self
+        }
+    }
+
+    /// Set resolver used for imported packages.
+    pub fn resolver(self, resolver: &'a mut Resolver) -> Self {
+        Self {
+            resolver: Some(resolver),
+            ..self
+        }
+    }
+
+    /// Compile all inputs, capturing the generated files in memory.
+    ///
+    /// Diagnostics for the inputs are sent to the reporter.
+    pub fn compile(self, reporter: &mut Reporter) -> Result<Output> {
+        let fs = core::MemoryFilesystem::new();
+        self.compile_to(reporter, &fs, None)?;
+        Ok(Output {
+            files: fs.take_files()?,
+        })
+    }
+
+    /// Compile all inputs, writing the generated files to the given filesystem.
+    ///
+    /// The root is passed to the filesystem when it is opened, which is where files are written
+    /// for the real filesystem.
+    pub fn compile_to(
+        self,
+        reporter: &mut Reporter,
+        fs: &Filesystem,
+        root: Option<&Path>,
+    ) -> Result<()> {
+        let Compiler {
+            lang,
+            inputs,
+            modules,
+            package_prefix,
+            resolver,
+        } = self;
+
+        let mut empty_resolver = core::EmptyResolver;
+        let resolver = resolver.unwrap_or_else(|| &mut empty_resolver);
+
+        let mut manifest = manifest::Manifest::default();
+        manifest.lang = Some(lang.copy());
+        manifest.modules = Some(modules);
+        manifest.package_prefix = package_prefix.clone();
+
+        let handle = fs.open_root(root)?;
+
+        let mut session = lang.into_session(package_prefix, reporter, resolver)?;
+
+        for input in inputs {
+            match input {
+                Input::File(file, package) => {
+                    session.import_file(file, package)?;
+                }
+                Input::Source(source, package) => {
+                    session.import_source(source, package)?;
+                }
+            }
+        }
+
+        lang.compile(handle.as_ref(), session, manifest)
+    }
+}
+
+/// Files generated by the [`Compiler`], in the order they were generated.
+///
+/// [`Compiler`]: struct.Compiler.html
+#[derive(Debug, Clone)]
+pub struct Output {
+    files: Vec<(RelativePathBuf, Vec<u8>)>,
+}
+
+impl Output {
+    /// Iterate over all generated files.
+    pub fn iter(&self) -> impl Iterator<Item = (&RelativePath, &[u8])> {
+        self.files
+            .iter()
+            .map(|&(ref path, ref content)| (path.as_relative_path(), content.as_slice()))
+    }
+
+    /// Get the content of the generated file with the given path.
+    pub fn get<P: AsRef<RelativePath>>(&self, path: P) -> Option<&[u8]> {
+        let path = path.as_ref();
+
+        self.files
+            .iter()
+            .find(|&&(ref p, _)| p.as_relative_path() == path)
+            .map(|&(_, ref content)| content.as_slice())
+    }
+
+    /// Convert into all generated files.
+    pub fn into_files(self) -> Vec<(RelativePathBuf, Vec<u8>)> {
+        self.files
+    }
+
+    /// Write all generated files relative to the given directory.
+    pub fn write_to(&self, root: &Path) -> Result<()> {
+        for (path, content) in self.iter() {
+            let path = path.to_path(root);
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            fs::File::create(&path)?.write_all(content)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Perform a simplified compilation that outputs the result into the provided Write
 /// implementation.
 pub fn simple_compile<'a, 'input, O>(
     mut out: O,
     config: SimpleCompile<'a, 'input>,
-    modules: Vec<Box<Any>>,
+    modules: Vec<Box<Any + Send>>,
     lang: &Lang,
-) -> core::errors::Result<()>
+) -> Result<()>
 where
-    O: FnMut(&RelativePath, &str) -> core::errors::Result<()>,
+    O: FnMut(&RelativePath, &str) -> Result<()>,
 {
     let SimpleCompile {
         input,
@@ -69,37 +263,18 @@ where
         resolver,
     } = config;
 
-    let mut empty_resolver = core::EmptyResolver;
-    let resolver = resolver.unwrap_or_else(|| &mut empty_resolver);
-
-    let capturing = core::CapturingFilesystem::new();
-    let fs = capturing.filesystem();
+    let mut compiler = Compiler::new(lang.copy()).input(input);
+    compiler.modules = modules;
 
-    let mut manifest = manifest::Manifest::default();
-    manifest.lang = Some(lang.copy());
-    manifest.modules = Some(modules);
-    manifest.package_prefix = package_prefix.clone();
-
-    let handle = fs.open_root(manifest.output.as_ref().map(AsRef::as_ref))?;
-
-    let mut session = lang.into_session(package_prefix, reporter, resolver)?;
-
-    match input {
-        Input::File(file, package) => {
-            session.import_file(file, package)?;
-        }
-        Input::Source(source, package) => {
-            session.import_source(source, package)?;
-        }
+    if let Some(package_prefix) = package_prefix {
+        compiler = compiler.package_prefix(package_prefix);
     }
 
-    lang.compile(handle.as_ref(), session, manifest)?;
-
-    let borrowed = capturing.files().try_borrow()?;
-
-    let mut it = borrowed.iter().peekable();
+    if let Some(resolver) = resolver {
+        compiler = compiler.resolver(resolver);
+    }
 
-    while let Some((path, content)) = it.next() {
+    for (path, content) in compiler.compile(reporter)?.iter() {
         let content = str::from_utf8(content)?;
         out(path, content)?;
     }
@@ -10,7 +10,7 @@ use std::hash::Hash;
 use std::mem;
 use {Diagnostics, Flavor, Loc, RpValue, Span, Translate, Translator};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(bound = "F::Package: Serialize")]
 pub struct Selection<F: 'static>
 where
@@ -33,6 +33,20 @@ where
         Selection { words, values }
     }
 
+    /// Get the given value.
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&Loc<RpValue<F>>>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.values.get(key).map(|v| &v.1)
+    }
+
+    /// Get all words.
+    pub fn words(&self) -> impl Iterator<Item = &Loc<RpValue<F>>> {
+        self.words.iter()
+    }
+
     /// Take the given value, removing it in the process.
     pub fn take<Q: ?Sized>(&mut self, key: &Q) -> Option<Loc<RpValue<F>>>
     where
@@ -76,7 +90,7 @@ where
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(bound = "F::Package: Serialize")]
 pub struct Attributes<F: 'static>
 where
@@ -97,6 +111,41 @@ where
         Attributes { words, selections }
     }
 
+    /// Test if there are no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.selections.is_empty()
+    }
+
+    /// Test if the given word is present.
+    pub fn has_word<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.words.contains_key(key)
+    }
+
+    /// Get the given selection.
+    pub fn selection<Q: ?Sized>(&self, key: &Q) -> Option<&Loc<Selection<F>>>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.selections.get(key)
+    }
+
+    /// Get the span of the given attribute, if it is present either as a word or a selection.
+    pub fn span<Q: ?Sized>(&self, key: &Q) -> Option<Span>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        self.words
+            .get(key)
+            .cloned()
+            .or_else(|| self.selections.get(key).map(Loc::span))
+    }
+
     /// Take the given selection, removing it in the process.
     pub fn take_word<Q: ?Sized>(&mut self, key: &Q) -> bool
     where
@@ -124,6 +173,18 @@ where
     }
 }
 
+impl<F: 'static> Default for Attributes<F>
+where
+    F: Flavor,
+{
+    fn default() -> Self {
+        Attributes {
+            words: HashMap::new(),
+            selections: HashMap::new(),
+        }
+    }
+}
+
 impl<F: 'static, T> Translate<T> for Attributes<F>
 where
     F: Flavor,
@@ -0,0 +1,311 @@
+//! Registry of stable error codes.
+//!
+//! Codes are attached to diagnostics, and are explained in detail by `reproto explain <code>`.
+//! A code must never be renumbered or reused for a different kind of error once released.
+
+use std::fmt;
+
+/// A stable code identifying a kind of error.
+#[derive(Debug)]
+pub struct Code {
+    /// Identifier of the code, like `E0001`.
+    pub id: &'static str,
+    /// Short summary of the error.
+    pub title: &'static str,
+    /// Detailed description of the error, with examples of how it is caused and fixed.
+    pub explanation: &'static str,
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.id)
+    }
+}
+
+pub static DUPLICATE_DEFINITION: Code = Code {
+    id: "E0001",
+    title: "name is already defined",
+    explanation: r#"A name was defined more than once in the same scope.
+
+Declarations in a file, fields in a type, variants in an enum, sub-types in an interface, and
+endpoints in a service must all have unique names.
+
+Erroneous example:
+
+```reproto
+type Post {
+  title: string;
+  title: string;
+}
+```
+
+Rename or remove one of the definitions:
+
+```reproto
+type Post {
+  title: string;
+  subtitle: string;
+}
+```
+"#,
+};
+
+pub static RESERVED_FIELD: Code = Code {
+    id: "E0002",
+    title: "field uses a reserved name",
+    explanation: r#"A field was declared with a name which is reserved through `#[reserved(...)]`.
+
+Names are typically reserved when a field is removed, to prevent it from being reused with a
+different meaning by clients which still send the old field.
+
+Erroneous example:
+
+```reproto
+#[reserved(author)]
+type Post {
+  author: string;
+}
+```
+
+Use a different name for the field:
+
+```reproto
+#[reserved(author)]
+type Post {
+  author_name: string;
+}
+```
+"#,
+};
+
+pub static FIELD_CONFLICTS_WITH_TAG: Code = Code {
+    id: "E0003",
+    title: "field has the same name as the sub-type tag",
+    explanation: r#"A field in a tagged interface or one of its sub-types has the same name as the tag
+which identifies the sub-type.
+
+The tag is added to the encoded object, so a field with the same name would be ambiguous.
+
+Erroneous example:
+
+```reproto
+#[type_info(strategy = "tagged", tag = "type")]
+interface Shape {
+  Circle {
+    type: string;
+  }
+}
+```
+
+Rename the field, or use a different tag:
+
+```reproto
+#[type_info(strategy = "tagged", tag = "kind")]
+interface Shape {
+  Circle {
+    type: string;
+  }
+}
+```
+"#,
+};
+
+pub static UNKNOWN_ATTRIBUTE: Code = Code {
+    id: "E0004",
+    title: "unknown attribute",
+    explanation: r#"An attribute, or an argument to an attribute, is not recognized by the compiler.
+
+Erroneous example:
+
+```reproto
+service Blog {
+  #[http(path = "/posts", verb = "GET")]
+  get_posts() -> [Post];
+}
+```
+
+Check the spelling of the attribute and its arguments:
+
+```reproto
+service Blog {
+  #[http(path = "/posts", method = "GET")]
+  get_posts() -> [Post];
+}
+```
+"#,
+};
+
+pub static MISPLACED_ATTRIBUTE: Code = Code {
+    id: "E0005",
+    title: "built-in attribute is not supported here",
+    explanation: r#"A built-in attribute was used in a location where it has no meaning, like `#[http]` on
+a type.
+
+Erroneous example:
+
+```reproto
+#[http(path = "/posts")]
+type Post {
+  title: string;
+}
+```
+
+Move the attribute to where it is supported, here on an endpoint:
+
+```reproto
+service Blog {
+  #[http(path = "/posts")]
+  get_posts() -> [Post];
+}
+```
+"#,
+};
+
+pub static MISSING_IMPORT: Code = Code {
+    id: "E0006",
+    title: "imported package does not exist",
+    explanation: r#"A `use` statement refers to a package which could not be found in any of the paths or
+repositories available to the build.
+
+Erroneous example:
+
+```reproto
+use io.reproto.missing "^1" as m;
+```
+
+Make sure that the package is available, either by adding the directory it is in to `paths` in
+the manifest, or by publishing it to the repository.
+If the package exists, check that one of its versions matches the requested version range.
+"#,
+};
+
+pub static UNKNOWN_PATH_VARIABLE: Code = Code {
+    id: "E0007",
+    title: "path variable is not an argument to the endpoint",
+    explanation: r#"A variable in the path of `#[http(path = ...)]` does not correspond to any argument of
+the endpoint.
+
+Erroneous example:
+
+```reproto
+service Blog {
+  #[http(path = "/posts/{id}")]
+  get_post() -> Post;
+}
+```
+
+Declare an argument with the same name as the variable:
+
+```reproto
+service Blog {
+  #[http(path = "/posts/{id}")]
+  get_post(id: string) -> Post;
+}
+```
+"#,
+};
+
+pub static UNUSED_HTTP_ARGUMENT: Code = Code {
+    id: "E0008",
+    title: "argument is not used in the HTTP request",
+    explanation: r#"An argument of an HTTP endpoint is neither a variable in its path, a query parameter, a
+header, nor the body of the request.
+
+Only one argument can be sent as the body, every other argument must be part of the path, or be
+declared with `query = (...)` or `headers = (...)`.
+
+Erroneous example:
+
+```reproto
+service Blog {
+  #[http(path = "/posts", method = "POST")]
+  create_post(post: Post, author: string);
+}
+```
+
+Capture the argument in the path:
+
+```reproto
+service Blog {
+  #[http(path = "/posts/{author}", method = "POST")]
+  create_post(post: Post, author: string);
+}
+```
+"#,
+};
+
+pub static CONFLICTING_UNION_VARIANT: Code = Code {
+    id: "E0009",
+    title: "union contains the same type more than once",
+    explanation: r#"Each variant of a union is identified by its type, so the same type can't be part of a
+union more than once.
+Arrays and maps count as the same type, regardless of what they contain.
+
+Erroneous example:
+
+```reproto
+type Value = string | [string] | [u64];
+```
+
+Remove one of the variants, or wrap one of them in a type of its own:
+
+```reproto
+type Numbers {
+  values: [u64];
+}
+
+type Value = string | [string] | Numbers;
+```
+"#,
+};
+
+/// All known codes, in order.
+pub static ALL: &[&Code] = &[
+    &DUPLICATE_DEFINITION,
+    &RESERVED_FIELD,
+    &FIELD_CONFLICTS_WITH_TAG,
+    &UNKNOWN_ATTRIBUTE,
+    &MISPLACED_ATTRIBUTE,
+    &MISSING_IMPORT,
+    &UNKNOWN_PATH_VARIABLE,
+    &UNUSED_HTTP_ARGUMENT,
+    &CONFLICTING_UNION_VARIANT,
+];
+
+/// Look up a code by its identifier, like `E0001`.
+///
+/// The leading `E` is optional, and the identifier is not case-sensitive.
+pub fn lookup(id: &str) -> Option<&'static Code> {
+    let id = id.trim().to_uppercase();
+
+    let id = if id.starts_with('E') {
+        id
+    } else {
+        format!("E{}", id)
+    };
+
+    ALL.iter().cloned().find(|code| code.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_unique_ids() {
+        let mut seen = HashSet::new();
+
+        for code in ALL {
+            assert!(seen.insert(code.id), "duplicate code: {}", code.id);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(Some("E0002"), lookup("E0002").map(|c| c.id));
+        assert_eq!(Some("E0002"), lookup("e0002").map(|c| c.id));
+        assert_eq!(Some("E0002"), lookup("0002").map(|c| c.id));
+        assert!(lookup("E9999").is_none());
+    }
+}
@@ -1,7 +1,7 @@
 //! Reporter for spanned diagnostics.
 use flavored::RpName;
 use std::fmt;
-use {Source, Span};
+use {Code, Source, Span};
 
 #[derive(Debug, Clone, Copy, Serialize)]
 pub enum SymbolKind {
@@ -15,13 +15,23 @@ pub enum SymbolKind {
     Enum,
     #[serde(rename = "service")]
     Service,
+    #[serde(rename = "union")]
+    Union,
+    #[serde(rename = "newtype")]
+    Newtype,
 }
 
 /// A single diagnostic emitted by the compiler.
 #[derive(Debug, Clone)]
 pub enum Diagnostic {
-    /// A positional error.
-    Error { span: Span, message: String },
+    /// A positional error, optionally identified by a stable code.
+    Error {
+        span: Span,
+        message: String,
+        code: Option<&'static Code>,
+    },
+    /// A positional warning.
+    Warning { span: Span, message: String },
     /// A positional information string.
     Info { span: Span, message: String },
     /// A symbol that was encountered, and its location.
@@ -61,6 +71,29 @@ impl Diagnostics {
         self.items.push(Diagnostic::Error {
             span: span.into(),
             message: error.to_string(),
+            code: None,
+        });
+    }
+
+    /// Report an error identified by the given code.
+    pub fn err_with_code<S: Into<Span>, E: fmt::Display>(
+        &mut self,
+        code: &'static Code,
+        span: S,
+        error: E,
+    ) {
+        self.items.push(Diagnostic::Error {
+            span: span.into(),
+            message: error.to_string(),
+            code: Some(code),
+        });
+    }
+
+    /// Report a warning.
+    pub fn warn<S: Into<Span>, W: fmt::Display>(&mut self, span: S, warning: W) {
+        self.items.push(Diagnostic::Warning {
+            span: span.into(),
+            message: warning.to_string(),
         });
     }
 
@@ -96,6 +129,12 @@ pub enum SourceDiagnostic {
         span: Span,
         message: String,
     },
+    /// A positional warning.
+    Warning {
+        source: Source,
+        span: Span,
+        message: String,
+    },
     /// A positional information string.
     Info {
         source: Source,
@@ -138,6 +177,18 @@ impl SourceDiagnostics {
             Diagnostic::Error {
                 span: span.into(),
                 message: error.to_string(),
+                code: None,
+            },
+        ));
+    }
+
+    /// Report a warning.
+    pub fn warn<S: Into<Span>, W: fmt::Display>(&mut self, source: &Source, span: S, warning: W) {
+        self.items.push((
+            source.clone(),
+            Diagnostic::Warning {
+                span: span.into(),
+                message: warning.to_string(),
             },
         ));
     }
@@ -0,0 +1,335 @@
+//! Generate example values for declarations and types.
+//!
+//! Examples are built to illustrate what the JSON representation of a declaration looks like, and
+//! respect things like optional fields, enum variants, and the array form of tuples.
+
+use errors::Result;
+use flavored::{RpDecl, RpField, RpInterfaceBody, RpName, RpSubType, RpType};
+use std::fmt::{self, Write};
+use {Loc, RpScalarBase, RpSubTypeStrategy, RpVariantValue};
+
+/// Placeholder used for datetime examples.
+const DATETIME_EXAMPLE: &str = "2018-01-01T00:00:00Z";
+/// Placeholder used for date examples.
+const DATE_EXAMPLE: &str = "2018-01-01";
+/// Placeholder used for time examples.
+const TIME_EXAMPLE: &str = "12:00:00";
+/// Placeholder used for duration examples, one hour and thirty minutes.
+const DURATION_EXAMPLE: &str = "PT1H30M";
+/// Placeholder used for bytes examples, base64-encoded `hello`.
+const BYTES_EXAMPLE: &str = "aGVsbG8=";
+
+/// An example value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Example {
+    Null,
+    Boolean(bool),
+    /// A number, stored in its formatted form.
+    Number(String),
+    String(String),
+    Array(Vec<Example>),
+    Object(Vec<(String, Example)>),
+}
+
+impl Example {
+    /// Format the example as indented JSON.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        // NB: writing to a String can't fail.
+        self.write_json(&mut out, 0).expect("failed to format example");
+        out
+    }
+
+    fn write_json(&self, out: &mut String, level: usize) -> fmt::Result {
+        use self::Example::*;
+
+        match *self {
+            Null => out.write_str("null"),
+            Boolean(value) => write!(out, "{}", value),
+            Number(ref number) => out.write_str(number),
+            String(ref string) => write_json_string(out, string),
+            Array(ref items) => {
+                if items.is_empty() {
+                    return out.write_str("[]");
+                }
+
+                out.write_str("[\n")?;
+
+                let mut it = items.iter().peekable();
+
+                while let Some(item) = it.next() {
+                    indent(out, level + 1)?;
+                    item.write_json(out, level + 1)?;
+
+                    if it.peek().is_some() {
+                        out.write_str(",")?;
+                    }
+
+                    out.write_str("\n")?;
+                }
+
+                indent(out, level)?;
+                out.write_str("]")
+            }
+            Object(ref entries) => {
+                if entries.is_empty() {
+                    return out.write_str("{}");
+                }
+
+                out.write_str("{\n")?;
+
+                let mut it = entries.iter().peekable();
+
+                while let Some(&(ref key, ref value)) = it.next() {
+                    indent(out, level + 1)?;
+                    write_json_string(out, key)?;
+                    out.write_str(": ")?;
+                    value.write_json(out, level + 1)?;
+
+                    if it.peek().is_some() {
+                        out.write_str(",")?;
+                    }
+
+                    out.write_str("\n")?;
+                }
+
+                indent(out, level)?;
+                out.write_str("}")
+            }
+        }
+    }
+}
+
+fn indent(out: &mut String, level: usize) -> fmt::Result {
+    for _ in 0..level {
+        out.write_str("  ")?;
+    }
+
+    Ok(())
+}
+
+fn write_json_string(out: &mut String, string: &str) -> fmt::Result {
+    out.write_str("\"")?;
+
+    for c in string.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+
+    out.write_str("\"")
+}
+
+/// Generator for example values.
+///
+/// Uses the provided lookup function to resolve the declarations referenced by name.
+pub struct ExampleGenerator<'a, L>
+where
+    L: Fn(&RpName) -> Result<&'a RpDecl>,
+{
+    lookup: L,
+    /// Declarations currently being generated, used to stop recursive types.
+    stack: Vec<&'a RpName>,
+}
+
+impl<'a, L> ExampleGenerator<'a, L>
+where
+    L: Fn(&RpName) -> Result<&'a RpDecl>,
+{
+    pub fn new(lookup: L) -> Self {
+        Self {
+            lookup,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Generate an example for the given declaration.
+    ///
+    /// Returns `None` for declarations which do not have a value representation, like services.
+    pub fn decl(&mut self, decl: &'a RpDecl) -> Result<Option<Example>> {
+        use RpDecl::*;
+
+        self.stack.push(decl.name());
+
+        let example = match *decl {
+            Type(ref body) => Some(self.fields(body.fields.iter())?),
+            Tuple(ref body) => {
+                let mut items = Vec::new();
+
+                for field in &body.fields {
+                    items.push(self.ty(&field.ty)?);
+                }
+
+                Some(Example::Array(items))
+            }
+            Interface(ref body) => match body.sub_types.first() {
+                Some(sub_type) => Some(self.sub_type(body, sub_type)?),
+                None => None,
+            },
+            Enum(ref body) => match body.variants.iter().next() {
+                Some(variant) => Some(match variant.value {
+                    RpVariantValue::String(string) => Example::String(string.to_string()),
+                    RpVariantValue::Number(number) => Example::Number(number.to_string()),
+                }),
+                None => None,
+            },
+            Service(_) => None,
+            Union(ref body) => match body.variants.first() {
+                Some(variant) => Some(self.ty(&variant.ty)?),
+                None => None,
+            },
+            Newtype(ref body) => Some(self.ty(&body.ty)?),
+        };
+
+        self.stack.pop();
+        Ok(example)
+    }
+
+    /// Generate an example for the given sub type of an interface.
+    pub fn sub_type(
+        &mut self,
+        body: &'a RpInterfaceBody,
+        sub_type: &'a RpSubType,
+    ) -> Result<Example> {
+        let mut entries = Vec::new();
+
+        if let RpSubTypeStrategy::Tagged { ref tag } = body.sub_type_strategy {
+            entries.push((tag.to_string(), Example::String(sub_type.name().to_string())));
+        }
+
+        let fields = body.fields.iter().chain(sub_type.fields.iter());
+
+        if let Example::Object(rest) = self.fields(fields)? {
+            entries.extend(rest);
+        }
+
+        Ok(Example::Object(entries))
+    }
+
+    /// Generate an example object for the given set of fields.
+    fn fields<I>(&mut self, fields: I) -> Result<Example>
+    where
+        I: IntoIterator<Item = &'a Loc<RpField>>,
+    {
+        let mut entries = Vec::new();
+
+        for field in fields {
+            // optional fields which would recurse are left out.
+            if field.is_optional() && self.is_recursive(&field.ty) {
+                continue;
+            }
+
+            entries.push((field.name().to_string(), self.ty(&field.ty)?));
+        }
+
+        Ok(Example::Object(entries))
+    }
+
+    /// Generate an example for the given type.
+    pub fn ty(&mut self, ty: &'a RpType) -> Result<Example> {
+        use RpType::*;
+
+        let example = match *ty {
+            Double | Float => Example::Number("3.14".to_string()),
+            Number(ref number) => {
+                let value = number
+                    .validate
+                    .as_ref()
+                    .and_then(|v| v.min.as_ref())
+                    .map(|min| min.to_string())
+                    .unwrap_or_else(|| "42".to_string());
+
+                Example::Number(value)
+            }
+            Boolean => Example::Boolean(true),
+            String(_) => Example::String("string".to_string()),
+            DateTime => Example::String(DATETIME_EXAMPLE.to_string()),
+            Date => Example::String(DATE_EXAMPLE.to_string()),
+            Time => Example::String(TIME_EXAMPLE.to_string()),
+            Duration => Example::String(DURATION_EXAMPLE.to_string()),
+            Bytes => Example::String(BYTES_EXAMPLE.to_string()),
+            Any => Example::Object(vec![]),
+            Scalar(ref scalar) => match scalar.base {
+                RpScalarBase::Double | RpScalarBase::Float => Example::Number("3.14".to_string()),
+                RpScalarBase::Number(_) => Example::Number("42".to_string()),
+                RpScalarBase::Boolean => Example::Boolean(true),
+                RpScalarBase::String(_) => Example::String(scalar.name.clone()),
+            },
+            Name { ref name } => {
+                // recursive types are cut short.
+                if self.stack.contains(&Loc::borrow(name)) {
+                    return Ok(Example::Null);
+                }
+
+                let decl = (self.lookup)(name)?;
+
+                match self.decl(decl)? {
+                    Some(example) => example,
+                    None => Example::Null,
+                }
+            }
+            Array { ref inner } | Set { ref inner } => {
+                if self.is_recursive(inner) {
+                    Example::Array(vec![])
+                } else {
+                    Example::Array(vec![self.ty(inner)?])
+                }
+            }
+            Map { ref key, ref value } => {
+                if self.is_recursive(value) {
+                    Example::Object(vec![])
+                } else {
+                    let key = match self.ty(key)? {
+                        Example::String(string) => string,
+                        Example::Number(number) => number,
+                        _ => "key".to_string(),
+                    };
+
+                    Example::Object(vec![(key, self.ty(value)?)])
+                }
+            }
+        };
+
+        Ok(example)
+    }
+
+    /// Test if generating an example for the given type would recurse.
+    fn is_recursive(&self, ty: &RpType) -> bool {
+        use RpType::*;
+
+        match *ty {
+            Name { ref name } => self.stack.contains(&Loc::borrow(name)),
+            Array { ref inner } | Set { ref inner } => self.is_recursive(inner),
+            Map { ref value, .. } => self.is_recursive(value),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Example;
+
+    #[test]
+    fn test_to_json() {
+        let example = Example::Object(vec![
+            ("a".to_string(), Example::Number("42".to_string())),
+            (
+                "b".to_string(),
+                Example::Array(vec![Example::String("x\"y".to_string()), Example::Null]),
+            ),
+            ("c".to_string(), Example::Object(vec![])),
+        ]);
+
+        assert_eq!(
+            "{\n  \"a\": 42,\n  \"b\": [\n    \"x\\\"y\",\n    null\n  ],\n  \"c\": {}\n}",
+            example.to_json()
+        );
+    }
+}
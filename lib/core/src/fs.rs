@@ -5,8 +5,10 @@ use linked_hash_map::LinkedHashMap;
 use std::cell::RefCell;
 use std::fs;
 use std::io;
+use std::mem;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use {RelativePath, RelativePathBuf};
 
 pub trait Handle {
@@ -161,3 +163,103 @@ impl Drop for CapturingFileCreate {
         files.insert(self.path.clone(), self.buffer.clone());
     }
 }
+
+/// Capture all generated files in-memory, in the order they were created.
+///
+/// Unlike `CapturingFilesystem`, this can be shared across threads, which makes it suitable for
+/// build tool integrations that post-process the output.
+#[derive(Clone, Default)]
+pub struct MemoryFilesystem {
+    files: Arc<Mutex<Vec<(RelativePathBuf, Vec<u8>)>>>,
+}
+
+impl MemoryFilesystem {
+    pub fn new() -> MemoryFilesystem {
+        MemoryFilesystem::default()
+    }
+
+    /// Take all files captured so far.
+    pub fn take_files(&self) -> Result<Vec<(RelativePathBuf, Vec<u8>)>> {
+        let mut files = self
+            .files
+            .lock()
+            .map_err(|_| "memory filesystem lock poisoned")?;
+
+        Ok(files.drain(..).collect())
+    }
+}
+
+impl Filesystem for MemoryFilesystem {
+    fn open_root(&self, _root: Option<&Path>) -> Result<Box<Handle>> {
+        Ok(Box::new(MemoryHandle {
+            files: self.files.clone(),
+        }))
+    }
+}
+
+/// A handle that captures files into a shared vector.
+struct MemoryHandle {
+    files: Arc<Mutex<Vec<(RelativePathBuf, Vec<u8>)>>>,
+}
+
+impl Handle for MemoryHandle {
+    fn is_dir(&self, _path: &RelativePath) -> bool {
+        true
+    }
+
+    fn is_file(&self, path: &RelativePath) -> bool {
+        match self.files.lock() {
+            Ok(files) => files.iter().any(|&(ref p, _)| p.as_relative_path() == path),
+            Err(_) => false,
+        }
+    }
+
+    fn create_dir_all(&self, _path: &RelativePath) -> Result<()> {
+        Ok(())
+    }
+
+    fn create(&self, path: &RelativePath) -> Result<Box<io::Write>> {
+        Ok(Box::new(MemoryFileCreate {
+            files: self.files.clone(),
+            path: path.to_owned(),
+            buffer: Vec::new(),
+        }))
+    }
+}
+
+/// An 'open file' for the memory handle.
+///
+/// The file is stored when dropped, replacing any earlier file with the same path.
+struct MemoryFileCreate {
+    files: Arc<Mutex<Vec<(RelativePathBuf, Vec<u8>)>>>,
+    path: RelativePathBuf,
+    buffer: Vec<u8>,
+}
+
+impl io::Write for MemoryFileCreate {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.buffer.flush()
+    }
+}
+
+impl Drop for MemoryFileCreate {
+    fn drop(&mut self) {
+        let mut files = match self.files.lock() {
+            Ok(files) => files,
+            Err(_) => return,
+        };
+
+        let buffer = mem::replace(&mut self.buffer, Vec::new());
+
+        if let Some(file) = files.iter_mut().find(|f| f.0 == self.path) {
+            file.1 = buffer;
+            return;
+        }
+
+        files.push((self.path.clone(), buffer));
+    }
+}
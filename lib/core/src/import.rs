@@ -1,16 +1,18 @@
 //! Trait used to handle imports.
 
 use errors::Result;
-use {RpRequiredPackage, RpVersionedPackage};
+use {RpRequiredPackage, RpVersionedPackage, Span};
 
 pub trait Import {
     /// Perform the import.
-    fn import(&mut self, &RpRequiredPackage) -> Result<Option<RpVersionedPackage>>;
+    ///
+    /// The span is the location of the `use` declaration which caused the import.
+    fn import(&mut self, &RpRequiredPackage, Span) -> Result<Option<RpVersionedPackage>>;
 }
 
 /// no-op implementation.
 impl Import for () {
-    fn import(&mut self, _: &RpRequiredPackage) -> Result<Option<RpVersionedPackage>> {
+    fn import(&mut self, _: &RpRequiredPackage, _: Span) -> Result<Option<RpVersionedPackage>> {
         Ok(None)
     }
 }
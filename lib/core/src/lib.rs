@@ -20,8 +20,10 @@ extern crate url;
 mod macros;
 mod as_loc;
 mod attributes;
+pub mod codes;
 mod diagnostics;
 pub mod errors;
+mod example;
 mod flavor;
 pub mod flavored;
 mod fs;
@@ -37,12 +39,14 @@ mod resolver;
 mod rp_channel;
 mod rp_code;
 mod rp_decl;
+mod rp_deprecated;
 mod rp_endpoint;
 mod rp_enum;
 mod rp_field;
 mod rp_file;
 mod rp_interface;
 mod rp_name;
+mod rp_newtype;
 mod rp_number;
 mod rp_package;
 mod rp_package_format;
@@ -53,6 +57,7 @@ mod rp_service;
 mod rp_tuple;
 mod rp_type;
 mod rp_type_model;
+mod rp_union;
 mod rp_value;
 mod rp_versioned_package;
 mod source;
@@ -62,11 +67,13 @@ pub mod utils;
 mod with_span;
 
 pub use self::attributes::{Attributes, Selection};
+pub use self::codes::Code;
+pub use self::example::{Example, ExampleGenerator};
 pub use self::diagnostics::{
     Diagnostic, Diagnostics, SourceDiagnostic, SourceDiagnostics, SymbolKind,
 };
 pub use self::flavor::{AsPackage, CoreFlavor, Flavor, FlavorField};
-pub use self::fs::{CapturingFilesystem, Filesystem, Handle, RealFilesystem};
+pub use self::fs::{CapturingFilesystem, Filesystem, Handle, MemoryFilesystem, RealFilesystem};
 pub use self::import::Import;
 pub use self::loc::Loc;
 pub use self::mime::Mime;
@@ -79,16 +86,19 @@ pub use self::ropey::Rope;
 pub use self::rp_channel::RpChannel;
 pub use self::rp_code::{RpCode, RpContext};
 pub use self::rp_decl::{RpDecl, RpNamed};
+pub use self::rp_deprecated::RpDeprecated;
 pub use self::rp_endpoint::{
-    RpAccept, RpEndpoint, RpEndpointArgument, RpEndpointHttp, RpEndpointHttp1, RpHttpMethod,
+    RpAccept, RpEndpoint, RpEndpointArgument, RpEndpointError, RpEndpointHttp, RpEndpointHttp1,
+    RpHttpMethod, RpHttpParam,
 };
 pub use self::rp_enum::{
-    RpEnumBody, RpEnumType, RpVariant, RpVariantRef, RpVariantValue, RpVariants,
+    RpEnumBody, RpEnumType, RpVariant, RpVariantAliases, RpVariantRef, RpVariantValue, RpVariants,
 };
 pub use self::rp_field::RpField;
 pub use self::rp_file::{EnabledFeature, RpFile};
 pub use self::rp_interface::{RpInterfaceBody, RpSubType, RpSubTypeStrategy, DEFAULT_TAG};
 pub use self::rp_name::RpName;
+pub use self::rp_newtype::RpNewtypeBody;
 pub use self::rp_number::RpNumber;
 pub use self::rp_package::RpPackage;
 pub use self::rp_package_format::RpPackageFormat;
@@ -98,9 +108,11 @@ pub use self::rp_required_package::RpRequiredPackage;
 pub use self::rp_service::{RpServiceBody, RpServiceBodyHttp};
 pub use self::rp_tuple::RpTupleBody;
 pub use self::rp_type::{
-    RpNumberKind, RpNumberType, RpNumberValidate, RpStringType, RpStringValidate, RpType,
+    RpNumberKind, RpNumberType, RpNumberValidate, RpScalarBase, RpScalarType, RpStringType,
+    RpStringValidate, RpType,
 };
 pub use self::rp_type_model::RpTypeBody;
+pub use self::rp_union::{RpUnionBody, RpUnionVariant};
 pub use self::rp_value::RpValue;
 pub use self::rp_versioned_package::RpVersionedPackage;
 pub use self::source::Source;
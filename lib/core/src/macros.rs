@@ -9,6 +9,11 @@ macro_rules! decl_body {
             pub comment: Vec<String>,
             pub decls: Vec<$crate::rp_decl::RpDecl<$f>>,
             pub decl_idents: ::linked_hash_map::LinkedHashMap<String, usize>,
+            /// Custom attributes which are not handled by the compiler.
+            #[serde(skip_serializing_if = "$crate::Attributes::is_empty")]
+            pub attributes: $crate::Attributes<$f>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub deprecated: Option<$crate::RpDeprecated>,
             $($rest)*
         }
     };
@@ -23,12 +28,14 @@ macro_rules! decl_flavor {
         pub type RpDecl = $source::RpDecl<$flavor>;
         pub type RpEndpoint = $source::RpEndpoint<$flavor>;
         pub type RpEndpointArgument = $source::RpEndpointArgument<$flavor>;
+        pub type RpEndpointError = $source::RpEndpointError<$flavor>;
         pub type RpEndpointHttp = $source::RpEndpointHttp<$flavor>;
         pub type RpEndpointHttp1 = $source::RpEndpointHttp1<$flavor>;
         pub type RpEnumBody = $source::RpEnumBody<$flavor>;
         pub type RpField = $source::RpField<$flavor>;
         pub type RpFile = $source::RpFile<$flavor>;
         pub type RpHttpMethod = $source::RpHttpMethod;
+        pub type RpHttpParam = $source::RpHttpParam<$flavor>;
         pub type RpInterfaceBody = $source::RpInterfaceBody<$flavor>;
         pub type RpPathPart = $source::RpPathPart<$flavor>;
         pub type RpPathSpec = $source::RpPathSpec<$flavor>;
@@ -38,6 +45,9 @@ macro_rules! decl_flavor {
         pub type RpSubType = $source::RpSubType<$flavor>;
         pub type RpTupleBody = $source::RpTupleBody<$flavor>;
         pub type RpTypeBody = $source::RpTypeBody<$flavor>;
+        pub type RpUnionBody = $source::RpUnionBody<$flavor>;
+        pub type RpUnionVariant = $source::RpUnionVariant<$flavor>;
+        pub type RpNewtypeBody = $source::RpNewtypeBody<$flavor>;
         pub type RpChannel = $source::RpChannel<$flavor>;
         pub type RpEnumType = $source::RpEnumType;
         pub type RpName = $source::RpName<$flavor>;
@@ -137,12 +147,30 @@ macro_rules! translator_defaults {
             Ok(RpType::DateTime)
         }
 
+        fn translate_date(&self) -> Result<RpType<$slf::Target>> {
+            Ok(RpType::Date)
+        }
+
+        fn translate_time(&self) -> Result<RpType<$slf::Target>> {
+            Ok(RpType::Time)
+        }
+
+        fn translate_duration(&self) -> Result<RpType<$slf::Target>> {
+            Ok(RpType::Duration)
+        }
+
         fn translate_array(&self, inner: RpType<$slf::Target>) -> Result<RpType<$slf::Target>> {
             Ok(RpType::Array {
                 inner: Box::new(inner),
             })
         }
 
+        fn translate_set(&self, inner: RpType<$slf::Target>) -> Result<RpType<$slf::Target>> {
+            Ok(RpType::Set {
+                inner: Box::new(inner),
+            })
+        }
+
         fn translate_map(
             &self,
             key: RpType<$slf::Target>,
@@ -162,6 +190,10 @@ macro_rules! translator_defaults {
             Ok(RpType::Bytes)
         }
 
+        fn translate_scalar(&self, scalar: $crate::RpScalarType) -> Result<RpType<$slf::Target>> {
+            Ok(RpType::Scalar(scalar))
+        }
+
         fn translate_name(
             &self,
             _reg: RpReg,
@@ -1,10 +1,15 @@
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum Language {
+    AsyncApi,
+    Avro,
     Csharp,
     Go,
+    Haskell,
     Java,
     JavaScript,
     Json,
+    JsonSchema,
+    Kotlin,
     OpenApi,
     Python,
     Python3,
@@ -11,6 +11,9 @@ pub trait Reporter {
 
     /// Check if reporter has diagnostics.
     fn has_diagnostics(&self) -> bool;
+
+    /// Check if reporter has diagnostics which are errors.
+    fn has_errors(&self) -> bool;
 }
 
 pub enum Reported {
@@ -32,4 +35,11 @@ impl Reporter for Vec<Reported> {
     fn has_diagnostics(&self) -> bool {
         !self.is_empty()
     }
+
+    fn has_errors(&self) -> bool {
+        self.iter().any(|reported| match *reported {
+            Reported::Diagnostics(ref diagnostics) => diagnostics.has_errors(),
+            Reported::SourceDiagnostics(ref diagnostics) => diagnostics.has_errors(),
+        })
+    }
 }
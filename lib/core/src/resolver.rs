@@ -44,6 +44,13 @@ pub trait Resolver {
     /// Resolve the specified request.
     fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Option<Resolved>>;
 
+    /// Resolve all versions matching the specified request, from oldest to newest.
+    ///
+    /// Defaults to only resolving the version that `resolve` would.
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        Ok(self.resolve(package)?.into_iter().collect())
+    }
+
     /// Resolve by prefix.
     fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>>;
 
@@ -4,8 +4,9 @@ use errors::Result;
 use serde::Serialize;
 use std::fmt;
 use {
-    Diagnostics, Flavor, Loc, RpEnumBody, RpInterfaceBody, RpReg, RpServiceBody, RpSubType,
-    RpTupleBody, RpTypeBody, RpVariantRef, Span, Translate, Translator,
+    Diagnostics, Flavor, Loc, RpDeprecated, RpEnumBody, RpInterfaceBody, RpNewtypeBody, RpReg,
+    RpServiceBody, RpSubType, RpTupleBody, RpTypeBody, RpUnionBody, RpVariantRef, Span, Translate,
+    Translator,
 };
 
 #[derive(Debug, Clone)]
@@ -20,6 +21,8 @@ where
     Enum(&'a Loc<RpEnumBody<F>>),
     EnumVariant(RpVariantRef<'a, F>),
     Service(&'a Loc<RpServiceBody<F>>),
+    Union(&'a Loc<RpUnionBody<F>>),
+    Newtype(&'a Loc<RpNewtypeBody<F>>),
 }
 
 impl<'a, F: 'static> RpNamed<'a, F>
@@ -38,6 +41,8 @@ where
             Enum(ref en) => &en.name,
             EnumVariant(ref variant) => variant.name,
             Service(ref service) => &service.name,
+            Union(ref union) => &union.name,
+            Newtype(ref newtype) => &newtype.name,
         }
     }
 
@@ -53,6 +58,8 @@ where
             Enum(ref en) => Loc::span(en),
             EnumVariant(ref variant) => variant.span,
             Service(ref service) => Loc::span(service),
+            Union(ref union) => Loc::span(union),
+            Newtype(ref newtype) => Loc::span(newtype),
         }
     }
 }
@@ -72,6 +79,8 @@ where
     Interface(Loc<RpInterfaceBody<F>>),
     Enum(Loc<RpEnumBody<F>>),
     Service(Loc<RpServiceBody<F>>),
+    Union(Loc<RpUnionBody<F>>),
+    Newtype(Loc<RpNewtypeBody<F>>),
 }
 
 impl<F: 'static> RpDecl<F>
@@ -92,6 +101,8 @@ where
             Enum(ref body) => body.decls.iter().collect::<Vec<_>>(),
             Tuple(ref body) => body.decls.iter().collect::<Vec<_>>(),
             Service(ref body) => body.decls.iter().collect::<Vec<_>>(),
+            Union(ref body) => body.decls.iter().collect::<Vec<_>>(),
+            Newtype(ref body) => body.decls.iter().collect::<Vec<_>>(),
         };
 
         decls.into_iter()
@@ -107,6 +118,8 @@ where
             Enum(ref body) => body.ident.as_str(),
             Tuple(ref body) => body.ident.as_str(),
             Service(ref body) => body.ident.as_str(),
+            Union(ref body) => body.ident.as_str(),
+            Newtype(ref body) => body.ident.as_str(),
         }
     }
 
@@ -120,6 +133,8 @@ where
             Enum(ref body) => &body.name,
             Tuple(ref body) => &body.name,
             Service(ref body) => &body.name,
+            Union(ref body) => &body.name,
+            Newtype(ref body) => &body.name,
         }
     }
 
@@ -133,6 +148,23 @@ where
             Enum(ref body) => &body.comment,
             Tuple(ref body) => &body.comment,
             Service(ref body) => &body.comment,
+            Union(ref body) => &body.comment,
+            Newtype(ref body) => &body.comment,
+        }
+    }
+
+    /// Get the deprecation of the declaration, if it is deprecated.
+    pub fn deprecated(&self) -> Option<&RpDeprecated> {
+        use self::RpDecl::*;
+
+        match *self {
+            Type(ref body) => body.deprecated.as_ref(),
+            Interface(ref body) => body.deprecated.as_ref(),
+            Enum(ref body) => body.deprecated.as_ref(),
+            Tuple(ref body) => body.deprecated.as_ref(),
+            Service(ref body) => body.deprecated.as_ref(),
+            Union(ref body) => body.deprecated.as_ref(),
+            Newtype(ref body) => body.deprecated.as_ref(),
         }
     }
 
@@ -166,6 +198,12 @@ where
             Service(ref service) => {
                 out.push((&service.name, Loc::span(service), RpReg::Service));
             }
+            Union(ref union) => {
+                out.push((&union.name, Loc::span(union), RpReg::Union));
+            }
+            Newtype(ref newtype) => {
+                out.push((&newtype.name, Loc::span(newtype), RpReg::Newtype));
+            }
         }
 
         out.extend(self.decls().flat_map(|d| d.to_reg()));
@@ -202,6 +240,12 @@ where
             Service(ref service) => {
                 out.push(RpNamed::Service(service));
             }
+            Union(ref union) => {
+                out.push(RpNamed::Union(union));
+            }
+            Newtype(ref newtype) => {
+                out.push(RpNamed::Newtype(newtype));
+            }
         }
 
         out.extend(self.decls().flat_map(|d| d.to_named()));
@@ -218,6 +262,8 @@ where
             Enum(_) => "enum",
             Tuple(_) => "tuple",
             Service(_) => "service",
+            Union(_) => "union",
+            Newtype(_) => "newtype",
         }
     }
 
@@ -231,6 +277,8 @@ where
             Enum(ref body) => Loc::span(body),
             Tuple(ref body) => Loc::span(body),
             Service(ref body) => Loc::span(body),
+            Union(ref body) => Loc::span(body),
+            Newtype(ref body) => Loc::span(body),
         }
     }
 
@@ -244,6 +292,8 @@ where
             Enum(ref body) => (&body.decls, &body.decl_idents),
             Tuple(ref body) => (&body.decls, &body.decl_idents),
             Service(ref body) => (&body.decls, &body.decl_idents),
+            Union(ref body) => (&body.decls, &body.decl_idents),
+            Newtype(ref body) => (&body.decls, &body.decl_idents),
         };
 
         match decl_idents.get(ident) {
@@ -270,6 +320,8 @@ where
             Interface(body) => Interface(body.translate(diag, translator)?),
             Enum(body) => Enum(body.translate(diag, translator)?),
             Service(body) => Service(body.translate(diag, translator)?),
+            Union(body) => Union(body.translate(diag, translator)?),
+            Newtype(body) => Newtype(body.translate(diag, translator)?),
         };
 
         Ok(out)
@@ -289,6 +341,8 @@ where
             Enum(ref body) => write!(f, "enum {}", body.name),
             Tuple(ref body) => write!(f, "tuple {}", body.name),
             Service(ref body) => write!(f, "service {}", body.name),
+            Union(ref body) => write!(f, "union {}", body.name),
+            Newtype(ref body) => write!(f, "newtype {}", body.name),
         }
     }
 }
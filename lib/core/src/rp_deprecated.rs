@@ -0,0 +1,15 @@
+//! Deprecation marker for declarations, fields, variants, and endpoints.
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RpDeprecated {
+    /// Note describing why something was deprecated, and what to use instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl RpDeprecated {
+    /// Get the note of the deprecation, if any.
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_ref().map(|s| s.as_str())
+    }
+}
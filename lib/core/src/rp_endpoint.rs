@@ -4,7 +4,10 @@ use errors::Result;
 use serde::Serialize;
 use std::default;
 use std::rc::Rc;
-use {Attributes, Diagnostics, Flavor, Loc, RpChannel, RpPathSpec, Translate, Translator};
+use {
+    Attributes, Diagnostics, Flavor, Loc, RpChannel, RpDeprecated, RpPathSpec, Translate,
+    Translator,
+};
 
 #[derive(Debug, Clone, Copy, Serialize)]
 pub enum RpHttpMethod {
@@ -42,6 +45,18 @@ pub enum RpAccept {
     Text,
 }
 
+impl RpAccept {
+    /// The media type, as it is used in `Content-Type` and `Accept` headers.
+    pub fn as_str(&self) -> &'static str {
+        use self::RpAccept::*;
+
+        match *self {
+            Json => "application/json",
+            Text => "text/plain",
+        }
+    }
+}
+
 impl default::Default for RpAccept {
     fn default() -> Self {
         RpAccept::Json
@@ -63,8 +78,16 @@ where
     /// HTTP method.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub method: Option<RpHttpMethod>,
-    /// Accepted media types.
-    pub accept: RpAccept,
+    /// Media type of the request body.
+    pub accepts: RpAccept,
+    /// Media type of the response.
+    pub produces: RpAccept,
+    /// Arguments which are sent as query parameters.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<RpHttpParam<F>>,
+    /// Arguments which are sent as headers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<RpHttpParam<F>>,
 }
 
 impl<F: 'static, T> Translate<T> for RpEndpointHttp<F>
@@ -84,7 +107,39 @@ where
             path: self.path.translate(diag, translator)?,
             body: self.body.translate(diag, translator)?,
             method: self.method,
-            accept: self.accept,
+            accepts: self.accepts,
+            produces: self.produces,
+            query: self.query.translate(diag, translator)?,
+            headers: self.headers.translate(diag, translator)?,
+        })
+    }
+}
+
+/// A query parameter or a header of an HTTP request, which is bound to an endpoint argument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(bound = "F::Type: Serialize")]
+pub struct RpHttpParam<F: 'static>
+where
+    F: Flavor,
+{
+    /// Name of the parameter, as it is sent over the wire.
+    pub name: String,
+    /// Argument bound to the parameter.
+    pub argument: RpEndpointArgument<F>,
+}
+
+impl<F: 'static, T> Translate<T> for RpHttpParam<F>
+where
+    F: Flavor,
+    T: Translator<Source = F>,
+{
+    type Out = RpHttpParam<T::Target>;
+
+    /// Translate into different flavor.
+    fn translate(self, diag: &mut Diagnostics, translator: &T) -> Result<RpHttpParam<T::Target>> {
+        Ok(RpHttpParam {
+            name: self.name,
+            argument: self.argument.translate(diag, translator)?,
         })
     }
 }
@@ -100,6 +155,9 @@ where
     pub ident: Rc<Loc<String>>,
     /// Safe identifier for the argument.
     pub safe_ident: Rc<Option<String>>,
+    /// Documentation of the argument.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub comment: Vec<String>,
     /// Channel of the argument.
     pub channel: Loc<RpChannel<F>>,
 }
@@ -120,6 +178,7 @@ where
         Ok(RpEndpointArgument {
             ident: self.ident,
             safe_ident: self.safe_ident,
+            comment: self.comment,
             channel: self.channel.translate(diag, translator)?,
         })
     }
@@ -142,6 +201,52 @@ where
     }
 }
 
+/// An error which an endpoint responds with.
+#[derive(Debug, Clone, Serialize)]
+#[serde(bound = "F::Type: Serialize")]
+pub struct RpEndpointError<F: 'static>
+where
+    F: Flavor,
+{
+    /// Identifier of the error.
+    pub ident: Loc<String>,
+    /// Type of the body which is sent with the error.
+    pub ty: F::Type,
+    /// HTTP status code of the error.
+    pub status: u16,
+}
+
+impl<F: 'static> RpEndpointError<F>
+where
+    F: Flavor,
+{
+    /// Access the identifier of the error.
+    pub fn ident(&self) -> &str {
+        self.ident.as_str()
+    }
+}
+
+impl<F: 'static, T> Translate<T> for RpEndpointError<F>
+where
+    F: Flavor,
+    T: Translator<Source = F>,
+{
+    type Out = RpEndpointError<T::Target>;
+
+    /// Translate into different flavor.
+    fn translate(
+        self,
+        diag: &mut Diagnostics,
+        translator: &T,
+    ) -> Result<RpEndpointError<T::Target>> {
+        Ok(RpEndpointError {
+            ident: self.ident,
+            ty: translator.translate_type(diag, self.ty)?,
+            status: self.status,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(bound = "F: Serialize, F::Type: Serialize, F::Package: Serialize")]
 pub struct RpEndpoint<F: 'static>
@@ -160,6 +265,9 @@ where
     pub comment: Vec<String>,
     /// Attributes associated with the endpoint.
     pub attributes: Attributes<F>,
+    /// Deprecation of the endpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<RpDeprecated>,
     /// Arguments that this endpoint accepts.
     pub arguments: Vec<RpEndpointArgument<F>>,
     /// Request type that this endpoint accepts with.
@@ -168,6 +276,9 @@ where
     /// Response type that this endpoint responds with.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response: Option<Loc<RpChannel<F>>>,
+    /// Errors that this endpoint responds with.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<RpEndpointError<F>>,
     /// HTTP configuration.
     pub http: RpEndpointHttp<F>,
 }
@@ -225,9 +336,11 @@ where
             name: self.name,
             comment: self.comment,
             attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             arguments: self.arguments.translate(diag, translator)?,
             request: self.request.translate(diag, translator)?,
             response: self.response.translate(diag, translator)?,
+            errors: self.errors.translate(diag, translator)?,
             http: self.http.translate(diag, translator)?,
         })
     }
@@ -243,6 +356,10 @@ where
     pub response: Option<F::Type>,
     pub path: RpPathSpec<F>,
     pub method: RpHttpMethod,
+    pub query: Vec<RpHttpParam<F>>,
+    pub headers: Vec<RpHttpParam<F>>,
+    pub accepts: RpAccept,
+    pub produces: RpAccept,
 }
 
 impl<F: 'static> RpEndpointHttp1<F>
@@ -284,6 +401,10 @@ where
             response,
             path,
             method,
+            query: endpoint.http.query.clone(),
+            headers: endpoint.http.headers.clone(),
+            accepts: endpoint.http.accepts,
+            produces: endpoint.http.produces,
         })
     }
 }
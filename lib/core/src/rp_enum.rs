@@ -5,8 +5,8 @@ use serde::Serialize;
 use std::fmt;
 use std::vec;
 use {
-    Diagnostics, Flavor, Loc, RpCode, RpNumber, RpNumberType, RpReg, RpStringType, RpValue, Span,
-    Translate, Translator,
+    Attributes, Diagnostics, Flavor, Loc, RpCode, RpDeprecated, RpNumber, RpNumberType, RpReg,
+    RpStringType, RpValue, Span, Translate, Translator,
 };
 
 decl_body!(pub struct RpEnumBody<F> {
@@ -40,6 +40,8 @@ where
             comment: self.comment,
             decls,
             decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             enum_type,
             variants,
             codes: self.codes,
@@ -76,6 +78,51 @@ impl<'a> fmt::Display for RpVariantValue<'a> {
     }
 }
 
+/// Type-erasured aliases of a variant.
+#[derive(Debug, Clone, Copy)]
+pub enum RpVariantAliases<'a> {
+    String(&'a [Loc<String>]),
+    Number(&'a [Loc<RpNumber>]),
+}
+
+impl<'a> RpVariantAliases<'a> {
+    /// Check if there are no aliases.
+    pub fn is_empty(&self) -> bool {
+        match *self {
+            RpVariantAliases::String(aliases) => aliases.is_empty(),
+            RpVariantAliases::Number(aliases) => aliases.is_empty(),
+        }
+    }
+
+    /// Iterate over the value of each alias.
+    pub fn iter(&self) -> vec::IntoIter<RpVariantValue<'a>> {
+        let values: Vec<_> = match *self {
+            RpVariantAliases::String(aliases) => aliases
+                .iter()
+                .map(|a| RpVariantValue::from(Loc::borrow(a)))
+                .collect(),
+            RpVariantAliases::Number(aliases) => aliases
+                .iter()
+                .map(|a| RpVariantValue::from(Loc::borrow(a)))
+                .collect(),
+        };
+
+        values.into_iter()
+    }
+}
+
+impl<'a> From<&'a [Loc<String>]> for RpVariantAliases<'a> {
+    fn from(value: &'a [Loc<String>]) -> Self {
+        RpVariantAliases::String(value)
+    }
+}
+
+impl<'a> From<&'a [Loc<RpNumber>]> for RpVariantAliases<'a> {
+    fn from(value: &'a [Loc<RpNumber>]) -> Self {
+        RpVariantAliases::Number(value)
+    }
+}
+
 /// A cheap, type-erasured variant that can be used for value comparisons.
 ///
 /// This is typically created using `RpVariants::iter()`.
@@ -88,7 +135,10 @@ where
     pub name: &'a F::Name,
     pub ident: &'a Loc<String>,
     pub comment: &'a Vec<String>,
+    pub attributes: &'a Attributes<F>,
+    pub deprecated: &'a Option<RpDeprecated>,
     pub value: RpVariantValue<'a>,
+    pub aliases: RpVariantAliases<'a>,
 }
 
 impl<'a, F: 'static> RpVariantRef<'a, F>
@@ -120,7 +170,16 @@ where
     pub name: F::Name,
     pub ident: Loc<String>,
     pub comment: Vec<String>,
+    /// Custom attributes which are not handled by the compiler.
+    #[serde(skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes<F>,
+    /// Deprecation of the variant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<RpDeprecated>,
     pub value: V,
+    /// Additional values which are accepted for the variant when decoding.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<Loc<V>>,
 }
 
 impl<'a, F: 'static, V> RpVariant<F, V>
@@ -161,7 +220,10 @@ where
             name,
             ident: self.ident,
             comment: self.comment,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             value: self.value,
+            aliases: self.aliases,
         })
     }
 }
@@ -258,7 +320,10 @@ where
                             name: &value.name,
                             ident: &value.ident,
                             comment: &value.comment,
+                            attributes: &value.attributes,
+                            deprecated: &value.deprecated,
                             value: RpVariantValue::from(&value.value),
+                            aliases: RpVariantAliases::from(value.aliases.as_slice()),
                         })
                     }
 
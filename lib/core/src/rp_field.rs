@@ -1,10 +1,10 @@
 //! Data Models for fields
 
 use errors::Result;
-use {Diagnostics, Flavor, FlavorField, Translate, Translator};
+use {Attributes, Diagnostics, RpDeprecated, Flavor, FlavorField, Translate, Translator};
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
-#[serde(bound = "F::Type: ::serde::Serialize")]
+#[serde(bound = "F::Type: ::serde::Serialize, F::Package: ::serde::Serialize")]
 pub struct RpField<F: 'static>
 where
     F: Flavor,
@@ -23,6 +23,22 @@ where
     /// Alias of field in JSON.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field_as: Option<String>,
+    /// Custom attributes which are not handled by the compiler.
+    #[serde(skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes<F>,
+    /// Deprecation of the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<RpDeprecated>,
+    /// Stable numeric identifier of the field, for formats which don't identify fields by name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    /// Encode the 64-bit integer value of the field as a JSON string.
+    #[serde(skip_serializing_if = "is_false")]
+    pub number_as_string: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl<F: 'static> FlavorField for RpField<F>
@@ -46,6 +62,10 @@ where
             comment: Vec::new(),
             ty,
             field_as: None,
+            attributes: Attributes::default(),
+            deprecated: None,
+            id: None,
+            number_as_string: false,
         }
     }
 
@@ -85,6 +105,16 @@ where
         self.field_as.as_ref().unwrap_or(&self.ident)
     }
 
+    /// Get the stable numeric identifier of the field, if one is assigned.
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    /// Check if the 64-bit integer value of the field should be encoded as a JSON string.
+    pub fn is_number_as_string(&self) -> bool {
+        self.number_as_string
+    }
+
     /// Get the type of the field.
     pub fn ty(&self) -> &F::Type {
         &self.ty
@@ -111,6 +141,10 @@ where
             comment: self.comment,
             ty: translator.translate_type(diag, self.ty)?,
             field_as: self.field_as,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
+            id: self.id,
+            number_as_string: self.number_as_string,
         })
     }
 }
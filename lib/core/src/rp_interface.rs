@@ -4,7 +4,10 @@ use errors::Result;
 use linked_hash_map::LinkedHashMap;
 use serde::Serialize;
 use translator;
-use {Diagnostics, Flavor, FlavorField, Loc, RpCode, RpDecl, RpReg, Translate, Translator};
+use {
+    Attributes, Diagnostics, Flavor, FlavorField, Loc, RpCode, RpDecl, RpDeprecated, RpReg,
+    Translate, Translator,
+};
 
 /// Default key to use for tagged sub type strategy.
 pub const DEFAULT_TAG: &str = "type";
@@ -33,6 +36,9 @@ decl_body!(pub struct RpInterfaceBody<F> {
     pub codes: Vec<Loc<RpCode>>,
     pub sub_types: Vec<Loc<RpSubType<F>>>,
     pub sub_type_strategy: RpSubTypeStrategy,
+    /// Field names which are reserved, and can't be used.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reserved: Vec<Loc<String>>,
 });
 
 impl<F: 'static> RpInterfaceBody<F>
@@ -70,10 +76,13 @@ where
             comment: self.comment,
             decls,
             decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             fields,
             codes: self.codes,
             sub_types,
             sub_type_strategy: self.sub_type_strategy,
+            reserved: self.reserved,
         })
     }
 }
@@ -93,10 +102,19 @@ where
     /// Inner declarations.
     pub decls: Vec<RpDecl<F>>,
     pub decl_idents: LinkedHashMap<String, usize>,
+    /// Custom attributes which are not handled by the compiler.
+    #[serde(skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes<F>,
+    /// Deprecation of the sub-type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<RpDeprecated>,
     pub fields: Vec<Loc<F::Field>>,
     pub codes: Vec<Loc<RpCode>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sub_type_name: Option<Loc<String>>,
+    /// Field names which are reserved, and can't be used.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reserved: Vec<Loc<String>>,
 }
 
 impl<F: 'static> RpSubType<F>
@@ -148,9 +166,12 @@ where
             comment: self.comment,
             decls,
             decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             fields,
             codes: self.codes,
             sub_type_name: self.sub_type_name,
+            reserved: self.reserved,
         })
     }
 }
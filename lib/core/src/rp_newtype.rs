@@ -0,0 +1,44 @@
+//! Model for newtypes.
+
+use errors::Result;
+use serde::Serialize;
+use {Diagnostics, Flavor, Loc, RpReg, RpStringValidate, Translate, Translator};
+
+decl_body!(
+    pub struct RpNewtypeBody<F> {
+        /// The wrapped type.
+        #[serde(rename = "type", bound = "F::Type: Serialize")]
+        pub ty: Loc<F::Type>,
+        /// Validation which is applied when a value is wrapped.
+        pub validate: RpStringValidate,
+    }
+);
+
+impl<F: 'static, T> Translate<T> for RpNewtypeBody<F>
+where
+    F: Flavor,
+    T: Translator<Source = F>,
+{
+    type Out = RpNewtypeBody<T::Target>;
+
+    /// Translate into different flavor.
+    fn translate(self, diag: &mut Diagnostics, translator: &T) -> Result<RpNewtypeBody<T::Target>> {
+        translator.visit(diag, &self.name)?;
+
+        let name = translator.translate_local_name(diag, RpReg::Newtype, self.name)?;
+        let decls = self.decls.translate(diag, translator)?;
+        let (ty, span) = Loc::take_pair(self.ty);
+
+        Ok(RpNewtypeBody {
+            name,
+            ident: self.ident,
+            comment: self.comment,
+            decls,
+            decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
+            ty: Loc::new(translator.translate_type(diag, ty)?, span),
+            validate: self.validate,
+        })
+    }
+}
@@ -13,6 +13,8 @@ pub enum RpReg {
     Enum,
     EnumVariant,
     Service,
+    Union,
+    Newtype,
 }
 
 impl RpReg {
@@ -30,7 +32,7 @@ impl RpReg {
         use self::RpReg::*;
 
         match *self {
-            Type | Interface | Enum | Tuple | Service => {
+            Type | Interface | Enum | Tuple | Service | Union | Newtype => {
                 let p = name.path.iter().map(String::as_str).collect();
                 package_fn(p)
             }
@@ -68,6 +70,8 @@ impl fmt::Display for RpReg {
             Enum => write!(fmt, "enum"),
             Tuple => write!(fmt, "tuple"),
             Service => write!(fmt, "service"),
+            Union => write!(fmt, "union"),
+            Newtype => write!(fmt, "newtype"),
             SubType => write!(fmt, "subtype"),
             EnumVariant => write!(fmt, "variant"),
         }
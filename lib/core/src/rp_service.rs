@@ -42,6 +42,8 @@ where
             comment: self.comment,
             decls,
             decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             http: self.http,
             endpoints,
         })
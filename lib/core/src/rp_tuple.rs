@@ -39,6 +39,8 @@ where
             comment: self.comment,
             decls,
             decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             fields,
             codes: self.codes,
         })
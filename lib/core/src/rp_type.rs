@@ -3,6 +3,7 @@
 use errors::Result;
 use regex::Regex;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fmt;
 use {BigInt, CoreFlavor, Flavor, Loc, RpEnumType, RpName, RpNumber};
 
@@ -34,6 +35,26 @@ pub enum RpNumberKind {
     I32,
     #[serde(rename = "i64")]
     I64,
+    #[serde(rename = "u128")]
+    U128,
+    #[serde(rename = "i128")]
+    I128,
+    /// Arbitrary precision decimal number.
+    #[serde(rename = "decimal")]
+    Decimal,
+}
+
+impl RpNumberKind {
+    /// Test if the number kind can't be represented using a native 64-bit number in all
+    /// languages.
+    pub fn is_arbitrary_precision(&self) -> bool {
+        use self::RpNumberKind::*;
+
+        match *self {
+            U128 | I128 | Decimal => true,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for RpNumberKind {
@@ -45,6 +66,9 @@ impl fmt::Display for RpNumberKind {
             U64 => "u64".fmt(fmt),
             I32 => "i32".fmt(fmt),
             I64 => "i64".fmt(fmt),
+            U128 => "u128".fmt(fmt),
+            I128 => "i128".fmt(fmt),
+            Decimal => "decimal".fmt(fmt),
         }
     }
 }
@@ -70,6 +94,10 @@ impl RpNumberType {
             RpNumberKind::U64 => (0u64.into(), MAX_SAFE_INTEGER.into()),
             RpNumberKind::I32 => (i32::min_value().into(), i32::max_value().into()),
             RpNumberKind::I64 => (MIN_SAFE_INTEGER.into(), MAX_SAFE_INTEGER.into()),
+            RpNumberKind::U128 => (0u32.into(), (BigInt::from(1) << 128) - 1),
+            RpNumberKind::I128 => (-(BigInt::from(1) << 127), (BigInt::from(1) << 127) - 1),
+            // decimals have arbitrary precision, any number is valid.
+            RpNumberKind::Decimal => return Ok(()),
         };
 
         let n = number.to_bigint().ok_or_else(|| "not a whole number")?;
@@ -95,6 +123,56 @@ pub struct RpStringType {
     pub validate: RpStringValidate,
 }
 
+/// Type which a custom scalar is encoded as.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpScalarBase {
+    Double,
+    Float,
+    Number(RpNumberType),
+    Boolean,
+    String(RpStringType),
+}
+
+/// A custom scalar type, declared in the manifest.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RpScalarType {
+    /// Name of the scalar, like `uuid`.
+    pub name: String,
+    /// Type which the scalar is encoded as.
+    pub base: RpScalarBase,
+    /// Types to use for the scalar, by the identifier of the language, like `java`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mappings: BTreeMap<String, String>,
+}
+
+impl RpScalarType {
+    /// Get the type to use for the scalar in the given language, if one is declared.
+    pub fn mapping(&self, language: &str) -> Option<&str> {
+        self.mappings.get(language).map(|m| m.as_str())
+    }
+
+    /// Get the type which the scalar is encoded as.
+    pub fn base_type<F: 'static>(&self) -> RpType<F>
+    where
+        F: Flavor,
+    {
+        match self.base {
+            RpScalarBase::Double => RpType::Double,
+            RpScalarBase::Float => RpType::Float,
+            RpScalarBase::Number(ref number) => RpType::Number(number.clone()),
+            RpScalarBase::Boolean => RpType::Boolean,
+            RpScalarBase::String(ref string) => RpType::String(string.clone()),
+        }
+    }
+}
+
+impl fmt::Display for RpScalarType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.name.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(bound = "F::Package: Serialize")]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -109,6 +187,12 @@ where
     String(RpStringType),
     /// ISO-8601 datetime
     DateTime,
+    /// ISO-8601 calendar date, like `2017-10-14`.
+    Date,
+    /// ISO-8601 time of day, like `11:42:06`.
+    Time,
+    /// ISO-8601 duration, like `PT1H30M`.
+    Duration,
     Bytes,
     Any,
     Name {
@@ -117,10 +201,16 @@ where
     Array {
         inner: Box<RpType<F>>,
     },
+    /// A collection of unique values, encoded as an array.
+    Set {
+        inner: Box<RpType<F>>,
+    },
     Map {
         key: Box<RpType<F>>,
         value: Box<RpType<F>>,
     },
+    /// A custom scalar type, declared in the manifest.
+    Scalar(RpScalarType),
 }
 
 impl<F: 'static> RpType<F>
@@ -133,7 +223,9 @@ where
 
         match *self {
             String(ref string) => Some(RpEnumType::String(string.clone())),
-            Number(ref number) => Some(RpEnumType::Number(number.clone())),
+            Number(ref number) if !number.kind.is_arbitrary_precision() => {
+                Some(RpEnumType::Number(number.clone()))
+            }
             _ => None,
         }
     }
@@ -152,6 +244,9 @@ where
             Array { inner } => Array {
                 inner: Box::new(inner.with_name(f)),
             },
+            Set { inner } => Set {
+                inner: Box::new(inner.with_name(f)),
+            },
             Map { key, value } => Map {
                 key: Box::new(key.with_name(f.clone())),
                 value: Box::new(value.with_name(f.clone())),
@@ -189,11 +284,16 @@ where
             Boolean => write!(f, "boolean"),
             String(..) => write!(f, "string"),
             DateTime => write!(f, "datetime"),
+            Date => write!(f, "date"),
+            Time => write!(f, "time"),
+            Duration => write!(f, "duration"),
             Name { ref name } => write!(f, "{}", name),
             Array { ref inner } => write!(f, "[{}]", inner),
+            Set { ref inner } => write!(f, "set<{}>", inner),
             Map { ref key, ref value } => write!(f, "{{{}: {}}}", key, value),
             Any => write!(f, "any"),
             Bytes => write!(f, "bytes"),
+            Scalar(ref scalar) => write!(f, "{}", scalar),
         }
     }
 }
@@ -7,6 +7,9 @@ use {Diagnostics, Flavor, Loc, RpCode, RpReg, Translate, Translator};
 decl_body!(pub struct RpTypeBody<F> {
     pub fields: Vec<Loc<F::Field>>,
     pub codes: Vec<Loc<RpCode>>,
+    /// Field names which are reserved, and can't be used.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reserved: Vec<Loc<String>>,
 });
 
 impl<F> RpTypeBody<F>
@@ -39,8 +42,11 @@ where
             comment: self.comment,
             decls,
             decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
             fields,
             codes: self.codes,
+            reserved: self.reserved,
         })
     }
 }
@@ -0,0 +1,75 @@
+//! Model for untagged unions.
+
+use errors::Result;
+use serde::Serialize;
+use {Diagnostics, Flavor, Loc, RpReg, Translate, Translator};
+
+decl_body!(
+    pub struct RpUnionBody<F> {
+        #[serde(bound = "F::Type: Serialize")]
+        pub variants: Vec<RpUnionVariant<F>>,
+    }
+);
+
+/// A single alternative of an untagged union.
+#[derive(Debug, Clone, Serialize)]
+#[serde(bound = "F::Type: Serialize")]
+pub struct RpUnionVariant<F: 'static>
+where
+    F: Flavor,
+{
+    /// Identifier of the variant, derived from its type.
+    pub ident: String,
+    /// The type of the variant.
+    #[serde(rename = "type")]
+    pub ty: Loc<F::Type>,
+}
+
+impl<F: 'static, T> Translate<T> for RpUnionVariant<F>
+where
+    F: Flavor,
+    T: Translator<Source = F>,
+{
+    type Out = RpUnionVariant<T::Target>;
+
+    /// Translate into different flavor.
+    fn translate(
+        self,
+        diag: &mut Diagnostics,
+        translator: &T,
+    ) -> Result<RpUnionVariant<T::Target>> {
+        let (ty, span) = Loc::take_pair(self.ty);
+
+        Ok(RpUnionVariant {
+            ident: self.ident,
+            ty: Loc::new(translator.translate_type(diag, ty)?, span),
+        })
+    }
+}
+
+impl<F: 'static, T> Translate<T> for RpUnionBody<F>
+where
+    F: Flavor,
+    T: Translator<Source = F>,
+{
+    type Out = RpUnionBody<T::Target>;
+
+    /// Translate into different flavor.
+    fn translate(self, diag: &mut Diagnostics, translator: &T) -> Result<RpUnionBody<T::Target>> {
+        translator.visit(diag, &self.name)?;
+
+        let name = translator.translate_local_name(diag, RpReg::Union, self.name)?;
+        let decls = self.decls.translate(diag, translator)?;
+
+        Ok(RpUnionBody {
+            name,
+            ident: self.ident,
+            comment: self.comment,
+            decls,
+            decl_idents: self.decl_idents,
+            attributes: self.attributes.translate(diag, translator)?,
+            deprecated: self.deprecated,
+            variants: self.variants.translate(diag, translator)?,
+        })
+    }
+}
@@ -5,7 +5,7 @@ use serde::Serialize;
 use std::fmt;
 use {Diagnostics, Flavor, Loc, RpName, RpNumber, Translate, Translator};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(
     tag = "type",
     content = "value",
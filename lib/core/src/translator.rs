@@ -10,7 +10,7 @@ use std::rc::Rc;
 use Flavor;
 use {
     CoreFlavor, Diagnostics, Loc, RpEndpoint, RpEnumType, RpField, RpName, RpNumberType, RpReg,
-    RpStringType, RpType, RpVersionedPackage,
+    RpScalarBase, RpScalarType, RpStringType, RpType, RpVersionedPackage,
 };
 
 /// Method for translating package.
@@ -35,11 +35,22 @@ pub trait FlavorTranslator {
 
     fn translate_datetime(&self) -> Result<<Self::Target as Flavor>::Type>;
 
+    fn translate_date(&self) -> Result<<Self::Target as Flavor>::Type>;
+
+    fn translate_time(&self) -> Result<<Self::Target as Flavor>::Type>;
+
+    fn translate_duration(&self) -> Result<<Self::Target as Flavor>::Type>;
+
     fn translate_array(
         &self,
         _: <Self::Target as Flavor>::Type,
     ) -> Result<<Self::Target as Flavor>::Type>;
 
+    fn translate_set(
+        &self,
+        _: <Self::Target as Flavor>::Type,
+    ) -> Result<<Self::Target as Flavor>::Type>;
+
     fn translate_map(
         &self,
         _: <Self::Target as Flavor>::Type,
@@ -50,6 +61,24 @@ pub trait FlavorTranslator {
 
     fn translate_bytes(&self) -> Result<<Self::Target as Flavor>::Type>;
 
+    /// Translate a custom scalar.
+    ///
+    /// Unless overridden, the scalar is translated as the type which it is encoded as.
+    fn translate_scalar(&self, scalar: RpScalarType) -> Result<<Self::Target as Flavor>::Type> {
+        self.translate_scalar_base(scalar.base)
+    }
+
+    /// Translate the type which a custom scalar is encoded as.
+    fn translate_scalar_base(&self, base: RpScalarBase) -> Result<<Self::Target as Flavor>::Type> {
+        match base {
+            RpScalarBase::Double => self.translate_double(),
+            RpScalarBase::Float => self.translate_float(),
+            RpScalarBase::Number(number) => self.translate_number(number),
+            RpScalarBase::Boolean => self.translate_boolean(),
+            RpScalarBase::String(string) => self.translate_string(string),
+        }
+    }
+
     /// Translate the given package.
     fn translate_package(
         &self,
@@ -404,6 +433,9 @@ where
         let out = match source {
             String(string) => self.flavor.translate_string(string)?,
             DateTime => self.flavor.translate_datetime()?,
+            Date => self.flavor.translate_date()?,
+            Time => self.flavor.translate_time()?,
+            Duration => self.flavor.translate_duration()?,
             Bytes => self.flavor.translate_bytes()?,
             Number(number) => self.flavor.translate_number(number)?,
             Float => self.flavor.translate_float()?,
@@ -413,6 +445,10 @@ where
                 let inner = self.translate_type(diag, *inner)?;
                 self.flavor.translate_array(inner)?
             }
+            Set { inner } => {
+                let inner = self.translate_type(diag, *inner)?;
+                self.flavor.translate_set(inner)?
+            }
             Name { name } => {
                 let reg = self.lookup(diag, &name)?;
                 let name = name.translate(diag, self)?;
@@ -424,6 +460,7 @@ where
                 self.flavor.translate_map(key, value)?
             }
             Any => self.flavor.translate_any()?,
+            Scalar(scalar) => self.flavor.translate_scalar(scalar)?,
         };
 
         Ok(out)
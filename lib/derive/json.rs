@@ -12,17 +12,25 @@ pub struct Json;
 
 impl format::Format for Json {
     fn decode(&self, object: &core::Source) -> Result<Sir> {
-        let mut der = json::Deserializer::from_reader(object.read()?).into_iter::<json::Value>();
-
-        let value: Result<json::Value> = der
-            .next()
-            .ok_or_else(|| format!("Expected at least one JSON value").into())
-            .and_then(|v| v.map_err(|e| format!("Bad JSON: {}", e).into()));
-
-        let value = value?;
-        let sir = from_json(&value)?;
+        let der = json::Deserializer::from_reader(object.read()?).into_iter::<json::Value>();
+
+        let mut sir: Option<Sir> = None;
+
+        // every document in the stream is a sample of the same structure.
+        for value in der {
+            let value = value.map_err(|e| format!("Bad JSON: {}", e))?;
+            let value = from_json(&value)?;
+
+            sir = Some(match sir.take() {
+                Some(mut sir) => {
+                    sir.refine(&value)?;
+                    sir
+                }
+                None => value,
+            });
+        }
 
-        Ok(sir)
+        Ok(sir.ok_or_else(|| "Expected at least one JSON value")?)
     }
 }
 
@@ -106,7 +114,10 @@ fn from_json(value: &json::Value) -> Result<Sir> {
                 entries.insert(key.to_string(), field);
             }
 
-            Sir::Object(entries)
+            match Sir::process_map(&entries) {
+                Some(map) => map,
+                None => Sir::Object(entries),
+            }
         }
     };
 
@@ -197,6 +197,23 @@ impl<'a, 'input: 'a> FieldInit<'a, 'input> {
                     inner: Box::new(f.item.ty.clone()),
                 }
             }
+            Sir::Map(ref inner) => {
+                let field = FieldSir {
+                    optional: false,
+                    field: (**inner).clone(),
+                };
+
+                let f = FieldInit::new(&self.span, self.ctx.clone(), self.types).init(
+                    name.clone(),
+                    &field,
+                    members,
+                )?;
+
+                Type::Map {
+                    key: Box::new(Loc::new(Type::String, self.span.clone())),
+                    value: Box::new(f.item.ty.clone()),
+                }
+            }
             ref sir => {
                 let ctx = self.ctx.join(to_pascal_case(&name));
 
@@ -311,8 +328,8 @@ impl<'a, 'input: 'a> DeclDeriver<'a, 'input> {
 
                 Decl::Interface(interface)
             }
-            // For arrays, only generate the inner type.
-            Sir::Array(ref inner) => self.derive(inner)?,
+            // For arrays and maps, only generate the inner type.
+            Sir::Array(ref inner) | Sir::Map(ref inner) => self.derive(inner)?,
             ref value => return Err(format!("Unexpected JSON value: {:?}", value).into()),
         };
 
@@ -511,15 +528,27 @@ impl<'a, 'input: 'a> TupleRefiner<'a, 'input> {
     }
 }
 
-/// Derive a declaration from the given input.
-pub fn derive<'input>(derive: Derive, object: &'input Source) -> Result<Decl<'input>> {
+/// Derive a declaration from the given inputs.
+///
+/// Every input is treated as a sample of the same structure, so fields which are missing from
+/// some of them are optional.
+pub fn derive<'input>(derive: Derive, sources: &'input [Source]) -> Result<Decl<'input>> {
     let Derive {
         root_name,
         format,
         package_prefix,
     } = derive;
 
-    let sir = format.decode(object)?;
+    let mut it = sources.iter();
+
+    let mut sir = match it.next() {
+        Some(source) => format.decode(source)?,
+        None => return Err("Expected at least one input".into()),
+    };
+
+    for source in it {
+        sir.refine(&format.decode(source)?)?;
+    }
 
     let span = Span::empty();
 
@@ -542,8 +571,8 @@ pub fn derive<'input>(derive: Derive, object: &'input Source) -> Result<Decl<'in
 #[cfg(test)]
 mod tests {
     use super::{derive, Derive, Json};
-    use ast::Decl;
-    use core::Source;
+    use ast::{Decl, Type};
+    use core::{Loc, Source};
 
     fn input<T>(input: &str, test: T)
     where
@@ -557,7 +586,7 @@ mod tests {
             package_prefix: None,
         };
 
-        test(derive(derive_config, &source).expect("bad derive"))
+        test(derive(derive_config, &[source]).expect("bad derive"))
     }
 
     #[test]
@@ -589,4 +618,43 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_optional_fields() {
+        input(
+            r#"{"id": 1, "name": "Oscar"}
+{"id": 2, "nickname": "Oz", "score": -4}"#,
+            |decl| {
+                let ty = match decl {
+                    Decl::Type(ty) => ty,
+                    other => panic!("expected type, got: {:?}", other),
+                };
+
+                let fields = ty.fields();
+                assert_eq!(4, fields.len());
+
+                let required = fields.iter().filter(|f| f.required).collect::<Vec<_>>();
+                assert_eq!(1, required.len());
+                assert_eq!("id", required[0].name);
+            },
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        input(r#"{"scores": {"2018-01": 1, "2018-02": 2}}"#, |decl| {
+            let ty = match decl {
+                Decl::Type(ty) => ty,
+                other => panic!("expected type, got: {:?}", other),
+            };
+
+            let fields = ty.fields();
+            assert_eq!(1, fields.len());
+
+            match *Loc::borrow(&fields[0].ty) {
+                Type::Map { .. } => {}
+                ref other => panic!("expected map, got: {:?}", other),
+            }
+        });
+    }
 }
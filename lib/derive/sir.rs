@@ -3,6 +3,7 @@ use format;
 use linked_hash_map::LinkedHashMap;
 use std::collections::HashSet;
 use std::mem;
+use utils::is_identifier;
 use Opaque;
 
 /// Results from calling `Sir::test_interface`.
@@ -27,6 +28,7 @@ pub enum Sir {
     Object(LinkedHashMap<String, FieldSir>),
     Interface(String, Vec<SubTypeSir>),
     Array(Box<Sir>),
+    Map(Box<Sir>),
     Tuple(Vec<FieldSir>),
 }
 
@@ -50,6 +52,7 @@ impl Sir {
                 sub_types.iter().map(SubTypeSir::hash).collect(),
             ),
             Sir::Array(ref inner) => HashSir::Array(Box::new(inner.hash())),
+            Sir::Map(ref inner) => HashSir::Map(Box::new(inner.hash())),
             Sir::Tuple(ref inner) => HashSir::Tuple(inner.iter().map(FieldSir::hash).collect()),
         }
     }
@@ -85,7 +88,7 @@ impl Sir {
     }
 
     /// Refine this SIR with another.
-    fn refine(&mut self, other: &Sir) -> Result<()> {
+    pub fn refine(&mut self, other: &Sir) -> Result<()> {
         // test for replacements
         let replace = match (&*self, other) {
             (&Sir::U64(ref examples), &Sir::I64(ref other)) => {
@@ -114,8 +117,16 @@ impl Sir {
                 examples.extend(other.iter().cloned());
             }
             // sign change
+            (&mut Sir::I64(ref mut examples), &Sir::U64(ref other)) => {
+                examples.extend(other.iter().map(|v| *v as i64));
+            }
             (&mut Sir::Float, &Sir::Float) => {}
             (&mut Sir::Double, &Sir::Double) => {}
+            // integers can be represented by the existing floating point type.
+            (&mut Sir::Float, &Sir::U64(_)) | (&mut Sir::Float, &Sir::I64(_)) => {}
+            (&mut Sir::Double, &Sir::Float)
+            | (&mut Sir::Double, &Sir::U64(_))
+            | (&mut Sir::Double, &Sir::I64(_)) => {}
             (&mut Sir::Boolean, &Sir::Boolean) => {}
             (&mut Sir::String(ref mut examples), &Sir::String(ref other)) => {
                 examples.extend(other.iter().cloned());
@@ -124,18 +135,31 @@ impl Sir {
                 examples.extend(other.iter().cloned());
             }
             (&mut Sir::Object(ref mut entries), &Sir::Object(ref other)) => {
-                for ((kl, vl), (kr, vr)) in entries.iter_mut().zip(other.iter()) {
-                    if kl != kr {
-                        return Err(format!("Expected object key {}, but got {}", kl, kr).into());
+                // fields which are not present in both objects are optional.
+                for (key, field) in entries.iter_mut() {
+                    if !other.contains_key(key) {
+                        field.optional = true;
                     }
+                }
 
-                    vl.refine(vr)?;
+                for (key, field) in other {
+                    if let Some(current) = entries.get_mut(key) {
+                        current.refine(field)?;
+                        continue;
+                    }
+
+                    let mut field = field.clone();
+                    field.optional = true;
+                    entries.insert(key.clone(), field);
                 }
             }
             (&mut Sir::Interface(_, ref mut _entries), &Sir::Interface(_, ref _other)) => {}
             (&mut Sir::Array(ref mut inner), &Sir::Array(ref other)) => {
                 return inner.refine(other);
             }
+            (&mut Sir::Map(ref mut inner), &Sir::Map(ref other)) => {
+                return inner.refine(other);
+            }
             (&mut Sir::Tuple(ref mut inner), &Sir::Tuple(ref other)) => {
                 for (inner, other) in inner.iter_mut().zip(other.iter()) {
                     inner.refine(other)?;
@@ -189,6 +213,19 @@ impl Sir {
                     return Ok(Sir::Interface(tag, sub_types));
                 }
 
+                // Objects with differing fields are merged, making fields which are not present
+                // in all of them optional.
+                if children.iter().all(Sir::is_object) {
+                    let mut it = children.into_iter();
+                    let mut merged = it.next().unwrap_or(Sir::Any);
+
+                    for child in it {
+                        merged.refine(&child)?;
+                    }
+
+                    return Ok(Sir::Array(Box::new(merged)));
+                }
+
                 // All tuple fields are required.
                 let children = children
                     .into_iter()
@@ -206,6 +243,27 @@ impl Sir {
         Ok(Sir::Array(Box::new(Sir::Any)))
     }
 
+    /// Test if the given object entries are a map.
+    ///
+    /// This is the case when none of the keys look like field names, and all values can be
+    /// refined into a single value.
+    pub fn process_map(entries: &LinkedHashMap<String, FieldSir>) -> Option<Sir> {
+        if entries.keys().any(|k| is_identifier(k)) {
+            return None;
+        }
+
+        let mut values = entries.values().map(|f| &f.field);
+        let mut value = values.next()?.clone();
+
+        for other in values {
+            if value.refine(other).is_err() {
+                return None;
+            }
+        }
+
+        Some(Sir::Map(Box::new(value)))
+    }
+
     /// Test if the given array is an interface.
     ///
     /// Interface detection happens by eliminating all common fields across a set of JSON
@@ -352,6 +410,7 @@ pub enum HashSir {
     Object(LinkedHashMap<String, FieldHashSir>),
     Interface(String, Vec<SubTypeHashSir>),
     Array(Box<HashSir>),
+    Map(Box<HashSir>),
     Tuple(Vec<FieldHashSir>),
 }
 
@@ -58,6 +58,18 @@ pub fn is_datetime(input: &str) -> bool {
     }
 }
 
+/// Check if the given input looks like the name of a field.
+pub fn is_identifier(input: &str) -> bool {
+    let mut it = input.chars();
+
+    match it.next() {
+        Some(c) if c.is_alphabetic() || c == '_' || c == '@' => {}
+        _ => return false,
+    }
+
+    it.all(|c| c.is_alphanumeric() || c == '_')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +81,14 @@ mod tests {
         assert_eq!(false, is_datetime("2018-02-01T00:02:02+00:00Z"));
         assert_eq!(false, is_datetime("-02-01T00:02:02+00:00Z"));
     }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(true, is_identifier("name"));
+        assert_eq!(true, is_identifier("@class"));
+        assert_eq!(true, is_identifier("created_at"));
+        assert_eq!(false, is_identifier("42"));
+        assert_eq!(false, is_identifier("en-US"));
+        assert_eq!(false, is_identifier(""));
+    }
 }
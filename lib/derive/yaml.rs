@@ -101,7 +101,10 @@ fn from_yaml(value: &yaml::Value) -> Result<Sir> {
                 entries.insert(key.to_string(), field);
             }
 
-            Sir::Object(entries)
+            match Sir::process_map(&entries) {
+                Some(map) => map,
+                None => Sir::Object(entries),
+            }
         }
     };
 
@@ -0,0 +1,112 @@
+//! Fixtures of JSON documents recorded by consumers of a schema.
+//!
+//! Fixtures are stored in the directory configured as `consumers` in the manifest, with one
+//! directory per package and one file per declaration, like `io.reproto.toystore/Toy.json`.
+//! Every non-empty line in a fixture which is not a comment (starting with `#`) is a document.
+
+use core::errors::*;
+use core::{CoreFlavor, RpPackage};
+use manifest::Manifest;
+use serde_json as json;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use trans::Session;
+
+/// Verify that all documents recorded by consumers still decode against the schemas in the
+/// session.
+pub fn verify_consumers(manifest: &Manifest, session: &Session<CoreFlavor>) -> Result<()> {
+    let dir = match manifest.consumers.as_ref() {
+        Some(dir) => dir,
+        None => return Ok(()),
+    };
+
+    if !dir.is_dir() {
+        return Err(format!("{}: no such directory", dir.display()).into());
+    }
+
+    let decoder = session.decoder();
+    let mut errors = Vec::new();
+
+    for e in fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let package_dir = e?.path();
+
+        if !package_dir.is_dir() {
+            continue;
+        }
+
+        let package = match package_dir.file_name().and_then(|n| n.to_str()) {
+            Some(name) => RpPackage::parse(name),
+            None => continue,
+        };
+
+        for e in
+            fs::read_dir(&package_dir).map_err(|e| format!("{}: {}", package_dir.display(), e))?
+        {
+            let path = e?.path();
+
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            let decl_path = match path.file_stem().and_then(|n| n.to_str()) {
+                Some(stem) => stem.split('.').map(ToOwned::to_owned).collect::<Vec<_>>(),
+                None => continue,
+            };
+
+            let decl = match decoder.lookup_latest(&package, &decl_path) {
+                Some(decl) => decl,
+                None => {
+                    errors.push(Error::from(format!(
+                        "{}: no such declaration `{}::{}`",
+                        path.display(),
+                        package,
+                        decl_path.join("::")
+                    )));
+                    continue;
+                }
+            };
+
+            for (location, document) in read_documents(&path)? {
+                let mut problems = Vec::new();
+                decoder.decode_decl(decl, &document, "$", &mut problems);
+
+                for problem in problems {
+                    errors.push(Error::from(format!("{}: {}", location, problem)));
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(
+            Error::new("documents recorded by consumers no longer decode").with_suppressed(errors),
+        );
+    }
+
+    Ok(())
+}
+
+/// Read all documents in the given fixture, together with where they were read from.
+fn read_documents(path: &Path) -> Result<Vec<(String, json::Value)>> {
+    let f = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let mut out = Vec::new();
+
+    for (index, line) in BufReader::new(f).lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let location = format!("{}:{}", path.display(), index + 1);
+
+        let document = json::from_str(&line)
+            .map_err(|e| format!("{}: not a JSON document: {}", location, e))?;
+
+        out.push((location, document));
+    }
+
+    Ok(out)
+}
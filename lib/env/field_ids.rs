@@ -0,0 +1,71 @@
+//! Sidecar file recording the stable identifiers assigned to fields.
+//!
+//! The file is only used if `field_ids` is set in the manifest. It is updated whenever a new field
+//! is assigned an identifier, and should be checked in together with the specifications.
+
+use core::errors::*;
+use core::CoreFlavor;
+use manifest::Manifest;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use toml;
+use trans::{FieldIds, Session};
+
+const HEADER: &'static str = "# This file is generated by reproto, do not edit it manually.\n\n";
+
+/// Read identifiers from the given path, or no identifiers if the file doesn't exist.
+fn read(path: &Path) -> Result<FieldIds> {
+    if !path.is_file() {
+        return Ok(FieldIds::new());
+    }
+
+    let mut content = String::new();
+
+    File::open(path)
+        .map_err(|e| format!("{}: failed to open: {}", path.display(), e))?
+        .read_to_string(&mut content)?;
+
+    let declarations: BTreeMap<String, BTreeMap<String, u32>> = toml::from_str(content.as_str())
+        .map_err(|e| format!("{}: bad field ids: {}", path.display(), e))?;
+
+    Ok(FieldIds { declarations })
+}
+
+/// Write identifiers to the given path.
+fn write(path: &Path, ids: &FieldIds) -> Result<()> {
+    let content = toml::to_string(&ids.declarations)?;
+
+    let mut tmp_path = path.to_owned();
+    tmp_path.set_extension("tmp");
+
+    {
+        let mut f = File::create(&tmp_path)?;
+        f.write_all(HEADER.as_bytes())?;
+        f.write_all(content.as_bytes())?;
+    }
+
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Assign stable identifiers to all fields in the session, as configured by the manifest.
+///
+/// Does nothing unless `field_ids` is set in the manifest, in which case fields without an
+/// `#[id(<n>)]` attribute are assigned an identifier which is recorded in that file.
+pub fn assign_field_ids(manifest: &Manifest, session: &mut Session<CoreFlavor>) -> Result<()> {
+    let path = match manifest.field_ids.as_ref() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    let mut ids = read(path)?;
+
+    if session.assign_field_ids(&mut ids)? {
+        debug!("writing: {}", path.display());
+        write(path, &ids)?;
+    }
+
+    Ok(())
+}
@@ -1,11 +1,68 @@
 //! Function to initialize a new project.
 
 use core::errors::Result;
-use core::{Handle, RelativePath};
+use core::{Handle, RelativePath, RpPackage};
+use manifest::Language;
 
 const EXAMPLE: &'static [u8] = include_bytes!("example.reproto");
+const DEFAULT_PACKAGE: &'static str = "io.reproto.example";
 
-pub fn initialize(handle: &Handle) -> Result<()> {
+const VSCODE_SETTINGS: &'static [u8] = b"{
+  \"files.associations\": {
+    \"*.reproto\": \"reproto\"
+  }
+}
+";
+
+const EDITORCONFIG: &'static [u8] = b"root = true
+
+[*.reproto]
+indent_style = space
+indent_size = 2
+insert_final_newline = true
+";
+
+/// Editor settings which can be written when initializing a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Editor {
+    /// Settings for Visual Studio Code, in `.vscode/settings.json`.
+    VsCode,
+    /// An `.editorconfig` file.
+    EditorConfig,
+}
+
+impl Editor {
+    pub fn parse(input: &str) -> Option<Editor> {
+        let editor = match input {
+            "vscode" => Editor::VsCode,
+            "editorconfig" => Editor::EditorConfig,
+            _ => return None,
+        };
+
+        Some(editor)
+    }
+
+    /// Path and content of the settings file for the editor.
+    fn settings(&self) -> (&'static RelativePath, &'static [u8]) {
+        match *self {
+            Editor::VsCode => (RelativePath::new(".vscode/settings.json"), VSCODE_SETTINGS),
+            Editor::EditorConfig => (RelativePath::new(".editorconfig"), EDITORCONFIG),
+        }
+    }
+}
+
+/// Options used when initializing a new project.
+#[derive(Debug, Clone, Default)]
+pub struct InitializeOptions {
+    /// Language to build for.
+    pub language: Option<Language>,
+    /// Package of the example specification.
+    pub package: Option<RpPackage>,
+    /// Editors to write settings for.
+    pub editors: Vec<Editor>,
+}
+
+pub fn initialize(handle: &Handle, options: InitializeOptions) -> Result<()> {
     let mut path = RelativePath::new("proto");
     let manifest = RelativePath::new("reproto.toml");
 
@@ -13,7 +70,9 @@ pub fn initialize(handle: &Handle) -> Result<()> {
     let mut maven = false;
     let mut swift = false;
 
-    let package = vec!["io", "reproto", "example"];
+    let package = options
+        .package
+        .unwrap_or_else(|| RpPackage::parse(DEFAULT_PACKAGE));
 
     // looks like a maven project
     if handle.is_file(RelativePath::new("pom.xml")) {
@@ -27,11 +86,19 @@ pub fn initialize(handle: &Handle) -> Result<()> {
         swift = true;
     }
 
+    if maven {
+        path = RelativePath::new("src/main/reproto");
+    }
+
     if !handle.is_file(manifest) {
         info!("Writing Manifest: {}", manifest.display());
 
         let mut manifest = handle.create(manifest)?;
 
+        if let Some(language) = options.language {
+            writeln!(manifest, "language = \"{}\"", language.id())?;
+        }
+
         if with_output {
             writeln!(manifest, "paths = [")?;
             writeln!(manifest, "  \"{}\"", path.display())?;
@@ -41,7 +108,6 @@ pub fn initialize(handle: &Handle) -> Result<()> {
 
         if maven {
             writeln!(manifest, "[presets.maven]")?;
-            path = RelativePath::new("src/main/reproto");
         }
 
         if swift {
@@ -60,8 +126,7 @@ pub fn initialize(handle: &Handle) -> Result<()> {
     }
 
     let example = package
-        .iter()
-        .cloned()
+        .parts()
         .fold(path.to_owned(), |p, part| p.join(part))
         .with_extension("reproto");
 
@@ -78,5 +143,24 @@ pub fn initialize(handle: &Handle) -> Result<()> {
         example.write_all(EXAMPLE)?;
     }
 
+    for editor in options.editors {
+        let (settings, content) = editor.settings();
+
+        if handle.is_file(settings) {
+            continue;
+        }
+
+        if let Some(parent) = settings.parent() {
+            if !handle.is_dir(parent) {
+                info!("Creating: {}", parent.display());
+                handle.create_dir_all(parent)?;
+            }
+        }
+
+        info!("Writing: {}", settings.display());
+        let mut settings = handle.create(settings)?;
+        settings.write_all(content)?;
+    }
+
     Ok(())
 }
@@ -1,18 +1,26 @@
+extern crate reproto_backend_asyncapi as asyncapi;
+extern crate reproto_backend_avro as avro;
 extern crate reproto_backend_csharp as csharp;
 extern crate reproto_backend_doc as doc;
 extern crate reproto_backend_go as go;
+extern crate reproto_backend_haskell as haskell;
 extern crate reproto_backend_java as java;
 extern crate reproto_backend_js as js;
 extern crate reproto_backend_json as json;
+extern crate reproto_backend_jsonschema as jsonschema;
+extern crate reproto_backend_kotlin as kotlin;
 extern crate reproto_backend_openapi as openapi;
+extern crate reproto_backend_plugin as plugin;
 extern crate reproto_backend_python as python;
 extern crate reproto_backend_reproto as reproto;
 extern crate reproto_backend_rust as rust;
 extern crate reproto_backend_swift as swift;
+extern crate reproto_backend_template as template;
 extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
 extern crate reproto_repository as repository;
 extern crate reproto_repository_http as repository_http;
+extern crate reproto_trans as trans;
 #[macro_use]
 extern crate log;
 extern crate toml;
@@ -20,26 +28,39 @@ extern crate url;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde;
+extern crate serde_json;
 
 mod config;
 mod config_env;
+mod consumers;
+mod field_ids;
 mod initialize;
+mod lockfile;
+mod vendor;
 
 pub use self::config_env::ConfigEnvironment;
-pub use self::initialize::initialize;
+pub use self::consumers::verify_consumers;
+pub use self::field_ids::assign_field_ids;
+pub use self::initialize::{initialize, Editor, InitializeOptions};
+pub use self::lockfile::{Locked, Lockfile, LOCK_NAME};
+pub use self::vendor::{Vendored, Vendoring, VENDOR_DIR};
 use core::errors::Result;
 use core::{RelativePath, Resolver};
 use manifest::{Lang, Language, Manifest};
 use repository::{
     index_from_path, index_from_url, objects_from_path, objects_from_url, Index, IndexConfig,
-    NoIndex, NoObjects, Objects, ObjectsConfig, Paths, Repository, Resolvers,
+    NoIndex, NoObjects, Objects, ObjectsConfig, Paths, Repository, Resolvers, SigningKey,
 };
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 pub const DEFAULT_INDEX: &'static str = "git+https://github.com/reproto/reproto-index";
 pub const MANIFEST_NAME: &'static str = "reproto.toml";
+/// Environment variable with additional paths to look for specifications in.
+pub const PATH_VARIABLE: &'static str = "REPROTO_PATH";
+/// Environment variable with overrides of manifest values, separated by `;`.
+pub const SET_VARIABLE: &'static str = "REPROTO_SET";
 
 fn load_index(base: &Path, url: &str, publishing: bool, config: IndexConfig) -> Result<Box<Index>> {
     let index_path = Path::new(url);
@@ -59,7 +80,16 @@ fn load_index(base: &Path, url: &str, publishing: bool, config: IndexConfig) ->
             index_from_path(&path).map_err(Into::into)
         }
         Err(e) => return Err(e.into()),
-        Ok(url) => index_from_url(config, &url, publishing).map_err(Into::into),
+        Ok(url) => index_from_url(
+            config,
+            &url,
+            |config, scheme, url| match scheme {
+                "http" => Ok(Some(repository_http::index_from_url(config, url)?)),
+                "https" => Ok(Some(repository_http::index_from_url(config, url)?)),
+                _ => Ok(None),
+            },
+            publishing,
+        ).map_err(Into::into),
     }
 }
 
@@ -138,6 +168,10 @@ pub fn repository(manifest: &Manifest) -> Result<Repository> {
 
     let repo_dir = repo_dir.ok_or_else(|| "repo_dir: must be specified")?;
 
+    if repository.offline {
+        debug!("offline: only using packages available locally");
+    }
+
     // NB: do not permit publishing to default index.
     let (index_url, index_publishing) = index
         .map(|index| (index, true))
@@ -145,6 +179,8 @@ pub fn repository(manifest: &Manifest) -> Result<Repository> {
 
     let index_config = IndexConfig {
         repo_dir: repo_dir.clone(),
+        cache_home: cache_home.clone(),
+        offline: repository.offline,
     };
 
     let index = load_index(base, index_url.as_str(), index_publishing, index_config)?;
@@ -153,6 +189,7 @@ pub fn repository(manifest: &Manifest) -> Result<Repository> {
         repo_dir,
         cache_home,
         missing_cache_time: Some(Duration::new(60, 0)),
+        offline: repository.offline,
     };
 
     let objects = load_objects(
@@ -163,12 +200,41 @@ pub fn repository(manifest: &Manifest) -> Result<Repository> {
         objects_config,
     )?;
 
-    Ok(Repository::new(index, objects))
+    let signing_key = match repository.signing_key {
+        Some(ref path) => Some(SigningKey::from_path(path)?),
+        None => None,
+    };
+
+    Ok(Repository::new(index, objects)
+        .with_signing_key(signing_key)
+        .with_trusted_keys(repository.trusted_keys.clone()))
+}
+
+/// Paths to look for specifications in.
+///
+/// These are the paths of the manifest, followed by any paths in the `REPROTO_PATH` environment
+/// variable, which are separated like `PATH` on the current platform.
+pub fn paths(manifest: &Manifest) -> Vec<PathBuf> {
+    let mut paths = manifest.paths.clone();
+
+    if let Some(value) = ::std::env::var_os(PATH_VARIABLE) {
+        for path in ::std::env::split_paths(&value) {
+            if path.as_os_str().is_empty() || paths.contains(&path) {
+                continue;
+            }
+
+            paths.push(path);
+        }
+    }
+
+    paths
 }
 
 /// Setup the path-based resolver from a manifest.
 pub fn path_resolver(manifest: &Manifest) -> Result<Option<Box<Resolver>>> {
-    if manifest.paths.is_empty() {
+    let paths = paths(manifest);
+
+    if paths.is_empty() {
         return Ok(None);
     }
 
@@ -180,45 +246,180 @@ pub fn path_resolver(manifest: &Manifest) -> Result<Option<Box<Resolver>>> {
         }
     }
 
-    Ok(Some(Box::new(Paths::new(
-        manifest.paths.clone(),
-        published,
-    ))))
+    let paths = Paths::new(paths, published)
+        .with_priorities(manifest.path_priorities.clone())
+        .with_pins(manifest.pins.clone());
+
+    Ok(Some(Box::new(paths)))
 }
 
 /// Set up the all resolvers based on this manifest.
+///
+/// The lockfile is only read, use `locking_resolver` to record newly resolved packages in it.
 pub fn resolver(manifest: &manifest::Manifest) -> Result<Box<Resolver>> {
     resolver_with_extra(manifest, None)
 }
 
+/// Set up all resolvers based on this manifest, recording newly resolved packages in the
+/// lockfile.
+pub fn locking_resolver(manifest: &manifest::Manifest) -> Result<Box<Resolver>> {
+    resolvers(manifest, None, true)
+}
+
 /// Resolver with an extra resolver prepended to it.
 pub fn resolver_with_extra(
     manifest: &manifest::Manifest,
     extra: Option<Box<Resolver>>,
+) -> Result<Box<Resolver>> {
+    resolvers(manifest, extra, false)
+}
+
+fn resolvers(
+    manifest: &manifest::Manifest,
+    extra: Option<Box<Resolver>>,
+    write_lockfile: bool,
 ) -> Result<Box<Resolver>> {
     let mut resolvers = Vec::<Box<Resolver>>::new();
 
     resolvers.extend(extra);
     resolvers.extend(path_resolver(manifest)?);
-    resolvers.push(Box::new(repository(manifest)?));
 
+    // NB: vendored packages take precedence over the repository.
+    if let Some(path) = vendor_path(manifest) {
+        if path.is_dir() {
+            resolvers.push(Box::new(Vendored::new(path)));
+        }
+    }
+
+    resolvers.push(locked_repository_resolver(manifest, write_lockfile)?);
     Ok(Box::new(Resolvers::new(resolvers)))
 }
 
+/// Set up the resolver for packages in the repository.
+///
+/// Packages resolved from the repository are pinned through the lockfile, which is only read.
+pub fn repository_resolver(manifest: &Manifest) -> Result<Box<Resolver>> {
+    locked_repository_resolver(manifest, false)
+}
+
+fn locked_repository_resolver(manifest: &Manifest, write_lockfile: bool) -> Result<Box<Resolver>> {
+    let repository = Box::new(repository(manifest)?);
+
+    match lock_path(manifest) {
+        Some(path) => {
+            let locked = Locked::new(path, repository)?.with_write(write_lockfile);
+            Ok(Box::new(locked))
+        }
+        None => Ok(repository),
+    }
+}
+
+/// Directory that the lockfile and vendored packages of the manifest are stored in.
+///
+/// Members of a workspace use the root of the workspace.
+fn lock_dir(manifest: &Manifest) -> Option<PathBuf> {
+    if let Some(root) = manifest.workspace_root.as_ref() {
+        return Some(root.clone());
+    }
+
+    manifest
+        .path
+        .as_ref()
+        .and_then(|p| p.parent())
+        .map(ToOwned::to_owned)
+}
+
+/// Path to the vendor directory of the manifest, if the manifest has a path.
+pub fn vendor_path(manifest: &Manifest) -> Option<PathBuf> {
+    lock_dir(manifest).map(|p| p.join(VENDOR_DIR))
+}
+
+/// Path to the lockfile of the manifest, if the manifest has a path.
+pub fn lock_path(manifest: &Manifest) -> Option<PathBuf> {
+    lock_dir(manifest).map(|p| p.join(LOCK_NAME))
+}
+
+/// Refresh the lockfile of the manifest, locking every package to the most recent version
+/// matching its range.
+pub fn update_lockfile(manifest: &Manifest) -> Result<()> {
+    let path = match lock_path(manifest) {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    if !path.is_file() {
+        return Ok(());
+    }
+
+    let mut lockfile = Lockfile::read(&path)?;
+    let mut repository = repository(manifest)?;
+    lockfile.refresh(&mut repository)?;
+    lockfile.write(&path)?;
+    Ok(())
+}
+
 /// Convert the manifest language to an actual language implementation.
 pub fn convert_lang(input: Language) -> Box<Lang> {
     use self::Language::*;
 
     match input {
+        AsyncApi => Box::new(::asyncapi::AsyncApiLang),
+        Avro => Box::new(::avro::AvroLang),
         Csharp => Box::new(::csharp::CsharpLang),
         Go => Box::new(::go::GoLang),
+        Haskell => Box::new(::haskell::HaskellLang),
         Java => Box::new(::java::JavaLang),
         Js => Box::new(::js::JsLang),
         Json => Box::new(::json::JsonLang),
+        JsonSchema => Box::new(::jsonschema::JsonSchemaLang),
+        Kotlin => Box::new(::kotlin::KotlinLang),
         Python => Box::new(::python::PythonLang),
         Reproto => Box::new(::reproto::ReprotoLang),
         Rust => Box::new(::rust::RustLang),
         Swift => Box::new(::swift::SwiftLang),
+        Template => Box::new(::template::TemplateLang),
         OpenApi => Box::new(::openapi::OpenApiLang),
+        Plugin(name) => Box::new(::plugin::PluginLang::new(name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every language which generates code must escape its reserved words.
+    #[test]
+    fn test_keywords() {
+        use self::Language::*;
+
+        let cases = vec![
+            (Csharp, "namespace", "_namespace"),
+            (Go, "range", "range_"),
+            (Haskell, "data", "data_"),
+            (Java, "class", "_class"),
+            (Js, "function", "_function"),
+            (Kotlin, "fun", "fun_"),
+            (Python, "await", "_await"),
+            (Rust, "async", "_async"),
+            (Swift, "protocol", "protocol_"),
+        ];
+
+        for (language, keyword, escaped) in cases {
+            let lang = convert_lang(language.clone());
+
+            let keywords = lang
+                .keywords()
+                .unwrap_or_else(|| panic!("{:?}: no keywords", language));
+
+            let replacements = keywords.replacements();
+
+            assert_eq!(
+                Some(&escaped.to_string()),
+                replacements.get(keyword),
+                "{:?}: `{}` is not escaped",
+                language,
+                keyword
+            );
+        }
     }
 }
@@ -0,0 +1,247 @@
+//! Lockfile pinning the exact versions of packages resolved from the repository.
+//!
+//! Every required package which is resolved from the repository is recorded together with the
+//! exact version it resolved to. Subsequent builds resolve the same version, until the lockfile is
+//! refreshed using `reproto update`.
+//!
+//! Only `reproto build` records new packages in the lockfile, every other command uses it
+//! read-only.
+
+use core::errors::*;
+use core::{Range, Resolved, ResolvedByPrefix, Resolver, RpPackage, RpRequiredPackage, Version};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use toml;
+
+/// Name of the lockfile, stored next to the manifest.
+pub const LOCK_NAME: &'static str = "reproto.lock";
+
+const HEADER: &'static str = "# This file is generated by reproto, do not edit it manually.\n\n";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct LockedPackage {
+    /// Name of the package.
+    name: String,
+    /// The range of versions that was required.
+    range: String,
+    /// The exact version the range was resolved to.
+    version: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    /// Read the lockfile from the given path, or an empty lockfile if it doesn't exist.
+    pub fn read(path: &Path) -> Result<Lockfile> {
+        if !path.is_file() {
+            return Ok(Lockfile::default());
+        }
+
+        let mut content = String::new();
+
+        File::open(path)
+            .map_err(|e| format!("{}: failed to open: {}", path.display(), e))?
+            .read_to_string(&mut content)?;
+
+        let lockfile: Lockfile = toml::from_str(content.as_str())
+            .map_err(|e| format!("{}: bad lockfile: {}", path.display(), e))?;
+
+        Ok(lockfile)
+    }
+
+    /// Write the lockfile to the given path.
+    pub fn write(&mut self, path: &Path) -> Result<()> {
+        self.packages.sort();
+        self.packages.dedup();
+
+        let content = toml::to_string(self)?;
+
+        let mut tmp_path = path.to_owned();
+        tmp_path.set_extension("lock.tmp");
+
+        {
+            let mut f = File::create(&tmp_path)?;
+            f.write_all(HEADER.as_bytes())?;
+            f.write_all(content.as_bytes())?;
+        }
+
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Get the locked version of the required package, if any.
+    pub fn get(&self, required: &RpRequiredPackage) -> Result<Option<Version>> {
+        let name = required.package.to_string();
+        let range = required.range.to_string();
+
+        let locked = match self
+            .packages
+            .iter()
+            .find(|p| p.name == name && p.range == range)
+        {
+            Some(locked) => locked,
+            None => return Ok(None),
+        };
+
+        let version = Version::parse(&locked.version)
+            .map_err(|e| format!("{}: bad version in {}: {}", required, LOCK_NAME, e))?;
+
+        Ok(Some(version))
+    }
+
+    /// Lock the required package to the given version.
+    ///
+    /// Returns `true` if the lockfile was modified.
+    pub fn insert(&mut self, required: &RpRequiredPackage, version: &Version) -> bool {
+        let name = required.package.to_string();
+        let range = required.range.to_string();
+        let version = version.to_string();
+
+        if let Some(locked) = self
+            .packages
+            .iter_mut()
+            .find(|p| p.name == name && p.range == range)
+        {
+            if locked.version == version {
+                return false;
+            }
+
+            locked.version = version;
+            return true;
+        }
+
+        self.packages.push(LockedPackage {
+            name,
+            range,
+            version,
+        });
+
+        true
+    }
+
+    /// Re-resolve all locked packages with the given resolver, locking them to the most recent
+    /// matching version.
+    pub fn refresh(&mut self, resolver: &mut Resolver) -> Result<()> {
+        for locked in &mut self.packages {
+            let range = Range::parse(&locked.range).map_err(|e| {
+                format!("{}: bad range in {}: {}", locked.name, LOCK_NAME, e)
+            })?;
+
+            let required = RpRequiredPackage::new(RpPackage::parse(&locked.name), range);
+
+            let version = match resolver.resolve(&required)? {
+                Some(Resolved {
+                    version: Some(version),
+                    ..
+                }) => version,
+                _ => return Err(format!("{}: could not be resolved", required).into()),
+            };
+
+            let version = version.to_string();
+
+            if locked.version != version {
+                info!("{}: {} -> {}", required, locked.version, version);
+                locked.version = version;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A resolver which pins resolved packages to the versions in a lockfile.
+pub struct Locked {
+    path: PathBuf,
+    lockfile: Lockfile,
+    inner: Box<Resolver>,
+    /// Write packages which are not locked yet to the lockfile.
+    write: bool,
+}
+
+impl Locked {
+    /// Resolve packages through the lockfile at the given path, without modifying it.
+    pub fn new(path: PathBuf, inner: Box<Resolver>) -> Result<Locked> {
+        let lockfile = Lockfile::read(&path)?;
+
+        Ok(Locked {
+            path,
+            lockfile,
+            inner,
+            write: false,
+        })
+    }
+
+    /// Set if packages which are not locked yet should be written to the lockfile.
+    pub fn with_write(self, write: bool) -> Self {
+        Locked { write, ..self }
+    }
+
+    /// Write the lockfile, merging in any entries that were written since it was read.
+    fn write(&mut self) -> Result<()> {
+        let mut current = Lockfile::read(&self.path)?;
+
+        for locked in current.packages.drain(..) {
+            let exists = self
+                .lockfile
+                .packages
+                .iter()
+                .any(|p| p.name == locked.name && p.range == locked.range);
+
+            if !exists {
+                self.lockfile.packages.push(locked);
+            }
+        }
+
+        debug!("writing: {}", self.path.display());
+        self.lockfile.write(&self.path)
+    }
+}
+
+impl Resolver for Locked {
+    fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Option<Resolved>> {
+        if let Some(version) = self.lockfile.get(package)? {
+            let exact = RpRequiredPackage::new(package.package.clone(), Range::exact(&version));
+
+            return match self.inner.resolve(&exact)? {
+                Some(resolved) => Ok(Some(resolved)),
+                None => Err(format!(
+                    "{}: locked version {} is not available, run `reproto update` to refresh {}",
+                    package, version, LOCK_NAME
+                ).into()),
+            };
+        }
+
+        let resolved = self.inner.resolve(package)?;
+
+        if let Some(Resolved {
+            version: Some(ref version),
+            ..
+        }) = resolved
+        {
+            // NB: also locked in memory, so that the session resolves it consistently.
+            if self.lockfile.insert(package, version) && self.write {
+                self.write()?;
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        // NB: all versions are requested explicitly, so the lockfile does not apply.
+        self.inner.resolve_all(package)
+    }
+
+    fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
+        self.inner.resolve_by_prefix(package)
+    }
+
+    fn resolve_packages(&mut self) -> Result<Vec<ResolvedByPrefix>> {
+        self.inner.resolve_packages()
+    }
+}
@@ -0,0 +1,107 @@
+//! Resolve packages from a local vendor directory.
+//!
+//! Vendored packages are stored in the same layout as path-based packages:
+//! `<vendor>/<package>/<last>-<version>.reproto`.
+
+use core::errors::*;
+use core::{Resolved, ResolvedByPrefix, Resolver, RpPackage, RpRequiredPackage};
+use repository::{Paths, EXT};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::PathBuf;
+
+/// Name of the vendor directory, stored next to the manifest.
+pub const VENDOR_DIR: &'static str = "vendor";
+
+/// A resolver which resolves packages from a vendor directory.
+///
+/// Unlike build paths, packages in the vendor directory are never built themselves.
+pub struct Vendored {
+    paths: Paths,
+}
+
+impl Vendored {
+    pub fn new(path: PathBuf) -> Vendored {
+        Vendored {
+            paths: Paths::new(vec![path], HashMap::new()),
+        }
+    }
+}
+
+impl Resolver for Vendored {
+    fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Option<Resolved>> {
+        self.paths.resolve(package)
+    }
+
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        self.paths.resolve_all(package)
+    }
+
+    fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
+        self.paths.resolve_by_prefix(package)
+    }
+
+    fn resolve_packages(&mut self) -> Result<Vec<ResolvedByPrefix>> {
+        Ok(vec![])
+    }
+}
+
+/// A resolver which writes everything resolved through it into a vendor directory.
+pub struct Vendoring {
+    path: PathBuf,
+    inner: Box<Resolver>,
+}
+
+impl Vendoring {
+    pub fn new(path: PathBuf, inner: Box<Resolver>) -> Vendoring {
+        Vendoring { path, inner }
+    }
+
+    /// Write the resolved package into the vendor directory.
+    fn vendor(&self, package: &RpPackage, resolved: &Resolved) -> Result<()> {
+        let version = match resolved.version {
+            Some(ref version) => version,
+            None => {
+                return Err(format!("{}: cannot vendor package without a version", package).into())
+            }
+        };
+
+        let (parent, last) = match package.clone().split_last() {
+            (parent, Some(last)) => (parent, last),
+            (_, None) => return Err("cannot vendor the empty package".into()),
+        };
+
+        let dir = parent.parts().fold(self.path.clone(), |p, part| p.join(part));
+
+        if !dir.is_dir() {
+            fs::create_dir_all(&dir)?;
+        }
+
+        let target = dir.join(format!("{}-{}.{}", last, version, EXT));
+
+        info!("vendoring: {}@{} -> {}", package, version, target.display());
+        io::copy(&mut resolved.source.read()?, &mut File::create(&target)?)?;
+        Ok(())
+    }
+}
+
+impl Resolver for Vendoring {
+    fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Option<Resolved>> {
+        let resolved = self.inner.resolve(package)?;
+
+        if let Some(ref resolved) = resolved {
+            self.vendor(&package.package, resolved)?;
+        }
+
+        Ok(resolved)
+    }
+
+    fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
+        self.inner.resolve_by_prefix(package)
+    }
+
+    fn resolve_packages(&mut self) -> Result<Vec<ResolvedByPrefix>> {
+        self.inner.resolve_packages()
+    }
+}
@@ -0,0 +1,60 @@
+//! Identifiers of JSON Schema documents, with one document per package.
+
+use core::errors::*;
+use core::flavored::RpVersionedPackage;
+
+/// Allocates the `$id` of documents.
+pub struct Ids<'a> {
+    /// URL that documents are published under.
+    base: Option<&'a str>,
+}
+
+impl<'a> Ids<'a> {
+    /// Allocate ids for documents published under the given URL, or `urn:reproto:` ids if it's
+    /// not specified.
+    pub fn new(base: Option<&'a str>) -> Self {
+        Ids {
+            base: base.map(|b| b.trim_right_matches('/')),
+        }
+    }
+
+    /// The `$id` of the document for the given package.
+    pub fn id(&self, package: &RpVersionedPackage) -> Result<String> {
+        let base = match self.base {
+            Some(base) => base,
+            None => return Ok(urn(package)),
+        };
+
+        let path = schema_path(package)?;
+        Ok(format!("{}/{}", base, path))
+    }
+}
+
+/// Path of the document for the given package, relative to where documents are published.
+pub fn schema_path(package: &RpVersionedPackage) -> Result<String> {
+    let mut parts = package
+        .package
+        .parts()
+        .map(|p| p.as_str())
+        .collect::<Vec<_>>();
+
+    let stem = parts
+        .pop()
+        .ok_or_else(|| format!("Missing file stem: {}", package))?;
+
+    let file_name = match package.version.as_ref() {
+        Some(version) => format!("{}-{}.schema.json", stem, version),
+        None => format!("{}.schema.json", stem),
+    };
+
+    parts.push(&file_name);
+    Ok(parts.join("/"))
+}
+
+/// Stable identifier of a package version, used when documents are not published anywhere.
+fn urn(package: &RpVersionedPackage) -> String {
+    match package.version.as_ref() {
+        Some(version) => format!("urn:reproto:{}:{}", package.package, version),
+        None => format!("urn:reproto:{}", package.package),
+    }
+}
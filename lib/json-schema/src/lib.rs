@@ -0,0 +1,726 @@
+//! JSON Schema generation shared by the OpenAPI, AsyncAPI, and JSON Schema backends.
+//!
+//! Declarations are converted on demand: converting a type only references the declarations it
+//! uses, and queues them up to be converted into components by [`process_components`].
+//!
+//! [`process_components`]: struct.SchemaBuilder.html#method.process_components
+
+extern crate reproto_core as core;
+extern crate reproto_trans as trans;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate linked_hash_map;
+extern crate reproto_naming as naming;
+
+mod ids;
+pub mod spec;
+
+/// A number rule to set up an enum for a given numeric type.
+macro_rules! number_rule {
+    ($variants:ident, $name:ident, $convert:ident) => {{
+        let mut __number = spec::$name::default();
+
+        for v in $variants {
+            let n = v.value.$convert().ok_or_else(|| "not a legal number")?;
+            __number.enum_.push(n);
+
+            // aliases are accepted when decoding.
+            for a in &v.aliases {
+                let n = a.$convert().ok_or_else(|| "not a legal number")?;
+                __number.enum_.push(n);
+            }
+        }
+
+        spec::Schema::from(__number)
+    }};
+}
+
+pub use self::ids::{schema_path, Ids};
+
+use core::errors::*;
+use core::flavored::{
+    RpEnumBody, RpField, RpInterfaceBody, RpName, RpNewtypeBody, RpTupleBody, RpType, RpTypeBody,
+    RpUnionBody, RpVersionedPackage,
+};
+use core::{CoreFlavor, Loc, RpNumberKind, RpScalarBase, RpStringValidate};
+use linked_hash_map::LinkedHashMap;
+use spec::Ref;
+use std::cell::RefCell;
+use std::collections::{hash_map, HashMap, HashSet, VecDeque};
+use trans::Translated;
+
+/// Prefix of references to components.
+const COMPONENTS: &str = "#/components/schemas/";
+
+/// Prefix of references to definitions in a JSON Schema document.
+const DEFS: &str = "#/$defs/";
+
+/// The dialect of JSON Schema to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// OpenAPI 3.1, which builds on JSON Schema 2020-12.
+    ///
+    /// Tuples use `prefixItems`, and the discriminator maps each tag to a schema.
+    OpenApi,
+    /// AsyncAPI 2, which builds on JSON Schema draft 7.
+    ///
+    /// Tuples use a list of `items`, and the discriminator only names the tag property.
+    AsyncApi,
+    /// JSON Schema 2020-12, with one document per package.
+    ///
+    /// Declarations are defined under `$defs` using their full path, and declarations in other
+    /// packages are referenced through the `$id` of their document.
+    JsonSchema,
+}
+
+/// Queued up things that will be processed.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Queued<'a> {
+    /// Create an entity for the given sub-type, with an optional tag.
+    SubType(Option<&'a str>, &'a RpName, usize),
+    Named(&'a RpName),
+    Any,
+}
+
+/// Converts declarations into schemas for a single document.
+///
+/// Allocates names of components that are conflict free.
+pub struct SchemaBuilder<'builder> {
+    dialect: Dialect,
+    /// Package of the document being built and the ids of all documents, if every package has its
+    /// own document.
+    document: Option<(&'builder RpVersionedPackage, &'builder Ids<'builder>)>,
+    upper_camel: naming::ToUpperCamel,
+    env: &'builder Translated<CoreFlavor>,
+    /// Names and what local names they are associated with.
+    allocated_names: RefCell<HashMap<RpName, String>>,
+    /// All allocated names to see if we conflict with an existing name.
+    all_names: RefCell<HashSet<String>>,
+    /// In case we can't generate a conflict-free name fall back to assigning an incrementing
+    /// counter.
+    name_counters: RefCell<HashMap<String, usize>>,
+    /// Special any type that needs to be constructed.
+    any_type: RpName,
+}
+
+impl<'builder> SchemaBuilder<'builder> {
+    pub fn new(dialect: Dialect, env: &'builder Translated<CoreFlavor>) -> Self {
+        SchemaBuilder {
+            dialect,
+            document: None,
+            upper_camel: naming::to_upper_camel(),
+            env,
+            allocated_names: RefCell::new(HashMap::new()),
+            all_names: RefCell::new(HashSet::new()),
+            name_counters: RefCell::new(HashMap::new()),
+            any_type: RpName::new(None, RpVersionedPackage::empty(), vec!["Any".to_string()]),
+        }
+    }
+
+    /// Build the JSON Schema document for the given package.
+    ///
+    /// Only declarations in the package are processed, others are referenced through `ids`.
+    pub fn for_package(
+        env: &'builder Translated<CoreFlavor>,
+        package: &'builder RpVersionedPackage,
+        ids: &'builder Ids<'builder>,
+    ) -> Self {
+        SchemaBuilder {
+            document: Some((package, ids)),
+            ..Self::new(Dialect::JsonSchema, env)
+        }
+    }
+
+    /// Process a queue of components, returning the schema of each component by name.
+    pub fn process_components(
+        &self,
+        mut queue: VecDeque<Queued<'builder>>,
+    ) -> Result<LinkedHashMap<String, spec::Schema<'builder>>> {
+        use self::Queued::*;
+
+        let mut schemas = LinkedHashMap::new();
+
+        // components that have been processed.
+        let mut processed = HashSet::new();
+
+        while let Some(item) = queue.pop_front() {
+            if !processed.insert(item) {
+                continue;
+            }
+
+            let (ref_, schema) = match item {
+                // A named type that was referenced by another type.
+                Named(name) => {
+                    // NB: declarations in other packages are defined in their own document.
+                    if let Some((package, _)) = self.document {
+                        if name.package != *package {
+                            continue;
+                        }
+                    }
+
+                    let ref_ = self.component_name(name)?;
+                    let decl = self.env.lookup_decl(name)?;
+
+                    let mut schema = match *decl {
+                        core::RpDecl::Type(ref body) => {
+                            self.decl_type_to_schema(&mut queue, body)?
+                        }
+                        core::RpDecl::Interface(ref body) => {
+                            self.decl_interface_to_schema(&mut queue, body)?
+                        }
+                        core::RpDecl::Enum(ref body) => self.decl_enum_to_schema(body)?,
+                        core::RpDecl::Tuple(ref body) => {
+                            self.decl_tuple_to_schema(&mut queue, body)?
+                        }
+                        core::RpDecl::Union(ref body) => {
+                            self.decl_union_to_schema(&mut queue, body)?
+                        }
+                        core::RpDecl::Newtype(ref body) => {
+                            self.decl_newtype_to_schema(&mut queue, body)?
+                        }
+                        _ => {
+                            continue;
+                        }
+                    };
+
+                    schema.deprecated = decl.deprecated().is_some();
+
+                    (ref_, schema)
+                }
+                // Sub-type being referenced needs a body created for it.
+                SubType(tag, name, index) => self.process_sub_type(&mut queue, tag, name, index)?,
+                Any => {
+                    let ref_ = self.component_name(&self.any_type)?;
+                    (ref_, spec::Schema::from(spec::SchemaAny))
+                }
+            };
+
+            schemas.insert(ref_, schema);
+        }
+
+        Ok(schemas)
+    }
+
+    /// Process a single sub-type, creating a component that can be referenced.
+    fn process_sub_type(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        tag: Option<&'builder str>,
+        name: &RpName,
+        index: usize,
+    ) -> Result<(String, spec::Schema<'builder>)> {
+        let decl = self.env.lookup_decl(name)?;
+
+        let (body, sub_type) = match *decl {
+            core::RpDecl::Interface(ref body) => match body.sub_types.get(index) {
+                Some(sub_type) => (body, sub_type),
+                None => return Err("bad sub-type index".into()),
+            },
+            _ => return Err("name does not refer to an interface".into()),
+        };
+
+        let ref_ = self.component_name(&sub_type.name)?;
+
+        let mut object = spec::Object::default();
+
+        // add the discriminator field, which only accepts the name of the sub-type.
+        if let Some(tag) = tag {
+            let mut string = spec::SchemaString::default();
+            string.enum_.push(sub_type.name());
+
+            object.required.push(tag);
+            object.properties.insert(tag, spec::Schema::from(string));
+        }
+
+        let mut fields = Vec::new();
+        fields.extend(body.fields());
+
+        let mut fields = fields.clone();
+        fields.extend(sub_type.fields());
+
+        if !sub_type.comment.is_empty() {
+            object.description = Some(sub_type.comment.join("\n"));
+        }
+
+        object.deprecated = sub_type.deprecated.is_some();
+
+        self.populate_properties(queue, &mut object, fields)?;
+        Ok((ref_, spec::Schema::from(object)))
+    }
+
+    /// Convert a declaration into a set of properties.
+    fn decl_type_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        body: &'builder RpTypeBody,
+    ) -> Result<spec::Schema<'builder>> {
+        let mut object = spec::Object::default();
+
+        if !body.comment.is_empty() {
+            object.description = Some(body.comment.join("\n"));
+        }
+
+        self.populate_properties(queue, &mut object, body.fields())?;
+        Ok(spec::Schema::from(object))
+    }
+
+    /// Convert a declaration into a set of properties.
+    fn decl_interface_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        body: &'builder RpInterfaceBody,
+    ) -> Result<spec::Schema<'builder>> {
+        let mut schema = spec::Schema::default();
+
+        if !body.comment.is_empty() {
+            schema.description = Some(body.comment.join("\n"));
+        }
+
+        match body.sub_type_strategy {
+            core::RpSubTypeStrategy::Untagged => {
+                for (index, sub_type) in body.sub_types.iter().enumerate() {
+                    let ref_ = self.reference(&sub_type.name)?;
+                    schema.one_of.push(spec::Schema::from(Ref(ref_)));
+                    queue.push_back(Queued::SubType(None, &body.name, index));
+                }
+            }
+            core::RpSubTypeStrategy::Tagged { ref tag } => {
+                let mut discriminator = spec::DiscriminatorObject::default();
+
+                discriminator.property_name = Some(tag);
+
+                for (index, sub_type) in body.sub_types.iter().enumerate() {
+                    let ref_ = self.reference(&sub_type.name)?;
+
+                    schema
+                        .one_of
+                        .push(spec::Schema::from(Ref(ref_.to_string())));
+                    queue.push_back(Queued::SubType(Some(tag), &body.name, index));
+
+                    discriminator.mapping.insert(sub_type.name(), ref_);
+                }
+
+                // NB: JSON Schema has no discriminators, the tag property of each sub-type only
+                // accepts its name instead.
+                schema.discriminator = match self.dialect {
+                    Dialect::OpenApi => Some(spec::Discriminator::Object(discriminator)),
+                    Dialect::AsyncApi => Some(spec::Discriminator::Property(tag)),
+                    Dialect::JsonSchema => None,
+                };
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Convert a declaration into a tuple.
+    fn decl_tuple_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        body: &'builder RpTupleBody,
+    ) -> Result<spec::Schema<'builder>> {
+        let mut items = Vec::new();
+
+        for field in body.fields() {
+            let schema = self.field_to_schema(queue, field)?;
+            items.push(schema);
+
+            // reference to external type, so add to queue.
+            if let core::RpType::Name { ref name } = *field.ty() {
+                queue.push_back(Queued::Named(name));
+            }
+        }
+
+        let mut array = spec::SchemaArray::default();
+
+        // NB: tuples have exactly as many items as they have fields.
+        array.min_items = Some(items.len());
+        array.max_items = Some(items.len());
+
+        match self.dialect {
+            Dialect::OpenApi | Dialect::JsonSchema => array.prefix_items = items,
+            Dialect::AsyncApi => array.items = Some(spec::Items::Tuple(items)),
+        }
+
+        let mut schema = spec::Schema::from(array);
+
+        if !body.comment.is_empty() {
+            schema.description = Some(body.comment.join("\n"));
+        }
+
+        Ok(schema)
+    }
+
+    /// Convert a union into a schema matching any of its variants.
+    fn decl_union_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        body: &'builder RpUnionBody,
+    ) -> Result<spec::Schema<'builder>> {
+        let mut schema = spec::Schema::default();
+
+        if !body.comment.is_empty() {
+            schema.description = Some(body.comment.join("\n"));
+        }
+
+        for variant in &body.variants {
+            schema.one_of.push(self.type_to_schema(queue, &variant.ty)?);
+
+            // reference to external type, so add to queue.
+            if let core::RpType::Name { ref name } = *variant.ty {
+                queue.push_back(Queued::Named(name));
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Convert a newtype into the schema of the type it wraps.
+    fn decl_newtype_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        body: &'builder RpNewtypeBody,
+    ) -> Result<spec::Schema<'builder>> {
+        let mut schema = self.type_to_schema(queue, &body.ty)?;
+        validate(&mut schema, &body.validate);
+
+        if !body.comment.is_empty() {
+            schema.description = Some(body.comment.join("\n"));
+        }
+
+        // reference to external type, so add to queue.
+        if let core::RpType::Name { ref name } = *body.ty {
+            queue.push_back(Queued::Named(name));
+        }
+
+        Ok(schema)
+    }
+
+    /// Convert an enum into a schema only accepting the values of its variants.
+    fn decl_enum_to_schema(&self, body: &'builder RpEnumBody) -> Result<spec::Schema<'builder>> {
+        let mut out = match body.variants {
+            core::RpVariants::String { ref variants } => {
+                let mut string = spec::SchemaString::default();
+
+                for v in variants {
+                    string.enum_.push(v.value.as_str());
+
+                    // aliases are accepted when decoding.
+                    for a in &v.aliases {
+                        string.enum_.push(a.as_str());
+                    }
+                }
+
+                spec::Schema::from(string)
+            }
+            // NB: only the kinds of numbers which fit in a JSON number are valid enum types.
+            core::RpVariants::Number { ref variants } => match body.enum_type {
+                core::RpEnumType::Number(ref number) => match number.kind {
+                    RpNumberKind::U32 => number_rule!(variants, U32, to_u32),
+                    RpNumberKind::U64 => number_rule!(variants, U64, to_u64),
+                    RpNumberKind::I32 => number_rule!(variants, I32, to_i32),
+                    RpNumberKind::I64 => number_rule!(variants, I64, to_i64),
+                    kind => return Err(format!("unsupported enum type: {}", kind).into()),
+                },
+                _ => return Err("unexpected enum type".into()),
+            },
+        };
+
+        if !body.comment.is_empty() {
+            out.description = Some(body.comment.join("\n"));
+        }
+
+        Ok(out)
+    }
+
+    /// Allocate a conflict-free name.
+    fn allocate_name(&self, name: &RpName) -> Result<String> {
+        use naming::Naming;
+
+        let mut all_names = self
+            .all_names
+            .try_borrow_mut()
+            .map_err(|_| "no mutable access")?;
+        let concat_ident = name.path.join("");
+
+        // local ident is sufficient
+        if all_names.insert(concat_ident.to_string()) {
+            return Ok(concat_ident);
+        }
+
+        // add concat package to concat identifier.
+        let concat_package = name
+            .package
+            .package
+            .parts()
+            .map(|p| self.upper_camel.convert(p))
+            .collect::<Vec<String>>()
+            .join("");
+
+        let test2 = format!("{}{}", concat_package, concat_ident);
+
+        if all_names.insert(test2.clone()) {
+            return Ok(test2);
+        }
+
+        // add version, if available
+        let base = if let Some(version) = name.package.version.as_ref() {
+            let mut parts = Vec::new();
+            parts.push(format!("V{}", version.major));
+            parts.push(version.minor.to_string());
+            parts.push(version.patch.to_string());
+
+            let version_concat = parts.join("");
+            format!("{}{}{}", concat_package, version_concat, concat_ident)
+        } else {
+            format!("{}{}", concat_package, concat_ident)
+        };
+
+        let mut name_counters = self
+            .name_counters
+            .try_borrow_mut()
+            .map_err(|_| "no mutable access")?;
+
+        let c = match name_counters.entry(base.clone()) {
+            hash_map::Entry::Occupied(mut e) => {
+                let c = *e.get();
+                *e.get_mut() += 1;
+                c
+            }
+            hash_map::Entry::Vacant(e) => *e.insert(0usize),
+        };
+
+        let test4 = format!("{}{}", base, c);
+
+        if all_names.insert(test4.clone()) {
+            return Ok(test4);
+        }
+
+        Err(format!("cannot allocate conflict-free name for: {}", name).into())
+    }
+
+    /// The name of the component for the given declaration.
+    fn component_name(&self, name: &RpName) -> Result<String> {
+        match self.dialect {
+            Dialect::OpenApi | Dialect::AsyncApi => self.name_to_ref(name),
+            // NB: documents only define declarations in a single package, so paths are unique.
+            Dialect::JsonSchema => Ok(name.path.join(".")),
+        }
+    }
+
+    /// Reference to the component for the given declaration.
+    fn reference(&self, name: &RpName) -> Result<String> {
+        let component = self.component_name(name)?;
+
+        if self.dialect != Dialect::JsonSchema {
+            return Ok(format!("{}{}", COMPONENTS, component));
+        }
+
+        match self.document {
+            Some((package, ids)) if name.package != *package => {
+                Ok(format!("{}{}{}", ids.id(&name.package)?, DEFS, component))
+            }
+            _ => Ok(format!("{}{}", DEFS, component)),
+        }
+    }
+
+    /// Convert a name into a conflict-free reference.
+    fn name_to_ref(&self, name: &RpName) -> Result<String> {
+        let mut allocated_names = self
+            .allocated_names
+            .try_borrow_mut()
+            .map_err(|_| "no mutable access")?;
+
+        match allocated_names.entry(name.clone()) {
+            hash_map::Entry::Vacant(e) => {
+                let name = self.allocate_name(&name)?;
+                e.insert(name.clone());
+                Ok(name)
+            }
+            hash_map::Entry::Occupied(e) => Ok(e.get().to_string()),
+        }
+    }
+
+    /// Convert the core type into a schema element.
+    ///
+    /// Named types are only referenced, and must be queued up by the caller.
+    pub fn type_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        ty: &'builder RpType,
+    ) -> Result<spec::Schema<'builder>> {
+        use core::RpType::*;
+
+        let out = match *ty {
+            Name { ref name } => {
+                // make sure that the name refers to something.
+                self.env.lookup_decl(name)?;
+                spec::Schema::from(Ref(self.reference(name)?))
+            }
+            // NB: only string keys are supported right now.
+            Map { ref value, .. } => {
+                let mut object = spec::Object::default();
+                object.additional_properties = Some(Box::new(self.type_to_schema(queue, value)?));
+                spec::Schema::from(object)
+            }
+            Array { ref inner } => {
+                let mut array = spec::SchemaArray::default();
+                array.items = Some(spec::Items::Single(Box::new(
+                    self.type_to_schema(queue, inner)?,
+                )));
+                spec::Schema::from(array)
+            }
+            Set { ref inner } => {
+                let mut array = spec::SchemaArray::default();
+                array.items = Some(spec::Items::Single(Box::new(
+                    self.type_to_schema(queue, inner)?,
+                )));
+                array.unique_items = true;
+                spec::Schema::from(array)
+            }
+            String(ref string) => {
+                let mut schema = spec::Schema::from(spec::SchemaString::default());
+                validate(&mut schema, &string.validate);
+                schema
+            }
+            Number(ref number) => number_to_schema(number.kind),
+            Float => spec::Schema::from(spec::Float::default()),
+            Double => spec::Schema::from(spec::Double::default()),
+            Boolean => spec::Schema::from(spec::SchemaBoolean::default()),
+            Scalar(ref scalar) => match scalar.base {
+                RpScalarBase::String(..) => spec::Schema::from(spec::SchemaString::default()),
+                RpScalarBase::Number(ref number) => number_to_schema(number.kind),
+                RpScalarBase::Float => spec::Schema::from(spec::Float::default()),
+                RpScalarBase::Double => spec::Schema::from(spec::Double::default()),
+                RpScalarBase::Boolean => spec::Schema::from(spec::SchemaBoolean::default()),
+            },
+            DateTime => {
+                let mut string = spec::SchemaString::default();
+                string.format = Some(spec::Format::DateTime);
+                spec::Schema::from(string)
+            }
+            Date => {
+                let mut string = spec::SchemaString::default();
+                string.format = Some(spec::Format::Date);
+                spec::Schema::from(string)
+            }
+            Time => {
+                let mut string = spec::SchemaString::default();
+                string.format = Some(spec::Format::Time);
+                spec::Schema::from(string)
+            }
+            Duration => {
+                let mut string = spec::SchemaString::default();
+                string.format = Some(spec::Format::Duration);
+                spec::Schema::from(string)
+            }
+            Bytes => {
+                let mut string = spec::SchemaString::default();
+                string.content_encoding = Some("base64");
+                spec::Schema::from(string)
+            }
+            // NB: the empty schema accepts anything.
+            Any if self.dialect == Dialect::JsonSchema => spec::Schema::from(spec::SchemaAny),
+            Any => {
+                queue.push_back(Queued::Any);
+                spec::Schema::from(Ref(self.reference(&self.any_type)?))
+            }
+        };
+
+        Ok(out)
+    }
+
+    /// Convert the type of a field into a schema, taking its number format into account.
+    fn field_to_schema(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        field: &'builder RpField,
+    ) -> Result<spec::Schema<'builder>> {
+        if field.is_number_as_string() {
+            if let core::RpType::Number(ref number) = *field.ty() {
+                let mut string = spec::SchemaString::default();
+                string.pattern = Some(number_pattern(number.kind).to_string());
+                return Ok(spec::Schema::from(string));
+            }
+        }
+
+        self.type_to_schema(queue, field.ty())
+    }
+
+    /// Populate properties on the given Object and collect additional types to process.
+    fn populate_properties(
+        &self,
+        queue: &mut VecDeque<Queued<'builder>>,
+        object: &mut spec::Object<'builder>,
+        fields: impl IntoIterator<Item = &'builder Loc<RpField>>,
+    ) -> Result<()> {
+        for field in fields {
+            let mut schema = self.field_to_schema(queue, field)?;
+
+            if field.is_required() {
+                object.required.push(field.name());
+            }
+
+            if field.name() != field.safe_ident() {
+                schema.title = Some(field.safe_ident());
+            }
+
+            if !field.comment.is_empty() {
+                schema.description = Some(field.comment.join("\n"));
+            }
+
+            schema.deprecated = field.deprecated.is_some();
+
+            object.properties.insert(field.name(), schema);
+
+            // reference to external type, so add to queue.
+            if let core::RpType::Name { ref name } = *field.ty() {
+                queue.push_back(Queued::Named(name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Pattern matching numbers of the given kind, when they are encoded as strings.
+fn number_pattern(kind: RpNumberKind) -> &'static str {
+    match kind {
+        RpNumberKind::U64 => "^[0-9]+$",
+        // NB: only 64-bit integers are encoded as strings.
+        _ => "^-?[0-9]+$",
+    }
+}
+
+/// Apply string validation to the given schema.
+fn validate(schema: &mut spec::Schema, validate: &RpStringValidate) {
+    if let Some(ref pattern) = validate.pattern {
+        schema.pattern = Some(pattern.to_string());
+    }
+
+    schema.min_length = validate.min_length;
+    schema.max_length = validate.max_length;
+}
+
+/// Build the schema for the given kind of number.
+fn number_to_schema<'a>(kind: RpNumberKind) -> spec::Schema<'a> {
+    let mut schema = match kind {
+        RpNumberKind::I32 => spec::Schema::from(spec::I32::default()),
+        RpNumberKind::I64 => spec::Schema::from(spec::I64::default()),
+        RpNumberKind::U32 => spec::Schema::from(spec::U32::default()),
+        RpNumberKind::U64 => spec::Schema::from(spec::U64::default()),
+        RpNumberKind::U128 | RpNumberKind::I128 => {
+            spec::Schema::from(spec::SchemaBigInteger::default())
+        }
+        RpNumberKind::Decimal => spec::Schema::from(spec::SchemaDecimal::default()),
+    };
+
+    // NB: formats are only annotations, so unsigned numbers also need a minimum.
+    match kind {
+        RpNumberKind::U32 | RpNumberKind::U64 | RpNumberKind::U128 => schema.minimum = Some(0),
+        _ => {}
+    }
+
+    schema
+}
@@ -0,0 +1,340 @@
+//! JSON Schema objects, as embedded in OpenAPI and AsyncAPI documents, or published on their own.
+
+use linked_hash_map::LinkedHashMap;
+
+pub struct Ref(pub String);
+
+impl<'a> From<Ref> for Schema<'a> {
+    fn from(reference: Ref) -> Self {
+        Schema {
+            reference: Some(reference.0),
+            ..Schema::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaBoolean {}
+
+impl<'a> From<SchemaBoolean> for Schema<'a> {
+    fn from(_: SchemaBoolean) -> Self {
+        Schema {
+            ty: Some("boolean"),
+            ..Schema::default()
+        }
+    }
+}
+
+/// Integers without a fixed size, like `u128` and `i128`.
+#[derive(Debug, Default)]
+pub struct SchemaBigInteger {}
+
+impl<'a> From<SchemaBigInteger> for Schema<'a> {
+    fn from(_: SchemaBigInteger) -> Self {
+        Schema {
+            ty: Some("integer"),
+            ..Schema::default()
+        }
+    }
+}
+
+/// Arbitrary precision decimal numbers.
+#[derive(Debug, Default)]
+pub struct SchemaDecimal {}
+
+impl<'a> From<SchemaDecimal> for Schema<'a> {
+    fn from(_: SchemaDecimal) -> Self {
+        Schema {
+            ty: Some("number"),
+            ..Schema::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaAny;
+
+impl<'a> From<SchemaAny> for Schema<'a> {
+    fn from(_: SchemaAny) -> Self {
+        Schema::default()
+    }
+}
+
+macro_rules! numeric_type {
+    ($name:ident, $ty:ty, $schema_ty:expr) => {
+        #[derive(Debug, Default)]
+        pub struct $name {
+            pub enum_: Vec<$ty>,
+        }
+
+        impl<'a> From<$name> for Schema<'a> {
+            #[allow(unused)]
+            fn from(integer: $name) -> Self {
+                Schema {
+                    ty: Some($schema_ty),
+                    format: Some(Format::$name),
+                    enum_: Enum::$name(integer.enum_),
+                    ..Schema::default()
+                }
+            }
+        }
+    };
+}
+
+numeric_type!(U32, u32, "integer");
+numeric_type!(U64, u64, "integer");
+numeric_type!(I32, i32, "integer");
+numeric_type!(I64, i64, "integer");
+numeric_type!(Float, f32, "number");
+numeric_type!(Double, f64, "number");
+
+#[derive(Debug, Default)]
+pub struct SchemaString<'a> {
+    pub enum_: Vec<&'a str>,
+    pub format: Option<Format>,
+    pub content_encoding: Option<&'static str>,
+    pub pattern: Option<String>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
+
+impl<'a> From<SchemaString<'a>> for Schema<'a> {
+    fn from(string: SchemaString<'a>) -> Self {
+        Schema {
+            ty: Some("string"),
+            enum_: Enum::String(string.enum_),
+            format: string.format,
+            content_encoding: string.content_encoding,
+            pattern: string.pattern,
+            min_length: string.min_length,
+            max_length: string.max_length,
+            ..Schema::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Object<'a> {
+    pub required: Vec<&'a str>,
+    pub properties: LinkedHashMap<&'a str, Schema<'a>>,
+    pub additional_properties: Option<Box<Schema<'a>>>,
+    pub title: Option<&'a str>,
+    pub description: Option<String>,
+    pub deprecated: bool,
+}
+
+impl<'a> From<Object<'a>> for Schema<'a> {
+    fn from(object: Object<'a>) -> Self {
+        Schema {
+            ty: Some("object"),
+            required: object.required,
+            properties: object.properties,
+            additional_properties: object.additional_properties,
+            title: object.title,
+            description: object.description,
+            deprecated: object.deprecated,
+            ..Schema::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaArray<'a> {
+    pub items: Option<Items<'a>>,
+    pub unique_items: bool,
+    /// For tuples, the type of each position.
+    pub prefix_items: Vec<Schema<'a>>,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+}
+
+impl<'a> From<SchemaArray<'a>> for Schema<'a> {
+    fn from(array: SchemaArray<'a>) -> Self {
+        Schema {
+            ty: Some("array"),
+            items: array.items,
+            unique_items: array.unique_items,
+            prefix_items: array.prefix_items,
+            min_items: array.min_items,
+            max_items: array.max_items,
+            ..Schema::default()
+        }
+    }
+}
+
+/// How the items of an array are specified.
+#[serde(untagged)]
+#[derive(Debug, Serialize)]
+pub enum Items<'a> {
+    /// Every item has the same type.
+    Single(Box<Schema<'a>>),
+    /// For tuples, the type of each position.
+    Tuple(Vec<Schema<'a>>),
+}
+
+#[serde(untagged, rename_all = "camelCase")]
+#[derive(Debug, Serialize)]
+pub enum Enum<'a> {
+    String(Vec<&'a str>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    None,
+}
+
+impl<'a> Default for Enum<'a> {
+    fn default() -> Self {
+        Enum::None
+    }
+}
+
+impl<'a> Enum<'a> {
+    fn is_empty(&self) -> bool {
+        use self::Enum::*;
+
+        match *self {
+            String(ref variants) => variants.is_empty(),
+            U32(ref variants) => variants.is_empty(),
+            U64(ref variants) => variants.is_empty(),
+            I32(ref variants) => variants.is_empty(),
+            I64(ref variants) => variants.is_empty(),
+            Float(ref variants) => variants.is_empty(),
+            Double(ref variants) => variants.is_empty(),
+            None => true,
+        }
+    }
+}
+
+/// How the sub-types of an interface are told apart.
+#[serde(untagged)]
+#[derive(Debug, Serialize)]
+pub enum Discriminator<'a> {
+    /// Name of the property holding the tag.
+    Property(&'a str),
+    /// Name of the property holding the tag, and the schema used for each tag.
+    Object(DiscriminatorObject<'a>),
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize)]
+pub struct DiscriminatorObject<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property_name: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "LinkedHashMap::is_empty")]
+    pub mapping: LinkedHashMap<&'a str, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum Format {
+    #[serde(rename = "uint32")]
+    U32,
+    #[serde(rename = "uint64")]
+    U64,
+    #[serde(rename = "int32")]
+    I32,
+    #[serde(rename = "int64")]
+    I64,
+    #[serde(rename = "float")]
+    Float,
+    #[serde(rename = "double")]
+    Double,
+    #[serde(rename = "date-time")]
+    DateTime,
+    #[serde(rename = "date")]
+    Date,
+    #[serde(rename = "time")]
+    Time,
+    #[serde(rename = "duration")]
+    Duration,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize)]
+pub struct Schema<'a> {
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<&'static str>,
+
+    /// Description of this schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// If the schema is deprecated.
+    #[serde(skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+
+    /// How arrays specify inner item type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Items<'a>>,
+
+    /// If the items of an array are unique.
+    #[serde(skip_serializing_if = "is_false")]
+    pub unique_items: bool,
+
+    /// The type of each position in a tuple.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub prefix_items: Vec<Schema<'a>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+
+    /// Format acts as extra specification of the type when needed.
+    /// Also extensible.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+
+    /// How the content of a string is encoded, like `base64`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<&'static str>,
+
+    /// Regular expression that strings must match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+
+    /// Smallest number that is accepted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<u32>,
+
+    /// Available enumerations.
+    #[serde(rename = "enum", skip_serializing_if = "Enum::is_empty")]
+    pub enum_: Enum<'a>,
+
+    /// `oneOf` field
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<Schema<'a>>,
+
+    /// How sub-types are told apart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Discriminator<'a>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<&'a str>,
+
+    #[serde(skip_serializing_if = "LinkedHashMap::is_empty")]
+    pub properties: LinkedHashMap<&'a str, Schema<'a>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<Box<Schema<'a>>>,
+}
+
+fn is_false(input: &bool) -> bool {
+    !input
+}
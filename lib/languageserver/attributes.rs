@@ -0,0 +1,118 @@
+//! Attributes which are known by the compiler, used to provide completions.
+
+/// Where an attribute is declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeTarget {
+    /// File attributes, like `#![reproto(version = "1.0.0")]`.
+    File,
+    /// Attributes on declarations, sub-types, and enum variants.
+    Decl,
+    /// Attributes on fields.
+    Field,
+    /// Attributes on endpoints.
+    Endpoint,
+    /// Attributes on code blocks.
+    Code,
+}
+
+/// An attribute which is known by the compiler.
+#[derive(Debug)]
+pub struct KnownAttribute {
+    /// Name of the attribute.
+    pub name: &'static str,
+    /// Where the attribute is permitted.
+    pub targets: &'static [AttributeTarget],
+    /// Keys accepted in the attribute, like `method` in `#[http(method = "GET")]`.
+    pub keys: &'static [&'static str],
+    /// Short description of the attribute.
+    pub detail: &'static str,
+}
+
+const KNOWN: &[KnownAttribute] = &[
+    KnownAttribute {
+        name: "deprecated",
+        targets: &[
+            AttributeTarget::Decl,
+            AttributeTarget::Field,
+            AttributeTarget::Endpoint,
+        ],
+        keys: &["note"],
+        detail: "mark as deprecated",
+    },
+    KnownAttribute {
+        name: "endpoint_naming",
+        targets: &[AttributeTarget::File],
+        keys: &[],
+        detail: "naming convention of endpoints",
+    },
+    KnownAttribute {
+        name: "feature",
+        targets: &[AttributeTarget::File],
+        keys: &[],
+        detail: "activate language features",
+    },
+    KnownAttribute {
+        name: "field_naming",
+        targets: &[AttributeTarget::File],
+        keys: &[],
+        detail: "naming convention of fields",
+    },
+    KnownAttribute {
+        name: "format",
+        targets: &[AttributeTarget::Field],
+        keys: &[],
+        detail: "format of a string field",
+    },
+    KnownAttribute {
+        name: "http",
+        targets: &[AttributeTarget::Endpoint],
+        keys: &["path", "method", "query", "headers", "accepts", "produces"],
+        detail: "HTTP options of an endpoint",
+    },
+    KnownAttribute {
+        name: "id",
+        targets: &[AttributeTarget::Field],
+        keys: &[],
+        detail: "stable numeric identifier of a field",
+    },
+    KnownAttribute {
+        name: "import",
+        targets: &[AttributeTarget::Code],
+        keys: &[],
+        detail: "imports required by a code block",
+    },
+    KnownAttribute {
+        name: "reproto",
+        targets: &[AttributeTarget::File],
+        keys: &["version"],
+        detail: "version of reproto required by the file",
+    },
+    KnownAttribute {
+        name: "reserved",
+        targets: &[AttributeTarget::Decl],
+        keys: &[],
+        detail: "reserve field names",
+    },
+    KnownAttribute {
+        name: "type_info",
+        targets: &[AttributeTarget::Decl],
+        keys: &["strategy", "tag"],
+        detail: "how sub-types of an interface are identified",
+    },
+    KnownAttribute {
+        name: "validate",
+        targets: &[AttributeTarget::Field],
+        keys: &["pattern"],
+        detail: "validation of a string field",
+    },
+];
+
+/// All attributes which are permitted at the given target.
+pub fn for_target(target: AttributeTarget) -> impl Iterator<Item = &'static KnownAttribute> {
+    KNOWN.iter().filter(move |a| a.targets.contains(&target))
+}
+
+/// Look up a known attribute by name.
+pub fn get(name: &str) -> Option<&'static KnownAttribute> {
+    KNOWN.iter().find(|a| a.name == name)
+}
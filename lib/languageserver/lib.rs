@@ -16,6 +16,7 @@ extern crate serde_json as json;
 extern crate url;
 extern crate url_serde;
 
+mod attributes;
 mod envelope;
 mod loaded_file;
 mod models;
@@ -23,20 +24,21 @@ mod triggers;
 mod workspace;
 
 use self::loaded_file::LoadedFile;
-use self::models::{Completion, Jump, Range, RenameResult};
+use self::models::{Action, Completion, Jump, Range, RenameResult};
 use self::workspace::Workspace;
 use self::ContentType::*;
 use core::errors::Result;
 use core::{Diagnostic, Encoding, Filesystem, RealFilesystem, Reported, Rope, Source};
 use serde::Deserialize;
-use std::cell::RefCell;
 use std::collections::{BTreeSet, Bound, HashMap};
 use std::fmt;
 use std::io::{self, BufRead, BufReader, Read, Write};
 use std::ops::DerefMut;
 use std::path::Path;
 use std::result;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread;
 use url::Url;
 
 /// newtype to serialize URLs
@@ -228,6 +230,106 @@ where
 
         self.send_frame(envelope)
     }
+
+    /// Send all diagnostics for a workspace.
+    fn send_workspace_diagnostics(&self, workspace: &Workspace) -> Result<()> {
+        self.send_manifest_diagnostics(workspace)?;
+
+        let mut by_url: HashMap<Url, Vec<(&Source, &Diagnostic)>> = HashMap::new();
+
+        for diagnostics in &workspace.reporter {
+            match *diagnostics {
+                Reported::Diagnostics(ref diagnostics) => {
+                    if let Some(url) = diagnostics.source.url() {
+                        let items = by_url.entry(url.clone()).or_insert_with(Vec::new);
+
+                        for d in diagnostics.items() {
+                            items.push((&diagnostics.source, d));
+                        }
+                    }
+                }
+                Reported::SourceDiagnostics(ref diagnostics) => {
+                    for d in diagnostics.items() {
+                        if let Some(url) = d.0.url() {
+                            by_url
+                                .entry(url)
+                                .or_insert_with(Vec::new)
+                                .push((&d.0, &d.1));
+                        }
+                    }
+                }
+            }
+        }
+
+        for file in workspace.files() {
+            let by_url = by_url.remove(&file.url);
+            let by_url_chain = by_url.into_iter().flat_map(|d| d.into_iter()).map(|d| d.1);
+
+            self.send_diagnostics(
+                &file.url,
+                &file.diag.source,
+                file.diag.items().chain(by_url_chain),
+            )?;
+        }
+
+        // diagnostics about other random files
+        for (url, diag) in by_url {
+            for (source, d) in diag {
+                self.send_diagnostics(&url, source, ::std::iter::once(d))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send manifest diagnostics.
+    fn send_manifest_diagnostics(&self, workspace: &Workspace) -> Result<()> {
+        let mut diagnostics = Vec::new();
+        let mut has_errors = false;
+
+        if let Some(diag) = workspace.manifest_diagnostics.as_ref() {
+            diagnostics.extend(convert_diagnostics(&diag.source, diag.items())?);
+            has_errors = diag.has_errors();
+        }
+
+        // errors from validation point into the manifest, and are more useful than the error
+        // they result in.
+        if !has_errors {
+            if let Some(e) = workspace.manifest_error.as_ref() {
+                let d = ty::Diagnostic {
+                    message: e.display().to_string(),
+                    severity: Some(ty::DiagnosticSeverity::Error),
+                    ..ty::Diagnostic::default()
+                };
+
+                diagnostics.push(d);
+            }
+        }
+
+        let url = workspace.manifest_url()?;
+
+        self.notification::<ty::notification::PublishDiagnostics>(ty::PublishDiagnosticsParams {
+            uri: url,
+            diagnostics: diagnostics,
+        })?;
+
+        Ok(())
+    }
+
+    /// Send diagnostics for a single URL.
+    fn send_diagnostics<'a, I>(&self, url: &Url, source: &Source, diagnostics: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Diagnostic>,
+    {
+        let out = convert_diagnostics(source, diagnostics)?;
+
+        self.notification::<ty::notification::PublishDiagnostics>(ty::PublishDiagnosticsParams {
+            uri: url.clone(),
+            diagnostics: out,
+        })?;
+
+        Ok(())
+    }
 }
 
 pub struct Logger<L>
@@ -388,7 +490,11 @@ fn trim(data: &[u8]) -> &[u8] {
 
 /// Server abstraction
 struct Server<R, W> {
-    workspace: Option<RefCell<Workspace>>,
+    workspace: Option<Arc<Mutex<Workspace>>>,
+    /// Indexing of the workspace which is running in the background.
+    indexing: Option<Indexing>,
+    /// Counter used to allocate indexing tokens.
+    indexing_id: u64,
     headers: Headers,
     reader: InputReader<BufReader<R>>,
     channel: Channel<W>,
@@ -398,24 +504,30 @@ struct Server<R, W> {
     expected: HashMap<envelope::RequestId, Expected>,
     /// Built-in types.
     built_ins: Vec<&'static str>,
+    /// If the client supports hierarchical document symbols.
+    hierarchical_symbols: bool,
 }
 
 impl<R, W> Server<R, W>
 where
     R: Read,
-    W: Write,
+    W: 'static + Send + Write,
 {
     pub fn new(reader: R, channel: Channel<W>) -> Self {
         Self {
             workspace: None,
+            indexing: None,
+            indexing_id: 0,
             headers: Headers::new(),
             reader: InputReader::new(BufReader::new(reader)),
             channel,
             fs: RealFilesystem::new(),
             expected: HashMap::new(),
             built_ins: vec![
-                "string", "bytes", "u32", "u64", "i32", "i64", "float", "double", "datetime", "any",
+                "string", "bytes", "u32", "u64", "u128", "i32", "i64", "i128", "decimal", "float",
+                "double", "datetime", "date", "time", "duration", "any",
             ],
+            hierarchical_symbols: false,
         }
     }
 
@@ -569,6 +681,10 @@ where
                 let params = ty::TextDocumentPositionParams::deserialize(request.params)?;
                 self.text_document_definition(request.id, params)?;
             }
+            "textDocument/signatureHelp" => {
+                let params = ty::TextDocumentPositionParams::deserialize(request.params)?;
+                self.text_document_signature_help(request.id, params)?;
+            }
             "textDocument/rename" => {
                 let params = ty::RenameParams::deserialize(request.params)?;
                 self.text_document_rename(request.id, params)?;
@@ -581,6 +697,10 @@ where
                 let params = ty::ReferenceParams::deserialize(request.params)?;
                 self.text_document_references(request.id, params)?;
             }
+            "textDocument/codeAction" => {
+                let params = ty::CodeActionParams::deserialize(request.params)?;
+                self.text_document_code_action(request.id, params)?;
+            }
             "workspace/symbol" => {
                 let params = ty::WorkspaceSymbolParams::deserialize(request.params)?;
                 self.workspace_symbol(request.id, params)?;
@@ -596,6 +716,10 @@ where
             "$/cancelRequest" => {
                 // ignore
             }
+            "window/workDoneProgress/cancel" => {
+                let params = WorkDoneProgressCancelParams::deserialize(request.params)?;
+                self.work_done_progress_cancel(params)?;
+            }
             method => {
                 error!("unsupported method: {}", method);
 
@@ -672,7 +796,7 @@ where
 
         if let Some(workspace) = self.workspace.as_ref() {
             let mut workspace = workspace
-                .try_borrow_mut()
+                .lock()
                 .map_err(|_| "failed to access mutable workspace")?;
 
             let handle = self.fs.open_root(Some(&workspace.root_path))?;
@@ -703,7 +827,7 @@ where
 
         if let Some(workspace) = self.workspace.as_ref() {
             let mut workspace = workspace
-                .try_borrow()
+                .lock()
                 .map_err(|_| "failed to access mutable workspace")?;
 
             if response.title == "Open project manifest" {
@@ -718,6 +842,93 @@ where
     }
 
     fn shutdown(&mut self) -> Result<()> {
+        self.cancel_indexing();
+        Ok(())
+    }
+
+    /// Handler for `window/workDoneProgress/cancel`.
+    fn work_done_progress_cancel(&mut self, params: WorkDoneProgressCancelParams) -> Result<()> {
+        if let Some(indexing) = self.indexing.as_ref() {
+            if indexing.token == params.token {
+                debug!("cancelling indexing: {}", indexing.token);
+                indexing.cancel();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Test if the workspace is being indexed in the background.
+    fn is_indexing(&self) -> bool {
+        self.indexing
+            .as_ref()
+            .map(|indexing| !indexing.is_done())
+            .unwrap_or(false)
+    }
+
+    /// Cancel indexing of the workspace, if it is in progress.
+    fn cancel_indexing(&self) {
+        if let Some(indexing) = self.indexing.as_ref() {
+            indexing.cancel();
+        }
+    }
+
+    /// Index the workspace in a background thread, replacing the current workspace once done.
+    ///
+    /// Any indexing which is already in progress is cancelled, and progress is reported to the
+    /// client through `$/progress`.
+    fn index(&mut self) -> Result<()> {
+        let workspace = match self.workspace.as_ref() {
+            Some(workspace) => Arc::clone(workspace),
+            None => return Ok(()),
+        };
+
+        self.cancel_indexing();
+
+        let indexed = {
+            let workspace = workspace
+                .lock()
+                .map_err(|_| "failed to access workspace immutably")?;
+
+            debug!("indexing project: {}", workspace.root_path.display());
+
+            let mut indexed = Workspace::new(Box::new(self.fs.clone()), &workspace.root_path);
+            indexed.open_files = workspace.open_files.clone();
+            indexed
+        };
+
+        self.indexing_id += 1;
+
+        let indexing = Indexing {
+            token: format!("reproto/indexing/{}", self.indexing_id),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            done: Arc::new(AtomicBool::new(false)),
+        };
+
+        self.channel
+            .request::<WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                token: indexing.token.clone(),
+            })?;
+
+        self.channel.notification::<Progress>(ProgressParams {
+            token: indexing.token.clone(),
+            value: WorkDoneProgress::Begin {
+                title: "Indexing".to_string(),
+                cancellable: true,
+                percentage: 0,
+            },
+        })?;
+
+        let channel = self.channel.clone();
+        let thread_indexing = indexing.clone();
+
+        thread::spawn(move || {
+            if let Err(e) = index_workspace(&channel, &workspace, indexed, &thread_indexing) {
+                error!("failed to index workspace: {}", e.display());
+            }
+        });
+
+        self.indexing = Some(indexing);
         Ok(())
     }
 
@@ -735,9 +946,17 @@ where
                 .map_err(|_| format!("could not canonicalize root path: {}", path.display()))?;
 
             let workspace = Workspace::new(Box::new(self.fs.clone()), path);
-            self.workspace = Some(RefCell::new(workspace));
+            self.workspace = Some(Arc::new(Mutex::new(workspace)));
         }
 
+        self.hierarchical_symbols = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|t| t.document_symbol.as_ref())
+            .and_then(|d| d.hierarchical_document_symbol_support)
+            .unwrap_or(false);
+
         let result = ty::InitializeResult {
             capabilities: ty::ServerCapabilities {
                 text_document_sync: Some(ty::TextDocumentSyncCapability::Kind(
@@ -747,11 +966,15 @@ where
                     trigger_characters: Some(vec![":".into(), ".".into()]),
                     ..ty::CompletionOptions::default()
                 }),
+                signature_help_provider: Some(ty::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".into(), ",".into()]),
+                }),
                 definition_provider: Some(true),
                 rename_provider: Some(true),
                 document_symbol_provider: Some(true),
                 workspace_symbol_provider: Some(true),
                 references_provider: Some(true),
+                code_action_provider: Some(true),
                 ..ty::ServerCapabilities::default()
             },
         };
@@ -762,16 +985,7 @@ where
 
     /// Handler for `initialized`.
     fn initialized(&mut self, _params: ty::InitializedParams) -> Result<()> {
-        if let Some(workspace) = self.workspace.as_ref() {
-            let mut workspace = workspace
-                .try_borrow_mut()
-                .map_err(|_| "failed to access mutable workspace")?;
-
-            debug!("loading project: {}", workspace.root_path.display());
-            workspace.reload()?;
-        }
-
-        self.send_workspace_diagnostics()?;
+        self.index()?;
         Ok(())
     }
 
@@ -787,7 +1001,7 @@ where
 
         if let Some(workspace) = self.workspace.as_ref() {
             let workspace = workspace
-                .try_borrow()
+                .lock()
                 .map_err(|_| "failed to access workspace immutably")?;
 
             for file in workspace.files() {
@@ -849,7 +1063,7 @@ where
 
                 symbols.push(ty::SymbolInformation {
                     name: path.join("::"),
-                    kind: ty::SymbolKind::Class,
+                    kind: s.kind,
                     location: location,
                     container_name: Some(file.package.to_string()),
                 });
@@ -867,20 +1081,71 @@ where
     ) -> Result<()> {
         let url = params.text_document.uri;
 
-        let mut symbols = Vec::new();
+        let mut response = None;
 
         if let Some(workspace) = self.workspace.as_ref() {
             let workspace = workspace
-                .try_borrow()
+                .lock()
                 .map_err(|_| "failed to access workspace immutably")?;
 
             if let Some(file) = workspace.file(&url) {
-                self.populate_symbols(&mut symbols, file, None)?;
+                if self.hierarchical_symbols {
+                    let symbols = document_symbols(file, &vec![]);
+                    response = Some(ty::DocumentSymbolResponse::Nested(symbols));
+                } else {
+                    let mut symbols = Vec::new();
+                    self.populate_symbols(&mut symbols, file, None)?;
+                    response = Some(ty::DocumentSymbolResponse::Flat(symbols));
+                }
             }
         }
 
-        self.channel.send(request_id, Some(symbols))?;
-        Ok(())
+        self.channel.send(request_id, response)?;
+        return Ok(());
+
+        /// Build the nested outline of all symbols under the given path.
+        fn document_symbols(file: &LoadedFile, path: &Vec<String>) -> Vec<ty::DocumentSymbol> {
+            let mut out = Vec::new();
+
+            let symbols = match file.symbols.get(path) {
+                Some(symbols) => symbols,
+                None => return out,
+            };
+
+            for s in symbols {
+                let mut path = path.clone();
+                path.push(s.name.to_string());
+
+                let mut children = s
+                    .members
+                    .iter()
+                    .map(|m| ty::DocumentSymbol {
+                        name: m.name.to_string(),
+                        detail: None,
+                        kind: m.kind,
+                        deprecated: None,
+                        range: convert_range(m.full_range),
+                        selection_range: convert_range(m.range),
+                        children: None,
+                    })
+                    .collect::<Vec<_>>();
+
+                children.extend(document_symbols(file, &path));
+                children.sort_by_key(|c| (c.range.start.line, c.range.start.character));
+
+                out.push(ty::DocumentSymbol {
+                    name: s.name.to_string(),
+                    detail: None,
+                    kind: s.kind,
+                    deprecated: None,
+                    range: convert_range(s.full_range),
+                    selection_range: convert_range(s.range),
+                    children: Some(children),
+                });
+            }
+
+            out
+        }
     }
 
     /// Handler for `textDocument/references`.
@@ -895,7 +1160,7 @@ where
 
         if let Some(workspace) = self.workspace.as_ref() {
             let workspace = workspace
-                .try_borrow()
+                .lock()
                 .map_err(|_| "failed to access workspace immutably")?;
 
             if let Some(references) = workspace.find_reference(&url, params.position) {
@@ -914,169 +1179,98 @@ where
         Ok(())
     }
 
-    /// Handler for `workspace/didChangeConfiguration`.
-    fn workspace_did_change_configuration(
+    /// Handler for `textDocument/codeAction`.
+    fn text_document_code_action(
         &mut self,
-        _: Option<envelope::RequestId>,
-        _: ty::DidChangeConfigurationParams,
+        request_id: Option<envelope::RequestId>,
+        params: ty::CodeActionParams,
     ) -> Result<()> {
-        Ok(())
-    }
+        let url = params.text_document.uri;
+
+        let mut response: ty::CodeActionResponse = Vec::new();
 
-    /// Send all diagnostics for a workspace.
-    fn send_workspace_diagnostics(&self) -> Result<()> {
         if let Some(workspace) = self.workspace.as_ref() {
             let workspace = workspace
-                .try_borrow()
+                .lock()
                 .map_err(|_| "failed to access workspace immutably")?;
 
-            self.send_manifest_diagnostics(&workspace)?;
-
-            let mut by_url: HashMap<Url, Vec<(&Source, &Diagnostic)>> = HashMap::new();
-
-            for diagnostics in &workspace.reporter {
-                match *diagnostics {
-                    Reported::Diagnostics(ref diagnostics) => {
-                        if let Some(url) = diagnostics.source.url() {
-                            let items = by_url.entry(url.clone()).or_insert_with(Vec::new);
-
-                            for d in diagnostics.items() {
-                                items.push((&diagnostics.source, d));
-                            }
-                        }
+            for action in workspace.find_actions(&url, params.range) {
+                match *action {
+                    Action::RemoveUse {
+                        ref prefix,
+                        ref range,
+                    } => {
+                        let edits = vec![ty::TextEdit {
+                            range: convert_range(range),
+                            new_text: String::new(),
+                        }];
+
+                        response.push(ty::CodeActionOrCommand::CodeAction(ty::CodeAction {
+                            title: format!("Remove unused import `{}`", prefix),
+                            kind: Some("quickfix".to_string()),
+                            diagnostics: None,
+                            edit: Some(local_edits(&url, edits)),
+                            command: None,
+                        }));
                     }
-                    Reported::SourceDiagnostics(ref diagnostics) => {
-                        for d in diagnostics.items() {
-                            if let Some(url) = d.0.url() {
-                                by_url
-                                    .entry(url)
-                                    .or_insert_with(Vec::new)
-                                    .push((&d.0, &d.1));
-                            }
-                        }
+                    Action::AddUse {
+                        ref package,
+                        ref position,
+                    } => {
+                        let edits = vec![ty::TextEdit {
+                            range: convert_range((*position, *position)),
+                            new_text: format!("use {};\n", package),
+                        }];
+
+                        response.push(ty::CodeActionOrCommand::CodeAction(ty::CodeAction {
+                            title: format!("Import `{}`", package),
+                            kind: Some("quickfix".to_string()),
+                            diagnostics: None,
+                            edit: Some(local_edits(&url, edits)),
+                            command: None,
+                        }));
                     }
                 }
             }
-
-            for file in workspace.files() {
-                let by_url = by_url.remove(&file.url);
-                let by_url_chain = by_url.into_iter().flat_map(|d| d.into_iter()).map(|d| d.1);
-
-                self.send_diagnostics(
-                    &file.url,
-                    &file.diag.source,
-                    file.diag.items().chain(by_url_chain),
-                )?;
-            }
-
-            // diagnostics about other random files
-            for (url, diag) in by_url {
-                for (source, d) in diag {
-                    self.send_diagnostics(&url, source, ::std::iter::once(d))?;
-                }
-            }
         }
 
+        self.channel.send(request_id, response)?;
         Ok(())
     }
 
-    /// Send manifest diagnostics.
-    fn send_manifest_diagnostics(&self, workspace: &Workspace) -> Result<()> {
-        let mut diagnostics = Vec::new();
-
-        if let Some(e) = workspace.manifest_error.as_ref() {
-            let d = ty::Diagnostic {
-                message: e.display().to_string(),
-                severity: Some(ty::DiagnosticSeverity::Error),
-                ..ty::Diagnostic::default()
-            };
-
-            diagnostics.push(d);
-        }
-
-        let url = workspace.manifest_url()?;
-
-        self.channel
-            .notification::<ty::notification::PublishDiagnostics>(ty::PublishDiagnosticsParams {
-                uri: url,
-                diagnostics: diagnostics,
-            })?;
-
+    /// Handler for `workspace/didChangeConfiguration`.
+    fn workspace_did_change_configuration(
+        &mut self,
+        _: Option<envelope::RequestId>,
+        _: ty::DidChangeConfigurationParams,
+    ) -> Result<()> {
         Ok(())
     }
 
-    /// Send diagnostics for a single URL.
-    fn send_diagnostics<'a, I>(&self, url: &Url, source: &Source, diagnostics: I) -> Result<()>
-    where
-        I: IntoIterator<Item = &'a Diagnostic>,
-    {
-        let mut out = Vec::new();
-
-        for d in diagnostics.into_iter() {
-            match *d {
-                core::Diagnostic::Error {
-                    ref span,
-                    ref message,
-                } => {
-                    let (start, end) = source.span_to_range(*span, Encoding::Utf16)?;
-                    let range = convert_range((start, end));
-
-                    let d = ty::Diagnostic {
-                        range: range,
-                        message: message.to_string(),
-                        severity: Some(ty::DiagnosticSeverity::Error),
-                        ..ty::Diagnostic::default()
-                    };
-
-                    out.push(d);
-                }
-                core::Diagnostic::Info {
-                    ref span,
-                    ref message,
-                } => {
-                    let (start, end) = source.span_to_range(*span, Encoding::Utf16)?;
-                    let range = convert_range((start, end));
-
-                    let d = ty::Diagnostic {
-                        range: range,
-                        message: message.to_string(),
-                        severity: Some(ty::DiagnosticSeverity::Information),
-                        ..ty::Diagnostic::default()
-                    };
+    /// Send all diagnostics for a workspace.
+    fn send_workspace_diagnostics(&self) -> Result<()> {
+        if let Some(workspace) = self.workspace.as_ref() {
+            let workspace = workspace
+                .lock()
+                .map_err(|_| "failed to access workspace immutably")?;
 
-                    out.push(d);
-                }
-                _ => {}
-            }
+            self.channel.send_workspace_diagnostics(&workspace)?;
         }
 
-        self.channel
-            .notification::<ty::notification::PublishDiagnostics>(ty::PublishDiagnosticsParams {
-                uri: url.clone(),
-                diagnostics: out,
-            })?;
-
         Ok(())
     }
 
     /// Handler for `textDocument/didSave`.
-    fn text_document_did_save(&self, _: ty::DidSaveTextDocumentParams) -> Result<()> {
-        if let Some(workspace) = self.workspace.as_ref() {
-            let mut workspace = workspace
-                .try_borrow_mut()
-                .map_err(|_| "failed to access mutable workspace")?;
-
-            workspace.reload()?;
-        }
-
-        self.send_workspace_diagnostics()?;
+    fn text_document_did_save(&mut self, _: ty::DidSaveTextDocumentParams) -> Result<()> {
+        self.index()?;
         Ok(())
     }
 
     /// Handler for `textDocument/didChange`.
-    fn text_document_did_change(&self, params: ty::DidChangeTextDocumentParams) -> Result<()> {
+    fn text_document_did_change(&mut self, params: ty::DidChangeTextDocumentParams) -> Result<()> {
         let text_document = params.text_document;
         let url = text_document.uri;
+        let mut restart = false;
 
         {
             let workspace = match self.workspace.as_ref() {
@@ -1085,7 +1279,7 @@ where
             };
 
             let mut workspace = workspace
-                .try_borrow_mut()
+                .lock()
                 .map_err(|_| "failed to access mutable workspace")?;
 
             if params.content_changes.is_empty() {
@@ -1104,8 +1298,19 @@ where
                 None => return Ok(()),
             }
 
-            workspace.dirty(&url)?;
-            workspace.reload()?;
+            // indexing is working from outdated sources, so it has to be restarted.
+            if self.is_indexing() {
+                self.cancel_indexing();
+                restart = true;
+            } else {
+                workspace.dirty(&url)?;
+                workspace.update()?;
+            }
+        }
+
+        if restart {
+            self.index()?;
+            return Ok(());
         }
 
         self.send_workspace_diagnostics()?;
@@ -1238,7 +1443,7 @@ where
 
         if let Some(workspace) = self.workspace.as_ref() {
             let mut workspace = workspace
-                .try_borrow_mut()
+                .lock()
                 .map_err(|_| "failed to access mutable workspace")?;
 
             // NOTE: access workspace.files is intentional to only access files which are not
@@ -1259,36 +1464,35 @@ where
                 }
             };
 
-            if !built {
+            // files are not known until the workspace has been indexed.
+            if !built && !self.is_indexing() {
                 if url != workspace.manifest_url()? {
                     handle_manifest_error!(workspace);
                 }
             }
 
             workspace.open_files.insert(url.clone(), source);
-            workspace.reload()?;
         }
 
-        self.send_workspace_diagnostics()?;
+        self.index()?;
         return Ok(());
     }
 
     /// Handler for `textDocument/didClose`.
-    fn text_document_did_close(&self, params: ty::DidCloseTextDocumentParams) -> Result<()> {
+    fn text_document_did_close(&mut self, params: ty::DidCloseTextDocumentParams) -> Result<()> {
         let text_document = params.text_document;
 
         if let Some(workspace) = self.workspace.as_ref() {
             let url = text_document.uri;
 
             let mut workspace = workspace
-                .try_borrow_mut()
+                .lock()
                 .map_err(|_| "failed to access mutable workspace")?;
 
             workspace.open_files.remove(&url);
-            workspace.reload()?;
         }
 
-        self.send_workspace_diagnostics()?;
+        self.index()?;
         Ok(())
     }
 
@@ -1321,7 +1525,7 @@ where
         };
 
         let workspace = workspace
-            .try_borrow()
+            .lock()
             .map_err(|_| "failed to access immutable workspace")?;
 
         let (file, value) = match workspace.find_completion(&url, params.position) {
@@ -1329,7 +1533,7 @@ where
             None => return Ok(()),
         };
 
-        debug!("type completion: {:?}", value);
+        debug!("completion: {:?}", value);
 
         match *value {
             Completion::Package { ref results, .. } => for r in results {
@@ -1381,6 +1585,67 @@ where
 
                 push_items(&mut list.items, &file, path, suffix)?;
             }
+            Completion::Attribute { target } => for a in attributes::for_target(target) {
+                list.items.push(ty::CompletionItem {
+                    label: a.name.to_string(),
+                    kind: Some(ty::CompletionItemKind::Property),
+                    detail: Some(a.detail.to_string()),
+                    ..ty::CompletionItem::default()
+                });
+            },
+            Completion::AttributeKey { ref attribute } => {
+                let keys = attributes::get(attribute).map(|a| a.keys).unwrap_or(&[]);
+
+                for key in keys {
+                    list.items.push(ty::CompletionItem {
+                        label: key.to_string(),
+                        kind: Some(ty::CompletionItemKind::Field),
+                        ..ty::CompletionItem::default()
+                    });
+                }
+            }
+            Completion::EnumVariant {
+                ref prefix,
+                ref path,
+                ..
+            } => {
+                let file = if let Some(ref prefix) = *prefix {
+                    match file
+                        .prefixes
+                        .get(prefix)
+                        .and_then(|p| workspace.packages.get(&p.package))
+                        .and_then(|url| workspace.file(url))
+                    {
+                        Some(file) => file,
+                        None => return Ok(()),
+                    }
+                } else {
+                    file
+                };
+
+                let (name, parent) = match path.split_last() {
+                    Some(split) => split,
+                    None => return Ok(()),
+                };
+
+                let symbols = file.symbols.get(parent).into_iter().flat_map(|s| s.iter());
+
+                for s in symbols.filter(|s| s.name == *name) {
+                    match s.kind {
+                        ty::SymbolKind::Enum => {}
+                        _ => continue,
+                    }
+
+                    for m in &s.members {
+                        list.items.push(ty::CompletionItem {
+                            label: m.name.to_string(),
+                            kind: Some(ty::CompletionItemKind::EnumMember),
+                            detail: Some(format!("{}::{}", path.join("::"), m.name)),
+                            ..ty::CompletionItem::default()
+                        });
+                    }
+                }
+            }
         }
 
         return Ok(());
@@ -1439,6 +1704,106 @@ where
         Ok(())
     }
 
+    /// Handler for `textDocument/signatureHelp`.
+    fn text_document_signature_help(
+        &self,
+        request_id: Option<envelope::RequestId>,
+        params: ty::TextDocumentPositionParams,
+    ) -> Result<()> {
+        let mut response: Option<ty::SignatureHelp> = None;
+        self.signature_help(params, &mut response)?;
+        self.channel.send(request_id, response)?;
+        Ok(())
+    }
+
+    /// Populate signature help for the endpoint at the given position.
+    fn signature_help(
+        &self,
+        params: ty::TextDocumentPositionParams,
+        response: &mut Option<ty::SignatureHelp>,
+    ) -> Result<()> {
+        let url = params.text_document.uri;
+
+        let workspace = match self.workspace.as_ref() {
+            Some(workspace) => workspace,
+            None => return Ok(()),
+        };
+
+        let workspace = workspace
+            .lock()
+            .map_err(|_| "failed to access immutable workspace")?;
+
+        let (file, signature) = match workspace.find_signature(&url, params.position) {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let position = core::Position {
+            line: params.position.line as usize,
+            col: params.position.character as usize,
+        };
+
+        let mut doc = Vec::new();
+
+        if let Some(ref comment) = signature.comment {
+            doc.push(comment.to_string());
+        }
+
+        doc.push(
+            "Arguments are declared as `<name>: <type>`, or as `<name>: stream <type>` for \
+             streaming requests."
+                .to_string(),
+        );
+
+        // types which can be used in arguments.
+        let mut types = self
+            .built_ins
+            .iter()
+            .map(|t| format!("`{}`", t))
+            .collect::<Vec<_>>();
+
+        let root: Vec<String> = Vec::new();
+
+        for s in file.symbols.get(&root).into_iter().flat_map(|s| s.iter()) {
+            types.push(format!("`{}`", s.name));
+        }
+
+        let mut prefixes = file.prefixes.keys().collect::<Vec<_>>();
+        prefixes.sort();
+
+        for prefix in prefixes {
+            types.push(format!("`{}::`", prefix));
+        }
+
+        doc.push(format!("Known types: {}", types.join(", ")));
+
+        let parameters = signature
+            .arguments
+            .iter()
+            .map(|a| ty::ParameterInformation {
+                label: a.to_string(),
+                documentation: None,
+            })
+            .collect();
+
+        let information = ty::SignatureInformation {
+            label: signature.label.to_string(),
+            documentation: Some(ty::Documentation::MarkupContent(ty::MarkupContent {
+                kind: ty::MarkupKind::Markdown,
+                value: doc.join("\n\n"),
+            })),
+            parameters: Some(parameters),
+        };
+
+        *response = Some(ty::SignatureHelp {
+            signatures: vec![information],
+            active_signature: Some(0),
+            active_parameter: Some(signature.active_argument(&position) as u64),
+        });
+
+        Ok(())
+    }
+
     /// Handler for renaming
     fn text_document_rename(
         &self,
@@ -1451,7 +1816,7 @@ where
         };
 
         let workspace = workspace
-            .try_borrow()
+            .lock()
             .map_err(|_| "failed to access immutable workspace")?;
 
         let url = params.text_document.uri;
@@ -1520,22 +1885,6 @@ where
 
             edits
         }
-
-        // Setup a workspace edit which is only local to the specified URL.
-        fn local_edits(url: &Url, edits: Vec<ty::TextEdit>) -> ty::WorkspaceEdit {
-            let changes = vec![ty::TextDocumentEdit {
-                text_document: ty::VersionedTextDocumentIdentifier {
-                    uri: url.clone(),
-                    version: None,
-                },
-                edits: edits,
-            }];
-
-            ty::WorkspaceEdit {
-                document_changes: Some(changes),
-                ..ty::WorkspaceEdit::default()
-            }
-        }
     }
 
     /// Populate the goto definition response.
@@ -1552,7 +1901,7 @@ where
         };
 
         let workspace = workspace
-            .try_borrow()
+            .lock()
             .map_err(|_| "failed to access immutable workspace")?;
 
         let (file, value) = match workspace.find_jump(&url, params.position) {
@@ -1623,6 +1972,72 @@ where
     }
 }
 
+/// Convert diagnostics for the given source into diagnostics of the language server protocol.
+fn convert_diagnostics<'a, I>(source: &Source, diagnostics: I) -> Result<Vec<ty::Diagnostic>>
+where
+    I: IntoIterator<Item = &'a Diagnostic>,
+{
+    let mut out = Vec::new();
+
+    for d in diagnostics.into_iter() {
+        match *d {
+            core::Diagnostic::Error {
+                ref span,
+                ref message,
+                code,
+            } => {
+                let (start, end) = source.span_to_range(*span, Encoding::Utf16)?;
+                let range = convert_range((start, end));
+
+                let d = ty::Diagnostic {
+                    range: range,
+                    message: message.to_string(),
+                    severity: Some(ty::DiagnosticSeverity::Error),
+                    code: code.map(|code| ty::NumberOrString::String(code.id.to_string())),
+                    ..ty::Diagnostic::default()
+                };
+
+                out.push(d);
+            }
+            core::Diagnostic::Warning {
+                ref span,
+                ref message,
+            } => {
+                let (start, end) = source.span_to_range(*span, Encoding::Utf16)?;
+                let range = convert_range((start, end));
+
+                let d = ty::Diagnostic {
+                    range: range,
+                    message: message.to_string(),
+                    severity: Some(ty::DiagnosticSeverity::Warning),
+                    ..ty::Diagnostic::default()
+                };
+
+                out.push(d);
+            }
+            core::Diagnostic::Info {
+                ref span,
+                ref message,
+            } => {
+                let (start, end) = source.span_to_range(*span, Encoding::Utf16)?;
+                let range = convert_range((start, end));
+
+                let d = ty::Diagnostic {
+                    range: range,
+                    message: message.to_string(),
+                    severity: Some(ty::DiagnosticSeverity::Information),
+                    ..ty::Diagnostic::default()
+                };
+
+                out.push(d);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(out)
+}
+
 /// Convert an internal range into a language-server range.
 fn convert_range<R: Into<Range>>(range: R) -> ty::Range {
     let range = range.into();
@@ -1643,6 +2058,119 @@ fn convert_range<R: Into<Range>>(range: R) -> ty::Range {
     ty::Range { start, end }
 }
 
+/// Setup a workspace edit which is only local to the specified URL.
+fn local_edits(url: &Url, edits: Vec<ty::TextEdit>) -> ty::WorkspaceEdit {
+    let changes = vec![ty::TextDocumentEdit {
+        text_document: ty::VersionedTextDocumentIdentifier {
+            uri: url.clone(),
+            version: None,
+        },
+        edits: edits,
+    }];
+
+    ty::WorkspaceEdit {
+        document_changes: Some(changes),
+        ..ty::WorkspaceEdit::default()
+    }
+}
+
+/// Indexing of the workspace running in a background thread.
+#[derive(Clone)]
+struct Indexing {
+    /// Token used to report progress of, and to cancel indexing.
+    token: String,
+    /// Set when indexing should be cancelled.
+    cancelled: Arc<AtomicBool>,
+    /// Set when indexing has finished.
+    done: Arc<AtomicBool>,
+}
+
+impl Indexing {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn is_done(&self) -> bool {
+        self.done.load(Ordering::SeqCst)
+    }
+}
+
+/// Index the given workspace, and replace the shared workspace with it unless cancelled.
+fn index_workspace<W>(
+    channel: &Channel<W>,
+    shared: &Mutex<Workspace>,
+    mut workspace: Workspace,
+    indexing: &Indexing,
+) -> Result<()>
+where
+    W: Write,
+{
+    let result = workspace.reload_with(|current, total| {
+        if indexing.is_cancelled() {
+            return false;
+        }
+
+        let percentage = if total > 0 {
+            current * 100 / total
+        } else {
+            100
+        };
+
+        let report = channel.notification::<Progress>(ProgressParams {
+            token: indexing.token.clone(),
+            value: WorkDoneProgress::Report {
+                message: format!("{}/{} packages", current, total),
+                percentage: percentage as u32,
+            },
+        });
+
+        if let Err(e) = report {
+            error!("failed to report progress: {}", e.display());
+        }
+
+        true
+    });
+
+    let message = match result {
+        Ok(true) => {
+            let mut shared = shared
+                .lock()
+                .map_err(|_| "failed to access mutable workspace")?;
+
+            // edits cancel indexing while holding the lock, so unless cancelled the indexed
+            // workspace is up to date.
+            if indexing.is_cancelled() {
+                "cancelled"
+            } else {
+                *shared = workspace;
+                indexing.done.store(true, Ordering::SeqCst);
+                channel.send_workspace_diagnostics(&*shared)?;
+                "done"
+            }
+        }
+        Ok(false) => "cancelled",
+        Err(e) => {
+            error!("failed to reload workspace: {}", e.display());
+            "failed"
+        }
+    };
+
+    indexing.done.store(true, Ordering::SeqCst);
+
+    channel.notification::<Progress>(ProgressParams {
+        token: indexing.token.clone(),
+        value: WorkDoneProgress::End {
+            message: message.to_string(),
+        },
+    })?;
+
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub enum Expected {
     /// Feedback from project init.
@@ -1659,3 +2187,56 @@ impl ty::notification::Notification for OpenUrl {
 
     const METHOD: &'static str = "$/openUrl";
 }
+
+/// `window/workDoneProgress/create` request.
+pub enum WorkDoneProgressCreate {}
+
+impl ty::request::Request for WorkDoneProgressCreate {
+    type Params = WorkDoneProgressCreateParams;
+    type Result = ();
+
+    const METHOD: &'static str = "window/workDoneProgress/create";
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkDoneProgressCreateParams {
+    token: String,
+}
+
+/// `window/workDoneProgress/cancel` notification.
+#[derive(Debug, Deserialize)]
+pub struct WorkDoneProgressCancelParams {
+    token: String,
+}
+
+/// `$/progress` notification.
+pub enum Progress {}
+
+impl ty::notification::Notification for Progress {
+    type Params = ProgressParams;
+
+    const METHOD: &'static str = "$/progress";
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgressParams {
+    token: String,
+    value: WorkDoneProgress,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WorkDoneProgress {
+    Begin {
+        title: String,
+        cancellable: bool,
+        percentage: u32,
+    },
+    Report {
+        message: String,
+        percentage: u32,
+    },
+    End {
+        message: String,
+    },
+}
@@ -1,9 +1,9 @@
 //! A file that is loaded into a workspace.
 
 use core::errors::Result;
-use core::{Diagnostics, Encoding, Position, RpVersionedPackage, Source, Span};
-use models::{Completion, Jump, Prefix, Range, Reference, Rename, Symbol};
-use std::collections::HashMap;
+use core::{Diagnostics, Encoding, Position, RpPackage, RpVersionedPackage, Source, Span};
+use models::{Action, Completion, Jump, Prefix, Range, Reference, Rename, Signature, Symbol};
+use std::collections::{HashMap, HashSet};
 use triggers::Triggers;
 use url::Url;
 
@@ -19,6 +19,8 @@ pub struct LoadedFile {
     pub completion_triggers: Triggers<Completion>,
     /// Rename locations.
     pub rename_triggers: Triggers<Rename>,
+    /// Argument lists of endpoints that have signature help.
+    pub signature_triggers: Triggers<Signature>,
     /// Local reference triggers.
     pub reference_triggers: Triggers<Reference>,
     /// All the locations that a given prefix is present at.
@@ -28,6 +30,12 @@ pub struct LoadedFile {
     /// All prefixes that are in-scope for the file.
     /// These are defined in the use-declarations at the top of the file.
     pub prefixes: HashMap<String, Prefix>,
+    /// Prefixes which are used somewhere in the file.
+    pub used_prefixes: HashSet<String>,
+    /// Names referencing prefixes which are not declared in the file.
+    pub missing_prefixes: Vec<(String, Vec<String>, Span)>,
+    /// Code actions available in the file, and the range in which they apply.
+    pub actions: Vec<(Range, Action)>,
     /// Symbols present in the file.
     /// The key is the path that the symbol is located in.
     pub symbols: HashMap<Vec<String>, Vec<Symbol>>,
@@ -51,10 +59,14 @@ impl LoadedFile {
             jump_triggers: Triggers::new(),
             completion_triggers: Triggers::new(),
             rename_triggers: Triggers::new(),
+            signature_triggers: Triggers::new(),
             reference_triggers: Triggers::new(),
             prefix_ranges: HashMap::new(),
             implicit_prefixes: HashMap::new(),
             prefixes: HashMap::new(),
+            used_prefixes: HashSet::new(),
+            missing_prefixes: Vec::new(),
+            actions: Vec::new(),
             symbols: HashMap::new(),
             references: HashMap::new(),
             type_ranges: HashMap::new(),
@@ -168,6 +180,54 @@ impl LoadedFile {
         Ok(())
     }
 
+    /// Register a use declaration whose prefix is never used.
+    ///
+    /// This function does nothing if the loaded file is read-only.
+    pub fn register_unused_use(&mut self, prefix: &str, span: Span) -> Result<()> {
+        if self.diag.source.read_only {
+            return Ok(());
+        }
+
+        self.diag.info(span, format!("unused import: {}", prefix));
+
+        let range = self.range(span)?;
+
+        // remove everything up until the start of the next line.
+        let remove = Range {
+            start: range.start,
+            end: Position {
+                line: range.end.line + 1,
+                col: 0,
+            },
+        };
+
+        let action = Action::RemoveUse {
+            prefix: prefix.to_string(),
+            range: remove,
+        };
+
+        self.actions.push((range, action));
+        Ok(())
+    }
+
+    /// Register a code action to import the given package, to resolve a missing prefix.
+    ///
+    /// This function does nothing if the loaded file is read-only.
+    pub fn register_add_use(
+        &mut self,
+        span: Span,
+        package: RpPackage,
+        position: Position,
+    ) -> Result<()> {
+        if self.diag.source.read_only {
+            return Ok(());
+        }
+
+        let range = self.range(span)?;
+        self.actions.push((range, Action::AddUse { package, position }));
+        Ok(())
+    }
+
     /// Register a reference.
     pub fn register_reference(
         &mut self,
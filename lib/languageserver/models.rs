@@ -1,6 +1,7 @@
 //! Data models that are shared for the language server.
 
-use core::{Position, RpVersionedPackage};
+use attributes::AttributeTarget;
+use core::{Position, RpPackage, RpVersionedPackage};
 use std::collections::BTreeSet;
 use ty;
 use url::Url;
@@ -45,7 +46,7 @@ pub enum RenameResult<'a> {
     NotSupported,
 }
 
-/// Specifies a type completion.
+/// Specifies a completion.
 #[derive(Debug, Clone)]
 pub enum Completion {
     /// Completions for type from a different package.
@@ -58,6 +59,40 @@ pub enum Completion {
     Package { results: BTreeSet<String> },
     /// Any type, including primitive types.
     Any { suffix: Option<String> },
+    /// Names of attributes which are permitted at the given target.
+    Attribute { target: AttributeTarget },
+    /// Keys of the given attribute, like `method` in `#[http(method = "GET")]`.
+    AttributeKey { attribute: String },
+    /// Variants of the enum at the given path, like `Red` in `Color::Red`.
+    EnumVariant {
+        prefix: Option<String>,
+        path: Vec<String>,
+        suffix: Option<String>,
+    },
+}
+
+/// The signature of an endpoint, used for signature help.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    /// The whole signature, like `get_entry(id: string) -> Entry`.
+    pub label: String,
+    /// Markdown documentation comment of the endpoint.
+    pub comment: Option<String>,
+    /// Each argument of the endpoint, like `id: string`.
+    pub arguments: Vec<String>,
+    /// Where each argument is located.
+    pub argument_ranges: Vec<Range>,
+}
+
+impl Signature {
+    /// Find the index of the argument at the given position.
+    pub fn active_argument(&self, position: &Position) -> usize {
+        self.argument_ranges
+            .iter()
+            .take_while(|r| r.start <= *position)
+            .count()
+            .saturating_sub(1)
+    }
 }
 
 /// Specifies a jump
@@ -74,6 +109,25 @@ pub enum Jump {
     Prefix { prefix: String },
 }
 
+/// Specifies a code action.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Remove a use declaration whose prefix is never used.
+    RemoveUse {
+        /// The unused prefix.
+        prefix: String,
+        /// The range of the use declaration to remove.
+        range: Range,
+    },
+    /// Add a use declaration for a package that declares a name with a missing prefix.
+    AddUse {
+        /// The package to import.
+        package: RpPackage,
+        /// The position at which the use declaration should be inserted.
+        position: Position,
+    },
+}
+
 /// Specifies a reference to some type.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Reference {
@@ -130,10 +184,29 @@ pub struct Symbol {
     pub url: Url,
     /// Range where the symbol is located.
     pub range: Range,
+    /// Range of the whole declaration of the symbol.
+    pub full_range: Range,
     /// The name of the symbol.
     pub name: String,
+    /// The kind of the symbol.
+    pub kind: ty::SymbolKind,
     /// Markdown documentation comment.
     pub comment: Option<String>,
+    /// Members of the symbol, like fields and endpoints.
+    pub members: Vec<Member>,
+}
+
+/// Information about a member of a symbol.
+#[derive(Debug, Clone)]
+pub struct Member {
+    /// The name of the member.
+    pub name: String,
+    /// The kind of the member.
+    pub kind: ty::SymbolKind,
+    /// Range where the name of the member is located.
+    pub range: Range,
+    /// Range of the whole member.
+    pub full_range: Range,
 }
 
 impl Symbol {
@@ -1,32 +1,37 @@
 //! A dynamically compiled and updated environment.
 
 use ast;
+use attributes::AttributeTarget;
 use core::errors::{Error, Result};
 use core::{
-    self, Encoding, Filesystem, Handle, Loc, Reported, Resolved, Resolver, RpPackage,
-    RpRequiredPackage, RpVersionedPackage, Source,
+    self, Diagnostics, Encoding, Filesystem, Handle, Loc, Position, Reported, Resolved, Resolver,
+    RpPackage, RpRequiredPackage, RpVersionedPackage, Source, Span,
 };
 use env;
 use loaded_file::LoadedFile;
 use manifest;
-use models::{Completion, Jump, Prefix, Range, Rename, RenameResult, Symbol};
+use models::{
+    Action, Completion, Jump, Member, Prefix, Range, Rename, RenameResult, Signature, Symbol,
+};
 use parser;
 use repository::{path_to_package, Packages, EXT};
 use std::collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
-use std::fs::File;
 use std::io::Read;
+use std::mem;
 use std::path::{Path, PathBuf};
 use ty;
 use url::Url;
 
 pub struct Workspace {
-    pub filesystem: Box<Filesystem>,
+    pub filesystem: Box<Filesystem + Send>,
     /// Path of the workspace.
     pub root_path: PathBuf,
     /// Path to manifest.
     pub manifest_path: PathBuf,
     /// Error encountered when loading manifest.
     pub manifest_error: Option<Error>,
+    /// Diagnostics from validating the manifest.
+    pub manifest_diagnostics: Option<Diagnostics>,
     /// Packages which have been loaded through project.
     pub packages: HashMap<RpVersionedPackage, Url>,
     /// Versioned packages that have been looked up.
@@ -43,18 +48,23 @@ pub struct Workspace {
     pub rev_dep: HashMap<RpVersionedPackage, HashSet<RpVersionedPackage>>,
     /// Sources queued up to build.
     pub sources: Vec<manifest::Source>,
+    /// Sources resolved from the manifest during the last reload.
+    pub roots: Vec<manifest::Source>,
     /// Currently loaded manifest.
     pub manifest: Option<manifest::Manifest>,
+    /// Paths declared by sources which have been parsed as import candidates.
+    declared_paths: HashMap<Url, HashSet<Vec<String>>>,
 }
 
 impl Workspace {
     /// Create a new workspace from the given path.
-    pub fn new<P: AsRef<Path>>(filesystem: Box<Filesystem>, root_path: P) -> Self {
+    pub fn new<P: AsRef<Path>>(filesystem: Box<Filesystem + Send>, root_path: P) -> Self {
         Self {
             filesystem,
             root_path: root_path.as_ref().to_owned(),
             manifest_path: root_path.as_ref().join(env::MANIFEST_NAME),
             manifest_error: None,
+            manifest_diagnostics: None,
             packages: HashMap::new(),
             lookup_required: HashMap::new(),
             lookup_versioned: HashSet::new(),
@@ -63,7 +73,9 @@ impl Workspace {
             reporter: Vec::new(),
             rev_dep: HashMap::new(),
             sources: Vec::new(),
+            roots: Vec::new(),
             manifest: None,
+            declared_paths: HashMap::new(),
         }
     }
 
@@ -83,7 +95,7 @@ impl Workspace {
 
     /// Initialize the current project.
     pub fn initialize(&mut self, handle: &Handle) -> Result<()> {
-        env::initialize(handle)?;
+        env::initialize(handle, env::InitializeOptions::default())?;
         Ok(())
     }
 
@@ -96,7 +108,7 @@ impl Workspace {
 
         let mut packages = BTreeMap::new();
 
-        for p in &manifest.paths {
+        for p in &env::paths(manifest) {
             if !p.is_dir() {
                 continue;
             }
@@ -150,10 +162,10 @@ impl Workspace {
         let mut manifest = manifest::Manifest::default();
         let url = self.manifest_url()?;
 
-        let reader: Box<Read> = {
+        let source = {
             match self.open_files.get(&url) {
                 // use the rope.
-                Some(source) => source.read()?,
+                Some(source) => source.clone(),
                 // open the underlying file.
                 None => {
                     if !self.manifest_path.is_file() {
@@ -164,22 +176,18 @@ impl Workspace {
                         return Ok(None);
                     }
 
-                    let file = File::open(&self.manifest_path).map_err(|e| {
-                        format!(
-                            "failed to open manifest: {}: {}",
-                            self.manifest_path.display(),
-                            e
-                        )
-                    })?;
-
-                    Box::new(file)
+                    Source::from_path(&self.manifest_path)
                 }
             }
         };
 
         manifest.path = Some(self.manifest_path.to_owned());
 
-        match manifest.from_yaml(reader, env::convert_lang) {
+        let mut diag = Diagnostics::new(source);
+        let result = manifest.from_source(&mut diag, env::convert_lang);
+        self.manifest_diagnostics = Some(diag);
+
+        match result {
             Err(e) => {
                 self.manifest_error = Some(e);
                 return Ok(None);
@@ -192,6 +200,19 @@ impl Workspace {
         Ok(Some(manifest))
     }
 
+    /// Get the most recent version of the given source, which is the edited version if the file
+    /// is open.
+    fn current_source(&self, source: &Source) -> Source {
+        if let Some(url) = source.url() {
+            if let Some(source) = self.open_files.get(&url) {
+                return source.clone();
+            }
+        }
+
+        source.clone()
+    }
+
+    /// Unload the given package, and queue it up to be processed again.
     fn dirty_package(&mut self, package: RpVersionedPackage) -> Result<()> {
         debug!("dirty: {}", package);
 
@@ -207,20 +228,21 @@ impl Workspace {
 
         self.lookup_versioned.remove(&package);
 
-        self.sources.push(manifest::Source {
-            package,
-            source: file.diag.source,
-        });
+        // imports are recorded again when the package is processed.
+        for importers in self.rev_dep.values_mut() {
+            importers.remove(&package);
+        }
 
+        let source = self.current_source(&file.diag.source);
+        self.sources.push(manifest::Source { package, source });
         Ok(())
     }
 
     /// Mark the given URL as dirty.
+    ///
+    /// The file and every file which imports it will be processed again on the next `update`.
     pub fn dirty(&mut self, url: &Url) -> Result<()> {
-        // TODO: enable dirty tracking when ready!
-        if true {
-            return Ok(());
-        }
+        self.declared_paths.remove(url);
 
         let package = match self.files.get(url) {
             Some(file) => file.package.clone(),
@@ -228,7 +250,7 @@ impl Workspace {
         };
 
         // by virtue of the language, we only care about 1 degree of separation.
-        if let Some(packages) = self.rev_dep.remove(&package) {
+        if let Some(packages) = self.rev_dep.get(&package).cloned() {
             for package in packages {
                 self.dirty_package(package)?;
             }
@@ -238,11 +260,76 @@ impl Workspace {
         Ok(())
     }
 
+    /// Process all files which have been marked as dirty, and compile the project again.
+    ///
+    /// Falls back to reloading the whole workspace if nothing is known to be dirty.
+    pub fn update(&mut self) -> Result<()> {
+        if self.sources.is_empty() || self.roots.is_empty() {
+            return self.reload();
+        }
+
+        let manifest = match self.open_manifest()? {
+            Some(manifest) => manifest,
+            None => return Ok(()),
+        };
+
+        let open_resolver = self.open_files_resolver(&manifest)?;
+        let mut resolver = env::resolver_with_extra(&manifest, open_resolver)?;
+
+        let dirty = mem::replace(&mut self.sources, Vec::new());
+
+        for s in dirty {
+            let manifest::Source { package, source } = s;
+
+            debug!("updating `{}` from source {}", package, source);
+
+            if let Err(e) = self.process_package(resolver.as_mut(), &package, None, source) {
+                error!("failed to process: {}: {}", package, e.display());
+
+                if let Some(backtrace) = e.backtrace() {
+                    error!("{:?}", backtrace);
+                }
+            }
+        }
+
+        let sources = self
+            .roots
+            .iter()
+            .map(|s| manifest::Source {
+                package: s.package.clone(),
+                source: self.current_source(&s.source),
+            })
+            .collect();
+
+        if let Err(e) = self.try_compile(resolver.as_mut(), manifest, sources) {
+            error!("failed to compile: {}", e.display());
+
+            if let Some(backtrace) = e.backtrace() {
+                error!("{:?}", backtrace);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Reload the workspace.
     pub fn reload(&mut self) -> Result<()> {
+        self.reload_with(|_, _| true)?;
+        Ok(())
+    }
+
+    /// Reload the workspace, reporting progress as packages are processed.
+    ///
+    /// `progress` is called with the number of processed packages and the total number of
+    /// packages before each package is processed, and before the project is compiled.
+    /// If it returns `false`, reloading is cancelled and this returns `false`.
+    pub fn reload_with<P>(&mut self, mut progress: P) -> Result<bool>
+    where
+        P: FnMut(usize, usize) -> bool,
+    {
         let manifest = match self.open_manifest()? {
             Some(manifest) => manifest,
-            None => return Ok(()),
+            None => return Ok(true),
         };
 
         let open_resolver = self.open_files_resolver(&manifest)?;
@@ -254,7 +341,7 @@ impl Workspace {
                 Ok(sources) => sources,
                 Err(e) => {
                     self.manifest_error = Some(e);
-                    return Ok(());
+                    return Ok(true);
                 }
             };
 
@@ -262,10 +349,17 @@ impl Workspace {
             self.lookup_required.clear();
             self.lookup_versioned.clear();
             self.files.clear();
+            self.rev_dep.clear();
+            self.sources.clear();
+            self.declared_paths.clear();
             sources
         };
 
-        for s in &sources {
+        for (index, s) in sources.iter().enumerate() {
+            if !progress(index, sources.len()) {
+                return Ok(false);
+            }
+
             let manifest::Source {
                 ref package,
                 ref source,
@@ -283,6 +377,18 @@ impl Workspace {
             }
         }
 
+        if !progress(sources.len(), sources.len()) {
+            return Ok(false);
+        }
+
+        self.roots = sources
+            .iter()
+            .map(|s| manifest::Source {
+                package: s.package.clone(),
+                source: s.source.clone(),
+            })
+            .collect();
+
         if let Err(e) = self.try_compile(resolver.as_mut(), manifest, sources) {
             error!("failed to compile: {}", e.display());
 
@@ -291,7 +397,7 @@ impl Workspace {
             }
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Try to compile the current environment.
@@ -305,7 +411,13 @@ impl Workspace {
         let package_prefix = manifest.package_prefix.clone();
 
         self.reporter.clear();
-        let mut session = lang.into_session(package_prefix, &mut self.reporter, resolver)?;
+        let session = lang.into_session(package_prefix, &mut self.reporter, resolver)?;
+
+        let mut session = manifest
+            .naming
+            .apply(session)
+            .with_scalars(manifest.scalars.clone())
+            .with_numbers_as_strings(manifest.numbers_as_strings);
 
         for s in &sources {
             let manifest::Source {
@@ -352,6 +464,14 @@ impl Workspace {
         imported_from: Option<&RpVersionedPackage>,
         package: &RpRequiredPackage,
     ) -> Result<Option<(RpVersionedPackage, bool)>> {
+        if let Some(found) = self.lookup_required.get(package).cloned() {
+            if let Some((ref versioned, _)) = found {
+                self.add_rev_dep(versioned, imported_from);
+            }
+
+            return Ok(found);
+        }
+
         let (versioned, source) = {
             let entry = match self.lookup_required.entry(package.clone()) {
                 hash_map::Entry::Occupied(e) => return Ok(e.get().clone()),
@@ -375,10 +495,25 @@ impl Workspace {
         };
 
         let read_only = source.read_only;
+        self.add_rev_dep(&versioned, imported_from);
         self.process_package(resolver, &versioned, imported_from, source)?;
         Ok(Some((versioned, read_only)))
     }
 
+    /// Record that the given package is imported from another package.
+    fn add_rev_dep(
+        &mut self,
+        versioned: &RpVersionedPackage,
+        imported_from: Option<&RpVersionedPackage>,
+    ) {
+        if let Some(imported_from) = imported_from.cloned() {
+            self.rev_dep
+                .entry(versioned.clone())
+                .or_insert_with(HashSet::new)
+                .insert(imported_from);
+        }
+    }
+
     /// Process the given required package request.
     ///
     /// If package has been found, returns a `(package, bool)` tuple.
@@ -404,13 +539,6 @@ impl Workspace {
                 .unwrap_or_else(|| String::from("*root*"))
         );
 
-        if let Some(imported_from) = imported_from.cloned() {
-            self.rev_dep
-                .entry(versioned.clone())
-                .or_insert_with(HashSet::new)
-                .insert(imported_from);
-        }
-
         let url = match source.url() {
             Some(url) => url,
             None => {
@@ -444,13 +572,16 @@ impl Workspace {
             content
         };
 
-        let file = match parser::parse(&mut loaded.diag, content.as_str()) {
+        let file = match parser::parse_partial(&mut loaded.diag, content.as_str()) {
             Ok(file) => file,
             Err(()) => {
                 return Ok(());
             }
         };
 
+        // prefixes declared by use declarations, and the span of the declaration.
+        let mut uses = Vec::new();
+
         for u in &file.uses {
             let range = match u.range {
                 Some(ref range) => match core::Range::parse(range.as_str()) {
@@ -509,6 +640,7 @@ impl Workspace {
 
             if let Some((prefix, prefix_span)) = prefix {
                 let prefix = prefix.to_string();
+                uses.push((prefix.clone(), Loc::span(u)));
 
                 if let Some((package, read_only)) = package {
                     // register a jump for the last part of the package, if it is present.
@@ -538,6 +670,14 @@ impl Workspace {
             }
         }
 
+        self.process_attributes(
+            &vec![],
+            loaded,
+            content.as_str(),
+            AttributeTarget::File,
+            &file.attributes,
+        )?;
+
         let mut queue = VecDeque::new();
 
         queue.extend(file.decls.iter().map(|d| (vec![], d)));
@@ -570,6 +710,8 @@ impl Workspace {
 
             let (start, end) = loaded.diag.source.span_to_range(span, Encoding::Utf16)?;
             let range = Range { start, end };
+            let full_range = loaded.range(decl.span())?;
+            let (kind, members) = decl_members(loaded, decl)?;
 
             loaded
                 .symbols
@@ -578,11 +720,61 @@ impl Workspace {
                 .push(Symbol {
                     url: loaded.url.clone(),
                     range,
+                    full_range,
                     name: name.to_string(),
+                    kind,
                     comment,
+                    members,
                 });
         }
 
+        // position at which new use declarations are inserted.
+        let position = match file.uses.last() {
+            Some(u) => {
+                let range = loaded.range(Loc::span(u))?;
+
+                Position {
+                    line: range.end.line + 1,
+                    col: 0,
+                }
+            }
+            None => Position { line: 0, col: 0 },
+        };
+
+        for (prefix, span) in &uses {
+            if !loaded.used_prefixes.contains(prefix) {
+                loaded.register_unused_use(prefix, *span)?;
+            }
+        }
+
+        if !loaded.diag.source.read_only {
+            let missing = mem::replace(&mut loaded.missing_prefixes, Vec::new());
+            let mut candidates = HashMap::new();
+
+            for (prefix, path, span) in missing {
+                // prefix is declared, but the package could not be resolved.
+                if uses.iter().any(|&(ref p, _)| *p == prefix) {
+                    continue;
+                }
+
+                let packages = match candidates.entry((prefix, path)) {
+                    hash_map::Entry::Occupied(e) => e.into_mut(),
+                    hash_map::Entry::Vacant(e) => {
+                        let packages = {
+                            let &(ref prefix, ref path) = e.key();
+                            self.import_candidates(resolver, &versioned.package, prefix, path)?
+                        };
+
+                        e.insert(packages)
+                    }
+                };
+
+                for package in packages.iter() {
+                    loaded.register_add_use(span, package.clone(), position)?;
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -634,7 +826,271 @@ impl Workspace {
                 if let Some(response) = e.response.as_ref() {
                     self.process_ty(current, loaded, content, response.ty())?;
                 }
+
+                for error in &e.errors {
+                    self.process_ty(current, loaded, content, &error.ty)?;
+                }
+            },
+            Union(ref union) => for v in &union.variants {
+                self.process_ty(current, loaded, content, v)?;
+            },
+            Newtype(ref newtype) => {
+                self.process_ty(current, loaded, content, &newtype.ty)?;
+            }
+        }
+
+        self.process_attributes(
+            current,
+            loaded,
+            content,
+            AttributeTarget::Decl,
+            decl.attributes(),
+        )?;
+
+        match *decl {
+            Type(ref ty) => {
+                self.process_member_attributes(current, loaded, content, &ty.members)?;
+            }
+            Tuple(ref tuple) => {
+                self.process_member_attributes(current, loaded, content, &tuple.members)?;
+            }
+            Interface(ref interface) => {
+                self.process_member_attributes(current, loaded, content, &interface.members)?;
+
+                for s in &interface.sub_types {
+                    self.process_attributes(
+                        current,
+                        loaded,
+                        content,
+                        AttributeTarget::Decl,
+                        &s.attributes,
+                    )?;
+
+                    self.process_member_attributes(current, loaded, content, &s.members)?;
+                }
+            }
+            Enum(ref en) => {
+                for v in &en.variants {
+                    self.process_attributes(
+                        current,
+                        loaded,
+                        content,
+                        AttributeTarget::Decl,
+                        &v.attributes,
+                    )?;
+                }
+
+                for m in &en.members {
+                    let ast::EnumMember::Code(ref code) = *m;
+                    let code = Loc::borrow(code);
+
+                    self.process_attributes(
+                        current,
+                        loaded,
+                        content,
+                        AttributeTarget::Code,
+                        &code.attributes,
+                    )?;
+                }
+            }
+            Service(ref service) => for m in &service.members {
+                if let ast::ServiceMember::Endpoint(ref e) = *m {
+                    self.process_attributes(
+                        current,
+                        loaded,
+                        content,
+                        AttributeTarget::Endpoint,
+                        &e.attributes,
+                    )?;
+
+                    self.process_signature(loaded, content, e)?;
+                }
+            },
+            Union(_) | Newtype(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Register signature help for the argument list of an endpoint.
+    fn process_signature<'input>(
+        &mut self,
+        loaded: &mut LoadedFile,
+        content: &str,
+        endpoint: &ast::Item<'input, ast::Endpoint<'input>>,
+    ) -> Result<()> {
+        let id_span = Loc::span(&endpoint.id);
+        let item_span = Loc::span(&endpoint.item);
+
+        let mut arguments = Vec::new();
+        let mut argument_ranges = Vec::new();
+
+        for a in &endpoint.arguments {
+            let start = Loc::span(&a.ident).start;
+            let end = Loc::span(&a.channel).end;
+
+            arguments.push(content[start..end].to_string());
+            argument_ranges.push(loaded.range(Span::from((start, end)))?);
+        }
+
+        let mut label = format!("{}({})", endpoint.id, arguments.join(", "));
+
+        // signature help is available from the name of the endpoint up until its response.
+        let end = match endpoint.response {
+            Some(ref response) => {
+                let span = Loc::span(response);
+                label.push_str(&format!(" -> {}", &content[span.start..span.end]));
+                span.start
+            }
+            None => item_span.end,
+        };
+
+        let comment = if !endpoint.comment.is_empty() {
+            Some(
+                endpoint
+                    .comment
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        } else {
+            None
+        };
+
+        let range = loaded.range(Span::from((id_span.end, end)))?;
+
+        loaded.signature_triggers.insert(
+            range,
+            Signature {
+                label,
+                comment,
+                arguments,
+                argument_ranges,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Process the attributes of fields and code blocks.
+    fn process_member_attributes<'input>(
+        &mut self,
+        current: &Vec<String>,
+        loaded: &mut LoadedFile,
+        content: &str,
+        members: &[ast::TypeMember<'input>],
+    ) -> Result<()> {
+        for m in members {
+            match *m {
+                ast::TypeMember::Field(ref f) => {
+                    self.process_attributes(
+                        current,
+                        loaded,
+                        content,
+                        AttributeTarget::Field,
+                        &f.attributes,
+                    )?;
+                }
+                ast::TypeMember::Code(ref code) => {
+                    let code = Loc::borrow(code);
+
+                    self.process_attributes(
+                        current,
+                        loaded,
+                        content,
+                        AttributeTarget::Code,
+                        &code.attributes,
+                    )?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Register completions for attribute names, their keys, and their values.
+    fn process_attributes<'input>(
+        &mut self,
+        current: &Vec<String>,
+        loaded: &mut LoadedFile,
+        content: &str,
+        target: AttributeTarget,
+        attributes: &[Loc<ast::Attribute<'input>>],
+    ) -> Result<()> {
+        for attribute in attributes {
+            let (name, items) = match *Loc::borrow(attribute) {
+                ast::Attribute::Word(ref name) => (name, None),
+                ast::Attribute::List(ref name, ref items) => (name, Some(items)),
+            };
+
+            let range = loaded.range(Loc::span(name))?;
+            loaded
+                .completion_triggers
+                .insert(range, Completion::Attribute { target });
+
+            for item in items.into_iter().flat_map(|items| items.iter()) {
+                match *item {
+                    ast::AttributeItem::Word(ref value) => {
+                        self.process_value(current, loaded, content, value)?;
+                    }
+                    ast::AttributeItem::NameValue {
+                        name: ref key,
+                        ref value,
+                    } => {
+                        let range = loaded.range(Loc::span(key))?;
+
+                        loaded.completion_triggers.insert(
+                            range,
+                            Completion::AttributeKey {
+                                attribute: Loc::borrow(name).to_string(),
+                            },
+                        );
+
+                        self.process_value(current, loaded, content, value)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Register completions for names used as values, which complete the variants of enums.
+    fn process_value<'input>(
+        &mut self,
+        current: &Vec<String>,
+        loaded: &mut LoadedFile,
+        content: &str,
+        value: &Loc<ast::Value<'input>>,
+    ) -> Result<()> {
+        match *Loc::borrow(value) {
+            ast::Value::Array(ref values) => for v in values {
+                self.process_value(current, loaded, content, v)?;
             },
+            ast::Value::Name(ref name) => {
+                let span = Loc::span(name);
+                let range = loaded.range(span)?;
+
+                let content = &content[span.start..span.end];
+
+                let completion = match self.type_completion(current, content)? {
+                    Completion::Absolute {
+                        prefix,
+                        path,
+                        suffix,
+                    } => Completion::EnumVariant {
+                        prefix,
+                        path,
+                        suffix,
+                    },
+                    completion => completion,
+                };
+
+                loaded.completion_triggers.insert(range, completion);
+            }
+            _ => {}
         }
 
         Ok(())
@@ -657,6 +1113,11 @@ impl Workspace {
                 self.process_ty(current, loaded, content, key.as_ref())?;
                 self.process_ty(current, loaded, content, value.as_ref())?;
             }
+            ast::Type::Generic { ref arguments, .. } => {
+                for argument in arguments {
+                    self.process_ty(current, loaded, content, argument)?;
+                }
+            }
             ref ty => {
                 // load jump-to definitions
                 if let ast::Type::Name { ref name } = *ty {
@@ -726,6 +1187,8 @@ impl Workspace {
                 if let Some(ref prefix) = *prefix {
                     let (prefix, span) = Loc::borrow_pair(prefix);
 
+                    loaded.used_prefixes.insert(prefix.to_string());
+
                     let range = loaded.range(span)?;
 
                     // register prefix rename.
@@ -750,6 +1213,13 @@ impl Workspace {
                     None => Some(loaded.package.clone()),
                 };
 
+                if let (&Some(ref prefix), None) = (prefix, package.as_ref()) {
+                    let (prefix, span) = Loc::borrow_pair(prefix);
+                    let path = path.iter().map(|p| p.to_string()).collect();
+
+                    loaded.missing_prefixes.push((prefix.to_string(), path, span));
+                }
+
                 let prefix = prefix.as_ref().map(|p| p.to_string());
 
                 for p in path {
@@ -820,6 +1290,93 @@ impl Workspace {
         Ok(Completion::Package { results })
     }
 
+    /// Find all packages which can be imported using the given prefix, and which declare the
+    /// given path.
+    ///
+    /// Only packages named after the prefix are looked up, either at the root or next to
+    /// `current` or one of its parents. The paths declared by every candidate are cached until it
+    /// changes.
+    fn import_candidates(
+        &mut self,
+        resolver: &mut Resolver,
+        current: &RpPackage,
+        prefix: &str,
+        path: &[String],
+    ) -> Result<Vec<RpPackage>> {
+        let mut parents = vec![RpPackage::empty()];
+
+        for part in current.parts() {
+            let parent = parents[parents.len() - 1].clone().join_part(part);
+            parents.push(parent);
+        }
+
+        let mut out = Vec::new();
+
+        for parent in parents {
+            let package = parent.join_part(prefix);
+
+            for r in resolver.resolve_by_prefix(&package)? {
+                // NB: sub-packages are also resolved.
+                if r.package.package != package {
+                    continue;
+                }
+
+                if !self.declares(&r.source, path)? {
+                    continue;
+                }
+
+                out.push(r.package.package);
+            }
+        }
+
+        out.sort();
+        out.dedup();
+        Ok(out)
+    }
+
+    /// Test if the given source declares the given path.
+    fn declares(&mut self, source: &Source, path: &[String]) -> Result<bool> {
+        let url = match source.url() {
+            Some(url) => url,
+            None => return Ok(declared_paths(source)?.contains(path)),
+        };
+
+        if !self.declared_paths.contains_key(&url) {
+            let declared = declared_paths(source)?;
+            self.declared_paths.insert(url.clone(), declared);
+        }
+
+        return Ok(self.declared_paths[&url].contains(path));
+
+        /// Parse all paths declared by the given source.
+        fn declared_paths(source: &Source) -> Result<HashSet<Vec<String>>> {
+            let mut content = String::new();
+            source.read()?.read_to_string(&mut content)?;
+
+            let mut diag = Diagnostics::new(source.clone());
+            let mut declared = HashSet::new();
+
+            let file = match parser::parse_partial(&mut diag, content.as_str()) {
+                Ok(file) => file,
+                Err(()) => return Ok(declared),
+            };
+
+            let mut queue = file
+                .decls
+                .iter()
+                .map(|d| (vec![], d))
+                .collect::<VecDeque<_>>();
+
+            while let Some((mut path, decl)) = queue.pop_front() {
+                path.push(decl.name().to_string());
+                queue.extend(decl.decls().map(|d| (path.clone(), d)));
+                declared.insert(path);
+            }
+
+            Ok(declared)
+        }
+    }
+
     /// Figure out the kind of completion to support.
     fn type_completion(&self, current: &Vec<String>, content: &str) -> Result<Completion> {
         let mut it = content.split("::").peekable();
@@ -888,6 +1445,24 @@ impl Workspace {
         None
     }
 
+    /// Find the signature of the endpoint at the given position.
+    pub fn find_signature(
+        &self,
+        url: &Url,
+        position: ty::Position,
+    ) -> Option<(&LoadedFile, &Signature)> {
+        let file = match self.file(url) {
+            Some(file) => file,
+            None => return None,
+        };
+
+        if let Some(value) = file.signature_triggers.find(position) {
+            return Some((file, value));
+        }
+
+        None
+    }
+
     /// Find the associated jump.
     pub fn find_jump(&self, url: &Url, position: ty::Position) -> Option<(&LoadedFile, &Jump)> {
         let file = match self.file(url) {
@@ -977,6 +1552,31 @@ impl Workspace {
         }
     }
 
+    /// Find all code actions which apply to the given range.
+    pub fn find_actions(&self, url: &Url, range: ty::Range) -> Vec<&Action> {
+        let file = match self.file(url) {
+            Some(file) => file,
+            None => return vec![],
+        };
+
+        let start = Position {
+            line: range.start.line as usize,
+            col: range.start.character as usize,
+        };
+
+        let end = Position {
+            line: range.end.line as usize,
+            col: range.end.character as usize,
+        };
+
+        file
+            .actions
+            .iter()
+            .filter(|&&(ref r, _)| r.start <= end && start <= r.end)
+            .map(|&(_, ref action)| action)
+            .collect()
+    }
+
     /// Find out if there is a reference in the given location.
     pub fn find_reference<'a>(
         &'a self,
@@ -1027,6 +1627,106 @@ fn relative<'a>(from: &Path, to: &'a Path) -> Option<&'a Path> {
     Some(t.as_path())
 }
 
+/// Get the symbol kind and all members of the given declaration.
+fn decl_members<'input>(
+    loaded: &LoadedFile,
+    decl: &ast::Decl<'input>,
+) -> Result<(ty::SymbolKind, Vec<Member>)> {
+    use ast::Decl::*;
+
+    let mut members = Vec::new();
+
+    let kind = match *decl {
+        Type(ref ty) => {
+            type_members(loaded, &mut members, &ty.members)?;
+            ty::SymbolKind::Class
+        }
+        Tuple(ref tuple) => {
+            type_members(loaded, &mut members, &tuple.members)?;
+            ty::SymbolKind::Struct
+        }
+        Interface(ref interface) => {
+            type_members(loaded, &mut members, &interface.members)?;
+
+            for s in &interface.sub_types {
+                let name = Loc::borrow(&s.name).to_string();
+                let range = loaded.range(Loc::span(&s.name))?;
+                let full_range = loaded.range(Loc::span(&s.item))?;
+
+                members.push(Member {
+                    name,
+                    kind: ty::SymbolKind::Class,
+                    range,
+                    full_range,
+                });
+            }
+
+            ty::SymbolKind::Interface
+        }
+        Enum(ref en) => {
+            for v in &en.variants {
+                let name = Loc::borrow(&v.name).to_string();
+                let range = loaded.range(Loc::span(&v.name))?;
+                let full_range = loaded.range(Loc::span(&v.item))?;
+
+                members.push(Member {
+                    name,
+                    kind: ty::SymbolKind::EnumMember,
+                    range,
+                    full_range,
+                });
+            }
+
+            ty::SymbolKind::Enum
+        }
+        Service(ref service) => {
+            for m in &service.members {
+                if let ast::ServiceMember::Endpoint(ref e) = *m {
+                    let name = Loc::borrow(&e.id).to_string();
+                    let range = loaded.range(Loc::span(&e.id))?;
+                    let full_range = loaded.range(Loc::span(&e.item))?;
+
+                    members.push(Member {
+                        name,
+                        kind: ty::SymbolKind::Method,
+                        range,
+                        full_range,
+                    });
+                }
+            }
+
+            ty::SymbolKind::Interface
+        }
+        Union(_) => ty::SymbolKind::Struct,
+        Newtype(_) => ty::SymbolKind::Struct,
+    };
+
+    return Ok((kind, members));
+
+    /// Collect all fields in the given type members.
+    fn type_members<'input>(
+        loaded: &LoadedFile,
+        members: &mut Vec<Member>,
+        type_members: &[ast::TypeMember<'input>],
+    ) -> Result<()> {
+        for m in type_members {
+            if let ast::TypeMember::Field(ref f) = *m {
+                // NB: fields do not have a separate span for their name.
+                let range = loaded.range(Loc::span(&f.item))?;
+
+                members.push(Member {
+                    name: f.name.to_string(),
+                    kind: ty::SymbolKind::Field,
+                    range,
+                    full_range: range,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::relative;
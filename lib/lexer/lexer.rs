@@ -36,13 +36,21 @@ pub fn match_keyword(content: &str) -> Option<Token> {
         "double" => Double,
         "i32" => I32,
         "i64" => I64,
+        "i128" => I128,
         "u32" => U32,
         "u64" => U64,
+        "u128" => U128,
+        "decimal" => Decimal,
         "boolean" => Boolean,
         "string" => String,
         "datetime" => Datetime,
+        "date" => Date,
+        "time" => Time,
+        "duration" => Duration,
         "bytes" => Bytes,
         "stream" => Stream,
+        "reserved" => Reserved,
+        "throws" => Throws,
         _ => return None,
     };
 
@@ -457,6 +465,10 @@ impl<'input> Lexer<'input> {
                     '#' => Token::Hash,
                     '!' => Token::Bang,
                     '=' => Token::Equal,
+                    '<' => Token::LeftAngle,
+                    '>' => Token::RightAngle,
+                    '|' => Token::Pipe,
+                    '@' => Token::At,
                     '_' | 'a'...'z' => return Some(self.identifier(start)),
                     'A'...'Z' => return Some(self.type_identifier(start)),
                     '"' => return Some(self.string(start)),
@@ -578,6 +590,18 @@ pub mod tests {
         assert_eq!(expected, tokenize("foo::Bar.Baz").unwrap());
     }
 
+    #[test]
+    pub fn test_throws() {
+        let expected = vec![
+            (0, Throws, 6),
+            (7, TypeIdentifier("NotFound".into()), 15),
+            (16, At, 17),
+            (18, Number(404.into()), 21),
+        ];
+
+        assert_eq!(expected, tokenize("throws NotFound @ 404").unwrap());
+    }
+
     #[test]
     pub fn test_strings() {
         let expected = vec![(0, QuotedString("foo\nbar".to_owned()), 10)];
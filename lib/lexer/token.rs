@@ -24,6 +24,10 @@ pub enum Token<'input> {
     Hash,
     Bang,
     RightArrow,
+    LeftAngle,
+    RightAngle,
+    Pipe,
+    At,
     CodeOpen,
     CodeClose,
     CodeContent(Cow<'input, str>),
@@ -33,20 +37,28 @@ pub enum Token<'input> {
     As,
     Boolean,
     Bytes,
+    Date,
     Datetime,
+    Decimal,
+    Duration,
     Enum,
     Float,
     Double,
     I32,
     I64,
+    I128,
     Interface,
+    Reserved,
     Service,
     Stream,
     String,
+    Throws,
+    Time,
     Tuple,
     Type,
     U32,
     U64,
+    U128,
     Use,
 }
 
@@ -60,20 +72,28 @@ impl<'input> Token<'input> {
             As => "_as",
             Boolean => "_boolean",
             Bytes => "_bytes",
+            Date => "_date",
             Datetime => "_datetime",
+            Decimal => "_decimal",
+            Duration => "_duration",
             Enum => "_enum",
             Float => "_float",
             Double => "_double",
             I32 => "_i32",
             I64 => "_i64",
+            I128 => "_i128",
             Interface => "_interface",
+            Reserved => "_reserved",
             Service => "_service",
             Stream => "_stream",
             String => "_string",
+            Throws => "_throws",
+            Time => "_time",
             Tuple => "_tuple",
             Type => "_type",
             U32 => "_u32",
             U64 => "_u64",
+            U128 => "_u128",
             Use => "_use",
             _ => return None,
         };
@@ -97,13 +117,21 @@ impl<'input> Token<'input> {
             Double => "double",
             I32 => "i32",
             I64 => "i64",
+            I128 => "i128",
             U32 => "u32",
             U64 => "u64",
+            U128 => "u128",
+            Decimal => "decimal",
             Boolean => "boolean",
             String => "string",
             Datetime => "datetime",
+            Date => "date",
+            Time => "time",
+            Duration => "duration",
             Bytes => "bytes",
             Stream => "stream",
+            Reserved => "reserved",
+            Throws => "throws",
             Identifier(ref ident) => ident.as_ref(),
             _ => return None,
         };
@@ -0,0 +1,679 @@
+//! Style checks for reproto specifications.
+//!
+//! Rules operate on the AST, so that they can point out names exactly as they are written.
+
+extern crate reproto_ast as ast;
+extern crate reproto_core as core;
+extern crate reproto_naming as naming;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+
+use core::{Loc, Span};
+use naming::Naming;
+use std::fmt;
+
+/// How violations of a rule are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// The rule is not checked.
+    Allow,
+    /// Violations are reported as warnings.
+    Warn,
+    /// Violations are reported as errors.
+    Deny,
+}
+
+/// All available rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    TypeNaming,
+    FieldNaming,
+    PackageNaming,
+    MissingDocs,
+    Nesting,
+}
+
+impl Rule {
+    /// The identifier of the rule, as used in the manifest.
+    pub fn id(&self) -> &'static str {
+        use self::Rule::*;
+
+        match *self {
+            TypeNaming => "type_naming",
+            FieldNaming => "field_naming",
+            PackageNaming => "package_naming",
+            MissingDocs => "missing_docs",
+            Nesting => "nesting",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.id())
+    }
+}
+
+/// Linter configuration, as specified in the `[lint]` section of the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Declarations, sub-types, and enum variants must be `UpperCamelCase`.
+    pub type_naming: Level,
+    /// Fields must be `lower_snake_case`.
+    pub field_naming: Level,
+    /// Imported packages and their aliases must be `lower_snake_case`.
+    pub package_naming: Level,
+    /// Declarations must be documented.
+    pub missing_docs: Level,
+    /// Declarations must not be nested deeper than `max_nesting`.
+    pub nesting: Level,
+    /// Deepest permitted nesting of declarations, top-level declarations have a depth of 1.
+    pub max_nesting: usize,
+}
+
+impl Config {
+    /// Get the level configured for the given rule.
+    pub fn level(&self, rule: Rule) -> Level {
+        use self::Rule::*;
+
+        match rule {
+            TypeNaming => self.type_naming,
+            FieldNaming => self.field_naming,
+            PackageNaming => self.package_naming,
+            MissingDocs => self.missing_docs,
+            Nesting => self.nesting,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            type_naming: Level::Warn,
+            field_naming: Level::Warn,
+            package_naming: Level::Warn,
+            missing_docs: Level::Allow,
+            nesting: Level::Warn,
+            max_nesting: 3,
+        }
+    }
+}
+
+/// A single replacement in the linted source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// Span to replace, empty for insertions.
+    pub span: Span,
+    /// Text to put in place of the span.
+    pub replacement: String,
+}
+
+/// A fix for a lint which can be applied without changing the meaning of the specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub message: String,
+    pub edits: Vec<Edit>,
+}
+
+/// A violation of a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub rule: Rule,
+    pub level: Level,
+    pub span: Span,
+    pub message: String,
+    pub suggestion: Option<Suggestion>,
+}
+
+struct Linter<'a> {
+    config: &'a Config,
+    lints: Vec<Lint>,
+}
+
+impl<'a> Linter<'a> {
+    fn report(&mut self, rule: Rule, span: Span, message: String, suggestion: Option<Suggestion>) {
+        let level = self.config.level(rule);
+
+        if level == Level::Allow {
+            return;
+        }
+
+        self.lints.push(Lint {
+            rule,
+            level,
+            span,
+            message,
+            suggestion,
+        });
+    }
+
+    /// Check that the name follows the given naming convention.
+    ///
+    /// The suggestion is built from the expected name, if renaming is safe.
+    fn naming<N, S>(&mut self, rule: Rule, naming: N, what: &str, name: &str, span: Span, fix: S)
+    where
+        N: Naming,
+        S: FnOnce(&str) -> Option<Suggestion>,
+    {
+        let expected = naming.convert(name);
+
+        if expected == name {
+            return;
+        }
+
+        let message = format!("{} `{}` should be named `{}`", what, name, expected);
+        let suggestion = fix(expected.as_str());
+        self.report(rule, span, message, suggestion);
+    }
+
+    fn file(&mut self, file: &ast::File) {
+        for u in &file.uses {
+            if let ast::Package::Package { ref parts } = *Loc::borrow(&u.package) {
+                for part in parts {
+                    let (part, span) = Loc::borrow_pair(part);
+                    self.naming(
+                        Rule::PackageNaming,
+                        naming::to_lower_snake(),
+                        "package",
+                        part,
+                        span,
+                        |_| None,
+                    );
+                }
+            }
+
+            if let Some(ref alias) = u.alias {
+                let (alias, span) = Loc::borrow_pair(alias);
+                self.naming(
+                    Rule::PackageNaming,
+                    naming::to_lower_snake(),
+                    "package alias",
+                    alias,
+                    span,
+                    |_| None,
+                );
+            }
+        }
+
+        for decl in &file.decls {
+            self.decl(decl, 1);
+        }
+    }
+
+    fn decl(&mut self, decl: &ast::Decl, depth: usize) {
+        use ast::Decl::*;
+
+        let what = match *decl {
+            Type(_) => "type",
+            Tuple(_) => "tuple",
+            Interface(_) => "interface",
+            Enum(_) => "enum",
+            Service(_) => "service",
+            Union(_) => "union",
+            Newtype(_) => "newtype",
+        };
+
+        let (name, span) = Loc::take_pair(decl.name());
+
+        // renaming a declaration requires updating every reference to it, so no suggestion.
+        self.naming(
+            Rule::TypeNaming,
+            naming::to_upper_camel(),
+            what,
+            name,
+            span,
+            |_| None,
+        );
+
+        if decl.comment().is_empty() {
+            let message = format!("{} `{}` is not documented", what, name);
+            self.report(Rule::MissingDocs, span, message, None);
+        }
+
+        if depth > self.config.max_nesting {
+            let message = format!(
+                "{} `{}` is nested {} levels deep, at most {} are permitted",
+                what, name, depth, self.config.max_nesting
+            );
+
+            self.report(Rule::Nesting, span, message, None);
+        }
+
+        match *decl {
+            Type(ref body) => self.members(&body.members),
+            Tuple(ref body) => self.members(&body.members),
+            Interface(ref body) => {
+                self.members(&body.members);
+
+                for sub_type in &body.sub_types {
+                    let (name, span) = Loc::borrow_pair(&sub_type.name);
+
+                    self.naming(
+                        Rule::TypeNaming,
+                        naming::to_upper_camel(),
+                        "sub-type",
+                        name,
+                        span,
+                        |_| None,
+                    );
+
+                    self.members(&sub_type.members);
+                }
+            }
+            Enum(ref body) => {
+                for variant in &body.variants {
+                    let (name, span) = Loc::borrow_pair(&variant.name);
+                    let argument = variant.argument.is_some();
+
+                    // variants without an explicit value are serialized using their name, which
+                    // has to be preserved when renaming.
+                    self.naming(
+                        Rule::TypeNaming,
+                        naming::to_upper_camel(),
+                        "variant",
+                        name,
+                        span,
+                        |expected| {
+                            let mut edits = vec![Edit {
+                                span,
+                                replacement: expected.to_string(),
+                            }];
+
+                            if !argument {
+                                edits.push(Edit {
+                                    span: span.end(),
+                                    replacement: format!(" as {:?}", name),
+                                });
+                            }
+
+                            Some(Suggestion {
+                                message: format!("rename to `{}`", expected),
+                                edits,
+                            })
+                        },
+                    );
+                }
+            }
+            Service(_) | Union(_) | Newtype(_) => {}
+        }
+
+        for inner in decl.decls() {
+            self.decl(inner, depth + 1);
+        }
+    }
+
+    fn members(&mut self, members: &[ast::TypeMember]) {
+        for member in members {
+            let field = match *member {
+                ast::TypeMember::Field(ref field) => field,
+                _ => continue,
+            };
+
+            let start = Loc::span(&field.item).start;
+
+            // a field always starts with its name.
+            let span = Span {
+                start,
+                end: start + field.name.len(),
+            };
+
+            let ty_end = Loc::span(&field.ty).end;
+            let aliased = field.field_as.is_some();
+
+            // fields without an alias are serialized using their name, which has to be preserved
+            // when renaming.
+            self.naming(
+                Rule::FieldNaming,
+                naming::to_lower_snake(),
+                "field",
+                field.name.as_ref(),
+                span,
+                |expected| {
+                    let mut edits = vec![Edit {
+                        span,
+                        replacement: expected.to_string(),
+                    }];
+
+                    if !aliased {
+                        edits.push(Edit {
+                            span: Span {
+                                start: ty_end,
+                                end: ty_end,
+                            },
+                            replacement: format!(" as {:?}", field.name),
+                        });
+                    }
+
+                    Some(Suggestion {
+                        message: format!("rename to `{}`", expected),
+                        edits,
+                    })
+                },
+            );
+        }
+    }
+}
+
+/// Check the given file against all rules enabled in the configuration.
+pub fn lint(config: &Config, file: &ast::File) -> Vec<Lint> {
+    let mut linter = Linter {
+        config,
+        lints: Vec::new(),
+    };
+
+    linter.file(file);
+    linter.lints
+}
+
+/// Apply the given edits to the content of a source.
+///
+/// Edits overlapping an earlier edit are skipped, they will be suggested again on the next run.
+pub fn apply_edits(content: &str, mut edits: Vec<Edit>) -> String {
+    edits.sort_by_key(|e| (e.span.start, e.span.end));
+
+    let mut applied: Vec<Edit> = Vec::new();
+
+    for edit in edits {
+        if let Some(last) = applied.last() {
+            if edit.span.start < last.span.end {
+                continue;
+            }
+        }
+
+        applied.push(edit);
+    }
+
+    let mut content = content.to_string();
+
+    for edit in applied.into_iter().rev() {
+        content.replace_range(edit.span.start..edit.span.end, edit.replacement.as_str());
+    }
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate reproto_parser as parser;
+
+    use super::*;
+    use core::{Diagnostics, Source};
+    use std::borrow::Cow;
+
+    fn parse(input: &str) -> ast::File {
+        let mut diag = Diagnostics::new(Source::empty("test"));
+        parser::parse(&mut diag, input).expect("bad file")
+    }
+
+    fn file<'a>(decls: Vec<ast::Decl<'a>>) -> ast::File<'a> {
+        ast::File {
+            comment: Vec::new(),
+            attributes: Vec::new(),
+            uses: Vec::new(),
+            decls,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Find the span of the first occurrence of `needle` in `input`.
+    fn span(input: &str, needle: &str) -> Span {
+        let start = input.find(needle).expect("needle not in input");
+
+        Span {
+            start,
+            end: start + needle.len(),
+        }
+    }
+
+    fn item<'a, T>(item: T, span: Span) -> ast::Item<'a, T> {
+        ast::Item {
+            comment: Vec::new(),
+            attributes: Vec::new(),
+            item: Loc::new(item, span),
+        }
+    }
+
+    /// Declaration names are lexed as upper camel case, so enums violating the rule are built by
+    /// hand with spans pointing into `input`.
+    fn enum_decl<'a>(
+        input: &'a str,
+        name: &'a str,
+        variants: Vec<(&'a str, Option<&'a str>)>,
+    ) -> ast::Decl<'a> {
+        let variants = variants
+            .into_iter()
+            .map(|(name, argument)| {
+                let argument = argument.map(|a| {
+                    let value = ast::Value::String(a.to_string());
+                    Loc::new(value, span(input, &format!("{:?}", a)))
+                });
+
+                let variant = ast::EnumVariant {
+                    name: Loc::new(Cow::Borrowed(name), span(input, name)),
+                    argument,
+                    aliases: Vec::new(),
+                };
+
+                item(variant, span(input, name))
+            })
+            .collect();
+
+        let body = ast::EnumBody {
+            name: Loc::new(Cow::Borrowed(name), span(input, name)),
+            ty: Loc::new(ast::Type::String, span(input, "string")),
+            variants,
+            members: Vec::new(),
+        };
+
+        ast::Decl::Enum(item(body, Span::from((0, input.len()))))
+    }
+
+    fn messages(lints: Vec<Lint>) -> Vec<String> {
+        lints.into_iter().map(|l| l.message).collect()
+    }
+
+    /// Apply all suggested fixes for the given file.
+    fn fix(input: &str, file: &ast::File) -> String {
+        let edits = lint(&Config::default(), file)
+            .into_iter()
+            .flat_map(|l| l.suggestion.into_iter().flat_map(|s| s.edits))
+            .collect();
+
+        apply_edits(input, edits)
+    }
+
+    #[test]
+    fn test_type_naming() {
+        let input = "enum foo_bar as string {\n  baz_qux;\n  Quux as \"quux\";\n}\n";
+        let file = file(vec![enum_decl(
+            input,
+            "foo_bar",
+            vec![("baz_qux", None), ("Quux", Some("quux"))],
+        )]);
+
+        let lints = lint(&Config::default(), &file);
+        assert!(lints.iter().all(|l| l.rule == Rule::TypeNaming));
+        assert!(lints.iter().all(|l| l.level == Level::Warn));
+
+        assert_eq!(
+            vec![
+                "enum `foo_bar` should be named `FooBar`",
+                "variant `baz_qux` should be named `BazQux`",
+            ],
+            messages(lints)
+        );
+
+        // the enum itself is referenced elsewhere, so only the variant is renamed.
+        assert_eq!(
+            "enum foo_bar as string {\n  BazQux as \"baz_qux\";\n  Quux as \"quux\";\n}\n",
+            fix(input, &file)
+        );
+
+        assert!(lint(
+            &Config::default(),
+            &parse("enum FooBar as string {\n  Baz;\n}\n")
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_type_naming_explicit_value() {
+        let input = "enum Foo as string {\n  bar_baz as \"bar\";\n}\n";
+        let file = file(vec![enum_decl(
+            input,
+            "Foo",
+            vec![("bar_baz", Some("bar"))],
+        )]);
+
+        assert_eq!(
+            "enum Foo as string {\n  BarBaz as \"bar\";\n}\n",
+            fix(input, &file)
+        );
+    }
+
+    #[test]
+    fn test_field_naming() {
+        let input = "type Foo {\n  foo__bar: string;\n  baz: string;\n}\n";
+        let file = parse(input);
+
+        let lints = lint(&Config::default(), &file);
+        assert!(lints.iter().all(|l| l.rule == Rule::FieldNaming));
+        assert_eq!(
+            vec!["field `foo__bar` should be named `foo_bar`"],
+            messages(lints)
+        );
+
+        assert_eq!(
+            "type Foo {\n  foo_bar: string as \"foo__bar\";\n  baz: string;\n}\n",
+            fix(input, &file)
+        );
+
+        assert!(lint(
+            &Config::default(),
+            &parse("type Foo {\n  foo_bar?: string;\n}\n")
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_field_naming_aliased() {
+        let input = "tuple Foo {\n  foo__bar?: [u32] as \"foo\";\n}\n";
+        let file = parse(input);
+
+        assert_eq!(
+            "tuple Foo {\n  foo_bar?: [u32] as \"foo\";\n}\n",
+            fix(input, &file)
+        );
+    }
+
+    #[test]
+    fn test_package_naming() {
+        let file = parse("use foo__bar.baz as baz__qux;\nuse foo.bar as bar;\n");
+        let lints = lint(&Config::default(), &file);
+
+        assert!(lints.iter().all(|l| l.rule == Rule::PackageNaming));
+        assert!(lints.iter().all(|l| l.suggestion.is_none()));
+
+        assert_eq!(
+            vec![
+                "package `foo__bar` should be named `foo_bar`",
+                "package alias `baz__qux` should be named `baz_qux`",
+            ],
+            messages(lints)
+        );
+    }
+
+    #[test]
+    fn test_missing_docs() {
+        let file = parse("type Foo {}\n\n/// Documented.\ntype Bar {}\n");
+
+        // not checked by default.
+        assert!(lint(&Config::default(), &file).is_empty());
+
+        let config = Config {
+            missing_docs: Level::Deny,
+            ..Config::default()
+        };
+
+        let lints = lint(&config, &file);
+        assert!(lints.iter().all(|l| l.rule == Rule::MissingDocs));
+        assert!(lints.iter().all(|l| l.level == Level::Deny));
+        assert_eq!(vec!["type `Foo` is not documented"], messages(lints));
+    }
+
+    #[test]
+    fn test_nesting() {
+        let file = parse("type A {\n  type B {\n    type C {}\n  }\n}\n");
+
+        assert!(lint(&Config::default(), &file).is_empty());
+
+        let config = Config {
+            max_nesting: 2,
+            ..Config::default()
+        };
+
+        let lints = lint(&config, &file);
+        assert!(lints.iter().all(|l| l.rule == Rule::Nesting));
+
+        assert_eq!(
+            vec!["type `C` is nested 3 levels deep, at most 2 are permitted"],
+            messages(lints)
+        );
+    }
+
+    #[test]
+    fn test_allow() {
+        let file = parse("use foo__bar;\n\ntype Foo {\n  foo__bar: string;\n}\n");
+
+        let config = Config {
+            field_naming: Level::Allow,
+            package_naming: Level::Allow,
+            ..Config::default()
+        };
+
+        assert!(lint(&config, &file).is_empty());
+    }
+
+    fn edit(start: usize, end: usize, replacement: &str) -> Edit {
+        Edit {
+            span: Span { start, end },
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        assert_eq!("foo bar", apply_edits("foo bar", vec![]));
+
+        // edits are applied regardless of the order they are provided in.
+        assert_eq!(
+            "Foo as \"foo\"; Bar;",
+            apply_edits(
+                "foo; Bar;",
+                vec![edit(3, 3, " as \"foo\""), edit(0, 3, "Foo")],
+            )
+        );
+
+        // insertions at the same position keep their order.
+        assert_eq!(
+            "ab",
+            apply_edits("", vec![edit(0, 0, "a"), edit(0, 0, "b")])
+        );
+    }
+
+    #[test]
+    fn test_apply_overlapping_edits() {
+        assert_eq!(
+            "a X e",
+            apply_edits("a bcd e", vec![edit(2, 5, "X"), edit(3, 4, "Y")])
+        );
+
+        assert_eq!(
+            "a X e",
+            apply_edits("a bcd e", vec![edit(3, 6, "Y"), edit(2, 5, "X")])
+        );
+    }
+}
@@ -7,6 +7,7 @@
 extern crate log;
 extern crate relative_path;
 pub extern crate reproto_core as core;
+pub extern crate reproto_lint as lint;
 extern crate reproto_naming as naming;
 pub extern crate reproto_trans as trans;
 extern crate serde;
@@ -14,19 +15,28 @@ extern crate serde;
 extern crate serde_derive;
 extern crate toml;
 
+mod overrides;
+mod validate;
+
+pub use self::overrides::Override;
+
 use core::errors::Result;
 use core::{
-    CoreFlavor, Range, Resolved, ResolvedByPrefix, Resolver, RpPackage, RpRequiredPackage,
-    RpVersionedPackage, Version,
+    CoreFlavor, Diagnostics, Range, Resolved, ResolvedByPrefix, Resolver, RpPackage,
+    RpRequiredPackage, RpScalarType, RpVersionedPackage, Version,
 };
-use naming::Naming;
+use naming::{Keywords, Naming};
 use relative_path::{RelativePath, RelativePathBuf};
 use std::any::Any;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
-use std::io::Read;
+use std::io::{Cursor, Read};
+use std::mem;
 use std::path::{Path, PathBuf};
 
+/// Prefix of languages which are provided by an external plugin.
+pub const PLUGIN_PREFIX: &str = "plugin:";
+
 #[macro_export]
 macro_rules! lang_base {
     ($module:ty, $compile:ident) => {
@@ -35,11 +45,11 @@ macro_rules! lang_base {
         }
 
         /// Module specs.
-        fn module_specs(&self, path: &Path, input: Option<toml::Value>) -> Result<Option<Vec<Box<Any>>>> {
+        fn module_specs(&self, path: &Path, input: Option<toml::Value>) -> Result<Option<Vec<Box<Any + Send>>>> {
             $crate::parse_section_any::<$module>(path, input)
         }
 
-        fn string_spec(&self, path: &Path, input: &str) -> Result<Box<Any>> {
+        fn string_spec(&self, path: &Path, input: &str) -> Result<Box<Any + Send>> {
             $crate::parse_string_any::<$module>(path, input)
         }
 
@@ -58,7 +68,9 @@ macro_rules! lang_base {
 ///
 /// TODO: move language-specific integrations into own crate.
 /// Options would have to be transferred to a local type.
-pub trait Lang: fmt::Debug {
+///
+/// Languages are `Send` so that independent targets can be built in parallel.
+pub trait Lang: fmt::Debug + Send {
     /// Copy self.
     ///
     /// Implemented through `lang_base!` macro.
@@ -71,12 +83,12 @@ pub trait Lang: fmt::Debug {
         &self,
         path: &Path,
         input: Option<toml::Value>,
-    ) -> Result<Option<Vec<Box<Any>>>>;
+    ) -> Result<Option<Vec<Box<Any + Send>>>>;
 
     /// Parse a module configuration consisting of _only_ a string.
     ///
     /// Implemented through `lang_base!` macro.
-    fn string_spec(&self, path: &Path, input: &str) -> Result<Box<Any>>;
+    fn string_spec(&self, path: &Path, input: &str) -> Result<Box<Any + Send>>;
 
     /// Language-specific compile hook.
     ///
@@ -93,9 +105,9 @@ pub trait Lang: fmt::Debug {
         None
     }
 
-    /// Get a list of keywords to transliterate.
-    fn keywords(&self) -> Vec<(&'static str, &'static str)> {
-        vec![]
+    /// Reserved words of the language, which are escaped in identifiers and packages.
+    fn keywords(&self) -> Option<&'static Keywords> {
+        None
     }
 
     /// Indicates if the language requires keyword-escaping in the packages.
@@ -103,6 +115,14 @@ pub trait Lang: fmt::Debug {
         false
     }
 
+    /// Indicates if the language can encode 64-bit integers as strings, as requested through
+    /// `number_format`.
+    ///
+    /// Fields which request it are warned about when building languages that can't.
+    fn numbers_as_strings(&self) -> bool {
+        true
+    }
+
     /// Helper to convert into session.
     fn into_session<'a>(
         &self,
@@ -112,13 +132,13 @@ pub trait Lang: fmt::Debug {
     ) -> Result<trans::Session<'a, CoreFlavor>> {
         let keywords = self
             .keywords()
-            .into_iter()
-            .map(|(f, t)| (f.to_string(), t.to_string()))
-            .collect();
+            .map(Keywords::replacements)
+            .unwrap_or_default();
 
         let session = trans::Session::new(package_prefix.clone(), reporter, resolver)?
             .with_keywords(keywords)
-            .with_safe_packages(self.safe_packages());
+            .with_safe_packages(self.safe_packages())
+            .with_numbers_as_strings_supported(self.numbers_as_strings());
 
         let session = if let Some(package_naming) = self.package_naming() {
             session.with_package_naming(package_naming)
@@ -156,7 +176,35 @@ pub trait Lang: fmt::Debug {
         None
     }
 
-    fn modules(&self) -> Option<String> { None }
+    /// Modules supported by the language, and the options they accept.
+    fn modules(&self) -> &'static [ModuleDoc] {
+        &[]
+    }
+}
+
+/// Description of a module supported by a language.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleDoc {
+    /// Identifier of the module, as used in the manifest.
+    pub name: &'static str,
+    /// Short description of what the module does.
+    pub description: &'static str,
+    /// Options accepted by the module.
+    pub options: &'static [ModuleOption],
+}
+
+/// Description of a single option accepted by a module.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleOption {
+    /// Name of the option.
+    pub name: &'static str,
+    /// Type of the option, like `string`, or the values that it accepts.
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    /// Default value of the option, if it has one.
+    pub default: Option<&'static str>,
+    /// Short description of the option.
+    pub description: &'static str,
 }
 
 /// Fallback language support in case no language is specified.
@@ -284,41 +332,113 @@ impl TryFromToml for RpRequiredPackage {
 }
 
 /// Enum designating which language is being compiled.
-#[derive(Debug, Clone, Copy, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Language {
+    AsyncApi,
+    Avro,
     Csharp,
     Go,
+    Haskell,
     Java,
     Js,
     Json,
+    JsonSchema,
+    Kotlin,
     OpenApi,
     Python,
     Reproto,
     Rust,
     Swift,
+    Template,
+    /// An external generator, invoked as `reproto-gen-<name>`.
+    Plugin(String),
 }
 
 impl Language {
+    /// All built-in languages.
+    pub fn all() -> Vec<Language> {
+        use self::Language::*;
+
+        vec![
+            AsyncApi, Avro, Csharp, Go, Haskell, Java, Js, Json, JsonSchema, Kotlin, OpenApi, Python,
+            Reproto, Rust, Swift, Template,
+        ]
+    }
+
     pub fn parse(input: &str) -> Option<Language> {
         use self::Language::*;
 
+        if input.starts_with(PLUGIN_PREFIX) {
+            let name = &input[PLUGIN_PREFIX.len()..];
+
+            if name.is_empty() {
+                return None;
+            }
+
+            return Some(Plugin(name.to_string()));
+        }
+
         let language = match input {
+            "asyncapi" => AsyncApi,
+            "avro" => Avro,
             "csharp" => Csharp,
             "go" => Go,
+            "haskell" => Haskell,
             "java" => Java,
             "js" => Js,
             "json" => Json,
+            "jsonschema" => JsonSchema,
+            "kotlin" => Kotlin,
             "openapi" => OpenApi,
             "python" => Python,
             "reproto" => Reproto,
             "rust" => Rust,
             "swift" => Swift,
+            "template" => Template,
             _ => return None,
         };
 
         Some(language)
     }
+
+    /// Identifier of the language, as used in the manifest.
+    pub fn id(&self) -> String {
+        use self::Language::*;
+
+        let id = match *self {
+            AsyncApi => "asyncapi",
+            Avro => "avro",
+            Csharp => "csharp",
+            Go => "go",
+            Haskell => "haskell",
+            Java => "java",
+            Js => "js",
+            Json => "json",
+            JsonSchema => "jsonschema",
+            Kotlin => "kotlin",
+            OpenApi => "openapi",
+            Python => "python",
+            Reproto => "reproto",
+            Rust => "rust",
+            Swift => "swift",
+            Template => "template",
+            Plugin(ref name) => return format!("{}{}", PLUGIN_PREFIX, name),
+        };
+
+        id.to_string()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Language {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let id = String::deserialize(deserializer)?;
+
+        Language::parse(id.as_str())
+            .ok_or_else(|| serde::de::Error::custom(format!("not a valid language: {}", id)))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -376,15 +496,15 @@ where
 pub fn parse_section_any<T: 'static>(
     base: &Path,
     value: Option<toml::Value>,
-) -> Result<Option<Vec<Box<Any>>>>
+) -> Result<Option<Vec<Box<Any + Send>>>>
 where
-    T: TryFromToml,
+    T: TryFromToml + Send,
 {
     if let Some(values) = parse_section::<T>(base, value)? {
         Ok(Some(
             values
                 .into_iter()
-                .map(|b| Box::new(b) as Box<Any>)
+                .map(|b| Box::new(b) as Box<Any + Send>)
                 .collect(),
         ))
     } else {
@@ -393,16 +513,16 @@ where
 }
 
 /// Parse the given string as a module.
-pub fn parse_string_any<T: 'static>(base: &Path, name: &str) -> Result<Box<Any>>
+pub fn parse_string_any<T: 'static>(base: &Path, name: &str) -> Result<Box<Any + Send>>
 where
-    T: TryFromToml,
+    T: TryFromToml + Send,
 {
     let value = toml::Value::Table(toml::value::Table::default());
-    Ok(Box::new(parse_spec::<T>(base, name, value)?) as Box<Any>)
+    Ok(Box::new(parse_spec::<T>(base, name, value)?) as Box<Any + Send>)
 }
 
 /// Attempt to perform a checked conversion of the given vector of modules.
-pub fn checked_modules<M: Any>(modules: Option<Vec<Box<Any>>>) -> Result<Vec<M>> {
+pub fn checked_modules<M: Any>(modules: Option<Vec<Box<Any + Send>>>) -> Result<Vec<M>> {
     let mut out = Vec::new();
 
     if let Some(modules) = modules {
@@ -453,10 +573,55 @@ impl TryFromToml for Preset {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Doc {
+    /// Theme to use.
+    pub theme: Option<String>,
+    /// Directory with a custom theme, overriding the built-in one.
+    pub theme_dir: Option<PathBuf>,
     /// Syntax theme to use.
     pub syntax_theme: Option<String>,
+    /// Output format to use.
+    pub format: Option<String>,
+    /// Build documentation for all available versions of the required packages.
+    pub all_versions: bool,
+    /// Template for links to declarations in a source browser, like GitHub.
+    pub source_url: Option<String>,
+}
+
+/// Naming of fields, declared under `[naming]` or for a single target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldNaming {
+    /// Naming of field identifiers in generated code, instead of the convention of the language.
+    pub ident: Option<naming::Strategy>,
+    /// Naming of fields on the wire, unless a specification declares `#![field_naming(..)]`.
+    pub name: Option<naming::Strategy>,
+}
+
+impl FieldNaming {
+    /// Use the naming of `other` for everything which is not set in this one.
+    pub fn or(self, other: FieldNaming) -> FieldNaming {
+        FieldNaming {
+            ident: self.ident.or(other.ident),
+            name: self.name.or(other.name),
+        }
+    }
+
+    /// Apply the naming to the given session.
+    pub fn apply<'a>(
+        &self,
+        session: trans::Session<'a, CoreFlavor>,
+    ) -> trans::Session<'a, CoreFlavor> {
+        let session = match self.ident {
+            Some(ident) => session.with_field_ident_naming(ident.naming()),
+            None => session,
+        };
+
+        match self.name {
+            Some(name) => session.with_field_naming(name.naming()),
+            None => session,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -465,6 +630,30 @@ pub struct Publish {
     pub version: Version,
 }
 
+/// Names of all naming strategies accepted by the `[naming]` section.
+pub const NAMING_STRATEGIES: &[&str] = &[
+    "lower_camel",
+    "upper_camel",
+    "lower_snake",
+    "upper_snake",
+    "keep",
+];
+
+/// A custom scalar declared in the `scalars` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scalar {
+    /// The built-in type which the scalar is encoded as.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Pattern that values must match, only for scalars encoded as strings.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Types to use for the scalar, by the identifier of the language.
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct File {
     pub path: PathBuf,
@@ -486,10 +675,16 @@ impl File {
 pub struct Repository {
     /// Skip using local repository.
     pub no_repository: bool,
+    /// Only use packages which are available locally.
+    pub offline: bool,
     /// URL to use for index.
     pub index: Option<String>,
     /// URL to use to objects storage.
     pub objects: Option<String>,
+    /// Path to the key used to sign published packages.
+    pub signing_key: Option<PathBuf>,
+    /// If set, only packages signed by one of these public keys will be used.
+    pub trusted_keys: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
@@ -498,6 +693,64 @@ pub struct Source {
     pub source: core::Source,
 }
 
+/// A named build target, declared under `[[targets]]`.
+///
+/// Each target is built with its own language, output directory, and modules.
+#[derive(Debug)]
+pub struct Target {
+    /// Name of the target.
+    pub name: Option<String>,
+    /// Language to build the target for.
+    pub language: Language,
+    /// Implementation of the language to build the target for.
+    pub lang: Box<Lang>,
+    /// Output directory of the target.
+    pub output: Option<PathBuf>,
+    /// Modules to enable for the target.
+    pub modules: Option<Vec<Box<Any + Send>>>,
+    /// Naming of fields for the target, falling back to the naming of the manifest.
+    pub naming: FieldNaming,
+}
+
+/// Round-trip tests, declared under `[test]`.
+#[derive(Debug, Default)]
+pub struct Test {
+    /// Files with JSON documents to feed through every project, one document per line.
+    pub fixtures: Vec<PathBuf>,
+    /// Directory where projects are built.
+    pub workdir: Option<PathBuf>,
+    /// Projects to test, declared under `[[test.projects]]`.
+    pub projects: Vec<TestProject>,
+}
+
+/// A project which decodes and encodes JSON documents using generated code.
+///
+/// The project is copied into the working directory, code is generated into `output`, and
+/// `make <instance>` is expected to produce a `script.sh` which reads documents from stdin, one per
+/// line, and writes them back to stdout after passing them through the generated models.
+#[derive(Debug)]
+pub struct TestProject {
+    /// Name of the project, defaults to the identifier of its language.
+    pub name: String,
+    /// Language to generate code for.
+    pub lang: Box<Lang>,
+    /// Directory of the project.
+    pub path: PathBuf,
+    /// Directory to generate code into, relative to the project.
+    pub output: RelativePathBuf,
+    /// Make target which builds the project.
+    pub instance: Option<String>,
+    /// Modules to enable when generating code.
+    pub modules: Option<Vec<Box<Any + Send>>>,
+}
+
+/// A workspace of member projects, declared under `[workspace]`.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    /// Directories of the member projects, each with their own manifest.
+    pub members: Vec<PathBuf>,
+}
+
 /// The realized project manifest.
 ///
 /// * All paths are absolute.
@@ -509,28 +762,71 @@ pub struct Manifest {
     pub path: Option<PathBuf>,
     /// Packages to build.
     pub packages: Option<Vec<RpRequiredPackage>>,
+    /// Prefixes of packages to build, declared as globs like `io.reproto.*`.
+    pub package_globs: Vec<RpRequiredPackage>,
+    /// Directories where every package should be built.
+    pub package_dirs: Vec<PathBuf>,
     /// Files to build.
     pub files: Option<Vec<File>>,
     /// Read files from stdin.
     ///
     /// This is not part of the manifest.
     pub stdin: bool,
+    /// Resolve all matching versions of required packages, instead of only the latest.
+    ///
+    /// This is not part of the manifest.
+    pub all_versions: bool,
     /// Packages to publish.
     pub publish: Option<Vec<Publish>>,
     /// Modules to enable.
-    pub modules: Option<Vec<Box<Any>>>,
+    pub modules: Option<Vec<Box<Any + Send>>>,
     /// Additional paths specified.
     pub paths: Vec<PathBuf>,
+    /// Priorities of paths, when the same package is found in more than one path.
+    pub path_priorities: HashMap<PathBuf, i32>,
+    /// Packages which are pinned to only be resolved from the given path.
+    pub pins: HashMap<RpPackage, PathBuf>,
     /// Output directory.
     pub output: Option<PathBuf>,
     /// Package prefix to apply.
     pub package_prefix: Option<RpPackage>,
     /// Conversion strategy to use for IDs.
     pub id_converter: Option<String>,
+    /// File where stable identifiers assigned to fields are recorded.
+    pub field_ids: Option<PathBuf>,
+    /// Directory with JSON documents recorded by consumers, which must decode against the
+    /// current schemas.
+    pub consumers: Option<PathBuf>,
+    /// Custom scalars, which can be used as types in specifications.
+    pub scalars: Vec<RpScalarType>,
+    /// Encode 64-bit integers as JSON strings, unless a field overrides it with the
+    /// `#[number_format(..)]` attribute.
+    pub numbers_as_strings: bool,
+    /// Naming of fields in generated code and on the wire.
+    pub naming: FieldNaming,
     /// Repository configuration.
     pub repository: Repository,
     /// Documentation settings.
     pub doc: Doc,
+    /// Lint settings.
+    pub lint: lint::Config,
+    /// Additional targets to build.
+    pub targets: Vec<Target>,
+    /// Round-trip tests.
+    pub test: Test,
+    /// Member projects of the workspace.
+    pub workspace: Option<Workspace>,
+    /// Root directory of the workspace that this manifest is a member of.
+    ///
+    /// Members share the lockfile and vendored packages of the workspace, so that dependencies
+    /// shared between members are resolved once.
+    ///
+    /// This is not part of the manifest.
+    pub workspace_root: Option<PathBuf>,
+    /// Overrides to apply to the manifest when it is loaded, in order.
+    ///
+    /// This is not part of the manifest.
+    pub overrides: Vec<Override>,
 }
 
 impl Manifest {
@@ -546,14 +842,31 @@ impl Manifest {
             toml::from_str(content.as_str())?
         };
 
-        if let Some(lang) = take_field::<Option<Language>>(&mut value, "language")? {
+        for o in &self.overrides {
+            o.apply(&mut value)
+                .map_err(|e| format!("override `{}`: {}", o, e.display()))?;
+        }
+
+        let language = take_field::<Option<Language>>(&mut value, "language")?;
+        let modules = take_field(&mut value, "modules")?;
+        let targets = take_field::<Vec<toml::value::Table>>(&mut value, "targets")?;
+
+        if let Some(lang) = language {
+            // NB: every target declares its own language.
+            if !targets.is_empty() {
+                return Err("`language` can't be used together with `[[targets]]`, use \
+                            `--lang <language>` to only build the targets of one language"
+                    .into());
+            }
+
             // Already set, do not override.
             if self.lang.is_none() {
                 self.lang = Some(convert_language(lang));
             }
         }
 
-        let modules = take_field(&mut value, "modules")?;
+        let test = take_field::<Option<toml::value::Table>>(&mut value, "test")?;
+        let workspace = take_field::<Option<toml::value::Table>>(&mut value, "workspace")?;
 
         // Only load components if we have a parent path.
         if let Some(path) = self.path.clone() {
@@ -565,6 +878,25 @@ impl Manifest {
                 self.modules = lang.module_specs(parent, modules)?;
             }
 
+            for (index, mut target) in targets.into_iter().enumerate() {
+                let target = load_target(parent, &mut target, &convert_language)
+                    .map_err(|e| format!("targets[{}]: {}", index, e.display()))?;
+
+                self.targets.push(target);
+            }
+
+            if let Some(mut test) = test {
+                self.test = load_test(parent, &mut test, &convert_language)
+                    .map_err(|e| format!("test: {}", e.display()))?;
+            }
+
+            if let Some(mut workspace) = workspace {
+                self.workspace = Some(
+                    load_workspace(parent, &mut workspace)
+                        .map_err(|e| format!("workspace: {}", e.display()))?,
+                );
+            }
+
             load_common_manifest(self, parent, &mut value)?;
         }
 
@@ -572,6 +904,138 @@ impl Manifest {
         Ok(())
     }
 
+    /// Load from the source of the given diagnostics.
+    ///
+    /// The manifest is validated before it is loaded, and every problem found is reported as a
+    /// diagnostic pointing into the manifest.
+    pub fn from_source<C>(&mut self, diag: &mut Diagnostics, convert_language: C) -> Result<()>
+    where
+        C: Fn(Language) -> Box<Lang>,
+    {
+        let mut content = String::new();
+        diag.source.read()?.read_to_string(&mut content)?;
+
+        {
+            let base = self.path.as_ref().and_then(|p| p.parent());
+            validate::validate(diag, &content, base, self.lang(), &convert_language);
+        }
+
+        if diag.has_errors() {
+            return Err(format!("{}: invalid manifest", diag.source).into());
+        }
+
+        self.from_yaml(Cursor::new(content), convert_language)
+    }
+
+    /// Split the manifest into one manifest for each declared target.
+    ///
+    /// If no targets are declared, the manifest itself is the only target.
+    /// Targets inherit everything except the language, output directory, and modules from the
+    /// top-level manifest.
+    ///
+    /// If `language` is specified, only the targets for that language are included, and it's an
+    /// error if there are none.
+    pub fn into_targets(mut self, language: Option<&Language>) -> Result<Vec<Manifest>> {
+        if self.targets.is_empty() {
+            return Ok(vec![self]);
+        }
+
+        let mut targets = mem::replace(&mut self.targets, Vec::new());
+
+        if let Some(language) = language {
+            let declared = targets
+                .iter()
+                .map(|t| t.language.id())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            targets.retain(|t| t.language == *language);
+
+            if targets.is_empty() {
+                return Err(format!(
+                    "no target builds `{}`, declared targets are for: {}",
+                    language.id(),
+                    declared
+                ).into());
+            }
+        }
+
+        let mut out = Vec::new();
+
+        for target in targets {
+            if let Some(name) = target.name.as_ref() {
+                debug!("target: {}", name);
+            }
+
+            let output = target.output.or_else(|| self.output.clone());
+            let mut manifest = self.inherit(target.lang, target.modules, output);
+            manifest.naming = target.naming.or(self.naming);
+            out.push(manifest);
+        }
+
+        Ok(out)
+    }
+
+    /// Split the manifest into one manifest for each declared test project.
+    ///
+    /// Each manifest generates code for the language of the project, using the modules of the
+    /// project, and inherits everything else from the top-level manifest.
+    /// The output directory is left unset, since it depends on where the project is built.
+    pub fn into_test_projects(mut self) -> Vec<(TestProject, Manifest)> {
+        let projects = mem::replace(&mut self.test.projects, Vec::new());
+        let mut out = Vec::new();
+
+        for mut project in projects {
+            let lang = project.lang.copy();
+            let modules = project.modules.take();
+            let manifest = self.inherit(lang, modules, None);
+            out.push((project, manifest));
+        }
+
+        out
+    }
+
+    /// Build a manifest which inherits everything except the language, output directory, and
+    /// modules from this manifest.
+    fn inherit(
+        &self,
+        lang: Box<Lang>,
+        modules: Option<Vec<Box<Any + Send>>>,
+        output: Option<PathBuf>,
+    ) -> Manifest {
+        Manifest {
+            lang: Some(lang),
+            path: self.path.clone(),
+            packages: self.packages.clone(),
+            package_globs: self.package_globs.clone(),
+            package_dirs: self.package_dirs.clone(),
+            files: self.files.clone(),
+            stdin: self.stdin,
+            all_versions: self.all_versions,
+            publish: self.publish.clone(),
+            modules: modules,
+            paths: self.paths.clone(),
+            path_priorities: self.path_priorities.clone(),
+            pins: self.pins.clone(),
+            output: output,
+            package_prefix: self.package_prefix.clone(),
+            id_converter: self.id_converter.clone(),
+            field_ids: self.field_ids.clone(),
+            consumers: self.consumers.clone(),
+            scalars: self.scalars.clone(),
+            numbers_as_strings: self.numbers_as_strings,
+            naming: self.naming,
+            repository: self.repository.clone(),
+            doc: self.doc.clone(),
+            lint: self.lint.clone(),
+            targets: Vec::new(),
+            test: Test::default(),
+            workspace: None,
+            workspace_root: self.workspace_root.clone(),
+            overrides: self.overrides.clone(),
+        }
+    }
+
     /// Access language to build for.
     pub fn lang(&self) -> Option<Box<Lang>> {
         self.lang.as_ref().map(|l| l.copy())
@@ -592,18 +1056,21 @@ impl Manifest {
             return false;
         }
 
+        if !self.package_globs.is_empty() || !self.package_dirs.is_empty() {
+            return false;
+        }
+
         self.path.is_none()
     }
 
     /// Resolve all sources for this manifest.
     pub fn resolve(&self, resolver: &mut Resolver) -> Result<Vec<Source>> {
         let mut sources = Vec::new();
+        // only build unique packages, some resolvers will resolve the same version.
+        let mut seen = HashSet::new();
 
         // if there are no packages, load from path resolver.
         if self.packages.is_none() {
-            // only build unique packages, some resolvers will resolve the same version.
-            let mut seen = HashSet::new();
-
             for ResolvedByPrefix { package, source } in resolver.resolve_packages()? {
                 if !seen.insert(package.clone()) {
                     continue;
@@ -614,6 +1081,67 @@ impl Manifest {
             }
         }
 
+        if !self.package_dirs.is_empty() {
+            let mut found = HashSet::new();
+
+            for ResolvedByPrefix { package, source } in resolver.resolve_packages()? {
+                let dir = {
+                    let path = match source.path() {
+                        Some(path) => path,
+                        None => continue,
+                    };
+
+                    match self.package_dirs.iter().find(|d| path.starts_with(d)) {
+                        Some(dir) => dir.clone(),
+                        None => continue,
+                    }
+                };
+
+                found.insert(dir);
+
+                if !seen.insert(package.clone()) {
+                    continue;
+                }
+
+                trace!("resolved package `{}` in directory to build", package);
+                sources.push(Source { package, source });
+            }
+
+            for dir in &self.package_dirs {
+                if !found.contains(dir) {
+                    return Err(format!("no packages found in directory: {}", dir.display()).into());
+                }
+            }
+        }
+
+        for glob in &self.package_globs {
+            let mut found = false;
+
+            for ResolvedByPrefix { package, source } in resolver.resolve_by_prefix(&glob.package)? {
+                let matches = match package.version {
+                    Some(ref version) => glob.range.matches(version),
+                    None => glob.range.matches_any(),
+                };
+
+                if !matches {
+                    continue;
+                }
+
+                found = true;
+
+                if !seen.insert(package.clone()) {
+                    continue;
+                }
+
+                trace!("resolved package `{}` by glob to build", package);
+                sources.push(Source { package, source });
+            }
+
+            if !found {
+                return Err(format!("no packages matching `{}.*` found", glob.package).into());
+            }
+        }
+
         for file in self.files.as_ref().iter().flat_map(|f| f.iter()) {
             let package = file.package.clone().unwrap_or_else(RpPackage::empty);
             let package = RpVersionedPackage::new(package, file.version.clone());
@@ -622,22 +1150,167 @@ impl Manifest {
         }
 
         for required in self.packages.iter().flat_map(|p| p.iter()).cloned() {
-            // find matching object from the resolver.
-            let Resolved { version, source } = match resolver.resolve(&required)? {
-                Some(resolved) => resolved,
-                None => {
-                    return Err(format!("required package `{}` not found", required).into());
-                }
+            // find matching objects from the resolver.
+            let resolved = if self.all_versions {
+                resolver.resolve_all(&required)?
+            } else {
+                resolver.resolve(&required)?.into_iter().collect()
             };
 
-            let package = RpVersionedPackage::new(required.package.clone(), version);
-            sources.push(Source { package, source });
+            if resolved.is_empty() {
+                return Err(format!("required package `{}` not found", required).into());
+            }
+
+            for Resolved { version, source } in resolved {
+                let package = RpVersionedPackage::new(required.package.clone(), version);
+                sources.push(Source { package, source });
+            }
         }
 
         Ok(sources)
     }
 }
 
+/// Load a single target declared under `[[targets]]`.
+fn load_target<C>(
+    base: &Path,
+    value: &mut toml::value::Table,
+    convert_language: C,
+) -> Result<Target>
+where
+    C: Fn(Language) -> Box<Lang>,
+{
+    let name = take_field::<Option<String>>(value, "name")?;
+
+    let language =
+        take_field::<Option<Language>>(value, "language")?.ok_or_else(|| "missing `language`")?;
+    let lang = convert_language(language.clone());
+
+    let output = take_field::<Option<RelativePathBuf>>(value, "output")?.map(|o| o.to_path(base));
+    let modules = lang.module_specs(base, take_field(value, "modules")?)?;
+
+    let mut naming = FieldNaming::default();
+    take_section(value, "naming", |value| load_naming(&mut naming, value))?;
+
+    check_empty(value)?;
+
+    Ok(Target {
+        name,
+        language,
+        lang,
+        output,
+        modules,
+        naming,
+    })
+}
+
+/// Load the naming of fields, declared under `[naming]` or for a single target.
+fn load_naming(naming: &mut FieldNaming, value: &mut toml::value::Table) -> Result<()> {
+    naming.ident = parse_strategy(take_field(value, "field_ident")?)
+        .map_err(|e| format!("field_ident: {}", e.display()))?;
+    naming.name = parse_strategy(take_field(value, "field_name")?)
+        .map_err(|e| format!("field_name: {}", e.display()))?;
+    return Ok(());
+
+    fn parse_strategy(name: Option<String>) -> Result<Option<naming::Strategy>> {
+        let name = match name {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+
+        match naming::Strategy::parse(&name) {
+            Some(strategy) => Ok(Some(strategy)),
+            None => Err(format!(
+                "unknown naming `{}`, expected one of: {}",
+                name,
+                NAMING_STRATEGIES.join(", ")
+            )
+            .into()),
+        }
+    }
+}
+
+/// Load round-trip tests declared under `[test]`.
+fn load_test<C>(base: &Path, value: &mut toml::value::Table, convert_language: C) -> Result<Test>
+where
+    C: Fn(Language) -> Box<Lang>,
+{
+    let fixtures = take_field::<Vec<RelativePathBuf>>(value, "fixtures")?
+        .iter()
+        .map(|f| f.to_path(base))
+        .collect();
+
+    let workdir = take_field::<Option<RelativePathBuf>>(value, "workdir")?.map(|w| w.to_path(base));
+
+    let mut projects = Vec::new();
+
+    for (index, mut project) in take_field::<Vec<toml::value::Table>>(value, "projects")?
+        .into_iter()
+        .enumerate()
+    {
+        let project = load_test_project(base, &mut project, &convert_language)
+            .map_err(|e| format!("projects[{}]: {}", index, e.display()))?;
+
+        projects.push(project);
+    }
+
+    check_empty(value)?;
+
+    Ok(Test {
+        fixtures,
+        workdir,
+        projects,
+    })
+}
+
+/// Load the workspace declared under `[workspace]`.
+fn load_workspace(base: &Path, value: &mut toml::value::Table) -> Result<Workspace> {
+    let members = take_field::<Vec<RelativePathBuf>>(value, "members")?
+        .iter()
+        .map(|m| m.to_path(base))
+        .collect();
+
+    check_empty(value)?;
+    Ok(Workspace { members })
+}
+
+/// Load a single project declared under `[[test.projects]]`.
+fn load_test_project<C>(
+    base: &Path,
+    value: &mut toml::value::Table,
+    convert_language: C,
+) -> Result<TestProject>
+where
+    C: Fn(Language) -> Box<Lang>,
+{
+    let language =
+        take_field::<Option<Language>>(value, "language")?.ok_or_else(|| "missing `language`")?;
+
+    let name = take_field::<Option<String>>(value, "name")?.unwrap_or_else(|| language.id());
+    let lang = convert_language(language);
+
+    let path = take_field::<Option<RelativePathBuf>>(value, "path")?
+        .ok_or_else(|| "missing `path`")?
+        .to_path(base);
+
+    let output = take_field::<Option<RelativePathBuf>>(value, "output")?
+        .unwrap_or_else(|| RelativePathBuf::from("."));
+
+    let instance = take_field::<Option<String>>(value, "instance")?;
+    let modules = lang.module_specs(base, take_field(value, "modules")?)?;
+
+    check_empty(value)?;
+
+    Ok(TestProject {
+        name,
+        lang,
+        path,
+        output,
+        instance,
+        modules,
+    })
+}
+
 fn take_field<'de, T>(value: &mut toml::value::Table, name: &str) -> Result<T>
 where
     T: Default + serde::Deserialize<'de>,
@@ -665,12 +1338,116 @@ fn take_section<F>(value: &mut toml::value::Table, name: &str, mut func: F) -> R
 where
     F: FnMut(&mut toml::value::Table) -> Result<()>,
 {
-    let mut inner = take_field::<toml::value::Table>(value, "repository")?;
+    let mut inner = take_field::<toml::value::Table>(value, name)?;
     func(&mut inner)?;
     check_empty(&inner).map_err(|e| format!("{}: {}", name, e.display()))?;
     Ok(())
 }
 
+/// A single entry in the `packages` section.
+enum PackageEntry {
+    /// A single package.
+    Package(RpRequiredPackage),
+    /// Every package with the given prefix, declared like `io.reproto.*`.
+    Glob(RpRequiredPackage),
+    /// Every package in the given directory.
+    Directory(PathBuf),
+}
+
+impl PackageEntry {
+    /// Classify a required package, which might be a glob.
+    fn from_required(required: RpRequiredPackage) -> PackageEntry {
+        let RpRequiredPackage { package, range } = required;
+
+        match package.clone().split_last() {
+            (prefix, Some(ref last)) if last == "*" => {
+                PackageEntry::Glob(RpRequiredPackage::new(prefix, range))
+            }
+            _ => PackageEntry::Package(RpRequiredPackage::new(package, range)),
+        }
+    }
+}
+
+/// Parse an entry in the `packages` section, when it is declared as an array.
+///
+/// Entries are either packages, globs, or tables with a `path` to a directory.
+fn parse_package_entry(base: &Path, value: toml::Value) -> Result<PackageEntry> {
+    match value {
+        toml::Value::String(id) => Ok(PackageEntry::from_required(RpRequiredPackage::new(
+            RpPackage::parse(id.as_str()),
+            Range::any(),
+        ))),
+        toml::Value::Table(mut table) => {
+            let path = take_field::<Option<RelativePathBuf>>(&mut table, "path")?
+                .ok_or_else(|| "missing `path`")?;
+            check_empty(&table)?;
+            Ok(PackageEntry::Directory(path.to_path(base)))
+        }
+        other => Err(format!(
+            "expected a package or a table with a `path`, but got a {}",
+            other.type_str()
+        )
+        .into()),
+    }
+}
+
+/// Load the `packages` section.
+///
+/// The section is either a table where every key is a package, or an array of entries as parsed
+/// by `parse_package_entry`.
+fn load_packages(manifest: &mut Manifest, base: &Path, value: Option<toml::Value>) -> Result<()> {
+    let entries = match value {
+        None => return Ok(()),
+        Some(toml::Value::Array(values)) => values
+            .into_iter()
+            .map(|v| parse_package_entry(base, v))
+            .collect::<Result<Vec<_>>>()?,
+        Some(value) => parse_specs::<RpRequiredPackage>(base, value)?
+            .into_iter()
+            .flat_map(|p| p)
+            .map(PackageEntry::from_required)
+            .collect(),
+    };
+
+    let mut packages = Vec::new();
+
+    for entry in entries {
+        match entry {
+            PackageEntry::Package(package) => packages.push(package),
+            PackageEntry::Glob(glob) => manifest.package_globs.push(glob),
+            PackageEntry::Directory(dir) => {
+                // packages in the directory need to be resolvable.
+                manifest.paths.push(dir.clone());
+                manifest.package_dirs.push(dir);
+            }
+        }
+    }
+
+    manifest.packages = Some(packages);
+    Ok(())
+}
+
+/// Parse an entry in the `paths` section.
+///
+/// Entries are either paths, or tables with a `path` and an optional `priority`.
+fn parse_path_entry(base: &Path, value: toml::Value) -> Result<(PathBuf, Option<i32>)> {
+    match value {
+        toml::Value::String(path) => Ok((RelativePathBuf::from(path).to_path(base), None)),
+        toml::Value::Table(mut table) => {
+            let path = take_field::<Option<RelativePathBuf>>(&mut table, "path")?
+                .ok_or_else(|| "missing `path`")?;
+            let priority = take_field::<Option<i32>>(&mut table, "priority")?;
+            check_empty(&table)?;
+            Ok((path.to_path(base), priority))
+        }
+        other => Err(format!(
+            "expected a path or a table with a `path`, but got a {}",
+            other.type_str()
+        )
+        .into()),
+    }
+}
+
 /// Load and apply all options to the given file manifest to build a realized manifest.
 ///
 /// `manifest` is the manifest that will be populated.
@@ -680,15 +1457,31 @@ pub fn load_common_manifest(
     base: &Path,
     value: &mut toml::value::Table,
 ) -> Result<()> {
-    manifest.packages = parse_section(base, take_field(value, "packages")?)?;
+    load_packages(manifest, base, take_field(value, "packages")?)?;
     manifest.files = parse_section(base, take_field(value, "files")?)?;
     manifest.publish = parse_section(base, take_field(value, "publish")?)?;
 
-    manifest.paths.extend(
-        take_field::<Vec<RelativePathBuf>>(value, "paths")?
-            .iter()
-            .map(|r| r.to_path(&base)),
-    );
+    for entry in take_field::<Vec<toml::Value>>(value, "paths")? {
+        let (path, priority) = parse_path_entry(base, entry)?;
+
+        if let Some(priority) = priority {
+            manifest.path_priorities.insert(path.clone(), priority);
+        }
+
+        manifest.paths.push(path);
+    }
+
+    for (package, path) in take_field::<HashMap<String, RelativePathBuf>>(value, "pins")? {
+        let path = path.to_path(base);
+
+        // pinned packages need to be resolvable from the given path.
+        if !manifest.paths.contains(&path) {
+            manifest.paths.push(path.clone());
+        }
+
+        let package = RpPackage::parse(package.as_str());
+        manifest.pins.insert(package, path);
+    }
 
     if let Some(output) = take_field::<Option<RelativePathBuf>>(value, "output")? {
         manifest.output = Some(output.to_path(base));
@@ -708,12 +1501,49 @@ pub fn load_common_manifest(
         manifest.id_converter = Some(id_converter);
     }
 
+    if let Some(field_ids) = take_field::<Option<RelativePathBuf>>(value, "field_ids")? {
+        manifest.field_ids = Some(field_ids.to_path(base));
+    }
+
+    if let Some(consumers) = take_field::<Option<RelativePathBuf>>(value, "consumers")? {
+        manifest.consumers = Some(consumers.to_path(base));
+    }
+
+    for (name, scalar) in take_field::<BTreeMap<String, Scalar>>(value, "scalars")? {
+        let pattern = scalar.pattern.as_ref().map(String::as_str);
+
+        let scalar = trans::scalars::scalar(&name, &scalar.ty, pattern, scalar.languages)
+            .map_err(|e| format!("scalars: {}", e.display()))?;
+
+        manifest.scalars.push(scalar);
+    }
+
+    if let Some(number_format) = take_field::<Option<String>>(value, "number_format")? {
+        manifest.numbers_as_strings = match number_format.as_str() {
+            "number" => false,
+            "string" => true,
+            other => {
+                return Err(format!(
+                    "number_format: expected `number` or `string`, but got `{}`",
+                    other
+                )
+                .into())
+            }
+        };
+    }
+
     take_section(value, "repository", |repository| {
         load_repository(&mut manifest.repository, base, repository)
     })?;
 
-    if let Some(doc) = take_field::<Option<Doc>>(value, "doc")? {
-        manifest.doc = doc;
+    take_section(value, "doc", |doc| load_doc(&mut manifest.doc, base, doc))?;
+
+    take_section(value, "naming", |naming| {
+        load_naming(&mut manifest.naming, naming)
+    })?;
+
+    if let Some(lint) = take_field::<Option<lint::Config>>(value, "lint")? {
+        manifest.lint = lint;
     }
 
     return Ok(());
@@ -721,12 +1551,28 @@ pub fn load_common_manifest(
     /// Load and apply all repository-specific information.
     pub fn load_repository(
         repository: &mut Repository,
-        _base: &Path,
+        base: &Path,
         value: &mut toml::value::Table,
     ) -> Result<()> {
         repository.no_repository = take_field(value, "no_repository")?;
+        repository.offline = take_field(value, "offline")?;
         repository.index = take_field(value, "index")?;
         repository.objects = take_field(value, "objects")?;
+        repository.signing_key = take_field::<Option<RelativePathBuf>>(value, "signing_key")?
+            .map(|p| p.to_path(base));
+        repository.trusted_keys = take_field(value, "trusted_keys")?;
+        Ok(())
+    }
+
+    /// Load all documentation settings.
+    pub fn load_doc(doc: &mut Doc, base: &Path, value: &mut toml::value::Table) -> Result<()> {
+        doc.theme = take_field(value, "theme")?;
+        doc.theme_dir =
+            take_field::<Option<RelativePathBuf>>(value, "theme_dir")?.map(|p| p.to_path(base));
+        doc.syntax_theme = take_field(value, "syntax_theme")?;
+        doc.format = take_field(value, "format")?;
+        doc.all_versions = take_field(value, "all_versions")?;
+        doc.source_url = take_field(value, "source_url")?;
         Ok(())
     }
 
@@ -822,6 +1668,14 @@ mod tests {
         assert_eq!(1, manifest.paths.len());
     }
 
+    #[test]
+    pub fn test_paths_priority() {
+        let manifest = include_manifest!("tests/paths_priority.reproto");
+        assert_eq!(3, manifest.paths.len());
+        assert_eq!(1, manifest.path_priorities.len());
+        assert_eq!(1, manifest.pins.len());
+    }
+
     #[test]
     pub fn test_presets_string() {
         let manifest = include_manifest!("tests/presets_string.reproto");
@@ -852,6 +1706,15 @@ mod tests {
         assert_eq!(Some(1), manifest.packages.map(|p| p.len()));
     }
 
+    #[test]
+    pub fn test_packages_array() {
+        let manifest = include_manifest!("tests/packages_array.reproto");
+        assert_eq!(Some(1), manifest.packages.map(|p| p.len()));
+        assert_eq!(1, manifest.package_globs.len());
+        assert_eq!(1, manifest.package_dirs.len());
+        assert_eq!(1, manifest.paths.len());
+    }
+
     #[test]
     pub fn test_publish_string() {
         let manifest = include_manifest!("tests/publish_string.reproto");
@@ -911,4 +1774,167 @@ mod tests {
             manifest.repository.objects.as_ref().map(String::as_str)
         );
     }
+
+    #[test]
+    pub fn test_lint() {
+        let manifest = include_manifest!("tests/lint.reproto");
+
+        assert_eq!(lint::Level::Deny, manifest.lint.field_naming);
+        assert_eq!(lint::Level::Warn, manifest.lint.missing_docs);
+        assert_eq!(lint::Level::Warn, manifest.lint.type_naming);
+        assert_eq!(2, manifest.lint.max_nesting);
+    }
+
+    #[test]
+    pub fn test_scalars() {
+        let manifest = include_manifest!("tests/scalars.reproto");
+
+        assert_eq!(2, manifest.scalars.len());
+
+        let money = &manifest.scalars[0];
+        assert_eq!("money", money.name);
+        assert_eq!(None, money.mapping("java"));
+
+        let uuid = &manifest.scalars[1];
+        assert_eq!("uuid", uuid.name);
+        assert_eq!(Some("java.util.UUID"), uuid.mapping("java"));
+        assert_eq!(Some("uuid::Uuid"), uuid.mapping("rust"));
+    }
+
+    #[test]
+    pub fn test_number_format() {
+        let manifest = include_manifest!("tests/number_format.reproto");
+        assert!(manifest.numbers_as_strings);
+    }
+
+    #[test]
+    pub fn test_targets() {
+        let manifest = include_manifest!("tests/targets.reproto");
+        assert_eq!(2, manifest.targets.len());
+
+        let targets = manifest.into_targets(None).expect("targets");
+        assert_eq!(2, targets.len());
+        assert!(targets.iter().all(|t| t.lang.is_some()));
+        assert!(targets.iter().all(|t| t.output.is_some()));
+    }
+
+    #[test]
+    pub fn test_targets_language() {
+        let manifest = include_manifest!("tests/targets.reproto");
+        let targets = manifest
+            .into_targets(Some(&Language::Swift))
+            .expect("targets");
+        assert_eq!(1, targets.len());
+        let output = targets[0].output.as_ref().expect("output");
+        assert!(output.ends_with("target"));
+
+        let manifest = include_manifest!("tests/targets.reproto");
+        assert!(manifest.into_targets(Some(&Language::Rust)).is_err());
+    }
+
+    #[test]
+    pub fn test_targets_top_level_language() {
+        let mut manifest = Manifest::default();
+
+        let result = manifest.from_yaml(
+            Cursor::new(b"language = \"java\"\n[[targets]]\nlanguage = \"swift\"\n".to_vec()),
+            |_| Box::new(NoLang),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_test_projects() {
+        let manifest = include_manifest!("tests/test.reproto");
+        assert_eq!(1, manifest.test.fixtures.len());
+        assert_eq!(2, manifest.test.projects.len());
+
+        let projects = manifest.into_test_projects();
+        assert_eq!(2, projects.len());
+        assert_eq!("java", projects[0].0.name);
+        assert_eq!("src/main/java", projects[0].0.output.as_str());
+        assert_eq!("python3", projects[1].0.name);
+        assert!(projects.iter().all(|p| p.1.lang.is_some()));
+        assert!(projects.iter().all(|p| p.1.output.is_none()));
+    }
+
+    #[test]
+    pub fn test_workspace() {
+        let manifest = include_manifest!("tests/workspace.reproto");
+
+        let workspace = manifest.workspace.expect("workspace");
+        assert_eq!(2, workspace.members.len());
+        assert!(workspace.members[0].ends_with("api/users"));
+    }
+
+    #[test]
+    pub fn test_overrides() {
+        let mut manifest = Manifest::default();
+        manifest.path = Some(Path::new(".").join("tests/targets.reproto"));
+        manifest.overrides = vec![
+            Override::parse("output=gen").expect("bad override"),
+            Override::parse("java.output = gen/java").expect("bad override"),
+        ];
+
+        manifest
+            .from_yaml(Cursor::new(include_vec!("tests/targets.reproto")), |_| {
+                Box::new(NoLang)
+            })
+            .expect("failed to read manifest");
+
+        let output = manifest.output.as_ref().expect("output");
+        assert!(output.ends_with("gen"));
+
+        let java = manifest.targets[0].output.as_ref().expect("output");
+        assert!(java.ends_with("gen/java"));
+        assert!(manifest.targets[1].output.is_none());
+    }
+
+    #[test]
+    pub fn test_override_values() {
+        let o = Override::parse("doc.all_versions=true").expect("bad override");
+        assert_eq!(vec!["doc", "all_versions"], o.key);
+        assert_eq!(::toml::Value::Boolean(true), o.value);
+
+        let o = Override::parse("output=gen/").expect("bad override");
+        assert_eq!(::toml::Value::String("gen/".to_string()), o.value);
+
+        assert!(Override::parse("output").is_err());
+        assert!(Override::parse("doc..theme=light").is_err());
+    }
+
+    #[test]
+    pub fn test_validate() {
+        let content = include_str!("tests/invalid.reproto");
+        let source = core::Source::bytes("invalid.reproto", include_vec!("tests/invalid.reproto"));
+        let mut diag = Diagnostics::new(source);
+
+        let mut manifest = Manifest::default();
+        manifest.path = Some(Path::new(".").join("tests/invalid.reproto"));
+
+        assert!(manifest
+            .from_source(&mut diag, |_| Box::new(NoLang))
+            .is_err());
+
+        let errors = diag
+            .items()
+            .filter_map(|d| match *d {
+                core::Diagnostic::Error { ref span, .. } => Some(&content[span.start..span.end]),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["unknown", "modules.foo", "bad_key"], errors);
+    }
+
+    #[test]
+    pub fn test_plugin_language() {
+        assert_eq!(
+            Some(Language::Plugin("sql".to_string())),
+            Language::parse("plugin:sql")
+        );
+        assert_eq!(None, Language::parse("plugin:"));
+        assert_eq!("plugin:sql", Language::Plugin("sql".to_string()).id());
+    }
 }
@@ -0,0 +1,160 @@
+//! Overrides of manifest values, like `--set output=gen/`.
+
+use core::errors::Result;
+use std::fmt;
+use toml;
+use toml::value::{Table, Value};
+use validate::MANIFEST_KEYS;
+
+/// An override of a single manifest value, like `output=gen/` or `java.modules=builder`.
+///
+/// The key is a dot-separated path into the manifest.
+/// If the first component is not a top-level key of the manifest, it selects every
+/// `[[targets]]` entry with that name or language, and the top-level manifest if it is built for
+/// that language.
+#[derive(Debug, Clone)]
+pub struct Override {
+    /// Path to the overridden value.
+    pub key: Vec<String>,
+    /// The new value.
+    pub value: Value,
+}
+
+impl Override {
+    /// Parse an override from `<key>=<value>`.
+    ///
+    /// Values which are valid TOML, like `true`, `42`, or `["a", "b"]` keep their type, anything
+    /// else is a string.
+    pub fn parse(input: &str) -> Result<Override> {
+        let mut parts = input.splitn(2, '=');
+
+        let key = parts.next().unwrap_or_default();
+
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("expected `<key>=<value>`, but got: {}", input))?;
+
+        let key = key
+            .split('.')
+            .map(|k| k.trim().to_string())
+            .collect::<Vec<_>>();
+
+        if key.iter().any(String::is_empty) {
+            return Err(format!("bad key: {}", input).into());
+        }
+
+        let raw = value.trim();
+
+        let value = match toml::from_str::<Table>(&format!("value = {}", raw)) {
+            Ok(mut table) => table.remove("value"),
+            Err(_) => None,
+        };
+
+        let value = value.unwrap_or_else(|| Value::String(raw.to_string()));
+
+        Ok(Override { key, value })
+    }
+
+    /// Apply the override to a parsed manifest.
+    pub fn apply(&self, manifest: &mut Table) -> Result<()> {
+        let (first, rest) = match self.key.split_first() {
+            Some(split) => split,
+            None => return Err("empty key".into()),
+        };
+
+        if rest.is_empty() || MANIFEST_KEYS.contains(&first.as_str()) {
+            return set(manifest, &self.key, self.value.clone());
+        }
+
+        let mut matched = false;
+
+        if let Some(&mut Value::Array(ref mut targets)) = manifest.get_mut("targets") {
+            for target in targets {
+                if let Value::Table(ref mut target) = *target {
+                    if selects(target, first) {
+                        set(target, rest, self.value.clone())?;
+                        matched = true;
+                    }
+                }
+            }
+        }
+
+        if manifest.get("language").and_then(Value::as_str) == Some(first.as_str()) {
+            set(manifest, rest, self.value.clone())?;
+            matched = true;
+        }
+
+        if !matched {
+            return Err(format!("no target or language named `{}`", first).into());
+        }
+
+        return Ok(());
+
+        /// Test if the given target is selected by name or language.
+        fn selects(target: &Table, name: &str) -> bool {
+            target.get("name").and_then(Value::as_str) == Some(name)
+                || target.get("language").and_then(Value::as_str) == Some(name)
+        }
+    }
+}
+
+impl fmt::Display for Override {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}={}", self.key.join("."), self.value)
+    }
+}
+
+/// Set the value at the given path, creating intermediate tables as needed.
+///
+/// Names assigned to `modules` enable those modules with their default configuration, in
+/// addition to the modules which are already enabled.
+fn set(table: &mut Table, key: &[String], value: Value) -> Result<()> {
+    let (first, rest) = match key.split_first() {
+        Some(split) => split,
+        None => return Err("empty key".into()),
+    };
+
+    if !rest.is_empty() {
+        return match *table
+            .entry(first.clone())
+            .or_insert_with(|| Value::Table(Table::new()))
+        {
+            Value::Table(ref mut inner) => set(inner, rest, value),
+            ref other => Err(format!("`{}` is a {}, not a table", first, other.type_str()).into()),
+        };
+    }
+
+    if first == "modules" {
+        if let Some(names) = module_names(&value) {
+            let modules = match *table
+                .entry(first.clone())
+                .or_insert_with(|| Value::Table(Table::new()))
+            {
+                Value::Table(ref mut modules) => modules,
+                ref other => {
+                    return Err(format!("`modules` is a {}, not a table", other.type_str()).into())
+                }
+            };
+
+            for name in names {
+                modules
+                    .entry(name.to_string())
+                    .or_insert_with(|| Value::Table(Table::new()));
+            }
+
+            return Ok(());
+        }
+    }
+
+    table.insert(first.clone(), value);
+    Ok(())
+}
+
+/// Names of modules, if the value is a name or an array of names.
+fn module_names(value: &Value) -> Option<Vec<&str>> {
+    match *value {
+        Value::String(ref name) => Some(vec![name.as_str()]),
+        Value::Array(ref names) => names.iter().map(Value::as_str).collect(),
+        _ => None,
+    }
+}
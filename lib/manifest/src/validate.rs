@@ -0,0 +1,729 @@
+//! Validation of manifests.
+//!
+//! Problems are reported as diagnostics which point into the manifest, instead of as the first
+//! error encountered when loading it.
+//! The TOML parser does not keep track of where values are declared, so the location of every key
+//! is looked up separately through `Locations`.
+
+use core::{Diagnostics, RpPackage, RpRequiredPackage, Span};
+use lint;
+use naming;
+use relative_path::RelativePathBuf;
+use std::collections::HashMap;
+use std::path::Path;
+use toml;
+use {
+    parse_package_entry, parse_path_entry, parse_spec, File, Lang, Language, ModuleDoc, Preset,
+    Publish, Scalar, TryFromToml, NAMING_STRATEGIES,
+};
+
+/// Keys permitted at the top level of the manifest.
+pub const MANIFEST_KEYS: &[&str] = &[
+    "language",
+    "modules",
+    "targets",
+    "packages",
+    "files",
+    "publish",
+    "paths",
+    "pins",
+    "output",
+    "presets",
+    "package_prefix",
+    "id_converter",
+    "field_ids",
+    "consumers",
+    "scalars",
+    "number_format",
+    "naming",
+    "repository",
+    "doc",
+    "lint",
+    "test",
+    "workspace",
+];
+
+/// Keys permitted in a `[[targets]]` entry.
+const TARGET_KEYS: &[&str] = &["name", "language", "output", "modules", "naming"];
+
+/// Keys permitted in the `[test]` section.
+const TEST_KEYS: &[&str] = &["fixtures", "workdir", "projects"];
+
+/// Keys permitted in a `[[test.projects]]` entry.
+const TEST_PROJECT_KEYS: &[&str] = &["name", "language", "path", "output", "instance", "modules"];
+
+/// Keys permitted in the `[workspace]` section.
+const WORKSPACE_KEYS: &[&str] = &["members"];
+
+/// Keys permitted in the `[repository]` section.
+const REPOSITORY_KEYS: &[&str] = &[
+    "no_repository",
+    "offline",
+    "index",
+    "objects",
+    "signing_key",
+    "trusted_keys",
+];
+
+/// Keys permitted in the `[doc]` section.
+const DOC_KEYS: &[&str] = &[
+    "theme",
+    "theme_dir",
+    "syntax_theme",
+    "format",
+    "all_versions",
+    "source_url",
+];
+
+/// Keys permitted in the `[naming]` section.
+const NAMING_KEYS: &[&str] = &["field_ident", "field_name"];
+
+/// Keys permitted in the `[lint]` section.
+const LINT_KEYS: &[&str] = &[
+    "type_naming",
+    "field_naming",
+    "package_naming",
+    "missing_docs",
+    "nesting",
+    "max_nesting",
+];
+
+/// Locations of all keys and table headers in a manifest.
+struct Locations {
+    keys: HashMap<Vec<String>, Span>,
+}
+
+impl Locations {
+    /// Scan the given content for keys and table headers.
+    ///
+    /// Entries in arrays of tables (`[[targets]]`) are identified by their index.
+    fn scan(content: &str) -> Locations {
+        let mut keys = HashMap::new();
+        let mut counts = HashMap::new();
+        let mut table = Vec::new();
+        let mut offset = 0;
+
+        for line in content.split('\n') {
+            let start = offset;
+            offset += line.len() + 1;
+
+            let trimmed = line.trim_left();
+            let indent = start + line.len() - trimmed.len();
+
+            if trimmed.starts_with("[[") {
+                let (name, name_offset) = match header_name(trimmed, "[[", "]]") {
+                    Some(name) => name,
+                    None => continue,
+                };
+
+                let path = split_key(name);
+                let count = counts.entry(path.clone()).or_insert(0usize);
+
+                table = path;
+                table.push(count.to_string());
+                *count += 1;
+
+                let name_start = indent + name_offset;
+                keys.entry(table.clone())
+                    .or_insert(Span::from((name_start, name_start + name.len())));
+                continue;
+            }
+
+            if trimmed.starts_with("[") {
+                let (name, name_offset) = match header_name(trimmed, "[", "]") {
+                    Some(name) => name,
+                    None => continue,
+                };
+
+                table = split_key(name);
+
+                let name_start = indent + name_offset;
+                keys.entry(table.clone())
+                    .or_insert(Span::from((name_start, name_start + name.len())));
+                continue;
+            }
+
+            if trimmed.starts_with("#") {
+                continue;
+            }
+
+            let key = match trimmed.find('=') {
+                Some(index) => trimmed[..index].trim_right(),
+                None => continue,
+            };
+
+            if key.is_empty() {
+                continue;
+            }
+
+            let mut path = table.clone();
+            path.extend(split_key(key));
+
+            keys.entry(path)
+                .or_insert(Span::from((indent, indent + key.len())));
+        }
+
+        return Locations { keys };
+
+        /// Extract the name of a table header, and its offset into the line.
+        fn header_name<'a>(line: &'a str, open: &str, close: &str) -> Option<(&'a str, usize)> {
+            let end = line.find(close)?;
+            let inner = &line[open.len()..end];
+            let name = inner.trim();
+            let offset = open.len() + inner.len() - inner.trim_left().len();
+            Some((name, offset))
+        }
+
+        /// Split a possibly dotted and quoted key into its components.
+        fn split_key(key: &str) -> Vec<String> {
+            let mut out = Vec::new();
+            let mut current = String::new();
+            let mut quote = None;
+
+            for c in key.chars() {
+                match (quote, c) {
+                    (None, '"') | (None, '\'') => quote = Some(c),
+                    (Some(q), c) if q == c => quote = None,
+                    (None, '.') => {
+                        out.push(current.trim().to_string());
+                        current.clear();
+                    }
+                    (_, c) => current.push(c),
+                }
+            }
+
+            out.push(current.trim().to_string());
+            out
+        }
+    }
+
+    /// Find the span of the given path.
+    ///
+    /// Falls back to the closest parent which could be located, since keys might be declared in
+    /// inline tables which are not scanned.
+    fn find(&self, path: &[String]) -> Span {
+        let mut path = path.to_vec();
+
+        loop {
+            if let Some(span) = self.keys.get(&path) {
+                return *span;
+            }
+
+            if path.pop().is_none() {
+                return Span::empty();
+            }
+        }
+    }
+}
+
+/// A validator of a single manifest.
+struct Validator<'a> {
+    diag: &'a mut Diagnostics,
+    locations: Locations,
+    base: Option<&'a Path>,
+    convert_language: &'a Fn(Language) -> Box<Lang>,
+}
+
+impl<'a> Validator<'a> {
+    /// Report an error at the given path.
+    fn err<E: ::std::fmt::Display>(&mut self, path: &[String], error: E) {
+        let span = self.locations.find(path);
+        self.diag.err(span, error);
+    }
+
+    /// Report a warning at the given path.
+    fn warn<W: ::std::fmt::Display>(&mut self, path: &[String], warning: W) {
+        let span = self.locations.find(path);
+        self.diag.warn(span, warning);
+    }
+
+    /// Report every key in the table which is not permitted.
+    fn unknown_keys(&mut self, path: &[String], table: &toml::value::Table, known: &[&str]) {
+        for key in table.keys() {
+            if known.contains(&key.as_str()) {
+                continue;
+            }
+
+            let error = format!(
+                "unknown key `{}`, expected one of: {}",
+                key,
+                known.join(", ")
+            );
+
+            self.err(&child(path, key), error);
+        }
+    }
+
+    /// Validate the top level of the manifest.
+    fn manifest(&mut self, mut value: toml::value::Table, lang: Option<Box<Lang>>) {
+        self.unknown_keys(&[], &value, MANIFEST_KEYS);
+
+        let root = Vec::new();
+
+        let language = value
+            .remove("language")
+            .and_then(|language| self.language(&root, language));
+
+        // a language which has already been set takes precedence, like when loading.
+        let lang = lang.or(language);
+
+        if let Some(modules) = value.remove("modules") {
+            self.modules(&root, lang.as_ref().map(|l| l.as_ref()), modules);
+        }
+
+        if let Some(targets) = value.remove("targets") {
+            self.targets(targets);
+        }
+
+        if let Some(packages) = value.remove("packages") {
+            self.packages(&root, packages);
+        }
+
+        if let Some(files) = value.remove("files") {
+            self.specs::<File>(&root, "files", files);
+        }
+
+        if let Some(publish) = value.remove("publish") {
+            self.specs::<Publish>(&root, "publish", publish);
+        }
+
+        if let Some(presets) = value.remove("presets") {
+            self.specs::<Preset>(&root, "presets", presets);
+        }
+
+        if let Some(paths) = value.remove("paths") {
+            self.paths(&root, paths);
+        }
+
+        self.field::<HashMap<String, RelativePathBuf>>(&root, &mut value, "pins");
+        self.field::<RelativePathBuf>(&root, &mut value, "output");
+        self.field::<RpPackage>(&root, &mut value, "package_prefix");
+        self.field::<String>(&root, &mut value, "id_converter");
+        self.field::<RelativePathBuf>(&root, &mut value, "field_ids");
+        self.field::<RelativePathBuf>(&root, &mut value, "consumers");
+        self.field::<HashMap<String, Scalar>>(&root, &mut value, "scalars");
+        self.field::<String>(&root, &mut value, "number_format");
+
+        if let Some(repository) = self.section(&mut value, "repository") {
+            self.unknown_keys(&["repository".to_string()], &repository, REPOSITORY_KEYS);
+        }
+
+        if let Some(doc) = self.section(&mut value, "doc") {
+            self.unknown_keys(&["doc".to_string()], &doc, DOC_KEYS);
+        }
+
+        if let Some(naming) = self.section(&mut value, "naming") {
+            self.naming(vec!["naming".to_string()], naming);
+        }
+
+        if let Some(lint) = self.section(&mut value, "lint") {
+            self.lint(lint);
+        }
+
+        if let Some(test) = self.section(&mut value, "test") {
+            self.test(test);
+        }
+
+        if let Some(mut workspace) = self.section(&mut value, "workspace") {
+            let path = vec!["workspace".to_string()];
+            self.unknown_keys(&path, &workspace, WORKSPACE_KEYS);
+            self.field::<Vec<RelativePathBuf>>(&path, &mut workspace, "members");
+        }
+    }
+
+    /// Validate the language, and convert it if valid.
+    fn language(&mut self, path: &[String], value: toml::Value) -> Option<Box<Lang>> {
+        let path = child(path, "language");
+
+        let language = match value {
+            toml::Value::String(language) => language,
+            other => {
+                let error = format!("expected a string, but got a {}", other.type_str());
+                self.err(&path, error);
+                return None;
+            }
+        };
+
+        match Language::parse(language.as_str()) {
+            Some(language) => Some((self.convert_language)(language)),
+            None => {
+                self.err(&path, format!("not a valid language: {}", language));
+                None
+            }
+        }
+    }
+
+    /// Validate every module individually, so that errors point to the faulty module.
+    fn modules(&mut self, path: &[String], lang: Option<&Lang>, value: toml::Value) {
+        let path = child(path, "modules");
+
+        let modules = match value {
+            toml::Value::Table(modules) => modules,
+            other => {
+                let error = format!("expected a table, but got a {}", other.type_str());
+                self.err(&path, error);
+                return;
+            }
+        };
+
+        let lang = match lang {
+            Some(lang) => lang,
+            None => {
+                self.warn(&path, "modules have no effect without a `language`");
+                return;
+            }
+        };
+
+        let base = match self.base {
+            Some(base) => base,
+            None => return,
+        };
+
+        for (id, value) in modules {
+            let module_path = child(&path, &id);
+
+            if let Some(doc) = lang.modules().iter().find(|m| m.name == id) {
+                if let toml::Value::Table(ref options) = value {
+                    self.module_options(&module_path, doc, options);
+                }
+            }
+
+            let mut single = toml::value::Table::new();
+            single.insert(id, value);
+
+            if let Err(e) = lang.module_specs(base, Some(toml::Value::Table(single))) {
+                self.err(&module_path, e.display());
+            }
+        }
+    }
+
+    /// Report every option which is not accepted by the module.
+    fn module_options(&mut self, path: &[String], doc: &ModuleDoc, options: &toml::value::Table) {
+        for key in options.keys() {
+            if doc.options.iter().any(|o| o.name == key.as_str()) {
+                continue;
+            }
+
+            let error = if doc.options.is_empty() {
+                format!("module `{}` does not accept any options", doc.name)
+            } else {
+                let known = doc.options.iter().map(|o| o.name).collect::<Vec<_>>();
+
+                format!(
+                    "unknown option `{}` for module `{}`, expected one of: {}",
+                    key,
+                    doc.name,
+                    known.join(", ")
+                )
+            };
+
+            self.err(&child(path, key), error);
+        }
+    }
+
+    /// Validate all declared targets.
+    fn targets(&mut self, value: toml::Value) {
+        for (path, mut target) in self.tables(&["targets".to_string()], value) {
+            self.unknown_keys(&path, &target, TARGET_KEYS);
+            self.field::<String>(&path, &mut target, "name");
+            self.field::<RelativePathBuf>(&path, &mut target, "output");
+            self.language_and_modules(&path, &mut target);
+
+            match target.remove("naming") {
+                Some(toml::Value::Table(naming)) => self.naming(child(&path, "naming"), naming),
+                Some(other) => {
+                    let error = format!("expected a table, but got a {}", other.type_str());
+                    self.err(&child(&path, "naming"), error);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Validate the `[test]` section, and all declared test projects.
+    fn test(&mut self, mut test: toml::value::Table) {
+        let path = vec!["test".to_string()];
+
+        self.unknown_keys(&path, &test, TEST_KEYS);
+        self.field::<Vec<RelativePathBuf>>(&path, &mut test, "fixtures");
+        self.field::<RelativePathBuf>(&path, &mut test, "workdir");
+
+        let projects = match test.remove("projects") {
+            Some(projects) => projects,
+            None => return,
+        };
+
+        for (path, mut project) in self.tables(&child(&path, "projects"), projects) {
+            self.unknown_keys(&path, &project, TEST_PROJECT_KEYS);
+            self.field::<String>(&path, &mut project, "name");
+            self.field::<RelativePathBuf>(&path, &mut project, "output");
+            self.field::<String>(&path, &mut project, "instance");
+
+            if project.contains_key("path") {
+                self.field::<RelativePathBuf>(&path, &mut project, "path");
+            } else {
+                self.err(&path, "missing `path`");
+            }
+
+            self.language_and_modules(&path, &mut project);
+        }
+    }
+
+    /// Take the entries of an array of tables, like `[[targets]]`, together with their paths.
+    fn tables(
+        &mut self,
+        path: &[String],
+        value: toml::Value,
+    ) -> Vec<(Vec<String>, toml::value::Table)> {
+        let entries = match value {
+            toml::Value::Array(entries) => entries,
+            other => {
+                let error = format!("expected an array, but got a {}", other.type_str());
+                self.err(path, error);
+                return Vec::new();
+            }
+        };
+
+        let mut out = Vec::new();
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let path = child(path, &index.to_string());
+
+            match entry {
+                toml::Value::Table(entry) => out.push((path, entry)),
+                other => {
+                    let error = format!("expected a table, but got a {}", other.type_str());
+                    self.err(&path, error);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Validate the required language of an entry, and the modules for that language.
+    fn language_and_modules(&mut self, path: &[String], table: &mut toml::value::Table) {
+        let lang = match table.remove("language") {
+            Some(language) => self.language(path, language),
+            None => {
+                self.err(path, "missing `language`");
+                None
+            }
+        };
+
+        if let Some(modules) = table.remove("modules") {
+            if let Some(lang) = lang {
+                self.modules(path, Some(lang.as_ref()), modules);
+            }
+        }
+    }
+
+    /// Validate the `packages` section, which is either a table of specs or an array of entries.
+    fn packages(&mut self, path: &[String], value: toml::Value) {
+        let entries = match value {
+            toml::Value::Array(entries) => entries,
+            other => {
+                self.specs::<RpRequiredPackage>(path, "packages", other);
+                return;
+            }
+        };
+
+        let path = child(path, "packages");
+
+        let base = match self.base {
+            Some(base) => base,
+            None => return,
+        };
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            if let Err(e) = parse_package_entry(base, entry) {
+                self.err(&child(&path, &index.to_string()), e.display());
+            }
+        }
+    }
+
+    /// Validate the `paths` section, which is an array of paths or tables with a `path`.
+    fn paths(&mut self, path: &[String], value: toml::Value) {
+        let path = child(path, "paths");
+
+        let entries = match value {
+            toml::Value::Array(entries) => entries,
+            other => {
+                let error = format!("expected an array, but got a {}", other.type_str());
+                self.err(&path, error);
+                return;
+            }
+        };
+
+        let base = match self.base {
+            Some(base) => base,
+            None => return,
+        };
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            if let Err(e) = parse_path_entry(base, entry) {
+                self.err(&child(&path, &index.to_string()), e.display());
+            }
+        }
+    }
+
+    /// Validate a section of specifications, like `packages`, where every key is a package.
+    fn specs<T: 'static>(&mut self, path: &[String], name: &str, value: toml::Value)
+    where
+        T: TryFromToml,
+    {
+        let path = child(path, name);
+
+        let specs = match value {
+            toml::Value::Table(specs) => specs,
+            other => {
+                let error = format!("expected a table, but got a {}", other.type_str());
+                self.err(&path, error);
+                return;
+            }
+        };
+
+        let base = match self.base {
+            Some(base) => base,
+            None => return,
+        };
+
+        for (id, value) in specs {
+            if let Err(e) = parse_spec::<T>(base, id.as_str(), value) {
+                self.err(&child(&path, &id), e.display());
+            }
+        }
+    }
+
+    /// Validate that the given field has the expected type.
+    fn field<'de, T>(&mut self, path: &[String], table: &mut toml::value::Table, name: &str)
+    where
+        T: ::serde::Deserialize<'de>,
+    {
+        if let Some(value) = table.remove(name) {
+            if let Err(e) = value.try_into::<T>() {
+                self.err(&child(path, name), e);
+            }
+        }
+    }
+
+    /// Take the given section, which must be a table.
+    fn section(
+        &mut self,
+        table: &mut toml::value::Table,
+        name: &str,
+    ) -> Option<toml::value::Table> {
+        match table.remove(name)? {
+            toml::Value::Table(section) => Some(section),
+            other => {
+                let error = format!("expected a table, but got a {}", other.type_str());
+                self.err(&[name.to_string()], error);
+                None
+            }
+        }
+    }
+
+    /// Validate the naming of fields, declared under `[naming]` or for a single target.
+    fn naming(&mut self, path: Vec<String>, naming: toml::value::Table) {
+        self.unknown_keys(&path, &naming, NAMING_KEYS);
+
+        for (key, value) in naming {
+            if !NAMING_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+
+            let error = match value {
+                toml::Value::String(ref name) if naming::Strategy::parse(name).is_some() => {
+                    continue;
+                }
+                toml::Value::String(ref name) => format!(
+                    "unknown naming `{}`, expected one of: {}",
+                    name,
+                    NAMING_STRATEGIES.join(", ")
+                ),
+                other => format!("expected a string, but got a {}", other.type_str()),
+            };
+
+            self.err(&child(&path, &key), error);
+        }
+    }
+
+    /// Validate every option of the `[lint]` section.
+    fn lint(&mut self, lint: toml::value::Table) {
+        let path = vec!["lint".to_string()];
+
+        self.unknown_keys(&path, &lint, LINT_KEYS);
+
+        for (key, value) in lint {
+            if !LINT_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+
+            let mut single = toml::value::Table::new();
+            single.insert(key.clone(), value);
+
+            if let Err(e) = toml::Value::Table(single).try_into::<lint::Config>() {
+                self.err(&child(&path, &key), e);
+            }
+        }
+    }
+}
+
+/// Build the path of a child key.
+fn child(path: &[String], name: &str) -> Vec<String> {
+    let mut path = path.to_vec();
+    path.push(name.to_string());
+    path
+}
+
+/// Validate the given manifest content, reporting every problem into `diag`.
+///
+/// `base` is the directory which paths in the manifest are relative to, modules and packages are
+/// only validated if it is available.
+/// `lang` is the language which has already been selected, and takes precedence over the one in
+/// the manifest.
+pub fn validate(
+    diag: &mut Diagnostics,
+    content: &str,
+    base: Option<&Path>,
+    lang: Option<Box<Lang>>,
+    convert_language: &Fn(Language) -> Box<Lang>,
+) {
+    let value = match toml::from_str::<toml::value::Table>(content) {
+        Ok(value) => value,
+        Err(e) => {
+            let span = match e.line_col() {
+                Some((line, col)) => line_col_to_span(content, line, col),
+                None => Span::empty(),
+            };
+
+            diag.err(span, e);
+            return;
+        }
+    };
+
+    let mut validator = Validator {
+        diag,
+        locations: Locations::scan(content),
+        base,
+        convert_language,
+    };
+
+    validator.manifest(value, lang);
+}
+
+/// Convert a zero-based line and column into a span which covers the rest of the line.
+fn line_col_to_span(content: &str, line: usize, col: usize) -> Span {
+    let mut offset = 0;
+
+    for (index, l) in content.split('\n').enumerate() {
+        if index == line {
+            let start = offset + l.char_indices().nth(col).map(|(i, _)| i).unwrap_or(l.len());
+            return Span::from((start, offset + l.len()));
+        }
+
+        offset += l.len() + 1;
+    }
+
+    Span::from((content.len(), content.len()))
+}
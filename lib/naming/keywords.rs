@@ -0,0 +1,515 @@
+//! Reserved words of the target languages, and how identifiers which collide with them are
+//! escaped.
+//!
+//! Every target language declares its reserved words here, so that fields, endpoints, and packages
+//! are escaped the same way regardless of where the identifier is built.
+
+use std::collections::HashMap;
+
+/// How an identifier which collides with a reserved word is escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// Prefix the identifier with an underscore, like `_class`.
+    Prefix,
+    /// Suffix the identifier with an underscore, like `class_`.
+    Suffix,
+}
+
+/// The reserved words of a target language.
+#[derive(Debug)]
+pub struct Keywords {
+    /// Reserved words, which must be sorted.
+    words: &'static [&'static str],
+    /// How identifiers which collide with a reserved word are escaped.
+    escape: Escape,
+}
+
+impl Keywords {
+    /// All reserved words.
+    pub fn words(&self) -> &'static [&'static str] {
+        self.words
+    }
+
+    /// Check if the given identifier is a reserved word.
+    pub fn is_keyword(&self, ident: &str) -> bool {
+        self.words.binary_search(&ident).is_ok()
+    }
+
+    /// Escape the given identifier, if it is a reserved word.
+    pub fn escape(&self, ident: &str) -> Option<String> {
+        if !self.is_keyword(ident) {
+            return None;
+        }
+
+        let escaped = match self.escape {
+            Escape::Prefix => format!("_{}", ident),
+            Escape::Suffix => format!("{}_", ident),
+        };
+
+        Some(escaped)
+    }
+
+    /// The escaped form of every reserved word.
+    pub fn replacements(&self) -> HashMap<String, String> {
+        self.words
+            .iter()
+            .filter_map(|w| self.escape(w).map(|escaped| (w.to_string(), escaped)))
+            .collect()
+    }
+}
+
+/// Reserved words of Java.
+pub static JAVA: Keywords = Keywords {
+    words: &[
+        "abstract",
+        "assert",
+        "boolean",
+        "break",
+        "byte",
+        "case",
+        "catch",
+        "char",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "extends",
+        "false",
+        "final",
+        "finally",
+        "float",
+        "for",
+        "goto",
+        "if",
+        "implements",
+        "import",
+        "instanceof",
+        "int",
+        "interface",
+        "long",
+        "native",
+        "new",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "short",
+        "static",
+        "strictfp",
+        "super",
+        "switch",
+        "synchronized",
+        "this",
+        "throw",
+        "throws",
+        "transient",
+        "true",
+        "try",
+        "void",
+        "volatile",
+        "while",
+    ],
+    escape: Escape::Prefix,
+};
+
+/// Reserved words of Kotlin.
+pub static KOTLIN: Keywords = Keywords {
+    words: &[
+        "as",
+        "break",
+        "class",
+        "continue",
+        "do",
+        "else",
+        "false",
+        "for",
+        "fun",
+        "if",
+        "in",
+        "interface",
+        "is",
+        "null",
+        "object",
+        "package",
+        "return",
+        "super",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typealias",
+        "typeof",
+        "val",
+        "var",
+        "when",
+        "while",
+    ],
+    escape: Escape::Suffix,
+};
+
+/// Reserved words of C#.
+pub static CSHARP: Keywords = Keywords {
+    words: &[
+        "abstract",
+        "as",
+        "base",
+        "bool",
+        "break",
+        "byte",
+        "case",
+        "catch",
+        "char",
+        "checked",
+        "class",
+        "const",
+        "continue",
+        "decimal",
+        "default",
+        "delegate",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "event",
+        "explicit",
+        "extern",
+        "false",
+        "finally",
+        "fixed",
+        "float",
+        "for",
+        "foreach",
+        "goto",
+        "if",
+        "implicit",
+        "in",
+        "int",
+        "interface",
+        "internal",
+        "is",
+        "lock",
+        "long",
+        "namespace",
+        "new",
+        "null",
+        "object",
+        "operator",
+        "out",
+        "override",
+        "params",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "ref",
+        "return",
+        "sbyte",
+        "sealed",
+        "short",
+        "sizeof",
+        "stackalloc",
+        "static",
+        "string",
+        "struct",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "uint",
+        "ulong",
+        "unchecked",
+        "unsafe",
+        "ushort",
+        "using",
+        "virtual",
+        "void",
+        "volatile",
+        "while",
+    ],
+    escape: Escape::Prefix,
+};
+
+/// Reserved words of Swift.
+pub static SWIFT: Keywords = Keywords {
+    words: &[
+        "as",
+        "associatedtype",
+        "associativity",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "continue",
+        "convenience",
+        "default",
+        "defer",
+        "deinit",
+        "do",
+        "dynamic",
+        "else",
+        "enum",
+        "extension",
+        "fallthrough",
+        "false",
+        "fileprivate",
+        "final",
+        "for",
+        "func",
+        "get",
+        "guard",
+        "if",
+        "import",
+        "in",
+        "indirect",
+        "infix",
+        "init",
+        "inout",
+        "internal",
+        "is",
+        "lazy",
+        "left",
+        "let",
+        "mutating",
+        "nil",
+        "none",
+        "nonmutating",
+        "open",
+        "operator",
+        "optional",
+        "override",
+        "postfix",
+        "precedence",
+        "prefix",
+        "private",
+        "protocol",
+        "public",
+        "repeat",
+        "required",
+        "rethrows",
+        "return",
+        "right",
+        "self",
+        "set",
+        "static",
+        "struct",
+        "subscript",
+        "super",
+        "switch",
+        "throw",
+        "throws",
+        "true",
+        "try",
+        "typealias",
+        "unowned",
+        "var",
+        "weak",
+        "where",
+        "while",
+    ],
+    escape: Escape::Suffix,
+};
+
+/// Reserved words of Rust.
+pub static RUST: Keywords = Keywords {
+    words: &[
+        "Self", "abstract", "alignof", "as", "async", "await", "become", "box", "break", "const",
+        "continue", "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for",
+        "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "offsetof",
+        "override", "priv", "proc", "pub", "pure", "ref", "return", "self", "sizeof", "static",
+        "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+        "virtual", "where", "while", "yield",
+    ],
+    escape: Escape::Prefix,
+};
+
+/// Reserved words of Python 2 and 3.
+///
+/// NB: combined set of keywords for Python 2/3 to avoid having two codegen implementations.
+pub static PYTHON: Keywords = Keywords {
+    words: &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "exec", "finally", "for", "from",
+        "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "print",
+        "raise", "return", "try", "while", "with", "yield",
+    ],
+    escape: Escape::Prefix,
+};
+
+/// Reserved words of JavaScript.
+pub static JS: Keywords = Keywords {
+    words: &[
+        "abstract",
+        "await",
+        "boolean",
+        "break",
+        "byte",
+        "case",
+        "catch",
+        "char",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "final",
+        "finally",
+        "float",
+        "for",
+        "function",
+        "goto",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "int",
+        "interface",
+        "let",
+        "long",
+        "native",
+        "new",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "short",
+        "static",
+        "super",
+        "switch",
+        "synchronized",
+        "this",
+        "throw",
+        "throws",
+        "transient",
+        "true",
+        "try",
+        "typeof",
+        "var",
+        "void",
+        "volatile",
+        "while",
+        "with",
+        "yield",
+    ],
+    escape: Escape::Prefix,
+};
+
+/// Reserved words of Go.
+pub static GO: Keywords = Keywords {
+    words: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "type",
+        "var",
+    ],
+    escape: Escape::Suffix,
+};
+
+/// Reserved words of Haskell.
+pub static HASKELL: Keywords = Keywords {
+    words: &[
+        "case", "class", "data", "default", "deriving", "do", "else", "foreign", "if", "import",
+        "in", "infix", "infixl", "infixr", "instance", "let", "module", "newtype", "of", "then",
+        "type", "where",
+    ],
+    escape: Escape::Suffix,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> Vec<(&'static str, &'static Keywords)> {
+        vec![
+            ("java", &JAVA),
+            ("kotlin", &KOTLIN),
+            ("csharp", &CSHARP),
+            ("swift", &SWIFT),
+            ("rust", &RUST),
+            ("python", &PYTHON),
+            ("js", &JS),
+            ("go", &GO),
+            ("haskell", &HASKELL),
+        ]
+    }
+
+    #[test]
+    pub fn test_sorted() {
+        for (lang, keywords) in all() {
+            for pair in keywords.words().windows(2) {
+                assert!(
+                    pair[0] < pair[1],
+                    "{}: `{}` must be sorted before `{}`",
+                    lang,
+                    pair[1],
+                    pair[0]
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_escaped_are_not_keywords() {
+        for (lang, keywords) in all() {
+            for word in keywords.words() {
+                let escaped = keywords.escape(word).expect("keyword not escaped");
+                assert!(
+                    !keywords.is_keyword(&escaped),
+                    "{}: `{}` escapes to a keyword",
+                    lang,
+                    word
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_escape() {
+        assert_eq!(Some("_class".to_string()), JAVA.escape("class"));
+        assert_eq!(Some("class_".to_string()), SWIFT.escape("class"));
+        assert_eq!(Some("type_".to_string()), GO.escape("type"));
+        assert_eq!(None, JAVA.escape("klass"));
+        assert_eq!(Some("_async".to_string()), RUST.escape("async"));
+        assert_eq!(Some("_await".to_string()), PYTHON.escape("await"));
+        assert_eq!(RUST.words().len(), RUST.replacements().len());
+    }
+}
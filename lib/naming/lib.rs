@@ -1,3 +1,7 @@
+pub mod keywords;
+
+pub use self::keywords::Keywords;
+
 pub trait Operator {
     type FirstIter: Iterator<Item = char>;
     type RestIter: Iterator<Item = char>;
@@ -165,6 +169,61 @@ impl Operator for ToUpperSnake {
     }
 }
 
+/// Keeps identifiers as they are written in the specification.
+#[derive(Clone, Copy)]
+pub struct Keep(());
+
+impl Naming for Keep {
+    fn convert(&self, input: &str) -> String {
+        input.to_string()
+    }
+
+    fn copy(&self) -> Box<Naming> {
+        Box::new(*self)
+    }
+}
+
+/// A naming convention which can be selected by name, like in a manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    LowerCamel,
+    UpperCamel,
+    LowerSnake,
+    UpperSnake,
+    Keep,
+}
+
+impl Strategy {
+    /// Parse a strategy from its name, like `lower_camel`.
+    pub fn parse(name: &str) -> Option<Strategy> {
+        use self::Strategy::*;
+
+        let strategy = match name {
+            "lower_camel" => LowerCamel,
+            "upper_camel" => UpperCamel,
+            "lower_snake" => LowerSnake,
+            "upper_snake" => UpperSnake,
+            "keep" => Keep,
+            _ => return None,
+        };
+
+        Some(strategy)
+    }
+
+    /// Build the naming convention of the strategy.
+    pub fn naming(&self) -> Box<Naming> {
+        use self::Strategy::*;
+
+        match *self {
+            LowerCamel => Box::new(to_lower_camel()),
+            UpperCamel => Box::new(to_upper_camel()),
+            LowerSnake => Box::new(to_lower_snake()),
+            UpperSnake => Box::new(to_upper_snake()),
+            Keep => Box::new(keep()),
+        }
+    }
+}
+
 /// A source for camel-cased strings.
 fn operate<O>(input: &str) -> String
 where
@@ -214,6 +273,10 @@ pub fn to_upper_snake() -> ToUpperSnake {
     ToUpperSnake(())
 }
 
+pub fn keep() -> Keep {
+    Keep(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,4 +328,17 @@ mod tests {
         assert_eq!("FOO_BAR_BAZ", naming.convert("fooBarBaz"));
         assert_eq!("FOO_BAR_BAZ", naming.convert("foo_bar_baz"));
     }
+
+    #[test]
+    pub fn strategy_test() {
+        let naming = Strategy::parse("lower_camel")
+            .expect("bad strategy")
+            .naming();
+        assert_eq!("fooBarBaz", naming.convert("foo_bar_baz"));
+
+        let naming = Strategy::parse("keep").expect("bad strategy").naming();
+        assert_eq!("foo_barBaz", naming.convert("foo_barBaz"));
+
+        assert_eq!(None, Strategy::parse("kebab"));
+    }
 }
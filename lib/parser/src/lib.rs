@@ -11,7 +11,7 @@ mod parser;
 mod utils;
 
 use core::errors::Result;
-use core::Diagnostics;
+use core::{Diagnostics, Loc};
 use std::io::Read;
 use std::result;
 
@@ -26,77 +26,46 @@ where
 }
 
 /// Parse the given object.
+///
+/// Fails if the file contains any syntax errors.
 pub fn parse<'input>(
     diag: &mut Diagnostics,
     input: &'input str,
 ) -> result::Result<ast::File<'input>, ()> {
-    use self::lexer::errors::Error::*;
-    use lalrpop_util::ParseError::*;
+    let file = parse_partial(diag, input)?;
 
+    if !file.errors.is_empty() {
+        return Err(());
+    }
+
+    Ok(file)
+}
+
+/// Parse the given object, recovering from syntax errors in declarations.
+///
+/// Declarations which could not be parsed are skipped, and their errors are reported to `diag`
+/// and recorded in `errors` of the returned file. This permits partial files to be processed,
+/// like the ones being edited in the language server.
+pub fn parse_partial<'input>(
+    diag: &mut Diagnostics,
+    input: &'input str,
+) -> result::Result<ast::File<'input>, ()> {
     let lexer = lexer::lex(input);
     let parser = parser::FileParser::new();
 
     match parser.parse(lexer) {
-        Ok(file) => Ok(file),
-        Err(e) => match e {
-            InvalidToken { location } => {
-                let span = (location, location);
-                diag.err(span, "syntax error");
-                Err(())
-            }
-            ExtraToken {
-                token: (start, token, end),
-            } => {
-                diag.err((start, end), format!("extra token: {:?}", token));
-                Err(())
-            }
-            UnrecognizedToken { token, expected } => {
-                match token {
-                    Some((start, token, end)) => {
-                        diag.err(
-                            (start, end),
-                            format!(
-                                "syntax error, got token {:?}, expected: {}",
-                                token,
-                                expected.join(", ")
-                            ),
-                        );
-                    }
-                    None => {
-                        let m = format!("syntax error, expected: {}", expected.join(", "));
-                        diag.err((0, 0), m);
-                    }
-                };
-
-                return Err(());
+        Ok(file) => {
+            for e in &file.errors {
+                diag.err(Loc::span(e), e.as_str());
             }
-            User { error } => match error {
-                UnterminatedString { start } => {
-                    diag.err((start, start), "unterminated string");
-                    return Err(());
-                }
-                UnterminatedEscape { start } => {
-                    diag.err((start, start), "unterminated escape sequence");
-                    return Err(());
-                }
-                InvalidEscape { pos, message } => {
-                    diag.err((pos, pos), message);
-                    return Err(());
-                }
-                UnterminatedCodeBlock { start } => {
-                    diag.err((start, start), "unterminated code block");
-                    return Err(());
-                }
-                InvalidNumber { pos, message } => {
-                    diag.err((pos, pos), message);
-                    return Err(());
-                }
-                Unexpected { pos } => {
-                    diag.err((pos, pos), "unexpected input");
-                    return Err(());
-                }
-            },
-        },
+
+            Ok(file)
+        }
+        Err(e) => {
+            let (span, message) = utils::parse_error(e);
+            diag.err(span, message);
+            Err(())
+        }
     }
 }
 
@@ -167,6 +136,42 @@ mod tests {
         panic!("Expected Type::Array(Type::String)");
     }
 
+    #[test]
+    fn test_set() {
+        if let Type::Generic { name, arguments } = parse_type("set<string>") {
+            assert_eq!("set", Loc::borrow(&name).to_string());
+            assert_eq!(1, arguments.len());
+            return;
+        }
+
+        panic!("Expected Type::Generic");
+    }
+
+    #[test]
+    fn test_scalar() {
+        if let Type::Scalar { name } = parse_type("uuid") {
+            assert_eq!("uuid", Loc::borrow(&name).to_string());
+            return;
+        }
+
+        panic!("Expected Type::Scalar");
+    }
+
+    #[test]
+    fn test_wide_numbers() {
+        assert_eq!(Type::Unsigned { size: 128 }, parse_type("u128"));
+        assert_eq!(Type::Signed { size: 128 }, parse_type("i128"));
+        assert_eq!(Type::Decimal, parse_type("decimal"));
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(Type::DateTime, parse_type("datetime"));
+        assert_eq!(Type::Date, parse_type("date"));
+        assert_eq!(Type::Time, parse_type("time"));
+        assert_eq!(Type::Duration, parse_type("duration"));
+    }
+
     #[test]
     fn test_map() {
         let ty = parse_type("{string: u32}");
@@ -201,6 +206,16 @@ mod tests {
         parse_member("java{{\na { b { c } d } e\n}}");
     }
 
+    #[test]
+    fn test_reserved() {
+        if let TypeMember::Reserved(reserved) = parse_member("reserved foo, \"bar\";") {
+            assert_eq!(2, reserved.len());
+            return;
+        }
+
+        panic!("Expected TypeMember::Reserved");
+    }
+
     #[test]
     fn test_interface() {
         let input = ::std::str::from_utf8(INTERFACE1).unwrap();
@@ -208,6 +223,99 @@ mod tests {
         assert_eq!(1, file.decls.len());
     }
 
+    #[test]
+    fn test_enum_aliases() {
+        let file = parse_file("enum Status as string { Active as \"active\", \"on\"; Inactive; }");
+        assert_eq!(1, file.decls.len());
+
+        if let Decl::Enum(ref body) = file.decls[0] {
+            assert_eq!(2, body.variants.len());
+            assert_eq!(1, body.variants[0].aliases.len());
+            assert_eq!(0, body.variants[1].aliases.len());
+            return;
+        }
+
+        panic!("Expected Decl::Enum");
+    }
+
+    #[test]
+    fn test_union() {
+        let file = parse_file("type Value = string | u64 | Foo;");
+        assert_eq!(1, file.decls.len());
+
+        if let Decl::Union(ref body) = file.decls[0] {
+            assert_eq!(3, body.variants.len());
+            return;
+        }
+
+        panic!("Expected Decl::Union");
+    }
+
+    #[test]
+    fn test_newtype() {
+        let file = parse_file("type UserId(string);");
+        assert_eq!(1, file.decls.len());
+
+        if let Decl::Newtype(ref body) = file.decls[0] {
+            assert_eq!("UserId", body.name.as_ref());
+            return;
+        }
+
+        panic!("Expected Decl::Newtype");
+    }
+
+    #[test]
+    fn test_endpoint_argument_comment() {
+        let file = parse_file("service Foo { get(/// The id.\n id: string); }");
+        assert_eq!(1, file.decls.len());
+
+        if let Decl::Service(ref body) = file.decls[0] {
+            if let ServiceMember::Endpoint(ref endpoint) = body.members[0] {
+                assert_eq!(vec![" The id."], endpoint.arguments[0].comment);
+                return;
+            }
+        }
+
+        panic!("Expected Decl::Service with an endpoint");
+    }
+
+    #[test]
+    fn test_endpoint_errors() {
+        let file = parse_file(
+            "service Foo { get(id: string) -> Foo throws NotFound: Error @ 404, Gone: Error @ 410; }",
+        );
+        assert_eq!(1, file.decls.len());
+
+        if let Decl::Service(ref body) = file.decls[0] {
+            if let ServiceMember::Endpoint(ref endpoint) = body.members[0] {
+                assert_eq!(2, endpoint.errors.len());
+                assert_eq!("NotFound", endpoint.errors[0].ident.as_ref());
+                return;
+            }
+        }
+
+        panic!("Expected Decl::Service with an endpoint");
+    }
+
+    #[test]
+    fn test_recover_decl() {
+        let input = "type Foo {}\ntype Bar { ; }\ntype Baz {}\n";
+
+        let file = parse_file(input);
+        assert_eq!(2, file.decls.len());
+        assert_eq!(1, file.errors.len());
+        assert_eq!("Foo", *file.decls[0].name());
+        assert_eq!("Baz", *file.decls[1].name());
+
+        let mut diag = Diagnostics::new(Source::empty("test"));
+        assert!(super::parse(&mut diag, input).is_err());
+
+        let mut diag = Diagnostics::new(Source::empty("test"));
+        let file = super::parse_partial(&mut diag, input).expect("partial file");
+        assert_eq!(2, file.decls.len());
+        assert!(diag.has_errors());
+    }
+
     #[test]
     fn test_strings() {
         assert_value_eq!(Value::String("foo\nbar".to_owned()), "\"foo\\nbar\"");
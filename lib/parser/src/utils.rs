@@ -1,5 +1,8 @@
 //! Utility functions for the parser.
 
+use core::Span;
+use lalrpop_util::ParseError;
+use lexer::{self, Token};
 use std::borrow::Cow;
 
 /// Check if character is not an indentation character.
@@ -82,6 +85,48 @@ pub fn strip_code_block<'a>(input: Cow<'a, str>) -> Vec<Cow<'a, str>> {
     }
 }
 
+/// Convert an error from the parser into the span it covers, and a message describing it.
+pub fn parse_error<'input>(e: ParseError<usize, Token<'input>, lexer::Error>) -> (Span, String) {
+    use lexer::errors::Error::*;
+    use lalrpop_util::ParseError::*;
+
+    match e {
+        InvalidToken { location } => ((location, location).into(), "syntax error".to_string()),
+        ExtraToken {
+            token: (start, token, end),
+        } => ((start, end).into(), format!("extra token: {:?}", token)),
+        UnrecognizedToken { token, expected } => match token {
+            Some((start, token, end)) => (
+                (start, end).into(),
+                format!(
+                    "syntax error, got token {:?}, expected: {}",
+                    token,
+                    expected.join(", ")
+                ),
+            ),
+            None => (
+                (0, 0).into(),
+                format!("syntax error, expected: {}", expected.join(", ")),
+            ),
+        },
+        User { error } => match error {
+            UnterminatedString { start } => {
+                ((start, start).into(), "unterminated string".to_string())
+            }
+            UnterminatedEscape { start } => (
+                (start, start).into(),
+                "unterminated escape sequence".to_string(),
+            ),
+            InvalidEscape { pos, message } => ((pos, pos).into(), message.to_string()),
+            UnterminatedCodeBlock { start } => {
+                ((start, start).into(), "unterminated code block".to_string())
+            }
+            InvalidNumber { pos, message } => ((pos, pos).into(), message.to_string()),
+            Unexpected { pos } => ((pos, pos).into(), "unexpected input".to_string()),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,271 @@
+//! ## Load an index from a `reproto-server` over HTTP
+
+use core::errors::Result;
+use core::{Range, RelativePath, RpPackage, Version};
+use futures::future::{err, ok};
+use futures::Future;
+use hyper::{Body, Method, Request, StatusCode};
+use repository::{
+    to_checksum, Algorithm, Cache, Checksum, Deployment, Index, IndexConfig, Objects, Signature,
+};
+use serde_json;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use url::Url;
+use {authorize, bad_response, client, handle_request, to_uri, token, HttpClient, HttpObjects};
+
+pub struct HttpIndex {
+    url: Url,
+    client: HttpClient,
+    token: Option<String>,
+    objects: String,
+    checksum: Algorithm,
+    /// Local directory where fetched files are cached.
+    cache: Option<PathBuf>,
+    /// Only use files from the local cache.
+    offline: bool,
+}
+
+impl HttpIndex {
+    /// Path to a file for the given package, relative to the index.
+    fn package_path(&self, package: &RpPackage, last: &str) -> String {
+        let mut path = package.parts().map(|p| p.as_str()).collect::<Vec<_>>();
+        path.push(last);
+        path.join("/")
+    }
+
+    /// Get a file from the index, returning `None` if it doesn't exist.
+    ///
+    /// Successfully fetched files are stored in the local cache, which is used exclusively when
+    /// offline.
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let url = self.url.join(path)?;
+        let cache_path = self.cache.as_ref().map(|c| RelativePath::new(path).to_path(c));
+
+        if self.offline {
+            let cache_path = match cache_path {
+                Some(ref cache_path) if cache_path.is_file() => cache_path,
+                _ => return Err(format!("{}: not available offline", url).into()),
+            };
+
+            let mut out = Vec::new();
+            File::open(cache_path)?.read_to_end(&mut out)?;
+            return Ok(Some(out));
+        }
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(to_uri(&url)?)
+            .body(Body::empty())?;
+
+        let work = handle_request(&self.client, request).and_then(|(body, status)| {
+            if status.is_success() {
+                return ok(Some(body));
+            }
+
+            if status == StatusCode::NOT_FOUND {
+                return ok(None);
+            }
+
+            err(bad_response(status, body))
+        });
+
+        let out = work.wait()?;
+
+        if let Some(cache_path) = cache_path {
+            match out {
+                Some(ref out) => {
+                    if let Some(parent) = cache_path.parent() {
+                        if !parent.is_dir() {
+                            fs::create_dir_all(parent)?;
+                        }
+                    }
+
+                    File::create(&cache_path)?.write_all(out)?;
+                }
+                None => {
+                    if cache_path.is_file() {
+                        fs::remove_file(&cache_path)?;
+                    }
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Read all deployments of a package which matches the given filter.
+    fn read_package<F>(&self, package: &RpPackage, filter: F) -> Result<Vec<Deployment>>
+    where
+        F: Fn(&Deployment) -> bool,
+    {
+        let path = self.package_path(package, "metadata.json");
+
+        let body = match self.get(&path)? {
+            Some(body) => body,
+            None => return Ok(vec![]),
+        };
+
+        let body = String::from_utf8(body).map_err(|_| format!("{}: not utf-8", path))?;
+
+        let mut out = Vec::new();
+
+        for (i, line) in body.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let deployment: Deployment = serde_json::from_str(line)
+                .map_err(|e| format!("{}: bad deployment on line #{}: {}", path, i + 1, e))?;
+
+            if filter(&deployment) {
+                out.push(deployment);
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl Index for HttpIndex {
+    fn resolve(&self, package: &RpPackage, range: &Range) -> Result<Vec<Deployment>> {
+        self.read_package(package, |d| range.matches(&d.version))
+    }
+
+    fn resolve_by_prefix(&self, _: &RpPackage) -> Result<Vec<(Deployment, RpPackage)>> {
+        Err("resolving packages by prefix is not supported by HTTP indexes".into())
+    }
+
+    fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>> {
+        self.read_package(package, |_| true)
+    }
+
+    fn put_version(
+        &self,
+        checksum: &Checksum,
+        package: &RpPackage,
+        version: &Version,
+        signature: Option<&Signature>,
+        changelog: &[String],
+        force: bool,
+    ) -> Result<()> {
+        if force {
+            return Err("forced publishing is not supported by HTTP indexes".into());
+        }
+
+        if self.offline {
+            return Err("cannot publish in offline mode".into());
+        }
+
+        let url = self
+            .url
+            .join(&self.package_path(package, &version.to_string()))?;
+
+        // NB: signed deployments and deployments with changelogs are sent as JSON, plain
+        // checksums are understood by all servers.
+        let body = if signature.is_some() || !changelog.is_empty() {
+            serde_json::to_string(
+                &Deployment::new(version.clone(), checksum.clone())
+                    .with_signature(signature.cloned())
+                    .with_changelog(changelog.to_vec()),
+            )?
+        } else {
+            checksum.to_string()
+        };
+
+        let mut request = Request::builder()
+            .method(Method::PUT)
+            .uri(to_uri(&url)?)
+            .body(Body::from(body))?;
+
+        authorize(&mut request, self.token.as_ref())?;
+
+        let work = handle_request(&self.client, request).and_then(|(body, status)| {
+            if !status.is_success() {
+                return err(bad_response(status, body));
+            }
+
+            ok(())
+        });
+
+        work.wait()
+    }
+
+    fn get_deployments(&self, package: &RpPackage, version: &Version) -> Result<Vec<Deployment>> {
+        self.read_package(package, |d| d.version == *version)
+    }
+
+    fn objects_url(&self) -> Result<&str> {
+        Ok(self.objects.as_str())
+    }
+
+    fn checksum_algorithm(&self) -> Algorithm {
+        self.checksum
+    }
+
+    fn objects_from_index(&self, relative_path: &RelativePath) -> Result<Box<Objects>> {
+        let mut url = self.url.join(relative_path.as_str())?;
+
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
+        }
+
+        Ok(Box::new(HttpObjects {
+            url,
+            client: client(),
+            token: self.token.clone(),
+        }))
+    }
+}
+
+/// Load an index from an HTTP url.
+pub fn index_from_url(config: IndexConfig, url: &Url) -> Result<Box<Index>> {
+    let mut url = url.clone();
+
+    // NB: the url must be a directory for relative urls to be resolved against it.
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+
+    let cache = match config.cache_home {
+        Some(cache_home) => {
+            let tail = to_checksum(Algorithm::Sha256, url.as_str().as_bytes())?;
+            let host = url.host_str().unwrap_or("unknown");
+            Some(Cache::new(cache_home).index_dir().join(format!("{}-{}", host, tail)))
+        }
+        None => None,
+    };
+
+    let mut index = HttpIndex {
+        url,
+        client: client(),
+        token: token(),
+        objects: String::new(),
+        checksum: Algorithm::default(),
+        cache,
+        offline: config.offline,
+    };
+
+    let config = index
+        .get("config.json")?
+        .ok_or_else(|| format!("{}: not an index, missing config.json", index.url))?;
+
+    let config: serde_json::Value = serde_json::from_slice(&config)
+        .map_err(|e| format!("{}: bad config.json: {}", index.url, e))?;
+
+    index.objects = config
+        .get("objects")
+        .and_then(|o| o.as_str())
+        .unwrap_or("./objects")
+        .to_string();
+
+    if let Some(checksum) = config.get("checksum").and_then(|c| c.as_str()) {
+        index.checksum = Algorithm::parse(checksum)
+            .ok_or_else(|| format!("{}: unsupported checksum algorithm: {}", index.url, checksum))?;
+    }
+
+    Ok(Box::new(index))
+}
@@ -1,64 +1,109 @@
-//! ## Load objects from a remote repository over HTTP
+//! ## Load objects and indexes from a remote repository over HTTP
 
 extern crate futures;
 extern crate hyper;
 extern crate hyper_rustls;
 extern crate reproto_core as core;
 extern crate reproto_repository as repository;
+extern crate serde_json;
 extern crate url;
 
+mod index;
+
+pub use self::index::index_from_url;
 use core::errors::{Error, Result};
 use core::Source;
 use futures::future::{err, ok};
 use futures::{Future, Stream};
 use hyper::client::HttpConnector;
+use hyper::header::{self, HeaderValue};
 use hyper::{Body, Client, Method, Request, StatusCode};
 use hyper_rustls::HttpsConnector;
-use repository::{CachedObjects, Checksum, HexSlice, Objects, ObjectsConfig};
+use repository::{Cache, CachedObjects, Checksum, Objects, ObjectsConfig};
+use std::env;
 use std::io::Read;
 use std::time::Duration;
 use url::Url;
 
+/// Environment variable holding the token used when publishing.
+const TOKEN_VAR: &str = "REPROTO_TOKEN";
+
+type HttpClient = Client<HttpsConnector<HttpConnector>, Body>;
+
 pub struct HttpObjects {
     url: Url,
-    client: Client<HttpsConnector<HttpConnector>, Body>,
+    client: HttpClient,
+    token: Option<String>,
 }
 
 impl HttpObjects {
     fn checksum_url(&self, checksum: &Checksum) -> Result<hyper::Uri> {
-        let url = self
-            .url
-            .join(HexSlice::new(checksum).to_string().as_ref())?;
+        // NB: prefixed to avoid the algorithm in the checksum being interpreted as a scheme.
+        let url = self.url.join(&format!("./{}", checksum))?;
+
+        to_uri(&url)
+    }
+}
+
+/// Build a new client.
+fn client() -> HttpClient {
+    Client::builder().build(HttpsConnector::new(4))
+}
+
+/// Get the token to use when publishing, if any.
+fn token() -> Option<String> {
+    env::var(TOKEN_VAR).ok()
+}
+
+/// Convert an URL into an URI.
+fn to_uri(url: &Url) -> Result<hyper::Uri> {
+    let uri = url
+        .to_string()
+        .parse::<hyper::Uri>()
+        .map_err(|e| format!("Failed to parse URL: {}: {}", e, url))?;
+
+    Ok(uri)
+}
 
-        let url = url
-            .to_string()
-            .parse::<hyper::Uri>()
-            .map_err(|e| format!("Failed to parse URL: {}: {}", e, url))?;
+/// Add the token used for publishing to the request.
+fn authorize(request: &mut Request<Body>, token: Option<&String>) -> Result<()> {
+    if let Some(token) = token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|_| format!("{}: bad token", TOKEN_VAR))?;
 
-        Ok(url)
+        request.headers_mut().insert(header::AUTHORIZATION, value);
     }
 
-    fn handle_request(
-        &mut self,
-        request: Request<Body>,
-    ) -> impl Future<Item = (Vec<u8>, StatusCode), Error = Error> {
-        let body_and_status = self
-            .client
-            .request(request)
-            .map_err::<_, Error>(|e| format!("Request to repository failed: {}", e).into())
-            .and_then(|res| {
-                let status = res.status().clone();
-
-                res.into_body()
-                    .map_err::<Error, _>(|e| format!("Failed to perform request: {}", e).into())
-                    .fold(Vec::new(), |mut out: Vec<u8>, chunk| {
-                        out.extend(chunk.as_ref());
-                        ok::<_, Error>(out)
-                    }).map(move |body| (body, status))
-            });
-
-        Box::new(body_and_status)
+    Ok(())
+}
+
+/// Send a request, and collect the body and status of the response.
+fn handle_request(
+    client: &HttpClient,
+    request: Request<Body>,
+) -> impl Future<Item = (Vec<u8>, StatusCode), Error = Error> {
+    client
+        .request(request)
+        .map_err::<_, Error>(|e| format!("Request to repository failed: {}", e).into())
+        .and_then(|res| {
+            let status = res.status().clone();
+
+            res.into_body()
+                .map_err::<Error, _>(|e| format!("Failed to perform request: {}", e).into())
+                .fold(Vec::new(), |mut out: Vec<u8>, chunk| {
+                    out.extend(chunk.as_ref());
+                    ok::<_, Error>(out)
+                }).map(move |body| (body, status))
+        })
+}
+
+/// Build an error for an unexpected response.
+fn bad_response(status: StatusCode, body: Vec<u8>) -> Error {
+    if let Ok(body) = String::from_utf8(body) {
+        return format!("bad response: {}: {}", status, body).into();
     }
+
+    format!("bad response: {}", status).into()
 }
 
 impl Objects for HttpObjects {
@@ -68,18 +113,16 @@ impl Objects for HttpObjects {
 
         let url = self.checksum_url(checksum)?;
 
-        let request = Request::builder()
+        let mut request = Request::builder()
             .method(Method::PUT)
             .uri(url)
             .body(Body::from(buffer))?;
 
-        let work = self.handle_request(request).and_then(|(body, status)| {
-            if !status.is_success() {
-                if let Ok(body) = String::from_utf8(body) {
-                    return err(format!("bad response: {}: {}", status, body).into());
-                }
+        authorize(&mut request, self.token.as_ref())?;
 
-                return err(format!("bad response: {}", status).into());
+        let work = handle_request(&self.client, request).and_then(|(body, status)| {
+            if !status.is_success() {
+                return err(bad_response(status, body));
             }
 
             ok(())
@@ -100,7 +143,7 @@ impl Objects for HttpObjects {
             .uri(url)
             .body(Body::empty())?;
 
-        let work = self.handle_request(request).and_then(|(body, status)| {
+        let work = handle_request(&self.client, request).and_then(|(body, status)| {
             if status.is_success() {
                 return ok(Some(body));
             }
@@ -109,11 +152,7 @@ impl Objects for HttpObjects {
                 return ok(None);
             }
 
-            if let Ok(body) = String::from_utf8(body) {
-                return err(format!("bad response: {}: {}", status, body).into());
-            }
-
-            return err(format!("bad response: {}", status).into());
+            err(bad_response(status, body))
         });
 
         let out = work.wait()?;
@@ -123,11 +162,10 @@ impl Objects for HttpObjects {
 
 /// Load objects from an HTTP url.
 pub fn objects_from_url(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>> {
-    let client = Client::builder().build(HttpsConnector::new(4));
-
     let http_objects = HttpObjects {
         url: url.clone(),
-        client,
+        client: client(),
+        token: token(),
     };
 
     if let Some(cache_home) = config.cache_home {
@@ -136,11 +174,16 @@ pub fn objects_from_url(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>
             .unwrap_or_else(|| Duration::new(60, 0));
 
         return Ok(Box::new(CachedObjects::new(
-            cache_home,
+            Cache::new(cache_home).objects_dir(),
             missing_cache_time,
+            config.offline,
             http_objects,
         )));
     }
 
+    if config.offline {
+        return Err(format!("{}: objects are not available offline without a cache", url).into());
+    }
+
     Ok(Box::new(http_objects))
 }
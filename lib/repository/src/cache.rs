@@ -0,0 +1,158 @@
+//! ## Maintenance of the global cache
+//!
+//! The cache is shared between all projects, and is laid out like the following:
+//!
+//! * `objects/[<algorithm>/]<aa>/<bb>/<checksum>` - cached objects, where the algorithm is omitted
+//!   for sha256 checksums.
+//! * `objects/missing/[<algorithm>/]<aa>/<bb>/<checksum>` - markers for objects which are known to
+//!   be missing.
+//! * `index/<host>-<hash>/` - cached files from remote indexes.
+
+use checksum::{Algorithm, Checksum};
+use core::errors::*;
+use digest::to_checksum;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Directory where objects are cached.
+const OBJECTS: &'static str = "objects";
+/// Directory where markers for missing objects are stored.
+const MISSING: &'static str = "missing";
+/// Directory where indexes are cached.
+const INDEX: &'static str = "index";
+
+pub struct Cache {
+    path: PathBuf,
+}
+
+impl Cache {
+    pub fn new<P: AsRef<Path>>(path: P) -> Cache {
+        Cache {
+            path: path.as_ref().to_owned(),
+        }
+    }
+
+    /// Path to the root of the cache.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory where objects are cached.
+    pub fn objects_dir(&self) -> PathBuf {
+        self.path.join(OBJECTS)
+    }
+
+    /// Directory where remote indexes are cached.
+    pub fn index_dir(&self) -> PathBuf {
+        self.path.join(INDEX)
+    }
+
+    /// Verify the checksum of all cached objects.
+    ///
+    /// Returns the paths of all objects whose content doesn't match their checksum.
+    pub fn verify(&self) -> Result<Vec<PathBuf>> {
+        let mut corrupt = Vec::new();
+
+        for (expected, path) in self.objects()? {
+            let actual = to_checksum(expected.algorithm(), File::open(&path)?)?;
+
+            if actual != expected {
+                debug!("cache: corrupt object: {}", path.display());
+                corrupt.push(path);
+            }
+        }
+
+        Ok(corrupt)
+    }
+
+    /// Prune the cache.
+    ///
+    /// This removes all markers for missing objects, and all objects which fail verification.
+    /// If `all` is set, everything in the cache is removed.
+    ///
+    /// Returns the paths that were removed.
+    pub fn prune(&self, all: bool) -> Result<Vec<PathBuf>> {
+        let mut removed = Vec::new();
+
+        if all {
+            for dir in vec![self.objects_dir(), self.index_dir()] {
+                if dir.is_dir() {
+                    fs::remove_dir_all(&dir)?;
+                    removed.push(dir);
+                }
+            }
+
+            return Ok(removed);
+        }
+
+        let missing = self.objects_dir().join(MISSING);
+
+        for path in files(&missing)? {
+            fs::remove_file(&path)?;
+            removed.push(path);
+        }
+
+        for path in self.verify()? {
+            fs::remove_file(&path)?;
+            removed.push(path);
+        }
+
+        Ok(removed)
+    }
+
+    /// List all cached objects, and their expected checksum.
+    fn objects(&self) -> Result<Vec<(Checksum, PathBuf)>> {
+        let mut out = Vec::new();
+
+        let objects = self.objects_dir();
+
+        for path in files(&objects)? {
+            let relative = path.strip_prefix(&objects).map_err(|e| e.to_string())?;
+
+            let mut components = relative.components().filter_map(|c| c.as_os_str().to_str());
+
+            let algorithm = match components.next() {
+                Some(MISSING) => continue,
+                Some(first) => Algorithm::parse(first).unwrap_or(Algorithm::Sha256),
+                None => continue,
+            };
+
+            let name = match path.file_name().and_then(|n| n.to_str()) {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let checksum = Checksum::from_str(&format!("{}:{}", algorithm, name))
+                .map_err(|e| format!("{}: bad object name: {}", path.display(), e))?;
+
+            out.push((checksum, path));
+        }
+
+        Ok(out)
+    }
+}
+
+/// Recursively list all files in the given directory.
+fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+
+    if !dir.is_dir() {
+        return Ok(out);
+    }
+
+    let mut queue = vec![dir.to_owned()];
+
+    while let Some(dir) = queue.pop() {
+        for e in fs::read_dir(&dir)? {
+            let path = e?.path();
+
+            if path.is_dir() {
+                queue.push(path);
+            } else {
+                out.push(path);
+            }
+        }
+    }
+
+    Ok(out)
+}
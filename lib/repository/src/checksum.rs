@@ -8,30 +8,142 @@ use std::fmt;
 use std::ops::{Index, Range};
 use std::result;
 
+/// Algorithms which can be used to calculate checksums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl Algorithm {
+    /// Name of the algorithm, as used in checksum ids.
+    pub fn name(&self) -> &'static str {
+        use self::Algorithm::*;
+
+        match *self {
+            Sha256 => "sha256",
+            Sha512 => "sha512",
+            Blake3 => "blake3",
+        }
+    }
+
+    /// Parse the algorithm from its name.
+    pub fn parse(input: &str) -> Option<Algorithm> {
+        use self::Algorithm::*;
+
+        let out = match input {
+            "sha256" => Sha256,
+            "sha512" => Sha512,
+            "blake3" => Blake3,
+            _ => return None,
+        };
+
+        Some(out)
+    }
+
+    /// The number of bytes in a checksum calculated by this algorithm.
+    pub fn len(&self) -> usize {
+        use self::Algorithm::*;
+
+        match *self {
+            Sha256 => 32,
+            Sha512 => 64,
+            Blake3 => 32,
+        }
+    }
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Sha256
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.name().fmt(fmt)
+    }
+}
+
+/// A checksum, and the algorithm used to calculate it.
+///
+/// Checksums are identified by `<algorithm>:<hex>`, except for sha256 checksums which are
+/// identified by their hex alone for compatibility with repositories which predate the support
+/// for multiple algorithms.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Checksum {
+    algorithm: Algorithm,
     bytes: Vec<u8>,
 }
 
 impl Checksum {
+    /// Construct a new sha256 checksum.
     pub fn new(bytes: Vec<u8>) -> Checksum {
-        Checksum { bytes: bytes }
+        Self::with_algorithm(Algorithm::Sha256, bytes)
+    }
+
+    /// Construct a new checksum calculated with the given algorithm.
+    pub fn with_algorithm(algorithm: Algorithm, bytes: Vec<u8>) -> Checksum {
+        Checksum {
+            algorithm: algorithm,
+            bytes: bytes,
+        }
     }
 
     pub fn from_str(input: &str) -> Result<Checksum> {
+        let (algorithm, input) = match input.find(':') {
+            Some(index) => {
+                let name = &input[..index];
+
+                let algorithm = Algorithm::parse(name)
+                    .ok_or_else(|| format!("unsupported checksum algorithm: {}", name))?;
+
+                (algorithm, &input[index + 1..])
+            }
+            None => (Algorithm::Sha256, input),
+        };
+
         let bytes: Vec<u8> = FromHex::from_hex(input)?;
 
-        if bytes.len() != 32usize {
-            return Err("expected 32 bytes".into());
+        if bytes.len() != algorithm.len() {
+            return Err(format!("expected {} bytes for {}", algorithm.len(), algorithm).into());
         }
 
-        Ok(Checksum { bytes: bytes })
+        Ok(Checksum {
+            algorithm: algorithm,
+            bytes: bytes,
+        })
+    }
+
+    /// The algorithm used to calculate this checksum.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Relative path components where the object with this checksum is stored.
+    ///
+    /// Sha256 objects are stored as `<aa>/<bb>/<hex>`, others are prefixed with the name of
+    /// their algorithm.
+    pub fn path_components(&self) -> Vec<String> {
+        let mut out = Vec::new();
+
+        if self.algorithm != Algorithm::Sha256 {
+            out.push(self.algorithm.name().to_string());
+        }
+
+        out.push(HexSlice::new(&self.bytes[0..1]).to_string());
+        out.push(HexSlice::new(&self.bytes[1..2]).to_string());
+        out.push(HexSlice::new(&self.bytes[..]).to_string());
+        out
     }
 }
 
 impl fmt::Debug for Checksum {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("Checksum")
+            .field("algorithm", &self.algorithm)
             .field("bytes", &HexSlice::new(&self.bytes[..]))
             .finish()
     }
@@ -54,6 +166,10 @@ impl Index<Range<usize>> for Checksum {
 
 impl fmt::Display for Checksum {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.algorithm != Algorithm::Sha256 {
+            write!(f, "{}:", self.algorithm)?;
+        }
+
         write!(f, "{}", HexSlice::new(&self.bytes[..]))
     }
 }
@@ -63,7 +179,7 @@ impl ser::Serialize for Checksum {
     where
         S: ser::Serializer,
     {
-        serializer.serialize_str(&format!("{}", HexSlice::new(&self.bytes[..])))
+        serializer.serialize_str(&self.to_string())
     }
 }
 
@@ -73,7 +189,7 @@ impl<'de> de::Visitor<'de> for ChecksumVisitor {
     type Value = Checksum;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "a hex encoded string of bytes")
+        write!(formatter, "a checksum, like `<algorithm>:<hex>`")
     }
 
     fn visit_str<E>(self, value: &str) -> result::Result<Self::Value, E>
@@ -101,3 +217,24 @@ impl<'de> de::Deserialize<'de> for Checksum {
         deserializer.deserialize_str(ChecksumVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Algorithm, Checksum};
+
+    #[test]
+    fn test_parse_checksum() {
+        let sha256 = "ab".repeat(32);
+        let checksum = Checksum::from_str(&sha256).expect("sha256 checksum");
+        assert_eq!(Algorithm::Sha256, checksum.algorithm());
+        assert_eq!(sha256, checksum.to_string());
+
+        let sha512 = format!("sha512:{}", "ab".repeat(64));
+        let checksum = Checksum::from_str(&sha512).expect("sha512 checksum");
+        assert_eq!(Algorithm::Sha512, checksum.algorithm());
+        assert_eq!(sha512, checksum.to_string());
+
+        assert!(Checksum::from_str(&format!("blake3:{}", "ab".repeat(64))).is_err());
+        assert!(Checksum::from_str(&format!("md5:{}", "ab".repeat(16))).is_err());
+    }
+}
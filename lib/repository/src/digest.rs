@@ -0,0 +1,69 @@
+//! Calculate checksums using one of the supported algorithms.
+
+use blake3;
+use checksum::{Algorithm, Checksum};
+use core::errors::*;
+use ring::digest;
+use std::io::Read;
+
+/// Calculate the checksum of everything in the given reader.
+pub fn to_checksum<R: Read>(algorithm: Algorithm, mut reader: R) -> Result<Checksum> {
+    let mut hasher = Digest::new(algorithm);
+    let mut buffer = [0u8; 1024];
+
+    loop {
+        let len = reader.read(&mut buffer)?;
+
+        if len == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[0..len]);
+    }
+
+    let checksum = hasher.finish()?;
+    Ok(checksum)
+}
+
+enum Context {
+    Ring(digest::Context),
+    Blake3(blake3::Hasher),
+}
+
+pub struct Digest {
+    algorithm: Algorithm,
+    context: Context,
+}
+
+impl Digest {
+    pub fn new(algorithm: Algorithm) -> Digest {
+        let context = match algorithm {
+            Algorithm::Sha256 => Context::Ring(digest::Context::new(&digest::SHA256)),
+            Algorithm::Sha512 => Context::Ring(digest::Context::new(&digest::SHA512)),
+            Algorithm::Blake3 => Context::Blake3(blake3::Hasher::new()),
+        };
+
+        Digest {
+            algorithm: algorithm,
+            context: context,
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self.context {
+            Context::Ring(ref mut context) => context.update(bytes),
+            Context::Blake3(ref mut hasher) => {
+                hasher.update(bytes);
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<Checksum> {
+        let bytes = match self.context {
+            Context::Ring(context) => context.finish().as_ref().to_vec(),
+            Context::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+        };
+
+        Ok(Checksum::with_algorithm(self.algorithm, bytes))
+    }
+}
@@ -1,8 +1,9 @@
 mod git_repo;
 
 pub use self::git_repo::GitRepo;
+use checksum::Algorithm;
 use core::errors::*;
-use sha256;
+use digest::Digest;
 use std::path::Path;
 use url::Url;
 
@@ -13,10 +14,13 @@ pub fn open_git_repo<P: AsRef<Path>>(path: P) -> Result<GitRepo> {
 }
 
 /// Open an already existing git repo.
+///
+/// If `offline` is set, the repo must already have been checked out.
 pub fn setup_git_repo<'a, P: AsRef<Path>>(
     repos: &P,
     scheme: &str,
     url: &'a Url,
+    offline: bool,
 ) -> Result<GitRepo> {
     let mut remote = url.clone();
 
@@ -27,7 +31,7 @@ pub fn setup_git_repo<'a, P: AsRef<Path>>(
     let path = repos.as_ref().to_owned();
 
     let tail = {
-        let mut tail = sha256::Sha256::new();
+        let mut tail = Digest::new(Algorithm::Sha256);
         tail.update(&remote.to_string().as_bytes());
         tail.finish()
     }?;
@@ -37,6 +41,10 @@ pub fn setup_git_repo<'a, P: AsRef<Path>>(
         _ => path.join(format!("unknown-{}", tail)),
     };
 
+    if offline && !path.is_dir() {
+        return Err(format!("{}: repository is not available offline", remote).into());
+    }
+
     let refspec = remote
         .query_pairs()
         .find(|e| e.0 == "ref")
@@ -1,12 +1,13 @@
-use checksum::Checksum;
+use checksum::{Algorithm, Checksum};
 use core::errors::*;
 use core::{Range, RelativePath, RpPackage, Version};
 use index::{Deployment, Index};
 use objects::{FileObjects, Objects};
 use serde_json;
+use signing::Signature;
 use std::collections::VecDeque;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 
 /// Default to objects relative to index repo.
@@ -24,6 +25,9 @@ fn default_objects() -> String {
 pub struct Config {
     #[serde(default = "default_objects")]
     objects: String,
+    /// Checksum algorithm used for published objects.
+    #[serde(default)]
+    checksum: Algorithm,
 }
 
 pub struct FileIndex {
@@ -163,9 +167,10 @@ impl Index for FileIndex {
                 continue;
             }
 
-            for s in fs::read_dir(&p)? {
-                let s = s?;
+            let mut entries = fs::read_dir(&p)?.collect::<io::Result<Vec<_>>>()?;
+            entries.sort_by_key(|e| e.path());
 
+            for s in entries {
                 let path = s.path();
 
                 if !path.is_dir() {
@@ -200,6 +205,8 @@ impl Index for FileIndex {
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<&Signature>,
+        changelog: &[String],
         force: bool,
     ) -> Result<()> {
         let (mut deployments, other_match) =
@@ -211,7 +218,11 @@ impl Index for FileIndex {
             }
         }
 
-        deployments.push(Deployment::new(version.clone(), checksum.clone()));
+        deployments.push(
+            Deployment::new(version.clone(), checksum.clone())
+                .with_signature(signature.cloned())
+                .with_changelog(changelog.to_vec()),
+        );
         deployments.sort_by(|a, b| a.version.cmp(&b.version));
         self.write_package(package, deployments)?;
         Ok(())
@@ -230,6 +241,10 @@ impl Index for FileIndex {
     fn objects_url(&self) -> Result<&str> {
         Ok(self.config.objects.as_str())
     }
+
+    fn checksum_algorithm(&self) -> Algorithm {
+        self.config.checksum
+    }
 }
 
 pub fn init_file_index<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
@@ -245,6 +260,7 @@ pub fn init_file_index<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
         let mut f = File::create(config_path)?;
         let config = Config {
             objects: DEFAULT_OBJECTS.to_owned(),
+            checksum: Algorithm::default(),
         };
         let config_content = serde_json::to_value(&config)?;
         writeln!(f, "{:#}", config_content)?;
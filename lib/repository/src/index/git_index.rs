@@ -1,9 +1,10 @@
-use checksum::Checksum;
+use checksum::{Algorithm, Checksum};
 use core::errors::*;
 use core::{Range, RelativePath, RpPackage, Version};
 use git::GitRepo;
 use index::{file_index, Deployment, Index};
 use objects::{FileObjects, GitObjects, Objects};
+use signing::Signature;
 use std::sync::Arc;
 use update::Update;
 use url::Url;
@@ -49,6 +50,8 @@ impl Index for GitIndex {
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<&Signature>,
+        changelog: &[String],
         force: bool,
     ) -> Result<()> {
         if !self.publishing {
@@ -59,7 +62,7 @@ impl Index for GitIndex {
         }
 
         self.file_index
-            .put_version(checksum, package, version, force)?;
+            .put_version(checksum, package, version, signature, changelog, force)?;
 
         let path = self.file_index.metadata_path(package);
         self.git_repo.add(path)?;
@@ -77,6 +80,10 @@ impl Index for GitIndex {
         self.file_index.objects_url()
     }
 
+    fn checksum_algorithm(&self) -> Algorithm {
+        self.file_index.checksum_algorithm()
+    }
+
     fn objects_from_index(&self, relative_path: &RelativePath) -> Result<Box<Objects>> {
         let path = relative_path.to_path(&self.file_index.path());
         let file_objects = FileObjects::new(&path);
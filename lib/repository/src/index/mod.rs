@@ -3,11 +3,12 @@ mod git_index;
 
 pub use self::file_index::init_file_index;
 use self::git_index::GitIndex;
-use checksum::Checksum;
+use checksum::{Algorithm, Checksum};
 use core::errors::*;
 use core::{Range, RelativePath, RpPackage, Version};
 use git;
 use objects::Objects;
+use signing::Signature;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use update::Update;
@@ -17,12 +18,22 @@ use url::Url;
 pub struct IndexConfig {
     /// Root path when checking out local repositories.
     pub repo_dir: PathBuf,
+    /// Directory where remote indexes are cached.
+    pub cache_home: Option<PathBuf>,
+    /// Only use what is available locally.
+    pub offline: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Deployment {
     pub version: Version,
     pub object: Checksum,
+    /// Signature of the object checksum, if the deployment was signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+    /// Changes since the previous version, if a changelog was generated when publishing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changelog: Vec<String>,
 }
 
 impl Deployment {
@@ -30,8 +41,20 @@ impl Deployment {
         Deployment {
             version: version,
             object: object,
+            signature: None,
+            changelog: Vec::new(),
         }
     }
+
+    /// Set the signature of the deployment.
+    pub fn with_signature(self, signature: Option<Signature>) -> Deployment {
+        Deployment { signature, ..self }
+    }
+
+    /// Set the changelog of the deployment.
+    pub fn with_changelog(self, changelog: Vec<String>) -> Deployment {
+        Deployment { changelog, ..self }
+    }
 }
 
 pub trait Index: Send {
@@ -46,11 +69,14 @@ pub trait Index: Send {
     /// The returned versions are sorted.
     fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>>;
 
+    /// Publish the given version of a package, optionally signed and with a changelog.
     fn put_version(
         &self,
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<&Signature>,
+        changelog: &[String],
         force: bool,
     ) -> Result<()>;
 
@@ -64,6 +90,11 @@ pub trait Index: Send {
     /// Load objects relative to the index repository.
     fn objects_from_index(&self, relative_path: &RelativePath) -> Result<Box<Objects>>;
 
+    /// The checksum algorithm to use for objects published to this index.
+    fn checksum_algorithm(&self) -> Algorithm {
+        Algorithm::default()
+    }
+
     /// Update local caches related to the index.
     fn update(&self) -> Result<Vec<Update>> {
         Ok(vec![])
@@ -85,7 +116,15 @@ impl Index for NoIndex {
         Ok(vec![])
     }
 
-    fn put_version(&self, _: &Checksum, _: &RpPackage, _: &Version, _: bool) -> Result<()> {
+    fn put_version(
+        &self,
+        _: &Checksum,
+        _: &RpPackage,
+        _: &Version,
+        _: Option<&Signature>,
+        _: &[String],
+        _: bool,
+    ) -> Result<()> {
         Err("Empty Index".into())
     }
 
@@ -160,13 +199,22 @@ where
 
         git::open_git_repo(path)?
     } else {
-        git::setup_git_repo(&config.repo_dir, sub_scheme, url)?
+        git::setup_git_repo(&config.repo_dir, sub_scheme, url, config.offline)?
     };
 
     open_git_index(url, git_repo, publishing)
 }
 
-pub fn index_from_url(config: IndexConfig, url: &Url, publishing: bool) -> Result<Box<Index>> {
+/// Load an index from an URL.
+pub fn index_from_url<F>(
+    config: IndexConfig,
+    url: &Url,
+    fallback: F,
+    publishing: bool,
+) -> Result<Box<Index>>
+where
+    F: Fn(IndexConfig, &str, &Url) -> Result<Option<Box<Index>>>,
+{
     let mut scheme = url.scheme().split("+");
 
     let first = scheme
@@ -179,6 +227,9 @@ pub fn index_from_url(config: IndexConfig, url: &Url, publishing: bool) -> Resul
             .map_err(|_| format!("url is not a file path: {}", url).into())
             .and_then(|path| index_from_path(&path)),
         "git" => index_from_git(config, scheme, url, publishing),
-        scheme => Err(format!("bad scheme: {}", scheme).into()),
+        scheme => match fallback(config, scheme, url)? {
+            Some(index) => Ok(index),
+            None => Err(format!("bad scheme: {}", scheme).into()),
+        },
     }.chain_err(|| format!("loading index from URL: {}", url))
 }
@@ -1,5 +1,6 @@
 #![recursion_limit = "1000"]
 
+extern crate blake3;
 extern crate bytes;
 extern crate hex;
 #[macro_use]
@@ -11,9 +12,12 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 extern crate toml;
+extern crate untrusted;
 extern crate url;
 
+mod cache;
 mod checksum;
+mod digest;
 mod git;
 mod hex_slice;
 mod index;
@@ -21,14 +25,16 @@ mod metadata;
 mod objects;
 mod repository;
 mod resolver;
-mod sha256;
+mod signing;
 mod update;
 
-pub use self::checksum::Checksum;
+pub use self::cache::Cache;
+pub use self::checksum::{Algorithm, Checksum};
+pub use self::digest::{to_checksum, Digest};
 pub use self::git::GitRepo;
 pub use self::hex_slice::HexSlice;
 pub use self::index::{
-    index_from_path, index_from_url, init_file_index, Index, IndexConfig, NoIndex,
+    index_from_path, index_from_url, init_file_index, Deployment, Index, IndexConfig, NoIndex,
 };
 pub use self::objects::{
     objects_from_path, objects_from_url, CachedObjects, FileObjects, NoObjects, Objects,
@@ -36,5 +42,5 @@ pub use self::objects::{
 };
 pub use self::repository::Repository;
 pub use self::resolver::{path_to_package, Packages, Paths, Resolvers, EXT};
-pub use self::sha256::{to_sha256 as to_checksum, Sha256 as Digest};
+pub use self::signing::{Signature, SigningKey};
 pub use self::update::Update;
@@ -3,7 +3,6 @@
 use checksum::Checksum;
 use core::errors::*;
 use core::Source;
-use hex_slice::HexSlice;
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::PathBuf;
@@ -13,32 +12,38 @@ use Objects;
 pub struct CachedObjects<T> {
     objects_cache: PathBuf,
     missing_cache_time: Duration,
+    offline: bool,
     inner: T,
 }
 
 impl<T: Objects> CachedObjects<T> {
-    pub fn new(objects_cache: PathBuf, missing_cache_time: Duration, inner: T) -> CachedObjects<T> {
+    pub fn new(
+        objects_cache: PathBuf,
+        missing_cache_time: Duration,
+        offline: bool,
+        inner: T,
+    ) -> CachedObjects<T> {
         CachedObjects {
             objects_cache: objects_cache,
             missing_cache_time: missing_cache_time,
+            offline: offline,
             inner: inner,
         }
     }
 
     fn cache_path(&self, checksum: &Checksum) -> Result<PathBuf> {
-        let path = self
-            .objects_cache
-            .join(format!("{}", HexSlice::new(&checksum[0..1])));
-        let path = path.join(format!("{}", HexSlice::new(&checksum[1..2])));
-        Ok(path.join(format!("{}", HexSlice::new(&checksum))))
+        Ok(checksum
+            .path_components()
+            .into_iter()
+            .fold(self.objects_cache.clone(), |path, c| path.join(c)))
     }
 
     /// Get the path to the missing file cache.
     fn missing_path(&self, checksum: &Checksum) -> Result<PathBuf> {
-        Ok(self
-            .objects_cache
-            .join("missing")
-            .join(format!("{}", HexSlice::new(checksum))))
+        Ok(checksum
+            .path_components()
+            .into_iter()
+            .fold(self.objects_cache.join("missing"), |path, c| path.join(c)))
     }
 
     /// Check if there is a local missing cached file, and assume that the remote file is missing
@@ -86,6 +91,10 @@ impl<T: Objects> CachedObjects<T> {
 
 impl<T: Objects> Objects for CachedObjects<T> {
     fn put_object(&mut self, checksum: &Checksum, source: &mut Read, force: bool) -> Result<bool> {
+        if self.offline {
+            return Err("cannot publish objects in offline mode".into());
+        }
+
         self.inner.put_object(checksum, source, force)
     }
 
@@ -96,6 +105,11 @@ impl<T: Objects> Objects for CachedObjects<T> {
             return Ok(Some(Source::from_path(cache_path)));
         }
 
+        if self.offline {
+            debug!("cache: object not available offline: {}", checksum);
+            return Ok(None);
+        }
+
         let (missing, missing_path) = self.check_missing(checksum)?;
 
         if missing {
@@ -4,7 +4,6 @@ use super::Objects;
 use checksum::Checksum;
 use core::errors::*;
 use core::Source;
-use hex_slice::HexSlice;
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
@@ -14,8 +13,11 @@ use std::path::{Path, PathBuf};
 pub struct FileObjects {
     /// The root path of the filesystem storage.
     ///
-    /// Objects will be fetched according to their checksum, like this using the example checksum
-    /// `deadbeef`: `<path>/de/adbeef.reproto`
+    /// Objects will be fetched according to their checksum, like this using the example sha256
+    /// checksum `deadbeef`: `<path>/de/ad/deadbeef.reproto`.
+    ///
+    /// Checksums using other algorithms are stored in a directory named after the algorithm, like
+    /// `<path>/blake3/de/ad/deadbeef.reproto`.
     path: PathBuf,
 }
 
@@ -31,11 +33,18 @@ impl FileObjects {
 
     /// Calculate the path to the given checksum.
     pub fn get_path(&self, checksum: &Checksum) -> Result<PathBuf> {
-        let path = self
-            .path
-            .join(format!("{}", HexSlice::new(&checksum[0..1])));
-        let path = path.join(format!("{}", HexSlice::new(&checksum[1..2])));
-        Ok(path.join(format!("{}.reproto", HexSlice::new(&checksum))))
+        let mut path = self.path.clone();
+        let mut components = checksum.path_components();
+
+        let last = components
+            .pop()
+            .ok_or_else(|| format!("no path for checksum: {}", checksum))?;
+
+        for c in components {
+            path = path.join(c);
+        }
+
+        Ok(path.join(format!("{}.reproto", last)))
     }
 }
 
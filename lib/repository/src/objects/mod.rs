@@ -22,6 +22,8 @@ pub struct ObjectsConfig {
     pub repo_dir: PathBuf,
     pub cache_home: Option<PathBuf>,
     pub missing_cache_time: Option<Duration>,
+    /// Only use objects which are available locally.
+    pub offline: bool,
 }
 
 pub trait Objects: Send {
@@ -84,7 +86,7 @@ where
         .next()
         .ok_or_else(|| format!("bad scheme ({}), expected git+scheme", url.scheme()))?;
 
-    let git_repo = git::setup_git_repo(&config.repo_dir, sub_scheme, url)?;
+    let git_repo = git::setup_git_repo(&config.repo_dir, sub_scheme, url, config.offline)?;
 
     let file_objects = FileObjects::new(git_repo.path());
 
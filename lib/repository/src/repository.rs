@@ -4,13 +4,18 @@ use core::{
     self, Resolved, ResolvedByPrefix, Resolver, RpPackage, RpRequiredPackage, RpVersionedPackage,
     Source, Version,
 };
+use digest::to_checksum;
 use index::{Deployment, Index};
-use sha256::to_sha256;
+use signing::SigningKey;
 use update::Update;
 
 pub struct Repository {
     index: Box<Index>,
     objects: Box<Objects>,
+    /// Key used to sign published objects.
+    signing_key: Option<SigningKey>,
+    /// If set, only objects signed by one of these keys will be resolved.
+    trusted_keys: Option<Vec<String>>,
 }
 
 impl Repository {
@@ -18,9 +23,58 @@ impl Repository {
         Repository {
             index: index,
             objects: objects,
+            signing_key: None,
+            trusted_keys: None,
         }
     }
 
+    /// Sign all published objects with the given key.
+    pub fn with_signing_key(self, signing_key: Option<SigningKey>) -> Repository {
+        Repository {
+            signing_key,
+            ..self
+        }
+    }
+
+    /// Require that all resolved objects are signed by one of the given keys.
+    pub fn with_trusted_keys(self, trusted_keys: Option<Vec<String>>) -> Repository {
+        Repository {
+            trusted_keys,
+            ..self
+        }
+    }
+
+    /// Verify that the deployment is signed by a trusted key, if trusted keys are required.
+    fn verify_signature(&self, package: &RpPackage, deployment: &Deployment) -> Result<()> {
+        let trusted_keys = match self.trusted_keys {
+            Some(ref trusted_keys) => trusted_keys,
+            None => return Ok(()),
+        };
+
+        let signature = match deployment.signature {
+            Some(ref signature) => signature,
+            None => {
+                return Err(format!(
+                    "{}@{}: deployment is not signed",
+                    package, deployment.version
+                ).into())
+            }
+        };
+
+        if !trusted_keys.iter().any(|k| k.eq_ignore_ascii_case(&signature.key)) {
+            return Err(format!(
+                "{}@{}: signed by untrusted key: {}",
+                package, deployment.version, signature.key
+            ).into());
+        }
+
+        signature
+            .verify(&deployment.object)
+            .chain_err(|| format!("{}@{}: bad signature", package, deployment.version))?;
+
+        Ok(())
+    }
+
     pub fn update(&self) -> Result<Vec<Update>> {
         let mut updates = Vec::new();
         updates.extend(self.index.update()?);
@@ -29,11 +83,14 @@ impl Repository {
     }
 
     /// Publish the given package and version.
+    ///
+    /// The changelog describes what changed since the previous version, and may be empty.
     pub fn publish(
         &mut self,
         object: &Source,
         package: &RpPackage,
         version: &Version,
+        changelog: &[String],
         force: bool,
     ) -> Result<()> {
         if !self.index.get_deployments(package, version)?.is_empty() {
@@ -44,11 +101,19 @@ impl Repository {
             }
         }
 
-        let checksum = to_sha256(object.read()?)?;
+        let checksum = to_checksum(self.index.checksum_algorithm(), object.read()?)?;
+        let signature = self.signing_key.as_ref().map(|k| k.sign(&checksum));
 
         self.objects
             .put_object(&checksum, &mut object.read()?, force)?;
-        self.index.put_version(&checksum, package, version, force)?;
+        self.index.put_version(
+            &checksum,
+            package,
+            version,
+            signature.as_ref(),
+            changelog,
+            force,
+        )?;
 
         Ok(())
     }
@@ -59,11 +124,27 @@ impl Repository {
     }
 
     /// Get the object for the specific deployment.
+    ///
+    /// The content of the object is verified against the checksum of the deployment.
     pub fn get_object(&mut self, deployment: &Deployment) -> Result<Option<Source>> {
+        let expected = &deployment.object;
+
+        let source = match self.objects.get_object(expected)? {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+
+        let actual = to_checksum(expected.algorithm(), source.read()?)?;
+
+        if actual != *expected {
+            return Err(format!(
+                "{}: checksum mismatch, expected {} but was {}",
+                source, expected, actual
+            ).into());
+        }
+
         // NOTE: objects from repositories are _always_ read-only.
-        self.objects
-            .get_object(&deployment.object)
-            .map(|s| s.map(|s| s.with_read_only(true)))
+        Ok(Some(source.with_read_only(true)))
     }
 }
 
@@ -72,6 +153,8 @@ impl Resolver for Repository {
         let deployments = self.index.resolve(&package.package, &package.range)?;
 
         if let Some(deployment) = deployments.into_iter().next_back() {
+            self.verify_signature(&package.package, &deployment)?;
+
             if let Some(source) = self.get_object(&deployment)? {
                 return Ok(Some(Resolved {
                     version: Some(deployment.version),
@@ -85,6 +168,24 @@ impl Resolver for Repository {
         Ok(None)
     }
 
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> core::errors::Result<Vec<Resolved>> {
+        let mut out = Vec::new();
+
+        for deployment in self.index.resolve(&package.package, &package.range)? {
+            self.verify_signature(&package.package, &deployment)?;
+
+            match self.get_object(&deployment)? {
+                Some(source) => out.push(Resolved {
+                    version: Some(deployment.version),
+                    source,
+                }),
+                None => return Err(format!("missing object: {}", deployment.object).into()),
+            }
+        }
+
+        Ok(out)
+    }
+
     fn resolve_by_prefix(
         &mut self,
         package: &RpPackage,
@@ -94,6 +195,8 @@ impl Resolver for Repository {
         let deployments = self.index.resolve_by_prefix(&package)?;
 
         for (deployment, package) in deployments {
+            self.verify_signature(&package, &deployment)?;
+
             if let Some(source) = self.get_object(&deployment)? {
                 let package = RpVersionedPackage::new(package, Some(deployment.version));
                 out.push(ResolvedByPrefix { package, source });
@@ -18,6 +18,10 @@ impl Packages {
 
 impl Resolver for Packages {
     fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Option<Resolved>> {
+        Ok(self.resolve_all(package)?.into_iter().next_back())
+    }
+
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
         let mut resolved = Vec::new();
 
         for (p, source) in &self.packages {
@@ -44,7 +48,7 @@ impl Resolver for Packages {
             });
         }
 
-        Ok(resolved.into_iter().next_back())
+        Ok(resolved)
     }
 
     fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
@@ -8,6 +8,10 @@
 //! * `<root>/<package>/<last>/<version>.reproto`
 //!
 //! The second form is only used when a version requirement is present.
+//!
+//! When the same version of a package is found in more than one path, the path with the highest
+//! priority is used. Finding it in several paths with the same priority is a conflict, which is
+//! reported as an error unless the package is pinned to one of the paths.
 
 use core::errors::{Error, Result};
 use core::{
@@ -17,6 +21,7 @@ use core::{
 use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::ffi::OsStr;
 use std::fs;
+use std::io;
 use std::path::{Component, Path, PathBuf};
 
 pub const EXT: &str = "reproto";
@@ -27,11 +32,62 @@ pub struct Paths {
     paths: Vec<PathBuf>,
     /// Entries which are locally published.
     published: HashMap<RpPackage, Version>,
+    /// Priorities of paths, paths without a priority have the priority `0`.
+    priorities: HashMap<PathBuf, i32>,
+    /// Packages which are pinned to only be resolved from the given path.
+    pins: HashMap<RpPackage, PathBuf>,
 }
 
 impl Paths {
     pub fn new(paths: Vec<PathBuf>, published: HashMap<RpPackage, Version>) -> Paths {
-        Paths { paths, published }
+        Paths {
+            paths,
+            published,
+            priorities: HashMap::new(),
+            pins: HashMap::new(),
+        }
+    }
+
+    /// Set the priorities of paths.
+    pub fn with_priorities(self, priorities: HashMap<PathBuf, i32>) -> Self {
+        Self { priorities, ..self }
+    }
+
+    /// Set the packages which are pinned to a path.
+    pub fn with_pins(self, pins: HashMap<RpPackage, PathBuf>) -> Self {
+        Self { pins, ..self }
+    }
+
+    /// Priority of the given path.
+    fn priority(&self, path: &Path) -> i32 {
+        self.priorities.get(path).cloned().unwrap_or(0)
+    }
+
+    /// Finds the path a package is pinned to, from most to least specific package.
+    pub fn find_pin(&self, package: &RpPackage) -> Option<&PathBuf> {
+        if let Some(path) = self.pins.get(package) {
+            return Some(path);
+        }
+
+        let mut it = package.parts();
+
+        while let Some(_) = it.next_back() {
+            let package = RpPackage::new(it.as_slice().to_vec());
+
+            if let Some(path) = self.pins.get(&package) {
+                return Some(path);
+            }
+        }
+
+        None
+    }
+
+    /// Test if the given package may be resolved from the given path.
+    fn is_permitted(&self, package: &RpPackage, path: &Path) -> bool {
+        match self.find_pin(package) {
+            Some(pin) => pin == path,
+            None => true,
+        }
     }
 
     /// Finds the published version from most to least specific package.
@@ -113,23 +169,75 @@ impl Paths {
     }
 }
 
+/// Add a source found in a path with the given priority to the set of candidates.
+///
+/// Sources from paths with a higher priority replace sources from paths with a lower priority,
+/// while finding the same package in paths with the same priority is a conflict.
+fn add_candidate<K, N>(
+    candidates: &mut BTreeMap<K, (i32, Source)>,
+    key: K,
+    priority: i32,
+    source: Source,
+    name: N,
+) -> Result<()>
+where
+    K: Ord,
+    N: FnOnce(&K) -> String,
+{
+    if let Some(&(existing_priority, ref existing)) = candidates.get(&key) {
+        if existing.path().is_some() && existing.path() == source.path() {
+            return Ok(());
+        }
+
+        if existing_priority > priority {
+            return Ok(());
+        }
+
+        if existing_priority == priority {
+            return Err(format!(
+                "conflicting sources for `{}`: found in both {} and {}, give one of the paths a \
+                 higher priority or pin the package to one of them",
+                name(&key),
+                existing,
+                source
+            ).into());
+        }
+    }
+
+    candidates.insert(key, (priority, source));
+    Ok(())
+}
+
 impl Resolver for Paths {
     fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Option<Resolved>> {
-        let mut files = Vec::new();
+        Ok(self.resolve_all(package)?.into_iter().next_back())
+    }
+
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        let mut files = BTreeMap::new();
 
-        for path in &self.paths {
-            let mut path: PathBuf = path.to_owned();
+        for root in &self.paths {
+            if !self.is_permitted(&package.package, root) {
+                continue;
+            }
+
+            let priority = self.priority(root);
+
+            let mut path: PathBuf = root.to_owned();
             let mut it = package.package.parts().peekable();
 
             while let Some(base) = it.next() {
                 if it.peek().is_none() {
                     if path.is_dir() {
-                        files.extend(self.find_by_range(
-                            &path,
-                            base,
-                            &package.package,
-                            &package.range,
-                        )?);
+                        let found =
+                            self.find_by_range(&path, base, &package.package, &package.range)?;
+
+                        for Resolved { version, source } in found {
+                            add_candidate(&mut files, version, priority, source, |version| {
+                                RpVersionedPackage::new(package.package.clone(), version.clone())
+                                    .to_string()
+                            })?;
+                        }
                     }
 
                     break;
@@ -139,17 +247,20 @@ impl Resolver for Paths {
             }
         }
 
-        Ok(files.into_iter().next_back())
+        Ok(files
+            .into_iter()
+            .map(|(version, (_, source))| Resolved { version, source })
+            .collect())
     }
 
     fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
-        let mut out = Vec::new();
-        // contains a tuple: (package, path, search)
+        let mut out = BTreeMap::new();
+        // contains a tuple: (root, package, path, search)
         // search is an optional value which designates that we are searching for a specific
         // package.
         let mut queue = VecDeque::new();
 
-        for path in &self.paths {
+        for root in &self.paths {
             // last component needs special treatment.
             // if present, crack open parent directory and list it - it might contain both
             // leaf packages and additional sub-packages.
@@ -158,30 +269,32 @@ impl Resolver for Paths {
                 (package, None) => {
                     let path = package
                         .parts()
-                        .fold(path.to_owned(), |p, part| p.join(part));
+                        .fold(root.to_owned(), |p, part| p.join(part));
 
-                    queue.push_back((package, path.to_owned(), None));
+                    queue.push_back((root, package, path.to_owned(), None));
                     continue;
                 }
             };
 
             let path = package
                 .parts()
-                .fold(path.to_owned(), |p, part| p.join(part));
-            queue.push_back((package, path.to_owned(), Some(last)));
+                .fold(root.to_owned(), |p, part| p.join(part));
+            queue.push_back((root, package, path.to_owned(), Some(last)));
         }
 
-        while let Some((package, path, search)) = queue.pop_front() {
+        while let Some((root, package, path, search)) = queue.pop_front() {
             if !path.is_dir() {
                 continue;
             }
 
-            let entries = fs::read_dir(&path)
-                .map_err(|e| format!("failed to read directory: {}: {}", path.display(), e))?;
+            let mut entries = fs::read_dir(&path)
+                .map_err(|e| format!("failed to read directory: {}: {}", path.display(), e))?
+                .collect::<io::Result<Vec<_>>>()?;
 
-            for entry in entries {
-                let entry = entry?;
+            // NB: directory listings are not ordered, sort to resolve packages in a stable order.
+            entries.sort_by_key(|e| e.path());
 
+            for entry in entries {
                 let path = entry.path();
 
                 if path.is_file() {
@@ -207,9 +320,14 @@ impl Resolver for Paths {
                     }
 
                     let package = RpVersionedPackage::new(package.clone().join_part(name), version);
-                    let source = Source::from_path(&path);
 
-                    out.push(ResolvedByPrefix { package, source });
+                    if !self.is_permitted(&package.package, root) {
+                        continue;
+                    }
+
+                    let source = Source::from_path(&path);
+                    let priority = self.priority(root);
+                    add_candidate(&mut out, package, priority, source, ToString::to_string)?;
                     continue;
                 }
 
@@ -226,13 +344,16 @@ impl Resolver for Paths {
                     }
 
                     let package = package.clone().join_part(base);
-                    queue.push_back((package, path, None));
+                    queue.push_back((root, package, path, None));
                     continue;
                 }
             }
         }
 
-        Ok(out)
+        Ok(out
+            .into_iter()
+            .map(|(package, (_, source))| ResolvedByPrefix { package, source })
+            .collect())
     }
 
     fn resolve_packages(&mut self) -> Result<Vec<ResolvedByPrefix>> {
@@ -2,6 +2,7 @@
 
 use core::errors::Result;
 use core::{Resolved, ResolvedByPrefix, Resolver, RpPackage, RpRequiredPackage};
+use std::collections::BTreeMap;
 
 pub struct Resolvers {
     resolvers: Vec<Box<Resolver>>,
@@ -26,6 +27,19 @@ impl Resolver for Resolvers {
         Ok(None)
     }
 
+    fn resolve_all(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        let mut out = BTreeMap::new();
+
+        // earlier resolvers take precedence when the same version is resolved more than once.
+        for resolver in &mut self.resolvers.iter_mut() {
+            for resolved in resolver.resolve_all(package)? {
+                out.entry(resolved.version.clone()).or_insert(resolved);
+            }
+        }
+
+        Ok(out.into_iter().map(|(_, resolved)| resolved).collect())
+    }
+
     fn resolve_by_prefix(&mut self, package: &RpPackage) -> Result<Vec<ResolvedByPrefix>> {
         let mut out = Vec::new();
 
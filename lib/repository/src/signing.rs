@@ -0,0 +1,108 @@
+//! ## Signing and verification of published objects
+//!
+//! Publishers sign the checksum of an object using an ed25519 key, and the resulting signature is
+//! stored with the deployment in the index.
+
+use checksum::Checksum;
+use core::errors::*;
+use hex::FromHex;
+use hex_slice::HexSlice;
+use ring::rand::SystemRandom;
+use ring::signature::{self, Ed25519KeyPair};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use untrusted::Input;
+
+/// A signature of the checksum of a published object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    /// Hex-encoded public key of the publisher.
+    pub key: String,
+    /// Hex-encoded signature.
+    pub signature: String,
+}
+
+impl Signature {
+    /// Verify that this is a valid signature of the given checksum.
+    pub fn verify(&self, checksum: &Checksum) -> Result<()> {
+        let key: Vec<u8> =
+            FromHex::from_hex(&self.key).map_err(|e| format!("bad public key: {}", e))?;
+
+        let signature: Vec<u8> =
+            FromHex::from_hex(&self.signature).map_err(|e| format!("bad signature: {}", e))?;
+
+        let message = checksum.to_string();
+
+        signature::verify(
+            &signature::ED25519,
+            Input::from(&key),
+            Input::from(message.as_bytes()),
+            Input::from(&signature),
+        ).map_err(|_| format!("{}: bad signature by key {}", checksum, self.key))?;
+
+        Ok(())
+    }
+}
+
+/// A key used to sign objects when publishing.
+pub struct SigningKey {
+    pair: Ed25519KeyPair,
+}
+
+impl SigningKey {
+    /// Load a PKCS#8 encoded ed25519 key from the given path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<SigningKey> {
+        let path = path.as_ref();
+
+        let mut pkcs8 = Vec::new();
+        File::open(path)
+            .map_err(|e| format!("{}: failed to open key: {}", path.display(), e))?
+            .read_to_end(&mut pkcs8)?;
+
+        let pair = Ed25519KeyPair::from_pkcs8(Input::from(&pkcs8))
+            .map_err(|_| format!("{}: not a PKCS#8 encoded ed25519 key", path.display()))?;
+
+        Ok(SigningKey { pair })
+    }
+
+    /// Generate a new PKCS#8 encoded ed25519 key, and write it to the given path.
+    ///
+    /// Returns the hex-encoded public key.
+    pub fn generate<P: AsRef<Path>>(path: P) -> Result<String> {
+        let path = path.as_ref();
+
+        if path.exists() {
+            return Err(format!("{}: already exists", path.display()).into());
+        }
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| "failed to generate key")?;
+
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.is_dir() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        File::create(path)?.write_all(&pkcs8[..])?;
+
+        Ok(Self::from_path(path)?.public_key())
+    }
+
+    /// Hex-encoded public key.
+    pub fn public_key(&self) -> String {
+        HexSlice::new(self.pair.public_key_bytes()).to_string()
+    }
+
+    /// Sign the given checksum.
+    pub fn sign(&self, checksum: &Checksum) -> Signature {
+        let message = checksum.to_string();
+        let signature = self.pair.sign(message.as_bytes());
+
+        Signature {
+            key: self.public_key(),
+            signature: HexSlice::new(signature.as_ref()).to_string(),
+        }
+    }
+}
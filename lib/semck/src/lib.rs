@@ -8,6 +8,7 @@ use core::flavored::{
 };
 use core::{Loc, Span, Version};
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug, Clone)]
 pub enum Component {
@@ -37,6 +38,8 @@ pub enum Violation {
     RemoveVariant(Component, Span),
     /// Field added.
     AddField(Component, Span),
+    /// Reserved field was removed.
+    RemoveReserved(Component, Span),
     /// Variant added.
     AddVariant(Component, Span),
     /// Field type was changed from one to another.
@@ -73,6 +76,17 @@ fn fields<'a>(named: &RpNamed<'a>) -> Vec<&'a Loc<RpField>> {
     }
 }
 
+fn reserved<'a>(named: &RpNamed<'a>) -> Vec<&'a Loc<String>> {
+    use core::RpNamed::*;
+
+    match *named {
+        Type(target) => target.reserved.iter().collect(),
+        Interface(target) => target.reserved.iter().collect(),
+        SubType(target) => target.reserved.iter().collect(),
+        _ => vec![],
+    }
+}
+
 fn enum_variants<'a>(named: &'a RpNamed) -> Vec<RpVariantRef<'a>> {
     use core::RpNamed::*;
 
@@ -82,20 +96,28 @@ fn enum_variants<'a>(named: &'a RpNamed) -> Vec<RpVariantRef<'a>> {
     }
 }
 
-fn endpoints_to_map<'a>(named: &RpNamed<'a>) -> HashMap<&'a str, &'a Loc<RpEndpoint>> {
+fn endpoints<'a>(named: &RpNamed<'a>) -> Vec<&'a Loc<RpEndpoint>> {
     use core::RpNamed::*;
 
     match *named {
-        Service(target) => target.endpoints.iter().map(|e| (e.ident(), e)).collect(),
-        _ => HashMap::new(),
+        Service(target) => target.endpoints.iter().collect(),
+        _ => vec![],
     }
 }
 
-fn decls_to_map<'a, I: 'a>(decls: I) -> HashMap<RpName, RpNamed<'a>>
+fn endpoints_to_map<'a>(named: &RpNamed<'a>) -> HashMap<&'a str, &'a Loc<RpEndpoint>> {
+    endpoints(named)
+        .into_iter()
+        .map(|e| (e.ident(), e))
+        .collect()
+}
+
+/// Named declarations in the order that they are declared, by their local name.
+fn named_decls<'a, I: 'a>(decls: I) -> Vec<(RpName, RpNamed<'a>)>
 where
     I: IntoIterator<Item = &'a RpDecl>,
 {
-    let mut storage = HashMap::new();
+    let mut out = Vec::new();
 
     for decl in decls {
         for named in decl.to_named() {
@@ -104,11 +126,18 @@ where
                 continue;
             }
 
-            storage.insert(Loc::borrow(named.name()).clone().localize(), named);
+            out.push((Loc::borrow(named.name()).clone().localize(), named));
         }
     }
 
-    storage
+    out
+}
+
+fn decls_to_map<'a, I: 'a>(decls: I) -> HashMap<RpName, RpNamed<'a>>
+where
+    I: IntoIterator<Item = &'a RpDecl>,
+{
+    named_decls(decls).into_iter().collect()
 }
 
 fn variants_to_map<'a, I: 'a>(variants: I) -> HashMap<RpName, RpVariantRef<'a>>
@@ -137,6 +166,29 @@ where
     storage
 }
 
+/// Check that no reserved fields have been removed.
+fn check_reserved(
+    component: Component,
+    violations: &mut Vec<Violation>,
+    from_named: &RpNamed,
+    to_named: &RpNamed,
+) {
+    let to_reserved = reserved(to_named);
+
+    for from_reserved in reserved(from_named) {
+        let exists = to_reserved
+            .iter()
+            .any(|to_reserved| to_reserved.as_str() == from_reserved.as_str());
+
+        if !exists {
+            violations.push(RemoveReserved(
+                component.clone(),
+                Loc::span(from_reserved).into(),
+            ));
+        }
+    }
+}
+
 /// Perform checks on an endpoint channel.
 fn check_endpoint_channel<F, E>(
     component: Component,
@@ -291,6 +343,8 @@ fn check_minor(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
                 }
             }
 
+            check_reserved(Minor, &mut violations, &from_named, &to_named);
+
             let from_variants = variants_to_map(enum_variants(&from_named));
             let mut to_variants = variants_to_map(enum_variants(&to_named));
 
@@ -381,6 +435,8 @@ fn check_patch(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
                 violations.push(AddField(Patch, Loc::span(to_field).into()));
             }
 
+            check_reserved(Patch, &mut violations, &from_named, &to_named);
+
             let from_variants = variants_to_map(enum_variants(&from_named));
             let mut to_variants = variants_to_map(enum_variants(&to_named));
 
@@ -476,3 +532,325 @@ pub fn check(from: (&Version, &RpFile), to: (&Version, &RpFile)) -> Result<Vec<V
 
     Ok(vec![])
 }
+
+/// A structural change between two versions of a file.
+#[derive(Debug)]
+pub enum Change {
+    /// A declaration of the given kind was added.
+    DeclAdded(&'static str, RpName),
+    /// A declaration of the given kind was removed.
+    DeclRemoved(&'static str, RpName),
+    /// The kind of a declaration was changed.
+    DeclKindChange(RpName, &'static str, &'static str),
+    /// A field was added, and if it is required.
+    FieldAdded(RpName, String, bool),
+    /// A field was removed.
+    FieldRemoved(RpName, String),
+    /// The type of a field was changed.
+    FieldTypeChange(RpName, String, RpType, RpType),
+    /// A field was made required, or optional.
+    FieldRequiredChange(RpName, String, bool),
+    /// The name of a field was changed.
+    FieldNameChange(RpName, String, String, String),
+    /// A variant was added.
+    VariantAdded(RpName),
+    /// A variant was removed.
+    VariantRemoved(RpName),
+    /// The value of a variant was changed.
+    VariantValueChange(RpName, String, String),
+    /// An endpoint was added to a service.
+    EndpointAdded(RpName, String),
+    /// An endpoint was removed from a service.
+    EndpointRemoved(RpName, String),
+    /// The request of an endpoint was changed.
+    EndpointRequestChange(RpName, String, Option<RpChannel>, Option<RpChannel>),
+    /// The response of an endpoint was changed.
+    EndpointResponseChange(RpName, String, Option<RpChannel>, Option<RpChannel>),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Change::*;
+
+        match *self {
+            DeclAdded(kind, ref name) => write!(f, "added {} `{}`", kind, name),
+            DeclRemoved(kind, ref name) => write!(f, "removed {} `{}`", kind, name),
+            DeclKindChange(ref name, from, to) => {
+                write!(f, "changed `{}` from {} to {}", name, from, to)
+            }
+            FieldAdded(ref name, ref field, required) => {
+                let modifier = if required { "required" } else { "optional" };
+                write!(f, "added {} field `{}` to `{}`", modifier, field, name)
+            }
+            FieldRemoved(ref name, ref field) => {
+                write!(f, "removed field `{}` from `{}`", field, name)
+            }
+            FieldTypeChange(ref name, ref field, ref from, ref to) => write!(
+                f,
+                "changed type of field `{}` in `{}` from `{}` to `{}`",
+                field, name, from, to
+            ),
+            FieldRequiredChange(ref name, ref field, required) => {
+                let modifier = if required { "required" } else { "optional" };
+                write!(f, "made field `{}` in `{}` {}", field, name, modifier)
+            }
+            FieldNameChange(ref name, ref field, ref from, ref to) => write!(
+                f,
+                "changed name of field `{}` in `{}` from `{}` to `{}`",
+                field, name, from, to
+            ),
+            VariantAdded(ref name) => write!(f, "added variant `{}`", name),
+            VariantRemoved(ref name) => write!(f, "removed variant `{}`", name),
+            VariantValueChange(ref name, ref from, ref to) => write!(
+                f,
+                "changed value of variant `{}` from {} to {}",
+                name, from, to
+            ),
+            EndpointAdded(ref name, ref endpoint) => {
+                write!(f, "added endpoint `{}` to `{}`", endpoint, name)
+            }
+            EndpointRemoved(ref name, ref endpoint) => {
+                write!(f, "removed endpoint `{}` from `{}`", endpoint, name)
+            }
+            EndpointRequestChange(ref name, ref endpoint, ref from, ref to) => write!(
+                f,
+                "changed request of endpoint `{}` in `{}` from {} to {}",
+                endpoint,
+                name,
+                describe_channel(from.as_ref()),
+                describe_channel(to.as_ref())
+            ),
+            EndpointResponseChange(ref name, ref endpoint, ref from, ref to) => write!(
+                f,
+                "changed response of endpoint `{}` in `{}` from {} to {}",
+                endpoint,
+                name,
+                describe_channel(from.as_ref()),
+                describe_channel(to.as_ref())
+            ),
+        }
+    }
+}
+
+fn describe_channel(channel: Option<&RpChannel>) -> String {
+    match channel {
+        Some(channel) if channel.is_streaming() => format!("`stream {}`", channel.ty()),
+        Some(channel) => format!("`{}`", channel.ty()),
+        None => "nothing".to_string(),
+    }
+}
+
+/// The kind of the given declaration, as used in descriptions.
+fn kind(named: &RpNamed) -> &'static str {
+    use core::RpNamed::*;
+
+    match *named {
+        Type(_) => "type",
+        Tuple(_) => "tuple",
+        Interface(_) => "interface",
+        SubType(_) => "sub-type",
+        Enum(_) => "enum",
+        EnumVariant(_) => "variant",
+        Service(_) => "service",
+        Union(_) => "union",
+        Newtype(_) => "newtype",
+    }
+}
+
+/// Key used to compare channels, which ignores where types are imported from.
+fn channel_key(channel: Option<&RpChannel>) -> Option<(bool, RpType)> {
+    channel.map(|c| (c.is_streaming(), c.ty().clone().localize()))
+}
+
+/// Describe every structural change between two versions of a file.
+///
+/// Unlike `check`, all changes are reported regardless of how the version changed.
+/// Changes are ordered as the declarations they affect, followed by added declarations.
+pub fn diff(from: &RpFile, to: &RpFile) -> Result<Vec<Change>> {
+    let mut changes = Vec::new();
+
+    let from_decls = named_decls(&from.decls);
+    let to_decls = named_decls(&to.decls);
+    let to_storage = decls_to_map(&to.decls);
+
+    for &(ref name, ref from_named) in &from_decls {
+        let to_named = match to_storage.get(name) {
+            Some(to_named) => to_named,
+            None => {
+                changes.push(Change::DeclRemoved(kind(from_named), name.clone()));
+                continue;
+            }
+        };
+
+        if kind(from_named) != kind(to_named) {
+            changes.push(Change::DeclKindChange(
+                name.clone(),
+                kind(from_named),
+                kind(to_named),
+            ));
+        }
+
+        diff_fields(&mut changes, name, from_named, to_named);
+        diff_variants(&mut changes, from_named, to_named);
+        diff_endpoints(&mut changes, name, from_named, to_named);
+    }
+
+    let from_storage = decls_to_map(&from.decls);
+
+    for (name, to_named) in to_decls {
+        if !from_storage.contains_key(&name) {
+            changes.push(Change::DeclAdded(kind(&to_named), name));
+        }
+    }
+
+    return Ok(changes);
+
+    fn diff_fields(changes: &mut Vec<Change>, name: &RpName, from: &RpNamed, to: &RpNamed) {
+        let from_fields = fields(from);
+        let to_fields = fields(to);
+        let from_map = fields_to_map(from_fields.iter().cloned());
+        let to_map = fields_to_map(to_fields.iter().cloned());
+
+        for from_field in from_fields {
+            let ident = from_field.ident().to_string();
+
+            let to_field = match to_map.get(&ident) {
+                Some(to_field) => to_field,
+                None => {
+                    changes.push(Change::FieldRemoved(name.clone(), ident));
+                    continue;
+                }
+            };
+
+            let from_ty = from_field.ty.clone().localize();
+            let to_ty = to_field.ty.clone().localize();
+
+            if from_ty != to_ty {
+                changes.push(Change::FieldTypeChange(
+                    name.clone(),
+                    ident.clone(),
+                    from_field.ty.clone(),
+                    to_field.ty.clone(),
+                ));
+            }
+
+            if from_field.is_required() != to_field.is_required() {
+                changes.push(Change::FieldRequiredChange(
+                    name.clone(),
+                    ident.clone(),
+                    to_field.is_required(),
+                ));
+            }
+
+            if from_field.name() != to_field.name() {
+                changes.push(Change::FieldNameChange(
+                    name.clone(),
+                    ident.clone(),
+                    from_field.name().to_string(),
+                    to_field.name().to_string(),
+                ));
+            }
+        }
+
+        for to_field in to_fields {
+            if !from_map.contains_key(to_field.ident()) {
+                changes.push(Change::FieldAdded(
+                    name.clone(),
+                    to_field.ident().to_string(),
+                    to_field.is_required(),
+                ));
+            }
+        }
+    }
+
+    fn diff_variants(changes: &mut Vec<Change>, from: &RpNamed, to: &RpNamed) {
+        let from_variants = enum_variants(from);
+        let to_variants = enum_variants(to);
+        let from_map = variants_to_map(from_variants.iter().cloned());
+        let to_map = variants_to_map(to_variants.iter().cloned());
+
+        for from_variant in from_variants {
+            let name = Loc::borrow(&from_variant.name).clone().localize();
+
+            let to_variant = match to_map.get(&name) {
+                Some(to_variant) => to_variant,
+                None => {
+                    changes.push(Change::VariantRemoved(name));
+                    continue;
+                }
+            };
+
+            if from_variant.value != to_variant.value {
+                changes.push(Change::VariantValueChange(
+                    name,
+                    from_variant.value.to_string(),
+                    to_variant.value.to_string(),
+                ));
+            }
+        }
+
+        for to_variant in to_variants {
+            let name = Loc::borrow(&to_variant.name).clone().localize();
+
+            if !from_map.contains_key(&name) {
+                changes.push(Change::VariantAdded(name));
+            }
+        }
+    }
+
+    fn diff_endpoints(changes: &mut Vec<Change>, name: &RpName, from: &RpNamed, to: &RpNamed) {
+        let from_map = endpoints_to_map(from);
+        let to_map = endpoints_to_map(to);
+
+        for from_endpoint in endpoints(from) {
+            let ident = from_endpoint.ident().to_string();
+
+            let to_endpoint = match to_map.get(from_endpoint.ident()) {
+                Some(to_endpoint) => to_endpoint,
+                None => {
+                    changes.push(Change::EndpointRemoved(name.clone(), ident));
+                    continue;
+                }
+            };
+
+            let from_request = from_endpoint
+                .request
+                .as_ref()
+                .map(|r| Loc::borrow(&r.channel));
+            let to_request = to_endpoint
+                .request
+                .as_ref()
+                .map(|r| Loc::borrow(&r.channel));
+
+            if channel_key(from_request) != channel_key(to_request) {
+                changes.push(Change::EndpointRequestChange(
+                    name.clone(),
+                    ident.clone(),
+                    from_request.cloned(),
+                    to_request.cloned(),
+                ));
+            }
+
+            let from_response = from_endpoint.response.as_ref().map(Loc::borrow);
+            let to_response = to_endpoint.response.as_ref().map(Loc::borrow);
+
+            if channel_key(from_response) != channel_key(to_response) {
+                changes.push(Change::EndpointResponseChange(
+                    name.clone(),
+                    ident.clone(),
+                    from_response.cloned(),
+                    to_response.cloned(),
+                ));
+            }
+        }
+
+        for to_endpoint in endpoints(to) {
+            if !from_map.contains_key(to_endpoint.ident()) {
+                changes.push(Change::EndpointAdded(
+                    name.clone(),
+                    to_endpoint.ident().to_string(),
+                ));
+            }
+        }
+    }
+}
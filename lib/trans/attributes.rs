@@ -2,10 +2,12 @@
 
 use core::errors::Error;
 use core::flavored::{
-    Attributes, RpAccept, RpChannel, RpEndpointArgument, RpEndpointHttp, RpHttpMethod, RpPathSpec,
-    RpValue,
+    Attributes, RpAccept, RpChannel, RpEndpointArgument, RpEndpointHttp, RpHttpMethod, RpHttpParam,
+    RpPathSpec, RpValue,
+};
+use core::{
+    self, Diagnostics, Import, Loc, RpDeprecated, RpStringValidate, Span, Version, WithSpan,
 };
-use core::{self, Diagnostics, Import, Loc, RpStringValidate, Span, Version, WithSpan};
 use features::Feature;
 use into_model::IntoModel;
 use path_parser;
@@ -13,6 +15,162 @@ use regex_parser;
 use scope::Scope;
 use std::collections::HashMap;
 
+/// Attributes which are handled by the compiler.
+///
+/// These are not permitted as custom attributes.
+const BUILTIN: &[&str] = &[
+    "deprecated",
+    "endpoint_naming",
+    "feature",
+    "field_naming",
+    "format",
+    "http",
+    "id",
+    "import",
+    "reproto",
+    "reserved",
+    "type_info",
+    "validate",
+];
+
+/// Check the attributes which remain after the compiler has taken the ones it handles.
+///
+/// Remaining attributes are kept as custom attributes in the model, where they are available to
+/// backends. Built-in attributes which are used in the wrong location are reported as errors.
+pub fn custom(diag: &mut Diagnostics, attributes: &Attributes) -> Result<(), ()> {
+    for name in BUILTIN {
+        if let Some(span) = attributes.span(*name) {
+            diag.err_with_code(
+                &core::codes::MISPLACED_ATTRIBUTE,
+                span,
+                format!("#[{}] is not supported here", name),
+            );
+        }
+    }
+
+    if diag.has_errors() {
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/// `#[deprecated]` and `#[deprecated(note = "..")]` attributes.
+pub fn deprecated(
+    diag: &mut Diagnostics,
+    attributes: &mut Attributes,
+) -> Result<Option<RpDeprecated>, ()> {
+    if attributes.take_word("deprecated") {
+        return Ok(Some(RpDeprecated { note: None }));
+    }
+
+    let selection = match attributes.take_selection("deprecated") {
+        Some(selection) => selection,
+        None => return Ok(None),
+    };
+
+    let (mut selection, _) = Loc::take_pair(selection);
+
+    let note = match selection.take("note") {
+        Some(note) => {
+            let (note, span) = Loc::take_pair(note);
+
+            Some(
+                note.as_string()
+                    .map(|note| note.to_string())
+                    .with_span(diag, span)?,
+            )
+        }
+        None => None,
+    };
+
+    check_selection!(diag, selection);
+
+    if diag.has_errors() {
+        return Err(());
+    }
+
+    Ok(Some(RpDeprecated { note }))
+}
+
+/// `#[id(..)]` attribute on fields, assigning a stable numeric identifier.
+pub fn field_id(diag: &mut Diagnostics, attributes: &mut Attributes) -> Result<Option<u32>, ()> {
+    let selection = match attributes.take_selection("id") {
+        Some(selection) => selection,
+        None => return Ok(None),
+    };
+
+    let (mut selection, attribute_span) = Loc::take_pair(selection);
+
+    let id = match selection.take_word() {
+        Some(id) => id,
+        None => {
+            diag.err(attribute_span, "expected argument");
+            return Err(());
+        }
+    };
+
+    let (id, span) = Loc::take_pair(id);
+
+    let id = match id.as_number().ok().and_then(|n| n.to_u32()) {
+        Some(id) if id > 0 => id,
+        _ => {
+            diag.err(span, "expected a positive integer");
+            return Err(());
+        }
+    };
+
+    check_selection!(diag, selection);
+    Ok(Some(id))
+}
+
+/// `#[number_format(..)]` attribute on fields, selecting if a 64-bit integer is encoded as a JSON
+/// number or a JSON string.
+///
+/// Returns `Some(true)` if the field should be encoded as a string.
+pub fn number_format(
+    diag: &mut Diagnostics,
+    attributes: &mut Attributes,
+) -> Result<Option<Loc<bool>>, ()> {
+    let selection = match attributes.take_selection("number_format") {
+        Some(selection) => selection,
+        None => return Ok(None),
+    };
+
+    let (mut selection, attribute_span) = Loc::take_pair(selection);
+
+    let format = match selection.take_word() {
+        Some(format) => format,
+        None => {
+            diag.err(attribute_span, "expected argument");
+            return Err(());
+        }
+    };
+
+    let (format, span) = Loc::take_pair(format);
+
+    let format = match format.into_string() {
+        Ok(format) => format,
+        Err(e) => {
+            diag.err(span, e.display());
+            return Err(());
+        }
+    };
+
+    let as_string = match format.as_str() {
+        "number" => false,
+        "string" => true,
+        _ => {
+            diag.err(span, "unexpected number format");
+            diag.info(span, "HINT: expected one of `number` or `string`");
+            return Err(());
+        }
+    };
+
+    check_selection!(diag, selection);
+    Ok(Some(Loc::new(as_string, attribute_span)))
+}
+
 /// `#![feature(..)]` attributes.
 pub fn features<'s, I>(
     scope: &'s Scope<I>,
@@ -155,30 +313,41 @@ where
         http.method = Some(parse_method(diag, method)?);
     }
 
-    if let Some(accept) = selection.take("accept") {
-        let (accept, span) = Loc::take_pair(accept);
+    if let Some(query) = selection.take("query") {
+        http.query = parse_params(diag, query, &mut args)?;
+    }
 
-        let a = accept.as_string().with_span(diag, span)?;
+    if let Some(headers) = selection.take("headers") {
+        http.headers = parse_params(diag, headers, &mut args)?;
+    }
 
-        let accept = match a {
-            "application/json" => core::RpAccept::Json,
-            "text/plain" => core::RpAccept::Text,
-            _ => {
-                diag.err(span, "unsupported media type");
-                return Err(());
-            }
-        };
+    // NB: `accept` is the original name of `produces`.
+    let produces = match selection.take("produces") {
+        Some(produces) => Some(produces),
+        None => selection.take("accept"),
+    };
 
-        let accept = Loc::new(accept, span);
-        http_verify_accept(diag, &accept, response)?;
-        http.accept = Loc::take(accept);
+    if let Some(produces) = produces {
+        let produces = parse_media_type(diag, produces)?;
+        http_verify_accept(diag, &produces, response, "response")?;
+        http.produces = Loc::take(produces);
     }
 
+    let accepts = match selection.take("accepts") {
+        Some(accepts) => Some(parse_media_type(diag, accepts)?),
+        None => None,
+    };
+
     // All arguments used, no request body.
     if args.is_empty() {
         *request = None;
     }
 
+    // The only remaining argument is the request body.
+    if args.len() == 1 {
+        *request = args.values().next().map(|a| (*a).clone());
+    }
+
     // Assert that all arguments are used somehow.
     if !args.is_empty() {
         for arg in args.values() {
@@ -188,7 +357,8 @@ where
                 }
             }
 
-            diag.err(
+            diag.err_with_code(
+                &core::codes::UNUSED_HTTP_ARGUMENT,
                 Loc::span(&arg.ident),
                 "Argument not used in #[http(...)] attribute",
             );
@@ -199,9 +369,31 @@ where
         }
     }
 
+    if let Some(accepts) = accepts {
+        let request = request.as_ref().map(|r| &r.channel);
+        http_verify_accept(diag, &accepts, request, "request")?;
+        http.accepts = Loc::take(accepts);
+    }
+
     check_selection!(diag, selection);
     return Ok(http);
 
+    /// Parse a supported media type.
+    fn parse_media_type(diag: &mut Diagnostics, value: Loc<RpValue>) -> Result<Loc<RpAccept>, ()> {
+        let (value, span) = Loc::take_pair(value);
+
+        let accept = match value.as_string().with_span(diag, span)? {
+            "application/json" => core::RpAccept::Json,
+            "text/plain" => core::RpAccept::Text,
+            _ => {
+                diag.err(span, "unsupported media type");
+                return Err(());
+            }
+        };
+
+        Ok(Loc::new(accept, span))
+    }
+
     /// Parse a path specification.
     fn parse_path<'a, 'b: 'a, I>(
         diag: &mut Diagnostics,
@@ -228,6 +420,95 @@ where
         Ok(path)
     }
 
+    /// Parse parameters which are bound to arguments, like `(limit, (token, "X-Token"))`.
+    ///
+    /// A plain argument is sent using its own name.
+    fn parse_params<'a, 'b: 'a>(
+        diag: &mut Diagnostics,
+        params: Loc<RpValue>,
+        args: &'a mut HashMap<&'b str, &'b RpEndpointArgument>,
+    ) -> Result<Vec<RpHttpParam>, ()> {
+        let (params, span) = Loc::take_pair(params);
+
+        let params = match params {
+            core::RpValue::Array(params) => params,
+            _ => {
+                diag.err(span, "expected a list of arguments, like `(a, b)`");
+                return Err(());
+            }
+        };
+
+        let mut out = Vec::new();
+
+        for param in params {
+            let (param, span) = Loc::take_pair(param);
+
+            let (ident, name) = match param {
+                core::RpValue::Identifier(ident) => (ident.clone(), ident),
+                core::RpValue::Array(pair) => {
+                    let mut it = pair.into_iter().map(Loc::take);
+
+                    match (it.next(), it.next(), it.next()) {
+                        (
+                            Some(core::RpValue::Identifier(ident)),
+                            Some(core::RpValue::String(name)),
+                            None,
+                        ) => (ident, name),
+                        _ => {
+                            diag.err(span, "expected an argument and a name, like `(a, \"A\")`");
+                            return Err(());
+                        }
+                    }
+                }
+                _ => {
+                    diag.err(span, "expected an argument");
+                    return Err(());
+                }
+            };
+
+            let argument = match args.remove(ident.as_str()) {
+                Some(argument) => argument.clone(),
+                None => {
+                    diag.err(
+                        span,
+                        format!(
+                            "`{}` is not an argument to endpoint, or is already used",
+                            ident
+                        ),
+                    );
+                    return Err(());
+                }
+            };
+
+            if !is_simple(&argument.channel) {
+                diag.err(
+                    Loc::span(&argument.channel),
+                    "must be a string, a number, or a boolean",
+                );
+                diag.info(span, "sent as a parameter here");
+                return Err(());
+            }
+
+            out.push(RpHttpParam { name, argument });
+        }
+
+        return Ok(out);
+
+        /// Only simple types can be encoded as a parameter.
+        fn is_simple(channel: &RpChannel) -> bool {
+            use core::RpType::*;
+
+            if channel.is_streaming() {
+                return false;
+            }
+
+            match *channel.ty() {
+                Double | Float | Number(..) | Boolean | String(..) | Scalar(..) => true,
+                _ => false,
+            }
+        }
+    }
+
     /// Parse a method.
     fn parse_method(diag: &mut Diagnostics, method: Loc<RpValue>) -> Result<RpHttpMethod, ()> {
         use core::RpHttpMethod::*;
@@ -251,14 +532,15 @@ where
         Ok(m)
     }
 
-    /// Check that accept matches response.
+    /// Check that the media type matches the request or response.
     fn http_verify_accept(
         diag: &mut Diagnostics,
         accept: &Loc<RpAccept>,
-        response: Option<&Loc<RpChannel>>,
+        channel: Option<&Loc<RpChannel>>,
+        what: &str,
     ) -> Result<(), ()> {
-        let response = match response {
-            Some(response) => response,
+        let channel = match channel {
+            Some(channel) => channel,
             None => return Ok(()),
         };
 
@@ -268,13 +550,16 @@ where
             // Can handle complex data types.
             ref accept if *accept == core::RpAccept::Json => return Ok(()),
             _ => {
-                if let core::RpType::String(..) = *response.ty() {
+                if let core::RpType::String(..) = *channel.ty() {
                     return Ok(());
                 }
 
                 diag.err(
-                    Loc::span(response),
-                    "only `string` responses are supported for the given `accept`",
+                    Loc::span(channel),
+                    format!(
+                        "only `string` {}s are supported for the given media type",
+                        what
+                    ),
                 );
 
                 diag.info(span, "Specified here");
@@ -303,6 +588,9 @@ pub fn import(diag: &mut Diagnostics, attributes: &mut Attributes) -> Result<Vec
 
 pub enum StringFormat {
     DateTime,
+    Date,
+    Time,
+    Duration,
     Bytes,
 }
 
@@ -338,10 +626,16 @@ pub fn string_format(
 
     let format = match format.as_str() {
         "datetime" => StringFormat::DateTime,
+        "date" => StringFormat::Date,
+        "time" => StringFormat::Time,
+        "duration" => StringFormat::Duration,
         "bytes" => StringFormat::Bytes,
         _ => {
             diag.err(span, "unexpected format");
-            diag.info(span, "HINT: expected one of `datetime` or `bytes`");
+            diag.info(
+                span,
+                "HINT: expected one of `datetime`, `date`, `time`, `duration`, or `bytes`",
+            );
             return Err(());
         }
     };
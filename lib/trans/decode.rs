@@ -0,0 +1,349 @@
+//! Checks that JSON documents decode against the declarations in a session.
+//!
+//! This is used to verify that documents which were recorded against an older version of a schema
+//! can still be decoded, like when a required field has been added.
+
+use core::{
+    CoreFlavor, Loc, RpDecl, RpField, RpFile, RpName, RpNumber, RpNumberKind, RpPackage,
+    RpSubTypeStrategy, RpType, RpVariantValue, RpVersionedPackage,
+};
+use json;
+use std::collections::HashMap;
+
+/// Decodes JSON documents against the declarations in a set of files.
+pub struct Decoder<'a> {
+    files: HashMap<&'a RpVersionedPackage, &'a RpFile<CoreFlavor>>,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(files: HashMap<&'a RpVersionedPackage, &'a RpFile<CoreFlavor>>) -> Decoder<'a> {
+        Decoder { files }
+    }
+
+    /// Look up the declaration with the given name.
+    pub fn lookup(&self, name: &RpName<CoreFlavor>) -> Option<&'a RpDecl<CoreFlavor>> {
+        let file = self.files.get(&name.package)?;
+        find(file, &name.path)
+    }
+
+    /// Look up the declaration with the given path in the most recent version of the given
+    /// package.
+    pub fn lookup_latest(
+        &self,
+        package: &RpPackage,
+        path: &[String],
+    ) -> Option<&'a RpDecl<CoreFlavor>> {
+        let (_, file) = self
+            .files
+            .iter()
+            .filter(|&(p, _)| p.package == *package)
+            .max_by_key(|&(p, _)| &p.version)?;
+
+        find(file, path)
+    }
+
+    /// Decode the given value as the given declaration.
+    ///
+    /// Every problem found is added to `errors`, prefixed with the path in the document where it
+    /// was found.
+    pub fn decode_decl(
+        &self,
+        decl: &RpDecl<CoreFlavor>,
+        value: &json::Value,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        use core::RpDecl::*;
+
+        match *decl {
+            Type(ref body) => {
+                self.decode_fields(body.fields.iter(), value, path, errors);
+            }
+            Tuple(ref body) => {
+                let values = match *value {
+                    json::Value::Array(ref values) => values,
+                    _ => {
+                        errors.push(format!("{}: expected array", path));
+                        return;
+                    }
+                };
+
+                for (index, field) in body.fields.iter().enumerate() {
+                    let path = format!("{}[{}]", path, index);
+                    self.decode_field(field, values.get(index), &path, errors);
+                }
+            }
+            Interface(ref body) => {
+                let object = match *value {
+                    json::Value::Object(ref object) => object,
+                    _ => {
+                        errors.push(format!("{}: expected object", path));
+                        return;
+                    }
+                };
+
+                match body.sub_type_strategy {
+                    RpSubTypeStrategy::Tagged { ref tag } => {
+                        let name = match object.get(tag.as_str()) {
+                            Some(&json::Value::String(ref name)) => name,
+                            Some(_) => {
+                                errors.push(format!("{}.{}: expected string", path, tag));
+                                return;
+                            }
+                            None => {
+                                errors.push(format!("{}: missing type tag `{}`", path, tag));
+                                return;
+                            }
+                        };
+
+                        let sub_type = match body.sub_types.iter().find(|s| s.name() == name) {
+                            Some(sub_type) => sub_type,
+                            None => {
+                                errors.push(format!(
+                                    "{}.{}: `{}` is not a sub-type of `{}`",
+                                    path, tag, name, body.name
+                                ));
+                                return;
+                            }
+                        };
+
+                        let fields = body.fields.iter().chain(sub_type.fields.iter());
+                        self.decode_fields(fields, value, path, errors);
+                    }
+                    RpSubTypeStrategy::Untagged => {
+                        let matches = body.sub_types.iter().any(|sub_type| {
+                            let mut sub_type_errors = Vec::new();
+                            let fields = body.fields.iter().chain(sub_type.fields.iter());
+                            self.decode_fields(fields, value, path, &mut sub_type_errors);
+                            sub_type_errors.is_empty()
+                        });
+
+                        if !matches {
+                            errors.push(format!(
+                                "{}: does not match any sub-type of `{}`",
+                                path, body.name
+                            ));
+                        }
+                    }
+                }
+            }
+            Enum(ref body) => {
+                let matches = body.variants.iter().any(|variant| {
+                    matches_variant(&variant.value, value)
+                        || variant.aliases.iter().any(|a| matches_variant(&a, value))
+                });
+
+                if !matches {
+                    errors.push(format!(
+                        "{}: {} is not a variant of `{}`",
+                        path, value, body.name
+                    ));
+                }
+            }
+            Union(ref body) => {
+                let matches = body.variants.iter().any(|variant| {
+                    let mut variant_errors = Vec::new();
+                    self.decode(&variant.ty, value, path, &mut variant_errors);
+                    variant_errors.is_empty()
+                });
+
+                if !matches {
+                    errors.push(format!(
+                        "{}: does not match any variant of `{}`",
+                        path, body.name
+                    ));
+                }
+            }
+            Newtype(ref body) => {
+                self.decode(&body.ty, value, path, errors);
+            }
+            Service(ref body) => {
+                errors.push(format!("{}: `{}` is a service", path, body.name));
+            }
+        }
+    }
+
+    /// Decode an object with the given fields.
+    fn decode_fields<'f, I>(
+        &self,
+        fields: I,
+        value: &json::Value,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) where
+        I: IntoIterator<Item = &'f Loc<RpField<CoreFlavor>>>,
+    {
+        let object = match *value {
+            json::Value::Object(ref object) => object,
+            _ => {
+                errors.push(format!("{}: expected object", path));
+                return;
+            }
+        };
+
+        // NB: unknown fields are ignored, since they might belong to fields which have since been
+        // removed.
+        for field in fields {
+            let path = format!("{}.{}", path, field.name());
+            self.decode_field(field, object.get(field.name()), &path, errors);
+        }
+    }
+
+    /// Decode a single field, which might be absent.
+    fn decode_field(
+        &self,
+        field: &RpField<CoreFlavor>,
+        value: Option<&json::Value>,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        match value {
+            None | Some(&json::Value::Null) => {
+                if !field.is_optional() {
+                    errors.push(format!("{}: missing required field", path));
+                }
+            }
+            Some(&json::Value::String(ref number)) if field.is_number_as_string() => {
+                if number.parse::<i64>().is_err() && number.parse::<u64>().is_err() {
+                    errors.push(format!("{}: expected integer string", path));
+                }
+            }
+            Some(value) => self.decode(&field.ty, value, path, errors),
+        }
+    }
+
+    /// Decode the given value as the given type.
+    fn decode(
+        &self,
+        ty: &RpType<CoreFlavor>,
+        value: &json::Value,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        use self::json::Value::*;
+
+        let expected = match (ty, value) {
+            (&RpType::Any, _) => return,
+            (&RpType::Double, &Number(_)) | (&RpType::Float, &Number(_)) => return,
+            (&RpType::Double, _) | (&RpType::Float, _) => "number",
+            (&RpType::Number(ref number), value) => {
+                if matches_number(number.kind, value) {
+                    return;
+                }
+
+                "integer"
+            }
+            (&RpType::Boolean, &Bool(_)) => return,
+            (&RpType::Boolean, _) => "boolean",
+            (&RpType::String(_), &String(_)) => return,
+            (&RpType::String(_), _) => "string",
+            (&RpType::DateTime, &String(_)) => return,
+            (&RpType::DateTime, _) => "datetime string",
+            (&RpType::Date, &String(_)) => return,
+            (&RpType::Date, _) => "date string",
+            (&RpType::Time, &String(_)) => return,
+            (&RpType::Time, _) => "time string",
+            (&RpType::Duration, &String(_)) => return,
+            (&RpType::Duration, _) => "duration string",
+            (&RpType::Bytes, &String(_)) => return,
+            (&RpType::Bytes, _) => "base64 string",
+            (&RpType::Scalar(ref scalar), value) => {
+                self.decode(&scalar.base_type(), value, path, errors);
+                return;
+            }
+            (&RpType::Name { ref name }, value) => {
+                match self.lookup(Loc::borrow(name)) {
+                    Some(decl) => self.decode_decl(decl, value, path, errors),
+                    None => errors.push(format!("{}: no such declaration `{}`", path, name)),
+                }
+
+                return;
+            }
+            (&RpType::Array { ref inner }, &Array(ref values))
+            | (&RpType::Set { ref inner }, &Array(ref values)) => {
+                for (index, value) in values.iter().enumerate() {
+                    let path = format!("{}[{}]", path, index);
+                    self.decode(inner, value, &path, errors);
+                }
+
+                return;
+            }
+            (&RpType::Array { .. }, _) | (&RpType::Set { .. }, _) => "array",
+            (&RpType::Map { ref value, .. }, &Object(ref entries)) => {
+                for (key, entry) in entries {
+                    let path = format!("{}[{:?}]", path, key);
+                    self.decode(value, entry, &path, errors);
+                }
+
+                return;
+            }
+            (&RpType::Map { .. }, _) => "object",
+        };
+
+        errors.push(format!("{}: expected {}", path, expected));
+    }
+}
+
+/// Find the declaration with the given path in a file.
+fn find<'a>(file: &'a RpFile<CoreFlavor>, path: &[String]) -> Option<&'a RpDecl<CoreFlavor>> {
+    let mut path = path.iter();
+
+    let first = path.next()?;
+    let mut decl = file.decls.iter().find(|d| d.ident() == first.as_str())?;
+
+    for part in path {
+        decl = decl.decls().find(|d| d.ident() == part.as_str())?;
+    }
+
+    Some(decl)
+}
+
+/// Check if the given value is a number of the given kind.
+fn matches_number(kind: RpNumberKind, value: &json::Value) -> bool {
+    use core::RpNumberKind::*;
+
+    let number = match *value {
+        json::Value::Number(ref number) => number,
+        // NB: numbers which don't fit in a double might be encoded as strings.
+        json::Value::String(_) => return kind == U128 || kind == I128 || kind == Decimal,
+        _ => return false,
+    };
+
+    match kind {
+        U32 => number
+            .as_u64()
+            .map(|n| n <= u32::max_value() as u64)
+            .unwrap_or(false),
+        U64 | U128 => number.is_u64(),
+        I32 => number
+            .as_i64()
+            .map(|n| n >= i32::min_value() as i64 && n <= i32::max_value() as i64)
+            .unwrap_or(false),
+        I64 => number.is_i64(),
+        I128 => number.is_i64() || number.is_u64(),
+        Decimal => true,
+    }
+}
+
+/// Check if the given value is the value of an enum variant.
+fn matches_variant(variant: &RpVariantValue, value: &json::Value) -> bool {
+    match (variant, value) {
+        (&RpVariantValue::String(variant), &json::Value::String(ref value)) => variant == value,
+        (&RpVariantValue::Number(variant), &json::Value::Number(ref value)) => {
+            matches_variant_number(variant, value)
+        }
+        _ => false,
+    }
+}
+
+fn matches_variant_number(variant: &RpNumber, value: &json::Number) -> bool {
+    if let (Some(variant), Some(value)) = (variant.to_u64(), value.as_u64()) {
+        return variant == value;
+    }
+
+    if let (Some(variant), Some(value)) = (variant.to_i64(), value.as_i64()) {
+        return variant == value;
+    }
+
+    false
+}
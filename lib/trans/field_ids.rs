@@ -0,0 +1,421 @@
+//! Assignment of stable numeric identifiers to fields.
+//!
+//! Identifiers are either declared in the schema using `#[id(<n>)]`, or assigned by the compiler.
+//! Every identifier which has been used is recorded, including the identifiers of fields which
+//! have since been removed, so that an identifier is never reused for a different field.
+
+use core::{CoreFlavor, Loc, RpDecl, RpField, RpName, Source, SourceDiagnostics};
+use std::collections::BTreeMap;
+
+/// Identifiers which have been used by the fields of each declaration.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldIds {
+    /// Identifiers by the name of the declaration and the identifier of the field, like
+    /// `io.reproto.example::Foo` and `name`.
+    pub declarations: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl FieldIds {
+    pub fn new() -> FieldIds {
+        FieldIds::default()
+    }
+
+    /// Assign identifiers to the fields of the given declarations, and all their nested
+    /// declarations.
+    ///
+    /// Returns `true` if any identifier was recorded which wasn't known before.
+    pub fn assign(
+        &mut self,
+        diag: &mut SourceDiagnostics,
+        source: &Source,
+        decls: &mut [RpDecl<CoreFlavor>],
+    ) -> bool {
+        let mut changed = false;
+
+        for decl in decls {
+            changed |= self.assign_decl(diag, source, decl);
+        }
+
+        changed
+    }
+
+    fn assign_decl(
+        &mut self,
+        diag: &mut SourceDiagnostics,
+        source: &Source,
+        decl: &mut RpDecl<CoreFlavor>,
+    ) -> bool {
+        use core::RpDecl::*;
+
+        let mut changed = false;
+
+        let decls = match *decl {
+            Type(ref mut body) => {
+                changed |= self.assign_fields(diag, source, &body.name, &mut body.fields, &[]);
+                &mut body.decls
+            }
+            Tuple(ref mut body) => {
+                changed |= self.assign_fields(diag, source, &body.name, &mut body.fields, &[]);
+                &mut body.decls
+            }
+            Interface(ref mut body) => {
+                // NB: the fields of an interface are shared with every sub-type, so they can't use
+                // the identifiers of any field in a sub-type, and the other way around.
+                let mut sub_type_ids = Vec::new();
+
+                for sub_type in &body.sub_types {
+                    sub_type_ids.extend(self.reserved(&sub_type.name, &sub_type.fields));
+                }
+
+                changed |=
+                    self.assign_fields(diag, source, &body.name, &mut body.fields, &sub_type_ids);
+
+                let shared = self.reserved(&body.name, &body.fields);
+
+                for sub_type in &mut body.sub_types {
+                    let sub_type = &mut **sub_type;
+
+                    changed |= self.assign_fields(
+                        diag,
+                        source,
+                        &sub_type.name,
+                        &mut sub_type.fields,
+                        &shared,
+                    );
+
+                    changed |= self.assign(diag, source, &mut sub_type.decls);
+                }
+
+                &mut body.decls
+            }
+            Enum(ref mut body) => &mut body.decls,
+            Service(ref mut body) => &mut body.decls,
+            Union(ref mut body) => &mut body.decls,
+            Newtype(ref mut body) => &mut body.decls,
+        };
+
+        changed |= self.assign(diag, source, decls);
+        changed
+    }
+
+    /// Identifiers which are used by the given declaration, either declared by its fields or
+    /// recorded before, together with the name of the declaration.
+    fn reserved(
+        &self,
+        name: &Loc<RpName<CoreFlavor>>,
+        fields: &[Loc<RpField<CoreFlavor>>],
+    ) -> Vec<(u32, String)> {
+        let mut out = fields
+            .iter()
+            .flat_map(|f| f.id)
+            .map(|id| (id, name.to_string()))
+            .collect::<Vec<_>>();
+
+        if let Some(recorded) = self.declarations.get(&key(name)) {
+            out.extend(recorded.values().map(|id| (*id, name.to_string())));
+        }
+
+        out
+    }
+
+    /// Assign identifiers to the given fields.
+    ///
+    /// `reserved` are identifiers which are used by other declarations whose fields end up in the
+    /// same object, like the fields of an interface and its sub-types, together with the name of
+    /// the declaration using them.
+    fn assign_fields(
+        &mut self,
+        diag: &mut SourceDiagnostics,
+        source: &Source,
+        name: &Loc<RpName<CoreFlavor>>,
+        fields: &mut [Loc<RpField<CoreFlavor>>],
+        reserved: &[(u32, String)],
+    ) -> bool {
+        let recorded = self
+            .declarations
+            .entry(key(name))
+            .or_insert_with(BTreeMap::new);
+
+        let mut changed = false;
+
+        // identifiers declared in the schema take precedence.
+        for field in fields.iter() {
+            let id = match field.id {
+                Some(id) => id,
+                None => continue,
+            };
+
+            if let Some(owner) = reserved_by(reserved, id) {
+                diag.err(
+                    source,
+                    Loc::span(field),
+                    format!("field id {} is already used by `{}`", id, owner),
+                );
+                continue;
+            }
+
+            let previous = recorded
+                .iter()
+                .find(|&(ident, other)| *other == id && ident != field.ident())
+                .map(|(ident, _)| ident.to_string());
+
+            if let Some(previous) = previous {
+                diag.err(
+                    source,
+                    Loc::span(field),
+                    format!(
+                        "field id {} was previously used by field `{}` of `{}`",
+                        id, previous, name
+                    ),
+                );
+                continue;
+            }
+
+            if recorded.insert(field.ident().to_string(), id) != Some(id) {
+                changed = true;
+            }
+        }
+
+        let mut next = recorded
+            .values()
+            .cloned()
+            .chain(reserved.iter().map(|r| r.0))
+            .max()
+            .unwrap_or(0);
+
+        for field in fields.iter_mut() {
+            if field.id.is_some() {
+                continue;
+            }
+
+            if let Some(id) = recorded.get(field.ident()).cloned() {
+                // NB: recorded identifiers might have been assigned before a conflicting field was
+                // added to a related declaration.
+                if let Some(owner) = reserved_by(reserved, id) {
+                    diag.err(
+                        source,
+                        Loc::span(field),
+                        format!(
+                            "field id {} recorded for `{}` is already used by `{}`",
+                            id,
+                            field.ident(),
+                            owner
+                        ),
+                    );
+                    continue;
+                }
+
+                field.id = Some(id);
+                continue;
+            }
+
+            next += 1;
+            recorded.insert(field.ident().to_string(), next);
+            field.id = Some(next);
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+/// Find the declaration which reserved the given identifier, if any.
+fn reserved_by(reserved: &[(u32, String)], id: u32) -> Option<&str> {
+    reserved.iter().find(|r| r.0 == id).map(|r| r.1.as_str())
+}
+
+/// Key of a declaration, which doesn't include the version of its package.
+fn key(name: &RpName<CoreFlavor>) -> String {
+    format!("{}::{}", name.package.package, name.path.join("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FieldIds;
+    use core::{
+        CoreFlavor, Diagnostic, EmptyResolver, Loc, Reported, RpDecl, RpField, RpPackage,
+        RpVersionedPackage, Source, SourceDiagnostics,
+    };
+    use session::Session;
+    use std::collections::BTreeMap;
+
+    /// Assign identifiers to the fields of the given specification.
+    ///
+    /// Returns the identifier of every field by its name, or the reported errors.
+    fn assign(ids: &mut FieldIds, input: &str) -> Result<Vec<(String, u32)>, Vec<String>> {
+        let mut reporter: Vec<Reported> = Vec::new();
+        let mut resolver = EmptyResolver;
+        let mut session = Session::<CoreFlavor>::new(None, &mut reporter, &mut resolver)
+            .expect("failed to build session");
+
+        let package = RpVersionedPackage::new(RpPackage::parse("test"), None);
+        let source = Source::bytes("test", input.as_bytes().to_vec());
+
+        let mut decls = session
+            .load_source(source.clone(), &package)
+            .expect("bad specification")
+            .decls;
+
+        let mut diag = SourceDiagnostics::new();
+        ids.assign(&mut diag, &source, &mut decls);
+
+        if diag.has_errors() {
+            return Err(diag
+                .items()
+                .filter_map(|item| match item.1 {
+                    Diagnostic::Error { ref message, .. } => Some(message.clone()),
+                    _ => None,
+                })
+                .collect());
+        }
+
+        let mut out = Vec::new();
+
+        for decl in &decls {
+            match *decl {
+                RpDecl::Type(ref body) => fields(&mut out, &body.fields),
+                RpDecl::Interface(ref body) => {
+                    fields(&mut out, &body.fields);
+
+                    for sub_type in &body.sub_types {
+                        fields(&mut out, &sub_type.fields);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(out);
+
+        fn fields(out: &mut Vec<(String, u32)>, fields: &[Loc<RpField<CoreFlavor>>]) {
+            for f in fields {
+                out.push((f.ident().to_string(), f.id().expect("field without id")));
+            }
+        }
+    }
+
+    fn expected(ids: &[(&str, u32)]) -> Result<Vec<(String, u32)>, Vec<String>> {
+        Ok(ids.iter().map(|&(f, id)| (f.to_string(), id)).collect())
+    }
+
+    #[test]
+    fn test_explicit_ids() {
+        let mut ids = FieldIds::new();
+
+        assert_eq!(
+            expected(&[("a", 5), ("b", 6)]),
+            assign(&mut ids, "type Foo { #[id(5)] a: string; b: string; }")
+        );
+
+        // explicit identifiers can't take the identifier of another field.
+        assert_eq!(
+            Err(vec![
+                "field id 6 was previously used by field `b` of `Foo`".to_string(),
+            ]),
+            assign(&mut ids, "type Foo { a: string; #[id(6)] c: string; }")
+        );
+    }
+
+    #[test]
+    fn test_sidecar_reuse() {
+        let mut ids = FieldIds::new();
+
+        assert_eq!(
+            expected(&[("a", 1), ("b", 2)]),
+            assign(&mut ids, "type Foo { a: string; b: string; }")
+        );
+
+        // removed fields keep their identifiers, so they aren't reused.
+        assert_eq!(
+            expected(&[("b", 2), ("c", 3)]),
+            assign(&mut ids, "type Foo { b: string; c: string; }")
+        );
+
+        assert_eq!(
+            expected(&[("a", 1), ("b", 2), ("c", 3)]),
+            assign(&mut ids, "type Foo { a: string; b: string; c: string; }")
+        );
+    }
+
+    #[test]
+    fn test_renamed_field() {
+        let mut ids = FieldIds::new();
+
+        assert_eq!(
+            expected(&[("a", 1)]),
+            assign(&mut ids, "type Foo { a: string; }")
+        );
+
+        // a field is identified by its identifier, so a renamed field is a new field.
+        assert_eq!(
+            expected(&[("b", 2)]),
+            assign(&mut ids, "type Foo { b: string; }")
+        );
+
+        // changing only the JSON name keeps the identifier.
+        assert_eq!(
+            expected(&[("a", 1)]),
+            assign(&mut ids, "type Foo { a: string as \"x\"; }")
+        );
+    }
+
+    #[test]
+    fn test_interface_sub_types() {
+        let mut ids = FieldIds::new();
+
+        assert_eq!(
+            expected(&[("a", 1), ("b", 2)]),
+            assign(
+                &mut ids,
+                "interface Shape { a: string; Circle { b: string; } }"
+            )
+        );
+
+        // fields added to the interface can't take the identifiers of sub-type fields.
+        assert_eq!(
+            expected(&[("a", 1), ("c", 3), ("b", 2)]),
+            assign(
+                &mut ids,
+                "interface Shape { a: string; c: string; Circle { b: string; } }"
+            )
+        );
+
+        assert_eq!(
+            expected(&[("a", 1), ("c", 3), ("b", 2), ("d", 4)]),
+            assign(
+                &mut ids,
+                "interface Shape { a: string; c: string; Circle { b: string; d: string; } }"
+            )
+        );
+    }
+
+    #[test]
+    fn test_interface_recorded_conflict() {
+        let mut declarations = BTreeMap::new();
+
+        declarations.insert(
+            "test::Shape".to_string(),
+            vec![("a".to_string(), 1), ("c".to_string(), 2)]
+                .into_iter()
+                .collect(),
+        );
+
+        declarations.insert(
+            "test::Shape::Circle".to_string(),
+            vec![("b".to_string(), 2)].into_iter().collect(),
+        );
+
+        let mut ids = FieldIds { declarations };
+
+        assert_eq!(
+            Err(vec![
+                "field id 2 recorded for `c` is already used by `Shape::Circle`".to_string(),
+                "field id 2 recorded for `b` is already used by `Shape`".to_string(),
+            ]),
+            assign(
+                &mut ids,
+                "interface Shape { a: string; c: string; Circle { b: string; } }"
+            )
+        );
+    }
+}
@@ -43,7 +43,8 @@ macro_rules! check_conflict {
     ($diag:expr, $existing:expr, $item:expr, $accessor:expr, $what:expr) => {
         if let Some(other) = $existing.insert($accessor.to_string(), Span::from(&$item).clone())
         {
-            $diag.err(
+            $diag.err_with_code(
+                &core::codes::DUPLICATE_DEFINITION,
                 Span::from(&$item),
                 format!(concat!($what, " `{}` is already defined"), $accessor),
             );
@@ -62,7 +63,8 @@ macro_rules! check_field_tag {
         match $strategy {
             core::RpSubTypeStrategy::Tagged { ref tag, .. } => {
                 if $field.name() == tag {
-                    $diag.err(
+                    $diag.err_with_code(
+                        &core::codes::FIELD_CONFLICTS_WITH_TAG,
                         Loc::span(&$field),
                         format!(
                             "field with name `{}` is the same as tag used in type_info",
@@ -84,7 +86,8 @@ macro_rules! check_field_tag {
 macro_rules! check_field_reserved {
     ($diag:ident, $field:expr, $reserved:expr) => {
         if let Some(reserved) = $reserved.get($field.name()) {
-            $diag.err(
+            $diag.err_with_code(
+                &core::codes::RESERVED_FIELD,
                 Loc::span(&$field),
                 format!("field with name `{}` is reserved", $field.name()),
             );
@@ -107,6 +110,7 @@ pub struct SubTypeConstraint<'input> {
     reserved: &'input HashMap<String, Span>,
     field_idents: &'input HashMap<String, Span>,
     field_names: &'input HashMap<String, Span>,
+    field_ids: &'input HashMap<String, Span>,
     untagged: &'input mut LinkedHashMap<BTreeSet<String>, Span>,
 }
 
@@ -118,6 +122,7 @@ pub struct Members {
     decl_idents: LinkedHashMap<String, usize>,
     field_names: HashMap<String, Span>,
     field_idents: HashMap<String, Span>,
+    field_ids: HashMap<String, Span>,
 }
 
 /// Adds a method for all types that supports conversion into core types.
@@ -291,6 +296,18 @@ impl<'input> IntoModel for (Option<&'input mut Attributes>, Loc<Type<'input>>) {
                 kind: RpNumberKind::I64,
                 validate: None,
             }),
+            Unsigned { size: 128 } => core::RpType::Number(RpNumberType {
+                kind: RpNumberKind::U128,
+                validate: None,
+            }),
+            Signed { size: 128 } => core::RpType::Number(RpNumberType {
+                kind: RpNumberKind::I128,
+                validate: None,
+            }),
+            Decimal => core::RpType::Number(RpNumberType {
+                kind: RpNumberKind::Decimal,
+                validate: None,
+            }),
             Boolean => core::RpType::Boolean,
             String => {
                 let validate = match attributes {
@@ -301,9 +318,30 @@ impl<'input> IntoModel for (Option<&'input mut Attributes>, Loc<Type<'input>>) {
                 core::RpType::String(RpStringType { validate })
             }
             DateTime => core::RpType::DateTime,
+            Date => core::RpType::Date,
+            Time => core::RpType::Time,
+            Duration => core::RpType::Duration,
             Name { name } => core::RpType::Name {
                 name: name.into_model(diag, scope)?,
             },
+            Scalar { name } => {
+                let (name, name_span) = Loc::take_pair(name);
+
+                match scope.scalar(name.as_ref()) {
+                    Some(scalar) => core::RpType::Scalar(scalar.clone()),
+                    None => {
+                        diag.err(
+                            name_span,
+                            format!(
+                                "unknown type `{}`, custom scalars are declared under \
+                                 `[scalars]` in the manifest",
+                                name
+                            ),
+                        );
+                        return Err(());
+                    }
+                }
+            }
             Array { inner } => core::RpType::Array {
                 inner: inner.into_model(diag, scope)?,
             },
@@ -311,6 +349,31 @@ impl<'input> IntoModel for (Option<&'input mut Attributes>, Loc<Type<'input>>) {
                 key: key.into_model(diag, scope)?,
                 value: value.into_model(diag, scope)?,
             },
+            Generic { name, arguments } => {
+                let (name, name_span) = Loc::take_pair(name);
+
+                if name != "set" {
+                    diag.err(
+                        name_span,
+                        format!("unsupported type `{}`, expected: `set<T>`", name),
+                    );
+                    return Err(());
+                }
+
+                let mut arguments = arguments.into_iter();
+
+                let inner = match (arguments.next(), arguments.next()) {
+                    (Some(inner), None) => inner,
+                    _ => {
+                        diag.err(span, "expected exactly one argument, like: `set<string>`");
+                        return Err(());
+                    }
+                };
+
+                core::RpType::Set {
+                    inner: Box::new(inner.into_model(diag, scope)?),
+                }
+            }
             Any => core::RpType::Any,
             Bytes => core::RpType::Bytes,
             Error { .. } => {
@@ -344,6 +407,8 @@ impl<'input> IntoModel for Decl<'input> {
             Enum(body) => body.into_model(diag, scope).map(core::RpDecl::Enum),
             Tuple(body) => body.into_model(diag, scope).map(core::RpDecl::Tuple),
             Service(body) => body.into_model(diag, scope).map(core::RpDecl::Service),
+            Union(body) => body.into_model(diag, scope).map(core::RpDecl::Union),
+            Newtype(body) => body.into_model(diag, scope).map(core::RpDecl::Newtype),
         };
 
         scope.pop();
@@ -379,6 +444,11 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
                         check_conflict!($diag, idents, v, v.ident, "variant");
                         check_conflict!($diag, values, v, v.value(), "variant value");
 
+                        for alias in &v.aliases {
+                            let value = core::RpVariantValue::from(Loc::borrow(alias));
+                            check_conflict!($diag, values, *alias, value, "variant value");
+                        }
+
                         out.push(v);
                     }
 
@@ -440,8 +510,9 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
             (Number, Number, NumberDefaultVariant)
         );
 
-        let attributes = attributes.into_model(diag, scope)?;
-        check_attributes!(diag, attributes);
+        let mut attributes = attributes.into_model(diag, scope)?;
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         return Ok(Loc::new(
             RpEnumBody {
@@ -450,6 +521,8 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
                 comment: Comment(&comment).into_model(diag, scope)?,
                 decls: vec![],
                 decl_idents: LinkedHashMap::new(),
+                attributes,
+                deprecated,
                 enum_type: enum_type,
                 variants: variants,
                 codes: codes,
@@ -483,16 +556,21 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
             }
 
             fn process(&mut self, value: RpValue) -> result::Result<RpNumber, Error> {
-                let number = value.into_number()?;
+                let number = self.convert(value)?;
 
                 {
                     let value = number
                         .to_bigint()
                         .ok_or_else(|| "value can't be used with generator")?;
 
-                    self.state = value.clone();
+                    self.state = value.clone() + BigInt::from(1);
                 }
 
+                Ok(number)
+            }
+
+            fn convert(&self, value: RpValue) -> result::Result<RpNumber, Error> {
+                let number = value.into_number()?;
                 self.number_type.validate_number(&number)?;
                 Ok(number)
             }
@@ -517,6 +595,10 @@ impl<'input> IntoModel for Item<'input, EnumBody<'input>> {
             }
 
             fn process(&mut self, value: RpValue) -> result::Result<String, Error> {
+                self.convert(value)
+            }
+
+            fn convert(&self, value: RpValue) -> result::Result<String, Error> {
                 value
                     .as_string()
                     .map(|s| s.to_string())
@@ -535,6 +617,9 @@ pub trait DefaultVariant {
 
     /// Process the value, attempting to convert it to the destination type.
     fn process(&mut self, value: RpValue) -> result::Result<Self::Type, Error>;
+
+    /// Convert the value to the destination type, without affecting the next default value.
+    fn convert(&self, value: RpValue) -> result::Result<Self::Type, Error>;
 }
 
 /// enum value with assigned ordinal
@@ -576,21 +661,91 @@ where
             default.next(&item).with_span(diag, span)?
         };
 
-        let attributes = attributes.into_model(diag, scope)?;
-        check_attributes!(diag, attributes);
+        let mut aliases = Vec::new();
+
+        for alias in item.aliases {
+            let (alias, span) = Loc::take_pair(alias.into_model(diag, scope)?);
+
+            match default.convert(alias) {
+                Err(e) => {
+                    diag.err(span, e.display());
+                    return Err(());
+                }
+                Ok(alias) => aliases.push(Loc::new(alias, span)),
+            }
+        }
+
+        let mut attributes = attributes.into_model(diag, scope)?;
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         Ok(Loc::new(
             RpVariant {
                 name,
                 ident: Loc::map(item.name.clone(), |s| s.to_string()),
                 comment: Comment(&comment).into_model(diag, scope)?,
+                attributes,
+                deprecated,
                 value: value,
+                aliases: aliases,
             },
             span,
         ))
     }
 }
 
+/// Collect all field names reserved through `reserved` statements among the given members.
+fn reserved_members<'input>(
+    diag: &mut Diagnostics,
+    members: &[TypeMember<'input>],
+    reserved: &mut HashMap<String, Span>,
+) -> Result<()> {
+    for member in members {
+        let values = match *member {
+            TypeMember::Reserved(ref values) => values,
+            _ => continue,
+        };
+
+        for value in values.iter() {
+            let span = Loc::span(value);
+
+            let field = match *Loc::borrow(value) {
+                Value::String(ref string) => string.to_string(),
+                Value::Identifier(ref ident) => ident.to_string(),
+                _ => {
+                    diag.err(span, "expected identifier or string");
+                    continue;
+                }
+            };
+
+            if let Some(other) = reserved.get(&field) {
+                diag.err(span, format!("field `{}` is already reserved", field));
+                diag.info(*other, "reserved here");
+                continue;
+            }
+
+            reserved.insert(field, span);
+        }
+    }
+
+    if diag.has_errors() {
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/// Convert reserved field names into their model representation, ordered by name.
+fn reserved_model(reserved: &HashMap<String, Span>) -> Vec<Loc<String>> {
+    let mut out = reserved
+        .iter()
+        .map(|(field, span)| Loc::new(field.to_string(), *span))
+        .collect::<Vec<_>>();
+
+    out.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    out
+}
+
 /// Helper function to build a safe identifier.
 fn build_safe_ident<I, N>(scope: &mut Scope<I>, ident: &str, naming: N) -> Option<String>
 where
@@ -678,7 +833,30 @@ impl<'input> IntoModel for Item<'input, Field<'input>> {
 
         let ty = (Some(&mut attributes), ty).into_model(diag, scope)?;
 
-        check_attributes!(diag, attributes);
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        let id = attributes::field_id(diag, &mut attributes)?;
+
+        let is_64_bit = match ty {
+            core::RpType::Number(ref number) => match number.kind {
+                RpNumberKind::U64 | RpNumberKind::I64 => true,
+                _ => false,
+            },
+            _ => false,
+        };
+
+        let number_as_string = match attributes::number_format(diag, &mut attributes)? {
+            Some(ref as_string) if !is_64_bit => {
+                diag.err(
+                    Loc::span(as_string),
+                    "`number_format` can only be used on fields of type `u64` or `i64`",
+                );
+                return Err(());
+            }
+            Some(as_string) => Loc::take(as_string),
+            None => is_64_bit && scope.numbers_as_strings(),
+        };
+
+        attributes::custom(diag, &attributes)?;
 
         return Ok(Loc::new(
             RpField {
@@ -688,6 +866,10 @@ impl<'input> IntoModel for Item<'input, Field<'input>> {
                 comment: Comment(&comment).into_model(diag, scope)?,
                 ty,
                 field_as: field_as,
+                attributes,
+                deprecated,
+                id,
+                number_as_string,
             },
             span,
         ));
@@ -740,10 +922,31 @@ impl<'input> IntoModel for Item<'input, Field<'input>> {
                     );
                     return Err(());
                 }
+                ty @ Type::Date | ty @ Type::Time | ty @ Type::Duration => {
+                    let format = match ty {
+                        Type::Date => "date",
+                        Type::Time => "time",
+                        _ => "duration",
+                    };
+
+                    scope.feature_err(diag, feature, span, "type not supported");
+
+                    diag.info(
+                        span,
+                        format!(
+                            "HINT: use #[format(\"{}\")] attribute on a `string` field instead",
+                            format
+                        ),
+                    );
+                    return Err(());
+                }
                 Type::String => {
                     if let Some(format) = format.map(Loc::take) {
                         match format {
                             attributes::StringFormat::DateTime => Type::DateTime,
+                            attributes::StringFormat::Date => Type::Date,
+                            attributes::StringFormat::Time => Type::Time,
+                            attributes::StringFormat::Duration => Type::Duration,
                             attributes::StringFormat::Bytes => Type::Bytes,
                         }
                     } else {
@@ -812,7 +1015,7 @@ impl<'input> IntoModel for Vec<Loc<UseDecl<'input>>> {
             };
 
             let required = RpRequiredPackage::new(package.clone(), range);
-            let use_package = scope.import(&required).with_span(diag, span)?;
+            let use_package = scope.import(&required, span).with_span(diag, span)?;
 
             if let Some(use_package) = use_package {
                 if let Some(used) = package.parts().last() {
@@ -836,7 +1039,8 @@ impl<'input> IntoModel for Vec<Loc<UseDecl<'input>>> {
                 continue;
             }
 
-            diag.err(
+            diag.err_with_code(
+                &core::codes::MISSING_IMPORT,
                 span,
                 format!("imported package `{}` does not exist", required),
             );
@@ -973,7 +1177,8 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
 
         let mut attributes = attributes.into_model(diag, scope)?;
 
-        let reserved = attributes::reserved(diag, &mut attributes)?;
+        let mut reserved = attributes::reserved(diag, &mut attributes)?;
+        reserved_members(diag, &item.members, &mut reserved)?;
 
         let mut sub_type_strategy = RpSubTypeStrategy::default();
 
@@ -982,7 +1187,8 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
             check_selection!(diag, type_info);
         }
 
-        check_attributes!(diag, attributes);
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         let Members {
             fields,
@@ -991,11 +1197,12 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
             decl_idents,
             field_idents,
             field_names,
+            field_ids,
             ..
         } = {
             let constraint = MemberConstraint {
                 sub_type_strategy: Some(&sub_type_strategy),
-                ..MemberConstraint::default()
+                reserved: Some(&reserved),
             };
 
             (item.members, constraint).into_model(diag, scope)?
@@ -1012,6 +1219,7 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
                 reserved: &reserved,
                 field_idents: &field_idents,
                 field_names: &field_names,
+                field_ids: &field_ids,
                 untagged: &mut untagged,
             };
 
@@ -1091,10 +1299,13 @@ impl<'input> IntoModel for Item<'input, InterfaceBody<'input>> {
                 comment: Comment(&comment).into_model(diag, scope)?,
                 decls,
                 decl_idents,
+                attributes,
+                deprecated,
                 fields,
                 codes,
                 sub_types,
                 sub_type_strategy,
+                reserved: reserved_model(&reserved),
             },
             span,
         ));
@@ -1306,7 +1517,8 @@ impl<'input> IntoModel for Item<'input, ServiceBody<'input>> {
             check_selection!(diag, selection);
         }
 
-        check_attributes!(diag, attributes);
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         return Ok(Loc::new(
             RpServiceBody {
@@ -1315,6 +1527,8 @@ impl<'input> IntoModel for Item<'input, ServiceBody<'input>> {
                 comment: Comment(&comment).into_model(diag, scope)?,
                 decls,
                 decl_idents,
+                attributes,
+                deprecated,
                 http,
                 endpoints,
             },
@@ -1350,6 +1564,7 @@ impl<'input> IntoModel for EndpointArgument<'input> {
         let argument = RpEndpointArgument {
             ident: Rc::new(ident),
             safe_ident: Rc::new(safe_ident),
+            comment: Comment(&self.comment).into_model(diag, scope)?,
             channel: self.channel.into_model(diag, scope)?,
         };
 
@@ -1404,6 +1619,29 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
         let response = item.response.into_model(diag, scope)?;
         let mut request = arguments.iter().cloned().next();
 
+        let mut errors = Vec::new();
+        let mut seen_idents = HashMap::new();
+        let mut seen_statuses = HashMap::new();
+
+        for error in item.errors {
+            let error = error.into_model(diag, scope)?;
+            let span = Loc::span(&error.ident);
+
+            if let Some(other) = seen_idents.insert(error.ident.to_string(), span) {
+                diag.err(span, "error already present");
+                diag.info(other, "error present here");
+                return Err(());
+            }
+
+            if let Some(other) = seen_statuses.insert(error.status, span) {
+                diag.err(span, format!("status {} is already used", error.status));
+                diag.info(other, "used here");
+                return Err(());
+            }
+
+            errors.push(error);
+        }
+
         let mut attributes = attributes.into_model(diag, scope)?;
 
         let http = attributes::endpoint_http(
@@ -1415,7 +1653,8 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
             &arguments,
         )?;
 
-        check_attributes!(diag, attributes);
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         Ok(Loc::new(
             RpEndpoint {
@@ -1424,9 +1663,11 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
                 name: name,
                 comment: Comment(&comment).into_model(diag, scope)?,
                 attributes: attributes,
+                deprecated: deprecated,
                 arguments: arguments,
                 request: request,
                 response: response,
+                errors: errors,
                 http: http,
             },
             span,
@@ -1434,6 +1675,33 @@ impl<'input> IntoModel for Item<'input, Endpoint<'input>> {
     }
 }
 
+impl<'input> IntoModel for Loc<EndpointError<'input>> {
+    type Output = RpEndpointError;
+
+    fn into_model<I>(self, diag: &mut Diagnostics, scope: &mut Scope<I>) -> Result<Self::Output>
+    where
+        I: Import,
+    {
+        let EndpointError { ident, ty, status } = Loc::take(self);
+
+        let (status, span) = Loc::take_pair(status);
+
+        let status = match status.to_u32() {
+            Some(status) if status >= 400 && status < 600 => status as u16,
+            _ => {
+                diag.err(span, "expected a status code between 400 and 599");
+                return Err(());
+            }
+        };
+
+        Ok(RpEndpointError {
+            ident: ident.into_model(diag, scope)?,
+            ty: ty.into_model(diag, scope)?,
+            status,
+        })
+    }
+}
+
 impl<'input> IntoModel for Channel<'input> {
     type Output = RpChannel;
 
@@ -1471,6 +1739,7 @@ impl<'input> IntoModel for (Item<'input, SubType<'input>>, SubTypeConstraint<'in
             reserved: interface_reserved,
             field_idents,
             field_names,
+            field_ids,
             sub_type_strategy,
             untagged,
         } = constraint;
@@ -1486,8 +1755,10 @@ impl<'input> IntoModel for (Item<'input, SubType<'input>>, SubTypeConstraint<'in
         let name = scope.as_name(Loc::span(&item.name));
 
         let mut attributes = attributes.into_model(diag, scope)?;
-        let reserved = attributes::reserved(diag, &mut attributes)?;
-        check_attributes!(diag, attributes);
+        let mut reserved = attributes::reserved(diag, &mut attributes)?;
+        reserved_members(diag, &item.members, &mut reserved)?;
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         let mut fields = Vec::new();
         let mut codes = Vec::new();
@@ -1498,6 +1769,7 @@ impl<'input> IntoModel for (Item<'input, SubType<'input>>, SubTypeConstraint<'in
         let mut idents = HashMap::new();
         let mut field_idents = field_idents.clone();
         let mut field_names = field_names.clone();
+        let mut field_ids = field_ids.clone();
 
         for member in item.members {
             match member {
@@ -1507,6 +1779,10 @@ impl<'input> IntoModel for (Item<'input, SubType<'input>>, SubTypeConstraint<'in
                     check_conflict!(diag, field_idents, field, field.ident(), "field");
                     check_conflict!(diag, field_names, field, field.name(), "field with name");
 
+                    if let Some(id) = field.id() {
+                        check_conflict!(diag, field_ids, field, id, "field with id");
+                    }
+
                     check_field_tag!(diag, field, *sub_type_strategy);
 
                     check_field_reserved!(diag, field, interface_reserved);
@@ -1523,6 +1799,8 @@ impl<'input> IntoModel for (Item<'input, SubType<'input>>, SubTypeConstraint<'in
                     decl_idents.insert(d.ident().to_string(), decls.len());
                     decls.push(d);
                 }
+                // NB: collected by `reserved_members` above.
+                Reserved(_) => {}
             }
         }
 
@@ -1556,9 +1834,12 @@ impl<'input> IntoModel for (Item<'input, SubType<'input>>, SubTypeConstraint<'in
                 comment: Comment(&comment).into_model(diag, scope)?,
                 decls,
                 decl_idents,
+                attributes,
+                deprecated,
                 fields,
                 codes,
                 sub_type_name,
+                reserved: reserved_model(&reserved),
             },
             span,
         ));
@@ -1627,8 +1908,9 @@ impl<'input> IntoModel for Item<'input, TupleBody<'input>> {
             ..
         } = item.members.into_model(diag, scope)?;
 
-        let attributes = attributes.into_model(diag, scope)?;
-        check_attributes!(diag, attributes);
+        let mut attributes = attributes.into_model(diag, scope)?;
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         Ok(Loc::new(
             RpTupleBody {
@@ -1637,6 +1919,8 @@ impl<'input> IntoModel for Item<'input, TupleBody<'input>> {
                 comment: Comment(&comment).into_model(diag, scope)?,
                 decls,
                 decl_idents,
+                attributes,
+                deprecated,
                 fields,
                 codes,
             },
@@ -1645,6 +1929,136 @@ impl<'input> IntoModel for Item<'input, TupleBody<'input>> {
     }
 }
 
+impl<'input> IntoModel for Item<'input, UnionBody<'input>> {
+    type Output = Loc<RpUnionBody>;
+
+    fn into_model<I>(self, diag: &mut Diagnostics, scope: &mut Scope<I>) -> Result<Self::Output>
+    where
+        I: Import,
+    {
+        let Item {
+            comment,
+            attributes,
+            item,
+        } = self;
+
+        let (item, span) = Loc::take_pair(item);
+
+        let name = scope.as_name(Loc::span(&item.name));
+
+        diag.symbol(SymbolKind::Union, &span, &name);
+
+        let to_upper_camel = naming::to_upper_camel();
+
+        let mut variants = Vec::new();
+        let mut idents: HashMap<String, Span> = HashMap::new();
+
+        for ty in item.variants {
+            let ty_span = Loc::span(&ty);
+            let ty = Loc::new(ty.into_model(diag, scope)?, ty_span);
+
+            // NB: variants are identified by their type, since there is nothing else to go by.
+            let ident = match *ty {
+                core::RpType::Name { ref name } => match name.path.last() {
+                    Some(last) => last.to_string(),
+                    None => {
+                        diag.err(ty_span, "bad name for union variant");
+                        return Err(());
+                    }
+                },
+                core::RpType::Array { .. } => "Array".to_string(),
+                core::RpType::Set { .. } => "Set".to_string(),
+                core::RpType::Map { .. } => "Map".to_string(),
+                ref other => to_upper_camel.convert(other.to_string().as_str()),
+            };
+
+            if let Some(other) = idents.insert(ident.clone(), ty_span) {
+                diag.err_with_code(
+                    &core::codes::CONFLICTING_UNION_VARIANT,
+                    ty_span,
+                    format!(
+                        "conflicting variant `{}` in union, types must be distinct",
+                        ident
+                    ),
+                );
+                diag.info(other, "previously defined here");
+                return Err(());
+            }
+
+            variants.push(RpUnionVariant { ident, ty });
+        }
+
+        let mut attributes = attributes.into_model(diag, scope)?;
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
+
+        Ok(Loc::new(
+            RpUnionBody {
+                name,
+                ident: item.name.to_string(),
+                comment: Comment(&comment).into_model(diag, scope)?,
+                decls: Vec::new(),
+                decl_idents: LinkedHashMap::new(),
+                attributes,
+                deprecated,
+                variants,
+            },
+            span,
+        ))
+    }
+}
+
+impl<'input> IntoModel for Item<'input, NewtypeBody<'input>> {
+    type Output = Loc<RpNewtypeBody>;
+
+    fn into_model<I>(self, diag: &mut Diagnostics, scope: &mut Scope<I>) -> Result<Self::Output>
+    where
+        I: Import,
+    {
+        let Item {
+            comment,
+            attributes,
+            item,
+        } = self;
+
+        let (item, span) = Loc::take_pair(item);
+
+        let name = scope.as_name(Loc::span(&item.name));
+
+        diag.symbol(SymbolKind::Newtype, &span, &name);
+
+        let mut attributes = attributes.into_model(diag, scope)?;
+
+        let ty_span = Loc::span(&item.ty);
+        let ty = (Some(&mut attributes), item.ty).into_model(diag, scope)?;
+
+        // NB: validation is applied by the wrapper, so keep it around after the wrapped type has
+        // been translated.
+        let validate = match ty {
+            core::RpType::String(ref string) => string.validate.clone(),
+            _ => RpStringValidate::default(),
+        };
+
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
+
+        Ok(Loc::new(
+            RpNewtypeBody {
+                name,
+                ident: item.name.to_string(),
+                comment: Comment(&comment).into_model(diag, scope)?,
+                decls: Vec::new(),
+                decl_idents: LinkedHashMap::new(),
+                attributes,
+                deprecated,
+                ty: Loc::new(ty, ty_span),
+                validate,
+            },
+            span,
+        ))
+    }
+}
+
 impl<'input> IntoModel for Item<'input, TypeBody<'input>> {
     type Output = Loc<RpTypeBody>;
 
@@ -1665,9 +2079,11 @@ impl<'input> IntoModel for Item<'input, TypeBody<'input>> {
         diag.symbol(SymbolKind::Type, &span, &name);
 
         let mut attributes = attributes.into_model(diag, scope)?;
-        let reserved = attributes::reserved(diag, &mut attributes)?;
+        let mut reserved = attributes::reserved(diag, &mut attributes)?;
+        reserved_members(diag, &item.members, &mut reserved)?;
 
-        check_attributes!(diag, attributes);
+        let deprecated = attributes::deprecated(diag, &mut attributes)?;
+        attributes::custom(diag, &attributes)?;
 
         let Members {
             fields,
@@ -1691,8 +2107,11 @@ impl<'input> IntoModel for Item<'input, TypeBody<'input>> {
                 comment: Comment(&comment).into_model(diag, scope)?,
                 decls,
                 decl_idents,
+                attributes,
+                deprecated,
                 fields,
                 codes,
+                reserved: reserved_model(&reserved),
             },
             span,
         ))
@@ -1734,6 +2153,7 @@ impl<'input> IntoModel for (Vec<TypeMember<'input>>, MemberConstraint<'input>) {
 
         let mut field_idents = HashMap::new();
         let mut field_names = HashMap::new();
+        let mut field_ids = HashMap::new();
         let mut idents = HashMap::new();
 
         for member in members {
@@ -1744,6 +2164,10 @@ impl<'input> IntoModel for (Vec<TypeMember<'input>>, MemberConstraint<'input>) {
                     check_conflict!(diag, field_idents, field, field.ident(), "field");
                     check_conflict!(diag, field_names, field, field.name(), "field with name");
 
+                    if let Some(id) = field.id() {
+                        check_conflict!(diag, field_ids, field, id, "field with id");
+                    }
+
                     if let Some(sub_type_strategy) = sub_type_strategy {
                         check_field_tag!(diag, field, *sub_type_strategy);
                     }
@@ -1761,6 +2185,15 @@ impl<'input> IntoModel for (Vec<TypeMember<'input>>, MemberConstraint<'input>) {
                     decl_idents.insert(d.ident().to_string(), decls.len());
                     decls.push(d);
                 }
+                // NB: collected by `reserved_members` when supported.
+                Reserved(values) => {
+                    if reserved.is_none() {
+                        diag.err(
+                            Loc::span(&values),
+                            "reserved fields are only supported in types, interfaces, and sub-types",
+                        );
+                    }
+                }
             }
         }
 
@@ -1775,6 +2208,7 @@ impl<'input> IntoModel for (Vec<TypeMember<'input>>, MemberConstraint<'input>) {
             decl_idents,
             field_names,
             field_idents,
+            field_ids,
         })
     }
 }
@@ -1964,7 +2398,8 @@ impl<'input, 'a: 'input> IntoModel for (Span, &'input mut Variables<'a>, PathPar
                 let var = match vars.remove(var.as_str()) {
                     Some(rp) => rp.clone(),
                     None => {
-                        diag.err(
+                        diag.err_with_code(
+                            &core::codes::UNKNOWN_PATH_VARIABLE,
                             span,
                             format!("path variable `{}` is not an argument to endpoint", var),
                         );
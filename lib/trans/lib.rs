@@ -7,12 +7,13 @@ extern crate reproto_naming as naming;
 extern crate reproto_parser as parser;
 extern crate reproto_path_parser as path_parser;
 extern crate reproto_regex_parser as regex_parser;
+extern crate serde_json as json;
 
 /// Helper macro to check that an attribute has been completely consumed.
 macro_rules! check_attributes {
     ($diag:expr, $attr:expr) => {{
         for unused in $attr.unused() {
-            $diag.err(unused, "unknown attribute");
+            $diag.err_with_code(&core::codes::UNKNOWN_ATTRIBUTE, unused, "unknown attribute");
         }
 
         if $diag.has_errors() {
@@ -25,7 +26,7 @@ macro_rules! check_attributes {
 macro_rules! check_selection {
     ($diag:expr, $sel:expr) => {{
         for unused in $sel.unused() {
-            $diag.err(unused, "unknown attribute");
+            $diag.err_with_code(&core::codes::UNKNOWN_ATTRIBUTE, unused, "unknown attribute");
         }
 
         if $diag.has_errors() {
@@ -36,10 +37,14 @@ macro_rules! check_selection {
 
 mod attributes;
 pub mod session;
+pub mod decode;
 mod features;
+pub mod field_ids;
 mod into_model;
+pub mod scalars;
 mod scope;
 pub mod translated;
 
+pub use self::field_ids::FieldIds;
 pub use self::session::{Session, Packages};
 pub use self::translated::Translated;
@@ -0,0 +1,74 @@
+//! Custom scalar types, which are declared in the manifest.
+
+use core::errors::*;
+use core::{
+    RpNumberKind, RpNumberType, RpScalarBase, RpScalarType, RpStringType, RpStringValidate,
+};
+use regex_parser;
+use std::collections::BTreeMap;
+
+/// Build a custom scalar.
+///
+/// `ty` is the name of the built-in type which the scalar is encoded as, like `string` or `u64`.
+/// A `pattern` can only be used with scalars encoded as strings.
+pub fn scalar(
+    name: &str,
+    ty: &str,
+    pattern: Option<&str>,
+    mappings: BTreeMap<String, String>,
+) -> Result<RpScalarType> {
+    let number = |kind| {
+        RpScalarBase::Number(RpNumberType {
+            kind,
+            validate: None,
+        })
+    };
+
+    let mut base = match ty {
+        "double" => RpScalarBase::Double,
+        "float" => RpScalarBase::Float,
+        "u32" => number(RpNumberKind::U32),
+        "u64" => number(RpNumberKind::U64),
+        "u128" => number(RpNumberKind::U128),
+        "i32" => number(RpNumberKind::I32),
+        "i64" => number(RpNumberKind::I64),
+        "i128" => number(RpNumberKind::I128),
+        "decimal" => number(RpNumberKind::Decimal),
+        "boolean" => RpScalarBase::Boolean,
+        "string" => RpScalarBase::String(RpStringType {
+            validate: RpStringValidate::default(),
+        }),
+        ty => {
+            return Err(format!(
+                "scalar `{}`: unsupported type `{}`, expected one of: `string`, `boolean`, \
+                 `float`, `double`, or a number like `u64`",
+                name, ty
+            )
+            .into())
+        }
+    };
+
+    if let Some(pattern) = pattern {
+        let string = match base {
+            RpScalarBase::String(ref mut string) => string,
+            _ => {
+                return Err(format!(
+                    "scalar `{}`: `pattern` can only be used with `string`",
+                    name
+                )
+                .into())
+            }
+        };
+
+        let regex = regex_parser::parse(pattern)
+            .map_err(|e| format!("scalar `{}`: bad pattern: {}", name, e.display()))?;
+
+        string.validate.pattern = Some(regex);
+    }
+
+    Ok(RpScalarType {
+        name: name.to_string(),
+        base,
+        mappings,
+    })
+}
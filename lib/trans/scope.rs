@@ -2,8 +2,8 @@
 
 use core::errors::Error;
 use core::{
-    CoreFlavor, Diagnostics, Import, Loc, RpName, RpRequiredPackage, RpVersionedPackage, Span,
-    Version,
+    CoreFlavor, Diagnostics, Import, Loc, RpName, RpRequiredPackage, RpScalarType,
+    RpVersionedPackage, Span, Version,
 };
 use features::{Feature, Features};
 use naming::Naming;
@@ -24,6 +24,10 @@ pub struct Scope<I> {
     package: RpVersionedPackage,
     /// Language keywords to avoid.
     keywords: Rc<HashMap<String, String>>,
+    /// Custom scalars declared in the manifest.
+    scalars: Rc<HashMap<String, RpScalarType>>,
+    /// Encode 64-bit integers as JSON strings unless a field says otherwise.
+    numbers_as_strings: bool,
     field_ident_naming: Option<Box<Naming>>,
     endpoint_ident_naming: Option<Box<Naming>>,
     import: I,
@@ -40,8 +44,11 @@ impl<I> Scope<I> {
         features: Rc<Features>,
         package: RpVersionedPackage,
         keywords: Rc<HashMap<String, String>>,
+        scalars: Rc<HashMap<String, RpScalarType>>,
+        numbers_as_strings: bool,
         field_ident_naming: Option<Box<Naming>>,
         endpoint_ident_naming: Option<Box<Naming>>,
+        field_naming: Option<Box<Naming>>,
         import: I,
     ) -> Scope<I> {
         Self {
@@ -51,11 +58,13 @@ impl<I> Scope<I> {
             activated_features: HashMap::new(),
             package,
             keywords,
+            scalars,
+            numbers_as_strings,
             field_ident_naming,
             endpoint_ident_naming,
             import,
             endpoint_naming: None,
-            field_naming: None,
+            field_naming,
             prefixes: HashMap::new(),
             path: vec![],
         }
@@ -125,6 +134,16 @@ impl<I> Scope<I> {
         self.keywords.get(identifier).map(|s| s.as_str())
     }
 
+    /// Lookup a custom scalar by name.
+    pub fn scalar(&self, name: &str) -> Option<&RpScalarType> {
+        self.scalars.get(name)
+    }
+
+    /// Check if 64-bit integers are encoded as JSON strings by default.
+    pub fn numbers_as_strings(&self) -> bool {
+        self.numbers_as_strings
+    }
+
     /// Check if a given feature is active and return its specification if it is.
     pub fn feature(&self, name: &'static str) -> Option<&Feature> {
         let feature = match self.features.get(name) {
@@ -197,8 +216,9 @@ where
     pub fn import(
         &mut self,
         package: &RpRequiredPackage,
+        span: Span,
     ) -> Result<Option<RpVersionedPackage>, Error> {
-        self.import.import(package)
+        self.import.import(package, span)
     }
 }
 
@@ -214,10 +234,22 @@ mod tests {
     pub fn test_scope() {
         let package = RpVersionedPackage::new(RpPackage::empty(), None);
         let keywords = Rc::new(HashMap::new());
+        let scalars = Rc::new(HashMap::new());
 
         let version = Rc::new(Version::new(0, 0, 0));
         let features = Rc::new(Features::new().expect("failed to build features"));
-        let mut s = Scope::new(version, features, package, keywords, None, None, ());
+        let mut s = Scope::new(
+            version,
+            features,
+            package,
+            keywords,
+            scalars,
+            false,
+            None,
+            None,
+            None,
+            (),
+        );
 
         s.push("foo");
         s.push("bar");
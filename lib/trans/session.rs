@@ -2,10 +2,13 @@ use ast;
 use core::errors::{Error, Result};
 use core::{
     translator, CoreFlavor, Diagnostics, Flavor, FlavorTranslator, Import, Loc, PackageTranslator,
-    Reporter, Resolved, Resolver, RpFile, RpName, RpPackage, RpReg, RpRequiredPackage,
-    RpVersionedPackage, Source, Translate, Translator, Version,
+    Reporter, Resolved, Resolver, RpDecl, RpDeprecated, RpFile, RpName, RpPackage, RpReg,
+    RpRequiredPackage, RpScalarType, RpType, RpVersionedPackage, Source, SourceDiagnostics, Span,
+    Translate, Translator, Version,
 };
+use decode::Decoder;
 use features::Features;
+use field_ids::FieldIds;
 use into_model::IntoModel;
 use linked_hash_map::LinkedHashMap;
 use naming::Naming;
@@ -13,6 +16,7 @@ use parser;
 use scope::Scope;
 use std::cell::RefCell;
 use std::collections::{btree_map, BTreeMap, HashMap, HashSet};
+use std::iter;
 use std::path::Path;
 use std::rc::Rc;
 use std::result;
@@ -47,6 +51,14 @@ where
     source: Source,
 }
 
+/// A package which is currently being loaded.
+struct Importing {
+    package: RpVersionedPackage,
+    source: Source,
+    /// Location of the last `use` declaration in this package which caused an import.
+    span: Option<Span>,
+}
+
 /// Scoped session for evaluating reproto IDLs.
 pub struct Session<'a, F: 'static>
 where
@@ -66,12 +78,22 @@ where
     lookup_required: HashMap<RpRequiredPackage, Option<RpVersionedPackage>>,
     /// Loaded versioned packages.
     lookup_versioned: HashSet<RpVersionedPackage>,
+    /// Stack of packages currently being loaded, used to detect import cycles.
+    importing: Vec<Importing>,
+    /// Where the packages in a detected import cycle were imported, reported after the error.
+    import_cycle: Option<SourceDiagnostics>,
     /// Files and associated declarations.
     files: BTreeMap<RpVersionedPackage, File<F>>,
     /// Registered types.
     types: Rc<LinkedHashMap<RpName<F>, Loc<RpReg>>>,
     /// Keywords that need to be translated.
     keywords: Rc<HashMap<String, String>>,
+    /// Custom scalars declared in the manifest.
+    scalars: Rc<HashMap<String, RpScalarType>>,
+    /// Encode 64-bit integers as JSON strings by default.
+    numbers_as_strings: bool,
+    /// Whether the language being built can encode 64-bit integers as strings.
+    numbers_as_strings_supported: bool,
     /// Whether to use safe packages or not.
     safe_packages: bool,
     /// Package naming to apply.
@@ -80,6 +102,8 @@ where
     field_ident_naming: Option<Box<Naming>>,
     /// Endpoint ident naming to apply.
     endpoint_ident_naming: Option<Box<Naming>>,
+    /// Field naming to apply, unless a file declares its own with `#![field_naming(..)]`.
+    field_naming: Option<Box<Naming>>,
     /// Hook to provide to paths that were loaded.
     path_hook: Option<Box<Fn(&Path) -> Result<()>>>,
 }
@@ -105,13 +129,19 @@ where
             resolver,
             lookup_required: HashMap::new(),
             lookup_versioned: HashSet::new(),
+            importing: Vec::new(),
+            import_cycle: None,
             files: BTreeMap::new(),
             types: Rc::new(LinkedHashMap::new()),
             keywords: Rc::new(HashMap::new()),
+            scalars: Rc::new(HashMap::new()),
+            numbers_as_strings: false,
+            numbers_as_strings_supported: true,
             safe_packages: false,
             package_naming: None,
             field_ident_naming: None,
             endpoint_ident_naming: None,
+            field_naming: None,
             path_hook: None,
         })
     }
@@ -143,6 +173,33 @@ where
         }
     }
 
+    /// Build the session with the given custom scalars.
+    pub fn with_scalars(self, scalars: Vec<RpScalarType>) -> Self {
+        let scalars = scalars.into_iter().map(|s| (s.name.clone(), s)).collect();
+
+        Self {
+            scalars: Rc::new(scalars),
+            ..self
+        }
+    }
+
+    /// Build the session to encode 64-bit integers as JSON strings by default.
+    pub fn with_numbers_as_strings(self, numbers_as_strings: bool) -> Self {
+        Self {
+            numbers_as_strings,
+            ..self
+        }
+    }
+
+    /// Build the session for a language which might not be able to encode 64-bit integers as
+    /// strings.
+    pub fn with_numbers_as_strings_supported(self, numbers_as_strings_supported: bool) -> Self {
+        Self {
+            numbers_as_strings_supported,
+            ..self
+        }
+    }
+
     /// Set package naming policy.
     pub fn with_package_naming(self, package_naming: Box<Naming>) -> Self {
         Self {
@@ -167,6 +224,14 @@ where
         }
     }
 
+    /// Set field naming, which is used for the names of fields on the wire.
+    pub fn with_field_naming(self, field_naming: Box<Naming>) -> Self {
+        Self {
+            field_naming: Some(field_naming),
+            ..self
+        }
+    }
+
     /// Identify if a character is unsafe for use in a package name.
     fn package_version_unsafe(c: char) -> bool {
         match c {
@@ -249,17 +314,18 @@ impl<'a> Session<'a, CoreFlavor> {
         T: FlavorTranslator<Source = CoreFlavor>,
     {
         // Report all collected errors.
-        if self.reporter.has_diagnostics() {
+        if self.reporter.has_errors() {
             return Err(Error::new("error in context"));
         }
 
         let mut files = BTreeMap::new();
+        let mut sources = BTreeMap::new();
 
         for (package, file) in self.files {
             let package = ctx.translate_package(package)?;
             let mut diag = Diagnostics::new(file.source.clone());
 
-            let file = match file.file.translate(&mut diag, &ctx) {
+            let translated = match file.file.translate(&mut diag, &ctx) {
                 Ok(file) => file,
                 Err(e) => {
                     self.reporter.diagnostics(diag);
@@ -267,7 +333,8 @@ impl<'a> Session<'a, CoreFlavor> {
                 }
             };
 
-            files.insert(package, file);
+            files.insert(package.clone(), translated);
+            sources.insert(package, file.source);
         }
 
         let mut decls = LinkedHashMap::new();
@@ -284,7 +351,7 @@ impl<'a> Session<'a, CoreFlavor> {
             }
         }
 
-        Ok(Translated::new(decls, files))
+        Ok(Translated::new(decls, files, sources))
     }
 
     /// Translation to simplified packages.
@@ -299,7 +366,8 @@ impl<'a> Session<'a, CoreFlavor> {
         let mut files = HashMap::new();
 
         while !queue.is_empty() {
-            let mut candidates = HashMap::new();
+            // NB: ordered, so that the suffixes used to disambiguate packages are stable.
+            let mut candidates = BTreeMap::new();
 
             for (count, (package, level)) in queue.drain(..).enumerate() {
                 let random = count.to_string();
@@ -367,13 +435,9 @@ impl<'a> Session<'a, CoreFlavor> {
             return Err(format!("package `{}` already loaded", package).into());
         }
 
-        let mut diag = Diagnostics::new(source.clone());
-
-        try_with_diag!(self.reporter, diag, {
-            let step = self.load_source_diag(&mut diag, &package);
-            let step = step.and_then(|file| self.process_file(&mut diag, package.clone(), file));
-            step
-        });
+        self.load_package(source, &package, |s, diag, package| {
+            s.load_source_diag(diag, package)
+        })?;
 
         Ok(package)
     }
@@ -390,22 +454,273 @@ impl<'a> Session<'a, CoreFlavor> {
             return Err(format!("package `{}` already loaded", package).into());
         }
 
-        let mut diag = Diagnostics::new(Source::empty("generated"));
+        self.load_package(Source::empty("generated"), &package, |s, diag, package| {
+            s.load_file(diag, file, package)
+        })?;
+
+        Ok(package)
+    }
+
+    /// Load and process the package from the given source, populating the session.
+    ///
+    /// The package is tracked as being loaded while this happens, so that import cycles can be
+    /// detected.
+    fn load_package<L>(
+        &mut self,
+        source: Source,
+        package: &RpVersionedPackage,
+        load: L,
+    ) -> Result<()>
+    where
+        L: FnOnce(
+            &mut Self,
+            &mut Diagnostics,
+            &RpVersionedPackage,
+        ) -> result::Result<RpFile<CoreFlavor>, ()>,
+    {
+        let mut diag = Diagnostics::new(source.clone());
 
-        try_with_diag!(self.reporter, diag, {
-            let step = self.load_file(&mut diag, file, &package);
-            let step = step.and_then(|file| self.process_file(&mut diag, package.clone(), file));
-            step
+        self.importing.push(Importing {
+            package: package.clone(),
+            source,
+            span: None,
         });
 
-        Ok(package)
+        let step = load(self, &mut diag, package);
+        let step = step.and_then(|file| self.process_file(&mut diag, package.clone(), file));
+
+        self.importing.pop();
+
+        if step.is_ok() && !diag.has_errors() {
+            return Ok(());
+        }
+
+        self.reporter.diagnostics(diag);
+
+        if let Some(import_cycle) = self.import_cycle.take() {
+            self.reporter.source_diagnostics(import_cycle);
+        }
+
+        Err("error in environment".into())
+    }
+
+    /// Check that importing the given package doesn't cause an import cycle.
+    fn check_import_cycle(&mut self, package: &RpVersionedPackage) -> Result<()> {
+        let start = match self.importing.iter().position(|i| i.package == *package) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+
+        let mut diag = SourceDiagnostics::new();
+
+        let path = {
+            let cycle = &self.importing[start..];
+
+            // NB: the last import in the cycle is reported as an error by the caller.
+            for (i, next) in cycle.iter().zip(cycle.iter().skip(1)) {
+                if let Some(span) = i.span {
+                    diag.info(&i.source, span, format!("`{}` imported here", next.package));
+                }
+            }
+
+            cycle
+                .iter()
+                .map(|i| &i.package)
+                .chain(iter::once(package))
+                .map(|p| format!("`{}`", p))
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        };
+
+        self.import_cycle = Some(diag);
+        Err(format!("import cycle detected: {}", path).into())
     }
 
     /// Verify all declarations.
+    ///
+    /// This warns about any references to deprecated declarations, and about fields encoding
+    /// numbers as strings in languages which can't.
     pub fn verify(&mut self) -> Result<()> {
+        let mut diag = SourceDiagnostics::new();
+
+        self.verify_deprecated(&mut diag);
+
+        if !self.numbers_as_strings_supported {
+            self.verify_numbers_as_strings(&mut diag);
+        }
+
+        if !diag.items.is_empty() {
+            self.reporter.source_diagnostics(diag);
+        }
+
         Ok(())
     }
 
+    /// Warn about any references to deprecated declarations.
+    fn verify_deprecated(&self, diag: &mut SourceDiagnostics) {
+        let mut deprecated = HashMap::new();
+
+        for file in self.files.values() {
+            for decl in file.file.for_each_decl() {
+                if let Some(d) = decl.deprecated() {
+                    let name = Loc::borrow(decl.name()).clone().without_prefix();
+                    deprecated.insert(name, d.clone());
+                }
+            }
+        }
+
+        if deprecated.is_empty() {
+            return;
+        }
+
+        for file in self.files.values() {
+            for decl in file.file.for_each_decl() {
+                // NB: deprecated declarations are permitted to reference other deprecated
+                // declarations.
+                if decl.deprecated().is_some() {
+                    continue;
+                }
+
+                let mut types = Vec::new();
+
+                match *decl {
+                    RpDecl::Type(ref body) => {
+                        types.extend(body.fields.iter().map(|f| &f.ty));
+                    }
+                    RpDecl::Tuple(ref body) => {
+                        types.extend(body.fields.iter().map(|f| &f.ty));
+                    }
+                    RpDecl::Interface(ref body) => {
+                        types.extend(body.fields.iter().map(|f| &f.ty));
+
+                        for sub_type in &body.sub_types {
+                            types.extend(sub_type.fields.iter().map(|f| &f.ty));
+                        }
+                    }
+                    RpDecl::Service(ref body) => for e in &body.endpoints {
+                        types.extend(e.arguments.iter().map(|a| a.channel.ty()));
+                        types.extend(e.response.iter().map(|r| r.ty()));
+                    },
+                    RpDecl::Union(ref body) => {
+                        types.extend(body.variants.iter().map(|v| Loc::borrow(&v.ty)));
+                    }
+                    RpDecl::Newtype(ref body) => {
+                        types.push(Loc::borrow(&body.ty));
+                    }
+                    RpDecl::Enum(_) => {}
+                }
+
+                for ty in types {
+                    referenced(diag, &file.source, &deprecated, ty);
+                }
+            }
+        }
+
+        return;
+
+        /// Warn if the given type references a deprecated declaration.
+        fn referenced(
+            diag: &mut SourceDiagnostics,
+            source: &Source,
+            deprecated: &HashMap<RpName<CoreFlavor>, RpDeprecated>,
+            ty: &RpType<CoreFlavor>,
+        ) {
+            match *ty {
+                RpType::Name { ref name } => {
+                    let (name, span) = Loc::borrow_pair(name);
+
+                    if let Some(d) = deprecated.get(&name.clone().without_prefix()) {
+                        let message = match d.note() {
+                            Some(note) => format!("`{}` is deprecated: {}", name, note),
+                            None => format!("`{}` is deprecated", name),
+                        };
+
+                        diag.warn(source, span, message);
+                    }
+                }
+                RpType::Array { ref inner } | RpType::Set { ref inner } => {
+                    referenced(diag, source, deprecated, inner);
+                }
+                RpType::Map { ref key, ref value } => {
+                    referenced(diag, source, deprecated, key);
+                    referenced(diag, source, deprecated, value);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Warn about fields encoding numbers as strings, since the language encodes them as numbers.
+    fn verify_numbers_as_strings(&self, diag: &mut SourceDiagnostics) {
+        for file in self.files.values() {
+            for decl in file.file.for_each_decl() {
+                let mut fields = Vec::new();
+
+                match *decl {
+                    RpDecl::Type(ref body) => {
+                        fields.extend(body.fields.iter());
+                    }
+                    RpDecl::Tuple(ref body) => {
+                        fields.extend(body.fields.iter());
+                    }
+                    RpDecl::Interface(ref body) => {
+                        fields.extend(body.fields.iter());
+
+                        for sub_type in &body.sub_types {
+                            fields.extend(sub_type.fields.iter());
+                        }
+                    }
+                    _ => {}
+                }
+
+                for field in fields {
+                    if !field.is_number_as_string() {
+                        continue;
+                    }
+
+                    diag.warn(
+                        &file.source,
+                        Loc::span(field),
+                        "`number_format(\"string\")` is not supported by this language, \
+                         the field is encoded as a number",
+                    );
+                }
+            }
+        }
+    }
+
+    /// Build a decoder for JSON documents, using the declarations in the session.
+    pub fn decoder(&self) -> Decoder {
+        Decoder::new(
+            self.files
+                .iter()
+                .map(|(package, file)| (package, &file.file))
+                .collect(),
+        )
+    }
+
+    /// Assign stable identifiers to all fields in the session.
+    ///
+    /// Identifiers which have been used before are taken from `ids`, and any newly assigned
+    /// identifiers are recorded in it.
+    ///
+    /// Returns `true` if `ids` was modified.
+    pub fn assign_field_ids(&mut self, ids: &mut FieldIds) -> Result<bool> {
+        let mut diag = SourceDiagnostics::new();
+        let mut changed = false;
+
+        for file in self.files.values_mut() {
+            changed |= ids.assign(&mut diag, &file.source, &mut file.file.decls);
+        }
+
+        if diag.has_errors() {
+            self.reporter.source_diagnostics(diag);
+            return Err("failed to assign field ids".into());
+        }
+
+        Ok(changed)
+    }
+
     /// Load the provided Source into an `RpFile` without registering it to the set of visited
     /// files.
     pub fn load_source(
@@ -485,8 +800,11 @@ impl<'a> Session<'a, CoreFlavor> {
             Rc::clone(&self.features),
             package.clone(),
             self.keywords.clone(),
+            self.scalars.clone(),
+            self.numbers_as_strings,
             self.field_ident_naming.as_ref().map(|n| n.copy()),
             self.endpoint_ident_naming.as_ref().map(|n| n.copy()),
+            self.field_naming.as_ref().map(|n| n.copy()),
             self,
         );
 
@@ -549,12 +867,25 @@ impl<'a> Session<'a, CoreFlavor> {
 
 impl<'e> Import for Session<'e, CoreFlavor> {
     /// Import a package based on a package and version criteria.
-    fn import(&mut self, required: &RpRequiredPackage) -> Result<Option<RpVersionedPackage>> {
+    fn import(
+        &mut self,
+        required: &RpRequiredPackage,
+        span: Span,
+    ) -> Result<Option<RpVersionedPackage>> {
         debug!("import: {}", required);
 
-        if let Some(existing) = self.lookup_required.get(required) {
+        if let Some(importing) = self.importing.last_mut() {
+            importing.span = Some(span);
+        }
+
+        if let Some(existing) = self.lookup_required.get(required).cloned() {
             debug!("already loaded: {:?} ({})", existing, required);
-            return Ok(existing.clone());
+
+            if let Some(ref package) = existing {
+                self.check_import_cycle(package)?;
+            }
+
+            return Ok(existing);
         }
 
         // find matching object from the resolver.
@@ -573,17 +904,15 @@ impl<'e> Import for Session<'e, CoreFlavor> {
             required, source, package
         );
 
-        let mut diag = Diagnostics::new(source.clone());
+        self.check_import_cycle(&package)?;
 
         // NOTE: import to insert before recursing (happens in process_file).
         self.lookup_required
             .insert(required.clone(), Some(package.clone()));
 
-        try_with_diag!(self.reporter, diag, {
-            let step = self.load_source_diag(&mut diag, &package);
-            let step = step.and_then(|file| self.process_file(&mut diag, package.clone(), file));
-            step
-        });
+        self.load_package(source, &package, |s, diag, package| {
+            s.load_source_diag(diag, package)
+        })?;
 
         Ok(Some(package))
     }
@@ -591,8 +920,12 @@ impl<'e> Import for Session<'e, CoreFlavor> {
 
 /// Forward implementation for a mutable reference to the session.
 impl<'a, 'e: 'a> Import for &'a mut Session<'e, CoreFlavor> {
-    fn import(&mut self, required: &RpRequiredPackage) -> Result<Option<RpVersionedPackage>> {
-        (*self).import(required)
+    fn import(
+        &mut self,
+        required: &RpRequiredPackage,
+        span: Span,
+    ) -> Result<Option<RpVersionedPackage>> {
+        (*self).import(required, span)
     }
 }
 
@@ -1,5 +1,5 @@
 use core::errors::Result;
-use core::{Flavor, RpDecl, RpFile, RpName, RpReg};
+use core::{Flavor, RpDecl, RpFile, RpName, RpReg, Source};
 use linked_hash_map::LinkedHashMap;
 use std::collections::{BTreeMap, LinkedList};
 
@@ -12,6 +12,8 @@ where
     decls: LinkedHashMap<RpName<F>, RpReg>,
     /// Files and associated declarations.
     files: BTreeMap<F::Package, RpFile<F>>,
+    /// Sources that the files were loaded from.
+    sources: BTreeMap<F::Package, Source>,
 }
 
 impl<F: 'static> Translated<F>
@@ -21,8 +23,13 @@ where
     pub fn new(
         decls: LinkedHashMap<RpName<F>, RpReg>,
         files: BTreeMap<F::Package, RpFile<F>>,
+        sources: BTreeMap<F::Package, Source>,
     ) -> Self {
-        Self { decls, files }
+        Self {
+            decls,
+            files,
+            sources,
+        }
     }
 
     /// Lookup the declaration matching the given name.
@@ -51,6 +58,11 @@ where
         }
     }
 
+    /// Access the source that the file of the given package was loaded from.
+    pub fn source<'a>(&'a self, package: &F::Package) -> Option<&'a Source> {
+        self.sources.get(package)
+    }
+
     /// Iterate over all files.
     pub fn for_each_file<'a>(&'a self) -> impl Iterator<Item = (&'a F::Package, &'a RpFile<F>)> {
         self.files.iter()
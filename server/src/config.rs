@@ -18,6 +18,14 @@ pub struct Config {
     /// Max file size permitted during upload.
     #[serde(default = "default_max_file_size")]
     pub max_file_size: u64,
+    /// URL to objects advertised by the index, relative to the index.
+    #[serde(default = "default_objects_url")]
+    pub objects_url: String,
+    /// Tokens permitted to publish, passed as `Authorization: Bearer <token>`.
+    ///
+    /// If empty, publishing does not require authentication.
+    #[serde(default)]
+    pub tokens: Vec<String>,
 }
 
 fn default_listen_address() -> String {
@@ -36,6 +44,10 @@ fn default_max_file_size() -> u64 {
     1_000_000u64
 }
 
+fn default_objects_url() -> String {
+    "../objects/".to_owned()
+}
+
 impl Default for Config {
     fn default() -> Config {
         Config {
@@ -43,6 +55,8 @@ impl Default for Config {
             objects: default_objects(),
             index: default_index(),
             max_file_size: default_max_file_size(),
+            objects_url: default_objects_url(),
+            tokens: Vec::new(),
         }
     }
 }
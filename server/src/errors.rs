@@ -4,7 +4,9 @@ use std::borrow::Cow;
 /// Service errors.
 pub enum Error {
     NotFound,
+    Unauthorized,
     BadRequest(Cow<'static, str>),
+    Conflict(Cow<'static, str>),
     InternalServerError(Cow<'static, str>),
     Core(core::Error),
 }
@@ -9,6 +9,7 @@ extern crate futures_cpupool;
 extern crate hyper;
 extern crate reproto_core as core;
 extern crate reproto_repository;
+extern crate serde_json;
 extern crate tokio_fs;
 extern crate tokio_io;
 extern crate toml;
@@ -44,6 +44,8 @@ fn entry() -> Result<()> {
         objects,
         index,
         max_file_size,
+        objects_url,
+        tokens,
     } = config;
     let listen_address = listen_address.parse()?;
 
@@ -55,12 +57,21 @@ fn entry() -> Result<()> {
     let index = index_from_path(index)?;
     let index = Arc::new(Mutex::new(index));
 
+    if tokens.is_empty() {
+        warn!("no tokens configured, publishing does not require authentication");
+    }
+
+    let objects_url = Arc::new(objects_url);
+    let tokens = Arc::new(tokens);
+
     let setup = move || {
         futures::future::ok::<_, hyper::Error>(reproto_service::ReprotoService {
             max_file_size,
             pool: pool.clone(),
             objects: objects.clone(),
             index: index.clone(),
+            objects_url: objects_url.clone(),
+            tokens: tokens.clone(),
         })
     };
 
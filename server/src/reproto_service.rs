@@ -6,13 +6,17 @@ use hyper::header::{self, HeaderMap, HeaderValue};
 use hyper::rt::{Future, Stream};
 use hyper::service::Service;
 use hyper::{self, Body, Method, Request, Response, StatusCode};
-use reproto_repository::{to_checksum, Checksum, Index, Objects};
+use core::{RpPackage, Version};
+use reproto_repository::{to_checksum, Algorithm, Checksum, Deployment, Index, Objects};
+use serde_json;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::sync::{Arc, Mutex};
 use tokio_fs;
 use tokio_io;
 
 const CHECKSUM_MISMATCH: &'static str = "checksum mismatch";
+/// Prefix of the authorization header carrying a token.
+const BEARER: &'static str = "Bearer ";
 
 type BoxFut = Box<Future<Item = Response<Body>, Error = Error> + Send>;
 
@@ -21,10 +25,19 @@ pub struct ReprotoService {
     pub pool: Arc<CpuPool>,
     pub objects: Arc<Mutex<Box<Objects>>>,
     pub index: Arc<Mutex<Box<Index>>>,
+    pub objects_url: Arc<String>,
+    pub tokens: Arc<Vec<String>>,
 }
 
 type EncodingFn = fn(&Cursor<Vec<u8>>) -> Box<Read>;
 
+/// Configuration of the index, in the same format as `config.json` in a file index.
+#[derive(Serialize)]
+struct IndexConfig<'a> {
+    objects: &'a str,
+    checksum: Algorithm,
+}
+
 impl ReprotoService {
     fn no_encoding(input: &Cursor<Vec<u8>>) -> Box<Read> {
         Box::new(input.clone())
@@ -44,6 +57,51 @@ impl ReprotoService {
         Self::no_encoding
     }
 
+    /// Check that the request is permitted to publish.
+    fn authorize(&self, req: &Request<Body>) -> Result<(), Error> {
+        if self.tokens.is_empty() {
+            return Ok(());
+        }
+
+        let token = match req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(value) if value.starts_with(BEARER) => &value[BEARER.len()..],
+            _ => return Err(Error::Unauthorized),
+        };
+
+        if self
+            .tokens
+            .iter()
+            .any(|t| constant_time_eq(t.as_bytes(), token.as_bytes()))
+        {
+            return Ok(());
+        }
+
+        Err(Error::Unauthorized)
+    }
+
+    /// Read the whole body of a request, up to the maximum file size.
+    fn read_body(
+        &self,
+        req: Request<Body>,
+    ) -> impl 'static + Future<Item = Vec<u8>, Error = Error> + Send {
+        let max_file_size = self.max_file_size;
+
+        req.into_body()
+            .map_err(|e| Error::InternalServerError(format!("error receiving body: {}", e).into()))
+            .fold(Vec::<u8>::new(), move |mut out, chunk| {
+                if out.len() as u64 + chunk.len() as u64 > max_file_size {
+                    return err(Error::BadRequest("file too large".into()));
+                }
+
+                out.extend(chunk.as_ref());
+                ok(out)
+            })
+    }
+
     fn build_index(&self, m: &mut String) -> Result<(), Error> {
         use std::fmt::Write;
 
@@ -135,7 +193,7 @@ impl ReprotoService {
 
                 let mut checksum_read = encoding(&tmp);
 
-                let actual = match to_checksum(&mut checksum_read) {
+                let actual = match to_checksum(checksum.algorithm(), &mut checksum_read) {
                     Ok(actual) => actual,
                     Err(e) => return Box::new(err(e.into())),
                 };
@@ -219,31 +277,203 @@ impl ReprotoService {
         self.put_uploaded_object(body, checksum, encoding)
     }
 
-    fn inner_call<'a>(
-        &self,
-        req: Request<Body>,
-        path: impl IntoIterator<Item = &'a str>,
-    ) -> BoxFut {
-        let mut it = path.into_iter();
+    /// Get the configuration of the index.
+    fn get_index_config(&self) -> BoxFut {
+        let checksum = match self.index.lock() {
+            Ok(index) => index.checksum_algorithm(),
+            Err(_) => return Box::new(err(Error::InternalServerError("lock poisoned".into()))),
+        };
+
+        let config = IndexConfig {
+            objects: self.objects_url.as_str(),
+            checksum,
+        };
+
+        let body = match serde_json::to_string(&config) {
+            Ok(body) => body,
+            Err(e) => return Box::new(err(e.into())),
+        };
+
+        Box::new(ok(json_response(body)))
+    }
+
+    /// Get the metadata of a package, in the same format as `metadata.json` in a file index.
+    fn get_metadata(&self, parts: &[&str]) -> BoxFut {
+        let package = match to_package(parts) {
+            Ok(package) => package,
+            Err(e) => return Box::new(err(e)),
+        };
+
+        let index = match self.index.lock() {
+            Ok(index) => index,
+            Err(_) => return Box::new(err(Error::InternalServerError("lock poisoned".into()))),
+        };
+
+        let deployments = match index.all(&package) {
+            Ok(deployments) => deployments,
+            Err(e) => return Box::new(err(e.into())),
+        };
+
+        if deployments.is_empty() {
+            return Box::new(err(Error::NotFound));
+        }
+
+        let mut body = String::new();
+
+        for d in deployments {
+            match serde_json::to_string(&d) {
+                Ok(line) => {
+                    body.push_str(&line);
+                    body.push('\n');
+                }
+                Err(e) => return Box::new(err(e.into())),
+            }
+        }
+
+        Box::new(ok(json_response(body)))
+    }
+
+    /// Publish a version of a package.
+    ///
+    /// The body is either the checksum of an uploaded object, or a JSON deployment if the
+    /// deployment is signed or has a changelog.
+    fn put_version(&self, parts: &[&str], version: &str, req: Request<Body>) -> BoxFut {
+        let package = match to_package(parts) {
+            Ok(package) => package,
+            Err(e) => return Box::new(err(e)),
+        };
+
+        let version = match Version::parse(version) {
+            Ok(version) => version,
+            Err(e) => {
+                return Box::new(err(Error::BadRequest(
+                    format!("bad version: {}: {}", version, e).into(),
+                )))
+            }
+        };
 
-        let a = it.next();
-        let b = it.next();
-        let c = it.next();
+        let pool = self.pool.clone();
+        let objects = self.objects.clone();
+        let index = self.index.clone();
+
+        let publish = self.read_body(req).and_then(move |body| {
+            pool.spawn_fn(move || {
+                let body = String::from_utf8(body)
+                    .map_err(|_| Error::BadRequest("body is not utf-8".into()))?;
+                let body = body.trim();
+
+                let (checksum, signature, changelog) = if body.starts_with('{') {
+                    let deployment: Deployment = serde_json::from_str(body)
+                        .map_err(|e| Error::BadRequest(format!("bad deployment: {}", e).into()))?;
+
+                    if deployment.version != version {
+                        return Err(Error::BadRequest("version mismatch".into()));
+                    }
+
+                    (
+                        deployment.object,
+                        deployment.signature,
+                        deployment.changelog,
+                    )
+                } else {
+                    let checksum = Checksum::from_str(body)
+                        .map_err(|_| Error::BadRequest("bad checksum".into()))?;
+
+                    (checksum, None, Vec::new())
+                };
+
+                if let Some(ref signature) = signature {
+                    if signature.verify(&checksum).is_err() {
+                        return Err(Error::BadRequest("bad signature".into()));
+                    }
+                }
+
+                {
+                    let mut objects = objects
+                        .lock()
+                        .map_err(|_| Error::InternalServerError("lock poisoned".into()))?;
+
+                    if objects.get_object(&checksum)?.is_none() {
+                        return Err(Error::BadRequest(
+                            format!("object not uploaded: {}", checksum).into(),
+                        ));
+                    }
+                }
+
+                let index = index
+                    .lock()
+                    .map_err(|_| Error::InternalServerError("lock poisoned".into()))?;
+
+                let deployments = index.get_deployments(&package, &version)?;
+
+                if !deployments.is_empty() {
+                    if deployments.iter().all(|d| d.object == checksum) {
+                        return Ok(Response::new(Body::empty()));
+                    }
+
+                    return Err(Error::Conflict(
+                        format!("{}@{}: already published", package, version).into(),
+                    ));
+                }
+
+                info!("Publishing: {}@{}: {}", package, version, checksum);
+                index.put_version(
+                    &checksum,
+                    &package,
+                    &version,
+                    signature.as_ref(),
+                    &changelog,
+                    false,
+                )?;
+                Ok(Response::new(Body::empty()))
+            })
+        });
+
+        Box::new(publish)
+    }
 
-        match (req.method(), a, b, c) {
-            (&Method::GET, Some(""), None, None) => {
+    fn inner_call(&self, req: Request<Body>, path: &[&str]) -> BoxFut {
+        match (req.method(), path) {
+            (&Method::GET, &[""]) => {
                 return self.get_index();
             }
-            (&Method::GET, Some("objects"), Some(id), None) => {
+            (&Method::GET, &["objects", id]) => {
                 return self.get_objects(id);
             }
-            (&Method::PUT, Some("objects"), Some(id), None) => {
+            (&Method::PUT, &["objects", id]) => {
+                if let Err(e) = self.authorize(&req) {
+                    return Box::new(err(e));
+                }
+
                 return self.put_objects(id, req);
             }
+            (&Method::GET, &["index", "config.json"]) => {
+                return self.get_index_config();
+            }
             _ => {}
         }
 
-        Box::new(err(Error::NotFound))
+        let path = match path.split_first() {
+            Some((&"index", rest)) => rest,
+            _ => return Box::new(err(Error::NotFound)),
+        };
+
+        let (last, parts) = match path.split_last() {
+            Some((last, parts)) if !parts.is_empty() => (*last, parts),
+            _ => return Box::new(err(Error::NotFound)),
+        };
+
+        match (req.method(), last) {
+            (&Method::GET, "metadata.json") => self.get_metadata(parts),
+            (&Method::PUT, version) => {
+                if let Err(e) = self.authorize(&req) {
+                    return Box::new(err(e));
+                }
+
+                self.put_version(parts, version, req)
+            }
+            _ => Box::new(err(Error::NotFound)),
+        }
     }
 
     fn handle_error(e: Error) -> Box<Future<Item = Response<Body>, Error = hyper::Error> + Send> {
@@ -258,10 +488,23 @@ impl ReprotoService {
                 *response.body_mut() = Body::from("not found");
                 *response.status_mut() = StatusCode::NOT_FOUND;
             }
+            Error::Unauthorized => {
+                *response.body_mut() = Body::from("unauthorized");
+                *response.status_mut() = StatusCode::UNAUTHORIZED;
+
+                response.headers_mut().insert(
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static("Bearer"),
+                );
+            }
             Error::BadRequest(message) => {
                 *response.body_mut() = Body::from(message);
                 *response.status_mut() = StatusCode::BAD_REQUEST;
             }
+            Error::Conflict(message) => {
+                *response.body_mut() = Body::from(message);
+                *response.status_mut() = StatusCode::CONFLICT;
+            }
             Error::InternalServerError(message) => {
                 *response.body_mut() = Body::from("internal server error");
                 *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
@@ -297,12 +540,46 @@ impl Service for ReprotoService {
     fn call(&mut self, req: Request<Body>) -> Self::Future {
         let full_path = String::from(req.uri().path());
 
-        let path = full_path.split('/').skip(1);
+        let path = full_path.split('/').skip(1).collect::<Vec<_>>();
 
         let fut = self
-            .inner_call(req, path)
+            .inner_call(req, &path)
             .or_else(|e| Self::handle_error(e));
 
         Box::new(fut)
     }
 }
+
+/// Convert path segments into a package.
+fn to_package(parts: &[&str]) -> Result<RpPackage, Error> {
+    for part in parts {
+        if part.is_empty() || *part == "." || *part == ".." {
+            return Err(Error::BadRequest(
+                format!("bad package: {}", parts.join("/")).into(),
+            ));
+        }
+    }
+
+    Ok(RpPackage::new(parts.iter().map(|p| p.to_string()).collect()))
+}
+
+/// Build a response with a JSON body.
+fn json_response(body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+
+    response
+}
+
+/// Compare two byte slices in constant time with regards to their content.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
@@ -0,0 +1,374 @@
+//! Compile fixtures through every backend and compare the output with golden files.
+
+extern crate diff;
+extern crate reproto_compile as compile;
+extern crate reproto_core as core;
+extern crate reproto_env as env;
+extern crate reproto_manifest as manifest;
+extern crate walkdir;
+
+use compile::Compiler;
+use core::errors::Result;
+use core::{
+    Diagnostic, RelativePath, RelativePathBuf, Reported, RpPackage, RpVersionedPackage, Source,
+};
+use manifest::Language;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use walkdir::WalkDir;
+
+/// Extension of fixtures.
+const EXT: &str = "reproto";
+
+/// A difference between generated and golden files.
+enum Difference {
+    /// A file was generated, but there is no golden file for it.
+    Added(String),
+    /// A golden file exists, but it was not generated.
+    Removed(String),
+    /// Content of a generated file differs from its golden file.
+    Changed(String, Vec<diff::Result<String>>),
+}
+
+/// A single fixture compiled for a single language.
+struct Case<'a> {
+    fixture: &'a Path,
+    name: String,
+    language: Language,
+}
+
+impl<'a> Case<'a> {
+    /// Keywords used to filter cases.
+    fn keywords(&self) -> Vec<String> {
+        vec![self.name.clone(), self.language.id()]
+    }
+
+    /// Directory containing the golden files for this case.
+    fn expected(&self, root: &Path) -> PathBuf {
+        root.join("expected")
+            .join(self.language.id())
+            .join(&self.name)
+    }
+
+    /// Compile the fixture, returning the generated files by path.
+    fn compile(&self) -> Result<BTreeMap<String, Vec<u8>>> {
+        let package = RpVersionedPackage::new(RpPackage::parse(&self.name), None);
+        let source = Source::from_path(self.fixture);
+
+        let mut reporter: Vec<Reported> = Vec::new();
+
+        let result = Compiler::new(env::convert_lang(self.language.clone()))
+            .source(source, Some(package))
+            .compile(&mut reporter);
+
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                print_reported(&reporter);
+                return Err(e);
+            }
+        };
+
+        // backends build paths from `.`, so they need to be normalized to match golden files.
+        Ok(output
+            .into_files()
+            .into_iter()
+            .map(|(path, content)| (path.normalize().as_str().to_string(), content))
+            .collect())
+    }
+}
+
+/// Print all reported diagnostics.
+fn print_reported(reporter: &[Reported]) {
+    for reported in reporter {
+        let items = match *reported {
+            Reported::Diagnostics(ref d) => {
+                d.items().map(|item| (&d.source, item)).collect::<Vec<_>>()
+            }
+            Reported::SourceDiagnostics(ref d) => {
+                d.items().map(|item| (&item.0, &item.1)).collect::<Vec<_>>()
+            }
+        };
+
+        for (source, item) in items {
+            match *item {
+                Diagnostic::Error {
+                    ref span,
+                    ref message,
+                    ..
+                } => println!("{}:{}: error: {}", source, span.start, message),
+                Diagnostic::Warning {
+                    ref span,
+                    ref message,
+                } => println!("{}:{}: warning: {}", source, span.start, message),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Read all golden files in the given directory.
+fn read_expected(dir: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut out = BTreeMap::new();
+
+    if !dir.is_dir() {
+        return Ok(out);
+    }
+
+    for entry in WalkDir::new(dir) {
+        let entry = entry.map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry
+            .path()
+            .strip_prefix(dir)
+            .map_err(|_| format!("{}: not in golden directory", entry.path().display()))?;
+
+        let mut relative = RelativePathBuf::new();
+
+        for c in path.components() {
+            let c = c
+                .as_os_str()
+                .to_str()
+                .ok_or_else(|| format!("{}: not a string", path.display()))?;
+
+            relative = relative.join(c);
+        }
+
+        let mut content = Vec::new();
+        File::open(entry.path())?.read_to_end(&mut content)?;
+        out.insert(relative.as_str().to_string(), content);
+    }
+
+    Ok(out)
+}
+
+/// Compare generated files with golden files.
+fn differences(
+    expected: &BTreeMap<String, Vec<u8>>,
+    actual: &BTreeMap<String, Vec<u8>>,
+) -> Vec<Difference> {
+    let mut out = Vec::new();
+
+    for (path, content) in actual {
+        let golden = match expected.get(path) {
+            Some(golden) => golden,
+            None => {
+                out.push(Difference::Added(path.clone()));
+                continue;
+            }
+        };
+
+        if golden == content {
+            continue;
+        }
+
+        let golden = String::from_utf8_lossy(golden);
+        let content = String::from_utf8_lossy(content);
+
+        let lines = diff::lines(&golden, &content)
+            .into_iter()
+            .map(|d| match d {
+                diff::Result::Left(l) => diff::Result::Left(l.to_string()),
+                diff::Result::Both(l, r) => diff::Result::Both(l.to_string(), r.to_string()),
+                diff::Result::Right(r) => diff::Result::Right(r.to_string()),
+            })
+            .collect();
+
+        out.push(Difference::Changed(path.clone(), lines));
+    }
+
+    for path in expected.keys() {
+        if !actual.contains_key(path) {
+            out.push(Difference::Removed(path.clone()));
+        }
+    }
+
+    out
+}
+
+/// Replace the golden files in the given directory with the generated files.
+fn bless(dir: &Path, actual: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+    if dir.is_dir() {
+        fs::remove_dir_all(dir)?;
+    }
+
+    for (path, content) in actual {
+        let path = RelativePath::new(path).to_path(dir);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        File::create(&path)?.write_all(content)?;
+    }
+
+    Ok(())
+}
+
+fn print_differences(dir: &Path, differences: &[Difference]) {
+    for d in differences {
+        match *d {
+            Difference::Added(ref path) => {
+                println!(
+                    "{}: missing golden file",
+                    RelativePath::new(path).to_path(dir).display()
+                );
+            }
+            Difference::Removed(ref path) => {
+                println!(
+                    "{}: no longer generated",
+                    RelativePath::new(path).to_path(dir).display()
+                );
+            }
+            Difference::Changed(ref path, ref lines) => {
+                println!(
+                    "{}: differs from generated file",
+                    RelativePath::new(path).to_path(dir).display()
+                );
+
+                for line in lines {
+                    match *line {
+                        diff::Result::Left(ref l) => println!("-{}", l),
+                        diff::Result::Right(ref r) => println!("+{}", r),
+                        diff::Result::Both(ref l, _) => println!(" {}", l),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Find all fixtures in the given directory.
+fn fixtures(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+
+    for e in fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let path = e?.path();
+
+        if path.is_file() && path.extension().map(|ext| ext == EXT).unwrap_or(false) {
+            out.push(path);
+        }
+    }
+
+    out.sort();
+    Ok(out)
+}
+
+fn try_main() -> Result<()> {
+    let mut root = PathBuf::from("it/golden");
+    let mut args = std::env::args();
+    args.next();
+
+    let mut do_bless = false;
+    let mut filters = HashSet::new();
+
+    while let Some(opt) = args.next() {
+        match opt.as_str() {
+            "--bless" => {
+                do_bless = true;
+            }
+            "--root" => {
+                let arg = args.next().ok_or_else(|| "expected argument to `--root`")?;
+                root = PathBuf::from(arg);
+            }
+            other => {
+                filters.insert(other.to_string());
+            }
+        }
+    }
+
+    let fixtures = fixtures(&root.join("proto"))?;
+
+    // the template backend has no output unless templates are configured as modules.
+    let languages = Language::all()
+        .into_iter()
+        .filter(|l| *l != Language::Template)
+        .collect::<Vec<_>>();
+
+    let mut cases = Vec::new();
+
+    for fixture in &fixtures {
+        let name = fixture
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| format!("{}: bad file name", fixture.display()))?
+            .to_string();
+
+        for language in &languages {
+            cases.push(Case {
+                fixture,
+                name: name.clone(),
+                language: language.clone(),
+            });
+        }
+    }
+
+    let mut failed = 0usize;
+    let mut total = 0usize;
+
+    for case in cases.iter().filter(|c| {
+        filters
+            .iter()
+            .all(|term| c.keywords().iter().any(|k| k.contains(term.as_str())))
+    }) {
+        total += 1;
+        let dir = case.expected(&root);
+
+        let actual = match case.compile() {
+            Ok(actual) => actual,
+            Err(e) => {
+                println!(
+                    "FAIL {} ({}): {}",
+                    case.name,
+                    case.language.id(),
+                    e.display()
+                );
+                failed += 1;
+                continue;
+            }
+        };
+
+        if do_bless {
+            bless(&dir, &actual)?;
+            println!("BLESS {} ({})", case.name, case.language.id());
+            continue;
+        }
+
+        let differences = differences(&read_expected(&dir)?, &actual);
+
+        if differences.is_empty() {
+            println!("OK {} ({})", case.name, case.language.id());
+            continue;
+        }
+
+        println!("FAIL {} ({})", case.name, case.language.id());
+        print_differences(&dir, &differences);
+        failed += 1;
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} case(s) failed, run with `--bless` to update golden files",
+            failed, total
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = try_main() {
+        eprintln!("{}", e.display());
+        process::exit(1);
+    }
+
+    process::exit(0);
+}
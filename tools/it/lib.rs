@@ -340,11 +340,14 @@ impl Reproto {
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum Language {
+    AsyncApi,
     Csharp,
     Go,
+    Haskell,
     Java,
     JavaScript,
     Json,
+    Kotlin,
     OpenApi,
     Python,
     Python3,
@@ -359,7 +362,7 @@ impl Language {
         use self::Language::*;
 
         match *self {
-            Json | Reproto => false,
+            AsyncApi | Json | Reproto => false,
             _ => true,
         }
     }
@@ -369,11 +372,14 @@ impl Language {
         use self::Language::*;
 
         match *self {
+            AsyncApi => "asyncapi",
             Csharp => "csharp",
             Go => "go",
+            Haskell => "haskell",
             Java => "java",
             JavaScript => "js",
             Json => "json",
+            Kotlin => "kotlin",
             OpenApi => "openapi",
             Python => "python",
             Python3 => "python3",
@@ -388,11 +394,14 @@ impl Language {
         use self::Language::*;
 
         match *self {
+            AsyncApi => "asyncapi",
             Csharp => "csharp",
             Go => "go",
+            Haskell => "haskell",
             Java => "java",
             JavaScript => "js",
             Json => "json",
+            Kotlin => "kotlin",
             OpenApi => "openapi",
             Python => "python",
             Python3 => "python",
@@ -414,6 +423,8 @@ impl Language {
             Rust => RelativePath::new("src"),
             Swift => RelativePath::new("Sources/Models"),
             Go => RelativePath::new("models"),
+            Haskell => RelativePath::new("generated"),
+            Kotlin => RelativePath::new("generated"),
             _ => RelativePath::new("."),
         }
     }
@@ -1000,6 +1011,8 @@ pub struct Suite<'a> {
     dir: Option<RelativePathBuf>,
     /// Include only the following languages.
     include: HashSet<Language>,
+    /// Project working directories to use instead of the default ones.
+    workdirs: HashMap<Language, &'a str>,
 }
 
 impl<'a> Suite<'a> {
@@ -1014,6 +1027,7 @@ impl<'a> Suite<'a> {
             checks: vec![],
             dir: None,
             include: HashSet::new(),
+            workdirs: HashMap::new(),
         }
     }
 
@@ -1047,6 +1061,11 @@ impl<'a> Suite<'a> {
         self.arguments.insert(lang, args);
     }
 
+    /// Build projects for the given language in another working directory.
+    pub fn workdir(&mut self, lang: Language, workdir: &'a str) {
+        self.workdirs.insert(lang, workdir);
+    }
+
     /// Hook up another proto file.
     pub fn proto<P: AsRef<RelativePath>>(&mut self, path: P) {
         self.proto.push(path.as_ref().to_owned());
@@ -1248,7 +1267,11 @@ impl<'a> Project<'a> {
                         && language.supports_project()
                         && self.project_languages.contains(language)
                     {
-                        let source_workdir = language.source_workdir(root);
+                        let source_workdir = match suite.workdirs.get(language) {
+                            Some(workdir) => root.join("workdir").join(workdir),
+                            None => language.source_workdir(root),
+                        };
+
                         let target_workdir = language.path(&target_workdir, &[suite.test, name]);
 
                         runners.push(Box::new(ProjectRunner {
@@ -77,6 +77,18 @@ fn detect() -> HashSet<Language> {
         println!("WARN: `go version` failed, not building Go projects");
     }
 
+    if test("cabal", &["--version"]) {
+        out.insert(Language::Haskell);
+    } else {
+        println!("WARN: `cabal --version` failed, not building Haskell projects");
+    }
+
+    if test("gradle", &["--version"]) {
+        out.insert(Language::Kotlin);
+    } else {
+        println!("WARN: `gradle --version` failed, not building Kotlin projects");
+    }
+
     out
 }
 
@@ -135,11 +147,14 @@ fn print_differences(source: &Path, target: &Path, errors: &[it::utils::Diff]) {
 
 fn try_main() -> Result<()> {
     let all_languages = vec![
+        it::Language::AsyncApi,
         it::Language::Csharp,
         it::Language::Go,
+        it::Language::Haskell,
         it::Language::Java,
         it::Language::JavaScript,
         it::Language::Json,
+        it::Language::Kotlin,
         it::Language::OpenApi,
         it::Language::Python,
         it::Language::Python3,
@@ -209,7 +224,9 @@ fn try_main() -> Result<()> {
     );
 
     project.arg(Language::Go, &["-m", "encoding/json"]);
+    project.arg(Language::Haskell, &["-m", "aeson"]);
     project.arg(Language::Java, &["-m", "builder", "-m", "jackson"]);
+    project.arg(Language::Kotlin, &["-m", "kotlinx_serialization"]);
     project.arg(Language::Csharp, &["-m", "Json.NET"]);
     project.arg(Language::Python, &["-m", "python2"]);
 